@@ -98,7 +98,7 @@ fn build_tool_impl(
                 #function_description
             }
 
-            async fn call(&mut self, Self::Params { #(#function_params_struct_field_names),* }: Self::Params) -> ::std::result::Result<
+            async fn call(&self, Self::Params { #(#function_params_struct_field_names),* }: Self::Params) -> ::std::result::Result<
                 ::std::string::String,
                 ::std::boxed::Box<dyn ::std::error::Error + Send + Sync>,
             > {
@@ -1,8 +1,23 @@
 use proc_macro::TokenStream;
 
 mod function;
+mod parameters;
+mod prompt;
 
 #[proc_macro_attribute]
 pub fn function(attr: TokenStream, value: TokenStream) -> TokenStream {
     function::function_impl(attr, value)
 }
+
+/// Implements `DescribedParameters` for tool parameters, failing to compile if a field has
+/// neither a doc comment nor a `#[schemars(description = "...")]`.
+#[proc_macro_derive(DescribedParameters)]
+pub fn described_parameters(input: TokenStream) -> TokenStream {
+    parameters::described_parameters_impl(input)
+}
+
+/// Implements `Prompt` for a struct, rendering each field on a line labelled with its name.
+#[proc_macro_derive(Prompt, attributes(prompt))]
+pub fn prompt(input: TokenStream) -> TokenStream {
+    prompt::prompt_impl(input)
+}
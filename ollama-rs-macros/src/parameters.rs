@@ -0,0 +1,144 @@
+use proc_macro::TokenStream;
+use proc_macro2::{TokenStream as TokenStream2, TokenTree};
+use quote::quote;
+use syn::{spanned::Spanned as _, Attribute, Data, DeriveInput, Error, Fields, Meta};
+
+pub fn described_parameters_impl(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+
+    let fields: Vec<&Fields> = match &input.data {
+        Data::Struct(data) => vec![&data.fields],
+        Data::Enum(data) => data.variants.iter().map(|v| &v.fields).collect(),
+        Data::Union(data) => {
+            return Error::new_spanned(data.union_token, "unions can't be tool parameters")
+                .to_compile_error()
+                .into()
+        }
+    };
+
+    let mut errors: Option<Error> = None;
+    let mut flattened = Vec::new();
+    for field in fields.into_iter().flatten() {
+        let Some(name) = &field.ident else {
+            push_error(
+                &mut errors,
+                Error::new(
+                    field.span(),
+                    "tuple fields can't be described, use named fields",
+                ),
+            );
+            continue;
+        };
+
+        if has_flag(&field.attrs, "serde", "skip")
+            || has_flag(&field.attrs, "serde", "skip_deserializing")
+            || has_flag(&field.attrs, "schemars", "skip")
+        {
+            continue;
+        }
+
+        // The fields of a flattened struct are parameters too, so it must be described as well
+        if has_flag(&field.attrs, "serde", "flatten") {
+            flattened.push(&field.ty);
+            continue;
+        }
+
+        if !has_doc(&field.attrs) && !has_description(&field.attrs) {
+            push_error(
+                &mut errors,
+                Error::new_spanned(
+                    name,
+                    format!(
+                        "parameter `{name}` has no description, add a doc comment or `#[schemars(description = \"...\")]`"
+                    ),
+                ),
+            );
+        }
+    }
+
+    if let Some(errors) = errors {
+        return errors.to_compile_error().into();
+    }
+
+    let ident = &input.ident;
+    // `DescribedParameters` requires `Parameters`, which generic types only implement for some
+    // of their arguments
+    let mut generics = input.generics.clone();
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+    generics
+        .make_where_clause()
+        .predicates
+        .push(syn::parse_quote!(#ident #ty_generics: ::ollama_rs::generation::tools::Parameters));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let assertions: TokenStream2 = flattened
+        .iter()
+        .map(|ty| {
+            quote! {
+                const _: fn() = || {
+                    fn assert_described<T: ?Sized + ::ollama_rs::generation::tools::DescribedParameters>() {}
+                    assert_described::<#ty>();
+                };
+            }
+        })
+        .collect();
+
+    quote! {
+        impl #impl_generics ::ollama_rs::generation::tools::DescribedParameters for #ident #ty_generics #where_clause {}
+
+        #assertions
+    }
+    .into()
+}
+
+fn push_error(errors: &mut Option<Error>, error: Error) {
+    match errors {
+        Some(errors) => errors.combine(error),
+        None => *errors = Some(error),
+    }
+}
+
+fn has_doc(attrs: &[Attribute]) -> bool {
+    attrs.iter().any(|attr| {
+        let Meta::NameValue(meta) = &attr.meta else {
+            return false;
+        };
+        attr.path().is_ident("doc")
+            && match &meta.value {
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(doc),
+                    ..
+                }) => !doc.value().trim().is_empty(),
+                // e.g. `#[doc = include_str!("...")]`
+                _ => true,
+            }
+    })
+}
+
+/// Whether a `#[schemars(...)]` attribute sets `description = ...`.
+fn has_description(attrs: &[Attribute]) -> bool {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("schemars"))
+        .filter_map(|attr| attr.meta.require_list().ok())
+        .any(|list| {
+            let tokens: Vec<TokenTree> = list.tokens.clone().into_iter().collect();
+            tokens.windows(2).any(|pair| {
+                matches!(&pair[0], TokenTree::Ident(ident) if ident == "description")
+                    && matches!(&pair[1], TokenTree::Punct(punct) if punct.as_char() == '=')
+            })
+        })
+}
+
+/// Whether an attribute like `#[serde(...)]` contains the word `flag` at its top level.
+fn has_flag(attrs: &[Attribute], attribute: &str, flag: &str) -> bool {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident(attribute))
+        .filter_map(|attr| attr.meta.require_list().ok())
+        .any(|list| {
+            list.tokens
+                .clone()
+                .into_iter()
+                .any(|token| matches!(token, TokenTree::Ident(ident) if ident == flag))
+        })
+}
@@ -0,0 +1,106 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{spanned::Spanned as _, Data, DeriveInput, Error, Fields, LitStr, Type};
+
+pub fn prompt_impl(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    match expand(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            fields => {
+                return Err(Error::new(
+                    fields.span(),
+                    "only structs with named fields can be rendered as prompts",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "only structs with named fields can be rendered as prompts",
+            ))
+        }
+    };
+
+    let mut writes = Vec::new();
+    for field in fields {
+        let name = field.ident.as_ref().unwrap();
+
+        let mut skip = false;
+        let mut label = None;
+        for attr in field.attrs.iter().filter(|a| a.path().is_ident("prompt")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("skip") {
+                    skip = true;
+                    Ok(())
+                } else if meta.path.is_ident("label") {
+                    label = Some(meta.value()?.parse::<LitStr>()?.value());
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `skip` or `label = \"...\"`"))
+                }
+            })?;
+        }
+        if skip {
+            continue;
+        }
+
+        let label = label.unwrap_or_else(|| default_label(&name.to_string()));
+        let write = quote!(::ollama_rs::generation::prompt::__private::write_field);
+        // Missing optional values are left out rather than rendered as a placeholder
+        writes.push(if is_option(&field.ty) {
+            quote! {
+                if let ::core::option::Option::Some(value) = &self.#name {
+                    #write(&mut prompt, #label, value);
+                }
+            }
+        } else {
+            quote! {
+                #write(&mut prompt, #label, &self.#name);
+            }
+        });
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::ollama_rs::generation::prompt::Prompt for #ident #ty_generics #where_clause {
+            fn render_prompt(&self) -> ::std::string::String {
+                let mut prompt = ::std::string::String::new();
+                #(#writes)*
+                prompt
+            }
+        }
+    })
+}
+
+/// `order_id` is labelled `Order id`.
+fn default_label(name: &str) -> String {
+    let name = name.strip_prefix("r#").unwrap_or(name).replace('_', " ");
+    let mut chars = name.trim().chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn is_option(ty: &Type) -> bool {
+    let Type::Path(path) = ty else {
+        return false;
+    };
+    path.qself.is_none()
+        && path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "Option")
+}
@@ -26,7 +26,7 @@ impl ::ollama_rs::generation::tools::Tool for hello_world {
         "Say something"
     }
     async fn call(
-        &mut self,
+        &self,
         Self::Params { greeting, name }: Self::Params,
     ) -> ::std::result::Result<
         ::std::string::String,
@@ -72,7 +72,7 @@ impl ::ollama_rs::generation::tools::Tool for dummy {
         "Dummy"
     }
     async fn call(
-        &mut self,
+        &self,
         Self::Params { one, two, three }: Self::Params,
     ) -> ::std::result::Result<
         ::std::string::String,
@@ -24,7 +24,7 @@ impl ::ollama_rs::generation::tools::Tool for hello_world {
         "Say hello"
     }
     async fn call(
-        &mut self,
+        &self,
         Self::Params { name }: Self::Params,
     ) -> ::std::result::Result<
         ::std::string::String,
@@ -0,0 +1,442 @@
+//! Audit logs of the requests of a client, for compliance.
+//!
+//! An [`AuditLog`] set on the client appends a JSON line, an [`AuditRecord`], per request:
+//! its endpoint, model, a salted SHA-256 of its prompt rather than the prompt itself, its
+//! token usage, latency and outcome.
+//!
+//! ```no_run
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! use ollama_rs::{audit::AuditLog, Ollama};
+//!
+//! let audit_log = AuditLog::file("audit.jsonl")?
+//!     .rotate_at(10 * 1024 * 1024)
+//!     .on_rotate(|rotated| println!("archive {}", rotated.display()));
+//! let ollama = Ollama::builder().audit_log(audit_log).build()?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Records are written once the response is read: for generation and chat, which report
+//! their token usage, once the whole response, or the whole stream, is read. A request
+//! dropped before its response is read, or a stream dropped before its end, is recorded as
+//! [`AuditOutcome::Cancelled`]. Responses of the
+//! OpenAI-compatible endpoint are recorded once received, without token counts. Failing to write a record doesn't
+//! fail the request, it is logged with the [`log`] crate instead.
+
+use std::{
+    fmt, io,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+use crate::Timestamp;
+
+/// A record of a request in an [`AuditLog`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditRecord {
+    /// When the request was sent.
+    pub created_at: Timestamp,
+    /// The ID of the request, see [`request_id`](crate::request_id).
+    pub request_id: String,
+    pub method: String,
+    /// The path of the endpoint, like `/api/chat`.
+    pub endpoint: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// The SHA-256 of the prompt, of the messages or of the input of the request, salted with
+    /// the salt of the log, like `sha256:8f3a...`, to match identical prompts without logging
+    /// them. See [`AuditLog::salt`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt_hash: Option<String>,
+    /// The token counts, reported by generation and chat responses.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<AuditUsage>,
+    /// The time from sending the request to reading its response, in milliseconds.
+    pub latency_ms: u64,
+    pub outcome: AuditOutcome,
+    /// The HTTP status of the response, if one was received.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// The token counts of a request in an [`AuditRecord`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditUsage {
+    /// Number of tokens in the prompt.
+    pub prompt_eval_count: u64,
+    /// Number of tokens in the response.
+    pub eval_count: u64,
+}
+
+/// How a request in an [`AuditRecord`] ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditOutcome {
+    Success,
+    /// The request failed, see [`AuditRecord::error`].
+    Failure,
+    /// The request, or its response stream, was dropped before its end.
+    Cancelled,
+}
+
+/// Where audit records are appended, as JSON lines.
+///
+/// Clones write to the same log.
+#[derive(Clone)]
+pub struct AuditLog {
+    shared: Arc<Shared>,
+    salt: [u8; 32],
+    rotate_at: Option<u64>,
+    on_rotate: Option<RotateHook>,
+}
+
+type RotateHook = Arc<dyn Fn(&Path) + Send + Sync>;
+
+struct Shared {
+    /// The lines waiting to be written, in the order the requests ended.
+    queue: Mutex<Vec<u8>>,
+    sink: tokio::sync::Mutex<Sink>,
+}
+
+enum Sink {
+    Writer(Box<dyn AsyncWrite + Send + Unpin>),
+    File {
+        path: PathBuf,
+        file: tokio::fs::File,
+        size: u64,
+    },
+}
+
+impl AuditLog {
+    /// An audit log written to `writer`, e.g. a socket or a pipe to a log shipper.
+    ///
+    /// Prompts are hashed with a salt drawn from the random number generator of the OS, see
+    /// [`AuditLog::salt`].
+    ///
+    /// # Panics
+    ///
+    /// If the random number generator of the OS is unavailable.
+    pub fn new(writer: impl AsyncWrite + Send + Unpin + 'static) -> Self {
+        Self::with_sink(Sink::Writer(Box::new(writer)))
+    }
+
+    /// An audit log appended to the file at `path`, created if needed.
+    ///
+    /// # Panics
+    ///
+    /// Like [`AuditLog::new`], if the random number generator of the OS is unavailable.
+    pub fn file(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let path = path.into();
+        let (file, size) = open_append(&path)?;
+        Ok(Self::with_sink(Sink::File { path, file, size }))
+    }
+
+    fn with_sink(sink: Sink) -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                queue: Mutex::new(Vec::new()),
+                sink: tokio::sync::Mutex::new(sink),
+            }),
+            salt: crate::logging::random_salt(),
+            rotate_at: None,
+            on_rotate: None,
+        }
+    }
+
+    /// Hashes prompts with `salt` rather than a random one, so that the hashes of the logs of
+    /// several processes or restarts can be matched. Keep it secret: short or common prompts
+    /// can be recovered from their hashes by whoever knows it.
+    pub fn salt(mut self, salt: [u8; 32]) -> Self {
+        self.salt = salt;
+        self
+    }
+
+    /// Rotates the file of the log once it reaches `max_bytes`, see [`AuditLog::rotate`].
+    /// Only applies to logs written to a file. (Default: never)
+    pub fn rotate_at(mut self, max_bytes: u64) -> Self {
+        self.rotate_at = Some(max_bytes);
+        self
+    }
+
+    /// Calls `hook` with the path of every rotated file, e.g. to compress or upload it.
+    pub fn on_rotate(mut self, hook: impl Fn(&Path) + Send + Sync + 'static) -> Self {
+        self.on_rotate = Some(Arc::new(hook));
+        self
+    }
+
+    /// Appends a record.
+    pub async fn write(&self, record: &AuditRecord) -> io::Result<()> {
+        self.push(record);
+        self.flush().await
+    }
+
+    /// Writes the records of the requests that ended, rotating the file if it grew too large.
+    pub async fn flush(&self) -> io::Result<()> {
+        let mut sink = self.shared.sink.lock().await;
+        let lines = std::mem::take(&mut *self.shared.queue.lock().unwrap());
+        if lines.is_empty() {
+            return Ok(());
+        }
+
+        let full = match &mut *sink {
+            Sink::Writer(writer) => {
+                writer.write_all(&lines).await?;
+                writer.flush().await?;
+                false
+            }
+            Sink::File { file, size, .. } => {
+                file.write_all(&lines).await?;
+                file.flush().await?;
+                *size += lines.len() as u64;
+                self.rotate_at.is_some_and(|max| *size >= max)
+            }
+        };
+        if full {
+            self.rotate_sink(&mut sink).await?;
+        }
+        Ok(())
+    }
+
+    /// Moves the file of the log to `<path>.<unix time in milliseconds>`, or
+    /// `<path>.<unix time in milliseconds>.<n>` if that file already exists, and starts a new
+    /// one, then calls the [`AuditLog::on_rotate`] hook. Fails for logs not written to a file.
+    pub async fn rotate(&self) -> io::Result<()> {
+        self.flush().await?;
+        let mut sink = self.shared.sink.lock().await;
+        self.rotate_sink(&mut sink).await
+    }
+
+    async fn rotate_sink(&self, sink: &mut Sink) -> io::Result<()> {
+        let Sink::File { path, file, size } = sink else {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "only audit logs written to a file can be rotated",
+            ));
+        };
+
+        file.sync_all().await?;
+        let millis = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis());
+        let rotated_path = |n: u32| {
+            let mut rotated = path.clone().into_os_string();
+            rotated.push(format!(".{millis}"));
+            if n > 0 {
+                rotated.push(format!(".{n}"));
+            }
+            PathBuf::from(rotated)
+        };
+        // Rotations within the same millisecond mustn't overwrite the previous archive
+        let mut n = 0;
+        let mut rotated = rotated_path(n);
+        while tokio::fs::try_exists(&rotated).await? {
+            n += 1;
+            rotated = rotated_path(n);
+        }
+        tokio::fs::rename(&*path, &rotated).await?;
+        (*file, *size) = reopen_append(path).await?;
+
+        if let Some(hook) = &self.on_rotate {
+            hook(&rotated);
+        }
+        Ok(())
+    }
+
+    /// Queues a record, to be written by the next flush.
+    fn push(&self, record: &AuditRecord) {
+        let mut line = serde_json::to_vec(record).expect("audit records serialize");
+        line.push(b'\n');
+        self.shared.queue.lock().unwrap().extend_from_slice(&line);
+    }
+
+    /// Starts the record of a request about to be sent.
+    pub(crate) fn start(&self, request: &reqwest::Request, request_id: &str) -> PendingAudit {
+        let body = request
+            .body()
+            .and_then(reqwest::Body::as_bytes)
+            .and_then(|body| serde_json::from_slice::<Value>(body).ok())
+            .unwrap_or_default();
+
+        PendingAudit {
+            log: self.clone(),
+            started: Instant::now(),
+            record: Some(AuditRecord {
+                created_at: crate::Timestamp::now(),
+                request_id: request_id.to_string(),
+                method: request.method().to_string(),
+                endpoint: request.url().path().to_string(),
+                model: body["model"].as_str().map(str::to_string),
+                prompt_hash: prompt_hash(&self.salt, &body),
+                usage: None,
+                latency_ms: 0,
+                outcome: AuditOutcome::Cancelled,
+                status: None,
+                error: None,
+            }),
+        }
+    }
+}
+
+impl fmt::Debug for AuditLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AuditLog")
+            .field("rotate_at", &self.rotate_at)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Opens the file at `path` to append to it, along with its size.
+fn open_append(path: &Path) -> io::Result<(tokio::fs::File, u64)> {
+    let file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    let size = file.metadata()?.len();
+    Ok((tokio::fs::File::from_std(file), size))
+}
+
+/// Like [`open_append`], without blocking the executor, once a file is rotated.
+async fn reopen_append(path: &Path) -> io::Result<(tokio::fs::File, u64)> {
+    let file = tokio::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .await?;
+    let size = file.metadata().await?.len();
+    Ok((file, size))
+}
+
+/// The salted SHA-256 of the prompt, messages or input of a request body.
+fn prompt_hash(salt: &[u8; 32], body: &Value) -> Option<String> {
+    let prompt = ["prompt", "messages", "input"]
+        .iter()
+        .find_map(|key| body.get(key))?;
+    let digest = match prompt {
+        Value::String(prompt) => crate::logging::salted_hash(salt, prompt.as_bytes()),
+        prompt => crate::logging::salted_hash(salt, prompt.to_string().as_bytes()),
+    };
+    Some(format!("sha256:{digest}"))
+}
+
+/// The record of a request that hasn't ended yet.
+///
+/// If dropped before being finished, the record is written with the outcome it was left
+/// with, [`AuditOutcome::Cancelled`] unless set otherwise.
+pub(crate) struct PendingAudit {
+    log: AuditLog,
+    started: Instant,
+    record: Option<AuditRecord>,
+}
+
+impl PendingAudit {
+    /// Records the HTTP status of the response, and the outcome it implies: a success until
+    /// its body is read, see [`AuditSlot::take`].
+    pub(crate) fn status(&mut self, status: reqwest::StatusCode) {
+        if let Some(record) = &mut self.record {
+            record.status = Some(status.as_u16());
+            if status.is_success() {
+                record.outcome = AuditOutcome::Success;
+            } else {
+                record.outcome = AuditOutcome::Failure;
+                record.error = status.canonical_reason().map(str::to_string);
+            }
+        }
+    }
+
+    /// Sets the outcome recorded if the request isn't finished.
+    pub(crate) fn outcome(&mut self, outcome: AuditOutcome) {
+        if let Some(record) = &mut self.record {
+            record.outcome = outcome;
+        }
+    }
+
+    /// Records the token counts of a response body, if it has some.
+    pub(crate) fn usage(&mut self, body: &[u8]) {
+        let Ok(body) = serde_json::from_slice::<Value>(body) else {
+            return;
+        };
+        if let (Some(record), Some(prompt_eval_count), Some(eval_count)) = (
+            &mut self.record,
+            body["prompt_eval_count"].as_u64(),
+            body["eval_count"].as_u64(),
+        ) {
+            record.usage = Some(AuditUsage {
+                prompt_eval_count,
+                eval_count,
+            });
+        }
+    }
+
+    /// Writes the record of a failed request.
+    pub(crate) async fn fail(mut self, error: String) {
+        if let Some(record) = &mut self.record {
+            record.outcome = AuditOutcome::Failure;
+            record.error = Some(error);
+        }
+        self.finish().await;
+    }
+
+    /// Writes the record.
+    pub(crate) async fn finish(mut self) {
+        self.queue();
+        if let Err(e) = self.log.flush().await {
+            log::warn!("failed to write an audit record: {e}");
+        }
+    }
+
+    fn queue(&mut self) {
+        if let Some(mut record) = self.record.take() {
+            record.latency_ms = self.started.elapsed().as_millis() as u64;
+            self.log.push(&record);
+        }
+    }
+}
+
+impl Drop for PendingAudit {
+    fn drop(&mut self) {
+        if self.record.is_none() {
+            return;
+        }
+        self.queue();
+        let log = self.log.clone();
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            runtime.spawn(async move {
+                if let Err(e) = log.flush().await {
+                    log::warn!("failed to write an audit record: {e}");
+                }
+            });
+        }
+    }
+}
+
+/// The pending record of a response, kept in its extensions until its body is read.
+#[derive(Clone)]
+pub(crate) struct AuditSlot(Arc<Mutex<Option<PendingAudit>>>);
+
+impl AuditSlot {
+    pub(crate) fn new(pending: PendingAudit) -> Self {
+        Self(Arc::new(Mutex::new(Some(pending))))
+    }
+
+    /// Takes the pending record of `res`, to finish it once its body is read. Until then, it
+    /// is recorded as [`AuditOutcome::Cancelled`].
+    ///
+    /// The records of responses whose body isn't read, only their status, are written as
+    /// successes once the response is dropped.
+    pub(crate) fn take(res: &mut reqwest::Response) -> Option<PendingAudit> {
+        res.extensions_mut()
+            .remove::<Self>()
+            .and_then(|Self(slot)| slot.lock().unwrap().take())
+            .map(|mut pending| {
+                pending.outcome(AuditOutcome::Cancelled);
+                pending
+            })
+    }
+}
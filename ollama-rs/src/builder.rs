@@ -0,0 +1,303 @@
+use std::time::Duration;
+
+use url::Url;
+
+use crate::{
+    concurrency::{ConcurrencyLimits, Limiter},
+    generation::{embeddings::EmbeddingsEndpoint, parameters::KeepAlive},
+    logging::ContentLogging,
+    models::ModelOptions,
+    moderation::{ContentFilter, ContentFilters},
+    CircuitBreaker, Config, Ollama, RetryPolicy,
+};
+
+/// A builder for [`Ollama`] clients, created with [`Ollama::builder`].
+///
+/// ```no_run
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use std::time::Duration;
+/// use ollama_rs::{models::ModelOptions, Ollama, RetryPolicy};
+///
+/// let ollama = Ollama::builder()
+///     .url("https://gw.example.com/ollama".parse()?)
+///     .api_key("secret")
+///     .timeout(Duration::from_secs(120))
+///     .retry_policy(RetryPolicy::new(3))
+///     .default_options(ModelOptions::default().temperature(0.2))
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct OllamaBuilder {
+    url: Url,
+    api_key: Option<String>,
+    #[cfg(feature = "headers")]
+    headers: reqwest::header::HeaderMap,
+    reqwest_client: Option<reqwest::Client>,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    retry_policy: RetryPolicy,
+    circuit_breaker: Option<CircuitBreaker>,
+    default_options: Option<ModelOptions>,
+    default_keep_alive: Option<KeepAlive>,
+    history_limit: Option<usize>,
+    deterministic_seed: Option<i32>,
+    default_model: Option<String>,
+    capture_raw_responses: bool,
+    response_compression: bool,
+    deduplicate_requests: bool,
+    content_logging: ContentLogging,
+    content_filters: ContentFilters,
+    version_check: bool,
+    embeddings_endpoint: EmbeddingsEndpoint,
+    #[cfg(feature = "openai-compat")]
+    openai_compatible: bool,
+    #[cfg(feature = "audit")]
+    audit_log: Option<crate::audit::AuditLog>,
+    concurrency_limits: Option<ConcurrencyLimits>,
+}
+
+impl OllamaBuilder {
+    pub fn new() -> Self {
+        Self {
+            url: Url::parse("http://127.0.0.1:11434").unwrap(),
+            api_key: None,
+            #[cfg(feature = "headers")]
+            headers: reqwest::header::HeaderMap::new(),
+            reqwest_client: None,
+            timeout: None,
+            connect_timeout: None,
+            retry_policy: RetryPolicy::none(),
+            circuit_breaker: None,
+            default_options: None,
+            default_keep_alive: None,
+            history_limit: None,
+            deterministic_seed: None,
+            default_model: None,
+            capture_raw_responses: false,
+            response_compression: true,
+            deduplicate_requests: false,
+            content_logging: ContentLogging::Omit,
+            content_filters: ContentFilters::default(),
+            version_check: false,
+            embeddings_endpoint: EmbeddingsEndpoint::Auto,
+            #[cfg(feature = "openai-compat")]
+            openai_compatible: false,
+            #[cfg(feature = "audit")]
+            audit_log: None,
+            concurrency_limits: None,
+        }
+    }
+
+    /// Starts from the configuration read from environment variables,
+    /// see [`Ollama::from_env`] for the variables used.
+    pub fn from_env() -> Self {
+        crate::env::builder_from_env()
+    }
+
+    /// The URL of the Ollama service, which may include a path prefix. (Default: `http://127.0.0.1:11434`)
+    pub fn url(mut self, url: Url) -> Self {
+        self.url = url;
+        self
+    }
+
+    /// A token sent as `Authorization: Bearer <token>` with every request.
+    pub fn api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    /// Headers sent with every request.
+    #[cfg_attr(docsrs, doc(cfg(feature = "headers")))]
+    #[cfg(feature = "headers")]
+    pub fn headers(mut self, headers: reqwest::header::HeaderMap) -> Self {
+        self.headers = headers;
+        self
+    }
+
+    /// Uses an existing `reqwest` client. Timeouts set on the builder are then ignored,
+    /// and must be set on the client instead.
+    pub fn reqwest_client(mut self, client: reqwest::Client) -> Self {
+        self.reqwest_client = Some(client);
+        self
+    }
+
+    /// The timeout of whole requests, streamed responses included.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// The timeout of the connection phase of requests.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// How failed requests are retried. (Default: never)
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Fails requests fast while the server is unhealthy, see [`CircuitBreaker`].
+    /// (Default: none)
+    pub fn circuit_breaker(mut self, circuit_breaker: CircuitBreaker) -> Self {
+        self.circuit_breaker = Some(circuit_breaker);
+        self
+    }
+
+    /// Options applied to every generation, chat and embeddings request.
+    /// Options set on a request take precedence, option by option.
+    pub fn default_options(mut self, options: ModelOptions) -> Self {
+        self.default_options = Some(options);
+        self
+    }
+
+    /// How long models stay loaded after generation, chat and embeddings requests that don't
+    /// specify it, e.g. [`KeepAlive::Indefinitely`] to keep them resident.
+    pub fn default_keep_alive(mut self, keep_alive: KeepAlive) -> Self {
+        self.default_keep_alive = Some(keep_alive);
+        self
+    }
+
+    /// The number of messages of a chat history sent with each request, see
+    /// [`Ollama::set_history_limit`]. (Default: unlimited)
+    pub fn history_limit(mut self, limit: usize) -> Self {
+        self.history_limit = Some(limit);
+        self
+    }
+
+    /// See [`Ollama::set_deterministic`]. (Default: disabled)
+    pub fn deterministic(mut self, seed: i32) -> Self {
+        self.deterministic_seed = Some(seed);
+        self
+    }
+
+    /// See [`Ollama::default_model`].
+    pub fn default_model(mut self, model: impl Into<String>) -> Self {
+        self.default_model = Some(model.into());
+        self
+    }
+
+    /// See [`Ollama::set_capture_raw_responses`]. (Default: false)
+    pub fn capture_raw_responses(mut self, capture: bool) -> Self {
+        self.capture_raw_responses = capture;
+        self
+    }
+
+    /// See [`Ollama::set_response_compression`]. (Default: true)
+    pub fn response_compression(mut self, enabled: bool) -> Self {
+        self.response_compression = enabled;
+        self
+    }
+
+    /// See [`Ollama::set_deduplicate_requests`]. (Default: false)
+    pub fn deduplicate_requests(mut self, enabled: bool) -> Self {
+        self.deduplicate_requests = enabled;
+        self
+    }
+
+    /// See [`Ollama::set_content_logging`]. (Default: [`ContentLogging::Omit`])
+    pub fn content_logging(mut self, content_logging: ContentLogging) -> Self {
+        self.content_logging = content_logging;
+        self
+    }
+
+    /// See [`Ollama::add_content_filter`].
+    pub fn content_filter(mut self, filter: impl ContentFilter + 'static) -> Self {
+        self.content_filters.push(std::sync::Arc::new(filter));
+        self
+    }
+
+    /// See [`Ollama::set_version_check`]. (Default: false)
+    pub fn version_check(mut self, enabled: bool) -> Self {
+        self.version_check = enabled;
+        self
+    }
+
+    /// See [`Ollama::set_embeddings_endpoint`]. (Default: [`EmbeddingsEndpoint::Auto`])
+    pub fn embeddings_endpoint(mut self, endpoint: EmbeddingsEndpoint) -> Self {
+        self.embeddings_endpoint = endpoint;
+        self
+    }
+
+    /// See [`Ollama::set_openai_compatible`]. (Default: false)
+    #[cfg_attr(docsrs, doc(cfg(feature = "openai-compat")))]
+    #[cfg(feature = "openai-compat")]
+    pub fn openai_compatible(mut self, enabled: bool) -> Self {
+        self.openai_compatible = enabled;
+        self
+    }
+
+    /// See [`Ollama::set_audit_log`]. (Default: none)
+    #[cfg_attr(docsrs, doc(cfg(feature = "audit")))]
+    #[cfg(feature = "audit")]
+    pub fn audit_log(mut self, audit_log: crate::audit::AuditLog) -> Self {
+        self.audit_log = Some(audit_log);
+        self
+    }
+
+    /// See [`Ollama::set_concurrency_limits`]. (Default: unlimited)
+    pub fn concurrency_limits(mut self, limits: ConcurrencyLimits) -> Self {
+        self.concurrency_limits = Some(limits);
+        self
+    }
+
+    /// Builds the client.
+    ///
+    /// Fails if the `reqwest` client can't be built, e.g. because the TLS backend can't be initialized.
+    pub fn build(self) -> crate::error::Result<Ollama> {
+        let reqwest_client = match self.reqwest_client {
+            Some(client) => client,
+            None => {
+                let mut builder = reqwest::Client::builder();
+                if let Some(timeout) = self.timeout {
+                    builder = builder.timeout(timeout);
+                }
+                if let Some(timeout) = self.connect_timeout {
+                    builder = builder.connect_timeout(timeout);
+                }
+                builder.build()?
+            }
+        };
+
+        Ok(Ollama::from_config(Config {
+            url: self.url,
+            reqwest_client,
+            #[cfg(feature = "headers")]
+            request_headers: self.headers,
+            capture_raw_responses: self.capture_raw_responses,
+            response_compression: self.response_compression,
+            api_key: self.api_key,
+            retry_policy: self.retry_policy,
+            circuit_breaker: self.circuit_breaker,
+            default_options: self.default_options,
+            default_keep_alive: self.default_keep_alive,
+            history_limit: self.history_limit,
+            deterministic_seed: self.deterministic_seed,
+            default_model: self.default_model,
+            in_flight: self.deduplicate_requests.then(Default::default),
+            content_logging: self.content_logging,
+            content_filters: self.content_filters,
+            version_check: self.version_check,
+            version_cache: Default::default(),
+            embeddings_endpoint: self.embeddings_endpoint,
+            legacy_embeddings: Default::default(),
+            #[cfg(feature = "openai-compat")]
+            openai_compatible: self.openai_compatible,
+            #[cfg(feature = "audit")]
+            audit_log: self.audit_log,
+            concurrency: self
+                .concurrency_limits
+                .map(|limits| std::sync::Arc::new(Limiter::limited(limits))),
+        }))
+    }
+}
+
+impl Default for OllamaBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
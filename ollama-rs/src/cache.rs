@@ -0,0 +1,4 @@
+//! Caches of responses, returning a previous response instead of calling the model again.
+
+pub mod exact;
+pub mod semantic;
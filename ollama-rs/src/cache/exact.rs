@@ -0,0 +1,311 @@
+//! A cache matching requests exactly, for requests whose response doesn't vary between
+//! calls, i.e. with a temperature of 0 or a fixed seed.
+
+use std::{
+    collections::HashMap,
+    error::Error,
+    future::Future,
+    path::PathBuf,
+    sync::Mutex,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    error::{self, OllamaError},
+    generation::{
+        chat::{request::ChatMessageRequest, ChatMessageResponse},
+        completion::{request::GenerationRequest, GenerationResponse},
+    },
+    models::ModelOptions,
+    Ollama,
+};
+
+/// A storage of the entries of an [`ExactCache`], by key.
+///
+/// Keys are made of the model name and a hash of the request, e.g. `llama3.2@6c1f0a3e8d9b2f47`.
+/// Values are opaque JSON documents.
+pub trait CacheBackend: Send + Sync {
+    fn get(
+        &self,
+        key: &str,
+    ) -> impl Future<Output = Result<Option<String>, Box<dyn Error + Send + Sync>>> + Send;
+
+    fn put(
+        &self,
+        key: &str,
+        value: String,
+    ) -> impl Future<Output = Result<(), Box<dyn Error + Send + Sync>>> + Send;
+
+    /// Removes an entry, e.g. once it has expired.
+    fn remove(
+        &self,
+        key: &str,
+    ) -> impl Future<Output = Result<(), Box<dyn Error + Send + Sync>>> + Send;
+}
+
+/// A [`CacheBackend`] keeping the entries in memory, for the lifetime of the process.
+#[derive(Debug, Default)]
+pub struct InMemoryBackend {
+    entries: Mutex<HashMap<String, String>>,
+}
+
+impl InMemoryBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of entries, expired ones included.
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+}
+
+impl CacheBackend for InMemoryBackend {
+    async fn get(&self, key: &str) -> Result<Option<String>, Box<dyn Error + Send + Sync>> {
+        Ok(self.entries.lock().unwrap().get(key).cloned())
+    }
+
+    async fn put(&self, key: &str, value: String) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.entries.lock().unwrap().insert(key.to_string(), value);
+        Ok(())
+    }
+
+    async fn remove(&self, key: &str) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.entries.lock().unwrap().remove(key);
+        Ok(())
+    }
+}
+
+/// A [`CacheBackend`] keeping every entry in a JSON file of a directory, so that entries
+/// outlive the process. With the `tokio` feature, files are read and written on the blocking
+/// threads of Tokio.
+#[derive(Debug, Clone)]
+pub struct DirectoryBackend {
+    dir: PathBuf,
+}
+
+impl DirectoryBackend {
+    /// Stores the entries in `dir`, which is created when the first entry is stored.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        let name: String = key
+            .chars()
+            .map(|c| match c {
+                'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '.' | '@' => c,
+                _ => '_',
+            })
+            .collect();
+        self.dir.join(format!("{name}.json"))
+    }
+}
+
+impl CacheBackend for DirectoryBackend {
+    async fn get(&self, key: &str) -> Result<Option<String>, Box<dyn Error + Send + Sync>> {
+        let path = self.path(key);
+        match crate::runtime::unblock(move || std::fs::read_to_string(path)).await {
+            Ok(value) => Ok(Some(value)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn put(&self, key: &str, value: String) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let (dir, path) = (self.dir.clone(), self.path(key));
+        crate::runtime::unblock(move || {
+            std::fs::create_dir_all(dir)?;
+            std::fs::write(path, value)
+        })
+        .await?;
+        Ok(())
+    }
+
+    async fn remove(&self, key: &str) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let path = self.path(key);
+        match crate::runtime::unblock(move || std::fs::remove_file(path)).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Entry<T> {
+    /// The request, to tell apart requests whose hashes collide.
+    request: Value,
+    response: T,
+    /// Milliseconds since the Unix epoch.
+    expires_at: Option<u64>,
+}
+
+/// Caches responses by model and request, for requests with a temperature of 0 or a seed.
+///
+/// Requests are compared once the default options of the client are applied, ignoring
+/// how long the model is kept loaded. Other requests always go to the model, as their
+/// responses are meant to vary. Errors of the backend are returned as
+/// [`OllamaError::Other`].
+///
+/// ```no_run
+/// # async fn run() -> ollama_rs::error::Result<()> {
+/// use std::time::Duration;
+///
+/// use ollama_rs::{
+///     cache::exact::{DirectoryBackend, ExactCache},
+///     generation::completion::request::GenerationRequest,
+///     models::ModelOptions,
+///     Ollama,
+/// };
+///
+/// let ollama = Ollama::default();
+/// let cache = ExactCache::new(DirectoryBackend::new(".cache/ollama"))
+///     .ttl(Duration::from_secs(24 * 3600));
+///
+/// let request = GenerationRequest::new("llama3.2".into(), "What's the capital of France?")
+///     .options(ModelOptions::default().temperature(0.0));
+/// let res = cache.generate(&ollama, request).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct ExactCache<B: CacheBackend = InMemoryBackend> {
+    backend: B,
+    ttl: Option<Duration>,
+}
+
+impl<B: CacheBackend> ExactCache<B> {
+    pub fn new(backend: B) -> Self {
+        Self { backend, ttl: None }
+    }
+
+    /// How long responses are kept. (Default: forever)
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    pub fn backend(&self) -> &B {
+        &self.backend
+    }
+
+    /// Returns the cached response of the same request, or generates one, caching it if
+    /// the request is deterministic.
+    pub async fn generate(
+        &self,
+        ollama: &Ollama,
+        request: GenerationRequest<'_>,
+    ) -> error::Result<GenerationResponse> {
+        let options = ollama.with_default_options(request.options.clone());
+        if !options.as_ref().is_some_and(ModelOptions::is_deterministic) {
+            return ollama.generate(request).await;
+        }
+
+        let mut key_request = request.clone();
+        key_request.options = options;
+        key_request.keep_alive = None;
+        key_request.stream = false;
+        let (key, request_value) = key(&request.model_name, &key_request)?;
+
+        if let Some(response) = self.lookup(&key, &request_value).await? {
+            return Ok(response);
+        }
+
+        let response = ollama.generate(request).await?;
+        self.insert(&key, request_value, &response).await?;
+        Ok(response)
+    }
+
+    /// Returns the cached response of the same request, or sends the messages, caching the
+    /// response if the request is deterministic.
+    pub async fn send_chat_messages(
+        &self,
+        ollama: &Ollama,
+        request: ChatMessageRequest,
+    ) -> error::Result<ChatMessageResponse> {
+        let options = ollama.with_default_options(request.options.clone());
+        if !options.as_ref().is_some_and(ModelOptions::is_deterministic) {
+            return ollama.send_chat_messages(request).await;
+        }
+
+        let mut key_request = request.clone();
+        key_request.options = options;
+        key_request.keep_alive = None;
+        key_request.stream = false;
+        let (key, request_value) = key(&request.model_name, &key_request)?;
+
+        if let Some(response) = self.lookup(&key, &request_value).await? {
+            return Ok(response);
+        }
+
+        let response = ollama.send_chat_messages(request).await?;
+        self.insert(&key, request_value, &response).await?;
+        Ok(response)
+    }
+
+    async fn lookup<T: DeserializeOwned>(
+        &self,
+        key: &str,
+        request: &Value,
+    ) -> error::Result<Option<T>> {
+        let Some(value) = self.backend.get(key).await.map_err(backend_error)? else {
+            return Ok(None);
+        };
+        // Entries that can't be read, e.g. written by another version, are treated as misses.
+        let Ok(entry) = serde_json::from_str::<Entry<T>>(&value) else {
+            return Ok(None);
+        };
+
+        if entry
+            .expires_at
+            .is_some_and(|expires_at| expires_at <= now())
+        {
+            self.backend.remove(key).await.map_err(backend_error)?;
+            return Ok(None);
+        }
+        Ok((entry.request == *request).then_some(entry.response))
+    }
+
+    async fn insert<T: Serialize>(
+        &self,
+        key: &str,
+        request: Value,
+        response: &T,
+    ) -> error::Result<()> {
+        let entry = Entry {
+            request,
+            response,
+            expires_at: self.ttl.map(|ttl| now() + ttl.as_millis() as u64),
+        };
+        let value = serde_json::to_string(&entry)?;
+        self.backend.put(key, value).await.map_err(backend_error)
+    }
+}
+
+/// The key of a request, along with the request as JSON.
+fn key<T: Serialize>(model_name: &str, request: &T) -> error::Result<(String, Value)> {
+    let value = serde_json::to_value(request)?;
+    let hash = crate::stable_hash(value.to_string().as_bytes());
+    Ok((format!("{model_name}@{hash:016x}"), value))
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+fn backend_error(e: Box<dyn Error + Send + Sync>) -> OllamaError {
+    OllamaError::Other(format!("Cache backend error: {e}"))
+}
@@ -0,0 +1,191 @@
+//! A cache matching prompts by meaning rather than by text, so that rephrasings of a
+//! previous prompt get its response.
+
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use serde_json::Value;
+
+use crate::{
+    error::{OllamaError, Result},
+    generation::{
+        completion::{request::GenerationRequest, GenerationResponse},
+        embeddings::{cosine_similarity, request::GenerateEmbeddingsRequest},
+    },
+    Ollama,
+};
+
+struct Entry {
+    /// The request without its prompt.
+    request: Value,
+    embedding: Vec<f32>,
+    response: GenerationResponse,
+    inserted_at: Instant,
+    used_at: Instant,
+}
+
+/// Caches generations by the embedding of their prompt.
+///
+/// A request is answered from the cache when a previous request had a prompt similar
+/// enough, i.e. whose embedding has a cosine similarity above the threshold, and was the
+/// same otherwise: same model, system prompt, format, options and so on. Requests with a
+/// context or images are neither answered from the cache nor cached, as their prompt
+/// doesn't tell them apart.
+///
+/// ```no_run
+/// # async fn run() -> ollama_rs::error::Result<()> {
+/// use std::time::Duration;
+///
+/// use ollama_rs::{
+///     cache::semantic::SemanticCache, generation::completion::request::GenerationRequest,
+///     Ollama,
+/// };
+///
+/// let ollama = Ollama::default();
+/// let cache = SemanticCache::new("nomic-embed-text")
+///     .threshold(0.95)
+///     .ttl(Duration::from_secs(3600));
+///
+/// let request = GenerationRequest::new("llama3.2".into(), "What's the capital of France?");
+/// let res = cache.generate(&ollama, request).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct SemanticCache {
+    embedding_model: String,
+    threshold: f32,
+    ttl: Option<Duration>,
+    max_entries: usize,
+    entries: Mutex<Vec<Entry>>,
+}
+
+impl SemanticCache {
+    /// Creates a cache embedding the prompts with `embedding_model`.
+    pub fn new(embedding_model: impl Into<String>) -> Self {
+        Self {
+            embedding_model: embedding_model.into(),
+            threshold: 0.95,
+            ttl: None,
+            max_entries: 1000,
+            entries: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// The minimal cosine similarity of the prompts of a hit. (Default: 0.95)
+    pub fn threshold(mut self, threshold: f32) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// How long responses are kept. (Default: forever)
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// The number of responses kept, the least recently used being evicted first.
+    /// (Default: 1000)
+    pub fn max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = max_entries;
+        self
+    }
+
+    /// The number of cached responses, expired ones included.
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+
+    /// Returns the cached response of a similar prompt, or generates and caches one.
+    pub async fn generate(
+        &self,
+        ollama: &Ollama,
+        request: GenerationRequest<'_>,
+    ) -> Result<GenerationResponse> {
+        if request.context.is_some() || !request.images.is_empty() {
+            return ollama.generate(request).await;
+        }
+
+        let key = key(ollama, &request)?;
+        let embedding = self.embed(ollama, &request.prompt).await?;
+        if let Some(response) = self.lookup(&key, &embedding) {
+            return Ok(response);
+        }
+
+        let response = ollama.generate(request).await?;
+        self.insert(key, embedding, response.clone());
+        Ok(response)
+    }
+
+    async fn embed(&self, ollama: &Ollama, prompt: &str) -> Result<Vec<f32>> {
+        let request = GenerateEmbeddingsRequest::new(self.embedding_model.clone(), prompt.into());
+        ollama
+            .generate_embeddings(request)
+            .await?
+            .embeddings
+            .pop()
+            .ok_or_else(|| OllamaError::Other("No embedding was returned".to_string()))
+    }
+
+    fn lookup(&self, request: &Value, embedding: &[f32]) -> Option<GenerationResponse> {
+        let mut entries = self.entries.lock().unwrap();
+        let now = Instant::now();
+        if let Some(ttl) = self.ttl {
+            entries.retain(|entry| now.duration_since(entry.inserted_at) < ttl);
+        }
+
+        let entry = entries
+            .iter_mut()
+            .filter(|entry| entry.request == *request)
+            .map(|entry| (cosine_similarity(&entry.embedding, embedding), entry))
+            .filter(|(similarity, _)| *similarity >= self.threshold)
+            .max_by(|(a, _), (b, _)| a.total_cmp(b))
+            .map(|(_, entry)| entry)?;
+
+        entry.used_at = now;
+        Some(entry.response.clone())
+    }
+
+    fn insert(&self, request: Value, embedding: Vec<f32>, response: GenerationResponse) {
+        let mut entries = self.entries.lock().unwrap();
+        while !entries.is_empty() && entries.len() >= self.max_entries {
+            let oldest = entries
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, entry)| entry.used_at)
+                .map(|(i, _)| i)
+                .unwrap();
+            entries.swap_remove(oldest);
+        }
+
+        if self.max_entries > 0 {
+            let now = Instant::now();
+            entries.push(Entry {
+                request,
+                embedding,
+                response,
+                inserted_at: now,
+                used_at: now,
+            });
+        }
+    }
+}
+
+/// The parts of `request` compared exactly, i.e. all but the prompt, as JSON.
+fn key(ollama: &Ollama, request: &GenerationRequest<'_>) -> Result<Value> {
+    let mut key = request.clone();
+    key.prompt = "".into();
+    key.options = ollama.with_default_options(request.options.clone());
+    key.keep_alive = None;
+    key.stream = false;
+    Ok(serde_json::to_value(key)?)
+}
@@ -0,0 +1,224 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use crate::error::OllamaError;
+
+/// The state of a [`CircuitBreaker`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CircuitState {
+    /// Requests are sent, and their outcomes recorded.
+    Closed,
+    /// Too many requests failed: requests fail with [`OllamaError::CircuitOpen`] without
+    /// being sent.
+    Open,
+    /// The open duration elapsed: a few probe requests are sent, closing the circuit if they
+    /// succeed or opening it again if they fail.
+    HalfOpen,
+}
+
+#[derive(Debug)]
+struct Breaker {
+    state: CircuitState,
+    /// The outcomes of the last requests while closed, `true` for failures.
+    outcomes: VecDeque<bool>,
+    opened_at: Instant,
+    probes_in_flight: u32,
+    probe_successes: u32,
+}
+
+/// Stops sending requests to a server failing too often, so that callers fail fast with
+/// [`OllamaError::CircuitOpen`] instead of piling up timeouts while Ollama is down or keeps
+/// running out of memory.
+///
+/// The circuit opens when the share of failures among the last requests reaches the
+/// threshold. After the open duration, probe requests are let through: the circuit closes
+/// once they succeed, and opens again if one fails. Failures are network errors and `5xx`
+/// responses; other errors, such as unknown models, are the caller's.
+///
+/// A breaker is given to a client with [`OllamaBuilder::circuit_breaker`](crate::OllamaBuilder::circuit_breaker),
+/// and shared by its clones.
+#[derive(Debug, Clone)]
+pub struct CircuitBreaker {
+    failure_threshold: f64,
+    window: usize,
+    min_requests: usize,
+    open_duration: Duration,
+    probes: u32,
+    breaker: Arc<Mutex<Breaker>>,
+}
+
+impl CircuitBreaker {
+    pub fn new() -> Self {
+        Self {
+            failure_threshold: 0.5,
+            window: 20,
+            min_requests: 5,
+            open_duration: Duration::from_secs(30),
+            probes: 1,
+            breaker: Arc::new(Mutex::new(Breaker {
+                state: CircuitState::Closed,
+                outcomes: VecDeque::new(),
+                opened_at: Instant::now(),
+                probes_in_flight: 0,
+                probe_successes: 0,
+            })),
+        }
+    }
+
+    /// The share of failed requests, between 0 and 1, opening the circuit. (Default: 0.5)
+    pub fn failure_threshold(mut self, failure_threshold: f64) -> Self {
+        self.failure_threshold = failure_threshold;
+        self
+    }
+
+    /// The number of last requests the failure rate is computed over. (Default: 20)
+    pub fn window(mut self, window: usize) -> Self {
+        self.window = window.max(1);
+        self
+    }
+
+    /// The number of requests needed before the circuit may open. (Default: 5)
+    pub fn min_requests(mut self, min_requests: usize) -> Self {
+        self.min_requests = min_requests;
+        self
+    }
+
+    /// How long the circuit stays open before probing the server. (Default: 30s)
+    pub fn open_duration(mut self, open_duration: Duration) -> Self {
+        self.open_duration = open_duration;
+        self
+    }
+
+    /// The number of probe requests that must succeed to close the circuit. (Default: 1)
+    pub fn probes(mut self, probes: u32) -> Self {
+        self.probes = probes.max(1);
+        self
+    }
+
+    pub fn state(&self) -> CircuitState {
+        let mut breaker = self.breaker.lock().unwrap();
+        self.refresh(&mut breaker);
+        breaker.state
+    }
+
+    /// Closes the circuit and forgets the outcomes of previous requests.
+    pub fn reset(&self) {
+        let mut breaker = self.breaker.lock().unwrap();
+        self.close(&mut breaker);
+    }
+
+    /// Moves to half-open once the open duration has elapsed.
+    fn refresh(&self, breaker: &mut Breaker) {
+        if breaker.state == CircuitState::Open && breaker.opened_at.elapsed() >= self.open_duration
+        {
+            breaker.state = CircuitState::HalfOpen;
+            breaker.probes_in_flight = 0;
+            breaker.probe_successes = 0;
+        }
+    }
+
+    fn open(&self, breaker: &mut Breaker) {
+        breaker.state = CircuitState::Open;
+        breaker.opened_at = Instant::now();
+        breaker.outcomes.clear();
+    }
+
+    fn close(&self, breaker: &mut Breaker) {
+        breaker.state = CircuitState::Closed;
+        breaker.outcomes.clear();
+    }
+
+    /// Lets a request through, or fails if the circuit is open or enough probes are in flight.
+    pub(crate) fn acquire(&self) -> Result<Attempt<'_>, OllamaError> {
+        let mut breaker = self.breaker.lock().unwrap();
+        self.refresh(&mut breaker);
+
+        let probe = match breaker.state {
+            CircuitState::Closed => false,
+            CircuitState::HalfOpen
+                if breaker.probes_in_flight + breaker.probe_successes < self.probes =>
+            {
+                breaker.probes_in_flight += 1;
+                true
+            }
+            CircuitState::HalfOpen | CircuitState::Open => {
+                let retry_in = self
+                    .open_duration
+                    .saturating_sub(breaker.opened_at.elapsed());
+                return Err(OllamaError::CircuitOpen { retry_in });
+            }
+        };
+
+        Ok(Attempt {
+            breaker: self,
+            probe,
+            recorded: false,
+        })
+    }
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A request let through by a [`CircuitBreaker`], whose outcome must be recorded.
+pub(crate) struct Attempt<'a> {
+    breaker: &'a CircuitBreaker,
+    probe: bool,
+    recorded: bool,
+}
+
+impl Attempt<'_> {
+    pub(crate) fn record(mut self, failed: bool) {
+        self.recorded = true;
+        let this = self.breaker;
+        let mut breaker = this.breaker.lock().unwrap();
+
+        if self.probe {
+            breaker.probes_in_flight = breaker.probes_in_flight.saturating_sub(1);
+            if breaker.state != CircuitState::HalfOpen {
+                return;
+            }
+            if failed {
+                this.open(&mut breaker);
+            } else {
+                breaker.probe_successes += 1;
+                if breaker.probe_successes >= this.probes {
+                    this.close(&mut breaker);
+                }
+            }
+            return;
+        }
+
+        if breaker.state != CircuitState::Closed {
+            return;
+        }
+        breaker.outcomes.push_back(failed);
+        while breaker.outcomes.len() > this.window {
+            breaker.outcomes.pop_front();
+        }
+
+        let failures = breaker.outcomes.iter().filter(|failed| **failed).count();
+        let requests = breaker.outcomes.len();
+        if requests >= this.min_requests
+            && failures as f64 >= this.failure_threshold * requests as f64
+        {
+            this.open(&mut breaker);
+        }
+    }
+}
+
+impl Drop for Attempt<'_> {
+    fn drop(&mut self) {
+        // A cancelled probe frees its slot for another one.
+        if self.probe && !self.recorded {
+            let mut breaker = self.breaker.breaker.lock().unwrap();
+            breaker.probes_in_flight = breaker.probes_in_flight.saturating_sub(1);
+        }
+    }
+}
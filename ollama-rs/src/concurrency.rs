@@ -0,0 +1,155 @@
+//! Limits on the requests a client sends at a time, so that bursts of requests wait in the
+//! client instead of being rejected by the server.
+//!
+//! ```no_run
+//! # fn main() -> ollama_rs::error::Result<()> {
+//! use ollama_rs::{concurrency::ConcurrencyLimits, Ollama};
+//!
+//! let ollama = Ollama::builder()
+//!     .concurrency_limits(
+//!         ConcurrencyLimits::new()
+//!             .max_requests(8)
+//!             // Matching `OLLAMA_NUM_PARALLEL=2` on the server
+//!             .max_requests_per_model(2)
+//!             .model("llama3.3:70b", 1),
+//!     )
+//!     .build()?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Requests over the limits wait in the order they were sent. A request holds its slot
+//! until its response is read: for streams, until the stream ends or is dropped. Clones of
+//! the client share the same slots.
+
+use std::{collections::HashMap, sync::Arc};
+
+use crate::slots::{Permit, SlotPolicy, Slots};
+
+/// The numbers of requests a client sends at a time, see [`concurrency`](self).
+#[derive(Debug, Clone, Default)]
+pub struct ConcurrencyLimits {
+    max_requests: Option<usize>,
+    max_requests_per_model: Option<usize>,
+    models: HashMap<String, usize>,
+}
+
+impl ConcurrencyLimits {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of requests sent at a time, to any endpoint. (Default: unlimited)
+    pub fn max_requests(mut self, max_requests: usize) -> Self {
+        self.max_requests = Some(max_requests.max(1));
+        self
+    }
+
+    /// The number of generation, chat and embeddings requests sent at a time for each
+    /// model, e.g. matching the `OLLAMA_NUM_PARALLEL` setting of the server.
+    /// (Default: unlimited)
+    pub fn max_requests_per_model(mut self, max_requests: usize) -> Self {
+        self.max_requests_per_model = Some(max_requests.max(1));
+        self
+    }
+
+    /// The number of requests sent at a time for `model_name`, instead of the
+    /// [`ConcurrencyLimits::max_requests_per_model`] of every model.
+    pub fn model(mut self, model_name: impl Into<String>, max_requests: usize) -> Self {
+        self.models.insert(model_name.into(), max_requests.max(1));
+        self
+    }
+
+    fn model_limit(&self, model_name: &str) -> Option<usize> {
+        self.models
+            .get(model_name)
+            .copied()
+            .or(self.max_requests_per_model)
+    }
+
+    fn has_model_limits(&self) -> bool {
+        self.max_requests_per_model.is_some() || !self.models.is_empty()
+    }
+}
+
+/// The requests running for a client and its clones, within its limits.
+#[derive(Debug)]
+pub(crate) struct Running {
+    limits: ConcurrencyLimits,
+    total: usize,
+    by_model: HashMap<String, usize>,
+}
+
+impl SlotPolicy for Running {
+    /// The model of the request, if it is limited.
+    type Key = Option<String>;
+    /// Requests over the limits wait in the order they were sent.
+    type Rank = ();
+
+    fn fits(&self, model_name: &Option<String>) -> bool {
+        let total = self.limits.max_requests.is_none_or(|max| self.total < max);
+        let model = model_name
+            .as_deref()
+            .and_then(|model_name| {
+                let limit = self.limits.model_limit(model_name)?;
+                let running = self.by_model.get(model_name).copied();
+                Some(running.unwrap_or(0) < limit)
+            })
+            .unwrap_or(true);
+        total && model
+    }
+
+    fn take(&mut self, model_name: &Option<String>) {
+        self.total += 1;
+        if let Some(model_name) = model_name {
+            *self.by_model.entry(model_name.clone()).or_default() += 1;
+        }
+    }
+
+    fn release(&mut self, model_name: &Option<String>) {
+        self.total -= 1;
+        if let Some(model_name) = model_name {
+            if let Some(running) = self.by_model.get_mut(model_name) {
+                *running -= 1;
+                if *running == 0 {
+                    self.by_model.remove(model_name);
+                }
+            }
+        }
+    }
+}
+
+/// The slots of the requests of a client and its clones.
+pub(crate) type Limiter = Slots<Running>;
+
+impl Limiter {
+    pub(crate) fn limited(limits: ConcurrencyLimits) -> Self {
+        Slots::new(Running {
+            limits,
+            total: 0,
+            by_model: HashMap::new(),
+        })
+    }
+
+    /// The model whose slots a request to `path` with `body` takes, if it is limited.
+    pub(crate) fn model_of(&self, path: &str, body: Option<&[u8]>) -> Option<String> {
+        const INFERENCE_ENDPOINTS: [&str; 5] = [
+            "api/generate",
+            "api/chat",
+            "api/embed",
+            "api/embeddings",
+            "v1/chat/completions",
+        ];
+
+        let has_model_limits = self.policy(|running| running.limits.has_model_limits());
+        if !has_model_limits || !INFERENCE_ENDPOINTS.iter().any(|e| path.ends_with(e)) {
+            return None;
+        }
+        let body: serde_json::Value = serde_json::from_slice(body?).ok()?;
+        body["model"].as_str().map(str::to_string)
+    }
+}
+
+/// The slot of the request of a response, kept in its extensions until its body is read.
+#[derive(Debug, Clone)]
+pub(crate) struct ConcurrencySlot(#[allow(dead_code)] pub(crate) Arc<Permit<Running>>);
@@ -1,16 +1,208 @@
-use std::collections::HashMap;
+use std::{
+    collections::BTreeMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
 use crate::{
+    error::{OllamaError, ToolCallError},
     generation::{
-        chat::{request::ChatMessageRequest, ChatMessage, ChatMessageResponse, MessageRole},
-        parameters::FormatType,
-        tools::{Tool, ToolHolder, ToolInfo},
+        chat::{
+            request::ChatMessageRequest, ChatMessage, ChatMessageFinalResponseData,
+            ChatMessageResponse, MessageRole,
+        },
+        done_reason::DoneReason,
+        parameters::{FormatType, Think},
+        tools::{
+            emulation::{self, ToolCallStrategy},
+            DynTool, StatefulTool, Tool, ToolCall, ToolChoice, ToolInfo, ToolSet, WithState,
+        },
+        usage::Usage,
     },
     history::ChatHistory,
     models::ModelOptions,
-    Ollama,
+    persona::Persona,
+    router::{LatencyTier, ModelRouter, RequestTraits},
+    Ollama, Timestamp,
 };
 
+/// What happens to a tool call in a [`Coordinator`], given to [`Coordinator::on_tool_event`].
+///
+/// Every event carries the id of the call, so that the result of a call can be told apart from
+/// the results of the other calls of the same turn.
+#[derive(Debug)]
+pub enum ToolEvent<'a> {
+    /// The model called a tool, which is about to run.
+    Call {
+        id: &'a str,
+        name: &'a str,
+        arguments: &'a Value,
+    },
+    /// The tool returned, and its result was added to the history.
+    Result {
+        id: &'a str,
+        name: &'a str,
+        content: &'a str,
+    },
+    /// The tool failed, which ends the chat with `error`.
+    Error {
+        id: &'a str,
+        name: &'a str,
+        error: &'a OllamaError,
+    },
+}
+
+impl ToolEvent<'_> {
+    /// The id of the tool call.
+    pub fn id(&self) -> &str {
+        match self {
+            Self::Call { id, .. } | Self::Result { id, .. } | Self::Error { id, .. } => id,
+        }
+    }
+
+    /// The name of the called tool.
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Call { name, .. } | Self::Result { name, .. } | Self::Error { name, .. } => name,
+        }
+    }
+}
+
+/// The record of a run of a [`Coordinator`], from [`Coordinator::transcript`], for audit logs
+/// or to replay the run in debugging tools.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Transcript {
+    /// The whole conversation, including the tool calls and their results.
+    pub messages: Vec<ChatMessage>,
+    /// The turns of the model and the tool calls, in order.
+    pub steps: Vec<TranscriptStep>,
+}
+
+impl Transcript {
+    /// The token counts and timings of the turns of the model, summed.
+    pub fn usage(&self) -> Usage {
+        let mut usage = Usage::default();
+        for step in &self.steps {
+            if let TranscriptStep::Model {
+                final_data: Some(data),
+                ..
+            } = step
+            {
+                usage += data.usage();
+            }
+        }
+        usage
+    }
+}
+
+/// A step of a [`Transcript`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TranscriptStep {
+    /// A turn of the model.
+    Model {
+        model: String,
+        created_at: Timestamp,
+        /// The message of the model, with its tool calls.
+        message: ChatMessage,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        done_reason: Option<DoneReason>,
+        /// The token counts and timings reported by the server.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        final_data: Option<ChatMessageFinalResponseData>,
+        /// The time from sending the request to receiving the whole response, in milliseconds.
+        duration_ms: u64,
+    },
+    /// A call of a tool.
+    ToolCall {
+        id: String,
+        name: String,
+        arguments: Value,
+        /// What the tool returned, if it succeeded.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        result: Option<String>,
+        /// Why the tool failed, if it did.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+        /// The time the tool took, in milliseconds.
+        duration_ms: u64,
+    },
+}
+
+fn millis(elapsed: Duration) -> u64 {
+    elapsed.as_millis().try_into().unwrap_or(u64::MAX)
+}
+
+/// The latencies and token counts of the last [`Coordinator::chat`] or
+/// [`Coordinator::chat_stream`], from [`Coordinator::metrics`].
+#[derive(Debug, Clone, Default)]
+pub struct RunMetrics {
+    /// The turns of the model, in order.
+    pub iterations: Vec<IterationMetrics>,
+}
+
+impl RunMetrics {
+    /// The time spent waiting for the model and running tools.
+    pub fn total_duration(&self) -> Duration {
+        self.iterations
+            .iter()
+            .map(|iteration| iteration.latency + iteration.tool_duration())
+            .sum()
+    }
+
+    /// The token counts and timings reported by the server, summed over the turns.
+    pub fn usage(&self) -> Usage {
+        let mut usage = Usage::default();
+        for iteration in &self.iterations {
+            usage += iteration.usage.unwrap_or_default();
+        }
+        usage
+    }
+
+    /// The time spent running each tool, by name.
+    pub fn tool_durations(&self) -> BTreeMap<String, Duration> {
+        let mut durations = BTreeMap::new();
+        for tool in self.iterations.iter().flat_map(|i| &i.tools) {
+            *durations.entry(tool.name.clone()).or_default() += tool.duration;
+        }
+        durations
+    }
+}
+
+/// The metrics of a turn of the model and of the tools it called.
+#[derive(Debug, Clone)]
+pub struct IterationMetrics {
+    pub model: String,
+    /// The time from sending the request to receiving the whole response.
+    pub latency: Duration,
+    /// The token counts and timings reported by the server, if it did.
+    pub usage: Option<Usage>,
+    /// The tools called in this turn, in order.
+    pub tools: Vec<ToolMetrics>,
+}
+
+impl IterationMetrics {
+    /// The time spent running the tools of this turn.
+    pub fn tool_duration(&self) -> Duration {
+        self.tools.iter().map(|tool| tool.duration).sum()
+    }
+}
+
+/// The metrics of a tool call.
+#[derive(Debug, Clone)]
+pub struct ToolMetrics {
+    /// The [`ToolCall::id`] of the call.
+    pub id: String,
+    pub name: String,
+    pub duration: Duration,
+    pub failed: bool,
+}
+
+type ToolHook = Arc<dyn Fn(&ToolEvent<'_>) + Send + Sync>;
+
 /// A coordinator for managing chat interactions and tool usage.
 ///
 /// This struct is responsible for coordinating chat messages and tool
@@ -21,10 +213,17 @@ pub struct Coordinator<C: ChatHistory> {
     ollama: Ollama,
     options: ModelOptions,
     history: C,
-    tool_infos: Vec<ToolInfo>,
-    tools: HashMap<&'static str, Box<dyn ToolHolder>>,
+    tools: ToolSet,
     debug: bool,
     format: Option<FormatType>,
+    think: Option<Think>,
+    tool_call_strategy: ToolCallStrategy,
+    tool_choice: ToolChoice,
+    router: Option<ModelRouter>,
+    latency: LatencyTier,
+    tool_hook: Option<ToolHook>,
+    steps: Vec<TranscriptStep>,
+    metrics: RunMetrics,
 }
 
 impl<C: ChatHistory> Coordinator<C> {
@@ -45,19 +244,46 @@ impl<C: ChatHistory> Coordinator<C> {
             ollama,
             options: ModelOptions::default(),
             history,
-            tool_infos: Vec::default(),
-            tools: HashMap::default(),
+            tools: ToolSet::default(),
             debug: false,
             format: None,
+            think: None,
+            tool_call_strategy: ToolCallStrategy::Native,
+            tool_choice: ToolChoice::Auto,
+            router: None,
+            latency: LatencyTier::default(),
+            tool_hook: None,
+            steps: Vec::new(),
+            metrics: RunMetrics::default(),
         }
     }
 
     pub fn add_tool<T: Tool + 'static>(mut self, tool: T) -> Self {
-        self.tool_infos.push(ToolInfo::new::<_, T>());
-        self.tools.insert(T::name(), Box::new(tool));
+        self.tools.insert(tool);
+        self
+    }
+
+    /// Adds an already boxed tool, e.g. one picked at runtime.
+    pub fn add_dyn_tool(mut self, tool: Box<dyn DynTool>) -> Self {
+        self.tools.insert_dyn(tool);
         self
     }
 
+    /// Adds every tool of the set, replacing existing tools with the same name.
+    pub fn tools(mut self, tools: ToolSet) -> Self {
+        self.tools.extend(tools);
+        self
+    }
+
+    /// Adds a tool that is given the shared `state` every time it is called.
+    pub fn add_tool_with_state<S, T>(self, tool: T, state: Arc<S>) -> Self
+    where
+        S: Send + Sync + 'static,
+        T: StatefulTool<S> + 'static,
+    {
+        self.add_tool(WithState::new(tool, state))
+    }
+
     pub fn format(mut self, format: FormatType) -> Self {
         self.format = Some(format);
         self
@@ -68,44 +294,307 @@ impl<C: ChatHistory> Coordinator<C> {
         self
     }
 
+    /// Whether and how much a thinking model reasons before each of its turns, see [`Think`].
+    pub fn think(mut self, think: impl Into<Think>) -> Self {
+        self.think = Some(think.into());
+        self
+    }
+
     pub fn debug(mut self, debug: bool) -> Self {
         self.debug = debug;
         self
     }
 
-    pub async fn chat(
+    /// How the model calls tools. Use [`ToolCallStrategy::Emulated`] with models whose template
+    /// doesn't support tools. (Default: [`ToolCallStrategy::Native`])
+    pub fn tool_call_strategy(mut self, strategy: ToolCallStrategy) -> Self {
+        self.tool_call_strategy = strategy;
+        self
+    }
+
+    /// Which tools the model may call. The choice applies until tools have been called, the model
+    /// then decides whether to call more tools. With [`ToolChoice::Required`] or [`ToolChoice::Tool`],
+    /// the chat fails with [`ToolCallError::ToolCallRequired`](crate::error::ToolCallError::ToolCallRequired)
+    /// if the model answers without calling a tool. (Default: [`ToolChoice::Auto`])
+    pub fn tool_choice(mut self, tool_choice: ToolChoice) -> Self {
+        self.tool_choice = tool_choice;
+        self
+    }
+
+    /// Routes each turn to a model of `router`, picked from the images, tools and length of
+    /// the turn. The model given to [`Coordinator::new`] is used when no model of the router
+    /// supports a turn.
+    pub fn router(mut self, router: ModelRouter) -> Self {
+        self.router = Some(router);
+        self
+    }
+
+    /// The latency tier of the turns, when routing them with [`Coordinator::router`].
+    /// (Default: [`LatencyTier::Standard`])
+    pub fn latency(mut self, latency: LatencyTier) -> Self {
+        self.latency = latency;
+        self
+    }
+
+    /// Applies a persona: its system prompt is added to the history, its tools are added, and
+    /// its options replace the options of the coordinator. Apply it before the first chat,
+    /// for the system prompt to come first.
+    ///
+    /// Fails if the system prompt can't be rendered, e.g. because a variable is missing.
+    pub fn persona(mut self, persona: &Persona) -> crate::error::Result<Self> {
+        self.history
+            .push(ChatMessage::system(persona.system_prompt()?));
+        for tool in persona.shared_tools() {
+            self.tools.insert_dyn(tool);
+        }
+        if let Some(options) = persona.default_options() {
+            self.options = options.clone();
+        }
+        Ok(self)
+    }
+
+    /// Calls `hook` with each [`ToolEvent`], e.g. to log tool calls or show them in a UI.
+    ///
+    /// Tool calls are given an id, when the model didn't give one, before being added to the
+    /// history; the message with the result of a call has the id in
+    /// [`ChatMessage::tool_call_id`].
+    pub fn on_tool_event(mut self, hook: impl Fn(&ToolEvent<'_>) + Send + Sync + 'static) -> Self {
+        self.tool_hook = Some(Arc::new(hook));
+        self
+    }
+
+    fn emit(&self, event: ToolEvent<'_>) {
+        if let Some(hook) = &self.tool_hook {
+            hook(&event);
+        }
+    }
+
+    /// Gives an id to the tool calls without one. Must be called before the message is added
+    /// to the history.
+    fn assign_tool_call_ids(message: &mut ChatMessage) {
+        for call in &mut message.tool_calls {
+            call.id.get_or_insert_with(|| crate::unique_id("call"));
+        }
+    }
+
+    /// The conversation so far, along with the turns of the model and the tool calls made
+    /// by this coordinator.
+    pub fn transcript(&self) -> Transcript {
+        Transcript {
+            messages: self.history.messages().into_owned(),
+            steps: self.steps.clone(),
+        }
+    }
+
+    /// The latencies and token counts of each turn of the last chat, to track the performance
+    /// of an agent over time.
+    pub fn metrics(&self) -> &RunMetrics {
+        &self.metrics
+    }
+
+    /// Records a turn of the model, whose last response is `response`.
+    fn record_turn(
         &mut self,
-        messages: Vec<ChatMessage>,
-    ) -> crate::error::Result<ChatMessageResponse> {
-        if self.debug {
-            for m in &messages {
-                eprintln!("Hit {} with:", self.model);
-                eprintln!("\t{:?}: '{}'", m.role, m.content);
+        message: &ChatMessage,
+        response: &ChatMessageResponse,
+        start: Instant,
+    ) {
+        let latency = start.elapsed();
+        self.metrics.iterations.push(IterationMetrics {
+            model: response.model.clone(),
+            latency,
+            usage: response.usage(),
+            tools: Vec::new(),
+        });
+        self.steps.push(TranscriptStep::Model {
+            model: response.model.clone(),
+            created_at: response.created_at.clone(),
+            message: message.clone(),
+            done_reason: response.done_reason.clone(),
+            final_data: response.final_data.clone(),
+            duration_ms: millis(latency),
+        });
+    }
+
+    /// Runs a tool call, reporting it to the hook, and adds its result to the history.
+    async fn run_tool_call(&mut self, call: ToolCall) -> crate::error::Result<()> {
+        let id = call.id.unwrap_or_default();
+        let name = call.function.name;
+        let arguments = call.function.arguments;
+        self.emit(ToolEvent::Call {
+            id: &id,
+            name: &name,
+            arguments: &arguments,
+        });
+
+        let start = Instant::now();
+        let res = self.tools.call(&name, arguments.clone()).await;
+        let duration = start.elapsed();
+        if let Some(iteration) = self.metrics.iterations.last_mut() {
+            iteration.tools.push(ToolMetrics {
+                id: id.clone(),
+                name: name.clone(),
+                duration,
+                failed: res.is_err(),
+            });
+        }
+        self.steps.push(TranscriptStep::ToolCall {
+            id: id.clone(),
+            name: name.clone(),
+            arguments,
+            result: res.as_ref().ok().cloned(),
+            error: res.as_ref().err().map(ToString::to_string),
+            duration_ms: millis(duration),
+        });
+
+        match res {
+            Ok(content) => {
+                self.emit(ToolEvent::Result {
+                    id: &id,
+                    name: &name,
+                    content: &content,
+                });
+                self.history
+                    .push(ChatMessage::tool(content).with_tool_call_id(id));
+                Ok(())
             }
+            Err(error) => {
+                self.emit(ToolEvent::Error {
+                    id: &id,
+                    name: &name,
+                    error: &error,
+                });
+                Err(error)
+            }
+        }
+    }
+
+    /// Whether the last message is the result of a tool, i.e. the model is answering a tool call.
+    fn after_tool_call(&self) -> bool {
+        self.history
+            .messages()
+            .last()
+            .is_some_and(|m| m.role == MessageRole::Tool)
+    }
+
+    /// The tool choice of the next turn.
+    fn current_tool_choice(&self) -> ToolChoice {
+        if self.after_tool_call() {
+            ToolChoice::Auto
+        } else {
+            self.tool_choice.clone()
+        }
+    }
+
+    /// The tools the model may call in the next turn.
+    fn offered_tools(&self) -> Vec<ToolInfo> {
+        match self.current_tool_choice() {
+            ToolChoice::None => vec![],
+            ToolChoice::Tool(name) => self
+                .tools
+                .infos()
+                .into_iter()
+                .filter(|info| info.name() == name)
+                .collect(),
+            ToolChoice::Auto | ToolChoice::Required => self.tools.infos(),
+        }
+    }
+
+    fn requires_tool_call(&self) -> bool {
+        matches!(
+            self.current_tool_choice(),
+            ToolChoice::Required | ToolChoice::Tool(_)
+        )
+    }
+
+    /// Creates the request of the next turn. The format is only set once the tools have been called.
+    fn request(&self, messages: Vec<ChatMessage>) -> ChatMessageRequest {
+        let tools = self.offered_tools();
+        let tool_choice = self.current_tool_choice();
+
+        let mut request = if tools.is_empty() {
+            ChatMessageRequest::new(self.model.clone(), messages)
+        } else if self.tool_call_strategy == ToolCallStrategy::Emulated {
+            let messages = emulation::emulate_messages(messages, &tools);
+            ChatMessageRequest::new(self.model.clone(), messages)
+        } else {
+            ChatMessageRequest::new(self.model.clone(), messages).tools(tools.clone())
+        }
+        .options(self.options.clone());
+
+        if let Some(think) = self.think {
+            request = request.think(think);
         }
 
-        let mut request = ChatMessageRequest::new(self.model.clone(), messages)
-            .options(self.options.clone())
-            .tools(self.tool_infos.clone());
+        if tool_choice != ToolChoice::Auto && self.tool_call_strategy == ToolCallStrategy::Native {
+            request = request.tool_choice(tool_choice);
+        }
+
+        if let Some(router) = &self.router {
+            let traits = RequestTraits::of(&request).latency(self.latency);
+            if let Some(model) = router.route_traits(&traits) {
+                request.model_name = model.to_string();
+            }
+        }
 
         if let Some(format) = &self.format {
             // If no tools are specified, set the format on the request. Otherwise wait for the
             // recursive call by checking that the last message in the history has a Tool role,
             // before setting the format. Ollama otherwise won't call the tool if the format
             // is set on the first request.
-            if self.tool_infos.is_empty() {
+            if tools.is_empty() || self.after_tool_call() {
                 request = request.format(format.clone());
-            } else if let Some(last_message) = self.history.messages().last() {
-                if last_message.role == MessageRole::Tool {
-                    request = request.format(format.clone());
-                }
             }
         }
 
-        let resp = self
-            .ollama
-            .send_chat_messages_with_history(&mut self.history, request)
-            .await?;
+        request
+    }
+
+    /// With emulated tool calls, fills the tool calls of a response from its text.
+    /// Must be called before the response is added to the history.
+    fn parse_tool_calls(&self, message: &mut ChatMessage) {
+        if self.tool_call_strategy == ToolCallStrategy::Emulated {
+            let tools = self.offered_tools();
+            if !tools.is_empty() {
+                message.tool_calls = emulation::parse_tool_calls(&message.content, &tools);
+            }
+        }
+    }
+
+    pub async fn chat(
+        &mut self,
+        messages: Vec<ChatMessage>,
+    ) -> crate::error::Result<ChatMessageResponse> {
+        self.metrics = RunMetrics::default();
+        self.chat_turn(messages).await
+    }
+
+    /// A turn of [`Coordinator::chat`], followed by the turns answering the tool calls.
+    async fn chat_turn(
+        &mut self,
+        messages: Vec<ChatMessage>,
+    ) -> crate::error::Result<ChatMessageResponse> {
+        if self.debug {
+            for m in &messages {
+                eprintln!("Hit {} with:", self.model);
+                eprintln!("\t{:?}: '{}'", m.role, m.content);
+            }
+        }
+
+        for m in messages {
+            self.history.push(m);
+        }
+
+        let request = self.request(self.history.messages().to_vec());
+        let start = Instant::now();
+        let mut resp = self.ollama.send_chat_messages(request).await?;
+        self.parse_tool_calls(&mut resp.message);
+        if self.requires_tool_call() && resp.message.tool_calls.is_empty() {
+            return Err(ToolCallError::ToolCallRequired.into());
+        }
+        Self::assign_tool_call_ids(&mut resp.message);
+        self.record_turn(&resp.message, &resp, start);
+        self.history.push(resp.message.clone());
 
         if !resp.message.tool_calls.is_empty() {
             for call in resp.message.tool_calls {
@@ -113,24 +602,17 @@ impl<C: ChatHistory> Coordinator<C> {
                     eprintln!("Tool call: {:?}", call.function); // TODO: Use log crate?
                 }
 
-                let Some(tool) = self.tools.get_mut(call.function.name.as_str()) else {
-                    return Err(crate::error::ToolCallError::UnknownToolName.into());
-                };
-
-                let resp = tool
-                    .call(call.function.arguments)
-                    .await
-                    .map_err(crate::error::ToolCallError::InternalToolError)?;
+                self.run_tool_call(call).await?;
 
                 if self.debug {
-                    eprintln!("Tool response: {}", &resp);
+                    if let Some(resp) = self.history.messages().last() {
+                        eprintln!("Tool response: {}", &resp.content);
+                    }
                 }
-
-                self.history.push(ChatMessage::tool(resp))
             }
 
             // recurse
-            Box::pin(self.chat(vec![])).await
+            Box::pin(self.chat_turn(vec![])).await
         } else {
             if self.debug {
                 eprintln!(
@@ -142,4 +624,116 @@ impl<C: ChatHistory> Coordinator<C> {
             Ok(resp)
         }
     }
+
+    /// Like [`Coordinator::chat`], but streams the responses of the model.
+    ///
+    /// Tool calls made during the stream are executed once the turn is done, and their results are
+    /// sent back to the model, whose next turn is streamed in turn. Every turn ends with a chunk
+    /// whose `done` is true; the stream ends after the first turn without tool calls.
+    ///
+    /// With [`ToolCallStrategy::Emulated`], the text of a turn is held back while it may be a
+    /// tool call, i.e. while it starts with a JSON object or array. If it is one, the text isn't
+    /// streamed and the calls come with the chunk whose `done` is true.
+    #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+    #[cfg(feature = "stream")]
+    pub fn chat_stream(
+        &mut self,
+        messages: Vec<ChatMessage>,
+    ) -> impl futures_util::Stream<Item = crate::error::Result<ChatMessageResponse>> + '_ {
+        use futures_util::StreamExt;
+
+        async_stream::stream! {
+            self.metrics = RunMetrics::default();
+            for m in messages {
+                self.history.push(m);
+            }
+
+            loop {
+                let request = self.request(self.history.messages().to_vec());
+                let start = Instant::now();
+                let mut stream = match self.ollama.send_chat_messages_stream(request).await {
+                    Ok(stream) => stream,
+                    Err(e) => {
+                        yield Err(e);
+                        return;
+                    }
+                };
+
+                let mut message = ChatMessage::assistant(String::new());
+                let mut last = None;
+                // With emulated tool calls, the length of the text streamed so far, as text which
+                // may be a call is held back
+                let emulated = self.tool_call_strategy == ToolCallStrategy::Emulated
+                    && !self.offered_tools().is_empty();
+                let mut streamed = 0;
+                while let Some(item) = stream.next().await {
+                    let mut item = match item {
+                        Ok(item) => item,
+                        Err(e) => {
+                            yield Err(e);
+                            return;
+                        }
+                    };
+
+                    // The ids are assigned before the calls are yielded, to match the events
+                    Self::assign_tool_call_ids(&mut item.message);
+                    message.content.push_str(&item.message.content);
+                    if let Some(part) = &item.message.thinking {
+                        message.thinking.get_or_insert_with(String::new).push_str(part);
+                    }
+                    message.tool_calls.extend(item.message.tool_calls.iter().cloned());
+                    if emulated {
+                        if item.done {
+                            self.parse_tool_calls(&mut message);
+                            Self::assign_tool_call_ids(&mut message);
+                            item.message.tool_calls = message.tool_calls.clone();
+                        }
+                        let held = if item.done {
+                            !message.tool_calls.is_empty()
+                        } else {
+                            streamed == 0 && emulation::may_be_tool_call(&message.content)
+                        };
+                        if held {
+                            item.message.content.clear();
+                            if !item.done && item.message.thinking.is_none() {
+                                continue;
+                            }
+                        } else {
+                            item.message.content = message.content[streamed..].to_string();
+                            streamed = message.content.len();
+                        }
+                    }
+                    if item.done {
+                        last = Some(item.clone());
+                    }
+                    yield Ok(item);
+                }
+
+                if self.requires_tool_call() && message.tool_calls.is_empty() {
+                    yield Err(ToolCallError::ToolCallRequired.into());
+                    return;
+                }
+                Self::assign_tool_call_ids(&mut message);
+                if let Some(last) = &last {
+                    self.record_turn(&message, last, start);
+                }
+                let tool_calls = message.tool_calls.clone();
+                self.history.push(message);
+                if tool_calls.is_empty() {
+                    return;
+                }
+
+                for call in tool_calls {
+                    if self.debug {
+                        eprintln!("Tool call: {:?}", call.function);
+                    }
+
+                    if let Err(e) = self.run_tool_call(call).await {
+                        yield Err(e);
+                        return;
+                    }
+                }
+            }
+        }
+    }
 }
@@ -0,0 +1,120 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{Arc, Mutex},
+    task::{Poll, Waker},
+};
+
+/// The body and headers of a successful response, shared between identical requests.
+#[derive(Debug, Clone)]
+pub(crate) struct SharedResponse {
+    pub(crate) bytes: Arc<[u8]>,
+    pub(crate) headers: reqwest::header::HeaderMap,
+    pub(crate) request_id: Option<String>,
+}
+
+/// The requests being sent, by key, so that identical concurrent requests wait for the
+/// response of the first one instead of being sent again.
+#[derive(Debug, Default)]
+pub(crate) struct InFlight {
+    calls: Mutex<HashMap<String, Arc<Mutex<Call>>>>,
+}
+
+#[derive(Debug, Default)]
+struct Call {
+    /// Set once the first request is over: `None` if it failed or was cancelled.
+    outcome: Option<Option<SharedResponse>>,
+    waiters: Vec<Waker>,
+}
+
+/// Completes the call when the first request is over, even if its future is dropped.
+struct Leader<'a> {
+    in_flight: &'a InFlight,
+    key: String,
+    call: Arc<Mutex<Call>>,
+    outcome: Option<SharedResponse>,
+}
+
+impl Drop for Leader<'_> {
+    fn drop(&mut self) {
+        self.in_flight.calls.lock().unwrap().remove(&self.key);
+        let mut call = self.call.lock().unwrap();
+        call.outcome = Some(self.outcome.take());
+        for waker in call.waiters.drain(..) {
+            waker.wake();
+        }
+    }
+}
+
+impl InFlight {
+    /// The key of a request to `path` with `body`, with the server, the credentials and the
+    /// headers of `config` in it: clones share the requests being sent, and a clone with
+    /// another API key mustn't get the response of a request sent with the key of another.
+    pub(crate) fn key(config: &crate::Config, path: &str, body: &str) -> String {
+        let mut key = format!("{} {path}\n", config.url);
+        if let Some(api_key) = &config.api_key {
+            key.push_str(&format!("bearer {api_key}\n"));
+        }
+        #[cfg(feature = "headers")]
+        for (name, value) in &config.request_headers {
+            let value = String::from_utf8_lossy(value.as_bytes());
+            key.push_str(&format!("{name}: {value}\n"));
+        }
+        key.push_str(body);
+        key
+    }
+
+    /// Sends the request with `send`, unless an identical request is being sent, in which
+    /// case its response is returned. If that request fails, the request is sent anyway,
+    /// so that every caller gets its own error.
+    pub(crate) async fn run<F, Fut>(
+        &self,
+        key: String,
+        send: F,
+    ) -> crate::error::Result<SharedResponse>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = crate::error::Result<SharedResponse>>,
+    {
+        let (call, is_leader) = {
+            let mut calls = self.calls.lock().unwrap();
+            match calls.get(&key) {
+                Some(call) => (call.clone(), false),
+                None => {
+                    let call = Arc::<Mutex<Call>>::default();
+                    calls.insert(key.clone(), call.clone());
+                    (call, true)
+                }
+            }
+        };
+
+        if is_leader {
+            let mut leader = Leader {
+                in_flight: self,
+                key,
+                call,
+                outcome: None,
+            };
+            let res = send().await;
+            leader.outcome = res.as_ref().ok().cloned();
+            return res;
+        }
+
+        let outcome = std::future::poll_fn(|cx| {
+            let mut call = call.lock().unwrap();
+            match &call.outcome {
+                Some(outcome) => Poll::Ready(outcome.clone()),
+                None => {
+                    call.waiters.push(cx.waker().clone());
+                    Poll::Pending
+                }
+            }
+        })
+        .await;
+
+        match outcome {
+            Some(response) => Ok(response),
+            None => send().await,
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use std::{net::IpAddr, time::Duration};
+
+use url::Url;
+
+use crate::{Ollama, OllamaBuilder};
+
+const DEFAULT_PORT: &str = "11434";
+
+impl Ollama {
+    /// Creates a new `Ollama` instance configured from environment variables:
+    ///
+    /// * `OLLAMA_HOST` - The address of the Ollama service, parsed like the official CLI does:
+    ///   `[scheme://]host[:port][/path]`. The scheme defaults to `http` and the port to `11434`,
+    ///   or to the default port of the scheme when one is given. Defaults to `127.0.0.1:11434`.
+    /// * `OLLAMA_API_KEY` - A token sent as `Authorization: Bearer <token>`.
+    /// * `OLLAMA_TIMEOUT` - The timeout of whole requests, streamed responses included.
+    /// * `OLLAMA_CONNECT_TIMEOUT` - The timeout of the connection phase.
+    ///
+    /// Timeouts are either a number of seconds or a number followed by `ms`, `s`, `m` or `h`.
+    /// Invalid values are ignored with a warning.
+    ///
+    /// Use [`OllamaBuilder::from_env`] to further configure the client.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `reqwest` client can't be built.
+    pub fn from_env() -> Self {
+        builder_from_env()
+            .build()
+            .expect("failed to build the reqwest client")
+    }
+}
+
+pub(crate) fn builder_from_env() -> OllamaBuilder {
+    let mut builder = OllamaBuilder::new().url(parse_host(env_var("OLLAMA_HOST").as_deref()));
+    if let Some(api_key) = env_var("OLLAMA_API_KEY") {
+        builder = builder.api_key(api_key);
+    }
+    if let Some(timeout) = env_duration("OLLAMA_TIMEOUT") {
+        builder = builder.timeout(timeout);
+    }
+    if let Some(timeout) = env_duration("OLLAMA_CONNECT_TIMEOUT") {
+        builder = builder.connect_timeout(timeout);
+    }
+    builder
+}
+
+/// Reads an environment variable, ignoring surrounding whitespace and quotes.
+fn env_var(key: &str) -> Option<String> {
+    let value = std::env::var(key).ok()?;
+    let value = value.trim().trim_matches(|c| c == '"' || c == '\'');
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+fn env_duration(key: &str) -> Option<Duration> {
+    let value = env_var(key)?;
+    let duration = parse_duration(&value);
+    if duration.is_none() {
+        log::warn!("invalid duration in {key}: {value:?}, ignoring it");
+    }
+    duration
+}
+
+pub(crate) fn parse_duration(value: &str) -> Option<Duration> {
+    let split = value
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number = number.parse::<f64>().ok()?;
+
+    let seconds = match unit.trim() {
+        "" | "s" => number,
+        "ms" => number / 1000.0,
+        "m" => number * 60.0,
+        "h" => number * 3600.0,
+        _ => return None,
+    };
+
+    Duration::try_from_secs_f64(seconds).ok()
+}
+
+/// Parses `OLLAMA_HOST` the same way as the `envconfig.Host` function of Ollama.
+pub(crate) fn parse_host(host: Option<&str>) -> Url {
+    let host = host.unwrap_or_default();
+
+    let (scheme, hostport, default_port) = match host.split_once("://") {
+        None => ("http", host, DEFAULT_PORT),
+        Some(("http", rest)) => ("http", rest, "80"),
+        Some(("https", rest)) => ("https", rest, "443"),
+        Some((scheme, rest)) => (scheme, rest, DEFAULT_PORT),
+    };
+    let (hostport, path) = hostport.split_once('/').unwrap_or((hostport, ""));
+
+    let (host, port) = match split_host_port(hostport) {
+        Some((host, port)) => (host.to_string(), port),
+        None => {
+            let host = match hostport.trim_matches(['[', ']']).parse::<IpAddr>() {
+                Ok(ip) => ip.to_string(),
+                Err(_) if hostport.is_empty() => "127.0.0.1".to_string(),
+                Err(_) => hostport.to_string(),
+            };
+            (host, default_port)
+        }
+    };
+
+    let port = if port.parse::<u16>().is_ok() {
+        port
+    } else {
+        log::warn!("invalid port in OLLAMA_HOST: {port:?}, using {default_port}");
+        default_port
+    };
+
+    // An empty host means every interface when serving, and the local machine when connecting
+    let host = match host.as_str() {
+        "" => "127.0.0.1".to_string(),
+        host if host.contains(':') => format!("[{host}]"),
+        host => host.to_string(),
+    };
+
+    Url::parse(&format!("{scheme}://{host}:{port}/{path}")).unwrap_or_else(|e| {
+        log::warn!("invalid OLLAMA_HOST: {e}, using the default address");
+        Url::parse(&format!("http://127.0.0.1:{DEFAULT_PORT}")).unwrap()
+    })
+}
+
+/// Splits `host:port` or `[host]:port`, like `net.SplitHostPort` in Go.
+fn split_host_port(hostport: &str) -> Option<(&str, &str)> {
+    if let Some(rest) = hostport.strip_prefix('[') {
+        let (host, rest) = rest.split_once(']')?;
+        Some((host, rest.strip_prefix(':')?))
+    } else {
+        let (host, port) = hostport.rsplit_once(':')?;
+        // IPv6 addresses must be bracketed when followed by a port
+        (!host.contains(':')).then_some((host, port))
+    }
+}
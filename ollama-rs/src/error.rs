@@ -1,7 +1,13 @@
-use serde::Deserialize;
+use reqwest::StatusCode;
+use serde::{de::DeserializeOwned, Deserialize};
 use static_assertions::assert_impl_all;
 use thiserror::Error;
 
+use crate::{
+    models::ByteSize,
+    version::{Feature, Version},
+};
+
 assert_impl_all!(OllamaError: Send, Sync);
 /// A result type for operations in the ollama-rs crate.
 ///
@@ -13,20 +19,498 @@ pub type Result<T> = std::result::Result<T, OllamaError>;
 ///
 /// This enum represents the various errors that can occur within the crate.
 /// Each variant corresponds to a different kind of error.
+///
+/// Errors caused by a response of the server, or by a failure to send the request, carry the
+/// ID of the request, see [`OllamaError::request_id`].
 #[derive(Error, Debug)]
 pub enum OllamaError {
     #[error("Error calling tool")]
     ToolCallError(#[from] ToolCallError),
+    #[error("Error rendering prompt template")]
+    TemplateError(#[from] TemplateError),
+    /// A request could not be serialized, or a value could not be converted from JSON.
     #[error("Ollama JSON error")]
     JsonError(#[from] serde_json::Error),
+    /// A response from Ollama could not be decoded. `payload` holds the offending response body.
+    #[error("Failed to decode Ollama response: {source}")]
+    DecodeError {
+        #[source]
+        source: serde_json::Error,
+        payload: String,
+        request_id: Option<String>,
+    },
+    /// The Ollama server could not be reached.
+    #[error("Failed to connect to Ollama")]
+    ConnectionError {
+        #[source]
+        source: reqwest::Error,
+        request_id: Option<String>,
+    },
+    /// The request to Ollama timed out.
+    #[error("Request to Ollama timed out")]
+    Timeout {
+        #[source]
+        source: reqwest::Error,
+        request_id: Option<String>,
+    },
     #[error("Reqwest error")]
-    ReqwestError(#[from] reqwest::Error),
+    ReqwestError {
+        #[source]
+        source: reqwest::Error,
+        request_id: Option<String>,
+    },
+    /// Ollama responded with a non-success HTTP status.
+    /// `message` is the `error` field of the response body when present, or the raw body otherwise.
+    #[error("Ollama responded with {status}: {message}")]
+    HttpError {
+        status: StatusCode,
+        message: String,
+        request_id: Option<String>,
+    },
+    /// The requested model is not available on the server, it may need to be pulled first.
+    #[error("Model not found: {model}")]
+    ModelNotFound {
+        model: String,
+        message: String,
+        request_id: Option<String>,
+    },
+    /// The model lacks a capability the request needs, such as tools or images. Another
+    /// model with the capability may be used instead.
+    #[error("Model {model} does not support {capability}")]
+    UnsupportedCapability {
+        model: String,
+        capability: Capability,
+        message: String,
+        request_id: Option<String>,
+    },
+    /// The server could not allocate enough memory to load or run the model, in system memory
+    /// or in the memory of a GPU. A smaller model, or a smaller context, may fit.
+    ///
+    /// `model` is the model of the request when known. `required` and `available` are set when
+    /// the server tells them, as in `model requires more system memory (10.2 GiB) than is
+    /// available (4.1 GiB)`.
+    #[error("Ollama ran out of memory: {message}")]
+    OutOfMemory {
+        model: Option<String>,
+        required: Option<ByteSize>,
+        available: Option<ByteSize>,
+        message: String,
+        request_id: Option<String>,
+    },
+    /// The connection broke while a response was being streamed, or, without an error
+    /// (`source` is `None`), was closed before the end of the response.
+    ///
+    /// For generation and chat streams, `partial_response` contains the text generated
+    /// before the failure and `last_chunk` the JSON of the last chunk received, so the
+    /// partial output can be salvaged.
+    #[error("The response stream was interrupted")]
+    StreamInterrupted {
+        #[source]
+        source: Option<reqwest::Error>,
+        partial_response: String,
+        last_chunk: Option<String>,
+        request_id: Option<String>,
+    },
+    /// An error returned by Ollama in the body of an otherwise successful response,
+    /// such as an error sent in the middle of a stream.
     #[error("Internal Ollama error: {}", .0.message)]
     InternalError(InternalOllamaError),
+    /// A structured response doesn't conform to the JSON schema it was requested with.
+    #[error("The response doesn't match the JSON schema: {}", .violations.iter().map(ToString::to_string).collect::<Vec<_>>().join("; "))]
+    SchemaValidationError { violations: Vec<SchemaViolation> },
+    /// The request wasn't sent, as the [`CircuitBreaker`](crate::CircuitBreaker) of the client
+    /// is open after too many failures. `retry_in` is the time left before the server is probed.
+    #[error("Circuit open after too many failures, retry in {retry_in:?}")]
+    CircuitOpen { retry_in: std::time::Duration },
+    /// A [content filter](crate::moderation) of the client blocked the prompt or the response.
+    #[error("Content blocked: {reason}")]
+    ContentBlocked { reason: String },
+    /// The response still failed the [guardrails](crate::guardrails) after every retry.
+    /// `response` is the last response of the model, and `violations` its failures.
+    #[error("The response failed its guardrails after {attempts} attempts: {}", .violations.join("; "))]
+    GuardrailViolation {
+        violations: Vec<String>,
+        response: String,
+        attempts: u32,
+    },
+    /// The server is too old for a feature the request uses, see [`version`](crate::version).
+    #[error("{feature} requires Ollama {required} or greater, but the server runs {server}")]
+    Unsupported {
+        feature: Feature,
+        required: Version,
+        server: Version,
+    },
+    /// A configuration, such as a profiles file, is invalid or couldn't be read.
+    #[error("Invalid configuration: {0}")]
+    ConfigError(String),
     #[error("{0}")]
     Other(String),
 }
 
+impl From<reqwest::Error> for OllamaError {
+    fn from(e: reqwest::Error) -> Self {
+        let request_id = None;
+        if e.is_timeout() {
+            Self::Timeout {
+                source: e,
+                request_id,
+            }
+        } else if e.is_connect() {
+            Self::ConnectionError {
+                source: e,
+                request_id,
+            }
+        } else {
+            Self::ReqwestError {
+                source: e,
+                request_id,
+            }
+        }
+    }
+}
+
+/// A broad classification of an [`OllamaError`], useful to decide how to react to it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorCategory {
+    /// The server couldn't be reached, the request timed out or the connection broke.
+    Network,
+    /// The server failed to process a valid request (5xx responses, `429 Too Many Requests`...).
+    Server,
+    /// The request was rejected by the server (4xx responses, unknown models...).
+    Client,
+    /// The server doesn't have enough memory to run the model.
+    Resource,
+    /// A request couldn't be serialized or a response couldn't be decoded.
+    Decode,
+    /// A tool failed when called by the model.
+    Tool,
+    Other,
+}
+
+impl OllamaError {
+    /// Returns the category of this error.
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            Self::ToolCallError(_) => ErrorCategory::Tool,
+            Self::TemplateError(_) => ErrorCategory::Other,
+            Self::JsonError(_)
+            | Self::DecodeError { .. }
+            | Self::SchemaValidationError { .. }
+            | Self::GuardrailViolation { .. } => ErrorCategory::Decode,
+            Self::ConnectionError { .. }
+            | Self::Timeout { .. }
+            | Self::StreamInterrupted { .. } => ErrorCategory::Network,
+            Self::ReqwestError { source: e, .. } => {
+                if e.is_decode() {
+                    ErrorCategory::Decode
+                } else if e.is_builder() {
+                    ErrorCategory::Other
+                } else if e.is_request() || e.is_body() {
+                    ErrorCategory::Network
+                } else {
+                    ErrorCategory::Other
+                }
+            }
+            Self::HttpError { status, .. } => {
+                if status.is_server_error() || *status == StatusCode::TOO_MANY_REQUESTS {
+                    ErrorCategory::Server
+                } else {
+                    ErrorCategory::Client
+                }
+            }
+            Self::ModelNotFound { .. }
+            | Self::UnsupportedCapability { .. }
+            | Self::ContentBlocked { .. }
+            | Self::Unsupported { .. } => ErrorCategory::Client,
+            Self::OutOfMemory { .. } => ErrorCategory::Resource,
+            Self::InternalError(_) | Self::CircuitOpen { .. } => ErrorCategory::Server,
+            Self::ConfigError(_) | Self::Other(_) => ErrorCategory::Other,
+        }
+    }
+
+    /// Returns whether sending the same request again may succeed.
+    ///
+    /// Network failures and transient server errors (`408`, `429`, and `5xx` other than
+    /// `501 Not Implemented` and `505 HTTP Version Not Supported`) are retryable.
+    /// Invalid requests, missing models, out of memory and decoding errors are not.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::HttpError { status, .. } => is_retryable_status(*status),
+            // Errors reported within a successful response are usually deterministic
+            Self::InternalError(_) => false,
+            _ => self.category() == ErrorCategory::Network,
+        }
+    }
+
+    /// Builds an error from a response with a non-success status, consuming its body.
+    pub(crate) async fn from_http_response(res: reqwest::Response) -> Self {
+        let status = res.status();
+        let request_id = crate::request_id::of(&res);
+        let body = match res.text().await {
+            Ok(body) => body,
+            Err(e) => return OllamaError::from(e).with_request_id(request_id.as_deref()),
+        };
+
+        let message = match serde_json::from_str::<InternalOllamaError>(&body) {
+            Ok(err) => err.message,
+            // OpenAI-compatible endpoints nest the message: `{"error": {"message": ...}}`
+            Err(_) => serde_json::from_str::<serde_json::Value>(&body)
+                .ok()
+                .and_then(|v| v["error"]["message"].as_str().map(str::to_string))
+                .unwrap_or(body),
+        };
+
+        Self::from_status(status, message).with_request_id(request_id.as_deref())
+    }
+
+    pub(crate) fn from_status(status: StatusCode, message: String) -> Self {
+        if status == StatusCode::NOT_FOUND {
+            if let Some(model) = parse_missing_model(&message) {
+                return Self::ModelNotFound {
+                    model,
+                    message,
+                    request_id: None,
+                };
+            }
+        }
+
+        if let Some((model, capability)) = parse_unsupported_capability(&message) {
+            return Self::UnsupportedCapability {
+                model,
+                capability,
+                message,
+                request_id: None,
+            };
+        }
+
+        if is_out_of_memory(&message) {
+            return Self::out_of_memory(message);
+        }
+
+        Self::HttpError {
+            status,
+            message,
+            request_id: None,
+        }
+    }
+
+    fn out_of_memory(message: String) -> Self {
+        let (required, available) = parse_memory_figures(&message);
+        Self::OutOfMemory {
+            model: None,
+            required,
+            available,
+            message,
+            request_id: None,
+        }
+    }
+
+    /// Sets the model of an [`OllamaError::OutOfMemory`] error, which the server doesn't tell,
+    /// to the model of the request that failed.
+    pub(crate) fn for_model(self, name: &str) -> Self {
+        match self {
+            Self::OutOfMemory {
+                model: None,
+                required,
+                available,
+                message,
+                request_id,
+            } => Self::OutOfMemory {
+                model: Some(name.to_string()),
+                required,
+                available,
+                message,
+                request_id,
+            },
+            e => e,
+        }
+    }
+
+    /// The ID of the request that failed, as sent in the
+    /// [`REQUEST_ID_HEADER`](crate::request_id::REQUEST_ID_HEADER) header, to find the request
+    /// in the logs of the server or of a gateway.
+    ///
+    /// It is set on the errors of a request sent by the client: connection failures,
+    /// timeouts, errors reading the body, error statuses, errors reported in the body,
+    /// responses that can't be decoded and broken streams.
+    pub fn request_id(&self) -> Option<&str> {
+        match self {
+            Self::DecodeError { request_id, .. }
+            | Self::ConnectionError { request_id, .. }
+            | Self::Timeout { request_id, .. }
+            | Self::ReqwestError { request_id, .. }
+            | Self::HttpError { request_id, .. }
+            | Self::ModelNotFound { request_id, .. }
+            | Self::UnsupportedCapability { request_id, .. }
+            | Self::OutOfMemory { request_id, .. }
+            | Self::StreamInterrupted { request_id, .. } => request_id.as_deref(),
+            Self::InternalError(err) => err.request_id.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Sets the ID of the request that failed, unless already set.
+    pub(crate) fn with_request_id(mut self, id: Option<&str>) -> Self {
+        let Some(id) = id else {
+            return self;
+        };
+        match &mut self {
+            Self::DecodeError { request_id, .. }
+            | Self::ConnectionError { request_id, .. }
+            | Self::Timeout { request_id, .. }
+            | Self::ReqwestError { request_id, .. }
+            | Self::HttpError { request_id, .. }
+            | Self::ModelNotFound { request_id, .. }
+            | Self::UnsupportedCapability { request_id, .. }
+            | Self::OutOfMemory { request_id, .. }
+            | Self::StreamInterrupted { request_id, .. }
+            | Self::InternalError(InternalOllamaError { request_id, .. }) => {
+                request_id.get_or_insert_with(|| id.to_string());
+            }
+            _ => {}
+        }
+        self
+    }
+}
+
+pub(crate) fn is_retryable_status(status: StatusCode) -> bool {
+    status == StatusCode::REQUEST_TIMEOUT
+        || status == StatusCode::TOO_MANY_REQUESTS
+        || (status.is_server_error()
+            && status != StatusCode::NOT_IMPLEMENTED
+            && status != StatusCode::HTTP_VERSION_NOT_SUPPORTED)
+}
+
+impl From<InternalOllamaError> for OllamaError {
+    fn from(err: InternalOllamaError) -> Self {
+        if is_out_of_memory(&err.message) {
+            Self::out_of_memory(err.message)
+        } else {
+            Self::InternalError(err)
+        }
+    }
+}
+
+/// Extracts the model name from errors such as `model "llama2" not found, try pulling it first`.
+fn parse_missing_model(message: &str) -> Option<String> {
+    let rest = message.strip_prefix("model ")?;
+    let quote = rest.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    let rest = &rest[1..];
+    let end = rest.find(quote)?;
+
+    rest[end + 1..]
+        .trim_start()
+        .starts_with("not found")
+        .then(|| rest[..end].to_string())
+}
+
+/// Extracts the model and the capability from errors such as `"llama2" does not support tools`.
+fn parse_unsupported_capability(message: &str) -> Option<(String, Capability)> {
+    let (model, capabilities) = message.split_once(" does not support ")?;
+    let model = model.trim().trim_matches(['"', '\'']);
+    // The first of the missing capabilities, e.g. of `tools insert`
+    let capability = capabilities.split_whitespace().next()?;
+    if model.is_empty() || model.contains(char::is_whitespace) {
+        return None;
+    }
+    Some((model.to_string(), capability.trim_end_matches('.').into()))
+}
+
+fn is_out_of_memory(message: &str) -> bool {
+    let message = message.to_lowercase();
+    [
+        "out of memory",
+        "requires more system memory",
+        "cudamalloc failed",
+        "insufficient memory",
+        "unable to allocate",
+        "failed to allocate",
+    ]
+    .iter()
+    .any(|pattern| message.contains(pattern))
+}
+
+/// Extracts the required and available memory from errors such as `model requires more system
+/// memory (10.2 GiB) than is available (4.1 GiB)`.
+fn parse_memory_figures(message: &str) -> (Option<ByteSize>, Option<ByteSize>) {
+    let mut figures = message
+        .split('(')
+        .skip(1)
+        .filter_map(|rest| parse_byte_size(&rest[..rest.find(')')?]));
+    match message.find("than is available") {
+        Some(_) => (figures.next(), figures.next()),
+        None => (None, None),
+    }
+}
+
+/// Parses sizes such as `4.1 GiB` or `512 MB`.
+fn parse_byte_size(text: &str) -> Option<ByteSize> {
+    let (value, unit) = text.trim().split_once(' ')?;
+    let value: f64 = value.parse().ok()?;
+    let scale = match unit.trim() {
+        "B" => 1.0,
+        "KB" => 1e3,
+        "MB" => 1e6,
+        "GB" => 1e9,
+        "TB" => 1e12,
+        "KiB" => 1024.0,
+        "MiB" => 1024.0 * 1024.0,
+        "GiB" => 1024.0 * 1024.0 * 1024.0,
+        "TiB" => 1024.0 * 1024.0 * 1024.0 * 1024.0,
+        _ => return None,
+    };
+    Some(ByteSize((value * scale) as u64))
+}
+
+/// Reads the body of `res`, with the ID of its request set on the error if that fails, e.g.
+/// on a read timeout during a long generation.
+///
+/// With an audit log, the record of the request is written once the body is read.
+pub(crate) async fn read_body(
+    #[allow(unused_mut)] mut res: reqwest::Response,
+) -> Result<impl std::ops::Deref<Target = [u8]>> {
+    let request_id = crate::request_id::of(&res);
+    // Recorded as cancelled unless the body is read
+    #[cfg(feature = "audit")]
+    let audit = crate::audit::AuditSlot::take(&mut res);
+
+    let bytes = res.bytes().await;
+
+    #[cfg(feature = "audit")]
+    if let Some(mut audit) = audit {
+        match &bytes {
+            Ok(bytes) => {
+                audit.outcome(crate::audit::AuditOutcome::Success);
+                audit.usage(bytes);
+                audit.finish().await;
+            }
+            Err(e) => audit.fail(e.to_string()).await,
+        }
+    }
+    bytes.map_err(|e| OllamaError::from(e).with_request_id(request_id.as_deref()))
+}
+
+/// Reads the body of `res` and [`decode`]s it, with the ID of its request set on the error.
+pub(crate) async fn decode_body<T: DeserializeOwned>(res: reqwest::Response) -> Result<T> {
+    let request_id = crate::request_id::of(&res);
+    let bytes = read_body(res).await?;
+    decode(&bytes).map_err(|e| e.with_request_id(request_id.as_deref()))
+}
+
+/// Deserializes a response body, keeping the body around if it can't be decoded.
+pub(crate) fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<T> {
+    serde_json::from_slice(bytes).map_err(|source| {
+        match serde_json::from_slice::<InternalOllamaError>(bytes) {
+            Ok(err) => err.into(),
+            Err(_) => OllamaError::DecodeError {
+                source,
+                payload: String::from_utf8_lossy(bytes).into_owned(),
+                request_id: None,
+            },
+        }
+    })
+}
+
 /// Represents an internal error within the Ollama service.
 ///
 /// This struct is used to deserialize error messages returned by the service.
@@ -34,6 +518,9 @@ pub enum OllamaError {
 pub struct InternalOllamaError {
     #[serde(rename = "error")]
     pub message: String,
+    /// The ID of the request the error was returned for.
+    #[serde(skip)]
+    pub request_id: Option<String>,
 }
 
 /// An error type for tool call operations.
@@ -50,4 +537,88 @@ pub enum ToolCallError {
     InvalidToolArguments(#[from] serde_json::Error),
     #[error("Tool errored internally when it was called")]
     InternalToolError(#[from] Box<dyn std::error::Error + Send + Sync>),
+    #[error("The tool choice required a tool call, but the model didn't call any tool")]
+    ToolCallRequired,
+}
+
+/// An error rendering a [`PromptTemplate`](crate::generation::prompt::PromptTemplate).
+#[derive(Error, Debug)]
+pub enum TemplateError {
+    #[error("No value for the template variable {0:?}")]
+    MissingVariable(String),
+    #[error("Unknown template partial {0:?}")]
+    UnknownPartial(String),
+    #[error("The template tag starting at byte {0} is never closed")]
+    UnclosedTag(usize),
+    #[error("Template partials are nested too deeply, a partial may include itself")]
+    PartialTooDeep,
+    #[error("Invalid template variables: {0}")]
+    InvalidVariables(String),
+}
+
+/// A capability of a model, required by some requests.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Capability {
+    /// Text generation.
+    Completion,
+    /// Tool calling.
+    Tools,
+    /// Filling in the middle, with a suffix.
+    Insert,
+    /// Images in prompts.
+    Vision,
+    /// Embeddings.
+    Embedding,
+    /// Thinking before answering.
+    Thinking,
+    /// A capability unknown to this version of the library.
+    Other(String),
+}
+
+impl From<&str> for Capability {
+    fn from(name: &str) -> Self {
+        match name {
+            "completion" => Self::Completion,
+            "tools" => Self::Tools,
+            "insert" => Self::Insert,
+            "vision" | "images" => Self::Vision,
+            "embedding" | "embeddings" => Self::Embedding,
+            "thinking" => Self::Thinking,
+            name => Self::Other(name.to_string()),
+        }
+    }
+}
+
+impl std::fmt::Display for Capability {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Completion => "completion",
+            Self::Tools => "tools",
+            Self::Insert => "insert",
+            Self::Vision => "vision",
+            Self::Embedding => "embedding",
+            Self::Thinking => "thinking",
+            Self::Other(name) => name,
+        })
+    }
+}
+
+/// A part of a JSON value that doesn't conform to a JSON schema.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaViolation {
+    /// A JSON pointer to the offending part of the value, e.g. `/items/0/name`.
+    pub instance_path: String,
+    /// A JSON pointer to the keyword of the schema that wasn't satisfied, e.g. `/properties/items/items/required`.
+    pub schema_path: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.instance_path.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{}: {}", self.instance_path, self.message)
+        }
+    }
 }
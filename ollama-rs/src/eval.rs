@@ -0,0 +1,331 @@
+//! Evaluation of prompts and models: comparison of two configurations over the same set of
+//! prompts, and scoring of outputs by a judge model.
+//!
+//! ```no_run
+//! # async fn run() {
+//! use ollama_rs::{eval::EvalConfig, Ollama};
+//!
+//! let ollama = Ollama::default();
+//! let comparison = ollama
+//!     .compare(
+//!         ["Why is the sky blue?", "What is a monad?"],
+//!         &EvalConfig::new("llama3.2").system("Answer in one sentence."),
+//!         &EvalConfig::new("llama3.2").system("Answer like a teacher."),
+//!     )
+//!     .await;
+//!
+//! for pair in &comparison.results {
+//!     println!("{:?} vs {:?}", pair.a.latency, pair.b.latency);
+//! }
+//! # }
+//! ```
+
+use std::{
+    ops::RangeInclusive,
+    time::{Duration, Instant},
+};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::{OllamaError, Result},
+    generation::{
+        completion::{request::GenerationRequest, GenerationResponse},
+        parameters::{FormatType, JsonStructure},
+    },
+    models::ModelOptions,
+    Ollama,
+};
+
+const DEFAULT_RUBRIC: &str =
+    "The response answers the prompt correctly and completely, without unnecessary content.";
+
+/// A model, along with the options and system prompt it is run with.
+#[derive(Debug, Clone)]
+pub struct EvalConfig {
+    pub model_name: String,
+    pub options: Option<ModelOptions>,
+    pub system: Option<String>,
+}
+
+impl EvalConfig {
+    pub fn new(model_name: impl Into<String>) -> Self {
+        Self {
+            model_name: model_name.into(),
+            options: None,
+            system: None,
+        }
+    }
+
+    pub fn options(mut self, options: ModelOptions) -> Self {
+        self.options = Some(options);
+        self
+    }
+
+    pub fn system(mut self, system: impl Into<String>) -> Self {
+        self.system = Some(system.into());
+        self
+    }
+
+    fn request<'a>(&'a self, prompt: &'a str) -> GenerationRequest<'a> {
+        let mut request = GenerationRequest::new(self.model_name.clone(), prompt);
+        if let Some(options) = &self.options {
+            request = request.options(options.clone());
+        }
+        if let Some(system) = &self.system {
+            request = request.system(system.as_str());
+        }
+        request
+    }
+}
+
+/// The outcome of a prompt with one configuration.
+#[derive(Debug)]
+pub struct EvalRun {
+    pub result: Result<GenerationResponse>,
+    /// The time the request took, as seen by the client.
+    pub latency: Duration,
+}
+
+impl EvalRun {
+    /// The text of the response, if the request succeeded.
+    pub fn output(&self) -> Option<&str> {
+        self.result.as_ref().ok().map(|res| res.response.as_str())
+    }
+}
+
+/// The outcomes of a prompt with both configurations.
+#[derive(Debug)]
+pub struct PairedResult {
+    pub prompt: String,
+    pub a: EvalRun,
+    pub b: EvalRun,
+}
+
+/// The results of [`Ollama::compare`], in the order of the prompts.
+#[derive(Debug)]
+pub struct Comparison {
+    pub results: Vec<PairedResult>,
+}
+
+/// Aggregates of the runs of one configuration.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct EvalSummary {
+    pub runs: usize,
+    pub errors: usize,
+    pub mean_latency: Duration,
+    /// The tokens of the successful responses.
+    pub eval_tokens: u64,
+    /// The mean speed of generation of the successful responses.
+    pub mean_tokens_per_second: Option<f64>,
+}
+
+impl Comparison {
+    /// Aggregates the runs of configuration A.
+    pub fn summary_a(&self) -> EvalSummary {
+        summarize(self.results.iter().map(|pair| &pair.a))
+    }
+
+    /// Aggregates the runs of configuration B.
+    pub fn summary_b(&self) -> EvalSummary {
+        summarize(self.results.iter().map(|pair| &pair.b))
+    }
+}
+
+fn summarize<'a>(runs: impl Iterator<Item = &'a EvalRun>) -> EvalSummary {
+    let mut summary = EvalSummary::default();
+    let mut latency = Duration::ZERO;
+    let mut speeds = Vec::new();
+
+    for run in runs {
+        summary.runs += 1;
+        latency += run.latency;
+        match &run.result {
+            Ok(res) => {
+                let usage = res.usage().unwrap_or_default();
+                summary.eval_tokens += usage.eval_count;
+                speeds.extend(usage.tokens_per_second());
+            }
+            Err(_) => summary.errors += 1,
+        }
+    }
+
+    if summary.runs > 0 {
+        summary.mean_latency = latency / summary.runs as u32;
+    }
+    if !speeds.is_empty() {
+        summary.mean_tokens_per_second = Some(speeds.iter().sum::<f64>() / speeds.len() as f64);
+    }
+    summary
+}
+
+impl Ollama {
+    /// Runs every prompt with configurations `a` and `b`, one request at a time so that
+    /// latencies are comparable.
+    ///
+    /// Which configuration runs first alternates from a prompt to the next, so that neither
+    /// always benefits from the model being loaded or the prompt cached.
+    pub async fn compare(
+        &self,
+        prompts: impl IntoIterator<Item = impl Into<String>>,
+        a: &EvalConfig,
+        b: &EvalConfig,
+    ) -> Comparison {
+        let mut results = Vec::new();
+        for (i, prompt) in prompts.into_iter().enumerate() {
+            let prompt = prompt.into();
+            let (a, b) = if i % 2 == 0 {
+                let a = self.eval_run(a, &prompt).await;
+                (a, self.eval_run(b, &prompt).await)
+            } else {
+                let b = self.eval_run(b, &prompt).await;
+                (self.eval_run(a, &prompt).await, b)
+            };
+            results.push(PairedResult { prompt, a, b });
+        }
+
+        Comparison { results }
+    }
+
+    async fn eval_run(&self, config: &EvalConfig, prompt: &str) -> EvalRun {
+        let start = Instant::now();
+        let result = self.generate(config.request(prompt)).await;
+        EvalRun {
+            result,
+            latency: start.elapsed(),
+        }
+    }
+}
+
+/// The score given by a [`Judge`] to an output.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Verdict {
+    /// Why the output deserves its score, written before the score
+    pub rationale: String,
+    /// The score of the output
+    pub score: u32,
+}
+
+/// The verdicts of a [`Judge`] on both outputs of a [`PairedResult`].
+#[derive(Debug)]
+pub struct PairedVerdicts {
+    pub a: Result<Verdict>,
+    pub b: Result<Verdict>,
+}
+
+/// Scores outputs against a rubric, and optionally a reference output, with a model
+/// constrained to answer with a [`Verdict`].
+///
+/// ```no_run
+/// # async fn run() -> ollama_rs::error::Result<()> {
+/// use ollama_rs::{eval::Judge, Ollama};
+///
+/// let judge = Judge::new(Ollama::default(), "qwen2.5:14b")
+///     .rubric("The answer is factually correct and fits in one sentence.")
+///     .scale(1..=5);
+///
+/// let verdict = judge
+///     .score("Why is the sky blue?", "Because of Rayleigh scattering.", None)
+///     .await?;
+/// assert!(verdict.score >= 4, "{}", verdict.rationale);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Judge {
+    ollama: Ollama,
+    model_name: String,
+    rubric: String,
+    scale: RangeInclusive<u32>,
+    options: ModelOptions,
+}
+
+impl Judge {
+    pub fn new(ollama: Ollama, model_name: impl Into<String>) -> Self {
+        Self {
+            ollama,
+            model_name: model_name.into(),
+            rubric: DEFAULT_RUBRIC.to_string(),
+            scale: 1..=10,
+            options: ModelOptions::default().temperature(0.0),
+        }
+    }
+
+    /// The criteria outputs are scored against.
+    /// (Default: "The response answers the prompt correctly and completely, without unnecessary content.")
+    pub fn rubric(mut self, rubric: impl Into<String>) -> Self {
+        self.rubric = rubric.into();
+        self
+    }
+
+    /// The range of the scores. (Default: `1..=10`)
+    pub fn scale(mut self, scale: RangeInclusive<u32>) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// The options of the judge model. (Default: a temperature of 0)
+    pub fn options(mut self, options: ModelOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Scores the `output` of `prompt`, compared to `reference` if given.
+    ///
+    /// Fails if the judge gives a score outside of the scale.
+    pub async fn score(
+        &self,
+        prompt: &str,
+        output: &str,
+        reference: Option<&str>,
+    ) -> Result<Verdict> {
+        let mut judge_prompt = format!(
+            "You are evaluating the response of an AI assistant to a prompt.\n\n\
+             Rubric: {}\n\n\
+             Give a score from {} (worst) to {} (best), after explaining your rationale.\n\n\
+             Prompt:\n{prompt}\n\n",
+            self.rubric,
+            self.scale.start(),
+            self.scale.end()
+        );
+        if let Some(reference) = reference {
+            judge_prompt.push_str(&format!("Reference response:\n{reference}\n\n"));
+        }
+        judge_prompt.push_str(&format!("Response to evaluate:\n{output}"));
+
+        let request = GenerationRequest::new(self.model_name.clone(), judge_prompt)
+            .options(self.options.clone())
+            .format(FormatType::StructuredJson(JsonStructure::new::<Verdict>()));
+        let res = self.ollama.generate(request).await?;
+
+        let verdict: Verdict = crate::error::decode(res.response.as_bytes())?;
+        if !self.scale.contains(&verdict.score) {
+            return Err(OllamaError::Other(format!(
+                "The judge gave a score of {}, outside of the scale {:?}",
+                verdict.score, self.scale
+            )));
+        }
+        Ok(verdict)
+    }
+
+    /// Scores both outputs of every pair of `comparison`, in order. The runs that failed
+    /// get an error.
+    pub async fn judge_comparison(&self, comparison: &Comparison) -> Vec<PairedVerdicts> {
+        let mut verdicts = Vec::with_capacity(comparison.results.len());
+        for pair in &comparison.results {
+            verdicts.push(PairedVerdicts {
+                a: self.score_run(&pair.prompt, &pair.a).await,
+                b: self.score_run(&pair.prompt, &pair.b).await,
+            });
+        }
+        verdicts
+    }
+
+    async fn score_run(&self, prompt: &str, run: &EvalRun) -> Result<Verdict> {
+        match run.output() {
+            Some(output) => self.score(prompt, output, None).await,
+            None => Err(OllamaError::Other("The run to score failed".to_string())),
+        }
+    }
+}
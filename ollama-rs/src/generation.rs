@@ -1,11 +1,36 @@
+#[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+#[cfg(feature = "stream")]
+pub mod benchmark;
 /// Modules related to generation tasks.
 ///
 /// This file aggregates various submodules that handle different aspects
 /// of generation tasks, including chat, completion, embeddings, images,
-/// options, parameters, and tools.
+/// options, parameters, prompt templates, tools, and usage statistics.
 pub mod chat;
+#[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+#[cfg(feature = "stream")]
+pub mod chunking;
+#[cfg_attr(docsrs, doc(cfg(feature = "tool-implementations")))]
+#[cfg(feature = "tool-implementations")]
+pub mod citations;
 pub mod completion;
+pub mod continuation;
+pub mod done_reason;
 pub mod embeddings;
 pub mod images;
 pub mod parameters;
+pub mod partial_json;
+pub mod pending;
+pub mod prompt;
+#[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+#[cfg(feature = "stream")]
+pub mod reconnect;
+#[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+#[cfg(feature = "stream")]
+pub mod stop;
+pub mod structured;
+#[cfg_attr(docsrs, doc(cfg(all(feature = "stream", feature = "tokio"))))]
+#[cfg(all(feature = "stream", feature = "tokio"))]
+pub mod tee;
 pub mod tools;
+pub mod usage;
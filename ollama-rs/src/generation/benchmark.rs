@@ -0,0 +1,184 @@
+//! Throughput benchmarks of a model, e.g. to compare quantizations or hardware.
+
+use std::time::{Duration, Instant};
+
+use futures_util::StreamExt;
+
+use crate::{
+    error::{OllamaError, Result},
+    generation::{completion::request::GenerationRequest, usage::Usage},
+    models::ModelOptions,
+    Ollama,
+};
+
+/// Options of [`Ollama::benchmark`].
+#[derive(Debug, Clone)]
+pub struct BenchmarkOptions {
+    warmup: usize,
+    runs: usize,
+    options: Option<ModelOptions>,
+}
+
+impl Default for BenchmarkOptions {
+    fn default() -> Self {
+        Self {
+            warmup: 1,
+            runs: 5,
+            options: None,
+        }
+    }
+}
+
+impl BenchmarkOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of untimed generations run first, loading the model. (Default: 1)
+    pub fn warmup(mut self, warmup: usize) -> Self {
+        self.warmup = warmup;
+        self
+    }
+
+    /// The number of timed generations. (Default: 5)
+    pub fn runs(mut self, runs: usize) -> Self {
+        self.runs = runs.max(1);
+        self
+    }
+
+    /// The options of the generations, e.g. a `num_predict` to bound their length.
+    pub fn options(mut self, options: ModelOptions) -> Self {
+        self.options = Some(options);
+        self
+    }
+}
+
+/// The mean, median and 95th percentile of a measure over the timed runs.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Statistics<T> {
+    pub mean: T,
+    pub p50: T,
+    pub p95: T,
+    pub min: T,
+    pub max: T,
+}
+
+impl Statistics<f64> {
+    fn from_samples(mut samples: Vec<f64>) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+        samples.sort_by(f64::total_cmp);
+
+        // Nearest-rank percentiles
+        let percentile = |p: f64| samples[((p * samples.len() as f64).ceil() as usize).max(1) - 1];
+        Self {
+            mean: samples.iter().sum::<f64>() / samples.len() as f64,
+            p50: percentile(0.5),
+            p95: percentile(0.95),
+            min: samples[0],
+            max: samples[samples.len() - 1],
+        }
+    }
+
+    fn to_durations(self) -> Statistics<Duration> {
+        Statistics {
+            mean: Duration::from_secs_f64(self.mean),
+            p50: Duration::from_secs_f64(self.p50),
+            p95: Duration::from_secs_f64(self.p95),
+            min: Duration::from_secs_f64(self.min),
+            max: Duration::from_secs_f64(self.max),
+        }
+    }
+}
+
+/// The results of [`Ollama::benchmark`].
+#[derive(Debug, Clone)]
+pub struct BenchmarkReport {
+    pub model: String,
+    /// The usage of each timed run.
+    pub runs: Vec<Usage>,
+    /// The time until the first token was received, as seen by the client.
+    pub time_to_first_token: Statistics<Duration>,
+    /// The speed of generation of the responses.
+    pub tokens_per_second: Statistics<f64>,
+    /// The speed of evaluation of the prompts, which are usually cached after the first run.
+    pub prompt_tokens_per_second: Statistics<f64>,
+    /// The time spent loading the model, close to zero once it is loaded.
+    pub load_duration: Statistics<Duration>,
+    /// The time spent loading the model by the first warmup run, if it wasn't loaded yet.
+    pub cold_load_duration: Option<Duration>,
+}
+
+impl Ollama {
+    /// Benchmarks `model` by running the warmup generations, then the timed ones, one at a
+    /// time.
+    pub async fn benchmark(
+        &self,
+        model: impl Into<String>,
+        prompt: &str,
+        options: BenchmarkOptions,
+    ) -> Result<BenchmarkReport> {
+        let model = model.into();
+
+        let mut cold_load_duration = None;
+        for i in 0..options.warmup {
+            let (_, usage) = self.timed_generation(&model, prompt, &options).await?;
+            if i == 0 {
+                cold_load_duration = Some(usage.load_duration);
+            }
+        }
+
+        let mut runs = Vec::with_capacity(options.runs);
+        let mut ttfts = Vec::with_capacity(options.runs);
+        for _ in 0..options.runs {
+            let (ttft, usage) = self.timed_generation(&model, prompt, &options).await?;
+            ttfts.push(ttft.as_secs_f64());
+            runs.push(usage);
+        }
+
+        let stats = |measure: fn(&Usage) -> Option<f64>| {
+            Statistics::from_samples(runs.iter().filter_map(measure).collect())
+        };
+        Ok(BenchmarkReport {
+            model,
+            time_to_first_token: Statistics::from_samples(ttfts).to_durations(),
+            tokens_per_second: stats(Usage::tokens_per_second),
+            prompt_tokens_per_second: stats(Usage::prompt_tokens_per_second),
+            load_duration: stats(|usage| Some(usage.load_duration.as_secs_f64())).to_durations(),
+            cold_load_duration,
+            runs,
+        })
+    }
+
+    /// Runs a streamed generation, returning its time to first token and its usage.
+    async fn timed_generation(
+        &self,
+        model: &str,
+        prompt: &str,
+        options: &BenchmarkOptions,
+    ) -> Result<(Duration, Usage)> {
+        let mut request = GenerationRequest::new(model.to_string(), prompt);
+        if let Some(model_options) = &options.options {
+            request = request.options(model_options.clone());
+        }
+
+        let start = Instant::now();
+        let mut stream = self.generate_stream(request).await?;
+        let mut ttft = None;
+        let mut usage = None;
+        while let Some(chunk) = stream.next().await {
+            for response in chunk? {
+                if ttft.is_none() && !response.response.is_empty() {
+                    ttft = Some(start.elapsed());
+                }
+                usage = usage.or(response.usage());
+            }
+        }
+
+        let usage = usage.ok_or_else(|| {
+            OllamaError::Other("The generation ended without reporting its timings".to_string())
+        })?;
+        Ok((ttft.unwrap_or_else(|| start.elapsed()), usage))
+    }
+}
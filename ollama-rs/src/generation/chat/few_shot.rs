@@ -0,0 +1,98 @@
+use super::{request::ChatMessageRequest, ChatMessage};
+
+/// Builds the message prefix of a few-shot chat request: an optional system instruction
+/// followed by example exchanges, as alternating user and assistant messages.
+///
+/// ```
+/// use ollama_rs::generation::chat::{few_shot::FewShot, ChatMessage};
+///
+/// let request = FewShot::new()
+///     .system("Classify the sentiment of the review as positive or negative.")
+///     .example("I loved it", "positive")
+///     .example("Waste of money", "negative")
+///     .max_example_tokens(512)
+///     .request(
+///         "llama3.2".to_string(),
+///         vec![ChatMessage::user("Works as advertised".to_string())],
+///     );
+///
+/// assert_eq!(request.messages.len(), 6);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct FewShot {
+    system: Option<String>,
+    examples: Vec<(String, String)>,
+    max_example_tokens: Option<usize>,
+}
+
+impl FewShot {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The system instruction placed before the examples.
+    pub fn system(mut self, instruction: impl Into<String>) -> Self {
+        self.system = Some(instruction.into());
+        self
+    }
+
+    /// Adds an example, sent as a user message with `input` answered by an assistant message with `output`.
+    pub fn example(mut self, input: impl Into<String>, output: impl Into<String>) -> Self {
+        self.examples.push((input.into(), output.into()));
+        self
+    }
+
+    /// Adds several examples, see [`FewShot::example`].
+    pub fn examples<I, O>(mut self, examples: impl IntoIterator<Item = (I, O)>) -> Self
+    where
+        I: Into<String>,
+        O: Into<String>,
+    {
+        self.examples.extend(
+            examples
+                .into_iter()
+                .map(|(input, output)| (input.into(), output.into())),
+        );
+        self
+    }
+
+    /// Limits the estimated number of tokens spent on examples. Examples are kept in order
+    /// until the next one would exceed the limit, see [`estimate_tokens`].
+    pub fn max_example_tokens(mut self, max_tokens: usize) -> Self {
+        self.max_example_tokens = Some(max_tokens);
+        self
+    }
+
+    /// Returns the system message and the examples that fit within the token limit.
+    pub fn messages(&self) -> Vec<ChatMessage> {
+        let mut messages = Vec::with_capacity(1 + self.examples.len() * 2);
+        if let Some(system) = &self.system {
+            messages.push(ChatMessage::system(system.clone()));
+        }
+
+        let mut tokens = 0;
+        for (input, output) in &self.examples {
+            tokens += estimate_tokens(input) + estimate_tokens(output);
+            if self.max_example_tokens.is_some_and(|max| tokens > max) {
+                break;
+            }
+
+            messages.push(ChatMessage::user(input.clone()));
+            messages.push(ChatMessage::assistant(output.clone()));
+        }
+
+        messages
+    }
+
+    /// Creates a chat request with the few-shot prefix followed by `messages`.
+    pub fn request(&self, model_name: String, messages: Vec<ChatMessage>) -> ChatMessageRequest {
+        let mut prefixed = self.messages();
+        prefixed.extend(messages);
+        ChatMessageRequest::new(model_name, prefixed)
+    }
+}
+
+/// A rough estimate of the number of tokens in `text`, assuming about four characters per token.
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
@@ -1,26 +1,23 @@
 use serde::{Deserialize, Serialize};
 
-use super::{images::Image, tools::ToolCall};
-use crate::{error::OllamaError, history::ChatHistory, Ollama};
+use super::{done_reason::DoneReason, images::Image, tools::ToolCall, usage::Usage};
+use crate::{history::ChatHistory, Ollama, RawResponse, Timestamp};
 use request::ChatMessageRequest;
 
-#[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
-#[cfg(feature = "stream")]
-use async_stream::stream;
 #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
 #[cfg(feature = "stream")]
 use std::sync::{Arc, Mutex};
-#[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
-#[cfg(feature = "stream")]
-use tokio_stream::StreamExt;
 
+pub mod few_shot;
 pub mod request;
+pub mod session;
 
 #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
 #[cfg(feature = "stream")]
 /// A stream of `ChatMessageResponse` objects
-pub type ChatMessageResponseStream =
-    std::pin::Pin<Box<dyn tokio_stream::Stream<Item = Result<ChatMessageResponse, ()>> + Send>>;
+pub type ChatMessageResponseStream = std::pin::Pin<
+    Box<dyn futures_util::Stream<Item = crate::error::Result<ChatMessageResponse>> + Send>,
+>;
 
 impl Ollama {
     #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
@@ -31,86 +28,51 @@ impl Ollama {
         &self,
         request: ChatMessageRequest,
     ) -> crate::error::Result<ChatMessageResponseStream> {
+        use futures_util::StreamExt;
+
         let mut request = request;
         request.stream = true;
+        request.options = self.with_default_options(request.options);
+        request.keep_alive = request
+            .keep_alive
+            .or_else(|| self.config.default_keep_alive.clone());
+
+        let prompt_annotations = self.filter_last_message(&mut request)?;
+        self.log_last_message(&request);
+
+        #[cfg(feature = "openai-compat")]
+        if self.config.openai_compatible {
+            let stream = self.openai_chat_stream(request).await?;
+            return Ok(self
+                .config
+                .content_filters
+                .filter_stream(stream, prompt_annotations));
+        }
 
-        let url = format!("{}api/chat", self.url_str());
-        let serialized = serde_json::to_string(&request)
-            .map_err(|e| e.to_string())
-            .unwrap();
-        let builder = self.reqwest_client.post(url);
-
-        #[cfg(feature = "headers")]
-        let builder = builder.headers(self.request_headers.clone());
+        self.check_features(crate::version::chat_features(&request))
+            .await?;
+        let serialized = serde_json::to_string(&request)?;
+        let builder = self.stream_request(reqwest::Method::POST, "api/chat");
 
-        let res = builder.body(serialized).send().await?;
+        let res = self.send(builder.body(serialized)).await?;
 
         if !res.status().is_success() {
-            return Err(OllamaError::Other(
-                res.text().await.unwrap_or_else(|e| e.to_string()),
-            ));
+            return Err(crate::error::OllamaError::from_http_response(res)
+                .await
+                .for_model(&request.model_name));
         }
 
-        let s = stream! {
-            let mut buffer = String::new();
-
-            let mut stream = res.bytes_stream();
-            while let Some(chunk_result) = stream.next().await {
-                match chunk_result {
-                    Ok(chunk) => {
-                        // Convert bytes to string and append to buffer
-                        if let Ok(chunk_str) = String::from_utf8(chunk.to_vec()) {
-                            buffer.push_str(&chunk_str);
-
-                            // Process complete lines in the buffer
-                            let mut lines_to_process = Vec::new();
-                            let mut start_pos = 0;
-
-                            // Find all complete lines in the buffer and collect them
-                            while let Some(pos) = buffer[start_pos..].find('\n') {
-                                let actual_pos = start_pos + pos;
-                                let line = buffer[start_pos..actual_pos].trim().to_string();
-                                if !line.is_empty() {
-                                    lines_to_process.push(line);
-                                }
-                                start_pos = actual_pos + 1;
-                            }
-
-                            // If we processed any lines, truncate the buffer
-                            if start_pos > 0 {
-                                buffer = buffer[start_pos..].to_string();
-                            }
-
-                            // Process all collected lines
-                            for line in lines_to_process {
-                                // Parse the JSON line
-                                match serde_json::from_str::<ChatMessageResponse>(&line) {
-                                    Ok(response) => yield Ok(response),
-                                    Err(e) => {
-                                        eprintln!("Failed to deserialize response: {}", e);
-                                        // Continue processing other lines even if one fails
-                                    }
-                                }
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        eprintln!("Failed to read response: {}", e);
-                        yield Err(());
-                        break;
-                    }
-                }
-            }
-
-            // Process any remaining data in the buffer
-            if !buffer.is_empty() {
-                if let Ok(response) = serde_json::from_str::<ChatMessageResponse>(&buffer) {
-                    yield Ok(response);
-                }
-            }
-        };
-
-        Ok(Box::pin(s))
+        let model = request.model_name;
+        let s = crate::ndjson::decode_stream::<ChatMessageResponse>(
+            res,
+            self.config.capture_raw_responses,
+        )
+        .map(move |res| res.map_err(|e| e.for_model(&model)));
+
+        Ok(self
+            .config
+            .content_filters
+            .filter_stream(Box::pin(s), prompt_annotations))
     }
 
     /// Chat message generation.
@@ -121,25 +83,43 @@ impl Ollama {
     ) -> crate::error::Result<ChatMessageResponse> {
         let mut request = request;
         request.stream = false;
+        request.options = self.with_default_options(request.options);
+        request.keep_alive = request
+            .keep_alive
+            .or_else(|| self.config.default_keep_alive.clone());
+
+        let mut prompt_annotations = self.filter_last_message(&mut request)?;
+        self.log_last_message(&request);
+
+        #[cfg(feature = "openai-compat")]
+        if self.config.openai_compatible {
+            let mut res = self.openai_chat(request).await?;
+            self.config
+                .content_filters
+                .filter(&mut res, &mut prompt_annotations)?;
+            return Ok(res);
+        }
 
-        let url = format!("{}api/chat", self.url_str());
+        self.check_features(crate::version::chat_features(&request))
+            .await?;
         let serialized = serde_json::to_string(&request)?;
-        let builder = self.reqwest_client.post(url);
-
-        #[cfg(feature = "headers")]
-        let builder = builder.headers(self.request_headers.clone());
-
-        let res = builder.body(serialized).send().await?;
-
-        if !res.status().is_success() {
-            return Err(OllamaError::Other(
-                res.text().await.unwrap_or_else(|e| e.to_string()),
-            ));
+        let shared = self
+            .post_json("api/chat", serialized)
+            .await
+            .map_err(|e| e.for_model(&request.model_name))?;
+
+        let mut res = crate::error::decode::<ChatMessageResponse>(&shared.bytes).map_err(|e| {
+            e.for_model(&request.model_name)
+                .with_request_id(shared.request_id.as_deref())
+        })?;
+        self.config
+            .content_filters
+            .filter(&mut res, &mut prompt_annotations)?;
+        self.log_content(&res.model, "response", &res.message.content);
+        if self.config.capture_raw_responses {
+            res.raw = Some(RawResponse::new(&shared.bytes, shared.headers));
         }
 
-        let bytes = res.bytes().await?;
-        let res = serde_json::from_slice::<ChatMessageResponse>(&bytes)?;
-
         Ok(res)
     }
 }
@@ -153,9 +133,10 @@ impl Ollama {
         mut request: ChatMessageRequest,
     ) -> crate::error::Result<ChatMessageResponseStream> {
         use async_stream::stream;
-        use tokio_stream::StreamExt;
+        use futures_util::StreamExt;
 
         // The request is modified to include the current chat messages
+        let current = request.messages.len();
         {
             let mut hist = history.lock().unwrap();
             for m in request.messages {
@@ -163,7 +144,7 @@ impl Ollama {
             }
         }
 
-        request.messages = history.lock().unwrap().messages().to_vec();
+        request.messages = self.history_window(&history.lock().unwrap().messages(), current);
         request.stream = true;
 
         let mut resp_stream: ChatMessageResponseStream =
@@ -171,14 +152,27 @@ impl Ollama {
 
         let s = stream! {
             let mut result = String::new();
+            let mut thinking: Option<String> = None;
 
-            while let Some(item) = resp_stream.try_next().await.unwrap() {
+            while let Some(item) = resp_stream.next().await {
+                let item = match item {
+                    Ok(item) => item,
+                    Err(e) => {
+                        yield Err(e);
+                        break;
+                    }
+                };
                 let msg_part = item.clone().message.content;
 
                 if item.done {
-                    history.lock().unwrap().push(ChatMessage::assistant(result.clone()));
+                    let mut message = ChatMessage::assistant(result.clone());
+                    message.thinking = thinking.clone();
+                    history.lock().unwrap().push(message);
                 } else {
                     result.push_str(&msg_part);
+                    if let Some(part) = &item.message.thinking {
+                        thinking.get_or_insert_with(String::new).push_str(part);
+                    }
                 }
 
                 yield Ok(item);
@@ -191,16 +185,17 @@ impl Ollama {
     /// Chat message generation
     /// Returns a `ChatMessageResponse` object
     pub async fn send_chat_messages_with_history<C: ChatHistory>(
-        &mut self,
+        &self,
         history: &mut C,
         mut request: ChatMessageRequest,
     ) -> crate::error::Result<ChatMessageResponse> {
         // The request is modified to include the current chat messages
+        let current = request.messages.len();
         for m in request.messages {
             history.push(m);
         }
 
-        request.messages = history.messages().to_vec();
+        request.messages = self.history_window(&history.messages(), current);
 
         let result = self.send_chat_messages(request.clone()).await;
 
@@ -215,23 +210,59 @@ impl Ollama {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct ChatMessageResponse {
     /// The name of the model used for the completion.
     pub model: String,
-    /// The creation time of the completion, in such format: `2023-08-04T08:52:19.385406455-07:00`.
-    pub created_at: String,
+    /// The creation time of the completion, such as `2023-08-04T08:52:19.385406455-07:00`.
+    #[cfg_attr(feature = "utoipa", schema(value_type = String, format = DateTime))]
+    pub created_at: Timestamp,
     /// The generated chat message.
     pub message: ChatMessage,
     pub done: bool,
+    /// Why the completion ended, only present in the last response.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub done_reason: Option<DoneReason>,
     #[serde(flatten)]
     /// The final data of the completion. This is only present if the completion is done.
     pub final_data: Option<ChatMessageFinalResponseData>,
+    /// The raw HTTP response, only present if the client captures raw responses.
+    #[serde(skip)]
+    pub raw: Option<RawResponse>,
+    /// The notes of the [content filters](crate::moderation) of the client about the prompt
+    /// and the response.
+    #[serde(skip)]
+    pub annotations: Vec<String>,
+}
+
+impl ChatMessageResponse {
+    /// The token counts and timings of the completion, only present in the last response.
+    pub fn usage(&self) -> Option<Usage> {
+        self.final_data
+            .as_ref()
+            .map(ChatMessageFinalResponseData::usage)
+    }
+}
+
+#[cfg(feature = "stream")]
+impl crate::ndjson::StreamChunk for ChatMessageResponse {
+    fn text(&self) -> &str {
+        &self.message.content
+    }
+
+    fn set_raw(&mut self, raw: RawResponse) {
+        self.raw = Some(raw);
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct ChatMessageFinalResponseData {
     /// Time spent generating the response
     pub total_duration: u64,
+    /// Time spent in nanoseconds loading the model
+    #[serde(default)]
+    pub load_duration: u64,
     /// Number of tokens in the prompt
     pub prompt_eval_count: u64,
     /// Time spent in nanoseconds evaluating the prompt
@@ -242,14 +273,36 @@ pub struct ChatMessageFinalResponseData {
     pub eval_duration: u64,
 }
 
+impl ChatMessageFinalResponseData {
+    /// The token counts and timings as a [`Usage`].
+    pub fn usage(&self) -> Usage {
+        Usage::from_nanos(
+            self.total_duration,
+            self.load_duration,
+            self.prompt_eval_count,
+            self.prompt_eval_duration,
+            self.eval_count,
+            self.eval_duration,
+        )
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct ChatMessage {
     pub role: MessageRole,
     pub content: String,
+    /// The reasoning of a thinking model before its answer, when requested with
+    /// [`ChatMessageRequest::think`](request::ChatMessageRequest::think).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thinking: Option<String>,
     #[serde(default)]
     pub tool_calls: Vec<ToolCall>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub images: Option<Vec<Image>>,
+    /// For a tool message, the [`ToolCall::id`] of the call it's the result of.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
 }
 
 impl ChatMessage {
@@ -257,8 +310,10 @@ impl ChatMessage {
         Self {
             role,
             content,
+            thinking: None,
             tool_calls: vec![],
             images: None,
+            tool_call_id: None,
         }
     }
 
@@ -278,22 +333,31 @@ impl ChatMessage {
         Self::new(MessageRole::Tool, content)
     }
 
-    pub fn with_images(mut self, images: Vec<Image>) -> Self {
-        self.images = Some(images);
+    /// Marks a tool message as the result of the call with the id `tool_call_id`.
+    pub fn with_tool_call_id(mut self, tool_call_id: impl Into<String>) -> Self {
+        self.tool_call_id = Some(tool_call_id.into());
         self
     }
 
-    pub fn add_image(mut self, image: Image) -> Self {
-        if let Some(images) = self.images.as_mut() {
-            images.push(image);
-        } else {
-            self.images = Some(vec![image]);
-        }
+    /// Attaches images to the message, replacing the images added before.
+    pub fn with_images(mut self, images: impl IntoIterator<Item = Image>) -> Self {
+        self.images = Some(images.into_iter().collect());
+        self
+    }
+
+    pub fn add_image(self, image: Image) -> Self {
+        self.add_images([image])
+    }
+
+    /// Attaches several images to the message, after the images added before.
+    pub fn add_images(mut self, images: impl IntoIterator<Item = Image>) -> Self {
+        self.images.get_or_insert_with(Vec::new).extend(images);
         self
     }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub enum MessageRole {
     #[serde(rename = "user")]
     User,
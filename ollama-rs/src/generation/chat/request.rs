@@ -1,9 +1,10 @@
+use schemars::JsonSchema;
 use serde::Serialize;
 
 use crate::{
     generation::{
-        parameters::{FormatType, KeepAlive},
-        tools::ToolInfo,
+        parameters::{FormatType, JsonStructure, KeepAlive, SchemaDialect, Think},
+        tools::{ToolChoice, ToolInfo},
     },
     models::ModelOptions,
 };
@@ -12,6 +13,7 @@ use super::ChatMessage;
 
 /// A chat message request to Ollama.
 #[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct ChatMessageRequest {
     #[serde(rename = "model")]
     pub model_name: String,
@@ -19,6 +21,8 @@ pub struct ChatMessageRequest {
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub tools: Vec<ToolInfo>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_choice: Option<ToolChoice>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub options: Option<ModelOptions>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub template: Option<String>,
@@ -26,7 +30,10 @@ pub struct ChatMessageRequest {
     pub format: Option<FormatType>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub keep_alive: Option<KeepAlive>,
-    /// Must be false if tools are provided
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub think: Option<Think>,
+    #[serde(skip)]
+    pub(crate) schema_dialect: Option<SchemaDialect>,
     pub(crate) stream: bool,
 }
 
@@ -39,9 +46,12 @@ impl ChatMessageRequest {
             template: None,
             format: None,
             keep_alive: None,
+            think: None,
+            schema_dialect: None,
             // Stream value will be overwritten by Ollama::send_chat_messages_stream() and Ollama::send_chat_messages() methods
             stream: false,
             tools: vec![],
+            tool_choice: None,
         }
     }
 
@@ -59,19 +69,63 @@ impl ChatMessageRequest {
 
     /// The format to return a response in.
     pub fn format(mut self, format: FormatType) -> Self {
-        self.format = Some(format);
+        self.format = Some(match (format, self.schema_dialect) {
+            (FormatType::StructuredJson(structure), Some(dialect)) => {
+                FormatType::StructuredJson(structure.to_dialect(dialect))
+            }
+            (format, _) => format,
+        });
         self
     }
 
+    /// Converts the JSON schemas of the format and of the tools of this request to the
+    /// conventions of `dialect`, whether they are set before or after. Schemas are otherwise sent as
+    /// they are, the generated ones following draft-07.
+    pub fn schema_dialect(mut self, dialect: SchemaDialect) -> Self {
+        self.schema_dialect = Some(dialect);
+        self.tools = std::mem::take(&mut self.tools)
+            .into_iter()
+            .map(|tool| tool.to_dialect(dialect))
+            .collect();
+        match self.format.take() {
+            Some(format) => self.format(format),
+            None => self,
+        }
+    }
+
+    /// Constrains the response to the JSON schema of `T`, a shorthand for
+    /// `.format(FormatType::StructuredJson(JsonStructure::new::<T>()))`. Requires Ollama 0.5.0
+    /// or greater.
+    pub fn structured<T: JsonSchema>(self) -> Self {
+        self.format(FormatType::StructuredJson(JsonStructure::new::<T>()))
+    }
+
     /// Used to control how long a model stays loaded in memory, by default models are unloaded after 5 minutes of inactivity
     pub fn keep_alive(mut self, keep_alive: KeepAlive) -> Self {
         self.keep_alive = Some(keep_alive);
         self
     }
 
+    /// Whether and how much a thinking model reasons before answering, see [`Think`].
+    pub fn think(mut self, think: impl Into<Think>) -> Self {
+        self.think = Some(think.into());
+        self
+    }
+
     /// Tools that are available to the LLM.
     pub fn tools(mut self, tools: Vec<ToolInfo>) -> Self {
-        self.tools = tools;
+        self.tools = match self.schema_dialect {
+            Some(dialect) => tools.into_iter().map(|t| t.to_dialect(dialect)).collect(),
+            None => tools,
+        };
         self
     }
+
+    /// Which of the tools the LLM may call.
+    pub fn tool_choice(mut self, tool_choice: ToolChoice) -> Self {
+        self.tool_choice = Some(tool_choice);
+        self
+    }
+
+    crate::models::model_option_setters!();
 }
@@ -0,0 +1,156 @@
+//! Conversations holding their own history, so that a client shared by many users serves
+//! independent chats.
+//!
+//! ```no_run
+//! # async fn run() -> ollama_rs::error::Result<()> {
+//! use ollama_rs::{generation::chat::ChatMessage, Ollama};
+//!
+//! let ollama = Ollama::default();
+//! let mut alice = ollama.chat_session("llama3.2").system("You are a travel agent.");
+//! let mut bob = ollama.chat_session("llama3.2");
+//!
+//! alice.send(ChatMessage::user("Where should I go in May?".into())).await?;
+//! bob.send(ChatMessage::user("What's a monad?".into())).await?;
+//! println!("{} tokens", alice.usage().total_tokens());
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{
+    error::Result,
+    generation::{
+        chat::{request::ChatMessageRequest, ChatMessage, ChatMessageResponse, MessageRole},
+        usage::Usage,
+    },
+    models::ModelOptions,
+    Ollama,
+};
+
+/// A conversation with a model: its options, its history and the tokens it used.
+///
+/// Sessions are cheap to create from a client, and each keeps its own history. A message is
+/// only added to the history once it is answered, so a failed request can be sent again.
+#[derive(Debug, Clone)]
+pub struct ChatSession {
+    ollama: Ollama,
+    model: String,
+    options: Option<ModelOptions>,
+    history: Vec<ChatMessage>,
+    usage: Usage,
+}
+
+impl ChatSession {
+    pub fn new(ollama: Ollama, model: impl Into<String>) -> Self {
+        Self {
+            ollama,
+            model: model.into(),
+            options: None,
+            history: Vec::new(),
+            usage: Usage::default(),
+        }
+    }
+
+    pub fn options(mut self, options: ModelOptions) -> Self {
+        self.options = Some(options);
+        self
+    }
+
+    /// Starts the conversation with a system prompt, kept by [`ChatSession::clear`].
+    pub fn system(mut self, prompt: impl Into<String>) -> Self {
+        self.history.retain(|m| m.role != MessageRole::System);
+        self.history.insert(0, ChatMessage::system(prompt.into()));
+        self
+    }
+
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    pub fn history(&self) -> &[ChatMessage] {
+        &self.history
+    }
+
+    /// The token counts and timings summed over every response of the session.
+    pub fn usage(&self) -> Usage {
+        self.usage
+    }
+
+    /// Forgets the conversation, but not the system prompt.
+    pub fn clear(&mut self) {
+        self.history.retain(|m| m.role == MessageRole::System);
+    }
+
+    fn request(&self, message: &ChatMessage) -> ChatMessageRequest {
+        let mut messages = self.history.clone();
+        messages.push(message.clone());
+        let mut request = ChatMessageRequest::new(self.model.clone(), messages);
+        request.options = self.options.clone();
+        request
+    }
+
+    fn record(&mut self, message: ChatMessage, res: &ChatMessageResponse) {
+        self.history.push(message);
+        self.history.push(res.message.clone());
+        if let Some(usage) = res.usage() {
+            self.usage += usage;
+        }
+    }
+
+    /// Sends a message, adding it and the response to the history.
+    pub async fn send(&mut self, message: ChatMessage) -> Result<ChatMessageResponse> {
+        let res = self
+            .ollama
+            .send_chat_messages(self.request(&message))
+            .await?;
+        self.record(message, &res);
+        Ok(res)
+    }
+
+    /// Like [`ChatSession::send`], but streams the response. The message and the response
+    /// are added to the history once the stream is done.
+    #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+    #[cfg(feature = "stream")]
+    pub fn send_stream(
+        &mut self,
+        message: ChatMessage,
+    ) -> impl futures_util::Stream<Item = Result<ChatMessageResponse>> + '_ {
+        use futures_util::StreamExt;
+
+        async_stream::stream! {
+            let mut stream = match self.ollama.send_chat_messages_stream(self.request(&message)).await {
+                Ok(stream) => stream,
+                Err(e) => {
+                    yield Err(e);
+                    return;
+                }
+            };
+
+            let mut response = ChatMessage::assistant(String::new());
+            while let Some(item) = stream.next().await {
+                let item = match item {
+                    Ok(item) => item,
+                    Err(e) => {
+                        yield Err(e);
+                        return;
+                    }
+                };
+
+                response.content.push_str(&item.message.content);
+                response.tool_calls.extend(item.message.tool_calls.iter().cloned());
+                if item.done {
+                    let mut done = item.clone();
+                    done.message = std::mem::replace(&mut response, ChatMessage::assistant(String::new()));
+                    self.record(message.clone(), &done);
+                }
+                yield Ok(item);
+            }
+        }
+    }
+}
+
+impl Ollama {
+    /// Starts a [`ChatSession`] with `model` on a clone of this client.
+    pub fn chat_session(&self, model: impl Into<String>) -> ChatSession {
+        ChatSession::new(self.clone(), model)
+    }
+}
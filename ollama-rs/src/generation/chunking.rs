@@ -0,0 +1,158 @@
+//! Re-chunking of streamed responses into whole words or sentences, e.g. for text-to-speech
+//! or UI animations, which cope poorly with arbitrary token boundaries.
+//!
+//! ```no_run
+//! # async fn run() -> ollama_rs::error::Result<()> {
+//! use ollama_rs::{
+//!     generation::{
+//!         chat::{request::ChatMessageRequest, ChatMessage},
+//!         chunking::TextChunker,
+//!     },
+//!     Ollama,
+//! };
+//! use tokio_stream::StreamExt;
+//!
+//! let ollama = Ollama::default();
+//! let request = ChatMessageRequest::new(
+//!     "llama3.2".into(),
+//!     vec![ChatMessage::user("Tell me a story.".into())],
+//! );
+//! let stream = ollama.send_chat_messages_stream(request).await?;
+//!
+//! let mut sentences = TextChunker::sentences().chat_stream(stream);
+//! while let Some(res) = sentences.next().await {
+//!     println!("speak: {}", res?.message.content);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use futures_util::StreamExt;
+
+use crate::generation::{chat::ChatMessageResponseStream, completion::GenerationResponseStream};
+
+/// Buffers the text of a stream, yielding it once a piece ends with a delimiter followed by
+/// whitespace, or with whitespace when whitespace is a delimiter. The rest of the text is
+/// yielded with the last chunk.
+///
+/// Chunks whose text is held back are skipped, unless they carry tool calls or end the
+/// response, so that no information but the text boundaries is lost.
+#[derive(Debug, Clone)]
+pub struct TextChunker {
+    delimiters: Vec<char>,
+}
+
+impl TextChunker {
+    /// Yields whole words, along with the whitespace following them.
+    pub fn words() -> Self {
+        Self {
+            delimiters: vec![' ', '\t', '\n', '\r'],
+        }
+    }
+
+    /// Yields whole sentences, ending with `.`, `!`, `?`, `…` or a line break.
+    pub fn sentences() -> Self {
+        Self::delimiters(['.', '!', '?', '…', '\n'])
+    }
+
+    /// Yields pieces of text ending with one of `delimiters`. Delimiters other than whitespace
+    /// only end a piece when followed by whitespace, so that e.g. `3.14` isn't split.
+    pub fn delimiters(delimiters: impl IntoIterator<Item = char>) -> Self {
+        Self {
+            delimiters: delimiters.into_iter().collect(),
+        }
+    }
+
+    /// The length of the longest start of `text` made of whole pieces.
+    fn boundary(&self, text: &str) -> usize {
+        let mut boundary = 0;
+        let mut after_delimiter = false;
+        for (i, c) in text.char_indices() {
+            if c.is_whitespace() && (after_delimiter || self.delimiters.contains(&c)) {
+                // Whitespace following the piece is part of it.
+                boundary = i + c.len_utf8();
+                after_delimiter = true;
+            } else {
+                after_delimiter = self.delimiters.contains(&c);
+            }
+        }
+        boundary
+    }
+
+    /// Re-chunks a stream of [`Ollama::generate_stream`](crate::Ollama::generate_stream).
+    pub fn generation_stream(
+        &self,
+        mut stream: GenerationResponseStream,
+    ) -> GenerationResponseStream {
+        let mut buffer = Buffer::new(self.clone());
+        Box::pin(async_stream::stream! {
+            while let Some(item) = stream.next().await {
+                let chunk = match item {
+                    Ok(chunk) => chunk,
+                    Err(e) => {
+                        yield Err(e);
+                        continue;
+                    }
+                };
+
+                let chunk: Vec<_> = chunk
+                    .into_iter()
+                    .filter_map(|mut res| {
+                        res.response = buffer.push(&res.response, res.done);
+                        (!res.response.is_empty() || res.done).then_some(res)
+                    })
+                    .collect();
+                if !chunk.is_empty() {
+                    yield Ok(chunk);
+                }
+            }
+        })
+    }
+
+    /// Re-chunks a stream of [`Ollama::send_chat_messages_stream`](crate::Ollama::send_chat_messages_stream).
+    pub fn chat_stream(&self, mut stream: ChatMessageResponseStream) -> ChatMessageResponseStream {
+        let mut buffer = Buffer::new(self.clone());
+        Box::pin(async_stream::stream! {
+            while let Some(item) = stream.next().await {
+                let mut res = match item {
+                    Ok(res) => res,
+                    Err(e) => {
+                        yield Err(e);
+                        continue;
+                    }
+                };
+
+                res.message.content = buffer.push(&res.message.content, res.done);
+                if !res.message.content.is_empty() || !res.message.tool_calls.is_empty() || res.done {
+                    yield Ok(res);
+                }
+            }
+        })
+    }
+}
+
+/// The text received but not yet yielded.
+struct Buffer {
+    chunker: TextChunker,
+    text: String,
+}
+
+impl Buffer {
+    fn new(chunker: TextChunker) -> Self {
+        Self {
+            chunker,
+            text: String::new(),
+        }
+    }
+
+    /// Adds the text of a chunk, returning the whole pieces to yield in its place.
+    fn push(&mut self, chunk: &str, done: bool) -> String {
+        self.text.push_str(chunk);
+        let end = if done {
+            self.text.len()
+        } else {
+            self.chunker.boundary(&self.text)
+        };
+        self.text.drain(..end).collect()
+    }
+}
@@ -0,0 +1,221 @@
+//! Citations of retrieved documents in answers.
+//!
+//! A [`Citations`] numbers the documents given to the model, asks it to cite them with their
+//! number in brackets, like `[2]`, and maps the citations of its answer back to the documents:
+//!
+//! ```no_run
+//! # async fn run(ollama: ollama_rs::Ollama) -> ollama_rs::error::Result<()> {
+//! use ollama_rs::{
+//!     coordinator::Coordinator,
+//!     generation::{
+//!         chat::ChatMessage,
+//!         citations::Citations,
+//!         tools::implementations::{Document, DocumentRetriever},
+//!     },
+//! };
+//!
+//! let citations = Citations::new();
+//! let mut retriever = DocumentRetriever::new(ollama.clone(), "nomic-embed-text".to_string())
+//!     .citations(citations.clone());
+//! retriever
+//!     .add_documents(vec![Document::new("faq.md", "Refunds take 5 days.")])
+//!     .await
+//!     .unwrap();
+//!
+//! let mut coordinator = Coordinator::new(ollama, "llama3.2".to_string(), vec![])
+//!     .add_tool(retriever);
+//! let res = coordinator
+//!     .chat(vec![
+//!         citations.instructions(),
+//!         ChatMessage::user("How long do refunds take?".to_string()),
+//!     ])
+//!     .await?;
+//!
+//! let answer = citations.cite(&res.message.content);
+//! for citation in &answer.citations {
+//!     println!("{:?} is backed by {}", &answer.text[citation.claim.clone()], citation.document.id);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{
+    ops::Range,
+    sync::{Arc, Mutex},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::generation::{chat::ChatMessage, tools::implementations::Document};
+
+/// The documents given to the model, numbered from 1 in the order they were added.
+///
+/// Clones share their documents, so that a clone given to a
+/// [`DocumentRetriever`](crate::generation::tools::implementations::DocumentRetriever) numbers
+/// the documents the application then resolves the citations to.
+#[derive(Debug, Clone, Default)]
+pub struct Citations {
+    sources: Arc<Mutex<Vec<Document>>>,
+}
+
+impl Citations {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a document, returning its number. A document with the id of a document added
+    /// before keeps the number of the first one.
+    pub fn add(&self, document: Document) -> usize {
+        let mut sources = self.sources.lock().unwrap();
+        if let Some(i) = sources.iter().position(|d| d.id == document.id) {
+            return i + 1;
+        }
+        sources.push(document);
+        sources.len()
+    }
+
+    /// The document numbered `index`.
+    pub fn get(&self, index: usize) -> Option<Document> {
+        let sources = self.sources.lock().unwrap();
+        index.checked_sub(1).and_then(|i| sources.get(i).cloned())
+    }
+
+    /// The documents added so far, in the order of their numbers.
+    pub fn sources(&self) -> Vec<Document> {
+        self.sources.lock().unwrap().clone()
+    }
+
+    /// The documents added so far, numbered, followed by the instruction to cite them, to be
+    /// given to the model when the documents are known before the chat.
+    pub fn prompt(&self) -> String {
+        let mut prompt = String::from("Sources:\n");
+        for (i, document) in self.sources.lock().unwrap().iter().enumerate() {
+            prompt.push_str(&format!("[{}] {}\n", i + 1, document.content));
+        }
+        prompt.push('\n');
+        prompt.push_str(INSTRUCTIONS);
+        prompt
+    }
+
+    /// A system message asking the model to cite the sources it is given by a tool.
+    pub fn instructions(&self) -> ChatMessage {
+        ChatMessage::system(INSTRUCTIONS.to_string())
+    }
+
+    /// Finds the citations of `answer`, resolved to the documents they refer to. Citations of
+    /// unknown numbers are ignored.
+    pub fn cite(&self, answer: &str) -> CitedAnswer {
+        let sources = self.sources.lock().unwrap();
+        let mut citations = Vec::new();
+        let mut claim_start = 0;
+
+        for (marker, indices) in find_markers(answer) {
+            let claim = claim_range(answer, claim_start, marker.start);
+            for index in indices {
+                if let Some(document) = index.checked_sub(1).and_then(|i| sources.get(i)) {
+                    citations.push(Citation {
+                        index,
+                        marker: marker.clone(),
+                        claim: claim.clone(),
+                        document: document.clone(),
+                    });
+                }
+            }
+            claim_start = marker.end;
+        }
+
+        CitedAnswer {
+            text: answer.to_string(),
+            citations,
+        }
+    }
+}
+
+const INSTRUCTIONS: &str = "Cite the sources backing each statement with their number in brackets right after it, like [1] or [1][3]. Only cite the sources you were given.";
+
+/// An answer along with the documents its citations refer to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CitedAnswer {
+    /// The answer, with its citation markers.
+    pub text: String,
+    /// The citations, in the order they appear. A marker citing several documents gives a
+    /// citation for each.
+    pub citations: Vec<Citation>,
+}
+
+impl CitedAnswer {
+    /// The cited documents, without duplicates, in the order they are first cited.
+    pub fn sources(&self) -> Vec<&Document> {
+        let mut sources: Vec<&Document> = Vec::new();
+        for citation in &self.citations {
+            if !sources.iter().any(|d| d.id == citation.document.id) {
+                sources.push(&citation.document);
+            }
+        }
+        sources
+    }
+
+    /// The answer without its citation markers.
+    pub fn plain_text(&self) -> String {
+        let mut text = String::with_capacity(self.text.len());
+        let mut last = 0;
+        for (marker, _) in find_markers(&self.text) {
+            text.push_str(self.text[last..marker.start].trim_end_matches(' '));
+            last = marker.end;
+        }
+        text.push_str(&self.text[last..]);
+        text
+    }
+}
+
+/// A citation of a document in an answer.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Citation {
+    /// The number of the cited document.
+    pub index: usize,
+    /// The byte range of the citation marker in the answer, like `[2]` or `[1, 2]`.
+    pub marker: Range<usize>,
+    /// The byte range of the statement the citation backs: the text from the end of the
+    /// previous sentence or marker up to this marker.
+    pub claim: Range<usize>,
+    pub document: Document,
+}
+
+/// Finds the citation markers, like `[1]` or `[1, 3]`, with the numbers they cite.
+fn find_markers(text: &str) -> Vec<(Range<usize>, Vec<usize>)> {
+    let mut markers = Vec::new();
+    let mut rest = 0;
+    while let Some(start) = text[rest..].find('[').map(|i| rest + i) {
+        let Some(end) = text[start..].find(']').map(|i| start + i) else {
+            break;
+        };
+        let indices: Option<Vec<usize>> = text[start + 1..end]
+            .split(',')
+            .map(|index| index.trim().parse().ok())
+            .collect();
+        match indices {
+            Some(indices) => {
+                markers.push((start..end + 1, indices));
+                rest = end + 1;
+            }
+            None => rest = start + 1,
+        }
+    }
+    markers
+}
+
+/// The statement ending at `end`, starting after the last sentence end after `start`.
+fn claim_range(text: &str, start: usize, end: usize) -> Range<usize> {
+    let before = text[start..end].trim_end();
+    // The sentence end just before the marker, as in "Refunds take 5 days. [1]", belongs to
+    // the statement
+    let body = before.trim_end_matches(['.', '!', '?']);
+    let sentence_start = body
+        .rfind(['.', '!', '?', '\n'])
+        .map_or(start, |i| start + i + 1);
+    let leading = text[sentence_start..start + before.len()].len()
+        - text[sentence_start..start + before.len()]
+            .trim_start()
+            .len();
+    sentence_start + leading..start + before.len()
+}
@@ -0,0 +1,134 @@
+//! Hedged generations: when a request is slow, the same request is sent to a backup model or
+//! server, and the first acceptable response wins, cutting the latency of the slowest requests.
+
+use std::{future::Future, sync::Arc, task::Poll, time::Duration};
+
+use crate::{error::Result, Ollama};
+
+use super::{request::GenerationRequest, GenerationResponse};
+
+type Acceptance = Arc<dyn Fn(&GenerationResponse) -> bool + Send + Sync>;
+
+/// How [`Ollama::hedged_generate`] sends the backup request.
+#[derive(Clone)]
+pub struct HedgeOptions {
+    stagger: Duration,
+    backup_client: Option<Ollama>,
+    backup_model: Option<String>,
+    accept: Option<Acceptance>,
+}
+
+impl std::fmt::Debug for HedgeOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HedgeOptions")
+            .field("stagger", &self.stagger)
+            .field("backup_client", &self.backup_client)
+            .field("backup_model", &self.backup_model)
+            .finish_non_exhaustive()
+    }
+}
+
+impl HedgeOptions {
+    /// Sends the backup request if no acceptable response came after `stagger`.
+    ///
+    /// Without the `tokio` or `futures-timer` feature, there is no timer, and the backup is
+    /// only sent once the first request fails.
+    pub fn new(stagger: Duration) -> Self {
+        Self {
+            stagger,
+            backup_client: None,
+            backup_model: None,
+            accept: None,
+        }
+    }
+
+    /// Sends the backup request to another server. (Default: the same client)
+    pub fn backup_client(mut self, client: Ollama) -> Self {
+        self.backup_client = Some(client);
+        self
+    }
+
+    /// Sends the backup request to another model. (Default: the same model)
+    pub fn backup_model(mut self, model_name: impl Into<String>) -> Self {
+        self.backup_model = Some(model_name.into());
+        self
+    }
+
+    /// Only accepts the responses for which `accept` returns true, e.g. non-empty ones.
+    /// Unacceptable responses are treated like errors. (Default: every response)
+    pub fn accept(
+        mut self,
+        accept: impl Fn(&GenerationResponse) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.accept = Some(Arc::new(accept));
+        self
+    }
+}
+
+impl Ollama {
+    /// Sends `request`, then the backup request described by `options` if no acceptable
+    /// response came after the stagger delay, or as soon as the first request fails.
+    /// Returns the first acceptable response, dropping the other request.
+    ///
+    /// If neither response is acceptable, returns the outcome of the first request.
+    pub async fn hedged_generate(
+        &self,
+        request: GenerationRequest<'_>,
+        options: HedgeOptions,
+    ) -> Result<GenerationResponse> {
+        let mut backup_request = Some(request.clone());
+        if let (Some(request), Some(model)) = (&mut backup_request, &options.backup_model) {
+            request.model_name = model.clone();
+        }
+        let backup_client = options.backup_client.as_ref().unwrap_or(self);
+        let acceptable = |result: &Result<GenerationResponse>| match (result, &options.accept) {
+            (Ok(response), Some(accept)) => accept(response),
+            (result, _) => result.is_ok(),
+        };
+
+        let mut primary = Box::pin(self.generate(request));
+        let mut primary_result = None;
+        let mut stagger = Box::pin(async {
+            if crate::runtime::HAS_TIMER {
+                crate::runtime::sleep(options.stagger).await;
+            } else {
+                std::future::pending().await
+            }
+        });
+        let mut backup = None;
+        let mut backup_result = None;
+
+        std::future::poll_fn(|cx| {
+            if primary_result.is_none() {
+                if let Poll::Ready(result) = primary.as_mut().poll(cx) {
+                    if acceptable(&result) {
+                        return Poll::Ready(result);
+                    }
+                    primary_result = Some(result);
+                }
+            }
+
+            if backup.is_none()
+                && (primary_result.is_some() || stagger.as_mut().poll(cx).is_ready())
+            {
+                let request = backup_request.take().expect("the backup is only sent once");
+                backup = Some(Box::pin(backup_client.generate(request)));
+            }
+
+            if let (Some(future), None) = (&mut backup, &backup_result) {
+                if let Poll::Ready(result) = future.as_mut().poll(cx) {
+                    if acceptable(&result) {
+                        return Poll::Ready(result);
+                    }
+                    backup_result = Some(result);
+                }
+            }
+
+            match (&mut primary_result, &backup_result) {
+                (result @ Some(_), Some(_)) => Poll::Ready(result.take().unwrap()),
+                _ => Poll::Pending,
+            }
+        })
+        .await
+    }
+}
@@ -1,17 +1,22 @@
 use serde::{Deserialize, Serialize};
 
-use crate::{error::OllamaError, Ollama};
+use crate::{
+    generation::{done_reason::DoneReason, usage::Usage},
+    Ollama, RawResponse, Timestamp,
+};
 
 use request::GenerationRequest;
 
+pub mod hedging;
 pub mod request;
+pub mod session;
 
 #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
 #[cfg(feature = "stream")]
 /// A stream of `GenerationResponse` objects
 pub type GenerationResponseStream = std::pin::Pin<
     Box<
-        dyn tokio_stream::Stream<Item = crate::error::Result<GenerationResponseStreamChunk>> + Send,
+        dyn futures_util::Stream<Item = crate::error::Result<GenerationResponseStreamChunk>> + Send,
     >,
 >;
 pub type GenerationResponseStreamChunk = Vec<GenerationResponse>;
@@ -25,43 +30,46 @@ impl Ollama {
         &self,
         request: GenerationRequest<'_>,
     ) -> crate::error::Result<GenerationResponseStream> {
-        use tokio_stream::StreamExt;
+        use futures_util::StreamExt;
 
         use crate::error::OllamaError;
 
         let mut request = request;
         request.stream = true;
-
-        let url = format!("{}api/generate", self.url_str());
+        request.options = self.with_default_options(request.options);
+        request.keep_alive = request
+            .keep_alive
+            .or_else(|| self.config.default_keep_alive.clone());
+
+        let mut prompt = request.prompt.into_owned();
+        let prompt_annotations = self.filter_prompt(&mut prompt)?;
+        request.prompt = prompt.into();
+
+        self.check_features(crate::version::generation_features(&request))
+            .await?;
+        self.log_content(&request.model_name, "prompt", &request.prompt);
         let serialized = serde_json::to_string(&request)?;
-        let builder = self.reqwest_client.post(url);
-
-        #[cfg(feature = "headers")]
-        let builder = builder.headers(self.request_headers.clone());
+        let builder = self.stream_request(reqwest::Method::POST, "api/generate");
 
-        let res = builder.body(serialized).send().await?;
+        let res = self.send(builder.body(serialized)).await?;
 
         if !res.status().is_success() {
-            return Err(OllamaError::Other(
-                res.text().await.unwrap_or_else(|e| e.to_string()),
-            ));
+            return Err(OllamaError::from_http_response(res)
+                .await
+                .for_model(&request.model_name));
         }
 
-        let stream = Box::new(res.bytes_stream().map(|res| match res {
-            Ok(bytes) => {
-                let res = serde_json::Deserializer::from_slice(&bytes).into_iter();
-                let res = res
-                    .filter_map(Result::ok) // Filter out the errors
-                    .collect::<Vec<GenerationResponse>>();
-                Ok(res)
-            }
-            Err(e) => Err(OllamaError::Other(format!(
-                "Failed to read response: {}",
-                e
-            ))),
-        }));
-
-        Ok(std::pin::Pin::from(stream))
+        let model = request.model_name;
+        let stream = crate::ndjson::decode_stream::<GenerationResponse>(
+            res,
+            self.config.capture_raw_responses,
+        )
+        .map(move |res| res.map(|res| vec![res]).map_err(|e| e.for_model(&model)));
+
+        Ok(self
+            .config
+            .content_filters
+            .filter_stream(Box::pin(stream), prompt_annotations))
     }
 
     /// Completion generation with a single response.
@@ -72,47 +80,96 @@ impl Ollama {
     ) -> crate::error::Result<GenerationResponse> {
         let mut request = request;
         request.stream = false;
-
-        let url = format!("{}api/generate", self.url_str());
+        request.options = self.with_default_options(request.options);
+        request.keep_alive = request
+            .keep_alive
+            .or_else(|| self.config.default_keep_alive.clone());
+
+        let mut prompt = request.prompt.into_owned();
+        let mut prompt_annotations = self.filter_prompt(&mut prompt)?;
+        request.prompt = prompt.into();
+
+        self.check_features(crate::version::generation_features(&request))
+            .await?;
+        self.log_content(&request.model_name, "prompt", &request.prompt);
         let serialized = serde_json::to_string(&request)?;
-        let builder = self.reqwest_client.post(url);
-
-        #[cfg(feature = "headers")]
-        let builder = builder.headers(self.request_headers.clone());
+        let shared = self
+            .post_json("api/generate", serialized)
+            .await
+            .map_err(|e| e.for_model(&request.model_name))?;
+
+        let mut res = crate::error::decode::<GenerationResponse>(&shared.bytes).map_err(|e| {
+            e.for_model(&request.model_name)
+                .with_request_id(shared.request_id.as_deref())
+        })?;
+        self.config
+            .content_filters
+            .filter(&mut res, &mut prompt_annotations)?;
+        self.log_content(&res.model, "response", &res.response);
+        if self.config.capture_raw_responses {
+            res.raw = Some(RawResponse::new(&shared.bytes, shared.headers));
+        }
 
-        let res = builder.body(serialized).send().await?;
+        Ok(res)
+    }
 
-        if !res.status().is_success() {
-            return Err(OllamaError::Other(
-                res.text().await.unwrap_or_else(|e| e.to_string()),
-            ));
-        }
+    /// Runs many independent generation requests, at most `concurrency` at a time.
+    /// Returns the result of each request, in the order of the requests.
+    pub async fn generate_batch<'a>(
+        &self,
+        requests: impl IntoIterator<Item = GenerationRequest<'a>>,
+        concurrency: usize,
+    ) -> Vec<crate::error::Result<GenerationResponse>> {
+        let futures = requests
+            .into_iter()
+            .map(|request| Box::pin(self.generate(request)))
+            .collect();
+
+        crate::runtime::join_all(futures, concurrency.max(1)).await
+    }
+}
 
-        let res = res.bytes().await?;
-        let res = serde_json::from_slice::<GenerationResponse>(&res)?;
+#[cfg(feature = "stream")]
+impl crate::ndjson::StreamChunk for GenerationResponse {
+    fn text(&self) -> &str {
+        &self.response
+    }
 
-        Ok(res)
+    fn set_raw(&mut self, raw: RawResponse) {
+        self.raw = Some(raw);
     }
 }
 
 /// An encoding of a conversation returned by Ollama after a completion request, this can be sent in a new request to keep a conversational memory.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct GenerationContext(pub Vec<i32>);
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct GenerationResponse {
     /// The name of the model used for the completion.
     pub model: String,
-    /// The creation time of the completion, in such format: `2023-08-04T08:52:19.385406455-07:00`.
-    pub created_at: String,
+    /// The creation time of the completion, such as `2023-08-04T08:52:19.385406455-07:00`.
+    #[cfg_attr(feature = "utoipa", schema(value_type = String, format = DateTime))]
+    pub created_at: Timestamp,
     /// The response of the completion. This can be the entire completion or only a token if the completion is streaming.
     pub response: String,
+    /// The reasoning of a thinking model before its response, when requested with
+    /// [`GenerationRequest::think`](request::GenerationRequest::think).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thinking: Option<String>,
     /// Whether the completion is done. If the completion is streaming, this will be false until the last response.
     pub done: bool,
+    /// Why the completion ended, only present in the last response.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub done_reason: Option<DoneReason>,
     /// An encoding of the conversation used in this response, this can be sent in the next request to keep a conversational memory
     pub context: Option<GenerationContext>,
     /// Time spent generating the response
     pub total_duration: Option<u64>,
+    /// Time spent in nanoseconds loading the model
+    pub load_duration: Option<u64>,
     /// Number of tokens in the prompt
     pub prompt_eval_count: Option<u64>,
     /// Time spent in nanoseconds evaluating the prompt
@@ -121,4 +178,29 @@ pub struct GenerationResponse {
     pub eval_count: Option<u64>,
     /// Time spent in nanoseconds generating the response
     pub eval_duration: Option<u64>,
+    /// The raw HTTP response, only present if the client captures raw responses.
+    #[serde(skip)]
+    pub raw: Option<RawResponse>,
+    /// The notes of the [content filters](crate::moderation) of the client about the prompt
+    /// and the response.
+    #[serde(skip)]
+    pub annotations: Vec<String>,
+}
+
+impl GenerationResponse {
+    /// The token counts and timings of the completion, only present in the last response.
+    pub fn usage(&self) -> Option<Usage> {
+        if !self.done {
+            return None;
+        }
+
+        Some(Usage::from_nanos(
+            self.total_duration.unwrap_or_default(),
+            self.load_duration.unwrap_or_default(),
+            self.prompt_eval_count.unwrap_or_default(),
+            self.prompt_eval_duration.unwrap_or_default(),
+            self.eval_count.unwrap_or_default(),
+            self.eval_duration.unwrap_or_default(),
+        ))
+    }
 }
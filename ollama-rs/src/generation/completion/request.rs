@@ -5,7 +5,7 @@ use serde::Serialize;
 use crate::{
     generation::{
         images::Image,
-        parameters::{FormatType, KeepAlive},
+        parameters::{FormatType, KeepAlive, SchemaDialect, Think},
     },
     models::ModelOptions,
 };
@@ -14,6 +14,7 @@ use super::GenerationContext;
 
 /// A generation request to Ollama.
 #[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct GenerationRequest<'a> {
     #[serde(rename = "model")]
     pub model_name: String,
@@ -33,6 +34,10 @@ pub struct GenerationRequest<'a> {
     pub format: Option<FormatType>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub keep_alive: Option<KeepAlive>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub think: Option<Think>,
+    #[serde(skip)]
+    pub(crate) schema_dialect: Option<SchemaDialect>,
     pub(crate) stream: bool,
 }
 
@@ -49,6 +54,8 @@ impl<'a> GenerationRequest<'a> {
             context: None,
             format: None,
             keep_alive: None,
+            think: None,
+            schema_dialect: None,
             // Stream value will be overwritten by Ollama::generate_stream() and Ollama::generate() methods
             stream: false,
         }
@@ -66,9 +73,9 @@ impl<'a> GenerationRequest<'a> {
         self
     }
 
-    /// A list of images to be used with the prompt
-    pub fn images(mut self, images: Vec<Image>) -> Self {
-        self.images = images;
+    /// A list of images to be used with the prompt, replacing the images added before
+    pub fn images(mut self, images: impl IntoIterator<Item = Image>) -> Self {
+        self.images = images.into_iter().collect();
         self
     }
 
@@ -78,6 +85,12 @@ impl<'a> GenerationRequest<'a> {
         self
     }
 
+    /// Add several images to be used with the prompt
+    pub fn add_images(mut self, images: impl IntoIterator<Item = Image>) -> Self {
+        self.images.extend(images);
+        self
+    }
+
     /// Additional model parameters listed in the documentation for the Modelfile
     pub fn options(mut self, options: ModelOptions) -> Self {
         self.options = Some(options);
@@ -104,13 +117,37 @@ impl<'a> GenerationRequest<'a> {
 
     /// The format to return a response in.
     pub fn format(mut self, format: FormatType) -> Self {
-        self.format = Some(format);
+        self.format = Some(match (format, self.schema_dialect) {
+            (FormatType::StructuredJson(structure), Some(dialect)) => {
+                FormatType::StructuredJson(structure.to_dialect(dialect))
+            }
+            (format, _) => format,
+        });
         self
     }
 
+    /// Converts the JSON schema of the format of this request to the conventions of `dialect`,
+    /// whether it is set before or after. Schemas are otherwise sent as they are, the generated
+    /// ones following draft-07.
+    pub fn schema_dialect(mut self, dialect: SchemaDialect) -> Self {
+        self.schema_dialect = Some(dialect);
+        match self.format.take() {
+            Some(format) => self.format(format),
+            None => self,
+        }
+    }
+
     /// Used to control how long a model stays loaded in memory, by default models are unloaded after 5 minutes of inactivity
     pub fn keep_alive(mut self, keep_alive: KeepAlive) -> Self {
         self.keep_alive = Some(keep_alive);
         self
     }
+
+    /// Whether and how much a thinking model reasons before answering, see [`Think`].
+    pub fn think(mut self, think: impl Into<Think>) -> Self {
+        self.think = Some(think.into());
+        self
+    }
+
+    crate::models::model_option_setters!();
 }
@@ -0,0 +1,157 @@
+//! Multi-turn conversations over the generate endpoint, sending the `context` returned by each
+//! response with the next prompt.
+//!
+//! ```no_run
+//! # async fn run(ollama: ollama_rs::Ollama) -> ollama_rs::error::Result<()> {
+//! use ollama_rs::generation::completion::session::GenerateSession;
+//!
+//! let mut session = GenerateSession::new(ollama, "llama3.2".to_string());
+//! session.generate("My name is Ada.").await?;
+//!
+//! // A branch of the conversation, leaving the session as it is
+//! let mut branch = session.fork();
+//! branch.generate("Actually, call me Grace.").await?;
+//!
+//! let res = session.generate("What is my name?").await?;
+//! println!("{}", res.response);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{
+    error::Result,
+    generation::completion::{request::GenerationRequest, GenerationContext, GenerationResponse},
+    models::ModelOptions,
+    Ollama,
+};
+
+/// A conversation over the generate endpoint. Each response's context is sent with the
+/// next request, so that the model remembers the previous prompts and responses.
+///
+/// Cloning a session, or calling [`GenerateSession::fork`], branches the conversation.
+#[derive(Debug, Clone)]
+pub struct GenerateSession {
+    ollama: Ollama,
+    model_name: String,
+    options: Option<ModelOptions>,
+    system: Option<String>,
+    context: Option<GenerationContext>,
+}
+
+impl GenerateSession {
+    pub fn new(ollama: Ollama, model_name: String) -> Self {
+        Self {
+            ollama,
+            model_name,
+            options: None,
+            system: None,
+            context: None,
+        }
+    }
+
+    /// The options of the requests of the session.
+    pub fn options(mut self, options: ModelOptions) -> Self {
+        self.options = Some(options);
+        self
+    }
+
+    /// The system prompt of the requests of the session.
+    pub fn system(mut self, system: impl Into<String>) -> Self {
+        self.system = Some(system.into());
+        self
+    }
+
+    /// Continues the conversation from `context`, e.g. one saved from a previous session.
+    pub fn with_context(mut self, context: GenerationContext) -> Self {
+        self.context = Some(context);
+        self
+    }
+
+    pub fn model_name(&self) -> &str {
+        &self.model_name
+    }
+
+    /// The context of the last response, sent with the next request.
+    pub fn context(&self) -> Option<&GenerationContext> {
+        self.context.as_ref()
+    }
+
+    /// Forgets the conversation, so that the next request starts a new one.
+    pub fn reset(&mut self) {
+        self.context = None;
+    }
+
+    /// A copy of the session, whose conversation continues independently.
+    pub fn fork(&self) -> Self {
+        self.clone()
+    }
+
+    /// Sends `prompt`, continuing the conversation.
+    pub async fn generate(&mut self, prompt: impl Into<String>) -> Result<GenerationResponse> {
+        let request = self.request(prompt.into());
+        self.send(request).await
+    }
+
+    /// Sends `request` with the context of the conversation, e.g. to set options for one
+    /// request. The options and system prompt of the session apply unless `request` sets its
+    /// own.
+    pub async fn send(&mut self, request: GenerationRequest<'_>) -> Result<GenerationResponse> {
+        let request = self.prepare(request);
+        let res = self.ollama.generate(request).await?;
+        if let Some(context) = &res.context {
+            self.context = Some(context.clone());
+        }
+        Ok(res)
+    }
+
+    /// Like [`GenerateSession::generate`], streaming the response. The context is updated
+    /// when the last chunk is received: if the stream is dropped or fails before, the
+    /// conversation is left as it was.
+    #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+    #[cfg(feature = "stream")]
+    pub async fn generate_stream(
+        &mut self,
+        prompt: impl Into<String>,
+    ) -> Result<
+        std::pin::Pin<
+            Box<
+                dyn futures_util::Stream<Item = Result<super::GenerationResponseStreamChunk>>
+                    + Send
+                    + '_,
+            >,
+        >,
+    > {
+        use futures_util::StreamExt;
+
+        let request = self.prepare(self.request(prompt.into()));
+        let mut stream = self.ollama.generate_stream(request).await?;
+
+        Ok(Box::pin(async_stream::stream! {
+            while let Some(chunk) = stream.next().await {
+                if let Ok(chunk) = &chunk {
+                    if let Some(context) = chunk.iter().find_map(|res| res.context.clone()) {
+                        self.context = Some(context);
+                    }
+                }
+                yield chunk;
+            }
+        }))
+    }
+
+    fn request(&self, prompt: String) -> GenerationRequest<'static> {
+        GenerationRequest::new(self.model_name.clone(), prompt)
+    }
+
+    fn prepare<'a>(&self, mut request: GenerationRequest<'a>) -> GenerationRequest<'a> {
+        if request.options.is_none() {
+            request.options = self.options.clone();
+        }
+        if request.system.is_none() {
+            request.system = self.system.clone().map(Into::into);
+        }
+        if let Some(context) = &self.context {
+            request.context = Some(context.clone());
+        }
+        request
+    }
+}
@@ -0,0 +1,116 @@
+//! Continuation of responses cut off by the token limit.
+
+use crate::{
+    generation::{
+        chat::{request::ChatMessageRequest, ChatMessage, ChatMessageResponse, MessageRole},
+        completion::{request::GenerationRequest, GenerationResponse},
+    },
+    Ollama,
+};
+
+/// The prompt asking the model to continue a generation, see [`Ollama::generate_with_continuation`].
+pub(crate) const CONTINUE_PROMPT: &str =
+    "Continue exactly where you stopped, without repeating anything you already wrote.";
+
+impl Ollama {
+    /// Like [`Ollama::send_chat_messages`], but when the response is cut off by the token limit
+    /// (see [`DoneReason::Length`](crate::generation::done_reason::DoneReason::Length)), the model
+    /// is asked to continue it, at most `max_continues` times.
+    ///
+    /// The partial response is sent back as the last assistant message, which the model continues.
+    /// The returned response holds the whole message, and token counts and durations summed over
+    /// all the requests.
+    pub async fn send_chat_messages_with_continuation(
+        &self,
+        request: ChatMessageRequest,
+        max_continues: u32,
+    ) -> crate::error::Result<ChatMessageResponse> {
+        let mut res = self.send_chat_messages(request.clone()).await?;
+
+        for _ in 0..max_continues {
+            if !res.done_reason.as_ref().is_some_and(|r| r.is_truncated()) {
+                break;
+            }
+
+            let mut request = request.clone();
+            request.messages.push(ChatMessage::new(
+                MessageRole::Assistant,
+                res.message.content.clone(),
+            ));
+            let next = self.send_chat_messages(request).await?;
+
+            res.message.content.push_str(&next.message.content);
+            res.message.tool_calls.extend(next.message.tool_calls);
+            res.final_data = match (res.final_data, next.final_data) {
+                (Some(mut total), Some(next)) => {
+                    total.total_duration += next.total_duration;
+                    total.load_duration += next.load_duration;
+                    total.prompt_eval_count += next.prompt_eval_count;
+                    total.prompt_eval_duration += next.prompt_eval_duration;
+                    total.eval_count += next.eval_count;
+                    total.eval_duration += next.eval_duration;
+                    Some(total)
+                }
+                (total, next) => next.or(total),
+            };
+            res.created_at = next.created_at;
+            res.done_reason = next.done_reason;
+            res.raw = next.raw;
+        }
+
+        Ok(res)
+    }
+
+    /// Like [`Ollama::generate`], but when the response is cut off by the token limit
+    /// (see [`DoneReason::Length`](crate::generation::done_reason::DoneReason::Length)), the model
+    /// is asked to continue it, at most `max_continues` times.
+    ///
+    /// The context of the truncated response is sent with a prompt asking the model to continue
+    /// where it stopped. As the model answers a new prompt, the seams may not be perfect;
+    /// [`Ollama::send_chat_messages_with_continuation`] continues the response itself.
+    /// The returned response holds the whole text, and token counts and durations summed over
+    /// all the requests.
+    pub async fn generate_with_continuation(
+        &self,
+        request: GenerationRequest<'_>,
+        max_continues: u32,
+    ) -> crate::error::Result<GenerationResponse> {
+        let mut res = self.generate(request.clone()).await?;
+
+        for _ in 0..max_continues {
+            if !res.done_reason.as_ref().is_some_and(|r| r.is_truncated()) {
+                break;
+            }
+            let Some(context) = res.context.clone() else {
+                break;
+            };
+
+            let mut request = request.clone();
+            request.prompt = CONTINUE_PROMPT.into();
+            request.suffix = None;
+            request.images.clear();
+            let next = self.generate(request.context(context)).await?;
+
+            res.response.push_str(&next.response);
+            res.total_duration = sum(res.total_duration, next.total_duration);
+            res.load_duration = sum(res.load_duration, next.load_duration);
+            res.prompt_eval_count = sum(res.prompt_eval_count, next.prompt_eval_count);
+            res.prompt_eval_duration = sum(res.prompt_eval_duration, next.prompt_eval_duration);
+            res.eval_count = sum(res.eval_count, next.eval_count);
+            res.eval_duration = sum(res.eval_duration, next.eval_duration);
+            res.created_at = next.created_at;
+            res.done_reason = next.done_reason;
+            res.context = next.context;
+            res.raw = next.raw;
+        }
+
+        Ok(res)
+    }
+}
+
+fn sum(a: Option<u64>, b: Option<u64>) -> Option<u64> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a + b),
+        (a, b) => a.or(b),
+    }
+}
@@ -0,0 +1,77 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Why a generation or chat response ended.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum DoneReason {
+    /// The model finished its response or a stop sequence was generated.
+    Stop,
+    /// The response was cut off because it reached `num_predict` tokens or the context is full.
+    Length,
+    /// The request only loaded the model.
+    Load,
+    /// The request only unloaded the model.
+    Unload,
+    /// A reason unknown to this version of the library.
+    Unknown(String),
+}
+
+impl DoneReason {
+    /// Whether the response was cut off before the model finished it.
+    pub fn is_truncated(&self) -> bool {
+        *self == Self::Length
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Stop => "stop",
+            Self::Length => "length",
+            Self::Load => "load",
+            Self::Unload => "unload",
+            Self::Unknown(reason) => reason,
+        }
+    }
+}
+
+impl From<&str> for DoneReason {
+    fn from(reason: &str) -> Self {
+        match reason {
+            "stop" => Self::Stop,
+            "length" => Self::Length,
+            "load" => Self::Load,
+            "unload" => Self::Unload,
+            reason => Self::Unknown(reason.to_string()),
+        }
+    }
+}
+
+impl std::fmt::Display for DoneReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(feature = "utoipa")]
+impl utoipa::PartialSchema for DoneReason {
+    fn schema() -> utoipa::openapi::RefOr<utoipa::openapi::schema::Schema> {
+        utoipa::openapi::ObjectBuilder::new()
+            .schema_type(utoipa::openapi::Type::String)
+            .examples(["stop", "length", "load", "unload"])
+            .into()
+    }
+}
+
+#[cfg(feature = "utoipa")]
+impl utoipa::ToSchema for DoneReason {}
+
+impl Serialize for DoneReason {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for DoneReason {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let reason = String::deserialize(deserializer)?;
+        Ok(Self::from(reason.as_str()))
+    }
+}
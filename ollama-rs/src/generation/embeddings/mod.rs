@@ -1,44 +1,181 @@
-use serde::Deserialize;
+use std::sync::atomic::Ordering;
 
-use crate::{error::OllamaError, Ollama};
+use serde::{Deserialize, Serialize};
 
-use self::request::GenerateEmbeddingsRequest;
+use crate::{
+    error::OllamaError, generation::parameters::KeepAlive, models::ModelOptions, Ollama,
+    RawResponse,
+};
+
+use self::request::{EmbeddingsInput, GenerateEmbeddingsRequest};
 
 pub mod request;
 
+/// The endpoint embeddings are generated with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum EmbeddingsEndpoint {
+    /// `/api/embed`, falling back to `/api/embeddings` if the server doesn't have it, which is
+    /// then remembered by the client and its clones.
+    #[default]
+    Auto,
+    /// `/api/embed`, which embeds several inputs at once.
+    Embed,
+    /// `/api/embeddings`, the only endpoint of older servers. Inputs are embedded with a
+    /// request each, [`GenerateEmbeddingsRequest::truncate`] is ignored, and the embeddings
+    /// aren't normalized.
+    Legacy,
+}
+
+#[derive(Serialize)]
+struct LegacyEmbeddingsRequest<'a> {
+    model: &'a str,
+    prompt: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    options: &'a Option<ModelOptions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    keep_alive: &'a Option<KeepAlive>,
+}
+
+#[derive(Deserialize)]
+struct LegacyEmbeddingsResponse {
+    embedding: Vec<f32>,
+}
+
 impl Ollama {
     /// Generate embeddings from a model
     /// * `model_name` - Name of model to generate embeddings from
     /// * `prompt` - Prompt to generate embeddings for
+    ///
+    /// The endpoint used is set with [`Ollama::set_embeddings_endpoint`].
     pub async fn generate_embeddings(
         &self,
         request: GenerateEmbeddingsRequest,
     ) -> crate::error::Result<GenerateEmbeddingsResponse> {
-        let url = format!("{}api/embed", self.url_str());
-        let serialized = serde_json::to_string(&request)?;
-        let builder = self.reqwest_client.post(url);
+        let mut request = request;
+        request.options = self.with_default_options(request.options);
+        request.keep_alive = request
+            .keep_alive
+            .or_else(|| self.config.default_keep_alive.clone());
 
-        #[cfg(feature = "headers")]
-        let builder = builder.headers(self.request_headers.clone());
+        match self.config.embeddings_endpoint {
+            EmbeddingsEndpoint::Embed => self.embed(&request).await,
+            EmbeddingsEndpoint::Legacy => self.legacy_embeddings(&request).await,
+            EmbeddingsEndpoint::Auto => {
+                if self.config.legacy_embeddings.load(Ordering::Relaxed) {
+                    return self.legacy_embeddings(&request).await;
+                }
+                match self.embed(&request).await {
+                    // A missing model is reported as `ModelNotFound`, so this is a missing endpoint
+                    Err(OllamaError::HttpError { status, .. })
+                        if status == reqwest::StatusCode::NOT_FOUND =>
+                    {
+                        log::debug!("/api/embed not found, falling back to /api/embeddings");
+                        self.config.legacy_embeddings.store(true, Ordering::Relaxed);
+                        self.legacy_embeddings(&request).await
+                    }
+                    res => res,
+                }
+            }
+        }
+    }
 
-        let res = builder.body(serialized).send().await?;
+    async fn embed(
+        &self,
+        request: &GenerateEmbeddingsRequest,
+    ) -> crate::error::Result<GenerateEmbeddingsResponse> {
+        let serialized = serde_json::to_string(request)?;
+        let builder = self.request(reqwest::Method::POST, "api/embed");
+
+        let res = self.send(builder.body(serialized)).await?;
 
         if !res.status().is_success() {
-            return Err(OllamaError::Other(
-                res.text().await.unwrap_or_else(|e| e.to_string()),
-            ));
+            return Err(OllamaError::from_http_response(res).await);
         }
 
-        let res = res.bytes().await?;
-        let res = serde_json::from_slice::<GenerateEmbeddingsResponse>(&res)?;
+        let headers = self
+            .config
+            .capture_raw_responses
+            .then(|| res.headers().clone());
+        // Keeps the concurrency slot of the request while its body is read
+        let _extensions = res.extensions().clone();
+        let request_id = crate::request_id::of(&res);
+        let bytes = crate::error::read_body(res).await?;
+        let mut res = crate::error::decode::<GenerateEmbeddingsResponse>(&bytes)
+            .map_err(|e| e.with_request_id(request_id.as_deref()))?;
+        if let Some(headers) = headers {
+            res.raw = Some(RawResponse::new(&bytes, headers));
+        }
 
         Ok(res)
     }
+
+    /// Embeds the inputs one by one with `/api/embeddings`.
+    async fn legacy_embeddings(
+        &self,
+        request: &GenerateEmbeddingsRequest,
+    ) -> crate::error::Result<GenerateEmbeddingsResponse> {
+        let prompts = match &request.input {
+            EmbeddingsInput::Single(prompt) => std::slice::from_ref(prompt),
+            EmbeddingsInput::Multiple(prompts) => prompts.as_slice(),
+        };
+
+        let mut embeddings = Vec::with_capacity(prompts.len());
+        let mut raw = None;
+        for prompt in prompts {
+            let serialized = serde_json::to_string(&LegacyEmbeddingsRequest {
+                model: &request.model_name,
+                prompt,
+                options: &request.options,
+                keep_alive: &request.keep_alive,
+            })?;
+            let builder = self.request(reqwest::Method::POST, "api/embeddings");
+
+            let res = self.send(builder.body(serialized)).await?;
+
+            if !res.status().is_success() {
+                return Err(OllamaError::from_http_response(res).await);
+            }
+
+            let headers = self
+                .config
+                .capture_raw_responses
+                .then(|| res.headers().clone());
+            let _extensions = res.extensions().clone();
+            let request_id = crate::request_id::of(&res);
+            let bytes = crate::error::read_body(res).await?;
+            let decoded = crate::error::decode::<LegacyEmbeddingsResponse>(&bytes)
+                .map_err(|e| e.with_request_id(request_id.as_deref()))?;
+            embeddings.push(decoded.embedding);
+            // With several inputs, the last response is kept
+            if let Some(headers) = headers {
+                raw = Some(RawResponse::new(&bytes, headers));
+            }
+        }
+
+        Ok(GenerateEmbeddingsResponse { embeddings, raw })
+    }
 }
 
 /// An embeddings generation response from Ollama.
 #[derive(Debug, Deserialize, Clone)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct GenerateEmbeddingsResponse {
     #[allow(dead_code)]
     pub embeddings: Vec<Vec<f32>>,
+    /// The raw HTTP response, only present if the client captures raw responses.
+    #[serde(skip)]
+    pub raw: Option<RawResponse>,
+}
+
+/// The cosine similarity of two embeddings, from -1 to 1, or 0 if either is null.
+pub(crate) fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a * norm_b)
+    }
 }
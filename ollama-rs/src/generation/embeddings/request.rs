@@ -47,18 +47,38 @@ impl Serialize for EmbeddingsInput {
     }
 }
 
+#[cfg(feature = "utoipa")]
+impl utoipa::PartialSchema for EmbeddingsInput {
+    fn schema() -> utoipa::openapi::RefOr<utoipa::openapi::schema::Schema> {
+        use utoipa::openapi::{ObjectBuilder, OneOfBuilder, Type};
+
+        OneOfBuilder::new()
+            .item(ObjectBuilder::new().schema_type(Type::String))
+            .item(
+                ObjectBuilder::new()
+                    .schema_type(Type::String)
+                    .to_array_builder(),
+            )
+            .into()
+    }
+}
+
+#[cfg(feature = "utoipa")]
+impl utoipa::ToSchema for EmbeddingsInput {}
+
 /// An embeddings generation request to Ollama.
 #[derive(Debug, Serialize, Default)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct GenerateEmbeddingsRequest {
     #[serde(rename = "model")]
-    model_name: String,
-    input: EmbeddingsInput,
+    pub(crate) model_name: String,
+    pub(crate) input: EmbeddingsInput,
     #[serde(skip_serializing_if = "Option::is_none")]
-    truncate: Option<bool>,
+    pub(crate) truncate: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    options: Option<ModelOptions>,
+    pub(crate) options: Option<ModelOptions>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    keep_alive: Option<KeepAlive>,
+    pub(crate) keep_alive: Option<KeepAlive>,
 }
 
 impl GenerateEmbeddingsRequest {
@@ -1,6 +1,6 @@
-use schemars::{gen::SchemaSettings, schema::RootSchema};
+use schemars::{generate::SchemaSettings, Schema};
 pub use schemars::{schema_for, JsonSchema};
-use serde::{Serialize, Serializer};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 /// The format to return a response in
 #[derive(Debug, Clone)]
@@ -23,6 +23,43 @@ impl Serialize for FormatType {
     }
 }
 
+impl<'de> Deserialize<'de> for FormatType {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match serde_json::Value::deserialize(deserializer)? {
+            serde_json::Value::String(format) if format == "json" => Ok(FormatType::Json),
+            value => Schema::try_from(value)
+                .map(|schema| FormatType::StructuredJson(JsonStructure::new_for_schema(schema)))
+                .map_err(|_| serde::de::Error::custom("expected \"json\" or a JSON schema")),
+        }
+    }
+}
+
+#[cfg(feature = "utoipa")]
+impl utoipa::PartialSchema for FormatType {
+    fn schema() -> utoipa::openapi::RefOr<utoipa::openapi::schema::Schema> {
+        use utoipa::openapi::{ObjectBuilder, OneOfBuilder, Type};
+
+        OneOfBuilder::new()
+            .item(
+                ObjectBuilder::new()
+                    .schema_type(Type::String)
+                    .enum_values(Some(["json"])),
+            )
+            .item(
+                ObjectBuilder::new()
+                    .schema_type(Type::Object)
+                    .description(Some("The JSON schema the response must conform to")),
+            )
+            .into()
+    }
+}
+
+#[cfg(feature = "utoipa")]
+impl utoipa::ToSchema for FormatType {}
+
 /// Represents a serialized JSON schema. You can create this by converting
 /// a JsonSchema:
 /// ```rust
@@ -31,26 +68,283 @@ impl Serialize for FormatType {
 /// ```
 #[derive(Debug, Clone)]
 pub struct JsonStructure {
-    schema: RootSchema,
+    schema: Schema,
 }
 
 impl JsonStructure {
     pub fn new<T: JsonSchema>() -> Self {
-        // Need to do this because Ollama doesn't support $refs (references in the schema)
-        // So we have to explicitly turn them off
-        let mut settings = SchemaSettings::draft07();
-        settings.inline_subschemas = true;
-        let generator = settings.into_generator();
-        let schema = generator.into_root_schema_for::<T>();
+        Self::new_with_dialect::<T>(SchemaDialect::Draft07)
+    }
 
-        Self { schema }
+    /// Like [`JsonStructure::new`], with the conventions of `dialect`.
+    pub fn new_with_dialect<T: JsonSchema>(dialect: SchemaDialect) -> Self {
+        Self {
+            schema: dialect.schema_for::<T>(),
+        }
+    }
+
+    /// Converts the schema to the conventions of `dialect`.
+    pub fn to_dialect(self, dialect: SchemaDialect) -> Self {
+        Self {
+            schema: dialect.convert(self.schema),
+        }
     }
 
-    pub fn new_for_schema(schema: RootSchema) -> Self {
+    pub fn new_for_schema(schema: Schema) -> Self {
         Self { schema }
     }
+
+    pub(crate) fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    /// Uses a raw JSON schema, e.g. a hand-written schema or one produced by another generator.
+    ///
+    /// Fails if `schema` is neither an object nor a boolean.
+    pub fn from_value(schema: serde_json::Value) -> crate::error::Result<Self> {
+        Ok(Self {
+            schema: Schema::try_from(schema)?,
+        })
+    }
+
+    /// Checks that `instance` conforms to the schema. Structured outputs are only enforced by
+    /// Ollama as far as its grammar allows, and deserializing into `Value` fields accepts anything,
+    /// so this reports every violation along with its location.
+    #[cfg_attr(docsrs, doc(cfg(feature = "jsonschema")))]
+    #[cfg(feature = "jsonschema")]
+    pub fn validate(&self, instance: &serde_json::Value) -> crate::error::Result<()> {
+        use crate::error::{OllamaError, SchemaViolation};
+
+        let schema = serde_json::to_value(&self.schema)?;
+        let validator = jsonschema::validator_for(&schema)
+            .map_err(|e| OllamaError::Other(format!("Invalid JSON schema: {e}")))?;
+
+        let violations: Vec<_> = validator
+            .iter_errors(instance)
+            .map(|e| SchemaViolation {
+                instance_path: e.instance_path.to_string(),
+                schema_path: e.schema_path.to_string(),
+                message: e.to_string(),
+            })
+            .collect();
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(OllamaError::SchemaValidationError { violations })
+        }
+    }
+
+    /// Parses a structured response, validates it against the schema and deserializes it.
+    #[cfg_attr(docsrs, doc(cfg(feature = "jsonschema")))]
+    #[cfg(feature = "jsonschema")]
+    pub fn parse<T: serde::de::DeserializeOwned>(&self, response: &str) -> crate::error::Result<T> {
+        let value = crate::error::decode::<serde_json::Value>(response.as_bytes())?;
+        self.validate(&value)?;
+        Ok(serde_json::from_value(value)?)
+    }
+}
+
+/// The version of JSON Schema the schemas of formats and tools follow.
+///
+/// Ollama accepts both, but newer versions and some models handle the modern dialect better.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SchemaDialect {
+    /// Draft-07, with `definitions` and tuples as arrays of `items`.
+    #[default]
+    Draft07,
+    /// Draft 2020-12, with `$defs` and tuples as `prefixItems`.
+    Draft2020_12,
+}
+
+impl SchemaDialect {
+    /// Generates the schema of `T`. Subschemas are inlined, as Ollama doesn't resolve `$ref`s.
+    pub(crate) fn schema_for<T: JsonSchema>(self) -> Schema {
+        let mut settings = match self {
+            Self::Draft07 => SchemaSettings::draft07(),
+            Self::Draft2020_12 => SchemaSettings::draft2020_12(),
+        };
+        settings.inline_subschemas = true;
+        settings.into_generator().into_root_schema_for::<T>()
+    }
+
+    /// Rewrites a schema, such as a hand-written one, with the keywords of this dialect.
+    pub(crate) fn convert(self, schema: Schema) -> Schema {
+        let original = schema.clone();
+        let mut value = schema.to_value();
+        convert_schema(&mut value, self);
+        Schema::try_from(value).unwrap_or(original)
+    }
+
+    fn meta_schema(self) -> &'static str {
+        match self {
+            Self::Draft07 => "http://json-schema.org/draft-07/schema#",
+            Self::Draft2020_12 => "https://json-schema.org/draft/2020-12/schema",
+        }
+    }
 }
 
+/// Renames the keywords of `schema` and its subschemas that differ between dialects.
+fn convert_schema(schema: &mut serde_json::Value, dialect: SchemaDialect) {
+    use serde_json::Value;
+
+    let Value::Object(object) = schema else {
+        return;
+    };
+
+    if object.contains_key("$schema") {
+        object.insert("$schema".into(), dialect.meta_schema().into());
+    }
+
+    let rename = |object: &mut serde_json::Map<String, Value>, from: &str, to: &str| {
+        if let Some(value) = object.remove(from) {
+            object.insert(to.to_string(), value);
+        }
+    };
+    match dialect {
+        SchemaDialect::Draft07 => {
+            rename(object, "$defs", "definitions");
+            if let Some(prefix) = object.remove("prefixItems") {
+                rename(object, "items", "additionalItems");
+                object.insert("items".into(), prefix);
+            }
+            let mut dependencies = serde_json::Map::new();
+            for key in ["dependentRequired", "dependentSchemas"] {
+                if let Some(Value::Object(map)) = object.remove(key) {
+                    dependencies.extend(map);
+                }
+            }
+            if !dependencies.is_empty() {
+                object.insert("dependencies".into(), Value::Object(dependencies));
+            }
+        }
+        SchemaDialect::Draft2020_12 => {
+            rename(object, "definitions", "$defs");
+            if matches!(object.get("items"), Some(Value::Array(_))) {
+                let prefix = object.remove("items").unwrap_or_default();
+                rename(object, "additionalItems", "items");
+                object.insert("prefixItems".into(), prefix);
+            }
+            if let Some(Value::Object(dependencies)) = object.remove("dependencies") {
+                for (name, dependency) in dependencies {
+                    let key = match dependency {
+                        Value::Array(_) => "dependentRequired",
+                        _ => "dependentSchemas",
+                    };
+                    if let Value::Object(map) = object
+                        .entry(key)
+                        .or_insert_with(|| Value::Object(Default::default()))
+                    {
+                        map.insert(name, dependency);
+                    }
+                }
+            }
+        }
+    }
+
+    if let Some(Value::String(reference)) = object.get_mut("$ref") {
+        *reference = match dialect {
+            SchemaDialect::Draft07 => reference.replacen("#/$defs/", "#/definitions/", 1),
+            SchemaDialect::Draft2020_12 => reference.replacen("#/definitions/", "#/$defs/", 1),
+        };
+    }
+
+    for (key, value) in object.iter_mut() {
+        match key.as_str() {
+            // Maps of subschemas
+            "properties" | "patternProperties" | "$defs" | "definitions" | "dependentSchemas"
+            | "dependencies" => {
+                if let Value::Object(subschemas) = value {
+                    for subschema in subschemas.values_mut() {
+                        convert_schema(subschema, dialect);
+                    }
+                }
+            }
+            // Lists of subschemas
+            "allOf" | "anyOf" | "oneOf" | "prefixItems" | "items" => match value {
+                Value::Array(subschemas) => {
+                    for subschema in subschemas {
+                        convert_schema(subschema, dialect);
+                    }
+                }
+                value => convert_schema(value, dialect),
+            },
+            // Subschemas
+            "additionalProperties"
+            | "additionalItems"
+            | "contains"
+            | "not"
+            | "if"
+            | "then"
+            | "else"
+            | "propertyNames"
+            | "unevaluatedProperties"
+            | "unevaluatedItems" => convert_schema(value, dialect),
+            // Data, such as `enum` and `default`, and other keywords
+            _ => {}
+        }
+    }
+}
+
+/// Whether and how much a thinking model, such as `deepseek-r1` or `qwen3`, reasons before
+/// answering. The reasoning is returned apart from the answer, in
+/// [`ChatMessage::thinking`](crate::generation::chat::ChatMessage::thinking) and
+/// [`GenerationResponse::thinking`](crate::generation::completion::GenerationResponse::thinking).
+///
+/// Requires Ollama 0.9.0 or greater. Only some models, such as `gpt-oss`, support levels;
+/// others treat them as [`Think::Enabled`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Think {
+    /// The model answers without reasoning, if it supports it.
+    Disabled,
+    /// The model reasons as much as it is trained to.
+    Enabled,
+    Low,
+    Medium,
+    High,
+}
+
+impl Serialize for Think {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Disabled => serializer.serialize_bool(false),
+            Self::Enabled => serializer.serialize_bool(true),
+            Self::Low => serializer.serialize_str("low"),
+            Self::Medium => serializer.serialize_str("medium"),
+            Self::High => serializer.serialize_str("high"),
+        }
+    }
+}
+
+impl From<bool> for Think {
+    fn from(enabled: bool) -> Self {
+        if enabled {
+            Self::Enabled
+        } else {
+            Self::Disabled
+        }
+    }
+}
+
+#[cfg(feature = "utoipa")]
+impl utoipa::PartialSchema for Think {
+    fn schema() -> utoipa::openapi::RefOr<utoipa::openapi::schema::Schema> {
+        use utoipa::openapi::{ObjectBuilder, OneOfBuilder, Type};
+
+        OneOfBuilder::new()
+            .item(ObjectBuilder::new().schema_type(Type::Boolean))
+            .item(
+                ObjectBuilder::new()
+                    .schema_type(Type::String)
+                    .enum_values(Some(["low", "medium", "high"])),
+            )
+            .into()
+    }
+}
+
+#[cfg(feature = "utoipa")]
+impl utoipa::ToSchema for Think {}
+
 /// Used to control how long a model stays loaded in memory, by default models are unloaded after 5 minutes of inactivity
 #[derive(Debug, Clone)]
 pub enum KeepAlive {
@@ -77,6 +371,25 @@ impl Serialize for KeepAlive {
     }
 }
 
+#[cfg(feature = "utoipa")]
+impl utoipa::PartialSchema for KeepAlive {
+    fn schema() -> utoipa::openapi::RefOr<utoipa::openapi::schema::Schema> {
+        use utoipa::openapi::{ObjectBuilder, OneOfBuilder, Type};
+
+        OneOfBuilder::new()
+            .item(ObjectBuilder::new().schema_type(Type::Integer))
+            .item(ObjectBuilder::new().schema_type(Type::String))
+            .description(Some(
+                "`-1` to keep the model loaded indefinitely, `0` to unload it immediately, \
+                 or a duration such as `5m`",
+            ))
+            .into()
+    }
+}
+
+#[cfg(feature = "utoipa")]
+impl utoipa::ToSchema for KeepAlive {}
+
 #[derive(Debug, Clone)]
 pub enum TimeUnit {
     Seconds,
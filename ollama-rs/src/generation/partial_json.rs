@@ -0,0 +1,337 @@
+//! Parsing of incomplete JSON, so that structured outputs can be rendered as they stream.
+//!
+//! A prefix of a JSON document is completed by closing its open strings, arrays and objects,
+//! and by dropping what can't be completed, like a key without a value. Deserializing it into
+//! a type whose fields are `Option`s gives progressively more complete values:
+//!
+//! ```no_run
+//! # async fn run() -> ollama_rs::error::Result<()> {
+//! use ollama_rs::{
+//!     generation::{
+//!         completion::request::GenerationRequest,
+//!         parameters::{FormatType, JsonSchema, JsonStructure},
+//!         partial_json::PartialJson,
+//!     },
+//!     Ollama,
+//! };
+//! use tokio_stream::StreamExt;
+//!
+//! #[derive(Debug, serde::Deserialize, JsonSchema)]
+//! struct Recipe {
+//!     title: Option<String>,
+//!     ingredients: Option<Vec<String>>,
+//! }
+//!
+//! let ollama = Ollama::default();
+//! let request = GenerationRequest::new("llama3.2".into(), "A pancake recipe")
+//!     .format(FormatType::StructuredJson(JsonStructure::new::<Recipe>()));
+//! let stream = ollama.generate_stream(request).await?;
+//!
+//! let mut recipes = PartialJson::generation_stream::<Recipe>(stream);
+//! while let Some(recipe) = recipes.next().await {
+//!     println!("{:?}", recipe?.value);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+/// Accumulates the text of a JSON document as it streams, parsing what has been received.
+#[derive(Debug, Clone, Default)]
+pub struct PartialJson {
+    text: String,
+    value: Option<Value>,
+}
+
+impl PartialJson {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends `text`, returning the value parsed from everything received so far if it
+    /// changed.
+    pub fn push(&mut self, text: &str) -> Option<&Value> {
+        self.text.push_str(text);
+        let value = parse_partial(&self.text);
+        if value.is_none() || value == self.value {
+            return None;
+        }
+        self.value = value;
+        self.value.as_ref()
+    }
+
+    /// The text received so far.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// The value parsed from the text received so far, if any.
+    pub fn value(&self) -> Option<&Value> {
+        self.value.as_ref()
+    }
+
+    /// Deserializes the value parsed so far into `T`, if it can be.
+    pub fn deserialize<T: DeserializeOwned>(&self) -> Option<T> {
+        T::deserialize(self.value.as_ref()?).ok()
+    }
+}
+
+/// Parses a prefix of a JSON document, completing it as little as needed to be valid.
+///
+/// Open strings, arrays and objects are closed. Keys without values, and numbers or literals
+/// that can't be completed, are dropped, as is a number at the end of the text, which may
+/// still go on, like `12` becoming `123`. Returns `None` if no value has started yet.
+///
+/// ```
+/// use ollama_rs::generation::partial_json::parse_partial;
+/// use serde_json::json;
+///
+/// assert_eq!(
+///     parse_partial(r#"{"title": "Panc"#),
+///     Some(json!({ "title": "Panc" }))
+/// );
+/// assert_eq!(
+///     parse_partial(r#"{"title": "Pancakes", "ingr"#),
+///     Some(json!({ "title": "Pancakes" }))
+/// );
+/// ```
+pub fn parse_partial(text: &str) -> Option<Value> {
+    let mut scanner = Scanner::default();
+    for (i, c) in text.char_indices() {
+        scanner.scan(i, c);
+    }
+
+    // The text as is, with its last string closed
+    let open_key = scanner.in_string && scanner.in_key();
+    let open_number = !scanner.in_string && ends_with_number(text);
+    if !open_key && !open_number {
+        let mut completed = text.to_string();
+        if scanner.in_string {
+            trim_partial_escape(&mut completed);
+            completed.push('"');
+        }
+        completed.push_str(&scanner.closers());
+        if let Ok(value) = serde_json::from_str(&completed) {
+            return Some(value);
+        }
+    }
+
+    // The text up to the end of the last complete value
+    let (end, closers) = scanner.safe.as_ref()?;
+    let mut completed = text[..*end].to_string();
+    completed.push_str(closers);
+    serde_json::from_str(&completed).ok()
+}
+
+/// Whether `text` ends with a number, with nothing after it to tell that it is over.
+fn ends_with_number(text: &str) -> bool {
+    let start = text
+        .trim_end_matches(|c: char| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E'))
+        .len();
+    // The `e` of `true` isn't an exponent
+    text[start..].starts_with(|c: char| c.is_ascii_digit() || c == '-')
+}
+
+/// Removes a trailing backslash or `\u` escape that isn't complete.
+fn trim_partial_escape(text: &mut String) {
+    let backslashes = text.chars().rev().take_while(|c| *c == '\\').count();
+    if backslashes % 2 == 1 {
+        text.pop();
+        return;
+    }
+    if let Some(i) = text.rfind("\\u") {
+        let escape = &text[i + 2..];
+        let preceding = text[..i].chars().rev().take_while(|c| *c == '\\').count();
+        if preceding % 2 == 0 && escape.len() < 4 {
+            text.truncate(i);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Container {
+    /// An object, and whether a key is expected next.
+    Object {
+        expects_key: bool,
+    },
+    Array,
+}
+
+#[derive(Debug, Default)]
+struct Scanner {
+    stack: Vec<Container>,
+    in_string: bool,
+    escaped: bool,
+    /// Where the text can be cut to be completed by closing its containers, along with the
+    /// closing characters.
+    safe: Option<(usize, String)>,
+}
+
+impl Scanner {
+    fn in_key(&self) -> bool {
+        matches!(
+            self.stack.last(),
+            Some(Container::Object { expects_key: true })
+        )
+    }
+
+    fn closers(&self) -> String {
+        self.stack
+            .iter()
+            .rev()
+            .map(|container| match container {
+                Container::Object { .. } => '}',
+                Container::Array => ']',
+            })
+            .collect()
+    }
+
+    fn mark_safe(&mut self, end: usize) {
+        self.safe = Some((end, self.closers()));
+    }
+
+    fn scan(&mut self, i: usize, c: char) {
+        if self.in_string {
+            if self.escaped {
+                self.escaped = false;
+            } else if c == '\\' {
+                self.escaped = true;
+            } else if c == '"' {
+                self.in_string = false;
+                if !self.in_key() {
+                    self.mark_safe(i + 1);
+                }
+            }
+            return;
+        }
+
+        match c {
+            '"' => self.in_string = true,
+            '{' => {
+                self.stack.push(Container::Object { expects_key: true });
+                self.mark_safe(i + 1);
+            }
+            '[' => {
+                self.stack.push(Container::Array);
+                self.mark_safe(i + 1);
+            }
+            '}' | ']' => {
+                self.stack.pop();
+                self.mark_safe(i + 1);
+            }
+            ':' => {
+                if let Some(Container::Object { expects_key }) = self.stack.last_mut() {
+                    *expects_key = false;
+                }
+            }
+            ',' => {
+                self.mark_safe(i);
+                if let Some(Container::Object { expects_key }) = self.stack.last_mut() {
+                    *expects_key = true;
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// A value deserialized from a structured response as it streams.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartialValue<T> {
+    /// The value parsed from the response received so far. Strings may be cut short, and
+    /// fields not received yet are missing.
+    pub value: T,
+    /// Whether the response is done, in which case `value` is the whole response.
+    pub done: bool,
+}
+
+/// A stream of [`PartialValue`]s.
+#[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+#[cfg(feature = "stream")]
+pub type PartialValueStream<T> = std::pin::Pin<
+    Box<dyn futures_util::Stream<Item = crate::error::Result<PartialValue<T>>> + Send>,
+>;
+
+#[cfg(feature = "stream")]
+impl PartialJson {
+    /// Yields the values deserialized from a stream of
+    /// [`Ollama::generate_stream`](crate::Ollama::generate_stream) each time they change.
+    ///
+    /// Partial values that can't be deserialized into `T`, e.g. because a required field
+    /// hasn't been received yet, are skipped. The whole response must deserialize into `T`,
+    /// or the stream ends with an error.
+    pub fn generation_stream<T>(
+        mut stream: crate::generation::completion::GenerationResponseStream,
+    ) -> PartialValueStream<T>
+    where
+        T: DeserializeOwned + Send + 'static,
+    {
+        use futures_util::StreamExt;
+
+        Box::pin(async_stream::stream! {
+            let mut json = PartialJson::new();
+            while let Some(chunk) = stream.next().await {
+                let chunk = match chunk {
+                    Ok(chunk) => chunk,
+                    Err(e) => {
+                        yield Err(e);
+                        return;
+                    }
+                };
+                for res in chunk {
+                    if let Some(item) = json.next_value(&res.response, res.done) {
+                        yield item;
+                    }
+                }
+            }
+        })
+    }
+
+    /// Like [`PartialJson::generation_stream`], for a stream of
+    /// [`Ollama::send_chat_messages_stream`](crate::Ollama::send_chat_messages_stream).
+    pub fn chat_stream<T>(
+        mut stream: crate::generation::chat::ChatMessageResponseStream,
+    ) -> PartialValueStream<T>
+    where
+        T: DeserializeOwned + Send + 'static,
+    {
+        use futures_util::StreamExt;
+
+        Box::pin(async_stream::stream! {
+            let mut json = PartialJson::new();
+            while let Some(res) = stream.next().await {
+                let res = match res {
+                    Ok(res) => res,
+                    Err(e) => {
+                        yield Err(e);
+                        return;
+                    }
+                };
+                if let Some(item) = json.next_value(&res.message.content, res.done) {
+                    yield item;
+                }
+            }
+        })
+    }
+
+    /// Appends the text of a chunk, returning the value to yield, if any.
+    fn next_value<T: DeserializeOwned>(
+        &mut self,
+        text: &str,
+        done: bool,
+    ) -> Option<crate::error::Result<PartialValue<T>>> {
+        if done {
+            self.text.push_str(text);
+            return Some(
+                crate::error::decode(self.text.as_bytes())
+                    .map(|value| PartialValue { value, done: true }),
+            );
+        }
+
+        self.push(text)?;
+        self.deserialize()
+            .map(|value| Ok(PartialValue { value, done: false }))
+    }
+}
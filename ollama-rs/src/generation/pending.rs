@@ -0,0 +1,106 @@
+//! Requests bound to a client, sent when awaited.
+//!
+//! ```no_run
+//! # async fn run() -> ollama_rs::error::Result<()> {
+//! use ollama_rs::{generation::completion::request::GenerationRequest, Ollama};
+//!
+//! let ollama = Ollama::default();
+//! let res = GenerationRequest::new("llama3.2".into(), "Why is the sky blue?")
+//!     .system("Answer in one sentence.")
+//!     .send(&ollama)
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{
+    future::{Future, IntoFuture},
+    pin::Pin,
+};
+
+use crate::{
+    error::Result,
+    generation::{
+        chat::{request::ChatMessageRequest, ChatMessageResponse},
+        completion::{request::GenerationRequest, GenerationResponse},
+        embeddings::{request::GenerateEmbeddingsRequest, GenerateEmbeddingsResponse},
+    },
+    Ollama,
+};
+
+/// A request bound to the client sending it, sent when awaited.
+///
+/// Created with the `send` method of [`GenerationRequest`], [`ChatMessageRequest`] and
+/// [`GenerateEmbeddingsRequest`].
+#[derive(Debug, Clone)]
+#[must_use = "requests are only sent when awaited"]
+pub struct PendingRequest<'a, R> {
+    ollama: &'a Ollama,
+    request: R,
+}
+
+impl<'a, R> PendingRequest<'a, R> {
+    pub fn new(ollama: &'a Ollama, request: R) -> Self {
+        Self { ollama, request }
+    }
+
+    pub fn request(&self) -> &R {
+        &self.request
+    }
+
+    /// Changes the request before it is sent.
+    pub fn map(mut self, f: impl FnOnce(R) -> R) -> Self {
+        self.request = f(self.request);
+        self
+    }
+}
+
+type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T>> + Send + 'a>>;
+
+impl<'a> IntoFuture for PendingRequest<'a, GenerationRequest<'a>> {
+    type Output = Result<GenerationResponse>;
+    type IntoFuture = BoxFuture<'a, GenerationResponse>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        Box::pin(self.ollama.generate(self.request))
+    }
+}
+
+impl<'a> IntoFuture for PendingRequest<'a, ChatMessageRequest> {
+    type Output = Result<ChatMessageResponse>;
+    type IntoFuture = BoxFuture<'a, ChatMessageResponse>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        Box::pin(self.ollama.send_chat_messages(self.request))
+    }
+}
+
+impl<'a> IntoFuture for PendingRequest<'a, GenerateEmbeddingsRequest> {
+    type Output = Result<GenerateEmbeddingsResponse>;
+    type IntoFuture = BoxFuture<'a, GenerateEmbeddingsResponse>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        Box::pin(self.ollama.generate_embeddings(self.request))
+    }
+}
+
+impl<'a> GenerationRequest<'a> {
+    /// Binds the request to `ollama`: awaiting it sends it with [`Ollama::generate`].
+    pub fn send(self, ollama: &'a Ollama) -> PendingRequest<'a, Self> {
+        PendingRequest::new(ollama, self)
+    }
+}
+
+impl ChatMessageRequest {
+    /// Binds the request to `ollama`: awaiting it sends it with [`Ollama::send_chat_messages`].
+    pub fn send(self, ollama: &Ollama) -> PendingRequest<'_, Self> {
+        PendingRequest::new(ollama, self)
+    }
+}
+
+impl GenerateEmbeddingsRequest {
+    /// Binds the request to `ollama`: awaiting it sends it with [`Ollama::generate_embeddings`].
+    pub fn send(self, ollama: &Ollama) -> PendingRequest<'_, Self> {
+        PendingRequest::new(ollama, self)
+    }
+}
@@ -0,0 +1,247 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{
+    error::TemplateError,
+    generation::chat::{ChatMessage, MessageRole},
+};
+
+/// Partials may include other partials, up to this depth.
+const MAX_PARTIAL_DEPTH: usize = 16;
+
+/// A prompt with `{{variable}}` placeholders.
+///
+/// * `{{name}}` is replaced with the value of the variable `name`. Nested values can be
+///   reached with dots, such as `{{user.name}}`. Strings are inserted as-is, other values
+///   as JSON.
+/// * `{{> name}}` is replaced with the partial template `name`, rendered with the same variables.
+/// * `\{{` is rendered as a literal `{{`.
+///
+/// ```
+/// use ollama_rs::generation::prompt::PromptTemplate;
+/// use serde_json::json;
+///
+/// let template = PromptTemplate::new("{{> persona}} Summarize this text: {{text}}")
+///     .partial("persona", "You are a {{tone}} assistant.");
+///
+/// let prompt = template
+///     .render(&json!({ "tone": "concise", "text": "..." }))
+///     .unwrap();
+///
+/// assert_eq!(prompt, "You are a concise assistant. Summarize this text: ...");
+/// ```
+#[derive(Debug, Clone)]
+pub struct PromptTemplate {
+    template: String,
+    partials: HashMap<String, String>,
+}
+
+enum Segment<'a> {
+    Text(&'a str),
+    Variable(&'a str),
+    Partial(&'a str),
+}
+
+impl PromptTemplate {
+    pub fn new(template: impl Into<String>) -> Self {
+        Self {
+            template: template.into(),
+            partials: HashMap::new(),
+        }
+    }
+
+    /// Registers a template that can be included with `{{> name}}`.
+    pub fn partial(mut self, name: impl Into<String>, template: impl Into<String>) -> Self {
+        self.partials.insert(name.into(), template.into());
+        self
+    }
+
+    /// Returns the names of the variables used by the template and its partials,
+    /// in order of first appearance.
+    pub fn variables(&self) -> Result<Vec<String>, TemplateError> {
+        let mut variables = Vec::new();
+        self.collect_variables(&self.template, &mut variables, 0)?;
+        Ok(variables)
+    }
+
+    /// Renders the template. `variables` must serialize to a map, such as a struct, a `HashMap`
+    /// or a `serde_json::json!({...})` object.
+    pub fn render(&self, variables: &impl Serialize) -> Result<String, TemplateError> {
+        let variables = serde_json::to_value(variables)
+            .map_err(|e| TemplateError::InvalidVariables(e.to_string()))?;
+        if !variables.is_object() {
+            return Err(TemplateError::InvalidVariables(
+                "variables must be a map".to_string(),
+            ));
+        }
+
+        let mut output = String::with_capacity(self.template.len());
+        self.render_into(&self.template, &variables, &mut output, 0)?;
+        Ok(output)
+    }
+
+    /// Renders the template into a chat message with the given role.
+    pub fn render_message(
+        &self,
+        role: MessageRole,
+        variables: &impl Serialize,
+    ) -> Result<ChatMessage, TemplateError> {
+        Ok(ChatMessage::new(role, self.render(variables)?))
+    }
+
+    fn render_into(
+        &self,
+        template: &str,
+        variables: &Value,
+        output: &mut String,
+        depth: usize,
+    ) -> Result<(), TemplateError> {
+        for segment in parse(template)? {
+            match segment {
+                Segment::Text(text) => output.push_str(text),
+                Segment::Variable(name) => match lookup(variables, name) {
+                    Some(Value::String(s)) => output.push_str(s),
+                    Some(value) => output.push_str(&value.to_string()),
+                    None => return Err(TemplateError::MissingVariable(name.to_string())),
+                },
+                Segment::Partial(name) => {
+                    let partial = self.get_partial(name, depth)?;
+                    self.render_into(partial, variables, output, depth + 1)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn collect_variables(
+        &self,
+        template: &str,
+        variables: &mut Vec<String>,
+        depth: usize,
+    ) -> Result<(), TemplateError> {
+        for segment in parse(template)? {
+            match segment {
+                Segment::Text(_) => {}
+                Segment::Variable(name) => {
+                    if !variables.iter().any(|v| v == name) {
+                        variables.push(name.to_string());
+                    }
+                }
+                Segment::Partial(name) => {
+                    let partial = self.get_partial(name, depth)?;
+                    self.collect_variables(partial, variables, depth + 1)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn get_partial(&self, name: &str, depth: usize) -> Result<&str, TemplateError> {
+        if depth >= MAX_PARTIAL_DEPTH {
+            return Err(TemplateError::PartialTooDeep);
+        }
+
+        self.partials
+            .get(name)
+            .map(String::as_str)
+            .ok_or_else(|| TemplateError::UnknownPartial(name.to_string()))
+    }
+}
+
+/// Data rendered as a section of a prompt, so that structured values don't have to be
+/// formatted by hand.
+///
+/// Implemented with `#[derive(Prompt)]` (requires the `macros` feature), which renders each
+/// field on its own line, labelled with its name and followed by its `Display` value. Values
+/// spanning several lines start on the line after their label. `Option` fields are left out
+/// when they are `None`. `#[prompt(label = "...")]` changes the label of a field and
+/// `#[prompt(skip)]` leaves it out.
+///
+/// ```
+/// use ollama_rs::generation::prompt::Prompt;
+///
+/// #[derive(Prompt)]
+/// struct Ticket {
+///     customer_name: String,
+///     #[prompt(label = "Plan")]
+///     subscription: &'static str,
+///     order_id: Option<u32>,
+///     #[prompt(skip)]
+///     internal_notes: String,
+/// }
+///
+/// let ticket = Ticket {
+///     customer_name: "Ada".to_string(),
+///     subscription: "Pro",
+///     order_id: None,
+///     internal_notes: String::new(),
+/// };
+/// assert_eq!(ticket.render_prompt(), "Customer name: Ada\nPlan: Pro");
+/// ```
+pub trait Prompt {
+    fn render_prompt(&self) -> String;
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "macros")))]
+#[cfg(feature = "macros")]
+pub use ollama_rs_macros::Prompt;
+
+/// Used by `#[derive(Prompt)]`.
+#[doc(hidden)]
+pub mod __private {
+    use std::fmt::Display;
+
+    pub fn write_field(prompt: &mut String, label: &str, value: &dyn Display) {
+        if !prompt.is_empty() {
+            prompt.push('\n');
+        }
+        let value = value.to_string();
+        let separator = if value.contains('\n') { "\n" } else { " " };
+        prompt.push_str(label);
+        prompt.push(':');
+        prompt.push_str(separator);
+        prompt.push_str(&value);
+    }
+}
+
+fn parse(template: &str) -> Result<Vec<Segment<'_>>, TemplateError> {
+    let mut segments = Vec::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        if rest[..start].ends_with('\\') {
+            segments.push(Segment::Text(&rest[..start - 1]));
+            segments.push(Segment::Text("{{"));
+            rest = &rest[start + 2..];
+            continue;
+        }
+
+        segments.push(Segment::Text(&rest[..start]));
+
+        let tag_start = template.len() - rest.len() + start;
+        let end = rest[start..]
+            .find("}}")
+            .ok_or(TemplateError::UnclosedTag(tag_start))?;
+        let tag = rest[start + 2..start + end].trim();
+
+        segments.push(match tag.strip_prefix('>') {
+            Some(name) => Segment::Partial(name.trim()),
+            None => Segment::Variable(tag),
+        });
+
+        rest = &rest[start + end + 2..];
+    }
+
+    segments.push(Segment::Text(rest));
+    Ok(segments)
+}
+
+fn lookup<'a>(variables: &'a Value, name: &str) -> Option<&'a Value> {
+    name.split('.')
+        .try_fold(variables, |value, key| value.get(key))
+        .filter(|value| !value.is_null())
+}
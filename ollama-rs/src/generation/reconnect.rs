@@ -0,0 +1,218 @@
+//! Streams that reconnect when their connection drops before the response is done.
+//!
+//! ```no_run
+//! # async fn run() -> ollama_rs::error::Result<()> {
+//! use ollama_rs::{
+//!     generation::{
+//!         chat::{request::ChatMessageRequest, ChatMessage},
+//!         reconnect::StreamEvent,
+//!     },
+//!     Ollama,
+//! };
+//! use tokio_stream::StreamExt;
+//!
+//! let ollama = Ollama::default();
+//! let request = ChatMessageRequest::new(
+//!     "llama3.2".into(),
+//!     vec![ChatMessage::user("Tell me a long story.".into())],
+//! );
+//!
+//! let mut stream = ollama.send_chat_messages_stream_resumable(request, 3).await?;
+//! while let Some(event) = stream.next().await {
+//!     match event? {
+//!         StreamEvent::Chunk(res) => print!("{}", res.message.content),
+//!         StreamEvent::Resumed { attempt, .. } => eprintln!("\n[reconnected, attempt {attempt}]"),
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::future::Future;
+
+use futures_util::{Stream, StreamExt};
+
+use crate::{
+    error::{OllamaError, Result},
+    generation::{
+        chat::{request::ChatMessageRequest, ChatMessage, ChatMessageResponse, MessageRole},
+        completion::{request::GenerationRequest, GenerationResponseStreamChunk},
+        continuation::CONTINUE_PROMPT,
+    },
+    Ollama,
+};
+
+/// An item of a resumable stream.
+#[derive(Debug)]
+pub enum StreamEvent<T> {
+    /// A chunk of the response, as yielded by the underlying stream.
+    Chunk(T),
+    /// The connection dropped and the request was sent again. The chunks that follow
+    /// continue `partial_response`, but the model may not pick up exactly where it stopped.
+    Resumed {
+        /// The number of reconnections so far, starting at 1.
+        attempt: u32,
+        /// The text received before the connection dropped, over all attempts.
+        partial_response: String,
+        /// The error the connection dropped with, or `None` if the stream ended early.
+        error: Option<OllamaError>,
+    },
+}
+
+/// A chat stream that reconnects, see [`Ollama::send_chat_messages_stream_resumable`].
+pub type ResumableChatStream =
+    std::pin::Pin<Box<dyn Stream<Item = Result<StreamEvent<ChatMessageResponse>>> + Send>>;
+
+/// A generation stream that reconnects, see [`Ollama::generate_stream_resumable`].
+pub type ResumableGenerationStream = std::pin::Pin<
+    Box<dyn Stream<Item = Result<StreamEvent<GenerationResponseStreamChunk>>> + Send>,
+>;
+
+/// The text and completion of a chunk of a resumable stream.
+trait Resumable {
+    fn append_text(&self, text: &mut String);
+    fn is_done(&self) -> bool;
+}
+
+impl Resumable for ChatMessageResponse {
+    fn append_text(&self, text: &mut String) {
+        text.push_str(&self.message.content);
+    }
+
+    fn is_done(&self) -> bool {
+        self.done
+    }
+}
+
+impl Resumable for GenerationResponseStreamChunk {
+    fn append_text(&self, text: &mut String) {
+        for res in self {
+            text.push_str(&res.response);
+        }
+    }
+
+    fn is_done(&self) -> bool {
+        self.iter().any(|res| res.done)
+    }
+}
+
+impl Ollama {
+    /// Like [`Ollama::send_chat_messages_stream`], but when the connection drops before the
+    /// last chunk, the request is sent again, at most `max_reconnects` times.
+    ///
+    /// The text received so far is sent back as the last assistant message, which the model
+    /// continues. Each reconnection yields a [`StreamEvent::Resumed`] marking the seam. Once
+    /// `max_reconnects` is exhausted, the error the connection dropped with is yielded.
+    pub async fn send_chat_messages_stream_resumable(
+        &self,
+        request: ChatMessageRequest,
+        max_reconnects: u32,
+    ) -> Result<ResumableChatStream> {
+        let stream = self.send_chat_messages_stream(request.clone()).await?;
+        let ollama = self.clone();
+
+        Ok(Box::pin(resume(stream, max_reconnects, move |partial| {
+            let ollama = ollama.clone();
+            let mut request = request.clone();
+            if !partial.is_empty() {
+                request
+                    .messages
+                    .push(ChatMessage::new(MessageRole::Assistant, partial));
+            }
+            async move { ollama.send_chat_messages_stream(request).await }
+        })))
+    }
+
+    /// Like [`Ollama::generate_stream`], but when the connection drops before the last chunk,
+    /// the request is sent again, at most `max_reconnects` times.
+    ///
+    /// The prompt is sent again along with the text received so far and a prompt asking the
+    /// model to continue it. As the model answers a new prompt, the seams may not be perfect;
+    /// [`Ollama::send_chat_messages_stream_resumable`] continues the response itself. Each
+    /// reconnection yields a [`StreamEvent::Resumed`] marking the seam. Once `max_reconnects`
+    /// is exhausted, the error the connection dropped with is yielded.
+    pub async fn generate_stream_resumable(
+        &self,
+        request: GenerationRequest<'static>,
+        max_reconnects: u32,
+    ) -> Result<ResumableGenerationStream> {
+        let stream = self.generate_stream(request.clone()).await?;
+        let ollama = self.clone();
+
+        Ok(Box::pin(resume(stream, max_reconnects, move |partial| {
+            let ollama = ollama.clone();
+            let mut request = request.clone();
+            if !partial.is_empty() {
+                request.prompt = format!(
+                    "{}\n\nYour answer so far:\n{partial}\n\n{CONTINUE_PROMPT}",
+                    request.prompt
+                )
+                .into();
+            }
+            async move { ollama.generate_stream(request).await }
+        })))
+    }
+}
+
+/// Yields the chunks of `stream`, calling `reissue` with the text received so far whenever
+/// it drops before a chunk marked as done.
+fn resume<T, S, F, Fut>(
+    mut stream: S,
+    max_reconnects: u32,
+    mut reissue: F,
+) -> impl Stream<Item = Result<StreamEvent<T>>> + Send
+where
+    T: Resumable + Send + 'static,
+    S: Stream<Item = Result<T>> + Unpin + Send + 'static,
+    F: FnMut(String) -> Fut + Send + 'static,
+    Fut: Future<Output = Result<S>> + Send,
+{
+    async_stream::stream! {
+        let mut partial_response = String::new();
+        let mut attempt = 0;
+
+        loop {
+            let mut done = false;
+            let mut error = None;
+            while let Some(item) = stream.next().await {
+                match item {
+                    Ok(chunk) => {
+                        chunk.append_text(&mut partial_response);
+                        done |= chunk.is_done();
+                        yield Ok(StreamEvent::Chunk(chunk));
+                    }
+                    Err(e @ OllamaError::StreamInterrupted { .. }) => {
+                        error = Some(e);
+                        break;
+                    }
+                    // Other errors, such as undecodable lines, don't end the stream
+                    Err(e) => yield Err(e),
+                }
+            }
+
+            if done {
+                return;
+            }
+            if attempt == max_reconnects {
+                if let Some(e) = error {
+                    yield Err(e);
+                }
+                return;
+            }
+
+            attempt += 1;
+            match reissue(partial_response.clone()).await {
+                Ok(next) => stream = next,
+                Err(e) => {
+                    yield Err(e);
+                    return;
+                }
+            }
+            yield Ok(StreamEvent::Resumed {
+                attempt,
+                partial_response: partial_response.clone(),
+                error,
+            });
+        }
+    }
+}
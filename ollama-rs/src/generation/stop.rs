@@ -0,0 +1,279 @@
+//! Stop sequences enforced on the client, for patterns the `stop` option of the server can't
+//! express, such as regular expressions, or for servers ignoring it.
+//!
+//! ```no_run
+//! # async fn run() -> ollama_rs::error::Result<()> {
+//! use ollama_rs::{
+//!     generation::{completion::request::GenerationRequest, stop::StopSequences},
+//!     Ollama,
+//! };
+//! use tokio_stream::StreamExt;
+//!
+//! let ollama = Ollama::default();
+//! let request = GenerationRequest::new("llama3.2".into(), "Write a dialogue.");
+//! let stream = ollama.generate_stream(request).await?;
+//!
+//! let mut stream = StopSequences::new().text("\nUser:").generation_stream(stream);
+//! while let Some(chunk) = stream.next().await {
+//!     for res in chunk? {
+//!         print!("{}", res.response);
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use futures_util::StreamExt;
+
+use crate::{
+    floor_char_boundary,
+    generation::{
+        chat::ChatMessageResponseStream, completion::GenerationResponseStream,
+        done_reason::DoneReason,
+    },
+};
+
+#[derive(Debug, Clone)]
+enum StopPattern {
+    Text(String),
+    #[cfg(feature = "regex")]
+    Regex(regex::Regex),
+}
+
+/// Patterns ending streamed responses. Once one is generated, the response is truncated
+/// before it, the last chunk is marked as done with [`DoneReason::Stop`], and the request is
+/// aborted so that the server stops generating.
+///
+/// Text that may be the beginning of a text pattern is held back until it is known not to
+/// be, so that no part of a stop sequence is ever yielded. Regular expressions can't be
+/// anticipated: the response is truncated at their match, or at the text already yielded.
+/// Holding back the end of the response with [`StopSequences::hold_back`] lets matches
+/// shorter than that be cut before they are yielded. Matches are only looked for in the
+/// text not yielded yet and the end of the text yielded, as long as the longest text
+/// pattern or the hold-back: longer regex matches reaching further back are missed.
+///
+/// If the stream ends before a chunk marked as done, the text held back is yielded in a
+/// last chunk.
+#[derive(Debug, Clone, Default)]
+pub struct StopSequences {
+    patterns: Vec<StopPattern>,
+    #[cfg(feature = "regex")]
+    hold_back: usize,
+}
+
+impl StopSequences {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stops the response at `text`.
+    pub fn text(mut self, text: impl Into<String>) -> Self {
+        let text = text.into();
+        if !text.is_empty() {
+            self.patterns.push(StopPattern::Text(text));
+        }
+        self
+    }
+
+    /// Stops the response at the first match of `pattern`.
+    #[cfg_attr(docsrs, doc(cfg(feature = "regex")))]
+    #[cfg(feature = "regex")]
+    pub fn pattern(mut self, pattern: regex::Regex) -> Self {
+        self.patterns.push(StopPattern::Regex(pattern));
+        self
+    }
+
+    /// Holds back the last `bytes` of the response while regular expressions are set, so
+    /// that their matches up to that length are never yielded. (Default: 0)
+    #[cfg_attr(docsrs, doc(cfg(feature = "regex")))]
+    #[cfg(feature = "regex")]
+    pub fn hold_back(mut self, bytes: usize) -> Self {
+        self.hold_back = bytes;
+        self
+    }
+
+    /// The start of the earliest match in `text`.
+    pub fn find(&self, text: &str) -> Option<usize> {
+        self.patterns
+            .iter()
+            .filter_map(|pattern| match pattern {
+                StopPattern::Text(stop) => text.find(stop.as_str()),
+                #[cfg(feature = "regex")]
+                StopPattern::Regex(regex) => regex.find(text).map(|m| m.start()),
+            })
+            .min()
+    }
+
+    /// The length of the end of `text` to hold back: the longest one which a text pattern
+    /// starts with, or the hold-back of regular expressions.
+    fn held_back(&self, text: &str) -> usize {
+        self.patterns
+            .iter()
+            .filter_map(|pattern| match pattern {
+                StopPattern::Text(stop) => (1..stop.len())
+                    .rev()
+                    .find(|len| stop.is_char_boundary(*len) && text.ends_with(&stop[..*len])),
+                #[cfg(feature = "regex")]
+                StopPattern::Regex(_) => Some(
+                    text.len()
+                        - floor_char_boundary(text, text.len().saturating_sub(self.hold_back)),
+                ),
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// How much of the text already yielded a match may still start in.
+    fn lookbehind(&self) -> usize {
+        self.patterns
+            .iter()
+            .map(|pattern| match pattern {
+                StopPattern::Text(stop) => stop.len() - 1,
+                #[cfg(feature = "regex")]
+                StopPattern::Regex(_) => self.hold_back,
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Enforces the stop sequences on a stream of [`Ollama::generate_stream`](crate::Ollama::generate_stream).
+    pub fn generation_stream(
+        &self,
+        mut stream: GenerationResponseStream,
+    ) -> GenerationResponseStream {
+        let mut watch = Watch::new(self.clone());
+        Box::pin(async_stream::stream! {
+            let mut last = None;
+            while let Some(item) = stream.next().await {
+                let mut chunk = match item {
+                    Ok(chunk) => chunk,
+                    Err(e) => {
+                        yield Err(e);
+                        continue;
+                    }
+                };
+
+                let mut stopped = false;
+                for (i, res) in chunk.iter_mut().enumerate() {
+                    res.response = watch.push(&res.response, res.done);
+                    if watch.stopped {
+                        res.done = true;
+                        res.done_reason = Some(DoneReason::Stop);
+                        chunk.truncate(i + 1);
+                        stopped = true;
+                        break;
+                    }
+                }
+                last = chunk.last().cloned();
+                yield Ok(chunk);
+                if stopped {
+                    // Dropping the stream aborts the request.
+                    return;
+                }
+            }
+
+            // The stream ended early, e.g. dropped by the server
+            let rest = watch.flush();
+            if let Some(mut res) = last.filter(|_| !rest.is_empty()) {
+                res.response = rest;
+                yield Ok(vec![res]);
+            }
+        })
+    }
+
+    /// Enforces the stop sequences on a stream of [`Ollama::send_chat_messages_stream`](crate::Ollama::send_chat_messages_stream).
+    pub fn chat_stream(&self, mut stream: ChatMessageResponseStream) -> ChatMessageResponseStream {
+        let mut watch = Watch::new(self.clone());
+        Box::pin(async_stream::stream! {
+            let mut last = None;
+            while let Some(item) = stream.next().await {
+                let mut res = match item {
+                    Ok(res) => res,
+                    Err(e) => {
+                        yield Err(e);
+                        continue;
+                    }
+                };
+
+                res.message.content = watch.push(&res.message.content, res.done);
+                let stopped = watch.stopped;
+                if stopped {
+                    res.done = true;
+                    res.done_reason = Some(DoneReason::Stop);
+                }
+                last = Some(res.clone());
+                yield Ok(res);
+                if stopped {
+                    // Dropping the stream aborts the request.
+                    return;
+                }
+            }
+
+            // The stream ended early, e.g. dropped by the server
+            let rest = watch.flush();
+            if let Some(mut res) = last.filter(|_| !rest.is_empty()) {
+                res.message.content = rest;
+                yield Ok(res);
+            }
+        })
+    }
+}
+
+/// The end of the text of a response so far, and how much of it was yielded.
+struct Watch {
+    stops: StopSequences,
+    /// The text not yielded yet, after the end of the text yielded a match may start in.
+    text: String,
+    yielded: usize,
+    stopped: bool,
+}
+
+impl Watch {
+    fn new(stops: StopSequences) -> Self {
+        Self {
+            stops,
+            text: String::new(),
+            yielded: 0,
+            stopped: false,
+        }
+    }
+
+    /// Adds the text of a chunk, returning the text to yield in its place.
+    fn push(&mut self, chunk: &str, done: bool) -> String {
+        self.text.push_str(chunk);
+
+        let end = match self.stops.find(&self.text) {
+            Some(start) => {
+                self.stopped = true;
+                start
+            }
+            None if done => self.text.len(),
+            None => self.text.len() - self.stops.held_back(&self.text),
+        };
+
+        let end = end.max(self.yielded);
+        let out = self.text[self.yielded..end].to_string();
+        self.yielded = end;
+
+        // Forget the text no match can start in anymore, so that each chunk is only
+        // compared with the end of the response
+        let start = floor_char_boundary(
+            &self.text,
+            self.yielded.saturating_sub(self.stops.lookbehind()),
+        );
+        self.text.drain(..start);
+        self.yielded -= start;
+
+        out
+    }
+
+    /// The text held back, once the stream ended without a chunk marked as done.
+    fn flush(&mut self) -> String {
+        if self.stopped {
+            return String::new();
+        }
+        let out = self.text[self.yielded..].to_string();
+        self.yielded = self.text.len();
+        out
+    }
+}
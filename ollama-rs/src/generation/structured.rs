@@ -0,0 +1,296 @@
+//! Helpers built on structured outputs.
+
+use schemars::JsonSchema;
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::Value;
+
+use crate::{
+    error::OllamaError,
+    generation::{
+        chat::request::ChatMessageRequest,
+        completion::request::GenerationRequest,
+        parameters::{FormatType, JsonStructure},
+    },
+    Ollama,
+};
+
+const DEFAULT_EXTRACTION_INSTRUCTION: &str =
+    "Extract the following fields from the text below. Use null for fields the text doesn't mention.";
+
+#[derive(Deserialize, JsonSchema)]
+struct Classification<E> {
+    label: E,
+}
+
+impl Ollama {
+    /// Classifies `text` with one of the variants of the enum `E`.
+    ///
+    /// The model is constrained to answer with one of the labels of `E`, which are listed in the
+    /// prompt along with the doc comments of the variants.
+    ///
+    /// ```no_run
+    /// # async fn run(ollama: ollama_rs::Ollama) -> ollama_rs::error::Result<()> {
+    /// use ollama_rs::generation::parameters::JsonSchema;
+    ///
+    /// #[derive(serde::Deserialize, JsonSchema)]
+    /// #[serde(rename_all = "lowercase")]
+    /// enum Sentiment {
+    ///     Positive,
+    ///     Negative,
+    ///     /// Neither positive nor negative
+    ///     Neutral,
+    /// }
+    ///
+    /// let sentiment: Sentiment = ollama
+    ///     .classify("llama3.2".to_string(), "The delivery was late again.")
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn classify<E: JsonSchema + DeserializeOwned>(
+        &self,
+        model: String,
+        text: &str,
+    ) -> crate::error::Result<E> {
+        let structure = JsonStructure::new::<Classification<E>>();
+        let labels = labels(&structure.schema().as_value()["properties"]["label"]);
+
+        let mut prompt = String::from("Classify the following text with one of these labels:\n");
+        for label in labels {
+            prompt.push_str("- ");
+            prompt.push_str(&label);
+            prompt.push('\n');
+        }
+        prompt.push_str("\nText:\n");
+        prompt.push_str(text);
+
+        let request =
+            GenerationRequest::new(model, prompt).format(FormatType::StructuredJson(structure));
+        let res = self.generate(request).await?;
+
+        let classification: Classification<E> = crate::error::decode(res.response.as_bytes())?;
+        Ok(classification.label)
+    }
+}
+
+/// The options of [`Ollama::extract_with`].
+#[derive(Debug, Clone)]
+pub struct Extraction<'a> {
+    model: String,
+    text: &'a str,
+    instruction: Option<String>,
+    parse_retries: u32,
+}
+
+impl<'a> Extraction<'a> {
+    pub fn new(model: String, text: &'a str) -> Self {
+        Self {
+            model,
+            text,
+            instruction: None,
+            parse_retries: 2,
+        }
+    }
+
+    /// Replaces the instruction preceding the list of fields.
+    /// (Default: "Extract the following fields from the text below. Use null for fields the text doesn't mention.")
+    pub fn instruction(mut self, instruction: impl Into<String>) -> Self {
+        self.instruction = Some(instruction.into());
+        self
+    }
+
+    /// How many times the generation is retried when the response can't be parsed. (Default: 2)
+    pub fn parse_retries(mut self, retries: u32) -> Self {
+        self.parse_retries = retries;
+        self
+    }
+}
+
+impl Ollama {
+    /// Extracts the fields of `T` from unstructured `text`.
+    ///
+    /// The fields are listed in the prompt along with their doc comments, and the response is
+    /// constrained to the schema of `T`. See [`Ollama::extract_with`] to customize the instruction
+    /// or the number of retries.
+    ///
+    /// ```no_run
+    /// # async fn run(ollama: ollama_rs::Ollama) -> ollama_rs::error::Result<()> {
+    /// use ollama_rs::generation::parameters::JsonSchema;
+    ///
+    /// #[derive(serde::Deserialize, JsonSchema)]
+    /// struct Contact {
+    ///     name: String,
+    ///     /// The email address, if any
+    ///     email: Option<String>,
+    /// }
+    ///
+    /// let contact: Contact = ollama
+    ///     .extract("llama3.2".to_string(), "Hi, I'm Ada, reach me at ada@example.com")
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn extract<T: JsonSchema + DeserializeOwned>(
+        &self,
+        model: String,
+        text: &str,
+    ) -> crate::error::Result<T> {
+        self.extract_with(Extraction::new(model, text)).await
+    }
+
+    /// Like [`Ollama::extract`], with custom options.
+    ///
+    /// Responses that aren't valid JSON or don't deserialize into `T` are retried. With the
+    /// `jsonschema` feature, responses that don't conform to the schema of `T` are retried too.
+    pub async fn extract_with<T: JsonSchema + DeserializeOwned>(
+        &self,
+        extraction: Extraction<'_>,
+    ) -> crate::error::Result<T> {
+        let structure = JsonStructure::new::<T>();
+
+        let mut prompt = extraction
+            .instruction
+            .unwrap_or_else(|| DEFAULT_EXTRACTION_INSTRUCTION.to_string());
+        prompt.push_str("\n\nFields:\n");
+        for field in fields(structure.schema().as_value()) {
+            prompt.push_str("- ");
+            prompt.push_str(&field);
+            prompt.push('\n');
+        }
+        prompt.push_str("\nText:\n");
+        prompt.push_str(extraction.text);
+
+        let request = GenerationRequest::new(extraction.model, prompt)
+            .format(FormatType::StructuredJson(structure.clone()));
+
+        let mut attempt = 0;
+        loop {
+            let res = self.generate(request.clone()).await?;
+            match parse(&structure, &res.response) {
+                Err(e) if attempt < extraction.parse_retries && is_parse_error(&e) => {
+                    log::debug!("Retrying an extraction whose response couldn't be parsed: {e}");
+                    attempt += 1;
+                }
+                res => return res,
+            }
+        }
+    }
+}
+
+impl Ollama {
+    /// Sends a chat request constraining the response to the JSON schema of `T`, and
+    /// deserializes the response. The chat counterpart of [`Ollama::extract`], for applications
+    /// that only use the chat endpoint.
+    ///
+    /// The format of `request` is replaced by the schema of `T`. Responses that aren't valid
+    /// JSON or don't deserialize into `T` are retried twice, and with the `jsonschema` feature,
+    /// responses that don't conform to the schema of `T` are retried too.
+    ///
+    /// ```no_run
+    /// # async fn run(ollama: ollama_rs::Ollama) -> ollama_rs::error::Result<()> {
+    /// use ollama_rs::generation::{
+    ///     chat::{request::ChatMessageRequest, ChatMessage},
+    ///     parameters::JsonSchema,
+    /// };
+    ///
+    /// #[derive(serde::Deserialize, JsonSchema)]
+    /// struct Country {
+    ///     name: String,
+    ///     capital: String,
+    /// }
+    ///
+    /// let request = ChatMessageRequest::new(
+    ///     "llama3.2".to_string(),
+    ///     vec![ChatMessage::user("Tell me about Canada.".to_string())],
+    /// );
+    /// let country: Country = ollama.chat_structured(request).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn chat_structured<T: JsonSchema + DeserializeOwned>(
+        &self,
+        request: ChatMessageRequest,
+    ) -> crate::error::Result<T> {
+        let structure = JsonStructure::new::<T>();
+        let request = request.format(FormatType::StructuredJson(structure.clone()));
+
+        let mut attempt = 0;
+        loop {
+            let res = self.send_chat_messages(request.clone()).await?;
+            match parse(&structure, &res.message.content) {
+                Err(e) if attempt < STRUCTURED_CHAT_RETRIES && is_parse_error(&e) => {
+                    log::debug!(
+                        "Retrying a structured chat whose response couldn't be parsed: {e}"
+                    );
+                    attempt += 1;
+                }
+                res => return res,
+            }
+        }
+    }
+}
+
+const STRUCTURED_CHAT_RETRIES: u32 = 2;
+
+#[cfg_attr(not(feature = "jsonschema"), allow(unused_variables))]
+fn parse<T: DeserializeOwned>(
+    structure: &JsonStructure,
+    response: &str,
+) -> crate::error::Result<T> {
+    let value: Value = crate::error::decode(response.as_bytes())?;
+    #[cfg(feature = "jsonschema")]
+    structure.validate(&value)?;
+    Ok(serde_json::from_value(value)?)
+}
+
+fn is_parse_error(e: &OllamaError) -> bool {
+    matches!(
+        e,
+        OllamaError::DecodeError { .. }
+            | OllamaError::JsonError(_)
+            | OllamaError::SchemaValidationError { .. }
+    )
+}
+
+/// Lists the properties of an object schema, along with their descriptions.
+fn fields(schema: &Value) -> Vec<String> {
+    let Some(properties) = schema["properties"].as_object() else {
+        return vec![];
+    };
+
+    properties
+        .iter()
+        .map(|(name, property)| match property["description"].as_str() {
+            Some(description) => format!("{name}: {description}"),
+            None => name.clone(),
+        })
+        .collect()
+}
+
+/// Lists the values allowed by the schema of an enum, along with their descriptions.
+fn labels(schema: &Value) -> Vec<String> {
+    if let Some(values) = schema["enum"].as_array() {
+        return values.iter().map(label).collect();
+    }
+
+    if let Some(value) = schema.get("const") {
+        return match schema["description"].as_str() {
+            Some(description) => vec![format!("{} ({description})", label(value))],
+            None => vec![label(value)],
+        };
+    }
+
+    ["oneOf", "anyOf"]
+        .iter()
+        .filter_map(|key| schema[key].as_array())
+        .flatten()
+        .flat_map(labels)
+        .collect()
+}
+
+fn label(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        value => value.to_string(),
+    }
+}
@@ -0,0 +1,116 @@
+//! Writing of streamed responses as they arrive, while collecting them.
+//!
+//! ```no_run
+//! # async fn run() -> ollama_rs::error::Result<()> {
+//! use ollama_rs::{
+//!     generation::{completion::request::GenerationRequest, tee::TeeStream},
+//!     Ollama,
+//! };
+//!
+//! let ollama = Ollama::default();
+//! let request = GenerationRequest::new("llama3.2".into(), "Why is the sky blue?");
+//! let res = ollama
+//!     .generate_stream(request)
+//!     .await?
+//!     .tee_to(tokio::io::stdout())
+//!     .await?;
+//! println!("\n{:?}", res.usage());
+//! # Ok(())
+//! # }
+//! ```
+
+use std::future::Future;
+
+use futures_util::StreamExt;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+use crate::{
+    error::{OllamaError, Result},
+    generation::{
+        chat::{ChatMessageResponse, ChatMessageResponseStream},
+        completion::{GenerationResponse, GenerationResponseStream},
+    },
+};
+
+/// A response stream whose text can be written as it arrives.
+pub trait TeeStream {
+    /// The whole response.
+    type Response;
+
+    /// Writes the text of every chunk to `writer`, flushing it, and returns the whole
+    /// response once the stream ends: the text of every chunk, along with the token counts
+    /// and durations of the last one.
+    fn tee_to<W>(self, writer: W) -> impl Future<Output = Result<Self::Response>> + Send
+    where
+        W: AsyncWrite + Unpin + Send;
+}
+
+impl TeeStream for GenerationResponseStream {
+    type Response = GenerationResponse;
+
+    async fn tee_to<W>(mut self, mut writer: W) -> Result<GenerationResponse>
+    where
+        W: AsyncWrite + Unpin + Send,
+    {
+        let mut text = String::new();
+        let mut annotations = Vec::new();
+        let mut last = None;
+        while let Some(chunk) = self.next().await {
+            for res in chunk? {
+                write(&mut writer, &res.response).await?;
+                text.push_str(&res.response);
+                annotations.extend(res.annotations.iter().cloned());
+                last = Some(res);
+            }
+        }
+
+        let mut res = last.ok_or_else(empty_stream)?;
+        res.response = text;
+        res.annotations = annotations;
+        Ok(res)
+    }
+}
+
+impl TeeStream for ChatMessageResponseStream {
+    type Response = ChatMessageResponse;
+
+    async fn tee_to<W>(mut self, mut writer: W) -> Result<ChatMessageResponse>
+    where
+        W: AsyncWrite + Unpin + Send,
+    {
+        let mut content = String::new();
+        let mut tool_calls = Vec::new();
+        let mut annotations = Vec::new();
+        let mut last = None;
+        while let Some(res) = self.next().await {
+            let res = res?;
+            write(&mut writer, &res.message.content).await?;
+            content.push_str(&res.message.content);
+            tool_calls.extend(res.message.tool_calls.iter().cloned());
+            annotations.extend(res.annotations.iter().cloned());
+            last = Some(res);
+        }
+
+        let mut res = last.ok_or_else(empty_stream)?;
+        res.message.content = content;
+        res.message.tool_calls = tool_calls;
+        res.annotations = annotations;
+        Ok(res)
+    }
+}
+
+async fn write<W: AsyncWrite + Unpin>(writer: &mut W, text: &str) -> Result<()> {
+    if text.is_empty() {
+        return Ok(());
+    }
+    async {
+        writer.write_all(text.as_bytes()).await?;
+        writer.flush().await
+    }
+    .await
+    .map_err(|e| OllamaError::Other(format!("Failed to write the response: {e}")))
+}
+
+fn empty_stream() -> OllamaError {
+    OllamaError::Other("The stream ended without a response".to_string())
+}
@@ -0,0 +1,132 @@
+//! Tool calling for models without native tool support.
+//!
+//! Instead of sending the tools to Ollama, the tools are described in the system prompt, and
+//! the model is asked to reply with a JSON object such as
+//! `{"name": "calculator", "arguments": {"expression": "2 + 2"}}` to call one of them.
+
+use serde_json::Value;
+
+use super::{ToolCall, ToolCallFunction, ToolInfo};
+use crate::generation::chat::{ChatMessage, MessageRole};
+
+/// How the [`Coordinator`](crate::coordinator::Coordinator) lets the model call tools.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ToolCallStrategy {
+    /// The tools are sent to Ollama, which requires a model whose template supports tools.
+    #[default]
+    Native,
+    /// The tools are described in the system prompt and the calls are parsed from the text
+    /// of the responses, which works with any model following instructions.
+    Emulated,
+}
+
+/// Returns the instructions describing `tools` to the model.
+pub fn tool_prompt(tools: &[ToolInfo]) -> String {
+    let mut prompt = String::from("You have access to the following tools:\n\n");
+    for tool in tools {
+        prompt.push_str(&serde_json::to_string(&tool.function).unwrap_or_default());
+        prompt.push('\n');
+    }
+
+    prompt.push_str(
+        "\nTo call a tool, reply with only a JSON object of the form \
+         {\"name\": \"<tool name>\", \"arguments\": {<arguments>}} and nothing else. \
+         The result of the tool will then be sent to you. \
+         If you don't need a tool, answer normally.",
+    );
+    prompt
+}
+
+/// Parses the tool calls in the text of a response. A call is a JSON object with a `name`
+/// (or `tool`) and `arguments` (or `parameters`), optionally in a Markdown code block.
+/// Several calls can be made with an array of such objects. Only calls of `tools` are returned.
+pub fn parse_tool_calls(content: &str, tools: &[ToolInfo]) -> Vec<ToolCall> {
+    let Some(start) = content.find(['{', '[']) else {
+        return vec![];
+    };
+
+    let Some(Ok(value)) = serde_json::Deserializer::from_str(&content[start..])
+        .into_iter::<Value>()
+        .next()
+    else {
+        return vec![];
+    };
+
+    let calls = match value {
+        Value::Array(values) => values,
+        value => vec![value],
+    };
+
+    calls
+        .into_iter()
+        .filter_map(|call| {
+            let name = call.get("name").or_else(|| call.get("tool"))?.as_str()?;
+            let arguments = call
+                .get("arguments")
+                .or_else(|| call.get("parameters"))
+                .cloned()
+                .unwrap_or(Value::Object(Default::default()));
+
+            tools
+                .iter()
+                .any(|tool| tool.function.name == name)
+                .then(|| ToolCall {
+                    id: None,
+                    function: ToolCallFunction {
+                        name: name.to_string(),
+                        arguments,
+                    },
+                })
+        })
+        .collect()
+}
+
+/// Whether `content`, the start of a response, may still turn out to be a tool call, i.e.
+/// whether it starts with a JSON object or array, possibly in a Markdown code block.
+#[cfg(feature = "stream")]
+pub(crate) fn may_be_tool_call(content: &str) -> bool {
+    let content = content.trim_start();
+    if "```".starts_with(content) {
+        return true;
+    }
+    let content = match content.strip_prefix("```") {
+        // Past the language of the block
+        Some(block) => match block.split_once('\n') {
+            Some((_, body)) => body.trim_start(),
+            None => return true,
+        },
+        None => content,
+    };
+    content.is_empty() || content.starts_with(['{', '['])
+}
+
+/// Rewrites a conversation for a model without tool support: the tool prompt is added to the
+/// system message, and the results of tools are sent as user messages.
+pub fn emulate_messages(messages: Vec<ChatMessage>, tools: &[ToolInfo]) -> Vec<ChatMessage> {
+    let prompt = tool_prompt(tools);
+    let mut emulated = Vec::with_capacity(messages.len() + 1);
+
+    let mut messages = messages.into_iter().peekable();
+    match messages.next_if(|m| m.role == MessageRole::System) {
+        Some(mut system) => {
+            system.content = format!("{}\n\n{prompt}", system.content);
+            emulated.push(system);
+        }
+        None => emulated.push(ChatMessage::system(prompt)),
+    }
+
+    for mut message in messages {
+        if message.role == MessageRole::Tool {
+            message.role = MessageRole::User;
+            message.content = format!("Result of the tool call:\n{}", message.content);
+        }
+        if !message.tool_calls.is_empty() && message.content.trim().is_empty() {
+            let calls: Vec<_> = message.tool_calls.iter().map(|c| &c.function).collect();
+            message.content = serde_json::to_string(&calls).unwrap_or_default();
+        }
+        message.tool_calls.clear();
+        emulated.push(message);
+    }
+
+    emulated
+}
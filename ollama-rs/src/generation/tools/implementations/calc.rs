@@ -29,7 +29,7 @@ impl Tool for Calculator {
     }
 
     async fn call(
-        &mut self,
+        &self,
         parameters: Self::Params,
     ) -> Result<String, Box<dyn std::error::Error + Sync + Send>> {
         let mut ctx: Context<f64> = Context::default();
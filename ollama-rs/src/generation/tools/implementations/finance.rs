@@ -81,7 +81,7 @@ impl Tool for StockScraper {
         "Scrapes stock information from Google Finance."
     }
 
-    async fn call(&mut self, params: Params) -> Result<String, Box<dyn Error + Sync + Send>> {
+    async fn call(&self, params: Params) -> Result<String, Box<dyn Error + Sync + Send>> {
         let result = self.scrape(&params.exchange, &params.ticker).await?;
         Ok(serde_json::to_string(&result)?)
     }
@@ -1,13 +1,19 @@
 mod browserless;
 mod calc;
 mod finance;
+mod retrieval;
 mod scraper;
+mod scratchpad;
 mod search_ddg;
 mod serper;
 
 pub use browserless::Browserless;
 pub use calc::Calculator;
 pub use finance::StockScraper;
+pub use retrieval::{
+    Document, DocumentRetriever, InMemoryVectorStore, ScoredDocument, VectorStore,
+};
 pub use scraper::Scraper;
+pub use scratchpad::Scratchpad;
 pub use search_ddg::DDGSearcher;
 pub use serper::SerperSearchTool;
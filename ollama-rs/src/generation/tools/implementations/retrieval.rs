@@ -0,0 +1,250 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::future::Future;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    generation::{
+        citations::Citations,
+        embeddings::{cosine_similarity, request::GenerateEmbeddingsRequest},
+        tools::Tool,
+    },
+    Ollama,
+};
+
+#[derive(Deserialize, JsonSchema)]
+pub struct Params {
+    #[schemars(description = "The search query used to find relevant documents")]
+    query: String,
+    #[schemars(description = "The maximum number of documents to return")]
+    top_k: Option<usize>,
+}
+
+/// A document that can be stored in a [`VectorStore`] and retrieved by the model.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Document {
+    /// An identifier of the document, such as a file path or a database key.
+    pub id: String,
+    /// The text content of the document.
+    pub content: String,
+    /// Arbitrary metadata attached to the document.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub metadata: HashMap<String, String>,
+}
+
+impl Document {
+    pub fn new(id: impl Into<String>, content: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            content: content.into(),
+            metadata: HashMap::new(),
+        }
+    }
+
+    pub fn with_metadata(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.metadata.insert(key.into(), value.into());
+        self
+    }
+}
+
+/// A [`Document`] returned by a [`VectorStore`] search, along with its similarity to the query.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScoredDocument {
+    #[serde(flatten)]
+    pub document: Document,
+    pub score: f32,
+}
+
+/// A storage backend for document embeddings.
+pub trait VectorStore: Send + Sync {
+    /// Stores the documents along with their embeddings. Both vectors have the same length.
+    fn add(
+        &mut self,
+        documents: Vec<Document>,
+        embeddings: Vec<Vec<f32>>,
+    ) -> impl Future<Output = Result<(), Box<dyn Error + Send + Sync>>>;
+
+    /// Returns the `top_k` documents most similar to the query embedding, most similar first.
+    fn search(
+        &self,
+        embedding: &[f32],
+        top_k: usize,
+    ) -> impl Future<Output = Result<Vec<ScoredDocument>, Box<dyn Error + Send + Sync>>>;
+}
+
+/// A simple [`VectorStore`] keeping every embedding in memory and ranking by cosine similarity.
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryVectorStore {
+    entries: Vec<(Document, Vec<f32>)>,
+}
+
+impl InMemoryVectorStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl VectorStore for InMemoryVectorStore {
+    async fn add(
+        &mut self,
+        documents: Vec<Document>,
+        embeddings: Vec<Vec<f32>>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if documents.len() != embeddings.len() {
+            return Err("The number of documents and embeddings must match".into());
+        }
+
+        self.entries.extend(documents.into_iter().zip(embeddings));
+        Ok(())
+    }
+
+    async fn search(
+        &self,
+        embedding: &[f32],
+        top_k: usize,
+    ) -> Result<Vec<ScoredDocument>, Box<dyn Error + Send + Sync>> {
+        let mut scored = self
+            .entries
+            .iter()
+            .map(|(document, e)| ScoredDocument {
+                document: document.clone(),
+                score: cosine_similarity(embedding, e),
+            })
+            .collect::<Vec<_>>();
+
+        scored.sort_by(|a, b| b.score.total_cmp(&a.score));
+        scored.truncate(top_k);
+
+        Ok(scored)
+    }
+}
+
+/// A tool letting the model search a [`VectorStore`] for documents relevant to a query.
+///
+/// The query is embedded with the same model used to index the documents, so make sure
+/// to add documents through [`DocumentRetriever::add_documents`].
+pub struct DocumentRetriever<S: VectorStore = InMemoryVectorStore> {
+    ollama: Ollama,
+    embedding_model: String,
+    store: S,
+    top_k: usize,
+    citations: Option<Citations>,
+}
+
+impl DocumentRetriever<InMemoryVectorStore> {
+    pub fn new(ollama: Ollama, embedding_model: String) -> Self {
+        Self::with_store(ollama, embedding_model, InMemoryVectorStore::new())
+    }
+}
+
+impl<S: VectorStore> DocumentRetriever<S> {
+    pub fn with_store(ollama: Ollama, embedding_model: String, store: S) -> Self {
+        Self {
+            ollama,
+            embedding_model,
+            store,
+            top_k: 4,
+            citations: None,
+        }
+    }
+
+    /// The default number of documents returned when the model doesn't ask for a specific amount. (Default: 4)
+    pub fn top_k(mut self, top_k: usize) -> Self {
+        self.top_k = top_k;
+        self
+    }
+
+    /// Numbers the documents returned to the model in `citations`, so that it can cite them.
+    /// Each document is then returned with its number, as `source`.
+    pub fn citations(mut self, citations: Citations) -> Self {
+        self.citations = Some(citations);
+        self
+    }
+
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// Embeds the documents and adds them to the store.
+    pub async fn add_documents(
+        &mut self,
+        documents: Vec<Document>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if documents.is_empty() {
+            return Ok(());
+        }
+
+        let input = documents
+            .iter()
+            .map(|d| d.content.clone())
+            .collect::<Vec<_>>();
+        let embeddings = self
+            .ollama
+            .generate_embeddings(GenerateEmbeddingsRequest::new(
+                self.embedding_model.clone(),
+                input.into(),
+            ))
+            .await?
+            .embeddings;
+
+        self.store.add(documents, embeddings).await
+    }
+
+    /// Returns the documents most relevant to the query.
+    pub async fn retrieve(
+        &self,
+        query: &str,
+        top_k: usize,
+    ) -> Result<Vec<ScoredDocument>, Box<dyn Error + Send + Sync>> {
+        let embedding = self
+            .ollama
+            .generate_embeddings(GenerateEmbeddingsRequest::new(
+                self.embedding_model.clone(),
+                query.into(),
+            ))
+            .await?
+            .embeddings
+            .pop()
+            .ok_or("Ollama returned no embedding for the query")?;
+
+        self.store.search(&embedding, top_k).await
+    }
+}
+
+impl<S: VectorStore> Tool for DocumentRetriever<S> {
+    type Params = Params;
+
+    fn name() -> &'static str {
+        "retrieve_documents"
+    }
+
+    fn description() -> &'static str {
+        "Searches a knowledge base for documents relevant to a query and returns their content."
+    }
+
+    async fn call(&self, params: Params) -> Result<String, Box<dyn Error + Sync + Send>> {
+        let top_k = params.top_k.unwrap_or(self.top_k);
+        let results = self.retrieve(&params.query, top_k).await?;
+        let Some(citations) = &self.citations else {
+            return Ok(serde_json::to_string(&results)?);
+        };
+
+        let mut numbered = Vec::with_capacity(results.len());
+        for result in results {
+            let mut value = serde_json::to_value(&result)?;
+            value["source"] = citations.add(result.document).into();
+            numbered.push(value);
+        }
+        Ok(serde_json::to_string(&numbered)?)
+    }
+}
@@ -0,0 +1,135 @@
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+};
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use crate::generation::tools::Tool;
+
+#[derive(Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Action {
+    /// Stores a note under a key, replacing the note stored before.
+    Set,
+    /// Reads the note stored under a key.
+    Get,
+    /// Adds text at the end of the note stored under a key.
+    Append,
+    /// Lists the keys of the stored notes.
+    List,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct Params {
+    #[schemars(description = "What to do with the scratchpad: set, get, append or list")]
+    action: Action,
+    #[schemars(description = "The key of the note, required except to list the keys")]
+    key: Option<String>,
+    #[schemars(description = "The text to set or append")]
+    value: Option<String>,
+}
+
+/// A key-value scratchpad in which the model keeps notes across turns, without adding them
+/// to the conversation.
+///
+/// Clones share their notes, so that the application can read what the model wrote. Each
+/// scratchpad is a session of its own: give a new one to each
+/// [`Coordinator`](crate::coordinator::Coordinator) whose notes must be kept apart.
+///
+/// ```no_run
+/// # async fn run(ollama: ollama_rs::Ollama) -> ollama_rs::error::Result<()> {
+/// use ollama_rs::{
+///     coordinator::Coordinator,
+///     generation::{chat::ChatMessage, tools::implementations::Scratchpad},
+/// };
+///
+/// let scratchpad = Scratchpad::new();
+/// let mut coordinator = Coordinator::new(ollama, "llama3.2".to_string(), vec![])
+///     .add_tool(scratchpad.clone());
+/// coordinator
+///     .chat(vec![ChatMessage::user("Plan a trip to Lisbon".to_string())])
+///     .await?;
+/// println!("{:?}", scratchpad.notes());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Scratchpad {
+    notes: Arc<Mutex<BTreeMap<String, String>>>,
+}
+
+impl Scratchpad {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The note stored under `key`.
+    pub fn get(&self, key: &str) -> Option<String> {
+        self.notes.lock().unwrap().get(key).cloned()
+    }
+
+    /// Stores a note, e.g. to give the model some context before the chat.
+    pub fn set(&self, key: impl Into<String>, value: impl Into<String>) {
+        self.notes.lock().unwrap().insert(key.into(), value.into());
+    }
+
+    /// Every note, by key.
+    pub fn notes(&self) -> BTreeMap<String, String> {
+        self.notes.lock().unwrap().clone()
+    }
+
+    /// Removes every note.
+    pub fn clear(&self) {
+        self.notes.lock().unwrap().clear();
+    }
+}
+
+impl Tool for Scratchpad {
+    type Params = Params;
+
+    fn name() -> &'static str {
+        "scratchpad"
+    }
+
+    fn description() -> &'static str {
+        "Keeps notes across turns, without showing them in the conversation. Set a note under a key, get it back, append to it, or list the keys of the notes."
+    }
+
+    async fn call(
+        &self,
+        parameters: Self::Params,
+    ) -> Result<String, Box<dyn std::error::Error + Sync + Send>> {
+        let mut notes = self.notes.lock().unwrap();
+
+        if let Action::List = parameters.action {
+            return Ok(if notes.is_empty() {
+                "The scratchpad is empty".to_string()
+            } else {
+                notes.keys().cloned().collect::<Vec<_>>().join("\n")
+            });
+        }
+
+        // Mistakes are reported to the model, which can fix its call
+        let Some(key) = parameters.key else {
+            return Ok("A key is required".to_string());
+        };
+        let value = parameters.value.unwrap_or_default();
+        Ok(match parameters.action {
+            Action::Set => {
+                notes.insert(key.clone(), value);
+                format!("Set {key}")
+            }
+            Action::Append => {
+                notes.entry(key.clone()).or_default().push_str(&value);
+                format!("Appended to {key}")
+            }
+            Action::Get => match notes.get(&key) {
+                Some(note) => note.clone(),
+                None => format!("No note under {key}"),
+            },
+            Action::List => unreachable!(),
+        })
+    }
+}
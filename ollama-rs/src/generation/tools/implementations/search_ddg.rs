@@ -105,7 +105,7 @@ impl Tool for DDGSearcher {
         "Searches the web using DuckDuckGo's HTML interface."
     }
 
-    async fn call(&mut self, params: Params) -> Result<String, Box<dyn Error + Sync + Send>> {
+    async fn call(&self, params: Params) -> Result<String, Box<dyn Error + Sync + Send>> {
         let results = self.search(&params.query).await?;
         let results_json = serde_json::to_string(&results)?;
         Ok(results_json)
@@ -0,0 +1,87 @@
+//! Models used as natural-language argument parsers: the model is made to call a single tool,
+//! whose parameters are parsed from the prompt.
+
+use super::{emulation, Tool, ToolChoice, ToolInfo};
+use crate::{
+    error::{Capability, OllamaError, ToolCallError},
+    generation::chat::{request::ChatMessageRequest, ChatMessage},
+    Ollama,
+};
+
+/// A call of a tool made by [`Ollama::call_tool_via_model`]: the parameters the model called
+/// it with, and what the tool returned.
+#[derive(Debug, Clone)]
+pub struct ToolInvocation<P> {
+    pub parameters: P,
+    pub result: String,
+}
+
+impl Ollama {
+    /// Makes `model` call `tool` to handle `prompt`, calls it and returns the parameters
+    /// along with the result.
+    ///
+    /// The tool is the only one offered and is required with
+    /// [`ToolChoice::Tool`]. Models whose template doesn't support tools are given the tool
+    /// in the system prompt instead, see [`emulation`]. Fails with
+    /// [`ToolCallError::ToolCallRequired`] if the model answers without calling the tool.
+    ///
+    /// ```no_run
+    /// # async fn run(ollama: ollama_rs::Ollama) -> ollama_rs::error::Result<()> {
+    /// use ollama_rs::generation::tools::implementations::Calculator;
+    ///
+    /// let call = ollama
+    ///     .call_tool_via_model("llama3.2", Calculator {}, "What's twelve times seven?")
+    ///     .await?;
+    /// println!("{}", call.result);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn call_tool_via_model<T: Tool>(
+        &self,
+        model: impl Into<String>,
+        tool: T,
+        prompt: impl Into<String>,
+    ) -> crate::error::Result<ToolInvocation<T::Params>> {
+        let model = model.into();
+        let info = ToolInfo::new::<T::Params, T>();
+        let messages = vec![ChatMessage::user(prompt.into())];
+
+        let request = ChatMessageRequest::new(model.clone(), messages.clone())
+            .tools(vec![info.clone()])
+            .tool_choice(ToolChoice::Tool(T::name().to_string()));
+        let arguments = match self.send_chat_messages(request).await {
+            Ok(res) => res.message.tool_calls,
+            Err(OllamaError::UnsupportedCapability {
+                capability: Capability::Tools,
+                ..
+            }) => {
+                let tools = [info];
+                let mut messages = messages;
+                messages.insert(
+                    0,
+                    ChatMessage::system(format!("Call the `{}` tool to answer.", T::name())),
+                );
+                let messages = emulation::emulate_messages(messages, &tools);
+                let res = self
+                    .send_chat_messages(ChatMessageRequest::new(model, messages))
+                    .await?;
+                emulation::parse_tool_calls(&res.message.content, &tools)
+            }
+            Err(e) => return Err(e),
+        }
+        .into_iter()
+        .find(|call| call.function.name == T::name())
+        .ok_or(ToolCallError::ToolCallRequired)?
+        .function
+        .arguments;
+
+        let parameters: T::Params =
+            serde_json::from_value(arguments.clone()).map_err(ToolCallError::from)?;
+        let result = tool
+            .call(serde_json::from_value(arguments).map_err(ToolCallError::from)?)
+            .await
+            .map_err(ToolCallError::InternalToolError)?;
+
+        Ok(ToolInvocation { parameters, result })
+    }
+}
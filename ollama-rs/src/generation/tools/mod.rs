@@ -2,18 +2,28 @@
 #[cfg(feature = "tool-implementations")]
 pub mod implementations;
 
-use std::{future::Future, pin::Pin};
+pub mod emulation;
+mod invoke;
 
-use schemars::{r#gen::SchemaSettings, schema::RootSchema, JsonSchema};
+use std::{future::Future, pin::Pin, sync::Arc};
+
+use schemars::{JsonSchema, Schema};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::Value;
 
+use crate::{error::ToolCallError, generation::parameters::SchemaDialect};
+
+pub use invoke::ToolInvocation;
+
 pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error + Send + Sync>>;
 
 /// It's highly recommended that the `JsonSchema` has descriptions for all attributes.
 /// Descriptions can be defined with `#[schemars(description = "Hi I am an attribute")]` above each attribute
-// TODO enforce at compile-time
-pub trait Tool: Send {
+/// or with doc comments. Deriving [`DescribedParameters`] enforces it at compile time.
+///
+/// Tools are called through a shared reference, so a stateless tool can be used concurrently.
+/// Use interior mutability if a tool needs to mutate its own state.
+pub trait Tool: Send + Sync {
     type Params: Parameters;
 
     fn name() -> &'static str;
@@ -22,19 +32,111 @@ pub trait Tool: Send {
     /// Call the tool.
     /// Note that returning an Err will cause it to be bubbled up. If you want the LLM to handle the error,
     /// return that error as a string.
-    fn call(&mut self, parameters: Self::Params) -> impl Future<Output = Result<String>>;
+    fn call(&self, parameters: Self::Params) -> impl Future<Output = Result<String>>;
+}
+
+/// A tool that is given shared application state (such as a database pool) on every call.
+///
+/// The state is passed when registering the tool, e.g. with
+/// [`Coordinator::add_tool_with_state`](crate::coordinator::Coordinator::add_tool_with_state),
+/// so a single `Arc<AppState>` can be shared between many tools.
+pub trait StatefulTool<S: Send + Sync>: Send + Sync {
+    type Params: Parameters;
+
+    fn name() -> &'static str;
+    fn description() -> &'static str;
+
+    /// Call the tool with the shared state.
+    /// Note that returning an Err will cause it to be bubbled up. If you want the LLM to handle the error,
+    /// return that error as a string.
+    fn call(&self, state: &S, parameters: Self::Params) -> impl Future<Output = Result<String>>;
+}
+
+/// A [`StatefulTool`] bound to its state, which can be used anywhere a [`Tool`] is expected.
+pub struct WithState<T, S> {
+    tool: T,
+    state: Arc<S>,
+}
+
+impl<T, S> WithState<T, S> {
+    pub fn new(tool: T, state: Arc<S>) -> Self {
+        Self { tool, state }
+    }
+
+    pub fn state(&self) -> &Arc<S> {
+        &self.state
+    }
+}
+
+impl<S: Send + Sync, T: StatefulTool<S>> Tool for WithState<T, S> {
+    type Params = T::Params;
+
+    fn name() -> &'static str {
+        T::name()
+    }
+
+    fn description() -> &'static str {
+        T::description()
+    }
+
+    async fn call(&self, parameters: Self::Params) -> Result<String> {
+        self.tool.call(&self.state, parameters).await
+    }
 }
 
 pub trait Parameters: DeserializeOwned + JsonSchema {}
 
 impl<P: DeserializeOwned + JsonSchema> Parameters for P {}
 
-pub(crate) trait ToolHolder {
-    fn call(&mut self, parameters: Value) -> Pin<Box<dyn Future<Output = Result<String>> + '_>>;
+/// Tool parameters whose every field has a description, which the model relies on to fill
+/// them in correctly.
+///
+/// Implemented with `#[derive(DescribedParameters)]` (requires the `macros` feature), which
+/// fails to compile if a field has neither a doc comment nor a
+/// `#[schemars(description = "...")]`. Skipped fields are exempt, and flattened fields must be
+/// `DescribedParameters` too.
+///
+/// ```
+/// use ollama_rs::generation::{parameters::JsonSchema, tools::DescribedParameters};
+///
+/// #[derive(serde::Deserialize, JsonSchema, DescribedParameters)]
+/// struct Params {
+///     /// The city to get the weather of
+///     city: String,
+///     #[schemars(description = "Celsius or Fahrenheit")]
+///     unit: String,
+/// }
+/// ```
+///
+/// ```compile_fail
+/// use ollama_rs::generation::{parameters::JsonSchema, tools::DescribedParameters};
+///
+/// #[derive(serde::Deserialize, JsonSchema, DescribedParameters)]
+/// struct Params {
+///     city: String,
+/// }
+/// ```
+pub trait DescribedParameters: Parameters {}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "macros")))]
+#[cfg(feature = "macros")]
+pub use ollama_rs_macros::DescribedParameters;
+
+/// An object-safe version of [`Tool`], allowing tools of different types to be stored together.
+///
+/// It is implemented for every [`Tool`], so you should rarely need to implement it yourself.
+pub trait DynTool: Send + Sync {
+    fn info(&self) -> ToolInfo;
+
+    fn call(&self, parameters: Value) -> Pin<Box<dyn Future<Output = Result<String>> + '_>>;
 }
 
-impl<T: Tool> ToolHolder for T {
-    fn call(&mut self, parameters: Value) -> Pin<Box<dyn Future<Output = Result<String>> + '_>> {
+impl<T: Tool> DynTool for T {
+    fn info(&self) -> ToolInfo {
+        ToolInfo::new::<T::Params, T>()
+    }
+
+    fn call(&self, parameters: Value) -> Pin<Box<dyn Future<Output = Result<String>> + '_>> {
         Box::pin(async move {
             let parameters = serde_json::from_value(parameters)?;
             T::call(self, parameters).await
@@ -42,7 +144,128 @@ impl<T: Tool> ToolHolder for T {
     }
 }
 
+/// A collection of tools of any type, which can be assembled at runtime.
+///
+/// Adding a tool with the same name as an existing one replaces it.
+#[derive(Default)]
+pub struct ToolSet {
+    tools: Vec<(ToolInfo, Box<dyn DynTool>)>,
+}
+
+impl ToolSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a tool to the set.
+    pub fn add_tool<T: Tool + 'static>(mut self, tool: T) -> Self {
+        self.insert(tool);
+        self
+    }
+
+    /// Adds a tool to the set, for use when the tool set is built in a loop or conditionally.
+    pub fn insert<T: Tool + 'static>(&mut self, tool: T) {
+        self.insert_dyn(Box::new(tool));
+    }
+
+    /// Adds an already boxed tool to the set.
+    pub fn insert_dyn(&mut self, tool: Box<dyn DynTool>) {
+        let info = tool.info();
+
+        match self
+            .tools
+            .iter_mut()
+            .find(|(i, _)| i.function.name == info.function.name)
+        {
+            Some(entry) => *entry = (info, tool),
+            None => self.tools.push((info, tool)),
+        }
+    }
+
+    /// Removes the tool with the given name, returning whether it was present.
+    pub fn remove(&mut self, name: &str) -> bool {
+        let len = self.tools.len();
+        self.tools.retain(|(info, _)| info.function.name != name);
+        len != self.tools.len()
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn DynTool> {
+        self.tools
+            .iter()
+            .find(|(info, _)| info.function.name == name)
+            .map(|(_, tool)| tool.as_ref())
+    }
+
+    /// The information about every tool in the set, to be sent to the model.
+    pub fn infos(&self) -> Vec<ToolInfo> {
+        self.tools.iter().map(|(info, _)| info.clone()).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.tools.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tools.is_empty()
+    }
+
+    /// Calls the tool with the given name.
+    pub async fn call(&self, name: &str, parameters: Value) -> crate::error::Result<String> {
+        let Some(tool) = self.get(name) else {
+            return Err(ToolCallError::UnknownToolName.into());
+        };
+
+        let resp = tool
+            .call(parameters)
+            .await
+            .map_err(ToolCallError::InternalToolError)?;
+
+        Ok(resp)
+    }
+}
+
+impl<T: Tool + 'static> Extend<T> for ToolSet {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for tool in iter {
+            self.insert(tool);
+        }
+    }
+}
+
+impl Extend<Box<dyn DynTool>> for ToolSet {
+    fn extend<I: IntoIterator<Item = Box<dyn DynTool>>>(&mut self, iter: I) {
+        for tool in iter {
+            self.insert_dyn(tool);
+        }
+    }
+}
+
+impl IntoIterator for ToolSet {
+    type Item = Box<dyn DynTool>;
+    type IntoIter = std::iter::Map<
+        std::vec::IntoIter<(ToolInfo, Box<dyn DynTool>)>,
+        fn((ToolInfo, Box<dyn DynTool>)) -> Box<dyn DynTool>,
+    >;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.tools.into_iter().map(|(_, tool)| tool)
+    }
+}
+
+impl FromIterator<Box<dyn DynTool>> for ToolSet {
+    fn from_iter<I: IntoIterator<Item = Box<dyn DynTool>>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
 #[derive(Clone, Debug, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct ToolInfo {
     #[serde(rename = "type")]
     tool_type: ToolType,
@@ -51,41 +274,145 @@ pub struct ToolInfo {
 
 impl ToolInfo {
     pub(crate) fn new<P: Parameters, T: Tool<Params = P>>() -> Self {
-        let mut settings = SchemaSettings::draft07();
-        settings.inline_subschemas = true;
-        let generator = settings.into_generator();
-
-        let parameters = generator.into_root_schema_for::<P>();
+        let parameters = SchemaDialect::Draft07.schema_for::<P>();
 
         Self {
             tool_type: ToolType::Function,
             function: ToolFunctionInfo {
-                name: T::name(),
-                description: T::description(),
+                name: T::name().to_string(),
+                description: T::description().to_string(),
                 parameters,
             },
         }
     }
+
+    /// Describes a tool whose parameters are given by a raw JSON schema, e.g. a hand-written
+    /// schema or one produced by another generator. Useful with a custom [`DynTool`].
+    ///
+    /// Fails if `parameters` is neither an object nor a boolean.
+    pub fn from_value(
+        name: impl Into<String>,
+        description: impl Into<String>,
+        parameters: Value,
+    ) -> crate::error::Result<Self> {
+        Ok(Self {
+            tool_type: ToolType::Function,
+            function: ToolFunctionInfo {
+                name: name.into(),
+                description: description.into(),
+                parameters: Schema::try_from(parameters)?,
+            },
+        })
+    }
+
+    /// Converts the schema of the parameters to the conventions of `dialect`.
+    pub fn to_dialect(mut self, dialect: SchemaDialect) -> Self {
+        self.function.parameters = dialect.convert(self.function.parameters);
+        self
+    }
+
+    /// The name of the tool.
+    pub fn name(&self) -> &str {
+        &self.function.name
+    }
+}
+
+/// Which tools the model may call, sent as `tool_choice`.
+///
+/// Ollama itself ignores it, but OpenAI-compatible servers honor it, and so does the
+/// [`Coordinator`](crate::coordinator::Coordinator).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum ToolChoice {
+    /// The model decides whether to call tools.
+    #[default]
+    Auto,
+    /// The model must not call tools.
+    None,
+    /// The model must call at least one tool.
+    Required,
+    /// The model must call the tool with this name.
+    Tool(String),
+}
+
+impl Serialize for ToolChoice {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        match self {
+            Self::Auto => serializer.serialize_str("auto"),
+            Self::None => serializer.serialize_str("none"),
+            Self::Required => serializer.serialize_str("required"),
+            Self::Tool(name) => {
+                serde_json::json!({ "type": "function", "function": { "name": name } })
+                    .serialize(serializer)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "utoipa")]
+impl utoipa::PartialSchema for ToolChoice {
+    fn schema() -> utoipa::openapi::RefOr<utoipa::openapi::schema::Schema> {
+        use utoipa::openapi::{ObjectBuilder, OneOfBuilder, Type};
+
+        OneOfBuilder::new()
+            .item(
+                ObjectBuilder::new()
+                    .schema_type(Type::String)
+                    .enum_values(Some(["auto", "none", "required"])),
+            )
+            .item(
+                ObjectBuilder::new()
+                    .property(
+                        "type",
+                        ObjectBuilder::new()
+                            .schema_type(Type::String)
+                            .enum_values(Some(["function"])),
+                    )
+                    .required("type")
+                    .property(
+                        "function",
+                        ObjectBuilder::new()
+                            .property("name", ObjectBuilder::new().schema_type(Type::String))
+                            .required("name"),
+                    )
+                    .required("function"),
+            )
+            .into()
+    }
 }
 
+#[cfg(feature = "utoipa")]
+impl utoipa::ToSchema for ToolChoice {}
+
 #[derive(Clone, Debug, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 enum ToolType {
     Function,
 }
 
 #[derive(Clone, Debug, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 struct ToolFunctionInfo {
-    name: &'static str,
-    description: &'static str,
-    parameters: RootSchema,
+    name: String,
+    description: String,
+    #[cfg_attr(feature = "utoipa", schema(value_type = Object))]
+    parameters: Schema,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct ToolCall {
+    /// Identifies the call, so that its result can be matched to it. Servers don't always send
+    /// one; the [`Coordinator`](crate::coordinator::Coordinator) assigns one to calls without.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
     pub function: ToolCallFunction,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct ToolCallFunction {
     pub name: String,
     // I don't love this (the Value)
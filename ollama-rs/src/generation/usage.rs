@@ -0,0 +1,70 @@
+use std::time::Duration;
+
+/// Token counts and timings of a completed generation or chat request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Usage {
+    /// Time spent on the whole request.
+    pub total_duration: Duration,
+    /// Time spent loading the model.
+    pub load_duration: Duration,
+    /// Number of tokens in the prompt.
+    pub prompt_eval_count: u64,
+    /// Time spent evaluating the prompt.
+    pub prompt_eval_duration: Duration,
+    /// Number of tokens in the response.
+    pub eval_count: u64,
+    /// Time spent generating the response.
+    pub eval_duration: Duration,
+}
+
+impl Usage {
+    pub(crate) fn from_nanos(
+        total_duration: u64,
+        load_duration: u64,
+        prompt_eval_count: u64,
+        prompt_eval_duration: u64,
+        eval_count: u64,
+        eval_duration: u64,
+    ) -> Self {
+        Self {
+            total_duration: Duration::from_nanos(total_duration),
+            load_duration: Duration::from_nanos(load_duration),
+            prompt_eval_count,
+            prompt_eval_duration: Duration::from_nanos(prompt_eval_duration),
+            eval_count,
+            eval_duration: Duration::from_nanos(eval_duration),
+        }
+    }
+
+    /// Number of tokens in the prompt and the response.
+    pub fn total_tokens(&self) -> u64 {
+        self.prompt_eval_count + self.eval_count
+    }
+
+    /// Speed of generation of the response, or `None` if no time was spent generating it.
+    pub fn tokens_per_second(&self) -> Option<f64> {
+        rate(self.eval_count, self.eval_duration)
+    }
+
+    /// Speed of evaluation of the prompt, or `None` if no time was spent evaluating it,
+    /// e.g. because it was cached.
+    pub fn prompt_tokens_per_second(&self) -> Option<f64> {
+        rate(self.prompt_eval_count, self.prompt_eval_duration)
+    }
+}
+
+/// Sums the token counts and timings of several requests.
+impl std::ops::AddAssign for Usage {
+    fn add_assign(&mut self, other: Self) {
+        self.total_duration += other.total_duration;
+        self.load_duration += other.load_duration;
+        self.prompt_eval_count += other.prompt_eval_count;
+        self.prompt_eval_duration += other.prompt_eval_duration;
+        self.eval_count += other.eval_count;
+        self.eval_duration += other.eval_duration;
+    }
+}
+
+fn rate(tokens: u64, duration: Duration) -> Option<f64> {
+    (!duration.is_zero()).then(|| tokens as f64 / duration.as_secs_f64())
+}
@@ -0,0 +1,245 @@
+//! Validation of responses, re-prompting the model with the violations until its response
+//! passes or the retries run out.
+//!
+//! ```no_run
+//! # async fn run() -> ollama_rs::error::Result<()> {
+//! use ollama_rs::{
+//!     generation::completion::request::GenerationRequest, guardrails::Guardrails, Ollama,
+//! };
+//!
+//! let guardrails = Guardrails::new()
+//!     .validator(|response: &str| {
+//!         if response.split_whitespace().count() <= 50 {
+//!             Ok(())
+//!         } else {
+//!             Err("The response must be at most 50 words long".to_string())
+//!         }
+//!     })
+//!     .max_retries(2);
+//!
+//! let request = GenerationRequest::new("llama3.2".into(), "Summarize the plot of Hamlet.");
+//! let res = guardrails.generate(&Ollama::default(), request).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{fmt, sync::Arc};
+
+use crate::{
+    error::{OllamaError, Result},
+    generation::{
+        chat::{request::ChatMessageRequest, ChatMessage, ChatMessageResponse},
+        completion::{request::GenerationRequest, GenerationResponse},
+    },
+    Ollama,
+};
+
+/// A check of a response. Returns a description of the violation, given to the model to fix
+/// it, if the response doesn't pass.
+pub trait Validator: Send + Sync {
+    fn validate(&self, response: &str) -> std::result::Result<(), String>;
+}
+
+impl<F> Validator for F
+where
+    F: Fn(&str) -> std::result::Result<(), String> + Send + Sync,
+{
+    fn validate(&self, response: &str) -> std::result::Result<(), String> {
+        self(response)
+    }
+}
+
+/// A [`Validator`] requiring responses to match a regular expression.
+#[cfg_attr(docsrs, doc(cfg(feature = "regex")))]
+#[cfg(feature = "regex")]
+#[derive(Debug, Clone)]
+pub struct RegexValidator {
+    pattern: regex::Regex,
+    violation: String,
+}
+
+#[cfg(feature = "regex")]
+impl RegexValidator {
+    pub fn new(pattern: regex::Regex) -> Self {
+        let violation = format!("The response must match the regular expression /{pattern}/");
+        Self { pattern, violation }
+    }
+
+    /// The description of the violation given to the model, e.g. to describe the pattern in
+    /// plain words. (Default: "The response must match the regular expression /.../")
+    pub fn violation(mut self, violation: impl Into<String>) -> Self {
+        self.violation = violation.into();
+        self
+    }
+}
+
+#[cfg(feature = "regex")]
+impl Validator for RegexValidator {
+    fn validate(&self, response: &str) -> std::result::Result<(), String> {
+        if self.pattern.is_match(response) {
+            Ok(())
+        } else {
+            Err(self.violation.clone())
+        }
+    }
+}
+
+/// A [`Validator`] requiring responses to be JSON conforming to a schema.
+#[cfg_attr(docsrs, doc(cfg(feature = "jsonschema")))]
+#[cfg(feature = "jsonschema")]
+#[derive(Debug, Clone)]
+pub struct JsonSchemaValidator {
+    structure: crate::generation::parameters::JsonStructure,
+}
+
+#[cfg(feature = "jsonschema")]
+impl JsonSchemaValidator {
+    pub fn new(structure: crate::generation::parameters::JsonStructure) -> Self {
+        Self { structure }
+    }
+
+    /// Validates against the schema of `T`.
+    pub fn of<T: schemars::JsonSchema>() -> Self {
+        Self::new(crate::generation::parameters::JsonStructure::new::<T>())
+    }
+}
+
+#[cfg(feature = "jsonschema")]
+impl Validator for JsonSchemaValidator {
+    fn validate(&self, response: &str) -> std::result::Result<(), String> {
+        let value = serde_json::from_str(response)
+            .map_err(|e| format!("The response must be valid JSON: {e}"))?;
+        self.structure.validate(&value).map_err(|e| e.to_string())
+    }
+}
+
+/// Validators checking the final response of generation and chat requests.
+///
+/// When the response fails a validator, the model is asked again, along with its response and
+/// the violations. Once the retries run out, the request fails with
+/// [`OllamaError::GuardrailViolation`].
+#[derive(Clone)]
+pub struct Guardrails {
+    validators: Vec<Arc<dyn Validator>>,
+    max_retries: u32,
+}
+
+impl fmt::Debug for Guardrails {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Guardrails")
+            .field("validators", &self.validators.len())
+            .field("max_retries", &self.max_retries)
+            .finish()
+    }
+}
+
+impl Default for Guardrails {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Guardrails {
+    pub fn new() -> Self {
+        Self {
+            validators: Vec::new(),
+            max_retries: 2,
+        }
+    }
+
+    pub fn validator(mut self, validator: impl Validator + 'static) -> Self {
+        self.validators.push(Arc::new(validator));
+        self
+    }
+
+    /// How many times the model is asked again when its response fails. (Default: 2)
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// The violations of `response`, empty if it passes every validator.
+    pub fn check(&self, response: &str) -> Vec<String> {
+        self.validators
+            .iter()
+            .filter_map(|validator| validator.validate(response).err())
+            .collect()
+    }
+
+    /// Generates a response passing the validators.
+    pub async fn generate(
+        &self,
+        ollama: &Ollama,
+        request: GenerationRequest<'_>,
+    ) -> Result<GenerationResponse> {
+        let mut attempt = 0;
+        let mut retry = request.clone();
+        loop {
+            let res = ollama.generate(retry.clone()).await?;
+            let violations = self.check(&res.response);
+            if violations.is_empty() {
+                return Ok(res);
+            }
+            self.give_up(attempt, &violations, &res.response)?;
+            attempt += 1;
+
+            retry.prompt = format!(
+                "{}\n\nA previous response was:\n{}\n\n{}",
+                request.prompt,
+                res.response,
+                correction(&violations)
+            )
+            .into();
+        }
+    }
+
+    /// Sends the messages, returning a response passing the validators. The rejected responses
+    /// and the corrections are only part of the retries, not of the returned response.
+    pub async fn send_chat_messages(
+        &self,
+        ollama: &Ollama,
+        request: ChatMessageRequest,
+    ) -> Result<ChatMessageResponse> {
+        let mut attempt = 0;
+        let mut retry = request;
+        loop {
+            let res = ollama.send_chat_messages(retry.clone()).await?;
+            let violations = self.check(&res.message.content);
+            if violations.is_empty() {
+                return Ok(res);
+            }
+            self.give_up(attempt, &violations, &res.message.content)?;
+            attempt += 1;
+
+            retry.messages.push(res.message);
+            retry
+                .messages
+                .push(ChatMessage::user(correction(&violations)));
+        }
+    }
+
+    fn give_up(&self, attempt: u32, violations: &[String], response: &str) -> Result<()> {
+        if attempt < self.max_retries {
+            log::debug!("Retrying a response failing its guardrails: {violations:?}");
+            return Ok(());
+        }
+
+        Err(OllamaError::GuardrailViolation {
+            violations: violations.to_vec(),
+            response: response.to_string(),
+            attempts: attempt + 1,
+        })
+    }
+}
+
+/// The message asking the model to fix its response.
+fn correction(violations: &[String]) -> String {
+    let mut message = String::from("That response was rejected because:\n");
+    for violation in violations {
+        message.push_str("- ");
+        message.push_str(violation);
+        message.push('\n');
+    }
+    message.push_str("Answer again, fixing these issues.");
+    message
+}
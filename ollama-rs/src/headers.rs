@@ -34,8 +34,8 @@ impl Ollama {
     /// If `None` is provided, the headers will be reset to an empty `HeaderMap`.
     pub fn set_headers(&mut self, headers: Option<HeaderMap>) {
         match headers {
-            Some(h) => self.request_headers = h,
-            None => self.request_headers = HeaderMap::new(),
+            Some(h) => self.config_mut().request_headers = h,
+            None => self.config_mut().request_headers = HeaderMap::new(),
         }
     }
 }
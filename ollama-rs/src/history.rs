@@ -1,6 +1,15 @@
-use std::borrow::Cow;
+use std::{borrow::Cow, collections::HashMap, sync::Mutex};
 
-use crate::generation::chat::ChatMessage;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::OllamaError,
+    generation::{
+        chat::ChatMessage,
+        embeddings::{cosine_similarity, request::GenerateEmbeddingsRequest},
+    },
+    Ollama, Timestamp,
+};
 
 /// A trait for managing chat message history.
 ///
@@ -18,6 +27,55 @@ pub trait ChatHistory {
     /// The messages are returned as a `Cow` (Clone on Write) to allow for
     /// efficient borrowing or cloning as needed.
     fn messages(&self) -> Cow<'_, [ChatMessage]>;
+
+    /// Removes the message with the id `id`, returning whether it was found.
+    ///
+    /// Histories keeping their messages elsewhere, such as in a database, override it to
+    /// delete the message there too. Histories without ids, like `Vec<ChatMessage>`, find none.
+    fn remove_message(&mut self, id: &str) -> bool {
+        let _ = id;
+        false
+    }
+
+    /// Replaces the content of the message with the id `id` with `placeholder`, and drops its
+    /// images and the arguments of its tool calls, returning whether it was found. The message
+    /// keeps its place, so the conversation still makes sense to the model.
+    ///
+    /// Like [`ChatHistory::remove_message`], persistent histories override it to redact their
+    /// copy of the message.
+    fn redact_message(&mut self, id: &str, placeholder: &str) -> bool {
+        let _ = (id, placeholder);
+        false
+    }
+
+    /// Finds the messages containing words of `query`, regardless of case, best matches
+    /// first. The score of a message is the share of the words of the query it contains, and
+    /// messages of the same score are ordered from the most recent.
+    ///
+    /// For matches by meaning rather than by words, see [`SemanticHistorySearch`].
+    fn search(&self, query: &str) -> Vec<HistoryMatch> {
+        let terms = words(query);
+        if terms.is_empty() {
+            return Vec::new();
+        }
+
+        let mut matches: Vec<HistoryMatch> = self
+            .messages()
+            .iter()
+            .enumerate()
+            .filter_map(|(index, message)| {
+                let content = words(&message.content);
+                let found = terms.iter().filter(|term| content.contains(term)).count();
+                (found > 0).then(|| HistoryMatch {
+                    index,
+                    message: message.clone(),
+                    score: found as f32 / terms.len() as f32,
+                })
+            })
+            .collect();
+        sort_matches(&mut matches);
+        matches
+    }
 }
 
 impl ChatHistory for Vec<ChatMessage> {
@@ -38,3 +96,348 @@ impl ChatHistory for Vec<ChatMessage> {
         Cow::Borrowed(self)
     }
 }
+
+/// A message of a [`MessageHistory`], along with its metadata.
+///
+/// Serialized as the message with the `id`, `created_at` and `tags` fields added.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct HistoryEntry {
+    /// A stable id, like `msg_8f3a...`, to refer to the message.
+    pub id: String,
+    #[cfg_attr(feature = "utoipa", schema(value_type = String, format = DateTime))]
+    pub created_at: Timestamp,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Whether the content of the message was redacted.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub redacted: bool,
+    #[serde(flatten)]
+    pub message: ChatMessage,
+}
+
+/// The placeholder replacing the content of the messages redacted by
+/// [`MessageHistory::redact`].
+pub const REDACTED: &str = "[redacted]";
+
+impl HistoryEntry {
+    /// Gives the message a new id and the current time.
+    pub fn new(message: ChatMessage) -> Self {
+        Self {
+            id: crate::unique_id("msg"),
+            created_at: crate::Timestamp::now(),
+            tags: Vec::new(),
+            redacted: false,
+            message,
+        }
+    }
+
+    /// Adds a tag, unless the entry already has it.
+    pub fn tag(mut self, tag: impl Into<String>) -> Self {
+        self.add_tag(tag);
+        self
+    }
+
+    pub fn add_tag(&mut self, tag: impl Into<String>) {
+        let tag = tag.into();
+        if !self.has_tag(&tag) {
+            self.tags.push(tag);
+        }
+    }
+
+    pub fn remove_tag(&mut self, tag: &str) {
+        self.tags.retain(|t| t != tag);
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+
+    fn redact(&mut self, placeholder: &str) {
+        self.message.content = placeholder.to_string();
+        self.message.images = None;
+        for call in &mut self.message.tool_calls {
+            call.function.arguments = serde_json::Value::Object(Default::default());
+        }
+        self.redacted = true;
+    }
+}
+
+/// A history keeping an id, a creation time and tags along with each message, so that
+/// applications can refer to, edit and annotate specific messages.
+///
+/// Only the messages are sent to the model. The history serializes as a list of
+/// [`HistoryEntry`], to be saved along with the metadata.
+///
+/// ```
+/// use ollama_rs::{
+///     generation::chat::ChatMessage,
+///     history::{ChatHistory, MessageHistory},
+/// };
+///
+/// let mut history = MessageHistory::new();
+/// let id = history.push_message(ChatMessage::user("I moved to Lisbon.".to_string()));
+/// history.add_tag(&id, "profile");
+///
+/// assert_eq!(history.tagged("profile").count(), 1);
+/// assert_eq!(history.messages().len(), 1);
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(transparent)]
+pub struct MessageHistory {
+    entries: Vec<HistoryEntry>,
+}
+
+impl MessageHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a message, returning its id.
+    pub fn push_message(&mut self, message: ChatMessage) -> String {
+        self.push_entry(HistoryEntry::new(message))
+    }
+
+    /// Adds a message with its metadata, e.g. loaded from another store, returning its id.
+    pub fn push_entry(&mut self, entry: HistoryEntry) -> String {
+        let id = entry.id.clone();
+        self.entries.push(entry);
+        id
+    }
+
+    /// The messages with their metadata, from the oldest.
+    pub fn entries(&self) -> &[HistoryEntry] {
+        &self.entries
+    }
+
+    pub fn get(&self, id: &str) -> Option<&HistoryEntry> {
+        self.entries.iter().find(|entry| entry.id == id)
+    }
+
+    pub fn get_mut(&mut self, id: &str) -> Option<&mut HistoryEntry> {
+        self.entries.iter_mut().find(|entry| entry.id == id)
+    }
+
+    /// Replaces the content of the message `id`, returning whether it was found.
+    pub fn edit(&mut self, id: &str, content: impl Into<String>) -> bool {
+        self.get_mut(id)
+            .map(|entry| entry.message.content = content.into())
+            .is_some()
+    }
+
+    /// Tags the message `id`, returning whether it was found.
+    pub fn add_tag(&mut self, id: &str, tag: impl Into<String>) -> bool {
+        self.get_mut(id).map(|entry| entry.add_tag(tag)).is_some()
+    }
+
+    /// Removes a tag of the message `id`, returning whether it was found.
+    pub fn remove_tag(&mut self, id: &str, tag: &str) -> bool {
+        self.get_mut(id)
+            .map(|entry| entry.remove_tag(tag))
+            .is_some()
+    }
+
+    /// Removes the message `id`, returning it.
+    ///
+    /// A [`SemanticHistorySearch`] keeps the embeddings of the messages it searched, see
+    /// [`SemanticHistorySearch::forget`].
+    pub fn remove(&mut self, id: &str) -> Option<HistoryEntry> {
+        let index = self.entries.iter().position(|entry| entry.id == id)?;
+        Some(self.entries.remove(index))
+    }
+
+    /// Replaces the content of the message `id` with [`REDACTED`], see
+    /// [`ChatHistory::redact_message`]. Returns whether the message was found.
+    pub fn redact(&mut self, id: &str) -> bool {
+        self.redact_message(id, REDACTED)
+    }
+
+    /// The messages tagged `tag`, from the oldest.
+    pub fn tagged<'a>(&'a self, tag: &'a str) -> impl Iterator<Item = &'a HistoryEntry> + 'a {
+        self.entries.iter().filter(move |entry| entry.has_tag(tag))
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl ChatHistory for MessageHistory {
+    fn push(&mut self, message: ChatMessage) {
+        self.push_message(message);
+    }
+
+    fn remove_message(&mut self, id: &str) -> bool {
+        self.remove(id).is_some()
+    }
+
+    fn redact_message(&mut self, id: &str, placeholder: &str) -> bool {
+        self.get_mut(id)
+            .map(|entry| entry.redact(placeholder))
+            .is_some()
+    }
+
+    fn messages(&self) -> Cow<'_, [ChatMessage]> {
+        Cow::Owned(
+            self.entries
+                .iter()
+                .map(|entry| entry.message.clone())
+                .collect(),
+        )
+    }
+}
+
+impl FromIterator<ChatMessage> for MessageHistory {
+    fn from_iter<I: IntoIterator<Item = ChatMessage>>(messages: I) -> Self {
+        Self {
+            entries: messages.into_iter().map(HistoryEntry::new).collect(),
+        }
+    }
+}
+
+/// A message of a history matching a search.
+#[derive(Debug, Clone)]
+pub struct HistoryMatch {
+    /// The position of the message in the history.
+    pub index: usize,
+    pub message: ChatMessage,
+    /// How well the message matches, from 0 to 1.
+    pub score: f32,
+}
+
+/// Searches a history by meaning, comparing the embeddings of the query and of the messages,
+/// so that "where does the user live?" finds "I moved to Lisbon last year".
+///
+/// The embeddings of the messages are kept, so a message is only embedded by the first search
+/// it is part of.
+///
+/// ```no_run
+/// # async fn run(ollama: ollama_rs::Ollama, history: Vec<ollama_rs::generation::chat::ChatMessage>) -> ollama_rs::error::Result<()> {
+/// use ollama_rs::history::SemanticHistorySearch;
+///
+/// let search = SemanticHistorySearch::new(ollama, "nomic-embed-text").limit(3);
+/// for found in search.search(&history, "where does the user live?").await? {
+///     println!("{:.2} {}", found.score, found.message.content);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct SemanticHistorySearch {
+    ollama: Ollama,
+    embedding_model: String,
+    limit: usize,
+    threshold: f32,
+    embeddings: Mutex<HashMap<String, Vec<f32>>>,
+}
+
+impl SemanticHistorySearch {
+    pub fn new(ollama: Ollama, embedding_model: impl Into<String>) -> Self {
+        Self {
+            ollama,
+            embedding_model: embedding_model.into(),
+            limit: 5,
+            threshold: 0.5,
+            embeddings: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The maximum number of messages found. (Default: 5)
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// The minimum cosine similarity between the query and a message found. (Default: 0.5)
+    pub fn threshold(mut self, threshold: f32) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Finds the messages of `history` closest in meaning to `query`, best matches first.
+    /// Messages without text, such as tool calls, are skipped.
+    pub async fn search(
+        &self,
+        history: &impl ChatHistory,
+        query: &str,
+    ) -> crate::error::Result<Vec<HistoryMatch>> {
+        let messages = history.messages();
+
+        let mut missing: Vec<String> = {
+            let embeddings = self.embeddings.lock().unwrap();
+            messages
+                .iter()
+                .map(|message| &message.content)
+                .filter(|content| !content.trim().is_empty() && !embeddings.contains_key(*content))
+                .cloned()
+                .collect()
+        };
+        missing.sort();
+        missing.dedup();
+
+        let mut inputs = vec![query.to_string()];
+        inputs.extend(missing.iter().cloned());
+        let request = GenerateEmbeddingsRequest::new(self.embedding_model.clone(), inputs.into());
+        let mut embedded = self.ollama.generate_embeddings(request).await?.embeddings;
+        if embedded.len() != missing.len() + 1 {
+            return Err(OllamaError::Other(format!(
+                "Expected {} embeddings, got {}",
+                missing.len() + 1,
+                embedded.len()
+            )));
+        }
+        let query = embedded.remove(0);
+
+        let mut embeddings = self.embeddings.lock().unwrap();
+        embeddings.extend(missing.into_iter().zip(embedded));
+
+        let mut matches: Vec<HistoryMatch> = messages
+            .iter()
+            .enumerate()
+            .filter_map(|(index, message)| {
+                let score = cosine_similarity(&query, embeddings.get(&message.content)?);
+                (score >= self.threshold).then(|| HistoryMatch {
+                    index,
+                    message: message.clone(),
+                    score,
+                })
+            })
+            .collect();
+        sort_matches(&mut matches);
+        matches.truncate(self.limit);
+        Ok(matches)
+    }
+
+    /// Forgets the embedding of a message content, e.g. of a message removed or redacted from
+    /// a history.
+    pub fn forget(&self, content: &str) {
+        self.embeddings.lock().unwrap().remove(content);
+    }
+
+    /// Forgets the embeddings of the messages searched so far.
+    pub fn clear(&self) {
+        self.embeddings.lock().unwrap().clear();
+    }
+}
+
+/// The lowercase words of `text`, without duplicates.
+fn words(text: &str) -> Vec<String> {
+    let mut words: Vec<String> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    words.sort();
+    words.dedup();
+    words
+}
+
+/// Sorts by decreasing score, then from the most recent message.
+fn sort_matches(matches: &mut [HistoryMatch]) {
+    matches.sort_by(|a, b| b.score.total_cmp(&a.score).then(b.index.cmp(&a.index)));
+}
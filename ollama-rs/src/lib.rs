@@ -1,18 +1,66 @@
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
+use std::sync::Arc;
+
 use url::Url;
 
+use crate::{generation::parameters::KeepAlive, models::ModelOptions};
+
 #[cfg(feature = "macros")]
 pub use ollama_rs_macros::function;
 
+pub use builder::OllamaBuilder;
+pub use circuit_breaker::{CircuitBreaker, CircuitState};
+pub use retry::RetryPolicy;
+pub use timestamp::Timestamp;
+
+#[cfg_attr(docsrs, doc(cfg(feature = "audit")))]
+#[cfg(feature = "audit")]
+pub mod audit;
+mod builder;
+pub mod cache;
+mod circuit_breaker;
+pub mod concurrency;
 pub mod coordinator;
+mod dedup;
+mod env;
 pub mod error;
+pub mod eval;
 pub mod generation;
+pub mod guardrails;
 #[cfg_attr(docsrs, doc(cfg(feature = "headers")))]
 #[cfg(feature = "headers")]
 pub mod headers;
 pub mod history;
+pub mod logging;
 pub mod models;
+pub mod moderation;
+#[cfg(feature = "stream")]
+mod ndjson;
+#[cfg(feature = "openai-compat")]
+mod openai;
+#[cfg(feature = "openai-interop")]
+mod openai_interop;
+#[cfg_attr(docsrs, doc(cfg(feature = "utoipa")))]
+#[cfg(feature = "utoipa")]
+pub mod openapi;
+pub mod persona;
+pub mod profile;
+pub mod request_id;
+pub mod request_profile;
+mod retry;
+pub mod router;
+mod runtime;
+pub mod scheduler;
+mod slots;
+#[cfg_attr(docsrs, doc(cfg(feature = "test-utils")))]
+#[cfg(feature = "test-utils")]
+pub mod test_utils;
+mod timestamp;
+pub mod version;
+#[cfg_attr(docsrs, doc(cfg(any(feature = "axum", feature = "actix"))))]
+#[cfg(any(feature = "axum", feature = "actix"))]
+pub mod web;
 
 /// A trait to try to convert some type into a [`Url`].
 ///
@@ -80,12 +128,72 @@ impl IntoUrlSealed for String {
     }
 }
 
+/// A client for the Ollama API.
+///
+/// Use [`Ollama::builder`] to configure authentication, timeouts, retries or default options.
+///
+/// Cloning a client is cheap: clones share the connection pool and the configuration, so a
+/// client can be stored in the state of a web server and used concurrently as-is. Changing
+/// the configuration of a clone, e.g. with [`Ollama::set_api_key`], doesn't affect the others.
 #[derive(Debug, Clone)]
 pub struct Ollama {
+    pub(crate) config: Arc<Config>,
+}
+
+static_assertions::assert_impl_all!(Ollama: Send, Sync);
+
+/// The configuration of an [`Ollama`] client, shared by its clones.
+#[derive(Debug, Clone)]
+pub(crate) struct Config {
     pub(crate) url: Url,
     pub(crate) reqwest_client: reqwest::Client,
     #[cfg(feature = "headers")]
     pub(crate) request_headers: reqwest::header::HeaderMap,
+    pub(crate) capture_raw_responses: bool,
+    pub(crate) response_compression: bool,
+    pub(crate) api_key: Option<String>,
+    pub(crate) retry_policy: RetryPolicy,
+    pub(crate) circuit_breaker: Option<CircuitBreaker>,
+    pub(crate) default_options: Option<ModelOptions>,
+    pub(crate) default_keep_alive: Option<KeepAlive>,
+    pub(crate) history_limit: Option<usize>,
+    pub(crate) deterministic_seed: Option<i32>,
+    pub(crate) default_model: Option<String>,
+    pub(crate) in_flight: Option<Arc<dedup::InFlight>>,
+    pub(crate) content_logging: logging::ContentLogging,
+    pub(crate) content_filters: moderation::ContentFilters,
+    pub(crate) version_check: bool,
+    pub(crate) version_cache: Arc<version::VersionCache>,
+    pub(crate) embeddings_endpoint: generation::embeddings::EmbeddingsEndpoint,
+    /// Whether [`EmbeddingsEndpoint::Auto`](generation::embeddings::EmbeddingsEndpoint::Auto)
+    /// found that the server only has `/api/embeddings`.
+    pub(crate) legacy_embeddings: Arc<std::sync::atomic::AtomicBool>,
+    #[cfg(feature = "openai-compat")]
+    pub(crate) openai_compatible: bool,
+    #[cfg(feature = "audit")]
+    pub(crate) audit_log: Option<audit::AuditLog>,
+    pub(crate) concurrency: Option<Arc<concurrency::Limiter>>,
+}
+
+/// The raw HTTP response a parsed response was built from.
+///
+/// Only captured when [`Ollama::set_capture_raw_responses`] is enabled, this is meant to help
+/// diagnose mismatches between the types of this crate and what the server actually sent.
+#[derive(Debug, Clone)]
+pub struct RawResponse {
+    /// The JSON body of the response. For streamed responses, only the line of this chunk.
+    pub body: String,
+    /// The headers of the HTTP response.
+    pub headers: reqwest::header::HeaderMap,
+}
+
+impl RawResponse {
+    pub(crate) fn new(body: &[u8], headers: reqwest::header::HeaderMap) -> Self {
+        Self {
+            body: String::from_utf8_lossy(body).into_owned(),
+            headers,
+        }
+    }
 }
 
 /// The main struct representing an Ollama client.
@@ -138,19 +246,53 @@ impl Ollama {
         let mut url: Url = host.into_url().unwrap();
         url.set_port(Some(port)).unwrap();
 
-        Self {
+        Self::from_config(Config {
             url,
             reqwest_client,
             #[cfg(feature = "headers")]
             request_headers: reqwest::header::HeaderMap::new(),
+            capture_raw_responses: false,
+            response_compression: true,
+            api_key: None,
+            retry_policy: RetryPolicy::none(),
+            circuit_breaker: None,
+            default_options: None,
+            default_keep_alive: None,
+            history_limit: None,
+            deterministic_seed: None,
+            default_model: None,
+            in_flight: None,
+            content_logging: logging::ContentLogging::Omit,
+            content_filters: moderation::ContentFilters::default(),
+            version_check: false,
+            version_cache: Arc::default(),
+            embeddings_endpoint: Default::default(),
+            legacy_embeddings: Arc::default(),
+            #[cfg(feature = "openai-compat")]
+            openai_compatible: false,
+            #[cfg(feature = "audit")]
+            audit_log: None,
+            concurrency: None,
+        })
+    }
+
+    pub(crate) fn from_config(config: Config) -> Self {
+        Self {
+            config: Arc::new(config),
         }
     }
 
+    /// The configuration of this client, copied first if it is shared with clones.
+    fn config_mut(&mut self) -> &mut Config {
+        Arc::make_mut(&mut self.config)
+    }
+
     /// Attempts to create a new `Ollama` instance from a URL.
     ///
     /// # Arguments
     ///
-    /// * `url` - The URL of the Ollama service.
+    /// * `url` - The URL of the Ollama service. It may include a path prefix, such as
+    ///   `https://gw.example.com/ollama`, when Ollama is served behind a reverse proxy.
     ///
     /// # Returns
     ///
@@ -163,10 +305,9 @@ impl Ollama {
     /// Create new instance from a [`Url`].
     #[inline]
     pub fn from_url(url: Url) -> Self {
-        Self {
-            url,
-            ..Default::default()
-        }
+        let mut ollama = Self::default();
+        ollama.config_mut().url = url;
+        ollama
     }
 
     /// Returns the URI of the Ollama service as a `String`.
@@ -176,12 +317,12 @@ impl Ollama {
     /// Panics if the URL does not have a host.
     #[inline]
     pub fn uri(&self) -> String {
-        self.url.host().unwrap().to_string()
+        self.config.url.host().unwrap().to_string()
     }
 
     /// Returns a reference to the URL of the Ollama service.
     pub fn url(&self) -> &Url {
-        &self.url
+        &self.config.url
     }
 
     /// Returns the URL of the Ollama service as a `&str`.
@@ -199,10 +340,305 @@ impl Ollama {
     /// ```
     #[inline]
     pub fn url_str(&self) -> &str {
-        self.url.as_str()
+        self.config.url.as_str()
+    }
+
+    /// Keeps the raw body and headers of responses, available in the `raw` field of
+    /// generation, chat and embeddings responses. Disabled by default.
+    pub fn set_capture_raw_responses(&mut self, capture: bool) {
+        self.config_mut().capture_raw_responses = capture;
+    }
+
+    /// Lets the server compress responses of non-streaming endpoints, which mostly benefits
+    /// large responses such as model details, model lists and batch embeddings.
+    /// Enabled by default, but only effective with the `gzip` or `zstd` features.
+    ///
+    /// Streamed responses are never compressed, so that chunks aren't held back.
+    pub fn set_response_compression(&mut self, enabled: bool) {
+        self.config_mut().response_compression = enabled;
+    }
+
+    /// Sends chat requests to the OpenAI-compatible `/v1/chat/completions` endpoint instead of
+    /// `/api/chat`, so that the client can also be used with gateways implementing the OpenAI
+    /// API. Requests and responses keep the same types; options without an OpenAI equivalent
+    /// are not sent, and responses only report token counts, not durations.
+    /// Disabled by default.
+    #[cfg_attr(docsrs, doc(cfg(feature = "openai-compat")))]
+    #[cfg(feature = "openai-compat")]
+    pub fn set_openai_compatible(&mut self, enabled: bool) {
+        self.config_mut().openai_compatible = enabled;
+    }
+
+    /// Appends a record of every request to `audit_log`, see [`audit`]. `None` stops
+    /// auditing. Disabled by default.
+    #[cfg_attr(docsrs, doc(cfg(feature = "audit")))]
+    #[cfg(feature = "audit")]
+    pub fn set_audit_log(&mut self, audit_log: Option<audit::AuditLog>) {
+        self.config_mut().audit_log = audit_log;
+    }
+
+    /// Queues the requests over `limits` until earlier requests are over, see
+    /// [`concurrency`]. `None` removes the limits. Requests already queued keep waiting for
+    /// the previous limits. Unlimited by default.
+    pub fn set_concurrency_limits(&mut self, limits: Option<concurrency::ConcurrencyLimits>) {
+        self.config_mut().concurrency =
+            limits.map(|limits| Arc::new(concurrency::Limiter::limited(limits)));
+    }
+
+    /// Returns a builder to configure a new client.
+    pub fn builder() -> OllamaBuilder {
+        OllamaBuilder::new()
+    }
+
+    /// The circuit breaker of this client, to inspect or reset its state, if configured.
+    pub fn circuit_breaker(&self) -> Option<&CircuitBreaker> {
+        self.config.circuit_breaker.as_ref()
+    }
+
+    /// The model to use when the application doesn't pick one, if configured.
+    /// The client doesn't use it by itself, as every request names its model.
+    pub fn default_model(&self) -> Option<&str> {
+        self.config.default_model.as_deref()
+    }
+
+    pub(crate) fn with_default_options(
+        &self,
+        options: Option<ModelOptions>,
+    ) -> Option<ModelOptions> {
+        let options = match (options, &self.config.default_options) {
+            (Some(options), Some(defaults)) => Some(options.or_defaults(defaults)),
+            (options, defaults) => options.or_else(|| defaults.clone()),
+        };
+
+        match self.config.deterministic_seed {
+            Some(seed) => {
+                log::debug!("deterministic mode: sending seed {seed} and temperature 0");
+                Some(options.unwrap_or_default().seed(seed).temperature(0.0))
+            }
+            None => options,
+        }
+    }
+
+    /// Makes responses reproducible, e.g. for integration tests and snapshots: every
+    /// generation, chat and embeddings request is sent with this seed and a temperature of 0,
+    /// whatever its options. `None` disables it, which is the default.
+    pub fn set_deterministic(&mut self, seed: Option<i32>) {
+        self.config_mut().deterministic_seed = seed;
+    }
+
+    /// The seed sent with every request when [`Ollama::set_deterministic`] is enabled, so
+    /// that it can be recorded along with the responses.
+    pub fn deterministic_seed(&self) -> Option<i32> {
+        self.config.deterministic_seed
+    }
+
+    /// Sets how long models stay loaded after generation, chat and embeddings requests that
+    /// don't specify it. `None` leaves it to the server, which defaults to 5 minutes.
+    pub fn set_default_keep_alive(&mut self, keep_alive: Option<KeepAlive>) {
+        self.config_mut().default_keep_alive = keep_alive;
+    }
+
+    /// Limits the messages of the history sent by [`Ollama::send_chat_messages_with_history`]
+    /// and its streaming variant to the last `limit` ones, along with the system messages the
+    /// history starts with. The messages of the request itself are always sent, and tool
+    /// results are sent with the assistant message that called the tools. The history itself
+    /// keeps every message. `None` sends the whole history, which is the default.
+    pub fn set_history_limit(&mut self, limit: Option<usize>) {
+        self.config_mut().history_limit = limit;
+    }
+
+    /// The messages of `history` sent with a request, see [`Ollama::set_history_limit`]. The
+    /// last `current` messages are those of the request.
+    pub(crate) fn history_window(
+        &self,
+        history: &[generation::chat::ChatMessage],
+        current: usize,
+    ) -> Vec<generation::chat::ChatMessage> {
+        use generation::chat::MessageRole;
+
+        let Some(limit) = self.config.history_limit else {
+            return history.to_vec();
+        };
+
+        let system = history
+            .iter()
+            .take_while(|m| m.role == MessageRole::System)
+            .count();
+        let mut start = history.len().saturating_sub(limit.max(current)).max(system);
+        // Tool results can't be sent without the call they answer
+        while start > system && start < history.len() && history[start].role == MessageRole::Tool {
+            start -= 1;
+        }
+        history[..system]
+            .iter()
+            .chain(&history[start..])
+            .cloned()
+            .collect()
+    }
+
+    /// Coalesces identical generation and chat requests sent concurrently by this client
+    /// and its clones: while a request is being sent, an identical request waits for its
+    /// response instead of being sent again. Streamed requests are never coalesced, nor are
+    /// requests of clones with another API key or other headers.
+    ///
+    /// This avoids stampedes when many callers ask the same thing at the same time, e.g. the
+    /// users of a web backend. If the first request fails, the others are sent anyway.
+    pub fn set_deduplicate_requests(&mut self, enabled: bool) {
+        self.config_mut().in_flight = enabled.then(Default::default);
+    }
+
+    /// Sets how prompts and responses appear in the logs, see [`logging`].
+    pub fn set_content_logging(&mut self, content_logging: logging::ContentLogging) {
+        self.config_mut().content_logging = content_logging;
+    }
+
+    /// Adds a filter of the prompts and responses of generation and chat requests, run after
+    /// the filters already added, see [`moderation`].
+    pub fn add_content_filter(&mut self, filter: impl moderation::ContentFilter + 'static) {
+        self.config_mut().content_filters.push(Arc::new(filter));
+    }
+
+    /// Checks the version of the server before sending requests using recent features, such
+    /// as tools or structured outputs, failing with [`error::OllamaError::Unsupported`] if the
+    /// server is too old for them. The version is fetched once. Disabled by default.
+    pub fn set_version_check(&mut self, enabled: bool) {
+        self.config_mut().version_check = enabled;
+    }
+
+    /// Sets the endpoint embeddings are generated with. By default, `/api/embed` is used, and
+    /// `/api/embeddings` if the server is too old to have it.
+    pub fn set_embeddings_endpoint(
+        &mut self,
+        endpoint: generation::embeddings::EmbeddingsEndpoint,
+    ) {
+        self.config_mut().embeddings_endpoint = endpoint;
+    }
+
+    /// Sets a token sent as `Authorization: Bearer <token>` with every request, as expected
+    /// by authenticating proxies and hosted Ollama services. `None` removes it.
+    pub fn set_api_key(&mut self, api_key: Option<String>) {
+        self.config_mut().api_key = api_key;
+    }
+
+    /// Starts a request to `path`, relative to the URL of the Ollama service, with the
+    /// settings of this client applied.
+    pub(crate) fn request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        self.build_request(method, path, self.config.response_compression)
+    }
+
+    /// Posts a JSON `body` to `path` and reads the response, coalescing identical requests
+    /// when [`Ollama::set_deduplicate_requests`] is enabled.
+    pub(crate) async fn post_json(
+        &self,
+        path: &str,
+        body: String,
+    ) -> crate::error::Result<dedup::SharedResponse> {
+        let send = || async {
+            let builder = self.request(reqwest::Method::POST, path);
+            let res = self.send(builder.body(body.clone())).await?;
+
+            if !res.status().is_success() {
+                return Err(error::OllamaError::from_http_response(res).await);
+            }
+
+            let headers = res.headers().clone();
+            let request_id = request_id::of(&res);
+            // Keeps the concurrency slot of the request while its body is read
+            let _extensions = res.extensions().clone();
+            let bytes = error::read_body(res).await?;
+            Ok(dedup::SharedResponse {
+                bytes: bytes.to_vec().into(),
+                headers,
+                request_id,
+            })
+        };
+
+        match &self.config.in_flight {
+            Some(in_flight) => {
+                let key = dedup::InFlight::key(&self.config, path, &body);
+                in_flight.run(key, send).await
+            }
+            None => send().await,
+        }
+    }
+
+    /// Like [`Ollama::request`], for endpoints streaming their response.
+    #[cfg(feature = "stream")]
+    pub(crate) fn stream_request(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+    ) -> reqwest::RequestBuilder {
+        self.build_request(method, path, false)
+    }
+
+    fn build_request(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        compression: bool,
+    ) -> reqwest::RequestBuilder {
+        let builder = self
+            .config
+            .reqwest_client
+            .request(method, join_path(&self.config.url, path));
+
+        #[cfg(feature = "headers")]
+        let builder = builder.headers(self.config.request_headers.clone());
+
+        let builder = match &self.config.api_key {
+            Some(api_key) => builder.bearer_auth(api_key),
+            None => builder,
+        };
+
+        // reqwest advertises the encodings enabled by the features unless told otherwise
+        if compression {
+            builder
+        } else {
+            builder.header(reqwest::header::ACCEPT_ENCODING, "identity")
+        }
     }
 }
 
+/// Appends `path` to the path of `base`, so that a client pointed at a reverse proxy serving
+/// Ollama under a prefix, such as `https://gw.example.com/ollama`, reaches
+/// `https://gw.example.com/ollama/api/chat` whether or not the prefix ends with a slash.
+pub(crate) fn join_path(base: &Url, path: &str) -> Url {
+    let mut url = base.clone();
+    let prefix = base.path().trim_end_matches('/');
+    url.set_path(&format!("{prefix}/{}", path.trim_start_matches('/')));
+    url
+}
+
+/// Hashes `bytes` with FNV-1a, whose hashes are stable across processes and versions,
+/// unlike the ones of the standard library.
+pub(crate) fn stable_hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// An id unlikely to be generated twice, even by several processes, like `call_8f3a...`.
+pub(crate) fn unique_id(prefix: &str) -> String {
+    static COUNTER: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+    let count = COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_nanos());
+    let seed = format!("{nanos}-{}-{count}", std::process::id());
+    format!("{prefix}_{:016x}", stable_hash(seed.as_bytes()))
+}
+
+/// The largest index of a char boundary of `text` not past `index`.
+#[cfg(any(feature = "stream", feature = "regex"))]
+pub(crate) fn floor_char_boundary(text: &str, index: usize) -> usize {
+    let mut index = index.min(text.len());
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
 impl From<Url> for Ollama {
     fn from(url: Url) -> Self {
         Self::from_url(url)
@@ -212,11 +648,6 @@ impl From<Url> for Ollama {
 impl Default for Ollama {
     /// Returns a default Ollama instance with the host set to `http://127.0.0.1:11434`.
     fn default() -> Self {
-        Self {
-            url: Url::parse("http://127.0.0.1:11434").unwrap(),
-            reqwest_client: reqwest::Client::new(),
-            #[cfg(feature = "headers")]
-            request_headers: reqwest::header::HeaderMap::new(),
-        }
+        Self::new_with_client("http://127.0.0.1", 11434, reqwest::Client::new())
     }
 }
@@ -0,0 +1,130 @@
+//! Logging of the content of requests and responses.
+//!
+//! Generation and chat requests log their prompts and responses with the [`log`] crate, at
+//! the `debug` level and under the `ollama_rs` target. By default, only the length of that
+//! content is logged. Set a [`ContentLogging`] on the client to log it, as-is or scrubbed or
+//! hashed first, e.g. where prompts hold personal data:
+//!
+//! ```no_run
+//! # fn main() -> ollama_rs::error::Result<()> {
+//! use ollama_rs::{logging::ContentLogging, Ollama};
+//!
+//! let ollama = Ollama::builder()
+//!     .content_logging(ContentLogging::redact(|text| {
+//!         text.split_whitespace()
+//!             .map(|word| if word.contains('@') { "[email]" } else { word })
+//!             .collect::<Vec<_>>()
+//!             .join(" ")
+//!     }))
+//!     .build()?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{fmt, sync::Arc};
+
+use sha2::{Digest, Sha256};
+
+use crate::generation::chat::request::ChatMessageRequest;
+
+/// How the content of prompts and responses appears in the logs.
+#[derive(Clone, Default)]
+pub enum ContentLogging {
+    /// The content is logged as-is.
+    Full,
+    /// Only the length of the content is logged. This is the default, so that enabling debug
+    /// logs doesn't reveal prompts.
+    #[default]
+    Omit,
+    /// A salted SHA-256 and the length of the content are logged, so that identical prompts
+    /// can be correlated without being revealed. Without the salt, the content can't be
+    /// recovered by hashing guesses of it. See [`ContentLogging::hash`].
+    Hash { salt: [u8; 32] },
+    /// The content is logged once transformed by the function, e.g. to mask secrets.
+    Redact(Arc<dyn Fn(&str) -> String + Send + Sync>),
+}
+
+impl ContentLogging {
+    pub fn redact(redact: impl Fn(&str) -> String + Send + Sync + 'static) -> Self {
+        Self::Redact(Arc::new(redact))
+    }
+
+    /// Hashes the content with a salt drawn from the random number generator of the OS, so
+    /// that hashes only match within the logs of the clients sharing this setting. Use
+    /// [`ContentLogging::Hash`] with a salt of your own to correlate the logs of several
+    /// processes.
+    ///
+    /// # Panics
+    ///
+    /// If the random number generator of the OS is unavailable.
+    #[cfg_attr(docsrs, doc(cfg(feature = "getrandom")))]
+    #[cfg(feature = "getrandom")]
+    pub fn hash() -> Self {
+        Self::Hash {
+            salt: random_salt(),
+        }
+    }
+
+    /// Returns `content` as it is logged.
+    pub fn apply(&self, content: &str) -> String {
+        match self {
+            Self::Full => content.to_string(),
+            Self::Omit => format!("[{} chars]", content.chars().count()),
+            Self::Hash { salt } => {
+                let digest = salted_hash(salt, content.as_bytes());
+                format!("[{digest}, {} chars]", content.chars().count())
+            }
+            Self::Redact(redact) => redact(content),
+        }
+    }
+}
+
+/// A salt drawn from the random number generator of the OS.
+///
+/// # Panics
+///
+/// If the random number generator of the OS is unavailable.
+#[cfg(feature = "getrandom")]
+pub(crate) fn random_salt() -> [u8; 32] {
+    let mut salt = [0; 32];
+    getrandom::fill(&mut salt).expect("the OS random number generator is unavailable");
+    salt
+}
+
+/// The SHA-256 of `salt` followed by `content`, in hex.
+pub(crate) fn salted_hash(salt: &[u8; 32], content: &[u8]) -> String {
+    let digest = Sha256::new()
+        .chain_update(salt)
+        .chain_update(content)
+        .finalize();
+    format!("{digest:x}")
+}
+
+impl fmt::Debug for ContentLogging {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Full => f.write_str("Full"),
+            Self::Omit => f.write_str("Omit"),
+            Self::Hash { .. } => f.write_str("Hash { .. }"),
+            Self::Redact(_) => f.write_str("Redact(..)"),
+        }
+    }
+}
+
+impl crate::Ollama {
+    /// Logs a piece of content of a request or response to `model`, e.g. its prompt.
+    pub(crate) fn log_content(&self, model: &str, kind: &str, content: &str) {
+        if log::log_enabled!(target: "ollama_rs", log::Level::Debug) {
+            let content = self.config.content_logging.apply(content);
+            log::debug!(target: "ollama_rs", "{model} {kind}: {content}");
+        }
+    }
+
+    /// Logs the last message of a chat request, the previous ones having been logged with
+    /// the previous turns.
+    pub(crate) fn log_last_message(&self, request: &ChatMessageRequest) {
+        if let Some(message) = request.messages.last() {
+            self.log_content(&request.model_name, "message", &message.content);
+        }
+    }
+}
@@ -2,31 +2,119 @@
 ///
 /// These modules provide functionality for copying, creating, deleting,
 /// listing, pulling, pushing, and showing information about models.
+pub mod blobs;
 pub mod copy;
 pub mod create;
 pub mod delete;
+pub mod disk_usage;
+pub mod eviction;
 pub mod list_local;
+pub mod list_running;
+pub mod load;
+pub mod modelfile;
+pub mod name;
+#[cfg(feature = "indicatif")]
+mod progress;
 pub mod pull;
 pub mod push;
+pub mod registry;
 pub mod show_info;
 
+pub use name::ModelName;
+
 #[cfg(feature = "modelfile")]
-use modelfile::modelfile::Modelfile;
+use ::modelfile::modelfile::Modelfile;
 
 #[cfg(feature = "modelfile")]
 use serde_with;
 
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
 /// Represents a local model pulled from Ollama.
 ///
 /// This struct contains information about a model that has been pulled
 /// from the Ollama service, including its name, modification date, and size.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct LocalModel {
     pub name: String,
-    pub modified_at: String,
+    #[cfg_attr(feature = "utoipa", schema(value_type = String, format = DateTime))]
+    pub modified_at: crate::Timestamp,
     pub size: u64,
+    #[serde(default)]
+    pub digest: String,
+    #[serde(default)]
+    pub details: ModelDetails,
+}
+
+/// The format, family and quantization of a model.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct ModelDetails {
+    #[serde(default)]
+    pub parent_model: String,
+    #[serde(default)]
+    pub format: String,
+    #[serde(default)]
+    pub family: String,
+    #[serde(default)]
+    pub families: Option<Vec<String>>,
+    #[serde(default)]
+    pub parameter_size: String,
+    #[serde(default)]
+    pub quantization_level: String,
+}
+
+/// A number of bytes, displayed in decimal units like the Ollama CLI, e.g. `4.7 GB`.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(transparent)]
+pub struct ByteSize(pub u64);
+
+impl ByteSize {
+    /// The number of bytes.
+    pub fn bytes(&self) -> u64 {
+        self.0
+    }
+
+    /// Formats the size in binary units, e.g. `4.4 GiB`.
+    pub fn to_binary_string(&self) -> String {
+        format_bytes(self.0, 1024.0, &["B", "KiB", "MiB", "GiB", "TiB"])
+    }
+}
+
+impl std::fmt::Display for ByteSize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&format_bytes(
+            self.0,
+            1000.0,
+            &["B", "KB", "MB", "GB", "TB"],
+        ))
+    }
+}
+
+impl From<u64> for ByteSize {
+    fn from(bytes: u64) -> Self {
+        Self(bytes)
+    }
+}
+
+fn format_bytes(bytes: u64, base: f64, units: &[&str]) -> String {
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= base && unit + 1 < units.len() {
+        value /= base;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{value:.1} {}", units[unit])
+    }
 }
 
 /// Represents information about a model.
@@ -39,11 +127,13 @@ pub struct LocalModel {
 /// it will be a `Modelfile` struct. See the modelfile crate for more information.
 #[cfg_attr(feature = "modelfile", serde_with::serde_as)]
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct ModelInfo {
     #[serde(default = "String::new")]
     pub license: String,
     #[cfg(feature = "modelfile")]
     #[serde_as(as = "serde_with::DisplayFromStr")]
+    #[cfg_attr(feature = "utoipa", schema(value_type = String))]
     pub modelfile: Modelfile,
     #[cfg(not(feature = "modelfile"))]
     #[serde(default = "String::new")]
@@ -58,6 +148,7 @@ pub struct ModelInfo {
 
 // Options for generation requests to Ollama.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct ModelOptions {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(super) mirostat: Option<u8>,
@@ -91,9 +182,62 @@ pub struct ModelOptions {
     pub(super) top_k: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(super) top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) logit_bias: Option<LogitBias>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) kv_cache_type: Option<KvCacheType>,
+    #[serde(rename = "flash_attn", skip_serializing_if = "Option::is_none")]
+    pub(super) flash_attention: Option<bool>,
 }
 
+/// Generates setters of single options in the `impl` block of a request type with an
+/// `options: Option<ModelOptions>` field, so that every request type has the same ones.
+macro_rules! model_option_setters {
+    () => {
+        crate::models::model_option_setters!(
+            temperature: f32,
+            num_ctx: u64,
+            seed: i32,
+            num_predict: i32,
+            top_k: u32,
+            top_p: f32,
+            repeat_penalty: f32,
+            stop: Vec<String>,
+        );
+    };
+    ($($option:ident: $ty:ty),* $(,)?) => {
+        $(
+            #[doc = concat!(
+                "Sets [`ModelOptions::", stringify!($option), "`](crate::models::ModelOptions::",
+                stringify!($option), ") in the options of this request."
+            )]
+            pub fn $option(mut self, $option: $ty) -> Self {
+                self.options = Some(self.options.take().unwrap_or_default().$option($option));
+                self
+            }
+        )*
+    };
+}
+pub(crate) use model_option_setters;
+
 impl ModelOptions {
+    /// Fills the options that aren't set with the ones set in `defaults`.
+    pub(crate) fn or_defaults(self, defaults: &ModelOptions) -> ModelOptions {
+        let (Ok(Value::Object(mut merged)), Ok(Value::Object(options))) =
+            (serde_json::to_value(defaults), serde_json::to_value(&self))
+        else {
+            return self;
+        };
+        merged.extend(options);
+
+        serde_json::from_value(Value::Object(merged)).unwrap_or(self)
+    }
+
+    /// Whether a response only depends on its request, i.e. sampling is greedy or seeded.
+    pub(crate) fn is_deterministic(&self) -> bool {
+        self.temperature == Some(0.0) || self.seed.is_some()
+    }
+
     /// Enable Mirostat sampling for controlling perplexity. (default: 0, 0 = disabled, 1 = Mirostat, 2 = Mirostat 2.0)
     pub fn mirostat(mut self, mirostat: u8) -> Self {
         self.mirostat = Some(mirostat);
@@ -189,4 +333,176 @@ impl ModelOptions {
         self.top_p = Some(top_p);
         self
     }
+
+    /// Makes specific tokens more or less likely to appear, or bans them. Only honored by
+    /// servers supporting `logit_bias`, others ignore it.
+    pub fn logit_bias(mut self, logit_bias: LogitBias) -> Self {
+        self.logit_bias = Some(logit_bias);
+        self
+    }
+
+    /// Sets the data type of the key/value cache. Quantized caches reduce the memory used by the
+    /// context. Ollama itself sets it server-wide with `OLLAMA_KV_CACHE_TYPE`, servers that don't
+    /// support it per request ignore it. Requires flash attention.
+    pub fn kv_cache_type(mut self, kv_cache_type: KvCacheType) -> Self {
+        self.kv_cache_type = Some(kv_cache_type);
+        self
+    }
+
+    /// Enables or disables flash attention, which reduces memory usage as the context grows.
+    /// Ollama itself sets it server-wide with `OLLAMA_FLASH_ATTENTION`, servers that don't
+    /// support it per request ignore it.
+    pub fn flash_attention(mut self, enabled: bool) -> Self {
+        self.flash_attention = Some(enabled);
+        self
+    }
+}
+
+/// The data type of the key/value cache of a model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub enum KvCacheType {
+    /// 16-bit floats, the most precise and largest.
+    #[serde(rename = "f16")]
+    F16,
+    /// 8-bit quantization, about half the memory of `f16` with little loss of precision.
+    #[serde(rename = "q8_0")]
+    Q8_0,
+    /// 4-bit quantization, about a quarter of the memory of `f16` with a noticeable loss of precision.
+    #[serde(rename = "q4_0")]
+    Q4_0,
+}
+
+/// A token whose likelihood is changed by a [`LogitBias`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(untagged)]
+pub enum LogitBiasToken {
+    /// A token id of the vocabulary of the model.
+    Id(u32),
+    /// Text, standing for the tokens it is tokenized into by the server.
+    Text(String),
+}
+
+/// Biases added to the logits of tokens before sampling. Positive biases make a token more
+/// likely, negative biases less likely, and [`LogitBias::BAN`] prevents it from being sampled.
+///
+/// Serialized as a list of `[token, bias]` pairs, the form expected by llama.cpp based servers.
+/// A map from token ids to biases, the OpenAI form, is accepted when deserializing.
+///
+/// ```
+/// use ollama_rs::models::{LogitBias, ModelOptions};
+///
+/// let options = ModelOptions::default().logit_bias(
+///     LogitBias::new()
+///         .token(15043, 2.0)
+///         .ban_text("Certainly"),
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LogitBias {
+    entries: Vec<(LogitBiasToken, f32)>,
+}
+
+impl LogitBias {
+    /// The bias banning a token.
+    pub const BAN: f32 = -100.0;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Biases the token with the given id, replacing any bias set before.
+    pub fn token(self, id: u32, bias: f32) -> Self {
+        self.insert(LogitBiasToken::Id(id), bias)
+    }
+
+    /// Biases the tokens of `text`, replacing any bias set before.
+    pub fn text(self, text: impl Into<String>, bias: f32) -> Self {
+        self.insert(LogitBiasToken::Text(text.into()), bias)
+    }
+
+    /// Prevents the token with the given id from being sampled.
+    pub fn ban_token(self, id: u32) -> Self {
+        self.token(id, Self::BAN)
+    }
+
+    /// Prevents the tokens of `text` from being sampled.
+    pub fn ban_text(self, text: impl Into<String>) -> Self {
+        self.text(text, Self::BAN)
+    }
+
+    /// The biased tokens with their bias, in the order they were added.
+    pub fn entries(&self) -> &[(LogitBiasToken, f32)] {
+        &self.entries
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn insert(mut self, token: LogitBiasToken, bias: f32) -> Self {
+        match self.entries.iter_mut().find(|(t, _)| *t == token) {
+            Some(entry) => entry.1 = bias,
+            None => self.entries.push((token, bias)),
+        }
+        self
+    }
+}
+
+#[cfg(feature = "utoipa")]
+impl utoipa::PartialSchema for LogitBias {
+    fn schema() -> utoipa::openapi::RefOr<utoipa::openapi::schema::Schema> {
+        use utoipa::openapi::{ArrayBuilder, ObjectBuilder, OneOfBuilder, Schema, Type};
+
+        let token = OneOfBuilder::new()
+            .item(ObjectBuilder::new().schema_type(Type::Integer))
+            .item(ObjectBuilder::new().schema_type(Type::String));
+        let bias = ObjectBuilder::new().schema_type(Type::Number);
+        let pair = ArrayBuilder::new()
+            .prefix_items([Schema::from(token.build()), Schema::from(bias.build())])
+            .min_items(Some(2))
+            .max_items(Some(2));
+
+        ArrayBuilder::new()
+            .items(pair)
+            .description(Some("`[token, bias]` pairs, a token being an id or text"))
+            .into()
+    }
+}
+
+#[cfg(feature = "utoipa")]
+impl utoipa::ToSchema for LogitBias {}
+
+impl Serialize for LogitBias {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.entries.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for LogitBias {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Pairs(Vec<(LogitBiasToken, f32)>),
+            Map(std::collections::HashMap<String, f32>),
+        }
+
+        let entries = match Repr::deserialize(deserializer)? {
+            Repr::Pairs(pairs) => pairs,
+            Repr::Map(map) => map
+                .into_iter()
+                .map(|(token, bias)| {
+                    let token = match token.parse() {
+                        Ok(id) => LogitBiasToken::Id(id),
+                        Err(_) => LogitBiasToken::Text(token),
+                    };
+                    (token, bias)
+                })
+                .collect(),
+        };
+
+        Ok(Self { entries })
+    }
 }
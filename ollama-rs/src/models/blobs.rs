@@ -0,0 +1,120 @@
+//! Blobs, the files models are created from, such as GGUF weights and LoRA adapters.
+//!
+//! A blob is uploaded once, under the digest of its content, then referenced by
+//! [`CreateModelRequest::file`](super::create::CreateModelRequest::file) and
+//! [`CreateModelRequest::adapter`](super::create::CreateModelRequest::adapter).
+
+use std::{io::Read, path::Path};
+
+use sha2::{Digest, Sha256};
+
+use crate::{error::OllamaError, Ollama};
+
+/// The digest of `data`, like `sha256:8f3a...`, under which it is stored as a blob.
+pub fn blob_digest(data: &[u8]) -> String {
+    format!("sha256:{:x}", Sha256::digest(data))
+}
+
+/// The digest of the file at `path`, read in chunks so that large weights aren't loaded in
+/// memory.
+///
+/// This blocks while the whole file is read, which takes a while for large weights: in async
+/// code, run it off the executor, e.g. with `tokio::task::spawn_blocking`.
+pub fn file_digest(path: impl AsRef<Path>) -> std::io::Result<String> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0; CHUNK_SIZE];
+    loop {
+        let n = file.read(&mut buffer)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buffer[..n]);
+    }
+    Ok(format!("sha256:{:x}", hasher.finalize()))
+}
+
+pub(crate) const CHUNK_SIZE: usize = 1 << 20;
+
+impl Ollama {
+    /// Whether the server has the blob with the digest `digest`, like `sha256:8f3a...`.
+    pub async fn blob_exists(&self, digest: &str) -> crate::error::Result<bool> {
+        let builder = self.request(reqwest::Method::HEAD, &format!("api/blobs/{digest}"));
+        let res = self.send(builder).await?;
+
+        match res.status() {
+            status if status.is_success() => Ok(true),
+            reqwest::StatusCode::NOT_FOUND => Ok(false),
+            _ => Err(OllamaError::from_http_response(res).await),
+        }
+    }
+
+    /// Uploads a blob, which the server checks against `digest`.
+    pub async fn create_blob(
+        &self,
+        digest: &str,
+        data: impl Into<reqwest::Body>,
+    ) -> crate::error::Result<()> {
+        let builder = self.request(reqwest::Method::POST, &format!("api/blobs/{digest}"));
+        let res = self.send(builder.body(data)).await?;
+
+        if res.status().is_success() {
+            Ok(())
+        } else {
+            Err(OllamaError::from_http_response(res).await)
+        }
+    }
+
+    /// Uploads the file at `path` as a blob, unless the server already has it, returning its
+    /// digest and whether it was uploaded.
+    ///
+    /// With the `tokio` feature, the file is hashed and read on the blocking threads of Tokio.
+    /// Without it, these reads block the executor: hash the file off it with [`file_digest`],
+    /// and upload it with [`Ollama::create_blob`].
+    #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+    #[cfg(feature = "stream")]
+    pub async fn upload_blob_file(
+        &self,
+        path: impl AsRef<Path>,
+    ) -> crate::error::Result<(String, bool)> {
+        let path = path.as_ref().to_path_buf();
+        let read_error = |e| OllamaError::Other(format!("Can't read {}: {e}", path.display()));
+        let digest = {
+            let path = path.clone();
+            crate::runtime::unblock(move || file_digest(path)).await
+        }
+        .map_err(read_error)?;
+        if self.blob_exists(&digest).await? {
+            return Ok((digest, false));
+        }
+
+        let file = std::fs::File::open(&path).map_err(read_error)?;
+        self.create_blob(&digest, reqwest::Body::wrap_stream(read_chunks(file)))
+            .await?;
+        Ok((digest, true))
+    }
+}
+
+/// The content of `file`, in chunks read off the executor, see [`crate::runtime::unblock`].
+#[cfg(feature = "stream")]
+fn read_chunks(
+    file: std::fs::File,
+) -> impl futures_util::Stream<Item = std::io::Result<Vec<u8>>> + Send + 'static {
+    futures_util::stream::unfold(Some(file), |file| async move {
+        let mut file = file?;
+        let (file, read) = crate::runtime::unblock(move || {
+            let mut buffer = vec![0; CHUNK_SIZE];
+            let read = file.read(&mut buffer).map(|n| {
+                buffer.truncate(n);
+                buffer
+            });
+            (file, read)
+        })
+        .await;
+        match read {
+            Ok(buffer) if buffer.is_empty() => None,
+            Ok(buffer) => Some((Ok(buffer), Some(file))),
+            Err(e) => Some((Err(e), None)),
+        }
+    })
+}
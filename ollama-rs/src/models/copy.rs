@@ -14,19 +14,15 @@ impl Ollama {
             destination,
         };
 
-        let url = format!("{}api/copy", self.url_str());
         let serialized = serde_json::to_string(&request)?;
-        let builder = self.reqwest_client.post(url);
+        let builder = self.request(reqwest::Method::POST, "api/copy");
 
-        #[cfg(feature = "headers")]
-        let builder = builder.headers(self.request_headers.clone());
-
-        let res = builder.body(serialized).send().await?;
+        let res = self.send(builder.body(serialized)).await?;
 
         if res.status().is_success() {
             Ok(())
         } else {
-            Err(OllamaError::Other(res.text().await?))
+            Err(OllamaError::from_http_response(res).await)
         }
     }
 }
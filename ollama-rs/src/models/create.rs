@@ -8,7 +8,7 @@ use super::ModelOptions;
 #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
 #[cfg(feature = "stream")]
 pub type CreateModelStatusStream = std::pin::Pin<
-    Box<dyn tokio_stream::Stream<Item = crate::error::Result<CreateModelStatus>> + Send>,
+    Box<dyn futures_util::Stream<Item = crate::error::Result<CreateModelStatus>> + Send>,
 >;
 
 impl Ollama {
@@ -19,47 +19,75 @@ impl Ollama {
         &self,
         mut request: CreateModelRequest,
     ) -> crate::error::Result<CreateModelStatusStream> {
-        use tokio_stream::StreamExt;
-
         use crate::error::OllamaError;
 
         request.stream = true;
 
-        let url = format!("{}api/create", self.url_str());
         let serialized = serde_json::to_string(&request)?;
-        let builder = self.reqwest_client.post(url);
-
-        #[cfg(feature = "headers")]
-        let builder = builder.headers(self.request_headers.clone());
+        let builder = self.stream_request(reqwest::Method::POST, "api/create");
 
-        let res = builder.body(serialized).send().await?;
+        let res = self.send(builder.body(serialized)).await?;
 
         if !res.status().is_success() {
-            return Err(OllamaError::Other(res.text().await?));
+            return Err(OllamaError::from_http_response(res).await);
         }
 
-        let stream = Box::new(res.bytes_stream().map(|res| match res {
-            Ok(bytes) => {
-                let res = serde_json::from_slice::<CreateModelStatus>(&bytes);
-                match res {
-                    Ok(res) => Ok(res),
+        let stream = crate::ndjson::decode_stream::<CreateModelStatus>(
+            res,
+            self.config.capture_raw_responses,
+        );
+
+        Ok(Box::pin(stream))
+    }
+
+    #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+    #[cfg(feature = "stream")]
+    /// Like [`Ollama::create_model_stream`], uploading the files of the model first, by file
+    /// name and path, such as GGUF weights.
+    ///
+    /// Files whose blob is already on the server aren't uploaded again, which is reported
+    /// with a `<file name>: skipped (exists)` status, so re-importing a model is quick.
+    pub async fn create_model_with_files_stream(
+        &self,
+        mut request: CreateModelRequest,
+        files: impl IntoIterator<Item = (impl Into<String>, impl Into<std::path::PathBuf>)>,
+    ) -> crate::error::Result<CreateModelStatusStream> {
+        use futures_util::StreamExt;
+
+        let files: Vec<(String, std::path::PathBuf)> = files
+            .into_iter()
+            .map(|(name, path)| (name.into(), path.into()))
+            .collect();
+        let ollama = self.clone();
+
+        Ok(Box::pin(async_stream::stream! {
+            for (file_name, path) in files {
+                yield Ok(CreateModelStatus {
+                    message: format!("{file_name}: checking"),
+                });
+                let (digest, uploaded) = match ollama.upload_blob_file(&path).await {
+                    Ok(res) => res,
                     Err(e) => {
-                        let err =
-                            serde_json::from_slice::<crate::error::InternalOllamaError>(&bytes);
-                        match err {
-                            Ok(err) => Err(OllamaError::InternalError(err)),
-                            Err(_) => Err(OllamaError::from(e)),
-                        }
+                        yield Err(e);
+                        return;
                     }
-                }
+                };
+                let status = if uploaded { "uploaded" } else { "skipped (exists)" };
+                yield Ok(CreateModelStatus {
+                    message: format!("{file_name}: {status}"),
+                });
+                request = request.file(file_name, digest);
             }
-            Err(e) => Err(OllamaError::Other(format!(
-                "Failed to read response: {}",
-                e
-            ))),
-        }));
 
-        Ok(std::pin::Pin::from(stream))
+            match ollama.create_model_stream(request).await {
+                Ok(mut stream) => {
+                    while let Some(status) = stream.next().await {
+                        yield status;
+                    }
+                }
+                Err(e) => yield Err(e),
+            }
+        }))
     }
 
     /// Create a model with a single response, only the final status will be returned.
@@ -67,27 +95,23 @@ impl Ollama {
         &self,
         request: CreateModelRequest,
     ) -> crate::error::Result<CreateModelStatus> {
-        let url = format!("{}api/create", self.url_str());
         let serialized = serde_json::to_string(&request)?;
-        let builder = self.reqwest_client.post(url);
-
-        #[cfg(feature = "headers")]
-        let builder = builder.headers(self.request_headers.clone());
+        let builder = self.request(reqwest::Method::POST, "api/create");
 
-        let res = builder.body(serialized).send().await?;
+        let res = self.send(builder.body(serialized)).await?;
 
         if !res.status().is_success() {
-            return Err(OllamaError::Other(res.text().await?));
+            return Err(OllamaError::from_http_response(res).await);
         }
 
-        let res = res.bytes().await?;
-        let res = serde_json::from_slice::<CreateModelStatus>(&res)?;
+        let res = crate::error::decode_body::<CreateModelStatus>(res).await?;
 
         Ok(res)
     }
 }
 
-#[derive(Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub enum QuantizationType {
     #[serde(rename = "q2_K")]
     Q2K,
@@ -120,13 +144,38 @@ pub enum QuantizationType {
 }
 
 /// A create model request to Ollama.
-#[derive(Serialize)]
+///
+/// A model is created from an existing model or from blobs already on the server, referenced
+/// by digest, with LoRA adapters, a template, a system prompt, parameters and seed messages
+/// layered on top:
+///
+/// ```no_run
+/// # async fn run() -> ollama_rs::error::Result<()> {
+/// use ollama_rs::{
+///     generation::chat::ChatMessage, models::create::CreateModelRequest,
+///     models::ModelOptions, Ollama,
+/// };
+///
+/// let request = CreateModelRequest::new("mario".into())
+///     .from_model("llama3.2".into())
+///     .adapter("mario-lora.gguf", "sha256:8f3a...")
+///     .system("You are Mario from Super Mario Bros.".into())
+///     .parameters(ModelOptions::default().temperature(0.8))
+///     .message(ChatMessage::user("Who are you?".into()))
+///     .message(ChatMessage::assistant("It's-a me, Mario!".into()))
+///     .license("MIT".into());
+/// Ollama::default().create_model(request).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct CreateModelRequest {
     /// Name of the model to create
     #[serde(rename = "model")]
     model_name: String,
     /// Name of an existing model to create the new model from
-    #[serde(rename = "from")]
+    #[serde(rename = "from", skip_serializing_if = "Option::is_none")]
     from_model: Option<String>,
     /// A dictionary of file names to SHA256 digests of blobs to create the model from
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -182,11 +231,27 @@ impl CreateModelRequest {
         self
     }
 
+    /// Adds a file of the model, by the digest of its blob.
+    pub fn file(mut self, file_name: impl Into<String>, digest: impl Into<String>) -> Self {
+        self.files
+            .get_or_insert_with(Default::default)
+            .insert(file_name.into(), digest.into());
+        self
+    }
+
     pub fn adapters(mut self, adapters: std::collections::HashMap<String, String>) -> Self {
         self.adapters = Some(adapters);
         self
     }
 
+    /// Adds a LoRA adapter, by the digest of its blob.
+    pub fn adapter(mut self, file_name: impl Into<String>, digest: impl Into<String>) -> Self {
+        self.adapters
+            .get_or_insert_with(Default::default)
+            .insert(file_name.into(), digest.into());
+        self
+    }
+
     pub fn template(mut self, template: String) -> Self {
         self.template = Some(template);
         self
@@ -217,14 +282,24 @@ impl CreateModelRequest {
         self
     }
 
+    /// Adds a message to the conversation the model starts with.
+    pub fn message(mut self, message: ChatMessage) -> Self {
+        self.messages.get_or_insert_with(Vec::new).push(message);
+        self
+    }
+
     pub fn quantize(mut self, quantize: QuantizationType) -> Self {
         self.quantize = Some(quantize);
         self
     }
 }
 
+#[cfg(feature = "stream")]
+impl crate::ndjson::StreamChunk for CreateModelStatus {}
+
 /// A create model status response from Ollama.
 #[derive(Deserialize, Debug)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct CreateModelStatus {
     #[serde(rename = "status")]
     pub message: String,
@@ -7,19 +7,15 @@ impl Ollama {
     pub async fn delete_model(&self, model_name: String) -> crate::error::Result<()> {
         let request = DeleteModelRequest { model_name };
 
-        let url = format!("{}api/delete", self.url_str());
         let serialized = serde_json::to_string(&request)?;
-        let builder = self.reqwest_client.delete(url);
+        let builder = self.request(reqwest::Method::DELETE, "api/delete");
 
-        #[cfg(feature = "headers")]
-        let builder = builder.headers(self.request_headers.clone());
-
-        let res = builder.body(serialized).send().await?;
+        let res = self.send(builder.body(serialized)).await?;
 
         if res.status().is_success() {
             Ok(())
         } else {
-            Err(OllamaError::Other(res.text().await?))
+            Err(OllamaError::from_http_response(res).await)
         }
     }
 }
@@ -0,0 +1,160 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde::Serialize;
+
+use crate::Ollama;
+
+use super::LocalModel;
+
+impl Ollama {
+    /// Summarizes the disk space used by the local models.
+    ///
+    /// The Modelfile of every model is fetched to find the models sharing their weights.
+    pub async fn models_disk_usage(&self) -> crate::error::Result<DiskUsage> {
+        let models = self.list_local_models().await?;
+        let futures = models
+            .iter()
+            .map(|model| Box::pin(self.model_weights(&model.name)))
+            .collect();
+        let weights = crate::runtime::join_all(futures, 4)
+            .await
+            .into_iter()
+            .collect::<crate::error::Result<Vec<_>>>()?;
+        Ok(DiskUsage::summarize(&models, &weights))
+    }
+
+    /// The digest of the blob holding the weights of `model`, read from its Modelfile.
+    async fn model_weights(&self, model: &str) -> crate::error::Result<Option<String>> {
+        let modelfile = self
+            .show_model_info(model.to_string())
+            .await?
+            .parse_modelfile()?;
+        Ok(modelfile
+            .from
+            .as_deref()
+            .and_then(super::modelfile::blob)
+            .map(|(_, digest)| digest))
+    }
+}
+
+/// The disk space used by local models.
+///
+/// Models sharing their weights, like copies made with [`Ollama::copy_model`] or models
+/// created from another one with a different template or parameters, are reported in
+/// `duplicates`, and their weights are counted once in the totals.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DiskUsage {
+    /// The bytes used by all models.
+    pub total_bytes: u64,
+    /// Every model, largest first.
+    pub models: Vec<ModelDiskUsage>,
+    /// The bytes used by the models of each family.
+    pub by_family: BTreeMap<String, u64>,
+    /// The bytes used by the models of each quantization level.
+    pub by_quantization: BTreeMap<String, u64>,
+    /// The groups of models sharing their weights.
+    pub duplicates: Vec<DuplicateModels>,
+}
+
+/// The disk space used by a local model.
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelDiskUsage {
+    pub name: String,
+    pub digest: String,
+    pub size: u64,
+    pub family: String,
+    pub quantization_level: String,
+    /// The digest of the blob holding the weights of the model, if known.
+    pub weights: Option<String>,
+}
+
+/// Models sharing their weights.
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateModels {
+    /// The digest of the blob holding the weights, or of the models if their weights aren't
+    /// known, in which case the models are identical.
+    pub digest: String,
+    pub names: Vec<String>,
+    /// The bytes shared by the models, counted once in the totals. The weights making up
+    /// nearly all of a model, this is the size of the smallest of them.
+    pub size: u64,
+}
+
+impl DiskUsage {
+    /// Summarizes the disk space used by `models`, as listed by
+    /// [`Ollama::list_local_models`].
+    ///
+    /// The list doesn't tell which blobs models are made of, so only identical models, with
+    /// the same digest, are reported as duplicates. See [`Ollama::models_disk_usage`].
+    pub fn from_models(models: &[LocalModel]) -> Self {
+        Self::summarize(models, &vec![None; models.len()])
+    }
+
+    /// Summarizes the disk space used by `models`, whose weights are the blobs with the
+    /// digests of `weights`, in the same order.
+    fn summarize(models: &[LocalModel], weights: &[Option<String>]) -> Self {
+        let mut usage = DiskUsage::default();
+        let mut models_by_blob = HashMap::<&str, Vec<&LocalModel>>::new();
+
+        for (model, weights) in models.iter().zip(weights) {
+            usage.models.push(ModelDiskUsage {
+                name: model.name.clone(),
+                digest: model.digest.clone(),
+                size: model.size,
+                family: model.details.family.clone(),
+                quantization_level: model.details.quantization_level.clone(),
+                weights: weights.clone(),
+            });
+
+            usage.total_bytes += model.size;
+            *usage
+                .by_family
+                .entry(model.details.family.clone())
+                .or_default() += model.size;
+            *usage
+                .by_quantization
+                .entry(model.details.quantization_level.clone())
+                .or_default() += model.size;
+
+            // Without a digest, models can't be told apart
+            let blob = weights.as_deref().unwrap_or(model.digest.as_str());
+            if !blob.is_empty() {
+                models_by_blob.entry(blob).or_default().push(model);
+            }
+        }
+
+        usage
+            .models
+            .sort_by(|a, b| b.size.cmp(&a.size).then(a.name.cmp(&b.name)));
+
+        for (digest, models) in models_by_blob {
+            if models.len() < 2 {
+                continue;
+            }
+            let shared = models.iter().map(|model| model.size).min().unwrap_or(0);
+            // The shared bytes were counted with every model
+            for model in &models[1..] {
+                usage.total_bytes -= shared;
+                *usage
+                    .by_family
+                    .entry(model.details.family.clone())
+                    .or_default() -= shared;
+                *usage
+                    .by_quantization
+                    .entry(model.details.quantization_level.clone())
+                    .or_default() -= shared;
+            }
+
+            let mut names: Vec<_> = models.iter().map(|model| model.name.clone()).collect();
+            names.sort();
+            usage.duplicates.push(DuplicateModels {
+                digest: digest.to_string(),
+                names,
+                size: shared,
+            });
+        }
+        usage.duplicates.sort_by(|a, b| a.names.cmp(&b.names));
+
+        usage
+    }
+}
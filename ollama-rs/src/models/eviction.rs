@@ -0,0 +1,121 @@
+//! Eviction of the least recently used models, to keep a GPU shared by several models
+//! within its memory.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use super::ByteSize;
+use crate::Ollama;
+
+/// Unloads the least recently used models when the models loaded in VRAM use more than
+/// a budget.
+///
+/// The manager only knows the models used through [`ModelEvictionManager::use_model`] or
+/// [`ModelEvictionManager::touch`]. Other loaded models, e.g. used by other applications,
+/// are considered older than all of them, and evicted first. Clones share their state.
+///
+/// ```no_run
+/// # async fn run() -> ollama_rs::error::Result<()> {
+/// use ollama_rs::{models::{eviction::ModelEvictionManager, ByteSize}, Ollama};
+///
+/// let ollama = Ollama::default();
+/// let manager = ModelEvictionManager::new(ollama.clone(), ByteSize(16_000_000_000));
+///
+/// // Before each request
+/// let evicted = manager.use_model("llama3.2").await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ModelEvictionManager {
+    ollama: Ollama,
+    max_vram: ByteSize,
+    last_used: Arc<Mutex<HashMap<String, Instant>>>,
+}
+
+impl ModelEvictionManager {
+    /// Creates a manager keeping the VRAM used by the models of `ollama` under `max_vram`.
+    pub fn new(ollama: Ollama, max_vram: ByteSize) -> Self {
+        Self {
+            ollama,
+            max_vram,
+            last_used: Default::default(),
+        }
+    }
+
+    /// Records that `model_name` was just used.
+    pub fn touch(&self, model_name: &str) {
+        self.last_used
+            .lock()
+            .unwrap()
+            .insert(with_tag(model_name), Instant::now());
+    }
+
+    /// Records that `model_name` is about to be used, then evicts other models if needed.
+    /// Returns the names of the evicted models.
+    ///
+    /// The VRAM `model_name` will use once loaded is unknown until it is, so the budget is
+    /// enforced again on the next call.
+    pub async fn use_model(&self, model_name: &str) -> crate::error::Result<Vec<String>> {
+        self.touch(model_name);
+        self.evict(Some(&with_tag(model_name))).await
+    }
+
+    /// Evicts the least recently used models until the loaded models fit in the budget.
+    /// Returns the names of the evicted models.
+    pub async fn enforce(&self) -> crate::error::Result<Vec<String>> {
+        self.evict(None).await
+    }
+
+    async fn evict(&self, keep: Option<&str>) -> crate::error::Result<Vec<String>> {
+        let mut running = self.ollama.list_running_models().await?;
+        let mut used: u64 = running.iter().map(|model| model.size_vram.0).sum();
+        if used <= self.max_vram.0 {
+            return Ok(Vec::new());
+        }
+
+        // Untracked models first, by expiration, then tracked ones from the least recent
+        {
+            let last_used = self.last_used.lock().unwrap();
+            running.sort_by(|a, b| {
+                let a_used = last_used.get(&a.name);
+                let b_used = last_used.get(&b.name);
+                let order = a_used.cmp(&b_used);
+                // Without a date type, untracked models are left in the order of the server
+                #[cfg(any(feature = "time", feature = "chrono"))]
+                let order = order.then_with(|| a.expires_at.cmp(&b.expires_at));
+                order
+            });
+        }
+
+        let mut evicted = Vec::new();
+        for model in running {
+            if used <= self.max_vram.0 {
+                break;
+            }
+            if Some(model.name.as_str()) == keep || model.size_vram.0 == 0 {
+                continue;
+            }
+
+            self.ollama.unload_model(model.name.clone()).await?;
+            self.last_used.lock().unwrap().remove(&model.name);
+            used -= model.size_vram.0;
+            evicted.push(model.name);
+        }
+
+        Ok(evicted)
+    }
+}
+
+/// Adds the implicit `latest` tag, as model names are listed with their tag.
+fn with_tag(model_name: &str) -> String {
+    let name = model_name.rsplit('/').next().unwrap_or(model_name);
+    if name.contains(':') {
+        model_name.to_string()
+    } else {
+        format!("{model_name}:latest")
+    }
+}
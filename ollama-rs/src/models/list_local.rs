@@ -6,20 +6,15 @@ use super::LocalModel;
 
 impl Ollama {
     pub async fn list_local_models(&self) -> crate::error::Result<Vec<LocalModel>> {
-        let url = format!("{}api/tags", self.url_str());
-        let builder = self.reqwest_client.get(url);
+        let builder = self.request(reqwest::Method::GET, "api/tags");
 
-        #[cfg(feature = "headers")]
-        let builder = builder.headers(self.request_headers.clone());
-
-        let res = builder.send().await?;
+        let res = self.send(builder).await?;
 
         if !res.status().is_success() {
-            return Err(OllamaError::Other(res.text().await?));
+            return Err(OllamaError::from_http_response(res).await);
         }
 
-        let res = res.bytes().await?;
-        let res = serde_json::from_slice::<ListLocalModelsResponse>(&res)?;
+        let res = crate::error::decode_body::<ListLocalModelsResponse>(res).await?;
 
         Ok(res.models)
     }
@@ -0,0 +1,82 @@
+#[cfg(any(feature = "time", feature = "chrono"))]
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{error::OllamaError, Ollama, Timestamp};
+
+use super::{ByteSize, ModelDetails};
+
+impl Ollama {
+    /// Lists the models currently loaded in memory.
+    pub async fn list_running_models(&self) -> crate::error::Result<Vec<RunningModel>> {
+        let builder = self.request(reqwest::Method::GET, "api/ps");
+
+        let res = self.send(builder).await?;
+
+        if !res.status().is_success() {
+            return Err(OllamaError::from_http_response(res).await);
+        }
+
+        let res = crate::error::decode_body::<ListRunningModelsResponse>(res).await?;
+
+        Ok(res.models)
+    }
+}
+
+/// A response from Ollama containing the list of running models.
+#[derive(Debug, Clone, Deserialize)]
+struct ListRunningModelsResponse {
+    models: Vec<RunningModel>,
+}
+
+/// A model loaded in memory.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct RunningModel {
+    pub name: String,
+    #[serde(default)]
+    pub model: String,
+    /// The memory used by the model, in RAM and VRAM.
+    pub size: ByteSize,
+    /// The part of `size` in VRAM.
+    #[serde(default)]
+    pub size_vram: ByteSize,
+    #[serde(default)]
+    pub digest: String,
+    #[serde(default)]
+    pub details: ModelDetails,
+    /// When the model will be unloaded, unless used again.
+    #[cfg_attr(feature = "utoipa", schema(value_type = String, format = DateTime))]
+    pub expires_at: Timestamp,
+}
+
+impl RunningModel {
+    /// The fraction of the model in VRAM, from 0 to 1.
+    pub fn gpu_fraction(&self) -> f64 {
+        if self.size.0 == 0 {
+            return 0.0;
+        }
+        (self.size_vram.0 as f64 / self.size.0 as f64).min(1.0)
+    }
+
+    /// Where the model runs, as shown by `ollama ps`: `100% GPU`, `100% CPU` or
+    /// e.g. `48%/52% CPU/GPU`.
+    pub fn processor(&self) -> String {
+        let gpu = (self.gpu_fraction() * 100.0).round() as u32;
+        match gpu {
+            100 => "100% GPU".to_string(),
+            0 => "100% CPU".to_string(),
+            gpu => format!("{}%/{gpu}% CPU/GPU", 100 - gpu),
+        }
+    }
+
+    /// The time left before the model is unloaded, or `None` if it already expired.
+    #[cfg_attr(docsrs, doc(cfg(any(feature = "time", feature = "chrono"))))]
+    #[cfg(any(feature = "time", feature = "chrono"))]
+    pub fn expires_in(&self) -> Option<Duration> {
+        SystemTime::from(self.expires_at.clone())
+            .duration_since(SystemTime::now())
+            .ok()
+    }
+}
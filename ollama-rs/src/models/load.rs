@@ -0,0 +1,63 @@
+use std::time::{Duration, Instant};
+
+use crate::{
+    generation::{completion::request::GenerationRequest, parameters::KeepAlive},
+    Ollama,
+};
+
+impl Ollama {
+    /// Loads a model into memory without generating anything, so that the next request
+    /// doesn't wait for it. Returns the time it took.
+    pub async fn load_model(&self, model_name: String) -> crate::error::Result<Duration> {
+        let start = Instant::now();
+        // A generation without a prompt only loads the model
+        self.generate(GenerationRequest::new(model_name, ""))
+            .await?;
+        Ok(start.elapsed())
+    }
+
+    /// Unloads a model from memory.
+    pub async fn unload_model(&self, model_name: String) -> crate::error::Result<()> {
+        self.generate(
+            GenerationRequest::new(model_name, "").keep_alive(KeepAlive::UnloadOnCompletion),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Loads models concurrently, at most `concurrency` at a time, e.g. at the startup of a
+    /// service. Returns the outcome of each model, in the order of `model_names`.
+    pub async fn warmup_models(
+        &self,
+        model_names: impl IntoIterator<Item = impl Into<String>>,
+        concurrency: usize,
+    ) -> Vec<ModelWarmup> {
+        let futures = model_names
+            .into_iter()
+            .map(|model_name| {
+                let model_name = model_name.into();
+                Box::pin(async move {
+                    let result = self.load_model(model_name.clone()).await;
+                    ModelWarmup { model_name, result }
+                })
+            })
+            .collect();
+
+        crate::runtime::join_all(futures, concurrency.max(1)).await
+    }
+}
+
+/// The outcome of loading a model with [`Ollama::warmup_models`].
+#[derive(Debug)]
+pub struct ModelWarmup {
+    pub model_name: String,
+    /// The time it took to load the model, or the error that prevented it.
+    pub result: crate::error::Result<Duration>,
+}
+
+impl ModelWarmup {
+    /// Whether the model was loaded.
+    pub fn is_loaded(&self) -> bool {
+        self.result.is_ok()
+    }
+}
@@ -0,0 +1,442 @@
+//! Parsing and writing of Modelfiles, the recipes models are built from.
+//!
+//! Deriving a model that is the same as another one, but with a lower temperature:
+//!
+//! ```no_run
+//! # async fn run() -> ollama_rs::error::Result<()> {
+//! use ollama_rs::Ollama;
+//!
+//! let ollama = Ollama::default();
+//! let mut modelfile = ollama
+//!     .show_model_info("llama3.2".into())
+//!     .await?
+//!     .parse_modelfile()?;
+//! modelfile.from = Some("llama3.2".into());
+//! modelfile.set_parameter("temperature", "0.3");
+//! println!("{modelfile}");
+//!
+//! ollama
+//!     .create_model(modelfile.create_request("llama3.2-precise".into())?)
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{fmt, path::Path, str::FromStr};
+
+use serde_json::{Map, Value};
+
+use crate::{
+    error::OllamaError,
+    generation::chat::{ChatMessage, MessageRole},
+    models::{create::CreateModelRequest, ModelInfo, ModelOptions},
+};
+
+/// A parsed Modelfile.
+///
+/// Parsed with [`str::parse`] and written back with [`fmt::Display`]. Comments aren't kept,
+/// and instructions are written in a fixed order.
+#[derive(Debug, Clone, Default)]
+pub struct Modelfile {
+    /// The model, or the path of the weights, the model is built from (`FROM`).
+    pub from: Option<String>,
+    /// The version of Ollama the model requires (`REQUIRES`).
+    pub requires: Option<String>,
+    /// The paths of the LoRA adapters applied to the model (`ADAPTER`).
+    pub adapters: Vec<String>,
+    /// The prompt template (`TEMPLATE`).
+    pub template: Option<String>,
+    /// The system prompt (`SYSTEM`).
+    pub system: Option<String>,
+    /// The parameters, by name and value, in order. A parameter may be repeated, like `stop`
+    /// (`PARAMETER`).
+    pub parameters: Vec<(String, String)>,
+    /// The licenses (`LICENSE`).
+    pub licenses: Vec<String>,
+    /// The conversation the model starts with (`MESSAGE`).
+    pub messages: Vec<ChatMessage>,
+}
+
+impl Modelfile {
+    /// The first value of a parameter.
+    pub fn parameter(&self, name: &str) -> Option<&str> {
+        self.parameters
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Sets a parameter, replacing its values.
+    pub fn set_parameter(&mut self, name: impl Into<String>, value: impl Into<String>) {
+        let name = name.into();
+        self.remove_parameter(&name);
+        self.parameters.push((name, value.into()));
+    }
+
+    /// Adds a value to a parameter that may be repeated, like `stop`.
+    pub fn add_parameter(&mut self, name: impl Into<String>, value: impl Into<String>) {
+        self.parameters.push((name.into(), value.into()));
+    }
+
+    pub fn remove_parameter(&mut self, name: &str) {
+        self.parameters
+            .retain(|(n, _)| !n.eq_ignore_ascii_case(name));
+    }
+
+    /// The parameters as [`ModelOptions`]. Parameters that `ModelOptions` doesn't have are
+    /// left out.
+    pub fn options(&self) -> crate::error::Result<ModelOptions> {
+        let mut options = Map::new();
+        for (name, value) in &self.parameters {
+            let name = name.to_ascii_lowercase();
+            if name == "stop" {
+                let stop = options
+                    .entry(name)
+                    .or_insert_with(|| Value::Array(Vec::new()));
+                if let Value::Array(stop) = stop {
+                    stop.push(Value::String(value.clone()));
+                }
+                continue;
+            }
+
+            let value = if let Ok(n) = value.parse::<i64>() {
+                Value::from(n)
+            } else if let Ok(n) = value.parse::<f64>() {
+                Value::from(n)
+            } else if let Ok(b) = value.parse::<bool>() {
+                Value::Bool(b)
+            } else {
+                Value::String(value.clone())
+            };
+            options.insert(name, value);
+        }
+
+        serde_json::from_value(Value::Object(options))
+            .map_err(|e| OllamaError::Other(format!("Invalid Modelfile parameter: {e}")))
+    }
+
+    /// A request creating a model named `model_name` from this Modelfile.
+    ///
+    /// The weights and the adapters must be models or blobs already on the server, as in the
+    /// Modelfiles of [`ModelInfo`]: a file path naming a blob, like
+    /// `/root/.ollama/models/blobs/sha256-8f3a...`, is sent as its digest.
+    pub fn create_request(&self, model_name: String) -> crate::error::Result<CreateModelRequest> {
+        let mut request = CreateModelRequest::new(model_name);
+
+        if let Some(from) = &self.from {
+            request = match blob(from) {
+                Some((file_name, digest)) => request.file(file_name, digest),
+                None if is_path(from) => {
+                    return Err(OllamaError::Other(format!(
+                        "The model can't be created from {from:?}: only blobs on the server are supported"
+                    )));
+                }
+                None => request.from_model(from.clone()),
+            };
+        }
+        for adapter in &self.adapters {
+            let (file_name, digest) = blob(adapter).ok_or_else(|| {
+                OllamaError::Other(format!(
+                    "The adapter {adapter:?} isn't a blob on the server"
+                ))
+            })?;
+            request = request.adapter(file_name, digest);
+        }
+        if let Some(template) = &self.template {
+            request = request.template(template.clone());
+        }
+        if let Some(system) = &self.system {
+            request = request.system(system.clone());
+        }
+        if !self.parameters.is_empty() {
+            request = request.parameters(self.options()?);
+        }
+        if !self.licenses.is_empty() {
+            request = request.licenses(self.licenses.clone());
+        }
+        if !self.messages.is_empty() {
+            request = request.messages(self.messages.clone());
+        }
+        Ok(request)
+    }
+}
+
+fn is_path(text: &str) -> bool {
+    text.starts_with(['/', '.', '~']) || text.contains('\\') || text.ends_with(".gguf")
+}
+
+/// The file name and the digest of a blob path, like `.../blobs/sha256-<hex>`.
+pub(crate) fn blob(path: &str) -> Option<(&str, String)> {
+    let file_name = Path::new(path).file_name()?.to_str()?;
+    let hex = file_name.strip_prefix("sha256-")?;
+    (hex.len() == 64 && hex.chars().all(|c| c.is_ascii_hexdigit()))
+        .then(|| (file_name, format!("sha256:{hex}")))
+}
+
+impl ModelInfo {
+    /// Parses the Modelfile of the model. With the `modelfile` feature, [`ModelInfo::modelfile`]
+    /// is still the type of the `modelfile` crate, which this converts.
+    pub fn parse_modelfile(&self) -> crate::error::Result<Modelfile> {
+        #[cfg(not(feature = "modelfile"))]
+        return self.modelfile.parse();
+        #[cfg(feature = "modelfile")]
+        return Ok(self.modelfile.clone().into());
+    }
+}
+
+/// Converts a Modelfile parsed by the `modelfile` crate, like [`ModelInfo::modelfile`] with
+/// the `modelfile` feature.
+#[cfg_attr(docsrs, doc(cfg(feature = "modelfile")))]
+#[cfg(feature = "modelfile")]
+impl From<::modelfile::Modelfile> for Modelfile {
+    fn from(parsed: ::modelfile::Modelfile) -> Self {
+        use ::modelfile::modelfile::Instruction;
+
+        // The `modelfile` crate keeps the quotes around some arguments
+        fn unquote(value: &str) -> String {
+            let value = value.trim();
+            value
+                .strip_prefix("\"\"\"")
+                .and_then(|value| value.strip_suffix("\"\"\""))
+                .or_else(|| value.strip_prefix('"')?.strip_suffix('"'))
+                .unwrap_or(value)
+                .to_string()
+        }
+
+        let mut modelfile = Modelfile::default();
+        for instruction in parsed.instructions() {
+            match instruction {
+                Instruction::Skip => {}
+                Instruction::From(from) => modelfile.from = Some(unquote(&from)),
+                Instruction::Parameter(parameter) => {
+                    let parameter = parameter.to_string();
+                    let (name, value) = parameter.split_once(' ').unwrap_or((&parameter, ""));
+                    modelfile.add_parameter(name, unquote(value));
+                }
+                Instruction::Template(template) => {
+                    modelfile.template = Some(unquote((*template).as_ref()))
+                }
+                Instruction::System(system) => modelfile.system = Some(unquote((*system).as_ref())),
+                Instruction::Adapter(adapter) => {
+                    modelfile.adapters.push(unquote(&adapter.to_string()))
+                }
+                Instruction::License(license) => {
+                    modelfile.licenses.push(unquote((*license).as_ref()))
+                }
+                Instruction::Message(message) => {
+                    let role = match message {
+                        ::modelfile::Message::Assistant(_) => MessageRole::Assistant,
+                        ::modelfile::Message::User(_) => MessageRole::User,
+                        ::modelfile::Message::System(_) => MessageRole::System,
+                    };
+                    modelfile
+                        .messages
+                        .push(ChatMessage::new(role, unquote(&message.content())));
+                }
+            }
+        }
+        modelfile
+    }
+}
+
+fn invalid(line: usize, reason: &str) -> OllamaError {
+    OllamaError::Other(format!("Invalid Modelfile at line {line}: {reason}"))
+}
+
+/// A cursor over the text of a Modelfile.
+struct Parser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.text[self.pos..]
+    }
+
+    fn line(&self) -> usize {
+        self.text[..self.pos].matches('\n').count() + 1
+    }
+
+    fn skip_spaces(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start_matches([' ', '\t']).len();
+    }
+
+    /// Skips blank lines and comments, returning whether there is an instruction left.
+    fn next_instruction(&mut self) -> bool {
+        loop {
+            let rest = self.rest();
+            self.pos += rest.len() - rest.trim_start().len();
+            if !self.rest().starts_with('#') {
+                return !self.rest().is_empty();
+            }
+            self.pos += self.rest().find('\n').unwrap_or(self.rest().len());
+        }
+    }
+
+    /// A word, up to the next whitespace.
+    fn word(&mut self) -> &'a str {
+        self.skip_spaces();
+        let rest = self.rest();
+        let len = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        self.pos += len;
+        &rest[..len]
+    }
+
+    /// The argument of an instruction: a `"""` or `"` quoted string, which may span lines, or
+    /// the rest of the line.
+    fn argument(&mut self) -> crate::error::Result<String> {
+        self.skip_spaces();
+        let line = self.line();
+        let rest = self.rest();
+
+        let value = if let Some(quoted) = rest.strip_prefix("\"\"\"") {
+            let end = quoted
+                .find("\"\"\"")
+                .ok_or_else(|| invalid(line, "unterminated `\"\"\"`"))?;
+            self.pos += 3 + end + 3;
+            quoted[..end].to_string()
+        } else if let Some(quoted) = rest.strip_prefix('"') {
+            let mut value = String::new();
+            let mut chars = quoted.char_indices();
+            let end = loop {
+                match chars.next() {
+                    Some((i, '"')) => break i,
+                    Some((_, '\\')) => match chars.next() {
+                        Some((_, c @ ('"' | '\\'))) => value.push(c),
+                        Some((_, c)) => {
+                            value.push('\\');
+                            value.push(c);
+                        }
+                        None => return Err(invalid(line, "unterminated `\"`")),
+                    },
+                    Some((_, c)) => value.push(c),
+                    None => return Err(invalid(line, "unterminated `\"`")),
+                }
+            };
+            self.pos += 1 + end + 1;
+            value
+        } else {
+            let len = rest.find('\n').unwrap_or(rest.len());
+            self.pos += len;
+            return Ok(rest[..len].trim_end().to_string());
+        };
+
+        self.skip_spaces();
+        let rest = self.rest();
+        if !(rest.is_empty() || rest.starts_with(['\n', '\r'])) {
+            return Err(invalid(
+                self.line(),
+                "unexpected text after a quoted string",
+            ));
+        }
+        Ok(value)
+    }
+}
+
+impl FromStr for Modelfile {
+    type Err = OllamaError;
+
+    fn from_str(text: &str) -> crate::error::Result<Self> {
+        let mut modelfile = Modelfile::default();
+        let mut parser = Parser { text, pos: 0 };
+
+        while parser.next_instruction() {
+            let line = parser.line();
+            let instruction = parser.word().to_ascii_uppercase();
+            match instruction.as_str() {
+                "FROM" => modelfile.from = Some(parser.argument()?),
+                "REQUIRES" => modelfile.requires = Some(parser.argument()?),
+                "ADAPTER" => modelfile.adapters.push(parser.argument()?),
+                "TEMPLATE" => modelfile.template = Some(parser.argument()?),
+                "SYSTEM" => modelfile.system = Some(parser.argument()?),
+                "LICENSE" => modelfile.licenses.push(parser.argument()?),
+                "PARAMETER" => {
+                    let name = parser.word();
+                    if name.is_empty() {
+                        return Err(invalid(line, "PARAMETER without a name"));
+                    }
+                    let value = parser.argument()?;
+                    modelfile.parameters.push((name.to_string(), value));
+                }
+                "MESSAGE" => {
+                    let role = match parser.word().to_ascii_lowercase().as_str() {
+                        "system" => MessageRole::System,
+                        "user" => MessageRole::User,
+                        "assistant" => MessageRole::Assistant,
+                        "tool" => MessageRole::Tool,
+                        role => {
+                            return Err(invalid(line, &format!("unknown message role {role:?}")))
+                        }
+                    };
+                    modelfile
+                        .messages
+                        .push(ChatMessage::new(role, parser.argument()?));
+                }
+                _ => {
+                    return Err(invalid(
+                        line,
+                        &format!("unknown instruction {instruction:?}"),
+                    ))
+                }
+            }
+        }
+
+        Ok(modelfile)
+    }
+}
+
+/// Quotes an argument unless it can be written as is.
+fn quote(value: &str) -> String {
+    let bare = !value.is_empty()
+        && !value.contains(['\n', '\r'])
+        && !value.starts_with(['"', ' ', '\t'])
+        && !value.ends_with([' ', '\t']);
+    // A `"""` quoted string ends at the first `"""`, which a value ending with `"` would
+    // move into it
+    let triple = value.contains('"') && !value.contains("\"\"\"") && !value.ends_with('"');
+    if bare {
+        value.to_string()
+    } else if triple {
+        format!("\"\"\"{value}\"\"\"")
+    } else {
+        format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+}
+
+impl fmt::Display for Modelfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(from) = &self.from {
+            writeln!(f, "FROM {}", quote(from))?;
+        }
+        if let Some(requires) = &self.requires {
+            writeln!(f, "REQUIRES {}", quote(requires))?;
+        }
+        for adapter in &self.adapters {
+            writeln!(f, "ADAPTER {}", quote(adapter))?;
+        }
+        if let Some(template) = &self.template {
+            writeln!(f, "TEMPLATE {}", quote(template))?;
+        }
+        if let Some(system) = &self.system {
+            writeln!(f, "SYSTEM {}", quote(system))?;
+        }
+        for (name, value) in &self.parameters {
+            writeln!(f, "PARAMETER {name} {}", quote(value))?;
+        }
+        for license in &self.licenses {
+            writeln!(f, "LICENSE {}", quote(license))?;
+        }
+        for message in &self.messages {
+            let role = match message.role {
+                MessageRole::System => "system",
+                MessageRole::User => "user",
+                MessageRole::Assistant => "assistant",
+                MessageRole::Tool => "tool",
+            };
+            writeln!(f, "MESSAGE {role} {}", quote(&message.content))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,261 @@
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::error::OllamaError;
+
+/// The registry models are pulled from when their name doesn't name one.
+pub const DEFAULT_REGISTRY: &str = "registry.ollama.ai";
+/// The namespace of the official models of the default registry.
+pub const DEFAULT_NAMESPACE: &str = "library";
+/// The tag of a model whose name doesn't have one.
+pub const DEFAULT_TAG: &str = "latest";
+
+/// A validated model name, of the form `[registry/][namespace/]name[:tag]`, such as
+/// `llama3.2`, `llama3.2:1b`, `jmorgan/mymodel` or `registry.example.com/team/model:v2`.
+///
+/// Model names are parsed with [`str::parse`], so that typos are caught before a request is
+/// sent. They convert to `String`, and are accepted wherever a model name is:
+///
+/// ```
+/// # fn main() -> ollama_rs::error::Result<()> {
+/// use ollama_rs::{generation::completion::request::GenerationRequest, models::ModelName};
+///
+/// let model: ModelName = "llama3.2:1b".parse()?;
+/// assert_eq!(model.name(), "llama3.2");
+/// assert_eq!(model.tag(), Some("1b"));
+///
+/// let request = GenerationRequest::new(model.into(), "Why is the sky blue?");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ModelName {
+    text: String,
+    registry: Option<String>,
+    namespace: Option<String>,
+    name: String,
+    tag: Option<String>,
+}
+
+impl ModelName {
+    /// The registry, if the name has one.
+    pub fn registry(&self) -> Option<&str> {
+        self.registry.as_deref()
+    }
+
+    /// The namespace, if the name has one.
+    pub fn namespace(&self) -> Option<&str> {
+        self.namespace.as_deref()
+    }
+
+    /// The name of the model, without registry, namespace or tag.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The tag, if the name has one.
+    pub fn tag(&self) -> Option<&str> {
+        self.tag.as_deref()
+    }
+
+    /// The model name as written.
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// The same model with another tag.
+    pub fn with_tag(&self, tag: &str) -> crate::error::Result<Self> {
+        let base = match &self.tag {
+            Some(old) => &self.text[..self.text.len() - old.len() - 1],
+            None => &self.text,
+        };
+        format!("{base}:{tag}").parse()
+    }
+
+    /// The name with the registry, namespace and tag filled with their defaults, e.g.
+    /// `registry.ollama.ai/library/llama3.2:latest` for `llama3.2`. Two names refer to the same
+    /// model when their full names are equal.
+    pub fn full_name(&self) -> String {
+        format!(
+            "{}/{}/{}:{}",
+            self.registry().unwrap_or(DEFAULT_REGISTRY),
+            self.namespace().unwrap_or(DEFAULT_NAMESPACE),
+            self.name,
+            self.tag().unwrap_or(DEFAULT_TAG)
+        )
+    }
+
+    /// The name in the form the server lists models with: with a tag, and without the
+    /// default registry and namespace.
+    pub fn short_name(&self) -> String {
+        let mut short = String::new();
+        if let Some(registry) = self.registry().filter(|r| *r != DEFAULT_REGISTRY) {
+            short.push_str(registry);
+            short.push('/');
+        }
+        match self.namespace() {
+            Some(DEFAULT_NAMESPACE) if short.is_empty() => {}
+            Some(namespace) => {
+                short.push_str(namespace);
+                short.push('/');
+            }
+            None => {}
+        }
+        short.push_str(&self.name);
+        short.push(':');
+        short.push_str(self.tag().unwrap_or(DEFAULT_TAG));
+        short
+    }
+}
+
+fn invalid(text: &str, reason: &str) -> OllamaError {
+    OllamaError::Other(format!("Invalid model name {text:?}: {reason}"))
+}
+
+/// Checks a namespace, name or tag: ASCII letters, digits, `_`, `-` and `.`, starting with a
+/// letter or a digit (or `_` for tags).
+fn check_part(text: &str, part: &str, kind: &str, max_len: usize) -> crate::error::Result<()> {
+    let Some(first) = part.chars().next() else {
+        return Err(invalid(text, &format!("the {kind} is empty")));
+    };
+    if part.len() > max_len {
+        return Err(invalid(
+            text,
+            &format!("the {kind} is longer than {max_len} characters"),
+        ));
+    }
+    if !(first.is_ascii_alphanumeric() || (kind == "tag" && first == '_')) {
+        return Err(invalid(
+            text,
+            &format!("the {kind} must start with a letter or a digit"),
+        ));
+    }
+    if let Some(c) = part
+        .chars()
+        .find(|c| !(c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.')))
+    {
+        return Err(invalid(
+            text,
+            &format!("{c:?} is not allowed in the {kind}"),
+        ));
+    }
+    Ok(())
+}
+
+/// Checks a registry: a host name, with an optional port.
+fn check_registry(text: &str, registry: &str) -> crate::error::Result<()> {
+    let (host, port) = match registry.split_once(':') {
+        Some((host, port)) => (host, Some(port)),
+        None => (registry, None),
+    };
+    if host.is_empty()
+        || !host
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_'))
+    {
+        return Err(invalid(text, "the registry isn't a valid host"));
+    }
+    if port.is_some_and(|port| port.parse::<u16>().is_err()) {
+        return Err(invalid(text, "the port of the registry isn't a number"));
+    }
+    Ok(())
+}
+
+impl FromStr for ModelName {
+    type Err = OllamaError;
+
+    fn from_str(text: &str) -> crate::error::Result<Self> {
+        let (path, tag) = match text.rsplit_once(':') {
+            // A colon before the last slash is the port of the registry.
+            Some((path, tag)) if !tag.contains('/') => (path, Some(tag)),
+            _ => (text, None),
+        };
+
+        let parts: Vec<&str> = path.split('/').collect();
+        let (registry, namespace, name) = match parts[..] {
+            [name] => (None, None, name),
+            [namespace, name] => (None, Some(namespace), name),
+            [registry, namespace, name] => (Some(registry), Some(namespace), name),
+            _ => return Err(invalid(text, "too many `/`")),
+        };
+
+        if let Some(registry) = registry {
+            check_registry(text, registry)?;
+        }
+        if let Some(namespace) = namespace {
+            check_part(text, namespace, "namespace", 80)?;
+        }
+        check_part(text, name, "name", 80)?;
+        if let Some(tag) = tag {
+            check_part(text, tag, "tag", 80)?;
+        }
+
+        Ok(Self {
+            text: text.to_string(),
+            registry: registry.map(str::to_string),
+            namespace: namespace.map(str::to_string),
+            name: name.to_string(),
+            tag: tag.map(str::to_string),
+        })
+    }
+}
+
+impl TryFrom<&str> for ModelName {
+    type Error = OllamaError;
+
+    fn try_from(text: &str) -> crate::error::Result<Self> {
+        text.parse()
+    }
+}
+
+impl TryFrom<String> for ModelName {
+    type Error = OllamaError;
+
+    fn try_from(text: String) -> crate::error::Result<Self> {
+        text.parse()
+    }
+}
+
+impl From<ModelName> for String {
+    fn from(name: ModelName) -> Self {
+        name.text
+    }
+}
+
+impl AsRef<str> for ModelName {
+    fn as_ref(&self) -> &str {
+        &self.text
+    }
+}
+
+impl PartialEq<str> for ModelName {
+    fn eq(&self, other: &str) -> bool {
+        self.text == other
+    }
+}
+
+impl PartialEq<&str> for ModelName {
+    fn eq(&self, other: &&str) -> bool {
+        self.text == *other
+    }
+}
+
+impl fmt::Display for ModelName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+impl Serialize for ModelName {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.text)
+    }
+}
+
+impl<'de> Deserialize<'de> for ModelName {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        text.parse().map_err(serde::de::Error::custom)
+    }
+}
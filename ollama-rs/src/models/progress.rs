@@ -0,0 +1,170 @@
+//! Progress bars for pulling and pushing models, rendered with [`indicatif`].
+
+use std::{collections::HashMap, time::Duration};
+
+use futures_util::{Stream, StreamExt};
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+
+use super::{pull::PullModelStatus, push::PushModelStatus};
+use crate::{
+    error::{OllamaError, Result},
+    Ollama,
+};
+
+const LAYER_TEMPLATE: &str =
+    "{msg} [{elapsed_precise}] [{wide_bar}] {bytes}/{total_bytes} ({bytes_per_sec}, {eta})";
+
+/// The status of a transfer, layer by layer.
+trait TransferStatus {
+    fn message(&self) -> &str;
+    fn digest(&self) -> Option<&str>;
+    fn total(&self) -> Option<u64>;
+    fn completed(&self) -> Option<u64>;
+}
+
+impl TransferStatus for PullModelStatus {
+    fn message(&self) -> &str {
+        &self.message
+    }
+
+    fn digest(&self) -> Option<&str> {
+        self.digest.as_deref()
+    }
+
+    fn total(&self) -> Option<u64> {
+        self.total
+    }
+
+    fn completed(&self) -> Option<u64> {
+        self.completed
+    }
+}
+
+impl TransferStatus for PushModelStatus {
+    fn message(&self) -> &str {
+        &self.message
+    }
+
+    fn digest(&self) -> Option<&str> {
+        self.digest.as_deref()
+    }
+
+    fn total(&self) -> Option<u64> {
+        self.total
+    }
+
+    fn completed(&self) -> Option<u64> {
+        self.completed
+    }
+}
+
+impl Ollama {
+    #[cfg_attr(docsrs, doc(cfg(feature = "indicatif")))]
+    /// Pulls a model, rendering a progress bar per layer on stderr, and returns the final
+    /// status. Messages without a layer, such as `verifying sha256 digest`, are shown on a
+    /// spinner.
+    /// - `model_name` - The name of the model to pull.
+    /// - `allow_insecure` - Allow insecure connections to the library. Only use this if you are pulling from your own library during development.
+    pub async fn pull_model_with_progress_bar(
+        &self,
+        model_name: String,
+        allow_insecure: bool,
+    ) -> Result<PullModelStatus> {
+        self.pull_model_with_progress_bars(model_name, allow_insecure, &MultiProgress::new())
+            .await
+    }
+
+    #[cfg_attr(docsrs, doc(cfg(feature = "indicatif")))]
+    /// Pulls a model like [`Ollama::pull_model_with_progress_bar`], adding the progress
+    /// bars to `progress`, e.g. to render them along other bars of the application.
+    pub async fn pull_model_with_progress_bars(
+        &self,
+        model_name: String,
+        allow_insecure: bool,
+        progress: &MultiProgress,
+    ) -> Result<PullModelStatus> {
+        let stream = self.pull_model_stream(model_name, allow_insecure).await?;
+        render(stream, progress).await
+    }
+
+    #[cfg_attr(docsrs, doc(cfg(feature = "indicatif")))]
+    /// Pushes a model, rendering a progress bar per layer on stderr, and returns the final
+    /// status.
+    /// - `model_name` - The name of the model to push in the form of `<namespace>/<model>:<tag>`.
+    /// - `allow_insecure` - Allow insecure connections to the library. Only use this if you are pushing to your library during development.
+    pub async fn push_model_with_progress_bar(
+        &self,
+        model_name: String,
+        allow_insecure: bool,
+    ) -> Result<PushModelStatus> {
+        self.push_model_with_progress_bars(model_name, allow_insecure, &MultiProgress::new())
+            .await
+    }
+
+    #[cfg_attr(docsrs, doc(cfg(feature = "indicatif")))]
+    /// Pushes a model like [`Ollama::push_model_with_progress_bar`], adding the progress
+    /// bars to `progress`.
+    pub async fn push_model_with_progress_bars(
+        &self,
+        model_name: String,
+        allow_insecure: bool,
+        progress: &MultiProgress,
+    ) -> Result<PushModelStatus> {
+        let stream = self.push_model_stream(model_name, allow_insecure).await?;
+        render(stream, progress).await
+    }
+}
+
+/// Renders each status of `stream`, returning the last one.
+async fn render<T: TransferStatus>(
+    mut stream: impl Stream<Item = Result<T>> + Unpin,
+    progress: &MultiProgress,
+) -> Result<T> {
+    let spinner = progress.add(ProgressBar::new_spinner());
+    spinner.enable_steady_tick(Duration::from_millis(100));
+
+    let mut layers = HashMap::<String, ProgressBar>::new();
+    let mut last = None;
+    let result = loop {
+        let status = match stream.next().await {
+            Some(Ok(status)) => status,
+            Some(Err(e)) => break Err(e),
+            None => break last.ok_or_else(|| OllamaError::Other("No status received".into())),
+        };
+
+        match (status.digest(), status.total()) {
+            (Some(digest), Some(total)) => {
+                let bar = layers.entry(digest.to_string()).or_insert_with(|| {
+                    let bar = progress.insert_before(&spinner, ProgressBar::new(total));
+                    bar.set_style(
+                        ProgressStyle::with_template(LAYER_TEMPLATE)
+                            .expect("the template is valid"),
+                    );
+                    bar.set_message(format!("{} {}", status.message(), short(digest)));
+                    bar
+                });
+                bar.set_length(total);
+                bar.set_position(status.completed().unwrap_or_default().min(total));
+            }
+            _ => spinner.set_message(status.message().to_string()),
+        }
+
+        last = Some(status);
+    };
+
+    for bar in layers.values() {
+        bar.finish();
+    }
+    match &result {
+        Ok(status) => spinner.finish_with_message(status.message().to_string()),
+        Err(e) => spinner.abandon_with_message(format!("Error: {e}")),
+    }
+
+    result
+}
+
+/// Shortens a layer digest such as `sha256:4f21…` to its first 12 hex digits.
+fn short(digest: &str) -> &str {
+    let hash = digest.split_once(':').map_or(digest, |(_, hash)| hash);
+    &hash[..hash.len().min(12)]
+}
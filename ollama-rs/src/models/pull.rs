@@ -1,12 +1,12 @@
 use serde::{Deserialize, Serialize};
 
-use crate::{error::OllamaError, Ollama};
+use crate::{error::OllamaError, models::registry::RegistryOptions, Ollama};
 
 /// A stream of `PullModelStatus` objects.
 #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
 #[cfg(feature = "stream")]
 pub type PullModelStatusStream = std::pin::Pin<
-    Box<dyn tokio_stream::Stream<Item = crate::error::Result<PullModelStatus>> + Send>,
+    Box<dyn futures_util::Stream<Item = crate::error::Result<PullModelStatus>> + Send>,
 >;
 
 impl Ollama {
@@ -20,47 +20,89 @@ impl Ollama {
         model_name: String,
         allow_insecure: bool,
     ) -> crate::error::Result<PullModelStatusStream> {
-        use tokio_stream::StreamExt;
-
-        use crate::error::{InternalOllamaError, OllamaError};
+        let options = RegistryOptions::new().insecure(allow_insecure);
+        self.pull_model_stream_with(model_name, &options).await
+    }
 
-        let request = PullModelRequest {
-            model_name,
-            allow_insecure,
-            stream: true,
-        };
+    /// Like [`Ollama::pull_model_stream`], with the registry override and `insecure` flag of
+    /// `options`.
+    #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+    #[cfg(feature = "stream")]
+    pub async fn pull_model_stream_with(
+        &self,
+        model_name: String,
+        options: &RegistryOptions,
+    ) -> crate::error::Result<PullModelStatusStream> {
+        let request = PullModelRequest::new(model_name, options, true)?;
 
-        let url = format!("{}api/pull", self.url_str());
         let serialized = serde_json::to_string(&request)?;
-        let builder = self.reqwest_client.post(url);
-
-        #[cfg(feature = "headers")]
-        let builder = builder.headers(self.request_headers.clone());
+        let builder = self.stream_request(reqwest::Method::POST, "api/pull");
 
-        let res = builder.body(serialized).send().await?;
+        let res = self.send(builder.body(serialized)).await?;
 
         if !res.status().is_success() {
-            return Err(OllamaError::Other(res.text().await?));
+            return Err(OllamaError::from_http_response(res).await);
         }
 
-        let stream = Box::new(res.bytes_stream().map(|res| match res {
-            Ok(bytes) => {
-                let res = serde_json::from_slice::<PullModelStatus>(&bytes);
-                match res {
-                    Ok(res) => Ok(res),
-                    Err(e) => {
-                        let err = serde_json::from_slice::<InternalOllamaError>(&bytes);
-                        match err {
-                            Ok(err) => Err(OllamaError::InternalError(err)),
-                            Err(_) => Err(e.into()),
-                        }
+        let stream =
+            crate::ndjson::decode_stream::<PullModelStatus>(res, self.config.capture_raw_responses);
+
+        Ok(Box::pin(stream))
+    }
+
+    /// Starts pulling a model and waits for the server to start downloading it, to tell how
+    /// much is left to download.
+    ///
+    /// The server doesn't expose the size of a manifest, and downloads the layers of a model
+    /// one after another, only reporting each one once it starts it. The estimate is therefore
+    /// what is left of the first layer not fully downloaded yet, usually the weights that
+    /// make up nearly all of a model: it is a lower bound, missing the size of the smaller
+    /// layers after it. The statuses read meanwhile are replayed by
+    /// [`PreparedPull::into_stream`].
+    #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+    #[cfg(feature = "stream")]
+    pub async fn prepare_pull(
+        &self,
+        model_name: String,
+        options: &RegistryOptions,
+    ) -> crate::error::Result<PreparedPull> {
+        use futures_util::StreamExt;
+
+        let mut stream = self
+            .pull_model_stream_with(model_name.clone(), options)
+            .await?;
+        let mut statuses = Vec::new();
+        let mut layers_seen = false;
+        let mut estimated_download_size = 0;
+
+        while let Some(status) = stream.next().await {
+            let status = status?;
+            match (&status.digest, status.total) {
+                // Layers already downloaded are reported as complete, and skipped
+                (Some(_), Some(total)) => {
+                    layers_seen = true;
+                    estimated_download_size = total.saturating_sub(status.completed.unwrap_or(0));
+                    statuses.push(status);
+                    if estimated_download_size > 0 {
+                        break;
                     }
                 }
+                // A status about the whole model after the layers, like "verifying sha256
+                // digest", means the downloads are over
+                (None, _) if layers_seen => {
+                    statuses.push(status);
+                    break;
+                }
+                _ => statuses.push(status),
             }
-            Err(e) => Err(e.into()),
-        }));
+        }
 
-        Ok(std::pin::Pin::from(stream))
+        Ok(PreparedPull {
+            model_name,
+            estimated_download_size,
+            statuses,
+            stream,
+        })
     }
 
     /// Pull a model with a single response, only the final status will be returned.
@@ -71,27 +113,29 @@ impl Ollama {
         model_name: String,
         allow_insecure: bool,
     ) -> crate::error::Result<PullModelStatus> {
-        let request = PullModelRequest {
-            model_name,
-            allow_insecure,
-            stream: false,
-        };
+        let options = RegistryOptions::new().insecure(allow_insecure);
+        self.pull_model_with(model_name, &options).await
+    }
 
-        let url = format!("{}api/pull", self.url_str());
-        let serialized = serde_json::to_string(&request)?;
-        let builder = self.reqwest_client.post(url);
+    /// Like [`Ollama::pull_model`], with the registry override and `insecure` flag of
+    /// `options`.
+    pub async fn pull_model_with(
+        &self,
+        model_name: String,
+        options: &RegistryOptions,
+    ) -> crate::error::Result<PullModelStatus> {
+        let request = PullModelRequest::new(model_name, options, false)?;
 
-        #[cfg(feature = "headers")]
-        let builder = builder.headers(self.request_headers.clone());
+        let serialized = serde_json::to_string(&request)?;
+        let builder = self.request(reqwest::Method::POST, "api/pull");
 
-        let res = builder.body(serialized).send().await?;
+        let res = self.send(builder.body(serialized)).await?;
 
         if !res.status().is_success() {
-            return Err(OllamaError::Other(res.text().await?));
+            return Err(OllamaError::from_http_response(res).await);
         }
 
-        let res = res.bytes().await?;
-        let res = serde_json::from_slice::<PullModelStatus>(&res)?;
+        let res = crate::error::decode_body::<PullModelStatus>(res).await?;
 
         Ok(res)
     }
@@ -107,8 +151,94 @@ struct PullModelRequest {
     stream: bool,
 }
 
+impl PullModelRequest {
+    fn new(
+        model_name: String,
+        options: &RegistryOptions,
+        stream: bool,
+    ) -> crate::error::Result<Self> {
+        Ok(Self {
+            model_name: options.model_name(model_name)?,
+            allow_insecure: options.insecure,
+            stream,
+        })
+    }
+}
+
+/// A pull started by [`Ollama::prepare_pull`], once the size of the download is estimated.
+///
+/// The server goes on downloading meanwhile: read the statuses with
+/// [`PreparedPull::into_stream`], or drop the pull to abort it.
+///
+/// ```no_run
+/// # async fn run(ollama: ollama_rs::Ollama) -> ollama_rs::error::Result<()> {
+/// use ollama_rs::models::registry::RegistryOptions;
+///
+/// let pull = ollama
+///     .prepare_pull("llama3.2".into(), &RegistryOptions::new())
+///     .await?
+///     .ensure_free_space(|needed| needed < 10_000_000_000)?;
+/// let mut stream = pull.into_stream();
+/// # Ok(())
+/// # }
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+#[cfg(feature = "stream")]
+pub struct PreparedPull {
+    model_name: String,
+    estimated_download_size: u64,
+    statuses: Vec<PullModelStatus>,
+    stream: PullModelStatusStream,
+}
+
+#[cfg(feature = "stream")]
+impl PreparedPull {
+    /// A lower bound of the number of bytes left to download, see [`Ollama::prepare_pull`].
+    /// 0 when the model is already pulled.
+    pub fn estimated_download_size(&self) -> u64 {
+        self.estimated_download_size
+    }
+
+    /// Aborts the pull unless `check`, given the estimated download size, tells there is
+    /// enough free space for it. Aborting closes the connection, which stops the download on
+    /// the server. As the estimate is a lower bound, leave some margin.
+    pub fn ensure_free_space(self, check: impl FnOnce(u64) -> bool) -> crate::error::Result<Self> {
+        if check(self.estimated_download_size) {
+            Ok(self)
+        } else {
+            Err(OllamaError::Other(format!(
+                "Not enough free space to pull {}: {} needed",
+                self.model_name,
+                super::ByteSize(self.estimated_download_size)
+            )))
+        }
+    }
+
+    /// Resumes the pull, starting with the statuses read while estimating its size.
+    pub fn into_stream(self) -> PullModelStatusStream {
+        use futures_util::StreamExt;
+
+        let statuses = futures_util::stream::iter(self.statuses.into_iter().map(Ok));
+        Box::pin(statuses.chain(self.stream))
+    }
+}
+
+#[cfg(feature = "stream")]
+impl std::fmt::Debug for PreparedPull {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PreparedPull")
+            .field("model_name", &self.model_name)
+            .field("estimated_download_size", &self.estimated_download_size)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "stream")]
+impl crate::ndjson::StreamChunk for PullModelStatus {}
+
 /// A pull model status response from Ollama.
 #[derive(Debug, Clone, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct PullModelStatus {
     #[serde(rename = "status")]
     pub message: String,
@@ -1,12 +1,21 @@
+#[cfg(feature = "stream")]
+use std::{
+    collections::BTreeMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+};
+
 use serde::{Deserialize, Serialize};
 
-use crate::{error::OllamaError, Ollama};
+use crate::{error::OllamaError, models::registry::RegistryOptions, Ollama};
 
 /// A stream of `PushModelStatus` objects.
 #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
 #[cfg(feature = "stream")]
 pub type PushModelStatusStream = std::pin::Pin<
-    Box<dyn tokio_stream::Stream<Item = crate::error::Result<PushModelStatus>> + Send>,
+    Box<dyn futures_util::Stream<Item = crate::error::Result<PushModelStatus>> + Send>,
 >;
 
 impl Ollama {
@@ -21,50 +30,80 @@ impl Ollama {
         model_name: String,
         allow_insecure: bool,
     ) -> crate::error::Result<PushModelStatusStream> {
-        use crate::error::OllamaError;
-        use tokio_stream::StreamExt;
+        let options = RegistryOptions::new().insecure(allow_insecure);
+        self.push_model_stream_with(model_name, &options).await
+    }
 
-        let request = PushModelRequest {
-            model_name,
-            allow_insecure,
-            stream: true,
-        };
+    /// Like [`Ollama::push_model_stream`], with the registry override and `insecure` flag of
+    /// `options`.
+    #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+    #[cfg(feature = "stream")]
+    pub async fn push_model_stream_with(
+        &self,
+        model_name: String,
+        options: &RegistryOptions,
+    ) -> crate::error::Result<PushModelStatusStream> {
+        let request = PushModelRequest::new(model_name, options, true)?;
 
-        let url = format!("{}api/push", self.url_str());
         let serialized = serde_json::to_string(&request)?;
-        let builder = self.reqwest_client.post(url);
+        let builder = self.stream_request(reqwest::Method::POST, "api/push");
 
-        #[cfg(feature = "headers")]
-        let builder = builder.headers(self.request_headers.clone());
-
-        let res = builder.body(serialized).send().await?;
+        let res = self.send(builder.body(serialized)).await?;
 
         if !res.status().is_success() {
-            return Err(OllamaError::Other(res.text().await?));
+            return Err(OllamaError::from_http_response(res).await);
         }
 
-        let stream = Box::new(res.bytes_stream().map(|res| match res {
-            Ok(bytes) => {
-                let res = serde_json::from_slice::<PushModelStatus>(&bytes);
-                match res {
-                    Ok(res) => Ok(res),
-                    Err(e) => {
-                        let err =
-                            serde_json::from_slice::<crate::error::InternalOllamaError>(&bytes);
-                        match err {
-                            Ok(err) => Err(OllamaError::InternalError(err)),
-                            Err(_) => Err(e.into()),
+        let stream =
+            crate::ndjson::decode_stream::<PushModelStatus>(res, self.config.capture_raw_responses);
+
+        Ok(Box::pin(stream))
+    }
+
+    /// Like [`Ollama::push_model_stream_with`], along with a handle cancelling the push from
+    /// another task, e.g. when a user presses a "cancel" button.
+    ///
+    /// Cancelling ends the stream at once, closing the connection, which stops the upload on
+    /// the server. The handle tells how much of each layer had been uploaded.
+    #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+    #[cfg(feature = "stream")]
+    pub async fn push_model_stream_cancellable(
+        &self,
+        model_name: String,
+        options: &RegistryOptions,
+    ) -> crate::error::Result<(PushModelStatusStream, PushCancelHandle)> {
+        use futures_util::StreamExt;
+
+        let mut stream = self.push_model_stream_with(model_name, options).await?;
+        let handle = PushCancelHandle::default();
+        let state = handle.state.clone();
+
+        let stream = async_stream::stream! {
+            loop {
+                let next = std::future::poll_fn(|cx| {
+                    state.waker.register(cx.waker());
+                    if state.cancelled.load(Ordering::Acquire) {
+                        return std::task::Poll::Ready(None);
+                    }
+                    stream.poll_next_unpin(cx).map(Some)
+                })
+                .await;
+
+                match next {
+                    // Cancelled: dropping the stream closes the connection
+                    None => return,
+                    Some(None) => return,
+                    Some(Some(status)) => {
+                        if let Ok(status) = &status {
+                            state.record(status);
                         }
+                        yield status;
                     }
                 }
             }
-            Err(e) => Err(OllamaError::Other(format!(
-                "Failed to read response: {}",
-                e
-            ))),
-        }));
+        };
 
-        Ok(std::pin::Pin::from(stream))
+        Ok((Box::pin(stream), handle))
     }
 
     /// Upload a model to a model library. Requires registering for ollama.ai and adding a public key first.
@@ -76,27 +115,29 @@ impl Ollama {
         model_name: String,
         allow_insecure: bool,
     ) -> crate::error::Result<PushModelStatus> {
-        let request = PushModelRequest {
-            model_name,
-            allow_insecure,
-            stream: false,
-        };
+        let options = RegistryOptions::new().insecure(allow_insecure);
+        self.push_model_with(model_name, &options).await
+    }
 
-        let url = format!("{}api/push", self.url_str());
-        let serialized = serde_json::to_string(&request)?;
-        let builder = self.reqwest_client.post(url);
+    /// Like [`Ollama::push_model`], with the registry override and `insecure` flag of
+    /// `options`.
+    pub async fn push_model_with(
+        &self,
+        model_name: String,
+        options: &RegistryOptions,
+    ) -> crate::error::Result<PushModelStatus> {
+        let request = PushModelRequest::new(model_name, options, false)?;
 
-        #[cfg(feature = "headers")]
-        let builder = builder.headers(self.request_headers.clone());
+        let serialized = serde_json::to_string(&request)?;
+        let builder = self.request(reqwest::Method::POST, "api/push");
 
-        let res = builder.body(serialized).send().await?;
+        let res = self.send(builder.body(serialized)).await?;
 
         if !res.status().is_success() {
-            return Err(OllamaError::Other(res.text().await?));
+            return Err(OllamaError::from_http_response(res).await);
         }
 
-        let res = res.bytes().await?;
-        let res = serde_json::from_slice::<PushModelStatus>(&res)?;
+        let res = crate::error::decode_body::<PushModelStatus>(res).await?;
 
         Ok(res)
     }
@@ -112,11 +153,123 @@ struct PushModelRequest {
     stream: bool,
 }
 
+impl PushModelRequest {
+    fn new(
+        model_name: String,
+        options: &RegistryOptions,
+        stream: bool,
+    ) -> crate::error::Result<Self> {
+        Ok(Self {
+            model_name: options.model_name(model_name)?,
+            allow_insecure: options.insecure,
+            stream,
+        })
+    }
+}
+
+#[cfg(feature = "stream")]
+impl crate::ndjson::StreamChunk for PushModelStatus {}
+
 /// A push model status response from Ollama.
 #[derive(Debug, Clone, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct PushModelStatus {
     #[serde(rename = "status")]
     pub message: String,
     pub digest: Option<String>,
     pub total: Option<u64>,
+    pub completed: Option<u64>,
+}
+
+/// Cancels a push started with [`Ollama::push_model_stream_cancellable`]. Clones cancel the
+/// same push.
+#[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+#[cfg(feature = "stream")]
+#[derive(Debug, Clone, Default)]
+pub struct PushCancelHandle {
+    state: Arc<CancelState>,
+}
+
+#[cfg(feature = "stream")]
+#[derive(Debug, Default)]
+struct CancelState {
+    cancelled: AtomicBool,
+    waker: futures_util::task::AtomicWaker,
+    layers: Mutex<BTreeMap<String, LayerProgress>>,
+}
+
+#[cfg(feature = "stream")]
+impl CancelState {
+    /// Remembers the progress of the layer `status` is about.
+    fn record(&self, status: &PushModelStatus) {
+        let Some(digest) = &status.digest else {
+            return;
+        };
+        let mut layers = self.layers.lock().unwrap();
+        let layer = layers
+            .entry(digest.clone())
+            .or_insert_with(|| LayerProgress {
+                digest: digest.clone(),
+                completed: 0,
+                total: 0,
+            });
+        layer.total = status.total.unwrap_or(layer.total);
+        layer.completed = status.completed.unwrap_or(layer.completed);
+    }
+}
+
+#[cfg(feature = "stream")]
+impl PushCancelHandle {
+    /// Stops the push, returning how much of each layer had been uploaded. Cancelling a push
+    /// that is over does nothing.
+    pub fn cancel(&self) -> PushProgress {
+        self.state.cancelled.store(true, Ordering::Release);
+        self.state.waker.wake();
+        self.progress()
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.state.cancelled.load(Ordering::Acquire)
+    }
+
+    /// How much of each layer has been uploaded so far.
+    pub fn progress(&self) -> PushProgress {
+        PushProgress {
+            layers: self
+                .state
+                .layers
+                .lock()
+                .unwrap()
+                .values()
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+/// How much of a model has been uploaded, layer by layer.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PushProgress {
+    /// The layers the server reported progress for, by digest.
+    pub layers: Vec<LayerProgress>,
+}
+
+impl PushProgress {
+    /// The bytes uploaded, over every layer.
+    pub fn completed(&self) -> u64 {
+        self.layers.iter().map(|layer| layer.completed).sum()
+    }
+
+    /// The size of the layers the server reported progress for.
+    pub fn total(&self) -> u64 {
+        self.layers.iter().map(|layer| layer.total).sum()
+    }
+}
+
+/// How much of a layer has been uploaded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LayerProgress {
+    pub digest: String,
+    pub completed: u64,
+    pub total: u64,
 }
@@ -0,0 +1,70 @@
+use crate::models::name::{ModelName, DEFAULT_NAMESPACE};
+
+/// Options of pulls and pushes against private or air-gapped registries, used with
+/// [`Ollama::pull_model_with`](crate::Ollama::pull_model_with) and
+/// [`Ollama::push_model_with`](crate::Ollama::push_model_with).
+///
+/// The API doesn't take registry credentials: the server authenticates to registries
+/// itself, e.g. with its key for ollama.com.
+///
+/// ```no_run
+/// # async fn run() -> ollama_rs::error::Result<()> {
+/// use ollama_rs::{models::registry::RegistryOptions, Ollama};
+///
+/// let options = RegistryOptions::new()
+///     .registry("registry.internal:5000")
+///     .insecure(true);
+///
+/// // Pulls `registry.internal:5000/library/llama3.2:1b`
+/// Ollama::default()
+///     .pull_model_with("llama3.2:1b".into(), &options)
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct RegistryOptions {
+    pub(crate) insecure: bool,
+    registry: Option<String>,
+}
+
+impl RegistryOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allows plain HTTP and unverified TLS certificates for the registry. Only use this with
+    /// registries you control. (Default: false)
+    pub fn insecure(mut self, insecure: bool) -> Self {
+        self.insecure = insecure;
+        self
+    }
+
+    /// Pulls from or pushes to this registry host, with an optional port, instead of the one
+    /// named by the model, e.g. a mirror of the default registry.
+    pub fn registry(mut self, registry: impl Into<String>) -> Self {
+        self.registry = Some(registry.into());
+        self
+    }
+
+    /// The name the model is pulled or pushed as, with the registry replaced if overridden.
+    pub(crate) fn model_name(&self, model_name: String) -> crate::error::Result<String> {
+        let Some(registry) = &self.registry else {
+            return Ok(model_name);
+        };
+
+        let name: ModelName = model_name.parse()?;
+        let mut full = format!(
+            "{registry}/{}/{}",
+            name.namespace().unwrap_or(DEFAULT_NAMESPACE),
+            name.name()
+        );
+        if let Some(tag) = name.tag() {
+            full.push(':');
+            full.push_str(tag);
+        }
+        // Validates the registry
+        full.parse::<ModelName>()?;
+        Ok(full)
+    }
+}
@@ -7,21 +7,16 @@ use super::ModelInfo;
 impl Ollama {
     /// Show details about a model including modelfile, template, parameters, license, and system prompt.
     pub async fn show_model_info(&self, model_name: String) -> crate::error::Result<ModelInfo> {
-        let url = format!("{}api/show", self.url_str());
         let serialized = serde_json::to_string(&ModelInfoRequest { model_name })?;
-        let builder = self.reqwest_client.post(url);
+        let builder = self.request(reqwest::Method::POST, "api/show");
 
-        #[cfg(feature = "headers")]
-        let builder = builder.headers(self.request_headers.clone());
-
-        let res = builder.body(serialized).send().await?;
+        let res = self.send(builder.body(serialized)).await?;
 
         if !res.status().is_success() {
-            return Err(OllamaError::Other(res.text().await?));
+            return Err(OllamaError::from_http_response(res).await);
         }
 
-        let res = res.bytes().await?;
-        let res = serde_json::from_slice::<ModelInfo>(&res)?;
+        let res = crate::error::decode_body::<ModelInfo>(res).await?;
 
         Ok(res)
     }
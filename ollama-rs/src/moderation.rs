@@ -0,0 +1,486 @@
+//! Filters of the content of prompts and responses, able to block, rewrite or annotate it.
+//!
+//! Filters are set on the client with [`OllamaBuilder::content_filter`](crate::OllamaBuilder::content_filter),
+//! and run on every generation and chat request: on the prompt, or the last message, before
+//! it is sent, and on the response once received. Streamed responses are filtered as they
+//! come, holding back the end of the text a filter may still match, see
+//! [`ContentFilter::held_back`]. A blocked request or response fails with
+//! [`OllamaError::ContentBlocked`], and annotations are gathered in the `annotations` of the
+//! response.
+//!
+//! ```no_run
+//! # fn main() -> ollama_rs::error::Result<()> {
+//! use ollama_rs::{moderation::KeywordFilter, Ollama};
+//!
+//! let ollama = Ollama::builder()
+//!     .content_filter(
+//!         KeywordFilter::new()
+//!             .block("ignore previous instructions")
+//!             .mask("hunter2", "[password]")
+//!             .flag("refund", "mentions refunds"),
+//!     )
+//!     .build()?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{fmt, sync::Arc};
+
+use crate::{
+    error::{OllamaError, Result},
+    generation::{
+        chat::{request::ChatMessageRequest, ChatMessageResponse},
+        completion::GenerationResponse,
+    },
+};
+
+/// What a [`ContentFilter`] decided about a piece of content.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FilterOutcome {
+    /// Why the content is blocked, if it is.
+    pub blocked: Option<String>,
+    /// The content to use instead, if it is rewritten.
+    pub rewritten: Option<String>,
+    /// Notes about the content, passed along with the response.
+    pub annotations: Vec<String>,
+}
+
+impl FilterOutcome {
+    /// Lets the content through as-is.
+    pub fn allow() -> Self {
+        Self::default()
+    }
+
+    pub fn block(reason: impl Into<String>) -> Self {
+        Self {
+            blocked: Some(reason.into()),
+            ..Self::default()
+        }
+    }
+
+    pub fn rewrite(text: impl Into<String>) -> Self {
+        Self {
+            rewritten: Some(text.into()),
+            ..Self::default()
+        }
+    }
+
+    pub fn annotate(mut self, annotation: impl Into<String>) -> Self {
+        self.annotations.push(annotation.into());
+        self
+    }
+}
+
+/// A check of prompts and responses, e.g. a moderation policy.
+///
+/// Both methods allow everything by default, so that a filter may only implement one.
+pub trait ContentFilter: Send + Sync {
+    /// Checks a prompt, or the last message of a chat, before it is sent.
+    fn filter_prompt(&self, _prompt: &str) -> FilterOutcome {
+        FilterOutcome::allow()
+    }
+
+    /// Checks a response, or the text of a streamed response no longer held back.
+    fn filter_response(&self, _response: &str) -> FilterOutcome {
+        FilterOutcome::allow()
+    }
+
+    /// The length of the end of a streamed response so far to hold back until more is
+    /// generated, because content the filter looks for may start in it. The text held back
+    /// is checked along with the next chunk. (Default: 0, each chunk is checked on its own)
+    fn held_back(&self, _response: &str) -> usize {
+        0
+    }
+}
+
+/// The filters of a client, run in the order they were added.
+#[derive(Clone, Default)]
+pub(crate) struct ContentFilters(Vec<Arc<dyn ContentFilter>>);
+
+impl fmt::Debug for ContentFilters {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ContentFilters({})", self.0.len())
+    }
+}
+
+impl ContentFilters {
+    pub(crate) fn push(&mut self, filter: Arc<dyn ContentFilter>) {
+        self.0.push(filter);
+    }
+
+    /// Runs the prompt filters on `text`, rewriting it in place. Returns the annotations.
+    pub(crate) fn prompt(&self, text: &mut String) -> Result<Vec<String>> {
+        self.run(text, |filter, text| filter.filter_prompt(text))
+    }
+
+    /// Runs the response filters on `text`, rewriting it in place. Returns the annotations.
+    pub(crate) fn response(&self, text: &mut String) -> Result<Vec<String>> {
+        self.run(text, |filter, text| filter.filter_response(text))
+    }
+
+    fn run(
+        &self,
+        text: &mut String,
+        check: impl Fn(&dyn ContentFilter, &str) -> FilterOutcome,
+    ) -> Result<Vec<String>> {
+        let mut annotations = Vec::new();
+        for filter in &self.0 {
+            let outcome = check(filter.as_ref(), text);
+            if let Some(reason) = outcome.blocked {
+                return Err(OllamaError::ContentBlocked { reason });
+            }
+            if let Some(rewritten) = outcome.rewritten {
+                *text = rewritten;
+            }
+            annotations.extend(outcome.annotations);
+        }
+        Ok(annotations)
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Adds `text`, a chunk of a streamed response, to the text `held` back, and replaces it
+    /// with the filtered text no filter holds back anymore. Returns the annotations.
+    #[cfg(feature = "stream")]
+    fn push_chunk(&self, held: &mut String, text: &mut String, done: bool) -> Result<Vec<String>> {
+        held.push_str(text);
+        let hold = match done {
+            true => 0,
+            false => self
+                .0
+                .iter()
+                .map(|filter| filter.held_back(held))
+                .max()
+                .unwrap_or(0),
+        };
+        let end = crate::floor_char_boundary(held, held.len().saturating_sub(hold));
+
+        *text = held.drain(..end).collect();
+        if text.is_empty() {
+            return Ok(Vec::new());
+        }
+        self.response(text)
+    }
+}
+
+enum Matcher {
+    /// ASCII lowercase
+    Keyword(String),
+    #[cfg(feature = "regex")]
+    Pattern(regex::Regex),
+}
+
+impl Matcher {
+    fn keyword(keyword: &str) -> Self {
+        Self::Keyword(keyword.to_ascii_lowercase())
+    }
+
+    /// The byte ranges of the matches in `text`.
+    fn find(&self, text: &str) -> Vec<std::ops::Range<usize>> {
+        match self {
+            Self::Keyword(keyword) if keyword.is_empty() => Vec::new(),
+            // ASCII lowercasing keeps the byte offsets of the text
+            Self::Keyword(keyword) => text
+                .to_ascii_lowercase()
+                .match_indices(keyword.as_str())
+                .map(|(start, matched)| start..start + matched.len())
+                .collect(),
+            #[cfg(feature = "regex")]
+            Self::Pattern(pattern) => pattern.find_iter(text).map(|m| m.range()).collect(),
+        }
+    }
+
+    /// The length of the longest end of `text` that a keyword starts with, or `hold_back`
+    /// for patterns.
+    fn held_back(&self, text: &str, _hold_back: usize) -> usize {
+        match self {
+            Self::Keyword(keyword) => (1..keyword.len())
+                .rev()
+                .find(|len| {
+                    keyword.is_char_boundary(*len)
+                        && text.len() >= *len
+                        && text.is_char_boundary(text.len() - len)
+                        && text[text.len() - len..].eq_ignore_ascii_case(&keyword[..*len])
+                })
+                .unwrap_or(0),
+            #[cfg(feature = "regex")]
+            Self::Pattern(_) => {
+                text.len() - crate::floor_char_boundary(text, text.len().saturating_sub(_hold_back))
+            }
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Self::Keyword(keyword) => format!("{keyword:?}"),
+            #[cfg(feature = "regex")]
+            Self::Pattern(pattern) => format!("/{pattern}/"),
+        }
+    }
+}
+
+enum Rule {
+    Block(Matcher),
+    Mask(Matcher, String),
+    Flag(Matcher, String),
+}
+
+/// A [`ContentFilter`] blocking, masking or flagging keywords, matched regardless of ASCII
+/// case, and regular expressions with the `regex` feature. It applies to prompts and
+/// responses alike.
+///
+/// In streamed responses, text that may be the beginning of a keyword is held back until it
+/// is known not to be, so that keywords split across chunks are matched. Regular expressions
+/// can't be anticipated: hold back the end of the response with
+/// [`KeywordFilter::hold_back`] for their matches up to that length to be found across
+/// chunks.
+#[derive(Default)]
+pub struct KeywordFilter {
+    rules: Vec<Rule>,
+    hold_back: usize,
+}
+
+impl KeywordFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Blocks content containing `keyword`.
+    pub fn block(mut self, keyword: &str) -> Self {
+        self.rules.push(Rule::Block(Matcher::keyword(keyword)));
+        self
+    }
+
+    /// Replaces `keyword` with `replacement`.
+    pub fn mask(mut self, keyword: &str, replacement: impl Into<String>) -> Self {
+        self.rules
+            .push(Rule::Mask(Matcher::keyword(keyword), replacement.into()));
+        self
+    }
+
+    /// Annotates content containing `keyword` with `annotation`.
+    pub fn flag(mut self, keyword: &str, annotation: impl Into<String>) -> Self {
+        self.rules
+            .push(Rule::Flag(Matcher::keyword(keyword), annotation.into()));
+        self
+    }
+
+    /// Blocks content matching `pattern`.
+    #[cfg_attr(docsrs, doc(cfg(feature = "regex")))]
+    #[cfg(feature = "regex")]
+    pub fn block_pattern(mut self, pattern: regex::Regex) -> Self {
+        self.rules.push(Rule::Block(Matcher::Pattern(pattern)));
+        self
+    }
+
+    /// Replaces the matches of `pattern` with `replacement`.
+    #[cfg_attr(docsrs, doc(cfg(feature = "regex")))]
+    #[cfg(feature = "regex")]
+    pub fn mask_pattern(mut self, pattern: regex::Regex, replacement: impl Into<String>) -> Self {
+        self.rules
+            .push(Rule::Mask(Matcher::Pattern(pattern), replacement.into()));
+        self
+    }
+
+    /// Annotates content matching `pattern` with `annotation`.
+    #[cfg_attr(docsrs, doc(cfg(feature = "regex")))]
+    #[cfg(feature = "regex")]
+    pub fn flag_pattern(mut self, pattern: regex::Regex, annotation: impl Into<String>) -> Self {
+        self.rules
+            .push(Rule::Flag(Matcher::Pattern(pattern), annotation.into()));
+        self
+    }
+
+    /// Holds back the last `bytes` of streamed responses while regular expressions are set,
+    /// so that their matches up to that length are found across chunks. (Default: 0)
+    #[cfg_attr(docsrs, doc(cfg(feature = "regex")))]
+    #[cfg(feature = "regex")]
+    pub fn hold_back(mut self, bytes: usize) -> Self {
+        self.hold_back = bytes;
+        self
+    }
+
+    fn filter(&self, text: &str) -> FilterOutcome {
+        for rule in &self.rules {
+            if let Rule::Block(matcher) = rule {
+                if !matcher.find(text).is_empty() {
+                    return FilterOutcome::block(format!(
+                        "The content matches the blocked {}",
+                        matcher.describe()
+                    ));
+                }
+            }
+        }
+
+        let mut text = text.to_string();
+        let mut outcome = FilterOutcome::allow();
+        for rule in &self.rules {
+            match rule {
+                Rule::Block(_) => {}
+                Rule::Mask(matcher, replacement) => {
+                    let matches = matcher.find(&text);
+                    if matches.is_empty() {
+                        continue;
+                    }
+                    for range in matches.into_iter().rev() {
+                        text.replace_range(range, replacement);
+                    }
+                    outcome.rewritten = Some(text.clone());
+                }
+                Rule::Flag(matcher, annotation) => {
+                    if !matcher.find(&text).is_empty() {
+                        outcome.annotations.push(annotation.clone());
+                    }
+                }
+            }
+        }
+        outcome
+    }
+}
+
+impl ContentFilter for KeywordFilter {
+    fn filter_prompt(&self, prompt: &str) -> FilterOutcome {
+        self.filter(prompt)
+    }
+
+    fn filter_response(&self, response: &str) -> FilterOutcome {
+        self.filter(response)
+    }
+
+    fn held_back(&self, response: &str) -> usize {
+        self.rules
+            .iter()
+            .map(|rule| match rule {
+                Rule::Block(matcher) | Rule::Mask(matcher, _) | Rule::Flag(matcher, _) => {
+                    matcher.held_back(response, self.hold_back)
+                }
+            })
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// The text, annotations and whether it is the last one, of a response or of a chunk of
+/// a streamed response.
+type EachResponse<'a> = dyn FnMut(&mut String, &mut Vec<String>, bool) -> Result<()> + 'a;
+
+/// A response, or a chunk of a streamed response, whose text can be filtered.
+pub(crate) trait Filtered {
+    /// Calls `f` on every response of the chunk.
+    fn each_response(&mut self, f: &mut EachResponse) -> Result<()>;
+}
+
+impl Filtered for GenerationResponse {
+    fn each_response(&mut self, f: &mut EachResponse) -> Result<()> {
+        f(&mut self.response, &mut self.annotations, self.done)
+    }
+}
+
+impl Filtered for Vec<GenerationResponse> {
+    fn each_response(&mut self, f: &mut EachResponse) -> Result<()> {
+        self.iter_mut().try_for_each(|res| res.each_response(f))
+    }
+}
+
+impl Filtered for ChatMessageResponse {
+    fn each_response(&mut self, f: &mut EachResponse) -> Result<()> {
+        f(&mut self.message.content, &mut self.annotations, self.done)
+    }
+}
+
+impl ContentFilters {
+    /// Runs the response filters on `res`. `prompt_annotations` are added to the first
+    /// response they are given to.
+    pub(crate) fn filter(
+        &self,
+        res: &mut impl Filtered,
+        prompt_annotations: &mut Vec<String>,
+    ) -> Result<()> {
+        res.each_response(&mut |text, annotations, _| {
+            annotations.append(prompt_annotations);
+            if !text.is_empty() {
+                annotations.extend(self.response(text)?);
+            }
+            Ok(())
+        })
+    }
+
+    /// Runs the response filters on the text of `stream` as it comes, which ends once the
+    /// response is blocked. Errors of `stream` are passed along.
+    #[cfg(feature = "stream")]
+    pub(crate) fn filter_stream<T: Filtered + Clone + Send + 'static>(
+        &self,
+        mut stream: std::pin::Pin<Box<dyn futures_util::Stream<Item = Result<T>> + Send>>,
+        mut prompt_annotations: Vec<String>,
+    ) -> std::pin::Pin<Box<dyn futures_util::Stream<Item = Result<T>> + Send>> {
+        use futures_util::StreamExt;
+
+        if self.is_empty() {
+            return stream;
+        }
+
+        let filters = self.clone();
+        Box::pin(async_stream::stream! {
+            let mut held = String::new();
+            let mut last = None;
+            while let Some(item) = stream.next().await {
+                let mut chunk = match item {
+                    Ok(chunk) => chunk,
+                    Err(e) => {
+                        yield Err(e);
+                        continue;
+                    }
+                };
+
+                let filtered = chunk.each_response(&mut |text, annotations, done| {
+                    annotations.append(&mut prompt_annotations);
+                    annotations.extend(filters.push_chunk(&mut held, text, done)?);
+                    Ok(())
+                });
+                if let Err(e) = filtered {
+                    yield Err(e);
+                    return;
+                }
+                last = Some(chunk.clone());
+                yield Ok(chunk);
+            }
+
+            // The stream ended before a chunk marked as done, e.g. dropped by the server
+            if let Some(mut chunk) = last.filter(|_| !held.is_empty()) {
+                let mut rest = Some(std::mem::take(&mut held));
+                let flushed = chunk.each_response(&mut |text, annotations, _| {
+                    annotations.clear();
+                    *text = rest.take().unwrap_or_default();
+                    if !text.is_empty() {
+                        annotations.extend(filters.response(text)?);
+                    }
+                    Ok(())
+                });
+                yield flushed.map(|()| chunk);
+            }
+        })
+    }
+}
+
+impl crate::Ollama {
+    /// Runs the prompt filters of the client on `prompt`, returning the annotations.
+    pub(crate) fn filter_prompt(&self, prompt: &mut String) -> Result<Vec<String>> {
+        if self.config.content_filters.is_empty() {
+            return Ok(Vec::new());
+        }
+        self.config.content_filters.prompt(prompt)
+    }
+
+    /// Runs the prompt filters of the client on the last message of a chat request.
+    pub(crate) fn filter_last_message(
+        &self,
+        request: &mut ChatMessageRequest,
+    ) -> Result<Vec<String>> {
+        match request.messages.last_mut() {
+            Some(message) => self.filter_prompt(&mut message.content),
+            None => Ok(Vec::new()),
+        }
+    }
+}
@@ -0,0 +1,161 @@
+//! Decoding of newline-delimited JSON streams, as sent by every streaming endpoint of Ollama.
+//!
+//! Network frames don't line up with JSON objects: a frame may hold several objects, or
+//! only part of one (possibly cutting a multi-byte UTF-8 character in half), so bytes are
+//! buffered until a complete line is available.
+
+use futures_util::{Stream, StreamExt};
+use serde::de::DeserializeOwned;
+
+use crate::{
+    error::{OllamaError, Result},
+    RawResponse,
+};
+
+/// A chunk of a streamed response.
+pub(crate) trait StreamChunk: DeserializeOwned {
+    /// The generated text carried by this chunk, accumulated to build partial responses.
+    fn text(&self) -> &str {
+        ""
+    }
+
+    /// Attaches the raw line this chunk was decoded from.
+    fn set_raw(&mut self, _raw: RawResponse) {}
+}
+
+/// Splits a stream of bytes into lines.
+#[derive(Debug, Default)]
+pub(crate) struct LineBuffer {
+    buffer: Vec<u8>,
+}
+
+impl LineBuffer {
+    /// Appends `bytes` to the buffer and returns every line completed by them, without
+    /// the line terminator. Blank lines are skipped.
+    pub(crate) fn push(&mut self, bytes: &[u8]) -> Vec<Vec<u8>> {
+        self.buffer.extend_from_slice(bytes);
+
+        let mut lines = Vec::new();
+        let mut start = 0;
+        while let Some(pos) = self.buffer[start..].iter().position(|b| *b == b'\n') {
+            let line = trim(&self.buffer[start..start + pos]);
+            if !line.is_empty() {
+                lines.push(line.to_vec());
+            }
+            start += pos + 1;
+        }
+        self.buffer.drain(..start);
+
+        lines
+    }
+
+    /// Returns what is left in the buffer once the stream has ended, if it isn't blank.
+    pub(crate) fn finish(&mut self) -> Option<Vec<u8>> {
+        let rest = trim(&self.buffer).to_vec();
+        self.buffer.clear();
+        (!rest.is_empty()).then_some(rest)
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.buffer.clear();
+    }
+}
+
+fn trim(bytes: &[u8]) -> &[u8] {
+    let start = bytes
+        .iter()
+        .position(|b| !b.is_ascii_whitespace())
+        .unwrap_or(bytes.len());
+    let end = bytes
+        .iter()
+        .rposition(|b| !b.is_ascii_whitespace())
+        .map_or(start, |i| i + 1);
+    &bytes[start..end]
+}
+
+/// Decodes the body of a streaming response, yielding one item per JSON line.
+///
+/// If the connection breaks, a [`OllamaError::StreamInterrupted`] error carrying the text
+/// received so far is yielded and the stream ends.
+///
+/// If `capture_raw` is set, each chunk is given its raw line along with the response headers.
+/// Errors carry the ID of the request.
+#[allow(unused_mut)]
+pub(crate) fn decode_stream<T: StreamChunk + Send + 'static>(
+    mut res: reqwest::Response,
+    capture_raw: bool,
+) -> impl Stream<Item = Result<T>> + Send {
+    let headers = capture_raw.then(|| res.headers().clone());
+    let request_id = crate::request_id::of(&res);
+    // Keeps the concurrency slot of the request until the stream is over
+    let extensions = res.extensions().clone();
+    // Recorded as cancelled unless the stream is read to its end
+    #[cfg(feature = "audit")]
+    let mut audit = crate::audit::AuditSlot::take(&mut res);
+
+    let stream = async_stream::stream! {
+        let mut lines = LineBuffer::default();
+        // Kept so that the output generated so far can be salvaged if the stream breaks
+        let mut partial_response = String::new();
+        let mut last_chunk = None;
+
+        let mut stream = res.bytes_stream();
+        while let Some(bytes) = stream.next().await {
+            match bytes {
+                Ok(bytes) => {
+                    for line in lines.push(&bytes) {
+                        let mut chunk = crate::error::decode::<T>(&line);
+                        if let Ok(chunk) = &mut chunk {
+                            partial_response.push_str(chunk.text());
+                            last_chunk = Some(String::from_utf8_lossy(&line).into_owned());
+                            if let Some(headers) = &headers {
+                                chunk.set_raw(RawResponse::new(&line, headers.clone()));
+                            }
+                        }
+                        yield chunk;
+                    }
+                }
+                Err(e) => {
+                    // The rest of the buffer is an incomplete line that can't be parsed
+                    lines.clear();
+                    #[cfg(feature = "audit")]
+                    if let Some(audit) = audit.take() {
+                        audit.fail(e.to_string()).await;
+                    }
+                    yield Err(OllamaError::StreamInterrupted {
+                        source: Some(e),
+                        partial_response,
+                        last_chunk,
+                        request_id: None,
+                    });
+                    return;
+                }
+            }
+        }
+
+        if let Some(line) = lines.finish() {
+            let mut chunk = crate::error::decode::<T>(&line);
+            if let (Ok(chunk), Some(headers)) = (&mut chunk, &headers) {
+                chunk.set_raw(RawResponse::new(&line, headers.clone()));
+            }
+            #[cfg(feature = "audit")]
+            if let Some(audit) = &mut audit {
+                audit.usage(&line);
+            }
+            yield chunk;
+        }
+
+        drop(extensions);
+
+        // The token counts are in the last chunk
+        #[cfg(feature = "audit")]
+        if let Some(mut audit) = audit.take() {
+            audit.outcome(crate::audit::AuditOutcome::Success);
+            if let Some(line) = &last_chunk {
+                audit.usage(line.as_bytes());
+            }
+            audit.finish().await;
+        }
+    };
+    stream.map(move |chunk| chunk.map_err(|e| e.with_request_id(request_id.as_deref())))
+}
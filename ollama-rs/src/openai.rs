@@ -0,0 +1,499 @@
+//! Chat over the OpenAI-compatible `/v1/chat/completions` endpoint, used when the client is
+//! configured with [`OllamaBuilder::openai_compatible`](crate::OllamaBuilder::openai_compatible).
+//!
+//! Requests and responses are translated from and to the types of this crate, so that the same
+//! code can talk to Ollama or to any gateway implementing the OpenAI chat API. Options without
+//! an OpenAI equivalent, such as `num_ctx` or `keep_alive`, are not sent.
+
+use std::collections::VecDeque;
+
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+
+use crate::{
+    error::{OllamaError, Result},
+    generation::{
+        chat::{
+            request::ChatMessageRequest, ChatMessage, ChatMessageFinalResponseData,
+            ChatMessageResponse, MessageRole,
+        },
+        done_reason::DoneReason,
+        parameters::FormatType,
+        tools::{ToolCall, ToolCallFunction},
+    },
+    Ollama, RawResponse,
+};
+
+const PATH: &str = "v1/chat/completions";
+
+impl Ollama {
+    pub(crate) async fn openai_chat(
+        &self,
+        request: ChatMessageRequest,
+    ) -> Result<ChatMessageResponse> {
+        let body = serde_json::to_string(&to_openai_request(&request, false))?;
+        let res = self
+            .send(self.request(reqwest::Method::POST, PATH).body(body))
+            .await?;
+
+        if !res.status().is_success() {
+            return Err(OllamaError::from_http_response(res).await);
+        }
+
+        let headers = self
+            .config
+            .capture_raw_responses
+            .then(|| res.headers().clone());
+        let request_id = crate::request_id::of(&res);
+        let bytes = crate::error::read_body(res).await?;
+        let completion = crate::error::decode::<Completion>(&bytes)
+            .map_err(|e| e.with_request_id(request_id.as_deref()))?;
+        let mut res = completion.into_response()?;
+        if let Some(headers) = headers {
+            res.raw = Some(RawResponse::new(&bytes, headers));
+        }
+
+        Ok(res)
+    }
+
+    #[cfg(feature = "stream")]
+    pub(crate) async fn openai_chat_stream(
+        &self,
+        request: ChatMessageRequest,
+    ) -> Result<crate::generation::chat::ChatMessageResponseStream> {
+        let body = serde_json::to_string(&to_openai_request(&request, true))?;
+        let res = self
+            .send(self.stream_request(reqwest::Method::POST, PATH).body(body))
+            .await?;
+
+        if !res.status().is_success() {
+            return Err(OllamaError::from_http_response(res).await);
+        }
+
+        Ok(Box::pin(decode_sse(res, self.config.capture_raw_responses)))
+    }
+}
+
+/// Builds the body of a `/v1/chat/completions` request.
+pub(crate) fn to_openai_request(request: &ChatMessageRequest, stream: bool) -> Value {
+    let mut body = Map::new();
+    body.insert("model".into(), request.model_name.clone().into());
+    body.insert("messages".into(), to_openai_messages(&request.messages));
+    body.insert("stream".into(), stream.into());
+    if stream {
+        body.insert("stream_options".into(), json!({ "include_usage": true }));
+    }
+
+    if !request.tools.is_empty() {
+        // OpenAI expects the lowercase type, unlike the native API
+        let tools = request
+            .tools
+            .iter()
+            .map(|tool| json!({ "type": "function", "function": json!(tool)["function"] }))
+            .collect();
+        body.insert("tools".into(), Value::Array(tools));
+    }
+    if let Some(tool_choice) = &request.tool_choice {
+        body.insert("tool_choice".into(), json!(tool_choice));
+    }
+
+    match &request.format {
+        Some(FormatType::Json) => {
+            body.insert("response_format".into(), json!({ "type": "json_object" }));
+        }
+        Some(format @ FormatType::StructuredJson(_)) => {
+            body.insert(
+                "response_format".into(),
+                json!({
+                    "type": "json_schema",
+                    "json_schema": { "name": "response", "schema": format },
+                }),
+            );
+        }
+        None => {}
+    }
+
+    if let Some(Value::Object(options)) = request.options.as_ref().map(|o| json!(o)) {
+        for (key, value) in options {
+            match key.as_str() {
+                "temperature" | "top_p" | "seed" | "stop" => {
+                    body.insert(key, value);
+                }
+                "num_predict" => {
+                    body.insert("max_tokens".into(), value);
+                }
+                "logit_bias" => {
+                    // Only token ids can be biased through the OpenAI API
+                    let bias: Map<String, Value> = value
+                        .as_array()
+                        .into_iter()
+                        .flatten()
+                        .filter_map(|pair| {
+                            let id = pair.get(0)?.as_u64()?;
+                            Some((id.to_string(), pair.get(1)?.clone()))
+                        })
+                        .collect();
+                    if !bias.is_empty() {
+                        body.insert("logit_bias".into(), bias.into());
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    Value::Object(body)
+}
+
+/// Converts chat messages to the OpenAI shape. Tool calls are given ids, which the following
+/// tool results refer to in the order of the calls.
+pub(crate) fn to_openai_messages(messages: &[ChatMessage]) -> Value {
+    let mut pending_ids = VecDeque::new();
+
+    let messages = messages
+        .iter()
+        .enumerate()
+        .map(|(i, message)| {
+            let mut m = Map::new();
+            m.insert("role".into(), json!(message.role));
+
+            let images = message.images.as_deref().unwrap_or_default();
+            if images.is_empty() {
+                m.insert("content".into(), message.content.clone().into());
+            } else {
+                let mut parts = vec![json!({ "type": "text", "text": message.content })];
+                parts.extend(images.iter().map(|image| {
+                    json!({
+                        "type": "image_url",
+                        "image_url": { "url": format!("data:image/png;base64,{}", image.to_base64()) },
+                    })
+                }));
+                m.insert("content".into(), parts.into());
+            }
+
+            if !message.tool_calls.is_empty() {
+                let calls: Vec<Value> = message
+                    .tool_calls
+                    .iter()
+                    .enumerate()
+                    .map(|(j, call)| {
+                        let id = call.id.clone().unwrap_or_else(|| format!("call_{i}_{j}"));
+                        pending_ids.push_back(id.clone());
+                        json!({
+                            "id": id,
+                            "type": "function",
+                            "function": {
+                                "name": call.function.name,
+                                "arguments": call.function.arguments.to_string(),
+                            },
+                        })
+                    })
+                    .collect();
+                m.insert("tool_calls".into(), calls.into());
+            }
+
+            if message.role == MessageRole::Tool {
+                let pending = pending_ids.pop_front();
+                let id = message
+                    .tool_call_id
+                    .clone()
+                    .or(pending)
+                    .unwrap_or_else(|| format!("call_{i}"));
+                m.insert("tool_call_id".into(), id.into());
+            }
+
+            Value::Object(m)
+        })
+        .collect();
+
+    Value::Array(messages)
+}
+
+#[derive(Deserialize)]
+struct Completion {
+    model: String,
+    #[serde(default)]
+    created: i64,
+    choices: Vec<Choice>,
+    usage: Option<CompletionUsage>,
+}
+
+#[derive(Deserialize)]
+struct Choice {
+    message: CompletionMessage,
+    finish_reason: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct CompletionMessage {
+    content: Option<String>,
+    tool_calls: Option<Vec<CompletionToolCall>>,
+}
+
+#[derive(Deserialize)]
+struct CompletionToolCall {
+    id: Option<String>,
+    function: CompletionFunction,
+}
+
+#[derive(Deserialize)]
+struct CompletionFunction {
+    name: String,
+    #[serde(default)]
+    arguments: String,
+}
+
+#[derive(Deserialize, Clone, Copy)]
+struct CompletionUsage {
+    prompt_tokens: u64,
+    completion_tokens: u64,
+}
+
+impl Completion {
+    fn into_response(self) -> Result<ChatMessageResponse> {
+        let choice = self
+            .choices
+            .into_iter()
+            .next()
+            .ok_or_else(|| OllamaError::Other("The completion has no choices".to_string()))?;
+
+        let mut message = ChatMessage::assistant(choice.message.content.unwrap_or_default());
+        for call in choice.message.tool_calls.into_iter().flatten() {
+            message.tool_calls.push(tool_call(
+                call.id,
+                call.function.name,
+                &call.function.arguments,
+            )?);
+        }
+
+        Ok(ChatMessageResponse {
+            model: self.model,
+            created_at: crate::Timestamp::from_unix_timestamp(self.created),
+            message,
+            done: true,
+            done_reason: choice.finish_reason.map(done_reason),
+            final_data: self.usage.map(final_data),
+            raw: None,
+            annotations: Vec::new(),
+        })
+    }
+}
+
+fn tool_call(id: Option<String>, name: String, arguments: &str) -> Result<ToolCall> {
+    let arguments = if arguments.trim().is_empty() {
+        Value::Object(Map::new())
+    } else {
+        serde_json::from_str(arguments)?
+    };
+    Ok(ToolCall {
+        id,
+        function: ToolCallFunction { name, arguments },
+    })
+}
+
+fn done_reason(finish_reason: String) -> DoneReason {
+    match finish_reason.as_str() {
+        "stop" | "tool_calls" => DoneReason::Stop,
+        "length" => DoneReason::Length,
+        _ => DoneReason::Unknown(finish_reason),
+    }
+}
+
+/// OpenAI responses only count tokens, so durations are left at zero.
+fn final_data(usage: CompletionUsage) -> ChatMessageFinalResponseData {
+    ChatMessageFinalResponseData {
+        total_duration: 0,
+        load_duration: 0,
+        prompt_eval_count: usage.prompt_tokens,
+        prompt_eval_duration: 0,
+        eval_count: usage.completion_tokens,
+        eval_duration: 0,
+    }
+}
+
+#[cfg(feature = "stream")]
+#[derive(Deserialize)]
+struct CompletionChunk {
+    model: String,
+    #[serde(default)]
+    created: i64,
+    #[serde(default)]
+    choices: Vec<ChunkChoice>,
+    usage: Option<CompletionUsage>,
+}
+
+#[cfg(feature = "stream")]
+#[derive(Deserialize)]
+struct ChunkChoice {
+    delta: Delta,
+    finish_reason: Option<String>,
+}
+
+#[cfg(feature = "stream")]
+#[derive(Deserialize)]
+struct Delta {
+    content: Option<String>,
+    tool_calls: Option<Vec<ToolCallDelta>>,
+}
+
+#[cfg(feature = "stream")]
+#[derive(Deserialize)]
+struct ToolCallDelta {
+    #[serde(default)]
+    index: usize,
+    id: Option<String>,
+    function: Option<FunctionDelta>,
+}
+
+#[cfg(feature = "stream")]
+#[derive(Deserialize)]
+struct FunctionDelta {
+    name: Option<String>,
+    arguments: Option<String>,
+}
+
+/// Decodes a stream of server-sent events into chat responses.
+///
+/// Content deltas are yielded as they arrive. Tool calls, which are streamed in fragments, are
+/// only yielded with the last response, along with the finish reason and token counts. A
+/// stream closed before `[DONE]` or a finish reason ends with an
+/// [`OllamaError::StreamInterrupted`] error instead.
+#[cfg(feature = "stream")]
+fn decode_sse(
+    res: reqwest::Response,
+    capture_raw: bool,
+) -> impl futures_util::Stream<Item = Result<ChatMessageResponse>> + Send {
+    use futures_util::StreamExt;
+
+    let headers = capture_raw.then(|| res.headers().clone());
+    let request_id = crate::request_id::of(&res);
+    // Keeps the concurrency slot of the request until the stream is over
+    let extensions = res.extensions().clone();
+
+    let stream = async_stream::stream! {
+        let mut lines = crate::ndjson::LineBuffer::default();
+        let mut partial_response = String::new();
+        let mut last_chunk = None;
+
+        let mut model = String::new();
+        let mut created = 0;
+        let mut finish_reason = None;
+        let mut usage = None;
+        let mut done = false;
+        let mut tool_calls: Vec<(Option<String>, String, String)> = Vec::new();
+
+        let mut stream = res.bytes_stream();
+        'events: while let Some(bytes) = stream.next().await {
+            let bytes = match bytes {
+                Ok(bytes) => bytes,
+                Err(e) => {
+                    lines.clear();
+                    yield Err(OllamaError::StreamInterrupted {
+                        source: Some(e),
+                        partial_response,
+                        last_chunk,
+                        request_id: None,
+                    });
+                    return;
+                }
+            };
+
+            for line in lines.push(&bytes) {
+                // Other fields, such as `event:` and comments, aren't used by chat completions
+                let Some(data) = line.strip_prefix(b"data:") else {
+                    continue;
+                };
+                let data = data.trim_ascii();
+                if data == b"[DONE]" {
+                    done = true;
+                    break 'events;
+                }
+
+                let chunk = match crate::error::decode::<CompletionChunk>(data) {
+                    Ok(chunk) => chunk,
+                    Err(e) => {
+                        yield Err(e);
+                        continue;
+                    }
+                };
+                last_chunk = Some(String::from_utf8_lossy(data).into_owned());
+
+                model = chunk.model;
+                created = chunk.created;
+                usage = chunk.usage.or(usage);
+
+                let Some(choice) = chunk.choices.into_iter().next() else {
+                    continue;
+                };
+                finish_reason = choice.finish_reason.or(finish_reason);
+
+                for delta in choice.delta.tool_calls.into_iter().flatten() {
+                    if tool_calls.len() <= delta.index {
+                        tool_calls.resize(delta.index + 1, Default::default());
+                    }
+                    let call = &mut tool_calls[delta.index];
+                    if delta.id.is_some() {
+                        call.0 = delta.id;
+                    }
+                    if let Some(function) = delta.function {
+                        call.1.push_str(&function.name.unwrap_or_default());
+                        call.2.push_str(&function.arguments.unwrap_or_default());
+                    }
+                }
+
+                let content = choice.delta.content.unwrap_or_default();
+                if content.is_empty() {
+                    continue;
+                }
+                partial_response.push_str(&content);
+
+                yield Ok(ChatMessageResponse {
+                    model: model.clone(),
+                    created_at: crate::Timestamp::from_unix_timestamp(created),
+                    message: ChatMessage::assistant(content),
+                    done: false,
+                    done_reason: None,
+                    final_data: None,
+                    raw: headers
+                        .as_ref()
+                        .map(|headers| RawResponse::new(data, headers.clone())),
+                    annotations: Vec::new(),
+                });
+            }
+        }
+
+        if !done && finish_reason.is_none() {
+            yield Err(OllamaError::StreamInterrupted {
+                source: None,
+                partial_response,
+                last_chunk,
+                request_id: None,
+            });
+            return;
+        }
+
+        let mut message = ChatMessage::assistant(String::new());
+        for (id, name, arguments) in tool_calls {
+            match tool_call(id, name, &arguments) {
+                Ok(call) => message.tool_calls.push(call),
+                Err(e) => {
+                    yield Err(e);
+                    return;
+                }
+            }
+        }
+
+        yield Ok(ChatMessageResponse {
+            model,
+            created_at: crate::Timestamp::from_unix_timestamp(created),
+            message,
+            done: true,
+            done_reason: finish_reason.map(done_reason),
+            final_data: usage.map(final_data),
+            raw: None,
+            annotations: Vec::new(),
+        });
+        drop(extensions);
+    };
+    stream.map(move |res| res.map_err(|e| e.with_request_id(request_id.as_deref())))
+}
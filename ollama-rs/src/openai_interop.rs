@@ -0,0 +1,406 @@
+//! Conversions between the types of this crate and those of [`async_openai`], for applications
+//! migrating from one crate to the other or talking to both Ollama and OpenAI.
+//!
+//! Conversions which can lose information, or fail, are `TryFrom` implementations. Tool call ids
+//! aren't kept by this crate: converted tool calls get generated ids, and converting a whole
+//! [`ChatMessageRequest`] links each tool result to the tool call it answers, in order.
+
+use async_openai::types::chat::{
+    ChatChoice, ChatCompletionMessageToolCall, ChatCompletionMessageToolCalls,
+    ChatCompletionNamedToolChoice, ChatCompletionRequestAssistantMessageContent,
+    ChatCompletionRequestAssistantMessageContentPart, ChatCompletionRequestDeveloperMessageContent,
+    ChatCompletionRequestDeveloperMessageContentPart, ChatCompletionRequestMessage,
+    ChatCompletionRequestSystemMessageContent, ChatCompletionRequestSystemMessageContentPart,
+    ChatCompletionRequestToolMessageContent, ChatCompletionRequestToolMessageContentPart,
+    ChatCompletionRequestUserMessageContent, ChatCompletionRequestUserMessageContentPart,
+    ChatCompletionResponseMessage, ChatCompletionTool, ChatCompletionToolChoiceOption,
+    ChatCompletionTools, CompletionUsage, CreateChatCompletionRequest,
+    CreateChatCompletionResponse, FinishReason, FunctionCall, FunctionName, FunctionObject, Role,
+    ToolChoiceOptions,
+};
+use serde_json::{Map, Value};
+
+use crate::{
+    error::{OllamaError, Result},
+    generation::{
+        chat::{
+            request::ChatMessageRequest, ChatMessage, ChatMessageFinalResponseData,
+            ChatMessageResponse, MessageRole,
+        },
+        done_reason::DoneReason,
+        images::Image,
+        tools::{ToolCall, ToolCallFunction, ToolChoice, ToolInfo},
+    },
+};
+
+impl TryFrom<ChatMessageRequest> for CreateChatCompletionRequest {
+    type Error = OllamaError;
+
+    /// Converts a request the way it is sent in OpenAI-compatible mode, see
+    /// [`Ollama::set_openai_compatible`](crate::Ollama::set_openai_compatible).
+    fn try_from(request: ChatMessageRequest) -> Result<Self> {
+        let mut body = crate::openai::to_openai_request(&request, false);
+        // `stream` is decided by the client sending the request
+        body.as_object_mut().map(|body| body.remove("stream"));
+        Ok(serde_json::from_value(body)?)
+    }
+}
+
+impl TryFrom<ChatMessage> for ChatCompletionRequestMessage {
+    type Error = OllamaError;
+
+    fn try_from(message: ChatMessage) -> Result<Self> {
+        let messages = crate::openai::to_openai_messages(std::slice::from_ref(&message));
+        let message = messages.as_array().and_then(|m| m.first()).cloned();
+        Ok(serde_json::from_value(message.unwrap_or_default())?)
+    }
+}
+
+impl TryFrom<ChatCompletionRequestMessage> for ChatMessage {
+    type Error = OllamaError;
+
+    /// Fails for images given by URL rather than inline, and for audio and file inputs,
+    /// which Ollama doesn't support.
+    fn try_from(message: ChatCompletionRequestMessage) -> Result<Self> {
+        use ChatCompletionRequestMessage as M;
+
+        Ok(match message {
+            M::Developer(m) => {
+                let content = match m.content {
+                    ChatCompletionRequestDeveloperMessageContent::Text(text) => text,
+                    ChatCompletionRequestDeveloperMessageContent::Array(parts) => parts
+                        .into_iter()
+                        .map(|part| match part {
+                            ChatCompletionRequestDeveloperMessageContentPart::Text(t) => t.text,
+                        })
+                        .collect(),
+                };
+                ChatMessage::system(content)
+            }
+            M::System(m) => ChatMessage::system(match m.content {
+                ChatCompletionRequestSystemMessageContent::Text(text) => text,
+                ChatCompletionRequestSystemMessageContent::Array(parts) => parts
+                    .into_iter()
+                    .map(|part| match part {
+                        ChatCompletionRequestSystemMessageContentPart::Text(t) => t.text,
+                    })
+                    .collect(),
+            }),
+            M::User(m) => match m.content {
+                ChatCompletionRequestUserMessageContent::Text(text) => ChatMessage::user(text),
+                ChatCompletionRequestUserMessageContent::Array(parts) => {
+                    let mut content = String::new();
+                    let mut images = Vec::new();
+                    for part in parts {
+                        match part {
+                            ChatCompletionRequestUserMessageContentPart::Text(t) => {
+                                content.push_str(&t.text)
+                            }
+                            ChatCompletionRequestUserMessageContentPart::ImageUrl(image) => {
+                                images.push(image_from_url(&image.image_url.url)?)
+                            }
+                            _ => {
+                                return Err(OllamaError::Other(
+                                    "Only text and image parts can be converted".to_string(),
+                                ))
+                            }
+                        }
+                    }
+                    ChatMessage::user(content).with_images(images)
+                }
+            },
+            M::Assistant(m) => {
+                let content = match m.content {
+                    Some(ChatCompletionRequestAssistantMessageContent::Text(text)) => text,
+                    Some(ChatCompletionRequestAssistantMessageContent::Array(parts)) => parts
+                        .into_iter()
+                        .map(|part| match part {
+                            ChatCompletionRequestAssistantMessageContentPart::Text(t) => t.text,
+                            ChatCompletionRequestAssistantMessageContentPart::Refusal(r) => {
+                                r.refusal
+                            }
+                        })
+                        .collect(),
+                    None => m.refusal.unwrap_or_default(),
+                };
+                let mut message = ChatMessage::assistant(content);
+                for call in m.tool_calls.into_iter().flatten() {
+                    message.tool_calls.push(call.try_into()?);
+                }
+                message
+            }
+            M::Tool(m) => ChatMessage::tool(match m.content {
+                ChatCompletionRequestToolMessageContent::Text(text) => text,
+                ChatCompletionRequestToolMessageContent::Array(parts) => parts
+                    .into_iter()
+                    .map(|part| match part {
+                        ChatCompletionRequestToolMessageContentPart::Text(t) => t.text,
+                    })
+                    .collect(),
+            })
+            .with_tool_call_id(m.tool_call_id),
+            M::Function(m) => ChatMessage::tool(m.content.unwrap_or_default()),
+        })
+    }
+}
+
+/// Extracts the base64 data of a `data:` URL.
+fn image_from_url(url: &str) -> Result<Image> {
+    url.strip_prefix("data:")
+        .and_then(|data| data.split_once(";base64,"))
+        .map(|(_, base64)| Image::from_base64(base64))
+        .ok_or_else(|| {
+            OllamaError::Other("Only images given as base64 data URLs can be converted".to_string())
+        })
+}
+
+impl TryFrom<ChatCompletionResponseMessage> for ChatMessage {
+    type Error = OllamaError;
+
+    fn try_from(message: ChatCompletionResponseMessage) -> Result<Self> {
+        let role = match message.role {
+            Role::System => MessageRole::System,
+            Role::User => MessageRole::User,
+            Role::Assistant => MessageRole::Assistant,
+            Role::Tool | Role::Function => MessageRole::Tool,
+        };
+        let content = message.content.or(message.refusal).unwrap_or_default();
+
+        let mut converted = ChatMessage::new(role, content);
+        for call in message.tool_calls.into_iter().flatten() {
+            converted.tool_calls.push(call.try_into()?);
+        }
+        Ok(converted)
+    }
+}
+
+impl From<ToolCall> for ChatCompletionMessageToolCall {
+    /// A tool call without an id gets an empty one, which OpenAI requires to be set before
+    /// sending it.
+    fn from(call: ToolCall) -> Self {
+        Self {
+            id: call.id.unwrap_or_default(),
+            function: FunctionCall {
+                name: call.function.name,
+                arguments: call.function.arguments.to_string(),
+            },
+        }
+    }
+}
+
+impl TryFrom<ChatCompletionMessageToolCall> for ToolCall {
+    type Error = OllamaError;
+
+    /// Fails if the arguments aren't valid JSON.
+    fn try_from(call: ChatCompletionMessageToolCall) -> Result<Self> {
+        let arguments = if call.function.arguments.trim().is_empty() {
+            Value::Object(Map::new())
+        } else {
+            serde_json::from_str(&call.function.arguments)?
+        };
+        Ok(Self {
+            id: (!call.id.is_empty()).then_some(call.id),
+            function: ToolCallFunction {
+                name: call.function.name,
+                arguments,
+            },
+        })
+    }
+}
+
+impl TryFrom<ChatCompletionMessageToolCalls> for ToolCall {
+    type Error = OllamaError;
+
+    /// Fails for calls of custom tools, which Ollama doesn't support.
+    fn try_from(call: ChatCompletionMessageToolCalls) -> Result<Self> {
+        match call {
+            ChatCompletionMessageToolCalls::Function(call) => call.try_into(),
+            ChatCompletionMessageToolCalls::Custom(call) => Err(OllamaError::Other(format!(
+                "Calls of custom tools can't be converted: {}",
+                call.custom_tool.name
+            ))),
+        }
+    }
+}
+
+impl From<ToolInfo> for ChatCompletionTools {
+    fn from(tool: ToolInfo) -> Self {
+        let value = serde_json::to_value(&tool).unwrap_or_default();
+        let function = &value["function"];
+        ChatCompletionTools::Function(ChatCompletionTool {
+            function: FunctionObject {
+                name: tool.name().to_string(),
+                description: function["description"].as_str().map(str::to_string),
+                parameters: Some(function["parameters"].clone()),
+                strict: None,
+            },
+        })
+    }
+}
+
+impl TryFrom<FunctionObject> for ToolInfo {
+    type Error = OllamaError;
+
+    fn try_from(function: FunctionObject) -> Result<Self> {
+        let parameters = function
+            .parameters
+            .unwrap_or_else(|| serde_json::json!({ "type": "object", "properties": {} }));
+        ToolInfo::from_value(
+            function.name,
+            function.description.unwrap_or_default(),
+            parameters,
+        )
+    }
+}
+
+impl TryFrom<ChatCompletionTools> for ToolInfo {
+    type Error = OllamaError;
+
+    /// Fails for custom tools, which Ollama doesn't support.
+    fn try_from(tool: ChatCompletionTools) -> Result<Self> {
+        match tool {
+            ChatCompletionTools::Function(tool) => tool.function.try_into(),
+            ChatCompletionTools::Custom(tool) => Err(OllamaError::Other(format!(
+                "Custom tools can't be converted: {}",
+                tool.custom.name
+            ))),
+        }
+    }
+}
+
+impl From<ToolChoice> for ChatCompletionToolChoiceOption {
+    fn from(choice: ToolChoice) -> Self {
+        match choice {
+            ToolChoice::Auto => Self::Mode(ToolChoiceOptions::Auto),
+            ToolChoice::None => Self::Mode(ToolChoiceOptions::None),
+            ToolChoice::Required => Self::Mode(ToolChoiceOptions::Required),
+            ToolChoice::Tool(name) => Self::Function(ChatCompletionNamedToolChoice {
+                function: FunctionName { name },
+            }),
+        }
+    }
+}
+
+impl TryFrom<ChatCompletionToolChoiceOption> for ToolChoice {
+    type Error = OllamaError;
+
+    /// Fails for allowed tool lists and custom tools.
+    fn try_from(choice: ChatCompletionToolChoiceOption) -> Result<Self> {
+        match choice {
+            ChatCompletionToolChoiceOption::Mode(ToolChoiceOptions::Auto) => Ok(Self::Auto),
+            ChatCompletionToolChoiceOption::Mode(ToolChoiceOptions::None) => Ok(Self::None),
+            ChatCompletionToolChoiceOption::Mode(ToolChoiceOptions::Required) => Ok(Self::Required),
+            ChatCompletionToolChoiceOption::Function(choice) => {
+                Ok(Self::Tool(choice.function.name))
+            }
+            _ => Err(OllamaError::Other(
+                "Only `auto`, `none`, `required` and function tool choices can be converted"
+                    .to_string(),
+            )),
+        }
+    }
+}
+
+impl TryFrom<CreateChatCompletionResponse> for ChatMessageResponse {
+    type Error = OllamaError;
+
+    /// Converts the first choice. Only token counts are known, so durations are left at zero.
+    fn try_from(response: CreateChatCompletionResponse) -> Result<Self> {
+        let choice = response
+            .choices
+            .into_iter()
+            .next()
+            .ok_or_else(|| OllamaError::Other("The completion has no choices".to_string()))?;
+
+        Ok(ChatMessageResponse {
+            model: response.model,
+            created_at: crate::Timestamp::from_unix_timestamp(response.created.into()),
+            message: choice.message.try_into()?,
+            done: true,
+            done_reason: choice.finish_reason.map(|reason| match reason {
+                FinishReason::Stop | FinishReason::ToolCalls | FinishReason::FunctionCall => {
+                    DoneReason::Stop
+                }
+                FinishReason::Length => DoneReason::Length,
+                FinishReason::ContentFilter => DoneReason::Unknown("content_filter".to_string()),
+            }),
+            final_data: response.usage.map(|usage| ChatMessageFinalResponseData {
+                total_duration: 0,
+                load_duration: 0,
+                prompt_eval_count: usage.prompt_tokens.into(),
+                prompt_eval_duration: 0,
+                eval_count: usage.completion_tokens.into(),
+                eval_duration: 0,
+            }),
+            raw: None,
+            annotations: Vec::new(),
+        })
+    }
+}
+
+impl From<ChatMessageResponse> for CreateChatCompletionResponse {
+    /// Converts a complete response, with the tool calls without an id given generated ones.
+    #[allow(deprecated)]
+    fn from(response: ChatMessageResponse) -> Self {
+        let tool_calls: Vec<_> = response
+            .message
+            .tool_calls
+            .into_iter()
+            .enumerate()
+            .map(|(i, call)| {
+                let id = call.id.clone().unwrap_or_else(|| format!("call_{i}"));
+                ChatCompletionMessageToolCalls::Function(ChatCompletionMessageToolCall {
+                    id,
+                    ..call.into()
+                })
+            })
+            .collect();
+
+        let finish_reason = if !tool_calls.is_empty() {
+            Some(FinishReason::ToolCalls)
+        } else {
+            match &response.done_reason {
+                Some(DoneReason::Length) => Some(FinishReason::Length),
+                Some(_) => Some(FinishReason::Stop),
+                None => response.done.then_some(FinishReason::Stop),
+            }
+        };
+
+        let created = response.created_at.unix_timestamp();
+        let usage = response.final_data.map(|data| {
+            let prompt_tokens = data.prompt_eval_count.try_into().unwrap_or(u32::MAX);
+            let completion_tokens = data.eval_count.try_into().unwrap_or(u32::MAX);
+            CompletionUsage {
+                prompt_tokens,
+                completion_tokens,
+                total_tokens: prompt_tokens.saturating_add(completion_tokens),
+                prompt_tokens_details: None,
+                completion_tokens_details: None,
+            }
+        });
+
+        CreateChatCompletionResponse {
+            id: format!("chatcmpl-{created}"),
+            choices: vec![ChatChoice {
+                index: 0,
+                message: ChatCompletionResponseMessage {
+                    content: Some(response.message.content),
+                    refusal: None,
+                    tool_calls: (!tool_calls.is_empty()).then_some(tool_calls),
+                    annotations: None,
+                    role: Role::Assistant,
+                    function_call: None,
+                    audio: None,
+                },
+                finish_reason,
+                logprobs: None,
+            }],
+            created: created.clamp(0, u32::MAX.into()) as u32,
+            model: response.model,
+            service_tier: None,
+            system_fingerprint: None,
+            object: "chat.completion".to_string(),
+            usage,
+        }
+    }
+}
@@ -0,0 +1,80 @@
+//! OpenAPI schemas of the request and response types, for services re-exposing them,
+//! e.g. an axum service documented with [`utoipa`].
+
+use utoipa::{
+    openapi::{schema::Schema, ComponentsBuilder, OpenApi, RefOr},
+    ToSchema,
+};
+
+use crate::{
+    generation::{
+        chat::{request::ChatMessageRequest, ChatMessage, ChatMessageResponse},
+        completion::{request::GenerationRequest, GenerationResponse},
+        embeddings::{request::GenerateEmbeddingsRequest, GenerateEmbeddingsResponse},
+        tools::{ToolCall, ToolChoice, ToolInfo},
+    },
+    models::{
+        create::{CreateModelRequest, CreateModelStatus},
+        list_running::RunningModel,
+        pull::PullModelStatus,
+        push::PushModelStatus,
+        LocalModel, ModelInfo, ModelOptions,
+    },
+};
+
+/// Returns the schemas of every request and response type of this crate, along with the
+/// schemas of the types they use, by name.
+pub fn schemas() -> Vec<(String, RefOr<Schema>)> {
+    let mut schemas = Vec::new();
+
+    add::<ChatMessageRequest>(&mut schemas);
+    add::<ChatMessageResponse>(&mut schemas);
+    add::<ChatMessage>(&mut schemas);
+    add::<GenerationRequest<'static>>(&mut schemas);
+    add::<GenerationResponse>(&mut schemas);
+    add::<GenerateEmbeddingsRequest>(&mut schemas);
+    add::<GenerateEmbeddingsResponse>(&mut schemas);
+    add::<ToolInfo>(&mut schemas);
+    add::<ToolCall>(&mut schemas);
+    add::<ToolChoice>(&mut schemas);
+    add::<ModelOptions>(&mut schemas);
+    add::<LocalModel>(&mut schemas);
+    add::<RunningModel>(&mut schemas);
+    add::<ModelInfo>(&mut schemas);
+    add::<CreateModelRequest>(&mut schemas);
+    add::<CreateModelStatus>(&mut schemas);
+    add::<PullModelStatus>(&mut schemas);
+    add::<PushModelStatus>(&mut schemas);
+
+    let mut seen = std::collections::HashSet::new();
+    schemas.retain(|(name, _)| seen.insert(name.clone()));
+    schemas
+}
+
+/// Registers the [`schemas`] into the components of `openapi`, keeping schemas it already has.
+///
+/// ```
+/// use utoipa::OpenApi;
+///
+/// #[derive(OpenApi)]
+/// struct ApiDoc;
+///
+/// let mut openapi = ApiDoc::openapi();
+/// ollama_rs::openapi::register_schemas(&mut openapi);
+///
+/// assert!(openapi.components.unwrap().schemas.contains_key("ChatMessageRequest"));
+/// ```
+pub fn register_schemas(openapi: &mut OpenApi) {
+    let components = openapi
+        .components
+        .get_or_insert_with(|| ComponentsBuilder::new().build());
+
+    for (name, schema) in schemas() {
+        components.schemas.entry(name).or_insert(schema);
+    }
+}
+
+fn add<T: ToSchema>(schemas: &mut Vec<(String, RefOr<Schema>)>) {
+    schemas.push((T::name().into_owned(), T::schema()));
+    T::schemas(schemas);
+}
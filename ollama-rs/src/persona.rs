@@ -0,0 +1,146 @@
+//! Reusable agent personalities: a system prompt template, the tools the agent starts with
+//! and its default options, applied to a [`Coordinator`] in one call.
+//!
+//! ```no_run
+//! # async fn run(ollama: ollama_rs::Ollama) -> ollama_rs::error::Result<()> {
+//! use ollama_rs::{
+//!     coordinator::Coordinator,
+//!     generation::{chat::ChatMessage, tools::implementations::Calculator},
+//!     models::ModelOptions,
+//!     persona::Persona,
+//! };
+//!
+//! let accountant = Persona::new("accountant", "You are {{name}}, a {{tone}} accountant.")
+//!     .variable("name", "Ada")
+//!     .variable("tone", "meticulous")
+//!     .add_tool(Calculator {})
+//!     .options(ModelOptions::default().temperature(0.2));
+//!
+//! let mut coordinator = Coordinator::new(ollama, "llama3.2".to_string(), vec![])
+//!     .persona(&accountant.clone().variable("tone", "friendly"))?;
+//! coordinator
+//!     .chat(vec![ChatMessage::user("What is 12% of 250?".to_string())])
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{future::Future, pin::Pin, sync::Arc};
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::{
+    error::TemplateError,
+    generation::{
+        prompt::PromptTemplate,
+        tools::{DynTool, Result as ToolResult, Tool, ToolInfo},
+    },
+    models::ModelOptions,
+};
+
+/// A system prompt template with its variables, default tools and default options.
+///
+/// Cloning a persona is cheap, and its clones share their tools, so that a persona can be
+/// defined once and applied to every coordinator, with variables set for each.
+#[derive(Clone)]
+pub struct Persona {
+    name: String,
+    system: PromptTemplate,
+    variables: Map<String, Value>,
+    tools: Vec<Arc<dyn DynTool>>,
+    options: Option<ModelOptions>,
+}
+
+impl Persona {
+    /// A persona with the system prompt `system`, a [`PromptTemplate`] whose variables are set
+    /// with [`Persona::variable`].
+    pub fn new(name: impl Into<String>, system: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            system: PromptTemplate::new(system),
+            variables: Map::new(),
+            tools: Vec::new(),
+            options: None,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Registers a template that the system prompt can include with `{{> name}}`.
+    pub fn partial(mut self, name: impl Into<String>, template: impl Into<String>) -> Self {
+        self.system = self.system.partial(name, template);
+        self
+    }
+
+    /// Sets a variable of the system prompt, replacing the value set before. Values that
+    /// can't be serialized are set to `null`, which fails when rendering.
+    pub fn variable(mut self, name: impl Into<String>, value: impl Serialize) -> Self {
+        self.variables.insert(
+            name.into(),
+            serde_json::to_value(value).unwrap_or(Value::Null),
+        );
+        self
+    }
+
+    /// Adds a tool given to the coordinators the persona is applied to.
+    pub fn add_tool<T: Tool + 'static>(mut self, tool: T) -> Self {
+        self.tools.push(Arc::new(tool));
+        self
+    }
+
+    /// The options of the coordinators the persona is applied to. Options set on a coordinator
+    /// after the persona replace them.
+    pub fn options(mut self, options: ModelOptions) -> Self {
+        self.options = Some(options);
+        self
+    }
+
+    /// The information about the tools of the persona.
+    pub fn tool_infos(&self) -> Vec<ToolInfo> {
+        self.tools.iter().map(|tool| tool.info()).collect()
+    }
+
+    /// Renders the system prompt with the variables of the persona.
+    pub fn system_prompt(&self) -> Result<String, TemplateError> {
+        self.system.render(&self.variables)
+    }
+
+    /// The tools of the persona, to be added to a coordinator.
+    pub(crate) fn shared_tools(&self) -> impl Iterator<Item = Box<dyn DynTool>> + '_ {
+        self.tools
+            .iter()
+            .map(|tool| Box::new(SharedTool(tool.clone())) as Box<dyn DynTool>)
+    }
+
+    pub(crate) fn default_options(&self) -> Option<&ModelOptions> {
+        self.options.as_ref()
+    }
+}
+
+impl std::fmt::Debug for Persona {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Persona")
+            .field("name", &self.name)
+            .field("system", &self.system)
+            .field("variables", &self.variables)
+            .field("tools", &self.tool_infos())
+            .field("options", &self.options)
+            .finish()
+    }
+}
+
+/// A tool of a persona, shared by the coordinators it is applied to.
+struct SharedTool(Arc<dyn DynTool>);
+
+impl DynTool for SharedTool {
+    fn info(&self) -> ToolInfo {
+        self.0.info()
+    }
+
+    fn call(&self, parameters: Value) -> Pin<Box<dyn Future<Output = ToolResult<String>> + '_>> {
+        self.0.call(parameters)
+    }
+}
@@ -0,0 +1,172 @@
+//! Named client configurations loaded from a TOML or JSON file.
+//!
+//! ```toml
+//! [profiles.local]
+//! host = "localhost:11434"
+//! model = "llama3.2"
+//!
+//! [profiles.prod]
+//! host = "https://ollama.example.com"
+//! api_key_env = "PROD_OLLAMA_API_KEY"
+//! model = "llama3.3:70b"
+//! timeout = "2m"
+//!
+//! [profiles.prod.options]
+//! temperature = 0.2
+//! num_ctx = 8192
+//! ```
+//!
+//! The file is looked up at `$OLLAMA_RS_CONFIG`, or at `ollama-rs/config.toml` in the
+//! configuration directory of the user (`$XDG_CONFIG_HOME` or `~/.config` on Unix,
+//! `%APPDATA%` on Windows). Files with a `.json` extension are parsed as JSON, and others as
+//! TOML with the `profiles` feature.
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+
+use crate::{error::OllamaError, models::ModelOptions, Ollama, OllamaBuilder};
+
+/// The content of a profiles configuration file.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ProfileFile {
+    #[serde(default)]
+    pub profiles: HashMap<String, Profile>,
+}
+
+/// The configuration of a client.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Profile {
+    /// The address of the Ollama service, in the same format as `OLLAMA_HOST`.
+    pub host: Option<String>,
+    /// A token sent as `Authorization: Bearer <token>`. Prefer `api_key_env` to keep
+    /// secrets out of configuration files.
+    pub api_key: Option<String>,
+    /// The name of an environment variable holding the API key.
+    pub api_key_env: Option<String>,
+    /// The model to use by default, see [`Ollama::default_model`].
+    pub model: Option<String>,
+    /// The timeout of whole requests, e.g. `30`, `500ms`, `5m`.
+    pub timeout: Option<String>,
+    /// The timeout of the connection phase of requests.
+    pub connect_timeout: Option<String>,
+    /// Options applied to every generation, chat and embeddings request.
+    pub options: Option<ModelOptions>,
+}
+
+impl ProfileFile {
+    /// Reads a profiles file, as JSON if its extension is `.json`, or as TOML, which requires
+    /// the `profiles` feature.
+    pub fn load(path: impl AsRef<Path>) -> crate::error::Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            OllamaError::ConfigError(format!("Failed to read {}: {e}", path.display()))
+        })?;
+
+        let parsed = if path.extension().is_some_and(|ext| ext == "json") {
+            serde_json::from_str(&content).map_err(|e| e.to_string())
+        } else {
+            #[cfg(feature = "profiles")]
+            {
+                toml::from_str(&content).map_err(|e| e.to_string())
+            }
+            #[cfg(not(feature = "profiles"))]
+            {
+                Err("TOML files require the `profiles` feature".to_string())
+            }
+        };
+
+        parsed.map_err(|e| OllamaError::ConfigError(format!("Invalid {}: {e}", path.display())))
+    }
+
+    /// The default location of the profiles file.
+    pub fn default_path() -> Option<PathBuf> {
+        if let Some(path) = std::env::var_os("OLLAMA_RS_CONFIG") {
+            return Some(path.into());
+        }
+
+        let config_dir = if cfg!(windows) {
+            std::env::var_os("APPDATA").map(PathBuf::from)
+        } else {
+            std::env::var_os("XDG_CONFIG_HOME")
+                .map(PathBuf::from)
+                .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".config")))
+        };
+
+        config_dir.map(|dir| dir.join("ollama-rs").join("config.toml"))
+    }
+
+    pub fn profile(&self, name: &str) -> crate::error::Result<&Profile> {
+        self.profiles
+            .get(name)
+            .ok_or_else(|| OllamaError::ConfigError(format!("No profile named {name:?}")))
+    }
+}
+
+impl Profile {
+    /// Returns a builder configured with this profile.
+    pub fn builder(&self) -> crate::error::Result<OllamaBuilder> {
+        let mut builder = OllamaBuilder::new();
+
+        if let Some(host) = &self.host {
+            builder = builder.url(crate::env::parse_host(Some(host)));
+        }
+
+        let api_key = match &self.api_key_env {
+            Some(var) => Some(std::env::var(var).map_err(|_| {
+                OllamaError::ConfigError(format!("The environment variable {var} isn't set"))
+            })?),
+            None => self.api_key.clone(),
+        };
+        if let Some(api_key) = api_key {
+            builder = builder.api_key(api_key);
+        }
+
+        if let Some(model) = &self.model {
+            builder = builder.default_model(model.clone());
+        }
+        if let Some(timeout) = &self.timeout {
+            builder = builder.timeout(parse_duration(timeout)?);
+        }
+        if let Some(timeout) = &self.connect_timeout {
+            builder = builder.connect_timeout(parse_duration(timeout)?);
+        }
+        if let Some(options) = &self.options {
+            builder = builder.default_options(options.clone());
+        }
+
+        Ok(builder)
+    }
+}
+
+fn parse_duration(value: &str) -> crate::error::Result<std::time::Duration> {
+    crate::env::parse_duration(value)
+        .ok_or_else(|| OllamaError::ConfigError(format!("Invalid duration: {value:?}")))
+}
+
+impl OllamaBuilder {
+    /// Starts from the profile `name` of the profiles file at its default location,
+    /// see [`crate::profile`].
+    pub fn from_profile(name: &str) -> crate::error::Result<Self> {
+        let path = ProfileFile::default_path().ok_or_else(|| {
+            OllamaError::ConfigError("Couldn't locate the configuration directory".to_string())
+        })?;
+        Self::from_profile_file(path, name)
+    }
+
+    /// Starts from the profile `name` of the profiles file at `path`.
+    pub fn from_profile_file(path: impl AsRef<Path>, name: &str) -> crate::error::Result<Self> {
+        ProfileFile::load(path)?.profile(name)?.builder()
+    }
+}
+
+impl Ollama {
+    /// Creates a client from the profile `name` of the profiles file at its default location,
+    /// see [`crate::profile`].
+    pub fn from_profile(name: &str) -> crate::error::Result<Self> {
+        OllamaBuilder::from_profile(name)?.build()
+    }
+}
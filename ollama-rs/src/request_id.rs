@@ -0,0 +1,57 @@
+//! Request IDs, to match a request of the client with the logs of the server or of a gateway.
+//!
+//! Every request is sent with a unique ID, like `req_8f3a...`, in the
+//! [`REQUEST_ID_HEADER`] header. Retries of a request keep its ID. A request ID set in the
+//! default headers of the client is sent as-is instead.
+//!
+//! The ID is logged with the [`log`] crate when the request is sent, and when it fails before
+//! a response is received, at the `debug` level and under the `ollama_rs::request_id` target.
+//! The errors of the request carry it, from connection failures and timeouts to error
+//! statuses and broken streams:
+//!
+//! ```no_run
+//! # async fn run(ollama: ollama_rs::Ollama) {
+//! use ollama_rs::generation::completion::request::GenerationRequest;
+//!
+//! let request = GenerationRequest::new("llama3.2".to_string(), "Why is the sky blue?");
+//! if let Err(e) = ollama.generate(request).await {
+//!     eprintln!("request {} failed: {e}", e.request_id().unwrap_or("-"));
+//! }
+//! # }
+//! ```
+
+/// The header the ID of a request is sent in.
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// The ID of the request of a response, kept in its extensions.
+#[derive(Debug, Clone)]
+struct RequestId(String);
+
+/// Sets the ID of `request`, unless it already has one, and returns it.
+pub(crate) fn ensure(request: &mut reqwest::Request) -> String {
+    let headers = request.headers_mut();
+    if let Some(id) = headers
+        .get(REQUEST_ID_HEADER)
+        .and_then(|id| id.to_str().ok())
+    {
+        return id.to_string();
+    }
+
+    let id = crate::unique_id("req");
+    if let Ok(value) = id.parse() {
+        headers.insert(REQUEST_ID_HEADER, value);
+    }
+    id
+}
+
+/// Remembers the ID of the request of `res`.
+pub(crate) fn attach(res: &mut reqwest::Response, id: &str) {
+    res.extensions_mut().insert(RequestId(id.to_string()));
+}
+
+/// The ID of the request of `res`, if sent by the client.
+pub(crate) fn of(res: &reqwest::Response) -> Option<String> {
+    res.extensions()
+        .get::<RequestId>()
+        .map(|RequestId(id)| id.clone())
+}
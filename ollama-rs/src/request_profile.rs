@@ -0,0 +1,256 @@
+//! Named presets of generation and chat requests, so that the tuned configuration of each
+//! task lives in one place, or in a file shipped with the application.
+//!
+//! ```no_run
+//! # async fn run(ollama: ollama_rs::Ollama) -> ollama_rs::error::Result<()> {
+//! use ollama_rs::{
+//!     generation::{chat::ChatMessage, parameters::FormatType},
+//!     models::ModelOptions,
+//!     request_profile::{RequestProfile, RequestProfiles},
+//! };
+//!
+//! let mut profiles = RequestProfiles::new();
+//! profiles.insert(
+//!     "summarize",
+//!     RequestProfile::new("llama3.2")
+//!         .options(ModelOptions::default().temperature(0.2).num_ctx(8192))
+//!         .system("Summarize the text in three sentences."),
+//! );
+//! profiles.insert(
+//!     "extract",
+//!     RequestProfile::new("qwen2.5:7b").format(FormatType::Json),
+//! );
+//! profiles.save("request_profiles.json")?;
+//!
+//! let profiles = RequestProfiles::load("request_profiles.json")?;
+//! let request = profiles.chat_request("summarize", vec![ChatMessage::user("...".into())])?;
+//! let res = ollama.send_chat_messages(request).await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Files are JSON, or TOML with the `profiles` feature if their extension is `.toml`.
+
+use std::{borrow::Cow, collections::BTreeMap, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::{OllamaError, Result},
+    generation::{
+        chat::{request::ChatMessageRequest, ChatMessage, MessageRole},
+        completion::request::GenerationRequest,
+        parameters::FormatType,
+    },
+    models::ModelOptions,
+};
+
+/// The model, options, format and system prompt of a kind of request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RequestProfile {
+    pub model: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub options: Option<ModelOptions>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<FormatType>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system: Option<String>,
+}
+
+impl RequestProfile {
+    pub fn new(model: impl Into<String>) -> Self {
+        Self {
+            model: model.into(),
+            options: None,
+            format: None,
+            system: None,
+        }
+    }
+
+    /// Options of the requests, under the options set on each request.
+    pub fn options(mut self, options: ModelOptions) -> Self {
+        self.options = Some(options);
+        self
+    }
+
+    /// The format of the responses, unless a request sets its own.
+    pub fn format(mut self, format: FormatType) -> Self {
+        self.format = Some(format);
+        self
+    }
+
+    /// The system prompt, unless a request sets its own.
+    pub fn system(mut self, system: impl Into<String>) -> Self {
+        self.system = Some(system.into());
+        self
+    }
+
+    /// A generation request for `prompt` with this profile.
+    pub fn generation_request<'a>(&self, prompt: impl Into<Cow<'a, str>>) -> GenerationRequest<'a> {
+        self.apply_to_generation(GenerationRequest::new(self.model.clone(), prompt))
+    }
+
+    /// A chat request for `messages` with this profile.
+    pub fn chat_request(&self, messages: Vec<ChatMessage>) -> ChatMessageRequest {
+        self.apply_to_chat(ChatMessageRequest::new(self.model.clone(), messages))
+    }
+
+    /// Applies this profile to `request`: its model is replaced, its options are completed
+    /// with those of the profile, and its format and system prompt are set unless it has some.
+    pub fn apply_to_generation<'a>(
+        &self,
+        mut request: GenerationRequest<'a>,
+    ) -> GenerationRequest<'a> {
+        request.model_name = self.model.clone();
+        request.options = self.merge_options(request.options);
+        if request.format.is_none() {
+            request.format = self.format.clone();
+        }
+        if request.system.is_none() {
+            request.system = self.system.clone().map(Cow::Owned);
+        }
+        request
+    }
+
+    /// Applies this profile to `request`: its model is replaced, its options are completed
+    /// with those of the profile, and its format is set unless it has one. The system prompt
+    /// is prepended to the messages unless they start with a system message.
+    pub fn apply_to_chat(&self, mut request: ChatMessageRequest) -> ChatMessageRequest {
+        request.model_name = self.model.clone();
+        request.options = self.merge_options(request.options);
+        if request.format.is_none() {
+            request.format = self.format.clone();
+        }
+        if let Some(system) = &self.system {
+            let has_system = request
+                .messages
+                .first()
+                .is_some_and(|message| message.role == MessageRole::System);
+            if !has_system {
+                request
+                    .messages
+                    .insert(0, ChatMessage::system(system.clone()));
+            }
+        }
+        request
+    }
+
+    fn merge_options(&self, options: Option<ModelOptions>) -> Option<ModelOptions> {
+        match (options, &self.options) {
+            (Some(options), Some(defaults)) => Some(options.or_defaults(defaults)),
+            (options, defaults) => options.or_else(|| defaults.clone()),
+        }
+    }
+}
+
+/// Request profiles by name, which can be saved to and loaded from a file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RequestProfiles {
+    #[serde(default)]
+    pub profiles: BTreeMap<String, RequestProfile>,
+}
+
+impl RequestProfiles {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the profile `name`, replacing the profile of that name if there is one.
+    pub fn insert(&mut self, name: impl Into<String>, profile: RequestProfile) {
+        self.profiles.insert(name.into(), profile);
+    }
+
+    pub fn profile(&self, name: &str) -> Result<&RequestProfile> {
+        self.profiles
+            .get(name)
+            .ok_or_else(|| OllamaError::ConfigError(format!("No request profile named {name:?}")))
+    }
+
+    /// A generation request for `prompt` with the profile `name`.
+    pub fn generation_request<'a>(
+        &self,
+        name: &str,
+        prompt: impl Into<Cow<'a, str>>,
+    ) -> Result<GenerationRequest<'a>> {
+        Ok(self.profile(name)?.generation_request(prompt))
+    }
+
+    /// A chat request for `messages` with the profile `name`.
+    pub fn chat_request(
+        &self,
+        name: &str,
+        messages: Vec<ChatMessage>,
+    ) -> Result<ChatMessageRequest> {
+        Ok(self.profile(name)?.chat_request(messages))
+    }
+
+    /// Applies the profile `name` to `request`, see [`RequestProfile::apply_to_generation`].
+    pub fn apply_to_generation<'a>(
+        &self,
+        name: &str,
+        request: GenerationRequest<'a>,
+    ) -> Result<GenerationRequest<'a>> {
+        Ok(self.profile(name)?.apply_to_generation(request))
+    }
+
+    /// Applies the profile `name` to `request`, see [`RequestProfile::apply_to_chat`].
+    pub fn apply_to_chat(
+        &self,
+        name: &str,
+        request: ChatMessageRequest,
+    ) -> Result<ChatMessageRequest> {
+        Ok(self.profile(name)?.apply_to_chat(request))
+    }
+
+    /// Reads profiles saved with [`RequestProfiles::save`].
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            OllamaError::ConfigError(format!("Failed to read {}: {e}", path.display()))
+        })?;
+
+        let parsed = if is_toml(path) {
+            #[cfg(feature = "profiles")]
+            {
+                toml::from_str(&content).map_err(|e| e.to_string())
+            }
+            #[cfg(not(feature = "profiles"))]
+            {
+                Err("TOML files require the `profiles` feature".to_string())
+            }
+        } else {
+            serde_json::from_str(&content).map_err(|e| e.to_string())
+        };
+
+        parsed.map_err(|e| OllamaError::ConfigError(format!("Invalid {}: {e}", path.display())))
+    }
+
+    /// Writes the profiles to `path`, as TOML if its extension is `.toml`, or as JSON.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+
+        let content = if is_toml(path) {
+            #[cfg(feature = "profiles")]
+            {
+                toml::to_string_pretty(self).map_err(|e| e.to_string())
+            }
+            #[cfg(not(feature = "profiles"))]
+            {
+                Err("TOML files require the `profiles` feature".to_string())
+            }
+        } else {
+            serde_json::to_string_pretty(self).map_err(|e| e.to_string())
+        };
+
+        let content = content.map_err(|e| {
+            OllamaError::ConfigError(format!("Failed to serialize {}: {e}", path.display()))
+        })?;
+        std::fs::write(path, content).map_err(|e| {
+            OllamaError::ConfigError(format!("Failed to write {}: {e}", path.display()))
+        })
+    }
+}
+
+fn is_toml(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "toml")
+}
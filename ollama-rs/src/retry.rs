@@ -0,0 +1,260 @@
+use std::{sync::Arc, time::Duration};
+
+use reqwest::{header::RETRY_AFTER, RequestBuilder, Response};
+
+use crate::{
+    circuit_breaker::{Attempt, CircuitBreaker},
+    concurrency::ConcurrencySlot,
+    error::OllamaError,
+    Ollama,
+};
+
+/// How failed requests are retried, see [`OllamaError::is_retryable`] for which failures are.
+///
+/// Retries wait for an exponentially increasing delay, or for the delay requested by the
+/// `Retry-After` header of the response, capped to `max_backoff`.
+/// Streamed responses are only retried if the request fails before streaming starts.
+///
+/// Only requests that can be sent twice without harm are retried: reads, like listing or
+/// showing models, and inference, i.e. generation, chat and embeddings. Requests with side
+/// effects, like creating, copying, deleting, pulling or pushing models, aren't, since a
+/// request that timed out may still have been carried out; see
+/// [`RetryPolicy::retry_side_effects`].
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    max_retries: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    retry_side_effects: bool,
+}
+
+impl RetryPolicy {
+    /// Retries failed requests up to `max_retries` times.
+    pub fn new(max_retries: u32) -> Self {
+        Self {
+            max_retries,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(10),
+            retry_side_effects: false,
+        }
+    }
+
+    /// Never retries failed requests. This is the default.
+    pub fn none() -> Self {
+        Self::new(0)
+    }
+
+    /// The delay before the first retry, doubled for each subsequent retry. (Default: 500ms)
+    pub fn initial_backoff(mut self, initial_backoff: Duration) -> Self {
+        self.initial_backoff = initial_backoff;
+        self
+    }
+
+    /// The maximum delay between two attempts. (Default: 10s)
+    pub fn max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /// Retries requests with side effects too, like creating, copying, deleting, pulling or
+    /// pushing models, which may then be carried out twice. (Default: false)
+    pub fn retry_side_effects(mut self, retry_side_effects: bool) -> Self {
+        self.retry_side_effects = retry_side_effects;
+        self
+    }
+
+    /// Whether `request` may be retried.
+    fn retries(&self, request: &reqwest::Request) -> bool {
+        self.retry_side_effects
+            || matches!(
+                *request.method(),
+                reqwest::Method::GET | reqwest::Method::HEAD
+            )
+            || INFERENCE_ENDPOINTS
+                .iter()
+                .any(|endpoint| request.url().path().ends_with(endpoint))
+    }
+
+    fn backoff(&self, retry: u32, res: Option<&Response>) -> Duration {
+        let retry_after = res
+            .and_then(|res| res.headers().get(RETRY_AFTER))
+            .and_then(|value| value.to_str().ok()?.parse::<u64>().ok())
+            .map(Duration::from_secs);
+
+        retry_after
+            .unwrap_or_else(|| {
+                self.initial_backoff
+                    .saturating_mul(2u32.saturating_pow(retry))
+            })
+            .min(self.max_backoff)
+    }
+}
+
+/// The endpoints posting requests without side effects, which may be retried.
+const INFERENCE_ENDPOINTS: &[&str] = &[
+    "/api/generate",
+    "/api/chat",
+    "/api/embed",
+    "/api/embeddings",
+    "/api/show",
+    "/v1/chat/completions",
+];
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::none()
+    }
+}
+
+impl Ollama {
+    /// Sends a request, retrying it according to the retry policy of the client.
+    ///
+    /// Responses with an error status are returned as-is once retries are exhausted, so that
+    /// endpoints can turn them into errors.
+    pub(crate) async fn send(&self, builder: RequestBuilder) -> crate::error::Result<Response> {
+        let (client, request) = builder.build_split();
+        let mut request = request?;
+        let request_id = crate::request_id::ensure(&mut request);
+        let permit = match &self.config.concurrency {
+            Some(limiter) => {
+                let body = request.body().and_then(reqwest::Body::as_bytes);
+                let model_name = limiter.model_of(request.url().path(), body);
+                Some(limiter.acquire(model_name, ()).await)
+            }
+            None => None,
+        };
+        log::debug!(target: TARGET, "request {request_id}: {} {}", request.method(), request.url());
+
+        #[cfg(feature = "audit")]
+        let audit = self
+            .config
+            .audit_log
+            .as_ref()
+            .map(|audit_log| audit_log.start(&request, &request_id));
+
+        let res = self.execute(client, request, &request_id).await;
+        // The slot is freed once the response is read
+        let res = res.map(|mut res| {
+            if let Some(permit) = permit {
+                res.extensions_mut()
+                    .insert(ConcurrencySlot(Arc::new(permit)));
+            }
+            res
+        });
+
+        #[cfg(feature = "audit")]
+        if let Some(audit) = audit {
+            return audit_response(audit, res).await;
+        }
+        res
+    }
+
+    /// Sends a request with its retries.
+    async fn execute(
+        &self,
+        client: reqwest::Client,
+        request: reqwest::Request,
+        request_id: &str,
+    ) -> crate::error::Result<Response> {
+        let max_retries = if self.config.retry_policy.retries(&request) {
+            self.config.retry_policy.max_retries
+        } else {
+            0
+        };
+        let mut retry = 0;
+        loop {
+            let attempt = self
+                .config
+                .circuit_breaker
+                .as_ref()
+                .map(CircuitBreaker::acquire)
+                .transpose()?;
+
+            let Some(next) = request.try_clone().filter(|_| retry < max_retries) else {
+                let res = client.execute(request).await;
+                record(attempt, &res);
+                return with_request_id(res, request_id);
+            };
+
+            let res = client.execute(next).await;
+            record(attempt, &res);
+            match res {
+                Ok(res) if crate::error::is_retryable_status(res.status()) => {
+                    log::debug!(target: TARGET, "request {request_id}: retrying after {}", res.status());
+                    crate::runtime::sleep(self.config.retry_policy.backoff(retry, Some(&res)))
+                        .await;
+                }
+                Ok(res) => return with_request_id(Ok(res), request_id),
+                Err(e) => {
+                    let e = OllamaError::from(e).with_request_id(Some(request_id));
+                    if !e.is_retryable() {
+                        log::debug!(target: TARGET, "request {request_id} failed: {e}");
+                        return Err(e);
+                    }
+                    log::debug!(target: TARGET, "request {request_id}: retrying after {e}");
+                    crate::runtime::sleep(self.config.retry_policy.backoff(retry, None)).await;
+                }
+            }
+
+            retry += 1;
+        }
+    }
+}
+
+/// Records the outcome of a request in the audit log: failures right away, successful
+/// responses once their body is read.
+#[cfg(feature = "audit")]
+async fn audit_response(
+    mut audit: crate::audit::PendingAudit,
+    res: crate::error::Result<Response>,
+) -> crate::error::Result<Response> {
+    match res {
+        Ok(mut res) => {
+            audit.status(res.status());
+            if res.status().is_success() {
+                res.extensions_mut()
+                    .insert(crate::audit::AuditSlot::new(audit));
+            } else {
+                audit.finish().await;
+            }
+            Ok(res)
+        }
+        Err(e) => {
+            audit.fail(e.to_string()).await;
+            Err(e)
+        }
+    }
+}
+
+/// The log target of the requests, see [`crate::request_id`].
+const TARGET: &str = "ollama_rs::request_id";
+
+/// Attaches the request ID to a response, or logs it with the failure.
+fn with_request_id(
+    res: reqwest::Result<Response>,
+    request_id: &str,
+) -> crate::error::Result<Response> {
+    match res {
+        Ok(mut res) => {
+            crate::request_id::attach(&mut res, request_id);
+            Ok(res)
+        }
+        Err(e) => {
+            let e = OllamaError::from(e).with_request_id(Some(request_id));
+            log::debug!(target: TARGET, "request {request_id} failed: {e}");
+            Err(e)
+        }
+    }
+}
+
+/// Records the outcome of a request in the circuit breaker: network errors and server
+/// errors are failures.
+fn record(attempt: Option<Attempt<'_>>, res: &reqwest::Result<Response>) {
+    if let Some(attempt) = attempt {
+        let failed = match res {
+            Ok(res) => res.status().is_server_error(),
+            Err(_) => true,
+        };
+        attempt.record(failed);
+    }
+}
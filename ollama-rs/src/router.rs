@@ -0,0 +1,185 @@
+//! Routing of requests to the models able to serve them, e.g. to send simple requests to a
+//! small model and the ones with images to a vision model.
+
+use crate::generation::chat::request::ChatMessageRequest;
+
+/// The latency a request can tolerate, from the fastest to the slowest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum LatencyTier {
+    /// Requests a user is waiting for, e.g. autocompletion.
+    Interactive,
+    #[default]
+    Standard,
+    /// Background requests, e.g. batch summarization.
+    Batch,
+}
+
+/// What a request needs from a model.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RequestTraits {
+    pub vision: bool,
+    pub tools: bool,
+    /// The number of tokens the context must hold.
+    pub context_length: u64,
+    pub latency: LatencyTier,
+}
+
+impl RequestTraits {
+    /// Infers the traits of `request`: whether it has images or tools, and its context
+    /// length, from its `num_ctx` option or else roughly estimated from its messages.
+    pub fn of(request: &ChatMessageRequest) -> Self {
+        let context_length = request
+            .options
+            .as_ref()
+            .and_then(|options| options.num_ctx)
+            .unwrap_or_else(|| {
+                // About 4 characters per token
+                let chars: usize = request.messages.iter().map(|m| m.content.len()).sum();
+                chars.div_ceil(4) as u64
+            });
+
+        Self {
+            vision: request
+                .messages
+                .iter()
+                .any(|m| m.images.as_ref().is_some_and(|images| !images.is_empty())),
+            tools: !request.tools.is_empty(),
+            context_length,
+            latency: LatencyTier::default(),
+        }
+    }
+
+    pub fn vision(mut self, vision: bool) -> Self {
+        self.vision = vision;
+        self
+    }
+
+    pub fn tools(mut self, tools: bool) -> Self {
+        self.tools = tools;
+        self
+    }
+
+    pub fn context_length(mut self, context_length: u64) -> Self {
+        self.context_length = context_length;
+        self
+    }
+
+    pub fn latency(mut self, latency: LatencyTier) -> Self {
+        self.latency = latency;
+        self
+    }
+}
+
+/// A model a [`ModelRouter`] may pick, and what it can do.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoutedModel {
+    name: String,
+    vision: bool,
+    tools: bool,
+    context_length: u64,
+    latency: LatencyTier,
+}
+
+impl RoutedModel {
+    /// A model without vision nor tools, with the default context of Ollama.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            vision: false,
+            tools: false,
+            context_length: 2048,
+            latency: LatencyTier::Standard,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Whether the model understands images.
+    pub fn vision(mut self, vision: bool) -> Self {
+        self.vision = vision;
+        self
+    }
+
+    /// Whether the model can call tools.
+    pub fn tools(mut self, tools: bool) -> Self {
+        self.tools = tools;
+        self
+    }
+
+    /// The number of tokens the context of the model holds.
+    pub fn context_length(mut self, context_length: u64) -> Self {
+        self.context_length = context_length;
+        self
+    }
+
+    /// The fastest tier of requests the model answers quickly enough for.
+    pub fn latency(mut self, latency: LatencyTier) -> Self {
+        self.latency = latency;
+        self
+    }
+
+    /// Whether the model can serve a request with these traits.
+    pub fn supports(&self, traits: &RequestTraits) -> bool {
+        (self.vision || !traits.vision)
+            && (self.tools || !traits.tools)
+            && self.context_length >= traits.context_length
+            && self.latency <= traits.latency
+    }
+}
+
+/// Picks a model for each request, among models in order of preference, e.g. from the
+/// smallest to the largest.
+///
+/// ```
+/// use ollama_rs::{
+///     generation::chat::{request::ChatMessageRequest, ChatMessage},
+///     router::{LatencyTier, ModelRouter, RoutedModel},
+/// };
+///
+/// let router = ModelRouter::new()
+///     .model(RoutedModel::new("llama3.2:1b").latency(LatencyTier::Interactive))
+///     .model(RoutedModel::new("qwen2.5:14b").tools(true).context_length(32_768))
+///     .model(RoutedModel::new("llama3.2-vision").vision(true).tools(true));
+///
+/// let request = ChatMessageRequest::new(String::new(), vec![ChatMessage::user("Hi".into())]);
+/// assert_eq!(router.route(&request), Some("llama3.2:1b"));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ModelRouter {
+    models: Vec<RoutedModel>,
+    fallback: Option<String>,
+}
+
+impl ModelRouter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a model, less preferred than the ones already added.
+    pub fn model(mut self, model: RoutedModel) -> Self {
+        self.models.push(model);
+        self
+    }
+
+    /// The model picked when no model supports a request.
+    pub fn fallback(mut self, model: impl Into<String>) -> Self {
+        self.fallback = Some(model.into());
+        self
+    }
+
+    /// Picks the preferred model supporting the traits of `request`.
+    pub fn route(&self, request: &ChatMessageRequest) -> Option<&str> {
+        self.route_traits(&RequestTraits::of(request))
+    }
+
+    /// Picks the preferred model supporting `traits`, or the fallback.
+    pub fn route_traits(&self, traits: &RequestTraits) -> Option<&str> {
+        self.models
+            .iter()
+            .find(|model| model.supports(traits))
+            .map(RoutedModel::name)
+            .or(self.fallback.as_deref())
+    }
+}
@@ -0,0 +1,68 @@
+//! The few runtime facilities the client needs, so that it runs on any executor.
+//!
+//! With the `tokio` feature (on by default), sleeping uses the Tokio timer, which follows
+//! `tokio::time::pause` in tests. Otherwise it uses `futures-timer`, with the feature of
+//! that name. Without either, there is no timer: retries happen without a backoff and
+//! hedged requests only send their backup once the first request fails.
+
+use std::{future::Future, pin::Pin, task::Poll, time::Duration};
+
+/// Whether [`sleep`] waits, rather than returning at once for lack of a timer.
+pub(crate) const HAS_TIMER: bool = cfg!(any(feature = "tokio", feature = "futures-timer"));
+
+/// Waits for `duration`, or returns at once without a timer.
+pub(crate) async fn sleep(duration: Duration) {
+    #[cfg(feature = "tokio")]
+    tokio::time::sleep(duration).await;
+    #[cfg(all(not(feature = "tokio"), feature = "futures-timer"))]
+    futures_timer::Delay::new(duration).await;
+    #[cfg(not(any(feature = "tokio", feature = "futures-timer")))]
+    let _ = duration;
+}
+
+/// Runs `f`, which blocks, off the executor: on the blocking threads of Tokio with the `tokio`
+/// feature, or else in place.
+pub(crate) async fn unblock<T: Send + 'static>(f: impl FnOnce() -> T + Send + 'static) -> T {
+    #[cfg(feature = "tokio")]
+    return tokio::task::spawn_blocking(f)
+        .await
+        .unwrap_or_else(|e| std::panic::resume_unwind(e.into_panic()));
+    #[cfg(not(feature = "tokio"))]
+    return f();
+}
+
+/// Polls the futures until they all complete, at most `limit` at a time, returning their
+/// outputs in order.
+///
+/// Futures are lazy, so those past the limit only start once earlier ones complete.
+pub(crate) async fn join_all<F: Future + Unpin>(
+    mut futures: Vec<F>,
+    limit: usize,
+) -> Vec<F::Output> {
+    let mut outputs = futures.iter().map(|_| None).collect::<Vec<_>>();
+
+    std::future::poll_fn(|cx| {
+        let mut running = 0;
+        for (future, output) in futures.iter_mut().zip(&mut outputs) {
+            if output.is_some() {
+                continue;
+            }
+            if running == limit {
+                break;
+            }
+            match Pin::new(future).poll(cx) {
+                Poll::Ready(value) => *output = Some(value),
+                Poll::Pending => running += 1,
+            }
+        }
+
+        if running == 0 {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    })
+    .await;
+
+    outputs.into_iter().map(Option::unwrap).collect()
+}
@@ -0,0 +1,195 @@
+//! Scheduling of requests sharing a server, so that interactive requests aren't starved by
+//! background jobs.
+//!
+//! ```no_run
+//! # async fn run() -> ollama_rs::error::Result<()> {
+//! use ollama_rs::{
+//!     generation::completion::request::GenerationRequest, router::LatencyTier,
+//!     scheduler::Scheduler, Ollama,
+//! };
+//!
+//! let scheduler = Scheduler::new(Ollama::default()).max_concurrency(2);
+//!
+//! let request = GenerationRequest::new("llama3.2".into(), "Summarize this report: ...");
+//! let res = scheduler.generate(request, LatencyTier::Batch).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{
+    error::Result,
+    generation::{
+        chat::{request::ChatMessageRequest, ChatMessageResponse},
+        completion::{request::GenerationRequest, GenerationResponse},
+        embeddings::{request::GenerateEmbeddingsRequest, GenerateEmbeddingsResponse},
+    },
+    router::LatencyTier,
+    slots::{Permit, SlotPolicy, Slots},
+    Ollama,
+};
+
+/// The requests running for each model, within their limits.
+#[derive(Debug)]
+struct Running {
+    default_limit: usize,
+    limits: HashMap<String, usize>,
+    by_model: HashMap<String, usize>,
+}
+
+impl Running {
+    fn limit(&self, model_name: &str) -> usize {
+        self.limits
+            .get(model_name)
+            .copied()
+            .unwrap_or(self.default_limit)
+            .max(1)
+    }
+}
+
+impl SlotPolicy for Running {
+    type Key = String;
+    /// The most urgent tier first, then the oldest request first.
+    type Rank = LatencyTier;
+
+    fn fits(&self, model_name: &String) -> bool {
+        self.by_model.get(model_name).copied().unwrap_or(0) < self.limit(model_name)
+    }
+
+    fn take(&mut self, model_name: &String) {
+        *self.by_model.entry(model_name.clone()).or_default() += 1;
+    }
+
+    fn release(&mut self, model_name: &String) {
+        if let Some(running) = self.by_model.get_mut(model_name) {
+            *running -= 1;
+            if *running == 0 {
+                self.by_model.remove(model_name);
+            }
+        }
+    }
+}
+
+/// A queue in front of a client, dispatching requests by priority while running at most
+/// a number of requests per model at a time.
+///
+/// Requests waiting for the same model are dispatched by [`LatencyTier`], the most urgent
+/// first, then in the order they were enqueued. Clones share the same queue.
+#[derive(Debug, Clone)]
+pub struct Scheduler {
+    ollama: Ollama,
+    slots: Arc<Slots<Running>>,
+}
+
+impl Scheduler {
+    pub fn new(ollama: Ollama) -> Self {
+        Self {
+            ollama,
+            slots: Arc::new(Slots::new(Running {
+                default_limit: 1,
+                limits: HashMap::new(),
+                by_model: HashMap::new(),
+            })),
+        }
+    }
+
+    /// The number of requests run at a time for each model, e.g. matching the
+    /// `OLLAMA_NUM_PARALLEL` setting of the server. (Default: 1)
+    pub fn max_concurrency(self, max_concurrency: usize) -> Self {
+        self.slots
+            .policy(|running| running.default_limit = max_concurrency);
+        self
+    }
+
+    /// The number of requests run at a time for `model_name`, instead of the
+    /// [`Scheduler::max_concurrency`] of every model.
+    pub fn model_concurrency(self, model_name: impl Into<String>, max_concurrency: usize) -> Self {
+        let model_name = model_name.into();
+        self.slots.policy(|running| {
+            running.limits.insert(model_name, max_concurrency);
+        });
+        self
+    }
+
+    /// The number of requests waiting for `model_name`.
+    pub fn queued(&self, model_name: &str) -> usize {
+        self.slots.waiting(|waiting| waiting == model_name)
+    }
+
+    /// The number of requests running for `model_name`.
+    pub fn running(&self, model_name: &str) -> usize {
+        self.slots
+            .policy(|running| running.by_model.get(model_name).copied().unwrap_or(0))
+    }
+
+    pub async fn generate(
+        &self,
+        request: GenerationRequest<'_>,
+        priority: LatencyTier,
+    ) -> Result<GenerationResponse> {
+        let _permit = self.acquire(&request.model_name, priority).await;
+        self.ollama.generate(request).await
+    }
+
+    pub async fn send_chat_messages(
+        &self,
+        request: ChatMessageRequest,
+        priority: LatencyTier,
+    ) -> Result<ChatMessageResponse> {
+        let _permit = self.acquire(&request.model_name, priority).await;
+        self.ollama.send_chat_messages(request).await
+    }
+
+    pub async fn generate_embeddings(
+        &self,
+        request: GenerateEmbeddingsRequest,
+        priority: LatencyTier,
+    ) -> Result<GenerateEmbeddingsResponse> {
+        let _permit = self.acquire(&request.model_name, priority).await;
+        self.ollama.generate_embeddings(request).await
+    }
+
+    /// Streams the response once dispatched. The slot of the model is held until the
+    /// stream is dropped.
+    #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+    #[cfg(feature = "stream")]
+    pub async fn generate_stream(
+        &self,
+        request: GenerationRequest<'_>,
+        priority: LatencyTier,
+    ) -> Result<crate::generation::completion::GenerationResponseStream> {
+        use futures_util::StreamExt;
+
+        let permit = self.acquire(&request.model_name, priority).await;
+        let stream = self.ollama.generate_stream(request).await?;
+        Ok(Box::pin(stream.map(move |item| {
+            let _ = &permit;
+            item
+        })))
+    }
+
+    /// Streams the response once dispatched. The slot of the model is held until the
+    /// stream is dropped.
+    #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+    #[cfg(feature = "stream")]
+    pub async fn send_chat_messages_stream(
+        &self,
+        request: ChatMessageRequest,
+        priority: LatencyTier,
+    ) -> Result<crate::generation::chat::ChatMessageResponseStream> {
+        use futures_util::StreamExt;
+
+        let permit = self.acquire(&request.model_name, priority).await;
+        let stream = self.ollama.send_chat_messages_stream(request).await?;
+        Ok(Box::pin(stream.map(move |item| {
+            let _ = &permit;
+            item
+        })))
+    }
+
+    /// Waits for a slot of `model_name`.
+    async fn acquire(&self, model_name: &str, priority: LatencyTier) -> Permit<Running> {
+        self.slots.acquire(model_name.to_string(), priority).await
+    }
+}
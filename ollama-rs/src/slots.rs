@@ -0,0 +1,189 @@
+//! A queue of requests waiting for slots, shared by the [`concurrency`](crate::concurrency)
+//! limits of a client and the [`Scheduler`](crate::scheduler::Scheduler).
+
+use std::{
+    fmt,
+    future::poll_fn,
+    sync::{Arc, Mutex},
+    task::{Poll, Waker},
+};
+
+/// Which requests fit in the slots left, and how many each takes.
+pub(crate) trait SlotPolicy {
+    /// What a request takes the slots of, such as its model.
+    type Key: Clone;
+    /// The order waiting requests are handed slots in, the lowest first.
+    type Rank: Ord;
+
+    fn fits(&self, key: &Self::Key) -> bool;
+    fn take(&mut self, key: &Self::Key);
+    fn release(&mut self, key: &Self::Key);
+}
+
+#[derive(Debug, Default)]
+struct Ticket {
+    granted: bool,
+    waker: Option<Waker>,
+}
+
+struct Waiter<P: SlotPolicy> {
+    key: P::Key,
+    rank: P::Rank,
+    ticket: Arc<Mutex<Ticket>>,
+}
+
+struct State<P: SlotPolicy> {
+    policy: P,
+    /// Sorted by rank, then in the order the requests were enqueued.
+    waiting: Vec<Waiter<P>>,
+}
+
+impl<P: SlotPolicy> State<P> {
+    /// Frees a slot, handing slots to the first waiting requests that now fit.
+    fn release(&mut self, key: &P::Key) {
+        self.policy.release(key);
+
+        let mut i = 0;
+        while i < self.waiting.len() {
+            if !self.policy.fits(&self.waiting[i].key) {
+                i += 1;
+                continue;
+            }
+            let waiter = self.waiting.remove(i);
+            self.policy.take(&waiter.key);
+            let mut ticket = waiter.ticket.lock().unwrap();
+            ticket.granted = true;
+            if let Some(waker) = ticket.waker.take() {
+                waker.wake();
+            }
+        }
+    }
+}
+
+/// Slots shared by the requests holding a [`Permit`] and those waiting for one.
+pub(crate) struct Slots<P: SlotPolicy> {
+    state: Mutex<State<P>>,
+}
+
+impl<P: SlotPolicy + fmt::Debug> fmt::Debug for Slots<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.state.lock().unwrap();
+        f.debug_struct("Slots")
+            .field("policy", &state.policy)
+            .field("waiting", &state.waiting.len())
+            .finish()
+    }
+}
+
+impl<P: SlotPolicy> Slots<P> {
+    pub(crate) fn new(policy: P) -> Self {
+        Self {
+            state: Mutex::new(State {
+                policy,
+                waiting: Vec::new(),
+            }),
+        }
+    }
+
+    /// Reads or updates the policy, e.g. its limits.
+    pub(crate) fn policy<T>(&self, f: impl FnOnce(&mut P) -> T) -> T {
+        f(&mut self.state.lock().unwrap().policy)
+    }
+
+    /// The number of requests waiting whose key matches `f`.
+    pub(crate) fn waiting(&self, f: impl Fn(&P::Key) -> bool) -> usize {
+        let state = self.state.lock().unwrap();
+        state.waiting.iter().filter(|waiter| f(&waiter.key)).count()
+    }
+
+    /// Waits for the slots of `key`, behind the waiting requests of a lower or equal rank.
+    pub(crate) async fn acquire(self: &Arc<Self>, key: P::Key, rank: P::Rank) -> Permit<P> {
+        let ticket = {
+            let mut state = self.state.lock().unwrap();
+            // Waiting requests only remain while they don't fit, so a request that fits
+            // doesn't overtake them
+            if state.policy.fits(&key) {
+                state.policy.take(&key);
+                return Permit {
+                    slots: self.clone(),
+                    key,
+                };
+            }
+
+            let ticket = Arc::new(Mutex::new(Ticket::default()));
+            let at = state.waiting.partition_point(|waiter| waiter.rank <= rank);
+            state.waiting.insert(
+                at,
+                Waiter {
+                    key: key.clone(),
+                    rank,
+                    ticket: ticket.clone(),
+                },
+            );
+            ticket
+        };
+
+        let mut waiting = Waiting {
+            slots: self,
+            key: &key,
+            ticket,
+            done: false,
+        };
+        poll_fn(|cx| {
+            let mut ticket = waiting.ticket.lock().unwrap();
+            if ticket.granted {
+                Poll::Ready(())
+            } else {
+                ticket.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        })
+        .await;
+        waiting.done = true;
+        drop(waiting);
+
+        Permit {
+            slots: self.clone(),
+            key,
+        }
+    }
+}
+
+/// The slots of a request, freed when dropped.
+#[derive(Debug)]
+pub(crate) struct Permit<P: SlotPolicy> {
+    slots: Arc<Slots<P>>,
+    key: P::Key,
+}
+
+impl<P: SlotPolicy> Drop for Permit<P> {
+    fn drop(&mut self) {
+        self.slots.state.lock().unwrap().release(&self.key);
+    }
+}
+
+/// Removes a request from the queue if it is cancelled while waiting.
+struct Waiting<'a, P: SlotPolicy> {
+    slots: &'a Slots<P>,
+    key: &'a P::Key,
+    ticket: Arc<Mutex<Ticket>>,
+    done: bool,
+}
+
+impl<P: SlotPolicy> Drop for Waiting<'_, P> {
+    fn drop(&mut self) {
+        if self.done {
+            return;
+        }
+
+        let mut state = self.slots.state.lock().unwrap();
+        if self.ticket.lock().unwrap().granted {
+            // The slot was handed over after the request was cancelled.
+            state.release(self.key);
+        } else {
+            state
+                .waiting
+                .retain(|waiter| !Arc::ptr_eq(&waiter.ticket, &self.ticket));
+        }
+    }
+}
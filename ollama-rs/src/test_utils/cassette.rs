@@ -0,0 +1,236 @@
+//! Recording of real exchanges with Ollama to fixture files ("cassettes"), and their replay.
+
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use url::Url;
+
+use super::{MockResponse, RecordedRequest};
+
+/// Headers that describe a single HTTP exchange rather than its content.
+const HOP_HEADERS: [&str; 6] = [
+    "host",
+    "connection",
+    "content-length",
+    "transfer-encoding",
+    "accept-encoding",
+    "date",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(super) struct Interaction {
+    hash: String,
+    request: InteractionRequest,
+    response: InteractionResponse,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct InteractionRequest {
+    method: String,
+    path: String,
+    body: Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct InteractionResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    #[serde(flatten)]
+    body: InteractionBody,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum InteractionBody {
+    Body(String),
+    /// The lines of a streamed response, in order.
+    Chunks(Vec<String>),
+}
+
+impl Interaction {
+    pub(super) fn to_response(&self) -> MockResponse {
+        let response = &self.response;
+        let mut mock = match &response.body {
+            InteractionBody::Body(body) => MockResponse::body(body.clone()),
+            InteractionBody::Chunks(chunks) => MockResponse::frames(chunks.clone()),
+        }
+        .status(response.status);
+
+        mock.headers = response.headers.clone();
+        mock
+    }
+}
+
+pub(super) enum Mode {
+    Record {
+        upstream: Url,
+        client: reqwest::Client,
+    },
+    Replay,
+}
+
+/// A set of recorded interactions, stored as JSON at `path`.
+pub(super) struct Cassette {
+    path: PathBuf,
+    pub(super) mode: Mode,
+    interactions: Vec<Interaction>,
+    /// How many times each request hash has been replayed
+    replayed: HashMap<String, usize>,
+}
+
+impl Cassette {
+    pub(super) fn record(path: &Path, upstream: Url) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            mode: Mode::Record {
+                upstream,
+                client: reqwest::Client::new(),
+            },
+            interactions: Vec::new(),
+            replayed: HashMap::new(),
+        }
+    }
+
+    pub(super) fn load(path: &Path) -> std::io::Result<Self> {
+        let interactions = serde_json::from_slice(&fs::read(path)?)?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            mode: Mode::Replay,
+            interactions,
+            replayed: HashMap::new(),
+        })
+    }
+
+    /// Returns the recorded response to `request`. Identical requests are answered with their
+    /// recorded responses in order, the last one being repeated once they are used up.
+    pub(super) fn replay(&mut self, request: &RecordedRequest) -> Option<MockResponse> {
+        let hash = request_hash(request);
+        let matching = self
+            .interactions
+            .iter()
+            .filter(|i| i.hash == hash)
+            .collect::<Vec<_>>();
+
+        let count = self.replayed.entry(hash).or_default();
+        let interaction = matching.get(*count).or(matching.last())?;
+        *count += 1;
+
+        Some(interaction.to_response())
+    }
+
+    /// Adds an interaction and saves the cassette.
+    pub(super) fn push(&mut self, interaction: Interaction) -> std::io::Result<()> {
+        self.interactions.push(interaction);
+
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, serde_json::to_vec_pretty(&self.interactions)?)
+    }
+}
+
+/// Sends `request` to the upstream server and records the exchange.
+pub(super) async fn forward(
+    client: &reqwest::Client,
+    upstream: &Url,
+    request: &RecordedRequest,
+) -> Result<Interaction, reqwest::Error> {
+    let url = crate::join_path(upstream, &request.path);
+    let method = request.method.parse().unwrap_or(reqwest::Method::POST);
+
+    let mut builder = client.request(method, url).body(request.body.clone());
+    for (name, value) in &request.headers {
+        if !HOP_HEADERS.contains(&name.as_str()) {
+            builder = builder.header(name, value);
+        }
+    }
+
+    let res = builder.send().await?;
+    let status = res.status().as_u16();
+    let headers = res
+        .headers()
+        .iter()
+        .filter(|(name, _)| !HOP_HEADERS.contains(&name.as_str()))
+        .map(|(name, value)| {
+            (
+                name.to_string(),
+                String::from_utf8_lossy(value.as_bytes()).into_owned(),
+            )
+        })
+        .collect::<Vec<_>>();
+    let is_stream = headers
+        .iter()
+        .any(|(name, value)| name == "content-type" && value.contains("ndjson"));
+
+    let body = String::from_utf8_lossy(&res.bytes().await?).into_owned();
+    let body = if is_stream {
+        InteractionBody::Chunks(body.split_inclusive('\n').map(str::to_string).collect())
+    } else {
+        InteractionBody::Body(body)
+    };
+
+    Ok(Interaction {
+        hash: request_hash(request),
+        request: InteractionRequest {
+            method: request.method.clone(),
+            path: request.path.clone(),
+            body: request_body(request),
+        },
+        response: InteractionResponse {
+            status,
+            headers,
+            body,
+        },
+    })
+}
+
+fn request_body(request: &RecordedRequest) -> Value {
+    if request.body.is_empty() {
+        return Value::Null;
+    }
+
+    serde_json::from_slice(&request.body)
+        .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(&request.body).into_owned()))
+}
+
+/// A hash of the method, path and body of a request which is stable across runs, platforms and
+/// key order, so it can be stored in fixtures.
+fn request_hash(request: &RecordedRequest) -> String {
+    let body = canonicalize(request_body(request)).to_string();
+
+    let hash = crate::stable_hash(
+        &[
+            request.method.as_bytes(),
+            b" ",
+            request.path.as_bytes(),
+            b"\n",
+            body.as_bytes(),
+        ]
+        .concat(),
+    );
+    format!("{hash:016x}")
+}
+
+/// Sorts the keys of every object, so that equivalent bodies serialize identically.
+fn canonicalize(value: Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut entries = map.into_iter().collect::<Vec<_>>();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(k, v)| (k, canonicalize(v)))
+                    .collect::<Map<_, _>>(),
+            )
+        }
+        Value::Array(values) => Value::Array(values.into_iter().map(canonicalize).collect()),
+        value => value,
+    }
+}
@@ -0,0 +1,603 @@
+//! Utilities to test code using ollama-rs without a running Ollama instance.
+//!
+//! [`MockOllama`] is a small in-process HTTP server answering with canned responses,
+//! which can be streamed chunk by chunk, delayed or cut short to simulate failures.
+//!
+//! ```no_run
+//! # async fn example() {
+//! use ollama_rs::{
+//!     generation::completion::request::GenerationRequest,
+//!     test_utils::{MockOllama, MockResponse},
+//! };
+//!
+//! let server = MockOllama::start().await;
+//! server.mock("/api/generate", MockResponse::generation("Rayleigh scattering."));
+//!
+//! let res = server
+//!     .client()
+//!     .generate(GenerationRequest::new("llama3.2".to_string(), "Why is the sky blue?"))
+//!     .await
+//!     .unwrap();
+//!
+//! assert_eq!(res.response, "Rayleigh scattering.");
+//! assert_eq!(server.requests().len(), 1);
+//! # }
+//! ```
+//!
+//! It can also sit in front of a real Ollama server to record the exchanges to a fixture file,
+//! and replay them later so that tests are deterministic and don't need the server, see
+//! [`MockOllama::record_or_replay`].
+
+mod cassette;
+
+use std::{
+    collections::{HashMap, VecDeque},
+    net::SocketAddr,
+    path::Path,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    task::JoinHandle,
+};
+use url::Url;
+
+use crate::Ollama;
+
+use cassette::{Cassette, Mode};
+
+const CREATED_AT: &str = "2024-01-01T00:00:00.000000000Z";
+
+/// A canned response returned by [`MockOllama`].
+#[derive(Debug, Clone)]
+pub struct MockResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: MockBody,
+    delay: Duration,
+    chunk_delay: Duration,
+    interrupt: bool,
+}
+
+#[derive(Debug, Clone)]
+enum MockBody {
+    Full(Vec<u8>),
+    Chunked(Vec<Vec<u8>>),
+}
+
+impl MockResponse {
+    /// A `200 OK` response with the given JSON body.
+    pub fn json(body: impl Serialize) -> Self {
+        Self::body(serde_json::to_vec(&body).expect("mock body must serialize"))
+    }
+
+    /// A `200 OK` response with the given body, sent as-is.
+    pub fn body(body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status: 200,
+            headers: vec![("content-type".to_string(), "application/json".to_string())],
+            body: MockBody::Full(body.into()),
+            delay: Duration::ZERO,
+            chunk_delay: Duration::ZERO,
+            interrupt: false,
+        }
+    }
+
+    /// An error response, with the `{"error": message}` body Ollama uses.
+    pub fn error(status: u16, message: impl Into<String>) -> Self {
+        Self::json(json!({ "error": message.into() })).status(status)
+    }
+
+    /// A streamed response sending each item as a line of NDJSON, in a separate chunk.
+    pub fn stream<T: Serialize>(chunks: impl IntoIterator<Item = T>) -> Self {
+        Self::frames(chunks.into_iter().map(|c| {
+            let mut line = serde_json::to_vec(&c).expect("mock chunk must serialize");
+            line.push(b'\n');
+            line
+        }))
+    }
+
+    /// A streamed response sending the given bytes as-is, one chunk per item.
+    /// Useful to test how a client copes with JSON objects split across chunks.
+    pub fn frames(frames: impl IntoIterator<Item = impl Into<Vec<u8>>>) -> Self {
+        Self {
+            status: 200,
+            headers: vec![(
+                "content-type".to_string(),
+                "application/x-ndjson".to_string(),
+            )],
+            body: MockBody::Chunked(frames.into_iter().map(Into::into).collect()),
+            delay: Duration::ZERO,
+            chunk_delay: Duration::ZERO,
+            interrupt: false,
+        }
+    }
+
+    /// A complete `/api/generate` response.
+    pub fn generation(response: &str) -> Self {
+        Self::json(generation_chunk(response, true))
+    }
+
+    /// A streamed `/api/generate` response, one chunk per token.
+    pub fn generation_stream(tokens: &[&str]) -> Self {
+        Self::stream(
+            tokens
+                .iter()
+                .enumerate()
+                .map(|(i, token)| generation_chunk(token, i + 1 == tokens.len())),
+        )
+    }
+
+    /// A complete `/api/chat` response from the assistant.
+    pub fn chat(content: &str) -> Self {
+        Self::json(chat_chunk(
+            json!({ "role": "assistant", "content": content }),
+            true,
+        ))
+    }
+
+    /// A streamed `/api/chat` response, one chunk per token.
+    pub fn chat_stream(tokens: &[&str]) -> Self {
+        Self::stream(tokens.iter().enumerate().map(|(i, token)| {
+            chat_chunk(
+                json!({ "role": "assistant", "content": token }),
+                i + 1 == tokens.len(),
+            )
+        }))
+    }
+
+    /// An `/api/chat` response in which the assistant calls a tool.
+    pub fn tool_call(name: &str, arguments: Value) -> Self {
+        Self::tool_calls([(name, arguments)])
+    }
+
+    /// An `/api/chat` response in which the assistant calls several tools.
+    pub fn tool_calls<'a>(calls: impl IntoIterator<Item = (&'a str, Value)>) -> Self {
+        let calls = calls
+            .into_iter()
+            .map(
+                |(name, arguments)| json!({ "function": { "name": name, "arguments": arguments } }),
+            )
+            .collect::<Vec<_>>();
+
+        Self::json(chat_chunk(
+            json!({ "role": "assistant", "content": "", "tool_calls": calls }),
+            true,
+        ))
+    }
+
+    /// An `/api/embed` response.
+    pub fn embeddings(embeddings: Vec<Vec<f32>>) -> Self {
+        Self::json(json!({ "model": "mock", "embeddings": embeddings }))
+    }
+
+    /// Sets the HTTP status of the response.
+    pub fn status(mut self, status: u16) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// Adds a header to the response.
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Waits before sending the response.
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Waits between the chunks of a streamed response.
+    pub fn chunk_delay(mut self, delay: Duration) -> Self {
+        self.chunk_delay = delay;
+        self
+    }
+
+    /// Drops the connection before the end of a streamed response, after sending every chunk.
+    pub fn interrupt(mut self) -> Self {
+        self.interrupt = true;
+        self
+    }
+}
+
+fn generation_chunk(response: &str, done: bool) -> Value {
+    let mut chunk = json!({
+        "model": "mock",
+        "created_at": CREATED_AT,
+        "response": response,
+        "done": done,
+    });
+    if done {
+        add_final_data(&mut chunk);
+    }
+    chunk
+}
+
+fn chat_chunk(message: Value, done: bool) -> Value {
+    let mut chunk = json!({
+        "model": "mock",
+        "created_at": CREATED_AT,
+        "message": message,
+        "done": done,
+    });
+    if done {
+        add_final_data(&mut chunk);
+    }
+    chunk
+}
+
+fn add_final_data(chunk: &mut Value) {
+    let chunk = chunk.as_object_mut().unwrap();
+    chunk.insert("done_reason".to_string(), json!("stop"));
+    chunk.insert("total_duration".to_string(), json!(1_000_000));
+    chunk.insert("load_duration".to_string(), json!(100_000));
+    chunk.insert("prompt_eval_count".to_string(), json!(10));
+    chunk.insert("prompt_eval_duration".to_string(), json!(200_000));
+    chunk.insert("eval_count".to_string(), json!(20));
+    chunk.insert("eval_duration".to_string(), json!(700_000));
+}
+
+/// A request received by [`MockOllama`].
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub path: String,
+    /// Header names are lowercased.
+    pub headers: HashMap<String, String>,
+    pub body: Vec<u8>,
+}
+
+impl RecordedRequest {
+    /// The body of the request parsed as JSON, or `Value::Null` if it isn't valid JSON.
+    pub fn json(&self) -> Value {
+        serde_json::from_slice(&self.body).unwrap_or(Value::Null)
+    }
+}
+
+type Responder = Box<dyn Fn(&RecordedRequest) -> MockResponse + Send + Sync>;
+
+#[derive(Default)]
+struct State {
+    once: HashMap<String, VecDeque<MockResponse>>,
+    always: HashMap<String, Responder>,
+    cassette: Option<Cassette>,
+    requests: Vec<RecordedRequest>,
+}
+
+enum Reply {
+    Mock(MockResponse),
+    Forward {
+        upstream: Url,
+        client: reqwest::Client,
+    },
+}
+
+impl State {
+    fn reply_for(&mut self, request: &RecordedRequest) -> Reply {
+        let path = &request.path;
+        if let Some(res) = self.once.get_mut(path).and_then(VecDeque::pop_front) {
+            return Reply::Mock(res);
+        }
+        if let Some(responder) = self.always.get(path) {
+            return Reply::Mock(responder(request));
+        }
+
+        match &mut self.cassette {
+            Some(Cassette {
+                mode: Mode::Record { upstream, client },
+                ..
+            }) => Reply::Forward {
+                upstream: upstream.clone(),
+                client: client.clone(),
+            },
+            Some(cassette) => Reply::Mock(cassette.replay(request).unwrap_or_else(|| {
+                MockResponse::error(404, format!("no recorded interaction for {path}"))
+            })),
+            None => Reply::Mock(MockResponse::error(404, format!("no mock for {path}"))),
+        }
+    }
+}
+
+/// An in-process mock of the Ollama HTTP API.
+///
+/// Responses are registered per path. Responses registered with [`MockOllama::mock_once`] are
+/// used first, in order, then the one registered with [`MockOllama::mock`], then the cassette
+/// being recorded or replayed, if any. Requests to a path without any response get a `404`.
+///
+/// The server stops when this value is dropped.
+pub struct MockOllama {
+    addr: SocketAddr,
+    state: Arc<Mutex<State>>,
+    handle: JoinHandle<()>,
+}
+
+impl MockOllama {
+    /// Starts a server listening on a random local port.
+    pub async fn start() -> Self {
+        Self::start_with(State::default()).await
+    }
+
+    /// Starts a server forwarding requests to `upstream`, typically a real Ollama server, and
+    /// saving every exchange to the `cassette` file, overwriting it.
+    /// Streamed responses are stored line by line.
+    pub async fn record(cassette: impl AsRef<Path>, upstream: Url) -> Self {
+        Self::start_with(State {
+            cassette: Some(Cassette::record(cassette.as_ref(), upstream)),
+            ..Default::default()
+        })
+        .await
+    }
+
+    /// Starts a server answering requests with the responses recorded in the `cassette` file.
+    ///
+    /// Requests are matched by a hash of their method, path and body. Requests that weren't
+    /// recorded get a `404`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cassette can't be read.
+    pub async fn replay(cassette: impl AsRef<Path>) -> Self {
+        let cassette = cassette.as_ref();
+        let cassette = Cassette::load(cassette)
+            .unwrap_or_else(|e| panic!("failed to load the cassette {}: {e}", cassette.display()));
+
+        Self::start_with(State {
+            cassette: Some(cassette),
+            ..Default::default()
+        })
+        .await
+    }
+
+    /// Replays the `cassette` file if it exists, or records it from `upstream` otherwise.
+    /// Setting the `OLLAMA_RS_RECORD` environment variable forces recording, to refresh fixtures.
+    pub async fn record_or_replay(cassette: impl AsRef<Path>, upstream: Url) -> Self {
+        let cassette = cassette.as_ref();
+        if cassette.exists() && std::env::var_os("OLLAMA_RS_RECORD").is_none() {
+            Self::replay(cassette).await
+        } else {
+            Self::record(cassette, upstream).await
+        }
+    }
+
+    async fn start_with(state: State) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("failed to bind the mock server");
+        let addr = listener.local_addr().unwrap();
+        let state = Arc::new(Mutex::new(state));
+
+        let handle = tokio::spawn({
+            let state = state.clone();
+            async move {
+                while let Ok((socket, _)) = listener.accept().await {
+                    tokio::spawn(handle_connection(socket, state.clone()));
+                }
+            }
+        });
+
+        Self {
+            addr,
+            state,
+            handle,
+        }
+    }
+
+    /// The URL of the server.
+    pub fn url(&self) -> Url {
+        Url::parse(&format!("http://{}/", self.addr)).unwrap()
+    }
+
+    /// A client connected to this server.
+    pub fn client(&self) -> Ollama {
+        Ollama::from_url(self.url())
+    }
+
+    /// Answers every request to `path` with `response`, once responses queued with
+    /// [`MockOllama::mock_once`] have been used up.
+    pub fn mock(&self, path: &str, response: MockResponse) -> &Self {
+        self.mock_with(path, move |_| response.clone())
+    }
+
+    /// Answers every request to `path` with the response built by `responder`, once responses
+    /// queued with [`MockOllama::mock_once`] have been used up.
+    pub fn mock_with(
+        &self,
+        path: &str,
+        responder: impl Fn(&RecordedRequest) -> MockResponse + Send + Sync + 'static,
+    ) -> &Self {
+        self.state
+            .lock()
+            .unwrap()
+            .always
+            .insert(path.to_string(), Box::new(responder));
+        self
+    }
+
+    /// Answers the next request to `path` with `response`. Calling it several times for the
+    /// same path queues the responses, which is useful to script multi-step tool calls.
+    pub fn mock_once(&self, path: &str, response: MockResponse) -> &Self {
+        self.state
+            .lock()
+            .unwrap()
+            .once
+            .entry(path.to_string())
+            .or_default()
+            .push_back(response);
+        self
+    }
+
+    /// The requests received so far, in order.
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.state.lock().unwrap().requests.clone()
+    }
+}
+
+impl Drop for MockOllama {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+async fn handle_connection(mut socket: TcpStream, state: Arc<Mutex<State>>) {
+    let Some(request) = read_request(&mut socket).await else {
+        return;
+    };
+
+    let reply = {
+        let mut state = state.lock().unwrap();
+        state.requests.push(request.clone());
+        state.reply_for(&request)
+    };
+
+    let response = match reply {
+        Reply::Mock(response) => response,
+        Reply::Forward { upstream, client } => {
+            match cassette::forward(&client, &upstream, &request).await {
+                Ok(interaction) => {
+                    let response = interaction.to_response();
+                    let mut state = state.lock().unwrap();
+                    if let Some(Err(e)) = state.cassette.as_mut().map(|c| c.push(interaction)) {
+                        MockResponse::error(500, format!("failed to save the cassette: {e}"))
+                    } else {
+                        response
+                    }
+                }
+                Err(e) => MockResponse::error(502, format!("failed to reach upstream: {e}")),
+            }
+        }
+    };
+
+    let _ = write_response(&mut socket, response).await;
+}
+
+async fn read_request(socket: &mut TcpStream) -> Option<RecordedRequest> {
+    let mut data = Vec::new();
+    let mut buf = [0; 8192];
+
+    let header_end = loop {
+        if let Some(pos) = data.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos;
+        }
+        let n = socket.read(&mut buf).await.ok()?;
+        if n == 0 {
+            return None;
+        }
+        data.extend_from_slice(&buf[..n]);
+    };
+
+    let head = String::from_utf8_lossy(&data[..header_end]).into_owned();
+    let mut lines = head.lines();
+    let mut request_line = lines.next()?.split_whitespace();
+    let method = request_line.next()?.to_string();
+    let target = request_line.next()?;
+    let path = target.split('?').next().unwrap_or(target).to_string();
+
+    let headers = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(k, v)| (k.trim().to_lowercase(), v.trim().to_string()))
+        .collect::<HashMap<_, _>>();
+
+    let content_length = headers
+        .get("content-length")
+        .and_then(|l| l.parse::<usize>().ok())
+        .unwrap_or(0);
+
+    let chunked = headers
+        .get("transfer-encoding")
+        .is_some_and(|encoding| encoding.eq_ignore_ascii_case("chunked"));
+
+    let mut body = data[header_end + 4..].to_vec();
+    loop {
+        let complete = if chunked {
+            body.ends_with(b"0\r\n\r\n")
+        } else {
+            body.len() >= content_length
+        };
+        if complete {
+            break;
+        }
+        let n = socket.read(&mut buf).await.ok()?;
+        if n == 0 {
+            break;
+        }
+        body.extend_from_slice(&buf[..n]);
+    }
+    if chunked {
+        body = decode_chunked(&body);
+    }
+
+    Some(RecordedRequest {
+        method,
+        path,
+        headers,
+        body,
+    })
+}
+
+/// Decodes a body sent with `transfer-encoding: chunked`.
+fn decode_chunked(mut data: &[u8]) -> Vec<u8> {
+    let mut body = Vec::new();
+    while let Some(line_end) = data.windows(2).position(|w| w == b"\r\n") {
+        let size = std::str::from_utf8(&data[..line_end])
+            .ok()
+            .and_then(|size| usize::from_str_radix(size.trim(), 16).ok())
+            .unwrap_or(0);
+        let start = line_end + 2;
+        if size == 0 || data.len() < start + size {
+            break;
+        }
+        body.extend_from_slice(&data[start..start + size]);
+        data = &data[(start + size + 2).min(data.len())..];
+    }
+    body
+}
+
+async fn write_response(socket: &mut TcpStream, response: MockResponse) -> std::io::Result<()> {
+    tokio::time::sleep(response.delay).await;
+
+    let mut head = format!("HTTP/1.1 {} Mock\r\nconnection: close\r\n", response.status);
+    for (name, value) in &response.headers {
+        head.push_str(&format!("{name}: {value}\r\n"));
+    }
+
+    match response.body {
+        MockBody::Full(body) => {
+            head.push_str(&format!("content-length: {}\r\n\r\n", body.len()));
+            socket.write_all(head.as_bytes()).await?;
+            socket.write_all(&body).await?;
+        }
+        MockBody::Chunked(frames) => {
+            head.push_str("transfer-encoding: chunked\r\n\r\n");
+            socket.write_all(head.as_bytes()).await?;
+
+            for (i, frame) in frames.iter().enumerate() {
+                if i > 0 {
+                    tokio::time::sleep(response.chunk_delay).await;
+                }
+                socket
+                    .write_all(format!("{:x}\r\n", frame.len()).as_bytes())
+                    .await?;
+                socket.write_all(frame).await?;
+                socket.write_all(b"\r\n").await?;
+                socket.flush().await?;
+            }
+
+            if response.interrupt {
+                // Announce a chunk that never arrives
+                socket.write_all(b"100\r\n{").await?;
+            } else {
+                socket.write_all(b"0\r\n\r\n").await?;
+            }
+        }
+    }
+
+    socket.flush().await?;
+    socket.shutdown().await
+}
@@ -0,0 +1,276 @@
+#[cfg(any(feature = "time", feature = "chrono"))]
+use std::time::Duration;
+use std::{fmt, time::SystemTime};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+#[cfg(feature = "time")]
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+
+#[cfg(feature = "time")]
+type Inner = OffsetDateTime;
+#[cfg(all(feature = "chrono", not(feature = "time")))]
+type Inner = chrono::DateTime<chrono::FixedOffset>;
+#[cfg(not(any(feature = "time", feature = "chrono")))]
+type Inner = String;
+
+/// A point in time sent by Ollama, such as `2023-08-04T08:52:19.385406455-07:00`.
+///
+/// Timestamps keep the UTC offset they were sent with, are compared by the instant they
+/// represent, and are serialized as RFC 3339. They are backed by [`time::OffsetDateTime`] with
+/// the `time` feature, which is enabled by default, or by `chrono::DateTime` with only the
+/// `chrono` feature, and convert from and into the types of the enabled features and
+/// [`SystemTime`].
+///
+/// With neither feature, timestamps keep the string Ollama sent as is: they can then only be
+/// displayed, serialized and compared for equality.
+#[cfg_attr(any(feature = "time", feature = "chrono"), derive(PartialOrd, Ord))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Timestamp(Inner);
+
+impl Timestamp {
+    /// The current time, in UTC.
+    pub fn now() -> Self {
+        #[cfg(feature = "time")]
+        return Self(OffsetDateTime::now_utc());
+        #[cfg(not(feature = "time"))]
+        return Self::from(SystemTime::now());
+    }
+
+    /// Parses an RFC 3339 timestamp.
+    #[cfg(any(feature = "time", feature = "chrono"))]
+    pub fn parse(s: &str) -> Option<Self> {
+        #[cfg(feature = "time")]
+        return OffsetDateTime::parse(s, &Rfc3339).ok().map(Self);
+        #[cfg(not(feature = "time"))]
+        return chrono::DateTime::parse_from_rfc3339(s).ok().map(Self);
+    }
+
+    /// The UTC timestamp of `secs` seconds since the Unix epoch, clamped to the range of the
+    /// underlying date type.
+    pub fn from_unix_timestamp(secs: i64) -> Self {
+        #[cfg(feature = "time")]
+        return Self(
+            OffsetDateTime::from_unix_timestamp(secs).unwrap_or(if secs < 0 {
+                time::PrimitiveDateTime::MIN.assume_utc()
+            } else {
+                time::PrimitiveDateTime::MAX.assume_utc()
+            }),
+        );
+        #[cfg(all(feature = "chrono", not(feature = "time")))]
+        return Self(
+            chrono::DateTime::from_timestamp(secs, 0)
+                .unwrap_or(if secs < 0 {
+                    chrono::DateTime::<chrono::Utc>::MIN_UTC
+                } else {
+                    chrono::DateTime::<chrono::Utc>::MAX_UTC
+                })
+                .fixed_offset(),
+        );
+        #[cfg(not(any(feature = "time", feature = "chrono")))]
+        return Self(format_utc(secs, 0));
+    }
+
+    /// The number of seconds since the Unix epoch.
+    #[cfg(any(feature = "time", feature = "chrono"))]
+    pub fn unix_timestamp(&self) -> i64 {
+        #[cfg(feature = "time")]
+        return self.0.unix_timestamp();
+        #[cfg(not(feature = "time"))]
+        return self.0.timestamp();
+    }
+
+    /// The number of nanoseconds since the Unix epoch.
+    #[cfg(any(feature = "time", feature = "chrono"))]
+    pub fn unix_timestamp_nanos(&self) -> i128 {
+        #[cfg(feature = "time")]
+        return self.0.unix_timestamp_nanos();
+        #[cfg(not(feature = "time"))]
+        return i128::from(self.0.timestamp()) * 1_000_000_000
+            + i128::from(self.0.timestamp_subsec_nanos());
+    }
+
+    /// The offset from UTC of the time zone of the timestamp, in seconds.
+    #[cfg(any(feature = "time", feature = "chrono"))]
+    pub fn utc_offset_seconds(&self) -> i32 {
+        #[cfg(feature = "time")]
+        return self.0.offset().whole_seconds();
+        #[cfg(not(feature = "time"))]
+        return chrono::Offset::fix(self.0.offset()).local_minus_utc();
+    }
+
+    /// The time elapsed from `earlier` to this timestamp, or `None` if `earlier` is later.
+    #[cfg(any(feature = "time", feature = "chrono"))]
+    pub fn duration_since(&self, earlier: &Timestamp) -> Option<Duration> {
+        #[cfg(feature = "time")]
+        return (self.0 - earlier.0).try_into().ok();
+        #[cfg(not(feature = "time"))]
+        return (self.0 - earlier.0).to_std().ok();
+    }
+
+    /// The timestamp as sent by Ollama.
+    #[cfg(not(any(feature = "time", feature = "chrono")))]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    #[cfg_attr(docsrs, doc(cfg(feature = "time")))]
+    #[cfg(feature = "time")]
+    pub fn to_offset_date_time(&self) -> OffsetDateTime {
+        self.0
+    }
+
+    #[cfg_attr(docsrs, doc(cfg(feature = "chrono")))]
+    #[cfg(feature = "chrono")]
+    pub fn to_chrono(&self) -> chrono::DateTime<chrono::FixedOffset> {
+        #[cfg(feature = "time")]
+        let time = {
+            let offset = chrono::FixedOffset::east_opt(self.utc_offset_seconds())
+                .unwrap_or_else(|| chrono::FixedOffset::east_opt(0).unwrap());
+            chrono::DateTime::from_timestamp(self.unix_timestamp(), self.0.nanosecond())
+                .unwrap_or_default()
+                .with_timezone(&offset)
+        };
+        #[cfg(not(feature = "time"))]
+        let time = self.0;
+        time
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "time")))]
+#[cfg(feature = "time")]
+impl From<OffsetDateTime> for Timestamp {
+    fn from(time: OffsetDateTime) -> Self {
+        Self(time)
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "time")))]
+#[cfg(feature = "time")]
+impl From<Timestamp> for OffsetDateTime {
+    fn from(timestamp: Timestamp) -> Self {
+        timestamp.0
+    }
+}
+
+impl From<SystemTime> for Timestamp {
+    fn from(time: SystemTime) -> Self {
+        #[cfg(feature = "time")]
+        return Self(time.into());
+        #[cfg(all(feature = "chrono", not(feature = "time")))]
+        return Self(chrono::DateTime::<chrono::Utc>::from(time).fixed_offset());
+        #[cfg(not(any(feature = "time", feature = "chrono")))]
+        return Self(match time.duration_since(std::time::UNIX_EPOCH) {
+            Ok(since) => format_utc(since.as_secs() as i64, since.subsec_nanos()),
+            Err(before) => {
+                let before = before.duration();
+                match before.subsec_nanos() {
+                    0 => format_utc(-(before.as_secs() as i64), 0),
+                    nanos => format_utc(-(before.as_secs() as i64) - 1, 1_000_000_000 - nanos),
+                }
+            }
+        });
+    }
+}
+
+#[cfg(any(feature = "time", feature = "chrono"))]
+impl From<Timestamp> for SystemTime {
+    fn from(timestamp: Timestamp) -> Self {
+        timestamp.0.into()
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "chrono")))]
+#[cfg(feature = "chrono")]
+impl<Tz: chrono::TimeZone> From<chrono::DateTime<Tz>> for Timestamp {
+    fn from(time: chrono::DateTime<Tz>) -> Self {
+        let time = time.fixed_offset();
+        #[cfg(feature = "time")]
+        let time = {
+            let offset = time::UtcOffset::from_whole_seconds(time.offset().local_minus_utc())
+                .unwrap_or(time::UtcOffset::UTC);
+            let utc = Self::from_unix_timestamp(time.timestamp()).0
+                + time::Duration::nanoseconds(time.timestamp_subsec_nanos().into());
+            utc.to_offset(offset)
+        };
+        Self(time)
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "chrono")))]
+#[cfg(feature = "chrono")]
+impl From<Timestamp> for chrono::DateTime<chrono::FixedOffset> {
+    fn from(timestamp: Timestamp) -> Self {
+        timestamp.to_chrono()
+    }
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        #[cfg(feature = "time")]
+        return f.write_str(&self.0.format(&Rfc3339).map_err(|_| fmt::Error)?);
+        #[cfg(all(feature = "chrono", not(feature = "time")))]
+        return f.write_str(&self.0.to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true));
+        #[cfg(not(any(feature = "time", feature = "chrono")))]
+        return f.write_str(&self.0);
+    }
+}
+
+#[cfg(any(feature = "time", feature = "chrono"))]
+impl std::str::FromStr for Timestamp {
+    type Err = crate::error::OllamaError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+            .ok_or_else(|| crate::error::OllamaError::Other(format!("Invalid timestamp: {s:?}")))
+    }
+}
+
+impl Serialize for Timestamp {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[cfg(feature = "time")]
+        let formatted = self.0.format(&Rfc3339).map_err(serde::ser::Error::custom)?;
+        #[cfg(not(feature = "time"))]
+        let formatted = self.to_string();
+        serializer.serialize_str(&formatted)
+    }
+}
+
+impl<'de> Deserialize<'de> for Timestamp {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = String::deserialize(deserializer)?;
+        #[cfg(any(feature = "time", feature = "chrono"))]
+        return Self::parse(&raw)
+            .ok_or_else(|| serde::de::Error::custom(format!("invalid timestamp: {raw:?}")));
+        #[cfg(not(any(feature = "time", feature = "chrono")))]
+        return Ok(Self(raw));
+    }
+}
+
+/// Formats `secs` seconds and `nanos` nanoseconds since the Unix epoch as an RFC 3339 UTC
+/// timestamp, with the days to civil date conversion of
+/// <http://howardhinnant.github.io/date_algorithms.html#civil_from_days>.
+#[cfg(not(any(feature = "time", feature = "chrono")))]
+fn format_utc(secs: i64, nanos: u32) -> String {
+    let (days, secs) = (secs.div_euclid(86_400), secs.rem_euclid(86_400));
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    let mut formatted = format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}",
+        secs / 3600,
+        secs / 60 % 60,
+        secs % 60
+    );
+    if nanos > 0 {
+        formatted.push_str(format!(".{nanos:09}").trim_end_matches('0'));
+    }
+    formatted.push('Z');
+    formatted
+}
@@ -0,0 +1,332 @@
+//! Server versions, and the features they support.
+//!
+//! With [`OllamaBuilder::version_check`](crate::OllamaBuilder::version_check), the client
+//! fetches the version of the server once, and fails requests using a feature the server is
+//! too old for with [`OllamaError::Unsupported`] instead of sending them:
+//!
+//! ```no_run
+//! # async fn run() -> ollama_rs::error::Result<()> {
+//! use ollama_rs::{version::Feature, Ollama};
+//!
+//! let ollama = Ollama::builder().version_check(true).build()?;
+//! println!("Ollama {}", ollama.server_version().await?);
+//! if !ollama.supports(Feature::StructuredOutputs).await? {
+//!     println!("Falling back to JSON mode");
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{
+    cmp::Ordering,
+    fmt,
+    str::FromStr,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use serde::Deserialize;
+
+use crate::{
+    error::{OllamaError, Result},
+    generation::{
+        chat::request::ChatMessageRequest,
+        completion::request::GenerationRequest,
+        parameters::{FormatType, Think},
+    },
+    Ollama,
+};
+
+/// The version of an Ollama server, like `0.5.7` or `0.6.0-rc1`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+    /// The pre-release, like `rc1`.
+    pub pre: Option<String>,
+}
+
+impl Version {
+    pub const fn new(major: u64, minor: u64, patch: u64) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+            pre: None,
+        }
+    }
+
+    /// Whether this is the version of a development build, `0.0.0`, which is assumed to
+    /// support every feature.
+    pub fn is_dev(&self) -> bool {
+        self.major == 0 && self.minor == 0 && self.patch == 0
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            // A pre-release comes before its release
+            .then_with(|| match (&self.pre, &other.pre) {
+                (None, None) => Ordering::Equal,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(_), None) => Ordering::Less,
+                (Some(a), Some(b)) => pre_release_key(a).cmp(&pre_release_key(b)),
+            })
+    }
+}
+
+/// A run of digits or of other characters of a pre-release identifier.
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+enum PreReleaseChunk<'a> {
+    /// The number of significant digits, the significant digits, and the whole run, so that
+    /// numbers compare by value and come before text.
+    Number(usize, &'a str, &'a str),
+    Text(&'a str),
+}
+
+/// The key comparing pre-releases the semver way, by their dot-separated identifiers, with
+/// numbers compared by value. Numbers within an identifier are compared by value too, so
+/// that `rc10` comes after `rc2`.
+fn pre_release_key(pre: &str) -> Vec<Vec<PreReleaseChunk<'_>>> {
+    pre.split('.')
+        .map(|identifier| {
+            let mut rest = identifier;
+            std::iter::from_fn(|| {
+                let digits = rest.starts_with(|c: char| c.is_ascii_digit());
+                let end = rest
+                    .find(|c: char| c.is_ascii_digit() != digits)
+                    .unwrap_or(rest.len());
+                let (chunk, tail) = rest.split_at(end);
+                rest = tail;
+                if chunk.is_empty() {
+                    None
+                } else if digits {
+                    let significant = chunk.trim_start_matches('0');
+                    Some(PreReleaseChunk::Number(
+                        significant.len(),
+                        significant,
+                        chunk,
+                    ))
+                } else {
+                    Some(PreReleaseChunk::Text(chunk))
+                }
+            })
+            .collect()
+        })
+        .collect()
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl FromStr for Version {
+    type Err = OllamaError;
+
+    fn from_str(text: &str) -> Result<Self> {
+        let invalid = || OllamaError::Other(format!("Invalid version {text:?}"));
+
+        let version = text.trim().trim_start_matches('v');
+        // Build metadata doesn't take part in comparisons
+        let version = version.split_once('+').map_or(version, |(v, _)| v);
+        let (version, pre) = match version.split_once('-') {
+            Some((version, pre)) => (version, Some(pre.to_string())),
+            None => (version, None),
+        };
+
+        let mut parts = version.split('.');
+        let mut part = |required: bool| match parts.next() {
+            Some(part) => part.parse::<u64>().map_err(|_| invalid()),
+            None if required => Err(invalid()),
+            None => Ok(0),
+        };
+        let version = Self {
+            major: part(true)?,
+            minor: part(true)?,
+            patch: part(false)?,
+            pre,
+        };
+        if parts.next().is_some() {
+            return Err(invalid());
+        }
+        Ok(version)
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if let Some(pre) = &self.pre {
+            write!(f, "-{pre}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A feature of the API that only recent servers support.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Feature {
+    /// Tools in chat requests.
+    Tools,
+    /// Tools in streamed chat requests.
+    ToolStreaming,
+    /// Responses following a JSON schema, with [`FormatType::StructuredJson`](crate::generation::parameters::FormatType::StructuredJson).
+    StructuredOutputs,
+    /// Reasoning returned apart from the answer, with [`Think`](crate::generation::parameters::Think).
+    Thinking,
+}
+
+impl Feature {
+    /// The first version of Ollama supporting the feature.
+    pub fn min_version(self) -> Version {
+        match self {
+            Self::Tools => Version::new(0, 3, 0),
+            Self::ToolStreaming => Version::new(0, 8, 0),
+            Self::StructuredOutputs => Version::new(0, 5, 0),
+            Self::Thinking => Version::new(0, 9, 0),
+        }
+    }
+}
+
+impl fmt::Display for Feature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Tools => "Tool calling",
+            Self::ToolStreaming => "Tool calling in streamed responses",
+            Self::StructuredOutputs => "Structured outputs",
+            Self::Thinking => "Thinking",
+        })
+    }
+}
+
+fn format_features(format: &Option<FormatType>) -> Option<Feature> {
+    matches!(format, Some(FormatType::StructuredJson(_))).then_some(Feature::StructuredOutputs)
+}
+
+fn think_features(think: &Option<Think>) -> Option<Feature> {
+    // Servers without thinking ignore `think`, so disabling it needs no check
+    matches!(think, Some(think) if *think != Think::Disabled).then_some(Feature::Thinking)
+}
+
+/// The features a generation request uses.
+pub(crate) fn generation_features(request: &GenerationRequest) -> Vec<Feature> {
+    format_features(&request.format)
+        .into_iter()
+        .chain(think_features(&request.think))
+        .collect()
+}
+
+/// The features a chat request uses.
+pub(crate) fn chat_features(request: &ChatMessageRequest) -> Vec<Feature> {
+    let mut features: Vec<Feature> = format_features(&request.format)
+        .into_iter()
+        .chain(think_features(&request.think))
+        .collect();
+    if !request.tools.is_empty() {
+        features.push(Feature::Tools);
+        if request.stream {
+            features.push(Feature::ToolStreaming);
+        }
+    }
+    features
+}
+
+/// How long the version checks are skipped after the version of the server couldn't be
+/// fetched, before fetching it again.
+const UNKNOWN_VERSION_TTL: Duration = Duration::from_secs(60);
+
+/// The version of the server, once fetched, shared by the clones of a client.
+#[derive(Debug, Default)]
+pub(crate) struct VersionCache {
+    version: Mutex<Option<Version>>,
+    /// When the version last couldn't be fetched.
+    unknown_since: Mutex<Option<Instant>>,
+}
+
+#[derive(Deserialize)]
+struct VersionResponse {
+    version: String,
+}
+
+impl Ollama {
+    /// The version of the server, fetched on the first call and remembered by this client and
+    /// its clones.
+    pub async fn server_version(&self) -> Result<Version> {
+        if let Some(version) = self.config.version_cache.version.lock().unwrap().clone() {
+            return Ok(version);
+        }
+
+        let builder = self.request(reqwest::Method::GET, "api/version");
+        let res = self.send(builder).await?;
+        if !res.status().is_success() {
+            return Err(OllamaError::from_http_response(res).await);
+        }
+        let version: Version = crate::error::decode_body::<VersionResponse>(res)
+            .await?
+            .version
+            .parse()?;
+
+        *self.config.version_cache.version.lock().unwrap() = Some(version.clone());
+        Ok(version)
+    }
+
+    /// Whether the server is recent enough for `feature`. Development builds are assumed to
+    /// support every feature.
+    pub async fn supports(&self, feature: Feature) -> Result<bool> {
+        let version = self.server_version().await?;
+        Ok(version.is_dev() || version >= feature.min_version())
+    }
+
+    /// Fails with [`OllamaError::Unsupported`] if the server is too old for one of `features`,
+    /// when [`Ollama::set_version_check`] is enabled.
+    ///
+    /// A server that doesn't tell its version, e.g. a gateway not forwarding `/api/version`,
+    /// is assumed to support every feature. Its version is only fetched again a minute later,
+    /// rather than before every request.
+    pub(crate) async fn check_features(
+        &self,
+        features: impl IntoIterator<Item = Feature>,
+    ) -> Result<()> {
+        let mut features = features.into_iter().peekable();
+        if !self.config.version_check || features.peek().is_none() {
+            return Ok(());
+        }
+
+        let cache = &self.config.version_cache;
+        if let Some(since) = *cache.unknown_since.lock().unwrap() {
+            if since.elapsed() < UNKNOWN_VERSION_TTL {
+                return Ok(());
+            }
+        }
+
+        let server = match self.server_version().await {
+            Ok(version) => version,
+            Err(
+                OllamaError::HttpError { .. }
+                | OllamaError::DecodeError { .. }
+                | OllamaError::Other(_),
+            ) => {
+                *cache.unknown_since.lock().unwrap() = Some(Instant::now());
+                return Ok(());
+            }
+            Err(e) => return Err(e),
+        };
+        if server.is_dev() {
+            return Ok(());
+        }
+        match features.find(|feature| server < feature.min_version()) {
+            Some(feature) => Err(OllamaError::Unsupported {
+                feature,
+                required: feature.min_version(),
+                server,
+            }),
+            None => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,71 @@
+//! Adapters serving the streams of this crate from web frameworks, e.g. to relay a
+//! generation to a browser.
+//!
+//! Every item is sent as JSON. An error ends the stream, after being sent as
+//! `{"error": message}`, the body Ollama itself sends when a streamed request fails.
+
+use futures_util::{Stream, StreamExt};
+use serde::Serialize;
+
+use crate::{
+    error::Result,
+    generation::completion::{GenerationResponse, GenerationResponseStream},
+};
+
+#[cfg_attr(docsrs, doc(cfg(feature = "actix")))]
+#[cfg(feature = "actix")]
+pub mod actix;
+#[cfg_attr(docsrs, doc(cfg(feature = "axum")))]
+#[cfg(feature = "axum")]
+pub mod axum;
+
+/// An item of a stream, encoded as JSON.
+pub(crate) enum Frame {
+    Item(String),
+    Error(String),
+}
+
+/// Encodes each item of `stream`, ending after the first error.
+pub(crate) fn json_frames<T, S>(stream: S) -> impl Stream<Item = Frame> + Send + 'static
+where
+    T: Serialize + Send,
+    S: Stream<Item = Result<T>> + Send + 'static,
+{
+    async_stream::stream! {
+        let mut stream = Box::pin(stream);
+        while let Some(item) = stream.next().await {
+            let error = match item.map(|item| serde_json::to_string(&item)) {
+                Ok(Ok(json)) => {
+                    yield Frame::Item(json);
+                    continue;
+                }
+                Ok(Err(e)) => e.to_string(),
+                Err(e) => e.to_string(),
+            };
+            yield Frame::Error(serde_json::json!({ "error": error }).to_string());
+            break;
+        }
+    }
+}
+
+/// Flattens the chunks of a generation stream into single responses.
+pub(crate) fn generation_items(
+    stream: GenerationResponseStream,
+) -> impl Stream<Item = Result<GenerationResponse>> + Send + 'static {
+    async_stream::stream! {
+        let mut stream = stream;
+        while let Some(chunk) = stream.next().await {
+            match chunk {
+                Ok(responses) => {
+                    for response in responses {
+                        yield Ok(response);
+                    }
+                }
+                Err(e) => {
+                    yield Err(e);
+                    break;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,83 @@
+//! Responses for [`actix_web`] handlers.
+//!
+//! ```no_run
+//! use actix_web::{get, HttpResponse};
+//! use ollama_rs::{generation::completion::request::GenerationRequest, web, Ollama};
+//!
+//! #[get("/generate")]
+//! async fn generate() -> HttpResponse {
+//!     let stream = Ollama::default()
+//!         .generate_stream(GenerationRequest::new("llama3.2".into(), "Why is the sky blue?"))
+//!         .await
+//!         .unwrap();
+//!     web::actix::generation_sse(stream)
+//! }
+//! ```
+
+use std::convert::Infallible;
+
+use actix_web::{http::header, web::Bytes, HttpResponse};
+use futures_util::{Stream, StreamExt};
+use serde::Serialize;
+
+use super::{generation_items, json_frames, Frame};
+use crate::{
+    error::Result,
+    generation::{chat::ChatMessageResponseStream, completion::GenerationResponseStream},
+};
+
+/// Sends each item of `stream` as a server-sent event whose data is the item as JSON.
+///
+/// An error is sent as an `error` event, ending the stream.
+pub fn sse<T, S>(stream: S) -> HttpResponse
+where
+    T: Serialize + Send,
+    S: Stream<Item = Result<T>> + Send + 'static,
+{
+    let events = json_frames(stream).map(|frame| {
+        let event = match frame {
+            Frame::Item(json) => format!("data: {json}\n\n"),
+            Frame::Error(json) => format!("event: error\ndata: {json}\n\n"),
+        };
+        Ok::<_, Infallible>(Bytes::from(event))
+    });
+
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header((header::CACHE_CONTROL, "no-cache"))
+        .streaming(events)
+}
+
+/// Sends each response of a generation stream as a server-sent event, see [`sse`].
+pub fn generation_sse(stream: GenerationResponseStream) -> HttpResponse {
+    sse(generation_items(stream))
+}
+
+/// Sends each response of a chat stream as a server-sent event, see [`sse`].
+pub fn chat_sse(stream: ChatMessageResponseStream) -> HttpResponse {
+    sse(stream)
+}
+
+/// Streams `stream` as newline-delimited JSON, the format of Ollama's own streaming
+/// endpoints.
+///
+/// An error is sent as a final `{"error": message}` line.
+pub fn ndjson<T, S>(stream: S) -> HttpResponse
+where
+    T: Serialize + Send,
+    S: Stream<Item = Result<T>> + Send + 'static,
+{
+    let lines = json_frames(stream).map(|frame| {
+        let (Frame::Item(json) | Frame::Error(json)) = frame;
+        Ok::<_, Infallible>(Bytes::from(json + "\n"))
+    });
+
+    HttpResponse::Ok()
+        .content_type("application/x-ndjson")
+        .streaming(lines)
+}
+
+/// Streams the responses of a generation stream as newline-delimited JSON, see [`ndjson`].
+pub fn generation_ndjson(stream: GenerationResponseStream) -> HttpResponse {
+    ndjson(generation_items(stream))
+}
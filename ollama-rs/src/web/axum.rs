@@ -0,0 +1,92 @@
+//! Responses for [`axum`](::axum) handlers.
+//!
+//! ```no_run
+//! use axum::{response::IntoResponse, routing::get, Router};
+//! use ollama_rs::{generation::completion::request::GenerationRequest, web, Ollama};
+//!
+//! async fn generate() -> impl IntoResponse {
+//!     let stream = Ollama::default()
+//!         .generate_stream(GenerationRequest::new("llama3.2".into(), "Why is the sky blue?"))
+//!         .await
+//!         .unwrap();
+//!     web::axum::generation_sse(stream)
+//! }
+//!
+//! let app: Router = Router::new().route("/generate", get(generate));
+//! ```
+
+use std::convert::Infallible;
+
+use ::axum::{
+    body::Body,
+    http::header,
+    response::{
+        sse::{Event, Sse},
+        IntoResponse, Response,
+    },
+};
+use futures_util::{Stream, StreamExt};
+use serde::Serialize;
+
+use super::{generation_items, json_frames, Frame};
+use crate::{
+    error::Result,
+    generation::{chat::ChatMessageResponseStream, completion::GenerationResponseStream},
+};
+
+/// Sends each item of `stream` as a server-sent event whose data is the item as JSON.
+///
+/// An error is sent as an `error` event, ending the stream. Enable axum's `tokio` feature
+/// to add keep-alive comments with [`Sse::keep_alive`].
+pub fn sse<T, S>(stream: S) -> Sse<impl Stream<Item = std::result::Result<Event, Infallible>>>
+where
+    T: Serialize + Send,
+    S: Stream<Item = Result<T>> + Send + 'static,
+{
+    Sse::new(json_frames(stream).map(|frame| {
+        Ok(match frame {
+            Frame::Item(json) => Event::default().data(json),
+            Frame::Error(json) => Event::default().event("error").data(json),
+        })
+    }))
+}
+
+/// Sends each response of a generation stream as a server-sent event, see [`sse`].
+pub fn generation_sse(
+    stream: GenerationResponseStream,
+) -> Sse<impl Stream<Item = std::result::Result<Event, Infallible>>> {
+    sse(generation_items(stream))
+}
+
+/// Sends each response of a chat stream as a server-sent event, see [`sse`].
+pub fn chat_sse(
+    stream: ChatMessageResponseStream,
+) -> Sse<impl Stream<Item = std::result::Result<Event, Infallible>>> {
+    sse(stream)
+}
+
+/// Streams `stream` as newline-delimited JSON, the format of Ollama's own streaming
+/// endpoints.
+///
+/// An error is sent as a final `{"error": message}` line.
+pub fn ndjson<T, S>(stream: S) -> Response
+where
+    T: Serialize + Send,
+    S: Stream<Item = Result<T>> + Send + 'static,
+{
+    let lines = json_frames(stream).map(|frame| {
+        let (Frame::Item(json) | Frame::Error(json)) = frame;
+        Ok::<_, Infallible>(json + "\n")
+    });
+
+    (
+        [(header::CONTENT_TYPE, "application/x-ndjson")],
+        Body::from_stream(lines),
+    )
+        .into_response()
+}
+
+/// Streams the responses of a generation stream as newline-delimited JSON, see [`ndjson`].
+pub fn generation_ndjson(stream: GenerationResponseStream) -> Response {
+    ndjson(generation_items(stream))
+}
@@ -0,0 +1,218 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use ollama_rs::{
+    audit::{AuditLog, AuditOutcome, AuditRecord, AuditUsage},
+    generation::completion::request::GenerationRequest,
+    logging::ContentLogging,
+    models::blobs::blob_digest,
+    test_utils::{MockOllama, MockResponse},
+    Ollama,
+};
+use serde_json::json;
+use tokio::io::AsyncReadExt;
+use tokio_stream::StreamExt;
+
+fn log_path(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("ollama-rs-audit-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(format!("{name}.jsonl"));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+fn records(path: &Path) -> Vec<AuditRecord> {
+    std::fs::read_to_string(path)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+fn client(server: &MockOllama, audit_log: AuditLog) -> Ollama {
+    Ollama::builder()
+        .url(server.url())
+        .audit_log(audit_log)
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_generation_is_audited() {
+    let server = MockOllama::start().await;
+    server.mock("/api/generate", MockResponse::generation("Hello"));
+    let path = log_path("generation");
+    let ollama = client(&server, AuditLog::file(&path).unwrap().salt([7; 32]));
+
+    ollama
+        .generate(GenerationRequest::new("llama3.2".to_string(), "Hi"))
+        .await
+        .unwrap();
+
+    let records = records(&path);
+    assert_eq!(records.len(), 1);
+    let record = &records[0];
+    assert_eq!(record.method, "POST");
+    assert_eq!(record.endpoint, "/api/generate");
+    assert_eq!(record.model.as_deref(), Some("llama3.2"));
+    // Hashed with the same salted SHA-256 as `ContentLogging::Hash`
+    let hashed = ContentLogging::Hash { salt: [7; 32] }.apply("Hi");
+    let digest = hashed.trim_start_matches('[').split(',').next().unwrap();
+    assert_eq!(record.prompt_hash, Some(format!("sha256:{digest}")));
+    assert_ne!(record.prompt_hash, Some(blob_digest(b"Hi")));
+    assert_eq!(
+        record.usage,
+        Some(AuditUsage {
+            prompt_eval_count: 10,
+            eval_count: 20,
+        })
+    );
+    assert_eq!(record.outcome, AuditOutcome::Success);
+    assert_eq!(record.status, Some(200));
+    assert_eq!(
+        record.request_id,
+        server.requests()[0].headers["x-request-id"]
+    );
+
+    // The prompt itself is never written
+    assert!(!std::fs::read_to_string(&path).unwrap().contains("\"Hi\""));
+}
+
+#[tokio::test]
+async fn test_failures_are_audited() {
+    let server = MockOllama::start().await;
+    server.mock("/api/generate", MockResponse::error(400, "invalid options"));
+    let path = log_path("failure");
+    let ollama = client(&server, AuditLog::file(&path).unwrap());
+
+    ollama
+        .generate(GenerationRequest::new("llama3.2".to_string(), "Hi"))
+        .await
+        .unwrap_err();
+
+    let records = records(&path);
+    assert_eq!(records[0].outcome, AuditOutcome::Failure);
+    assert_eq!(records[0].status, Some(400));
+    assert!(records[0].usage.is_none());
+}
+
+#[tokio::test]
+async fn test_streams_are_audited_when_read() {
+    let server = MockOllama::start().await;
+    server.mock(
+        "/api/generate",
+        MockResponse::generation_stream(&["Hello", " there"]),
+    );
+    let path = log_path("stream");
+    let audit_log = AuditLog::file(&path).unwrap();
+    let ollama = client(&server, audit_log.clone());
+    let request = || GenerationRequest::new("llama3.2".to_string(), "Hi");
+
+    let mut stream = ollama.generate_stream(request()).await.unwrap();
+    while let Some(chunk) = stream.next().await {
+        chunk.unwrap();
+    }
+    let mut stream = ollama.generate_stream(request()).await.unwrap();
+    stream.next().await.unwrap().unwrap();
+    drop(stream);
+    audit_log.flush().await.unwrap();
+
+    let records = records(&path);
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].outcome, AuditOutcome::Success);
+    assert!(records[0].usage.is_some());
+    assert_eq!(records[1].outcome, AuditOutcome::Cancelled);
+}
+
+#[tokio::test]
+async fn test_cancelled_requests_are_audited() {
+    let server = MockOllama::start().await;
+    server.mock(
+        "/api/generate",
+        MockResponse::generation("Hello").delay(std::time::Duration::from_secs(5)),
+    );
+    let path = log_path("cancelled");
+    let audit_log = AuditLog::file(&path).unwrap();
+    let ollama = client(&server, audit_log.clone());
+
+    let request = ollama.generate(GenerationRequest::new("llama3.2".to_string(), "Hi"));
+    assert!(
+        tokio::time::timeout(std::time::Duration::from_millis(100), request)
+            .await
+            .is_err()
+    );
+    audit_log.flush().await.unwrap();
+
+    let records = records(&path);
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].outcome, AuditOutcome::Cancelled);
+    assert!(records[0].usage.is_none());
+}
+
+#[tokio::test]
+async fn test_audit_log_to_writer() {
+    let server = MockOllama::start().await;
+    server.mock("/api/tags", MockResponse::json(json!({ "models": [] })));
+    let (writer, mut reader) = tokio::io::duplex(4096);
+    let audit_log = AuditLog::new(writer);
+    let ollama = client(&server, audit_log.clone());
+
+    ollama.list_local_models().await.unwrap();
+    audit_log.flush().await.unwrap();
+
+    let mut buffer = vec![0; 4096];
+    let n = reader.read(&mut buffer).await.unwrap();
+    let record: AuditRecord = serde_json::from_slice(&buffer[..n]).unwrap();
+    assert_eq!(record.method, "GET");
+    assert_eq!(record.endpoint, "/api/tags");
+    assert!(record.model.is_none());
+    assert_eq!(record.outcome, AuditOutcome::Success);
+
+    assert!(audit_log.rotate().await.is_err());
+}
+
+#[tokio::test]
+async fn test_audit_log_rotation() {
+    let server = MockOllama::start().await;
+    server.mock("/api/generate", MockResponse::generation("Hello"));
+    let path = log_path("rotation");
+    let rotated = Arc::new(Mutex::new(Vec::new()));
+    let hook = rotated.clone();
+    let audit_log = AuditLog::file(&path)
+        .unwrap()
+        .rotate_at(1)
+        .on_rotate(move |path| hook.lock().unwrap().push(path.to_path_buf()));
+    let ollama = client(&server, audit_log);
+
+    ollama
+        .generate(GenerationRequest::new("llama3.2".to_string(), "Hi"))
+        .await
+        .unwrap();
+
+    let rotated = rotated.lock().unwrap().clone();
+    assert_eq!(rotated.len(), 1);
+    assert_eq!(records(&rotated[0]).len(), 1);
+    assert!(records(&path).is_empty());
+}
+
+#[tokio::test]
+async fn test_rotations_keep_earlier_files() {
+    let path = log_path("rotations");
+    let rotated = Arc::new(Mutex::new(Vec::new()));
+    let hook = rotated.clone();
+    let audit_log = AuditLog::file(&path)
+        .unwrap()
+        .on_rotate(move |path| hook.lock().unwrap().push(path.to_path_buf()));
+
+    audit_log.rotate().await.unwrap();
+    audit_log.rotate().await.unwrap();
+    audit_log.rotate().await.unwrap();
+
+    let mut rotated = rotated.lock().unwrap().clone();
+    assert_eq!(rotated.len(), 3);
+    assert!(rotated.iter().all(|path| path.exists()));
+    rotated.dedup();
+    assert_eq!(rotated.len(), 3);
+}
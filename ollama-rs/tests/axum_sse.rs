@@ -0,0 +1,112 @@
+use axum::{body::to_bytes, response::IntoResponse};
+use ollama_rs::{
+    generation::{
+        chat::{request::ChatMessageRequest, ChatMessage},
+        completion::request::GenerationRequest,
+    },
+    test_utils::{MockOllama, MockResponse},
+    web,
+};
+use serde_json::{json, Value};
+
+async fn body(response: impl IntoResponse) -> String {
+    let bytes = to_bytes(response.into_response().into_body(), usize::MAX)
+        .await
+        .unwrap();
+    String::from_utf8(bytes.to_vec()).unwrap()
+}
+
+fn chat_request() -> ChatMessageRequest {
+    ChatMessageRequest::new(
+        "llama3.2".to_string(),
+        vec![ChatMessage::user("Hi".to_string())],
+    )
+}
+
+#[tokio::test]
+async fn test_generation_sse() {
+    let server = MockOllama::start().await;
+    server.mock(
+        "/api/generate",
+        MockResponse::generation_stream(&["Hello", " world"]),
+    );
+
+    let stream = server
+        .client()
+        .generate_stream(GenerationRequest::new("llama3.2".to_string(), "Hi"))
+        .await
+        .unwrap();
+    let body = body(web::axum::generation_sse(stream)).await;
+
+    let events: Vec<Value> = body
+        .split("\n\n")
+        .filter(|event| !event.is_empty())
+        .map(|event| serde_json::from_str(event.strip_prefix("data: ").unwrap()).unwrap())
+        .collect();
+    let text: String = events
+        .iter()
+        .map(|event| event["response"].as_str().unwrap())
+        .collect();
+    assert_eq!(text, "Hello world");
+    assert_eq!(events.last().unwrap()["done"], true);
+}
+
+#[tokio::test]
+async fn test_chat_sse_ends_on_error() {
+    let server = MockOllama::start().await;
+    server.mock(
+        "/api/chat",
+        MockResponse::frames([
+            format!(
+                "{}\n",
+                json!({
+                    "model": "llama3.2",
+                    "created_at": "2024-01-01T00:00:00Z",
+                    "message": { "role": "assistant", "content": "Hel" },
+                    "done": false
+                })
+            ),
+            "{\"error\":\"model runner has unexpectedly stopped\"}\n".to_string(),
+        ]),
+    );
+
+    let stream = server
+        .client()
+        .send_chat_messages_stream(chat_request())
+        .await
+        .unwrap();
+    let body = body(web::axum::chat_sse(stream)).await;
+
+    let events: Vec<&str> = body.split("\n\n").filter(|e| !e.is_empty()).collect();
+    assert_eq!(events.len(), 2);
+    assert!(events[0].starts_with("data: "));
+
+    let error = events[1].strip_prefix("event: error\ndata: ").unwrap();
+    let error: Value = serde_json::from_str(error).unwrap();
+    assert!(error["error"]
+        .as_str()
+        .unwrap()
+        .contains("model runner has unexpectedly stopped"));
+}
+
+#[tokio::test]
+async fn test_chat_ndjson() {
+    let server = MockOllama::start().await;
+    server.mock("/api/chat", MockResponse::chat_stream(&["Hello", "!"]));
+
+    let stream = server
+        .client()
+        .send_chat_messages_stream(chat_request())
+        .await
+        .unwrap();
+    let response = web::axum::ndjson(stream);
+    assert_eq!(response.headers()["content-type"], "application/x-ndjson");
+
+    let body = body(response).await;
+    let lines: Vec<Value> = body
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(lines[0]["message"]["content"], "Hello");
+    assert_eq!(lines.last().unwrap()["done"], true);
+}
@@ -0,0 +1,44 @@
+use ollama_rs::{
+    generation::completion::request::GenerationRequest,
+    test_utils::{MockOllama, MockResponse},
+    Ollama,
+};
+
+#[tokio::test]
+async fn test_url_path_prefix() {
+    let server = MockOllama::start().await;
+    server.mock(
+        "/ollama/api/generate",
+        MockResponse::generation("Behind a proxy"),
+    );
+
+    for prefix in ["ollama", "ollama/"] {
+        let ollama = Ollama::try_new(format!("{}{prefix}", server.url())).unwrap();
+        let res = ollama
+            .generate(GenerationRequest::new("llama2".to_string(), "Hi"))
+            .await
+            .unwrap();
+
+        assert_eq!(res.response, "Behind a proxy");
+    }
+
+    assert!(server
+        .requests()
+        .iter()
+        .all(|r| r.path == "/ollama/api/generate"));
+}
+
+#[tokio::test]
+async fn test_url_without_prefix() {
+    let server = MockOllama::start().await;
+    server.mock("/api/generate", MockResponse::generation("At the root"));
+
+    let url = server.url().to_string();
+    let ollama = Ollama::try_new(url.trim_end_matches('/')).unwrap();
+    let res = ollama
+        .generate(GenerationRequest::new("llama2".to_string(), "Hi"))
+        .await
+        .unwrap();
+
+    assert_eq!(res.response, "At the root");
+}
@@ -0,0 +1,42 @@
+use std::time::{Duration, Instant};
+
+use ollama_rs::{
+    generation::completion::request::GenerationRequest,
+    test_utils::{MockOllama, MockResponse},
+};
+
+#[tokio::test]
+async fn test_generate_batch() {
+    let server = MockOllama::start().await;
+    server.mock_with("/api/generate", |request| {
+        let prompt = request.json()["prompt"].as_str().unwrap().to_string();
+        if prompt == "fail" {
+            return MockResponse::error(400, "invalid prompt");
+        }
+
+        // Later requests answer faster, so that they complete first
+        let delay = 200 - prompt.parse::<u64>().unwrap() * 20;
+        MockResponse::generation(&prompt).delay(Duration::from_millis(delay))
+    });
+
+    let prompts = ["0", "1", "2", "fail", "4", "5"];
+    let requests = prompts
+        .iter()
+        .map(|prompt| GenerationRequest::new("llama2".to_string(), *prompt));
+
+    let start = Instant::now();
+    let results = server.client().generate_batch(requests, 3).await;
+    let elapsed = start.elapsed();
+
+    assert_eq!(results.len(), prompts.len());
+    for (prompt, result) in prompts.iter().zip(&results) {
+        match result {
+            Ok(res) => assert_eq!(&res.response, prompt),
+            Err(_) => assert_eq!(*prompt, "fail"),
+        }
+    }
+
+    // Two rounds of three concurrent requests
+    assert!(elapsed < Duration::from_millis(600), "{elapsed:?}");
+    assert!(elapsed >= Duration::from_millis(200), "{elapsed:?}");
+}
@@ -0,0 +1,81 @@
+use std::time::Duration;
+
+use ollama_rs::{
+    generation::benchmark::BenchmarkOptions,
+    models::ModelOptions,
+    test_utils::{MockOllama, MockResponse},
+};
+use serde_json::json;
+
+fn generation(load_duration: u64, eval_duration: u64) -> MockResponse {
+    MockResponse::stream([
+        json!({ "model": "llama3.2", "created_at": "2024-01-01T00:00:00Z", "response": "Hi", "done": false }),
+        json!({
+            "model": "llama3.2",
+            "created_at": "2024-01-01T00:00:00Z",
+            "response": "",
+            "done": true,
+            "total_duration": 2_000_000_000u64,
+            "load_duration": load_duration,
+            "prompt_eval_count": 10,
+            "prompt_eval_duration": 100_000_000,
+            "eval_count": 20,
+            "eval_duration": eval_duration
+        }),
+    ])
+}
+
+#[tokio::test]
+async fn test_benchmark() {
+    let server = MockOllama::start().await;
+    server
+        .mock_once("/api/generate", generation(3_000_000_000, 1_000_000_000))
+        .mock_once("/api/generate", generation(1_000_000, 1_000_000_000))
+        .mock_once("/api/generate", generation(1_000_000, 500_000_000))
+        .mock_once("/api/generate", generation(3_000_000, 250_000_000));
+
+    let report = server
+        .client()
+        .benchmark(
+            "llama3.2",
+            "Hi",
+            BenchmarkOptions::new()
+                .runs(3)
+                .options(ModelOptions::default().num_predict(20)),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(report.runs.len(), 3);
+    assert_eq!(report.cold_load_duration, Some(Duration::from_secs(3)));
+
+    assert_eq!(report.tokens_per_second.min, 20.0);
+    assert_eq!(report.tokens_per_second.p50, 40.0);
+    assert_eq!(report.tokens_per_second.p95, 80.0);
+    assert!((report.tokens_per_second.mean - 140.0 / 3.0).abs() < 1e-9);
+    assert_eq!(report.prompt_tokens_per_second.mean, 100.0);
+
+    assert_eq!(report.load_duration.max, Duration::from_millis(3));
+    assert_eq!(report.load_duration.p50, Duration::from_millis(1));
+    assert!(report.time_to_first_token.max < Duration::from_secs(1));
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 4);
+    assert_eq!(requests[0].json()["options"]["num_predict"], 20);
+}
+
+#[tokio::test]
+async fn test_benchmark_error() {
+    let server = MockOllama::start().await;
+    server.mock(
+        "/api/generate",
+        MockResponse::error(404, "model \"missing\" not found, try pulling it first"),
+    );
+
+    let result = server
+        .client()
+        .benchmark("missing", "Hi", BenchmarkOptions::new())
+        .await;
+    assert!(result.is_err());
+    assert_eq!(server.requests().len(), 1);
+}
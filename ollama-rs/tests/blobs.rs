@@ -0,0 +1,115 @@
+use std::sync::{Arc, Mutex};
+
+use ollama_rs::{
+    models::{
+        blobs::{blob_digest, file_digest},
+        create::CreateModelRequest,
+    },
+    test_utils::{MockOllama, MockResponse},
+};
+use tokio_stream::StreamExt;
+
+#[test]
+fn test_blob_digest() {
+    assert_eq!(
+        blob_digest(b""),
+        "sha256:e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+    );
+    assert_eq!(
+        blob_digest(b"abc"),
+        "sha256:ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+    assert_eq!(
+        blob_digest(b"The quick brown fox jumps over the lazy dog"),
+        "sha256:d7a8fbb307d7809469ca9abcb0082e4f8d5651e46d3cdb762d02d0bf37c9e592"
+    );
+    assert_eq!(
+        blob_digest(&vec![b'a'; 1_000_000]),
+        "sha256:cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0"
+    );
+}
+
+fn write_file(name: &str, content: &[u8]) -> std::path::PathBuf {
+    let path = std::env::temp_dir().join(format!("ollama-rs-{}-{name}", std::process::id()));
+    std::fs::write(&path, content).unwrap();
+    path
+}
+
+#[test]
+fn test_file_digest() {
+    let content = vec![7; 3 << 20];
+    let path = write_file("digest.bin", &content);
+    assert_eq!(file_digest(&path).unwrap(), blob_digest(&content));
+    std::fs::remove_file(path).unwrap();
+}
+
+#[tokio::test]
+async fn test_create_model_skips_existing_blobs() {
+    let existing = write_file("existing.gguf", b"existing weights");
+    let new = write_file("new.gguf", b"new weights");
+    let existing_digest = blob_digest(b"existing weights");
+    let new_digest = blob_digest(b"new weights");
+
+    let server = MockOllama::start().await;
+    server.mock(
+        &format!("/api/blobs/{existing_digest}"),
+        MockResponse::json(serde_json::json!({})),
+    );
+    let uploaded = Arc::new(Mutex::new(false));
+    let state = uploaded.clone();
+    server.mock_with(&format!("/api/blobs/{new_digest}"), move |request| {
+        let mut uploaded = state.lock().unwrap();
+        match request.method.as_str() {
+            "POST" => {
+                assert_eq!(request.body, b"new weights");
+                *uploaded = true;
+                MockResponse::json(serde_json::json!({})).status(201)
+            }
+            _ if *uploaded => MockResponse::json(serde_json::json!({})),
+            _ => MockResponse::error(404, "not found"),
+        }
+    });
+    server.mock(
+        "/api/create",
+        MockResponse::stream([serde_json::json!({ "status": "success" })]),
+    );
+
+    let ollama = server.client();
+    let statuses: Vec<String> = ollama
+        .create_model_with_files_stream(
+            CreateModelRequest::new("imported".to_string()),
+            [("existing.gguf", &existing), ("new.gguf", &new)],
+        )
+        .await
+        .unwrap()
+        .map(|status| status.unwrap().message)
+        .collect()
+        .await;
+
+    assert_eq!(
+        statuses,
+        [
+            "existing.gguf: checking",
+            "existing.gguf: skipped (exists)",
+            "new.gguf: checking",
+            "new.gguf: uploaded",
+            "success",
+        ]
+    );
+
+    let requests = server.requests();
+    let create = requests.last().unwrap().json();
+    assert_eq!(create["files"]["existing.gguf"], existing_digest);
+    assert_eq!(create["files"]["new.gguf"], new_digest);
+    assert_eq!(
+        requests.iter().filter(|r| r.method == "POST").count(),
+        2,
+        "Only the new blob is uploaded, then the model is created"
+    );
+
+    // Both blobs now exist, so nothing is uploaded again
+    assert!(ollama.blob_exists(&new_digest).await.unwrap());
+
+    std::fs::remove_file(existing).unwrap();
+    std::fs::remove_file(new).unwrap();
+}
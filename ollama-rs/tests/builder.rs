@@ -0,0 +1,346 @@
+use std::time::Duration;
+
+use ollama_rs::{
+    error::OllamaError,
+    generation::{
+        chat::{request::ChatMessageRequest, ChatMessage},
+        completion::request::GenerationRequest,
+        embeddings::request::GenerateEmbeddingsRequest,
+        parameters::KeepAlive,
+    },
+    models::ModelOptions,
+    test_utils::{MockOllama, MockResponse},
+    Ollama, RetryPolicy,
+};
+use serde_json::json;
+
+fn request() -> GenerationRequest<'static> {
+    GenerationRequest::new("llama2".to_string(), "Why is the sky blue?")
+}
+
+fn fast_retries(max_retries: u32) -> RetryPolicy {
+    RetryPolicy::new(max_retries).initial_backoff(Duration::from_millis(10))
+}
+
+#[tokio::test]
+async fn test_retries_transient_errors() {
+    let server = MockOllama::start().await;
+    server
+        .mock_once("/api/generate", MockResponse::error(503, "busy"))
+        .mock_once("/api/generate", MockResponse::error(500, "crashed"))
+        .mock("/api/generate", MockResponse::generation("Blue"));
+
+    let ollama = Ollama::builder()
+        .url(server.url())
+        .retry_policy(fast_retries(3))
+        .build()
+        .unwrap();
+
+    let res = ollama.generate(request()).await.unwrap();
+
+    assert_eq!(res.response, "Blue");
+    assert_eq!(server.requests().len(), 3);
+}
+
+#[tokio::test]
+async fn test_gives_up_after_max_retries() {
+    let server = MockOllama::start().await;
+    server.mock("/api/generate", MockResponse::error(503, "busy"));
+
+    let ollama = Ollama::builder()
+        .url(server.url())
+        .retry_policy(fast_retries(2))
+        .build()
+        .unwrap();
+
+    let err = ollama.generate(request()).await.unwrap_err();
+
+    assert!(matches!(err, OllamaError::HttpError { .. }), "{err:?}");
+    assert_eq!(server.requests().len(), 3);
+}
+
+#[tokio::test]
+async fn test_does_not_retry_client_errors() {
+    let server = MockOllama::start().await;
+    server.mock("/api/generate", MockResponse::error(400, "invalid options"));
+
+    let ollama = Ollama::builder()
+        .url(server.url())
+        .retry_policy(fast_retries(3))
+        .build()
+        .unwrap();
+
+    ollama.generate(request()).await.unwrap_err();
+
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn test_does_not_retry_side_effects_unless_asked() {
+    let server = MockOllama::start().await;
+    server.mock("/api/copy", MockResponse::error(503, "busy"));
+    let copy = |ollama: Ollama| async move {
+        ollama
+            .copy_model("llama2".to_string(), "llama2-copy".to_string())
+            .await
+    };
+
+    let ollama = Ollama::builder()
+        .url(server.url())
+        .retry_policy(fast_retries(2))
+        .build()
+        .unwrap();
+    copy(ollama).await.unwrap_err();
+    assert_eq!(server.requests().len(), 1);
+
+    let ollama = Ollama::builder()
+        .url(server.url())
+        .retry_policy(fast_retries(2).retry_side_effects(true))
+        .build()
+        .unwrap();
+    copy(ollama).await.unwrap_err();
+    assert_eq!(server.requests().len(), 4);
+}
+
+#[tokio::test]
+async fn test_default_options_are_merged() {
+    let server = MockOllama::start().await;
+    server.mock("/api/generate", MockResponse::generation("Blue"));
+
+    let ollama = Ollama::builder()
+        .url(server.url())
+        .default_options(ModelOptions::default().temperature(0.2).num_ctx(4096))
+        .build()
+        .unwrap();
+
+    ollama.generate(request()).await.unwrap();
+    ollama
+        .generate(request().options(ModelOptions::default().temperature(0.9)))
+        .await
+        .unwrap();
+
+    let requests = server.requests();
+    assert_eq!(
+        requests[0].json()["options"],
+        json!({ "temperature": 0.2, "num_ctx": 4096 })
+    );
+    assert_eq!(
+        requests[1].json()["options"],
+        json!({ "temperature": 0.9, "num_ctx": 4096 })
+    );
+}
+
+#[tokio::test]
+async fn test_auth_and_timeout() {
+    let server = MockOllama::start().await;
+    server
+        .mock_once("/api/generate", MockResponse::generation("Blue"))
+        .mock(
+            "/api/generate",
+            MockResponse::generation("Blue").delay(Duration::from_millis(500)),
+        );
+
+    let ollama = Ollama::builder()
+        .url(server.url())
+        .api_key("secret")
+        .timeout(Duration::from_millis(100))
+        .build()
+        .unwrap();
+
+    ollama.generate(request()).await.unwrap();
+    assert_eq!(
+        server.requests()[0].headers["authorization"],
+        "Bearer secret"
+    );
+
+    let err = ollama.generate(request()).await.unwrap_err();
+    assert!(matches!(err, OllamaError::Timeout { .. }), "{err:?}");
+}
+
+#[tokio::test]
+async fn test_default_keep_alive() {
+    let server = MockOllama::start().await;
+    server.mock("/api/generate", MockResponse::generation("Blue"));
+    server.mock("/api/embed", MockResponse::embeddings(vec![vec![0.1, 0.2]]));
+
+    let ollama = Ollama::builder()
+        .url(server.url())
+        .default_keep_alive(KeepAlive::Indefinitely)
+        .build()
+        .unwrap();
+
+    ollama.generate(request()).await.unwrap();
+    ollama
+        .generate(request().keep_alive(KeepAlive::UnloadOnCompletion))
+        .await
+        .unwrap();
+    ollama
+        .generate_embeddings(GenerateEmbeddingsRequest::new(
+            "nomic-embed-text".to_string(),
+            "Hi".into(),
+        ))
+        .await
+        .unwrap();
+
+    let requests = server.requests();
+    assert_eq!(requests[0].json()["keep_alive"], -1);
+    assert_eq!(requests[1].json()["keep_alive"], 0);
+    assert_eq!(requests[2].json()["keep_alive"], -1);
+}
+
+#[tokio::test]
+async fn test_deterministic_mode_overrides_seed_and_temperature() {
+    let server = MockOllama::start().await;
+    server.mock("/api/generate", MockResponse::generation("Blue"));
+    server.mock("/api/chat", MockResponse::chat("Blue"));
+
+    let mut ollama = Ollama::builder()
+        .url(server.url())
+        .default_options(ModelOptions::default().num_ctx(4096))
+        .deterministic(42)
+        .build()
+        .unwrap();
+    assert_eq!(ollama.deterministic_seed(), Some(42));
+
+    ollama.generate(request()).await.unwrap();
+    ollama
+        .generate(request().options(ModelOptions::default().temperature(0.9).seed(7)))
+        .await
+        .unwrap();
+    let chat = ollama_rs::generation::chat::request::ChatMessageRequest::new(
+        "llama2".to_string(),
+        vec![ollama_rs::generation::chat::ChatMessage::user(
+            "Hi".to_string(),
+        )],
+    );
+    ollama.send_chat_messages(chat).await.unwrap();
+
+    ollama.set_deterministic(None);
+    assert_eq!(ollama.deterministic_seed(), None);
+    ollama.generate(request()).await.unwrap();
+
+    let requests = server.requests();
+    let expected = json!({ "seed": 42, "temperature": 0.0, "num_ctx": 4096 });
+    assert_eq!(requests[0].json()["options"], expected);
+    assert_eq!(requests[1].json()["options"], expected);
+    assert_eq!(requests[2].json()["options"], expected);
+    assert_eq!(requests[3].json()["options"], json!({ "num_ctx": 4096 }));
+}
+
+#[tokio::test]
+async fn test_history_limit() {
+    let server = MockOllama::start().await;
+    server.mock("/api/chat", MockResponse::chat("Fine."));
+
+    let ollama = Ollama::builder()
+        .url(server.url())
+        .history_limit(2)
+        .build()
+        .unwrap();
+
+    let mut history = vec![ChatMessage::system("Be brief.".to_string())];
+    for question in ["Hi!", "How are you?"] {
+        let request = ChatMessageRequest::new(
+            "llama2".to_string(),
+            vec![ChatMessage::user(question.to_string())],
+        );
+        ollama
+            .send_chat_messages_with_history(&mut history, request)
+            .await
+            .unwrap();
+    }
+
+    let sent: Vec<_> = server.requests()[1].json()["messages"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|message| message["content"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(sent, ["Be brief.", "Fine.", "How are you?"]);
+    assert_eq!(history.len(), 5);
+}
+
+fn sent_contents(server: &MockOllama, request: usize) -> Vec<String> {
+    server.requests()[request].json()["messages"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|message| message["content"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_history_limit_keeps_the_request() {
+    let server = MockOllama::start().await;
+    server.mock("/api/chat", MockResponse::chat("Fine."));
+
+    let ollama = Ollama::builder()
+        .url(server.url())
+        .history_limit(0)
+        .build()
+        .unwrap();
+
+    let mut history = vec![ChatMessage::system("Be brief.".to_string())];
+    let request = ChatMessageRequest::new(
+        "llama2".to_string(),
+        vec![
+            ChatMessage::user("Hi!".to_string()),
+            ChatMessage::user("How are you?".to_string()),
+        ],
+    );
+    ollama
+        .send_chat_messages_with_history(&mut history, request)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        sent_contents(&server, 0),
+        ["Be brief.", "Hi!", "How are you?"]
+    );
+}
+
+#[tokio::test]
+async fn test_history_limit_keeps_tool_calls() {
+    let server = MockOllama::start().await;
+    server.mock("/api/chat", MockResponse::chat("Fine."));
+
+    let ollama = Ollama::builder()
+        .url(server.url())
+        .history_limit(3)
+        .build()
+        .unwrap();
+
+    let mut call = ChatMessage::assistant("Checking.".to_string());
+    call.tool_calls = vec![serde_json::from_value(json!({
+        "function": { "name": "weather", "arguments": {} }
+    }))
+    .unwrap()];
+    let mut history = vec![
+        ChatMessage::system("Be brief.".to_string()),
+        ChatMessage::user("Weather?".to_string()),
+        call,
+        ChatMessage::tool("Sunny".to_string()),
+        ChatMessage::tool("Warm".to_string()),
+        ChatMessage::assistant("Sunny and warm.".to_string()),
+    ];
+    let request = ChatMessageRequest::new(
+        "llama2".to_string(),
+        vec![ChatMessage::user("Thanks!".to_string())],
+    );
+    ollama
+        .send_chat_messages_with_history(&mut history, request)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        sent_contents(&server, 0),
+        [
+            "Be brief.",
+            "Checking.",
+            "Sunny",
+            "Warm",
+            "Sunny and warm.",
+            "Thanks!"
+        ]
+    );
+}
@@ -0,0 +1,110 @@
+use ollama_rs::{
+    error::{OllamaError, ToolCallError},
+    generation::tools::Tool,
+    test_utils::{MockOllama, MockResponse},
+};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::json;
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct Reminder {
+    /// When to remind, as HH:MM.
+    time: String,
+    /// What to remind of.
+    text: String,
+}
+
+struct SetReminder;
+
+impl Tool for SetReminder {
+    type Params = Reminder;
+
+    fn name() -> &'static str {
+        "set_reminder"
+    }
+
+    fn description() -> &'static str {
+        "Sets a reminder."
+    }
+
+    async fn call(&self, reminder: Reminder) -> ollama_rs::generation::tools::Result<String> {
+        Ok(format!("Reminder set at {}", reminder.time))
+    }
+}
+
+#[tokio::test]
+async fn test_call_tool_via_model() {
+    let server = MockOllama::start().await;
+    server.mock(
+        "/api/chat",
+        MockResponse::tool_call(
+            "set_reminder",
+            json!({ "time": "17:00", "text": "Call mom" }),
+        ),
+    );
+
+    let call = server
+        .client()
+        .call_tool_via_model("llama3.2", SetReminder, "Remind me to call mom at 5pm")
+        .await
+        .unwrap();
+    assert_eq!(call.parameters.time, "17:00");
+    assert_eq!(call.parameters.text, "Call mom");
+    assert_eq!(call.result, "Reminder set at 17:00");
+
+    let request = server.requests()[0].json();
+    assert_eq!(request["tools"].as_array().unwrap().len(), 1);
+    assert_eq!(request["tool_choice"]["function"]["name"], "set_reminder");
+    assert_eq!(
+        request["messages"][0]["content"],
+        "Remind me to call mom at 5pm"
+    );
+}
+
+#[tokio::test]
+async fn test_call_tool_via_model_emulated() {
+    let server = MockOllama::start().await;
+    server
+        .mock_once(
+            "/api/chat",
+            MockResponse::error(
+                400,
+                "registry.ollama.ai/library/gemma:2b does not support tools",
+            ),
+        )
+        .mock(
+            "/api/chat",
+            MockResponse::chat(
+                r#"{"name": "set_reminder", "arguments": {"time": "08:30", "text": "Gym"}}"#,
+            ),
+        );
+
+    let call = server
+        .client()
+        .call_tool_via_model("gemma:2b", SetReminder, "Gym at 8:30 tomorrow")
+        .await
+        .unwrap();
+    assert_eq!(call.parameters.time, "08:30");
+
+    let request = server.requests()[1].json();
+    assert!(request.get("tools").is_none());
+    let system = request["messages"][0]["content"].as_str().unwrap();
+    assert!(system.contains("Call the `set_reminder` tool"));
+}
+
+#[tokio::test]
+async fn test_call_tool_via_model_without_call() {
+    let server = MockOllama::start().await;
+    server.mock("/api/chat", MockResponse::chat("Sure, I'll remember!"));
+
+    let err = server
+        .client()
+        .call_tool_via_model("llama3.2", SetReminder, "Remind me to call mom")
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        OllamaError::ToolCallError(ToolCallError::ToolCallRequired)
+    ));
+}
@@ -0,0 +1,87 @@
+use ollama_rs::{
+    generation::chat::{ChatMessage, MessageRole},
+    test_utils::{MockOllama, MockResponse},
+};
+use tokio_stream::StreamExt;
+
+#[tokio::test]
+async fn test_sessions_keep_independent_histories() {
+    let server = MockOllama::start().await;
+    server.mock_with("/api/chat", |request| {
+        let messages = request.json()["messages"].as_array().unwrap().clone();
+        MockResponse::chat(&format!("{} messages", messages.len()))
+    });
+    let ollama = server.client();
+
+    let mut alice = ollama.chat_session("llama3.2").system("Be brief.");
+    let mut bob = ollama.chat_session("llama3.2");
+
+    alice.send(ChatMessage::user("Hi".into())).await.unwrap();
+    bob.send(ChatMessage::user("Hello".into())).await.unwrap();
+    let res = alice.send(ChatMessage::user("Again".into())).await.unwrap();
+
+    assert_eq!(res.message.content, "4 messages");
+    assert_eq!(alice.history().len(), 5);
+    assert_eq!(alice.history()[0].role, MessageRole::System);
+    assert_eq!(bob.history().len(), 2);
+    assert_eq!(bob.history()[0].content, "Hello");
+}
+
+#[tokio::test]
+async fn test_usage_is_summed() {
+    let server = MockOllama::start().await;
+    server.mock("/api/chat", MockResponse::chat("Hi!"));
+
+    let mut session = server.client().chat_session("llama3.2");
+    session.send(ChatMessage::user("Hi".into())).await.unwrap();
+    session.send(ChatMessage::user("Hi".into())).await.unwrap();
+
+    let usage = session.usage();
+    assert_eq!(usage.prompt_eval_count, 20);
+    assert_eq!(usage.eval_count, 40);
+}
+
+#[tokio::test]
+async fn test_failed_message_is_not_kept() {
+    let server = MockOllama::start().await;
+    server.mock_once("/api/chat", MockResponse::error(500, "boom"));
+    server.mock_once("/api/chat", MockResponse::chat("Hi!"));
+
+    let mut session = server.client().chat_session("llama3.2");
+    assert!(session.send(ChatMessage::user("Hi".into())).await.is_err());
+    assert!(session.history().is_empty());
+
+    session.send(ChatMessage::user("Hi".into())).await.unwrap();
+    assert_eq!(session.history().len(), 2);
+}
+
+#[tokio::test]
+async fn test_clear_keeps_the_system_prompt() {
+    let server = MockOllama::start().await;
+    server.mock("/api/chat", MockResponse::chat("Hi!"));
+
+    let mut session = server.client().chat_session("llama3.2").system("Be brief.");
+    session.send(ChatMessage::user("Hi".into())).await.unwrap();
+    session.clear();
+
+    assert_eq!(session.history().len(), 1);
+    assert_eq!(session.history()[0].content, "Be brief.");
+}
+
+#[tokio::test]
+async fn test_streamed_response_is_added_to_history() {
+    let server = MockOllama::start().await;
+    server.mock("/api/chat", MockResponse::chat_stream(&["Hel", "lo!"]));
+
+    let mut session = server.client().chat_session("llama3.2");
+    {
+        let mut stream = std::pin::pin!(session.send_stream(ChatMessage::user("Hi".into())));
+        while let Some(res) = stream.next().await {
+            res.unwrap();
+        }
+    }
+
+    assert_eq!(session.history().len(), 2);
+    assert_eq!(session.history()[1].content, "Hello!");
+    assert_eq!(session.usage().eval_count, 20);
+}
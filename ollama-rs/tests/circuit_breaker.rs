@@ -0,0 +1,133 @@
+use std::time::Duration;
+
+use ollama_rs::{
+    error::OllamaError,
+    generation::completion::request::GenerationRequest,
+    test_utils::{MockOllama, MockResponse},
+    CircuitBreaker, CircuitState, Ollama,
+};
+
+fn breaker() -> CircuitBreaker {
+    CircuitBreaker::new()
+        .min_requests(3)
+        .failure_threshold(0.5)
+        .open_duration(Duration::from_millis(100))
+}
+
+fn client(server: &MockOllama, breaker: CircuitBreaker) -> Ollama {
+    Ollama::builder()
+        .url(server.url())
+        .circuit_breaker(breaker)
+        .build()
+        .unwrap()
+}
+
+fn request() -> GenerationRequest<'static> {
+    GenerationRequest::new("llama3.2".into(), "Hi")
+}
+
+#[tokio::test]
+async fn test_circuit_opens_after_failures() {
+    let server = MockOllama::start().await;
+    server.mock("/api/generate", MockResponse::error(500, "boom"));
+    let ollama = client(&server, breaker());
+
+    for _ in 0..3 {
+        let err = ollama.generate(request()).await.unwrap_err();
+        assert!(matches!(err, OllamaError::HttpError { .. }), "{err:?}");
+    }
+    assert_eq!(
+        ollama.circuit_breaker().unwrap().state(),
+        CircuitState::Open
+    );
+
+    let err = ollama.generate(request()).await.unwrap_err();
+    let OllamaError::CircuitOpen { retry_in } = err else {
+        panic!("{err:?}");
+    };
+    assert!(retry_in <= Duration::from_millis(100));
+    assert_eq!(server.requests().len(), 3);
+}
+
+#[tokio::test]
+async fn test_successful_probe_closes_circuit() {
+    let server = MockOllama::start().await;
+    for _ in 0..3 {
+        server.mock_once("/api/generate", MockResponse::error(503, "overloaded"));
+    }
+    server.mock("/api/generate", MockResponse::generation("Hello"));
+    let ollama = client(&server, breaker());
+
+    for _ in 0..3 {
+        ollama.generate(request()).await.unwrap_err();
+    }
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    assert_eq!(
+        ollama.circuit_breaker().unwrap().state(),
+        CircuitState::HalfOpen
+    );
+
+    let res = ollama.generate(request()).await.unwrap();
+    assert_eq!(res.response, "Hello");
+    assert_eq!(
+        ollama.circuit_breaker().unwrap().state(),
+        CircuitState::Closed
+    );
+}
+
+#[tokio::test]
+async fn test_failed_probe_reopens_circuit() {
+    let server = MockOllama::start().await;
+    server.mock("/api/generate", MockResponse::error(500, "boom"));
+    let ollama = client(&server, breaker());
+
+    for _ in 0..3 {
+        ollama.generate(request()).await.unwrap_err();
+    }
+    tokio::time::sleep(Duration::from_millis(150)).await;
+
+    let err = ollama.generate(request()).await.unwrap_err();
+    assert!(matches!(err, OllamaError::HttpError { .. }), "{err:?}");
+    let err = ollama.generate(request()).await.unwrap_err();
+    assert!(matches!(err, OllamaError::CircuitOpen { .. }), "{err:?}");
+    assert_eq!(server.requests().len(), 4);
+}
+
+#[tokio::test]
+async fn test_client_errors_dont_open_circuit() {
+    let server = MockOllama::start().await;
+    server.mock(
+        "/api/generate",
+        MockResponse::error(404, "model \"llama3.2\" not found, try pulling it first"),
+    );
+    let ollama = client(&server, breaker());
+
+    for _ in 0..5 {
+        let err = ollama.generate(request()).await.unwrap_err();
+        assert!(matches!(err, OllamaError::ModelNotFound { .. }), "{err:?}");
+    }
+    assert_eq!(
+        ollama.circuit_breaker().unwrap().state(),
+        CircuitState::Closed
+    );
+}
+
+#[tokio::test]
+async fn test_circuit_is_shared_by_clones_and_resettable() {
+    let server = MockOllama::start().await;
+    server.mock("/api/generate", MockResponse::error(500, "boom"));
+    let ollama = client(&server, breaker());
+    let clone = ollama.clone();
+
+    for _ in 0..3 {
+        ollama.generate(request()).await.unwrap_err();
+    }
+    let err = clone.generate(request()).await.unwrap_err();
+    assert!(matches!(err, OllamaError::CircuitOpen { .. }), "{err:?}");
+
+    clone.circuit_breaker().unwrap().reset();
+    assert_eq!(
+        ollama.circuit_breaker().unwrap().state(),
+        CircuitState::Closed
+    );
+}
@@ -0,0 +1,103 @@
+use ollama_rs::{
+    generation::{
+        citations::Citations,
+        tools::{
+            implementations::{Document, DocumentRetriever},
+            ToolSet,
+        },
+    },
+    test_utils::{MockOllama, MockResponse},
+};
+use serde_json::json;
+
+fn citations() -> Citations {
+    let citations = Citations::new();
+    citations.add(Document::new("refunds.md", "Refunds take 5 days."));
+    citations.add(Document::new("shipping.md", "Shipping is free over $50."));
+    citations
+}
+
+#[test]
+fn test_citation_numbers() {
+    let citations = citations();
+    assert_eq!(
+        citations.add(Document::new("refunds.md", "Refunds take 5 days.")),
+        1
+    );
+    assert_eq!(citations.sources().len(), 2);
+    assert_eq!(citations.get(2).unwrap().id, "shipping.md");
+    assert!(citations.get(0).is_none());
+    assert!(citations
+        .prompt()
+        .starts_with("Sources:\n[1] Refunds take 5 days.\n[2] Shipping is free over $50.\n"));
+}
+
+#[test]
+fn test_cite_answer() {
+    let answer = "Refunds take 5 days [1]. Shipping is free over $50.[2] Both apply [1, 2][7]. See [the FAQ].";
+    let cited = citations().cite(answer);
+
+    let found: Vec<_> = cited
+        .citations
+        .iter()
+        .map(|c| {
+            (
+                c.index,
+                &answer[c.marker.clone()],
+                &answer[c.claim.clone()],
+                c.document.id.as_str(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        found,
+        [
+            (1, "[1]", "Refunds take 5 days", "refunds.md"),
+            (2, "[2]", "Shipping is free over $50.", "shipping.md"),
+            (1, "[1, 2]", "Both apply", "refunds.md"),
+            (2, "[1, 2]", "Both apply", "shipping.md"),
+        ]
+    );
+
+    let sources: Vec<_> = cited.sources().iter().map(|d| d.id.as_str()).collect();
+    assert_eq!(sources, ["refunds.md", "shipping.md"]);
+    assert_eq!(
+        cited.plain_text(),
+        "Refunds take 5 days. Shipping is free over $50. Both apply. See [the FAQ]."
+    );
+}
+
+#[tokio::test]
+async fn test_retriever_numbers_sources() {
+    let server = MockOllama::start().await;
+    server
+        .mock_once(
+            "/api/embed",
+            MockResponse::embeddings(vec![vec![1.0, 0.0], vec![0.0, 1.0]]),
+        )
+        .mock("/api/embed", MockResponse::embeddings(vec![vec![0.1, 1.0]]));
+
+    let citations = citations();
+    let mut retriever = DocumentRetriever::new(server.client(), "nomic-embed-text".to_string())
+        .citations(citations.clone())
+        .top_k(1);
+    retriever
+        .add_documents(vec![
+            Document::new("refunds.md", "Refunds take 5 days."),
+            Document::new("returns.md", "Returns are accepted for 30 days."),
+        ])
+        .await
+        .unwrap();
+
+    let mut tools = ToolSet::new();
+    tools.insert(retriever);
+    let res = tools
+        .call("retrieve_documents", json!({ "query": "returns" }))
+        .await
+        .unwrap();
+    let res: serde_json::Value = serde_json::from_str(&res).unwrap();
+
+    assert_eq!(res[0]["id"], "returns.md");
+    assert_eq!(res[0]["source"], 3);
+    assert_eq!(citations.get(3).unwrap().id, "returns.md");
+}
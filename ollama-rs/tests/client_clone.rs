@@ -0,0 +1,47 @@
+use ollama_rs::{
+    generation::completion::request::GenerationRequest,
+    test_utils::{MockOllama, MockResponse},
+};
+
+#[tokio::test]
+async fn test_clones_are_used_concurrently() {
+    let server = MockOllama::start().await;
+    server.mock("/api/generate", MockResponse::generation("Hi!"));
+    let ollama = server.client();
+
+    let tasks: Vec<_> = (0..8)
+        .map(|i| {
+            let ollama = ollama.clone();
+            tokio::spawn(async move {
+                let request = GenerationRequest::new("llama3.2".into(), format!("Hi {i}"));
+                ollama.generate(request).await.map(|res| res.response)
+            })
+        })
+        .collect();
+
+    for task in tasks {
+        assert_eq!(task.await.unwrap().unwrap(), "Hi!");
+    }
+    assert_eq!(server.requests().len(), 8);
+}
+
+#[tokio::test]
+async fn test_configuring_a_clone_leaves_the_original_alone() {
+    let server = MockOllama::start().await;
+    server.mock("/api/generate", MockResponse::generation("Hi!"));
+    let ollama = server.client();
+
+    let mut authenticated = ollama.clone();
+    authenticated.set_api_key(Some("secret".into()));
+
+    let request = GenerationRequest::new("llama3.2".into(), "Hi");
+    authenticated.generate(request.clone()).await.unwrap();
+    ollama.generate(request).await.unwrap();
+
+    let requests = server.requests();
+    assert_eq!(
+        requests[0].headers.get("authorization").map(String::as_str),
+        Some("Bearer secret")
+    );
+    assert_eq!(requests[1].headers.get("authorization"), None);
+}
@@ -0,0 +1,70 @@
+use std::io::Write;
+
+use flate2::{write::GzEncoder, Compression};
+use ollama_rs::{
+    generation::completion::request::GenerationRequest,
+    test_utils::{MockOllama, MockResponse},
+};
+use serde_json::json;
+use tokio_stream::StreamExt;
+
+fn gzip(bytes: &[u8]) -> Vec<u8> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(bytes).unwrap();
+    encoder.finish().unwrap()
+}
+
+#[tokio::test]
+async fn test_compressed_response_is_decoded() {
+    let body = json!({
+        "models": [{
+            "name": "llama2:latest",
+            "modified_at": "2023-08-04T08:52:19.385406455-07:00",
+            "size": 3825819519u64,
+        }]
+    });
+
+    let server = MockOllama::start().await;
+    server.mock(
+        "/api/tags",
+        MockResponse::body(gzip(body.to_string().as_bytes())).header("content-encoding", "gzip"),
+    );
+
+    let models = server.client().list_local_models().await.unwrap();
+
+    assert_eq!(models[0].name, "llama2:latest");
+    let accept_encoding = &server.requests()[0].headers["accept-encoding"];
+    assert!(accept_encoding.contains("gzip"), "{accept_encoding}");
+}
+
+#[tokio::test]
+async fn test_streams_are_not_compressed() {
+    let server = MockOllama::start().await;
+    server.mock(
+        "/api/generate",
+        MockResponse::generation_stream(&["a", "b"]),
+    );
+
+    let mut stream = server
+        .client()
+        .generate_stream(GenerationRequest::new("llama2".to_string(), "Hi"))
+        .await
+        .unwrap();
+    while let Some(res) = stream.next().await {
+        res.unwrap();
+    }
+
+    assert_eq!(server.requests()[0].headers["accept-encoding"], "identity");
+}
+
+#[tokio::test]
+async fn test_compression_can_be_disabled() {
+    let server = MockOllama::start().await;
+    server.mock("/api/tags", MockResponse::json(json!({ "models": [] })));
+
+    let mut ollama = server.client();
+    ollama.set_response_compression(false);
+    ollama.list_local_models().await.unwrap();
+
+    assert_eq!(server.requests()[0].headers["accept-encoding"], "identity");
+}
@@ -0,0 +1,107 @@
+use std::time::Duration;
+
+use ollama_rs::{
+    concurrency::ConcurrencyLimits,
+    generation::completion::request::GenerationRequest,
+    test_utils::{MockOllama, MockResponse},
+    Ollama,
+};
+use serde_json::json;
+use tokio_stream::StreamExt;
+
+async fn server() -> MockOllama {
+    let server = MockOllama::start().await;
+    server
+        .mock(
+            "/api/generate",
+            MockResponse::generation_stream(&["Hello", " there"]),
+        )
+        .mock("/api/tags", MockResponse::json(json!({ "models": [] })));
+    server
+}
+
+fn client(server: &MockOllama, limits: ConcurrencyLimits) -> Ollama {
+    Ollama::builder()
+        .url(server.url())
+        .concurrency_limits(limits)
+        .build()
+        .unwrap()
+}
+
+fn request(model: &str) -> GenerationRequest<'static> {
+    GenerationRequest::new(model.to_string(), "Hi")
+}
+
+#[tokio::test]
+async fn test_requests_over_the_limit_wait() {
+    let server = server().await;
+    let ollama = client(&server, ConcurrencyLimits::new().max_requests(1));
+
+    let mut stream = ollama.generate_stream(request("llama3.2")).await.unwrap();
+    let queued = tokio::spawn({
+        let ollama = ollama.clone();
+        async move { ollama.list_local_models().await }
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(server.requests().len(), 1);
+    assert!(!queued.is_finished());
+
+    // The slot is freed once the stream is read
+    while let Some(chunk) = stream.next().await {
+        chunk.unwrap();
+    }
+    tokio::time::timeout(Duration::from_secs(5), queued)
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+    assert_eq!(server.requests().len(), 2);
+}
+
+#[tokio::test]
+async fn test_per_model_limits() {
+    let server = server().await;
+    let ollama = client(
+        &server,
+        ConcurrencyLimits::new()
+            .max_requests_per_model(1)
+            .model("qwen3", 2),
+    );
+
+    let _llama = ollama.generate_stream(request("llama3.2")).await.unwrap();
+
+    // Other models and endpoints without a model aren't held back
+    let _qwen = ollama.generate_stream(request("qwen3")).await.unwrap();
+    let _qwen = ollama.generate_stream(request("qwen3")).await.unwrap();
+    ollama.list_local_models().await.unwrap();
+
+    let res = tokio::time::timeout(
+        Duration::from_millis(100),
+        ollama.generate_stream(request("llama3.2")),
+    )
+    .await;
+    assert!(res.is_err());
+    assert_eq!(server.requests().len(), 4);
+}
+
+#[tokio::test]
+async fn test_cancelled_requests_leave_the_queue() {
+    let server = server().await;
+    let ollama = client(&server, ConcurrencyLimits::new().max_requests(1));
+
+    let stream = ollama.generate_stream(request("llama3.2")).await.unwrap();
+    let res = tokio::time::timeout(
+        Duration::from_millis(50),
+        ollama.generate_stream(request("llama3.2")),
+    )
+    .await;
+    assert!(res.is_err());
+
+    drop(stream);
+    tokio::time::timeout(Duration::from_secs(5), ollama.list_local_models())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(server.requests().len(), 2);
+}
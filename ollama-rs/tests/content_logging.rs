@@ -0,0 +1,142 @@
+use std::sync::Mutex;
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use ollama_rs::{
+    generation::{
+        chat::{request::ChatMessageRequest, ChatMessage},
+        completion::request::GenerationRequest,
+    },
+    logging::ContentLogging,
+    test_utils::{MockOllama, MockResponse},
+    Ollama,
+};
+
+/// Keeps the messages logged by ollama-rs.
+struct Capture(Mutex<Vec<String>>);
+
+impl Log for Capture {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.target() == "ollama_rs" && metadata.level() <= Level::Debug
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            self.0.lock().unwrap().push(record.args().to_string());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: Capture = Capture(Mutex::new(Vec::new()));
+// Tests run concurrently, while the logger is global.
+static SERIAL: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+fn logs() -> Vec<String> {
+    std::mem::take(&mut *LOGGER.0.lock().unwrap())
+}
+
+async fn client(content_logging: ContentLogging) -> (MockOllama, Ollama) {
+    let _ = log::set_logger(&LOGGER);
+    log::set_max_level(LevelFilter::Debug);
+    logs();
+
+    let server = MockOllama::start().await;
+    server
+        .mock(
+            "/api/generate",
+            MockResponse::generation("Your key is sk-12345"),
+        )
+        .mock("/api/chat", MockResponse::chat("Hello alice@example.com"));
+    let ollama = Ollama::builder()
+        .url(server.url())
+        .content_logging(content_logging)
+        .build()
+        .unwrap();
+    (server, ollama)
+}
+
+#[test]
+fn test_content_logging_apply() {
+    assert_eq!(ContentLogging::Full.apply("secret"), "secret");
+    assert_eq!(ContentLogging::Omit.apply("secret"), "[6 chars]");
+    assert!(matches!(ContentLogging::default(), ContentLogging::Omit));
+
+    let hash = ContentLogging::hash();
+    let hashed = hash.apply("secret");
+    assert!(!hashed.contains("secret"));
+    assert!(hashed.ends_with(", 6 chars]"));
+    assert_eq!(hashed, hash.apply("secret"));
+    assert_ne!(hashed, hash.apply("secret!"));
+
+    // Another salt gives other hashes
+    assert_ne!(hashed, ContentLogging::hash().apply("secret"));
+    let salted = ContentLogging::Hash { salt: [7; 32] };
+    assert_eq!(
+        salted.apply("secret"),
+        ContentLogging::Hash { salt: [7; 32] }.apply("secret")
+    );
+
+    let redact = ContentLogging::redact(|text| text.replace("secret", "***"));
+    assert_eq!(redact.apply("my secret"), "my ***");
+}
+
+#[tokio::test]
+async fn test_prompts_and_responses_are_logged() {
+    let _serial = SERIAL.lock().await;
+    let (_server, ollama) = client(ContentLogging::Full).await;
+
+    let request = GenerationRequest::new("llama3.2".into(), "What is my key?");
+    ollama.generate(request).await.unwrap();
+
+    assert_eq!(
+        logs(),
+        [
+            "llama3.2 prompt: What is my key?",
+            "mock response: Your key is sk-12345"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_redacted_content_is_logged() {
+    let _serial = SERIAL.lock().await;
+    let redact = ContentLogging::redact(|text| {
+        text.split_whitespace()
+            .map(|word| if word.contains('@') { "[email]" } else { word })
+            .collect::<Vec<_>>()
+            .join(" ")
+    });
+    let (_server, ollama) = client(redact).await;
+
+    let messages = vec![
+        ChatMessage::user("I'm bob@example.com".into()),
+        ChatMessage::assistant("Hi!".into()),
+        ChatMessage::user("Who else is here?".into()),
+    ];
+    let request = ChatMessageRequest::new("llama3.2".into(), messages);
+    ollama.send_chat_messages(request).await.unwrap();
+
+    assert_eq!(
+        logs(),
+        [
+            "llama3.2 message: Who else is here?",
+            "mock response: Hello [email]"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_omitted_content_is_not_logged() {
+    let _serial = SERIAL.lock().await;
+    let (_server, ollama) = client(ContentLogging::Omit).await;
+
+    let request = GenerationRequest::new("llama3.2".into(), "What is my key?");
+    ollama.generate(request).await.unwrap();
+
+    let logs = logs();
+    assert_eq!(
+        logs,
+        ["llama3.2 prompt: [15 chars]", "mock response: [20 chars]"]
+    );
+}
@@ -0,0 +1,92 @@
+use ollama_rs::{
+    generation::{
+        chat::{request::ChatMessageRequest, ChatMessage},
+        completion::request::GenerationRequest,
+        done_reason::DoneReason,
+    },
+    test_utils::{MockOllama, MockResponse},
+};
+use serde_json::{json, Value};
+
+fn truncated(mut response: Value) -> MockResponse {
+    response["done_reason"] = json!("length");
+    response["eval_count"] = json!(5);
+    MockResponse::json(response)
+}
+
+#[tokio::test]
+async fn test_chat_continuation() {
+    let chunk = |content: &str| {
+        json!({
+            "model": "llama2",
+            "created_at": "2024-01-01T00:00:00Z",
+            "message": { "role": "assistant", "content": content },
+            "done": true,
+            "total_duration": 10, "prompt_eval_count": 3, "prompt_eval_duration": 1, "eval_duration": 1
+        })
+    };
+
+    let server = MockOllama::start().await;
+    server
+        .mock_once("/api/chat", truncated(chunk("The sky is")))
+        .mock_once("/api/chat", truncated(chunk(" blue because")))
+        .mock("/api/chat", MockResponse::chat(" of scattering."));
+
+    let request = ChatMessageRequest::new(
+        "llama2".to_string(),
+        vec![ChatMessage::user("Why is the sky blue?".to_string())],
+    );
+    let res = server
+        .client()
+        .send_chat_messages_with_continuation(request, 5)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        res.message.content,
+        "The sky is blue because of scattering."
+    );
+    assert_eq!(res.done_reason, Some(DoneReason::Stop));
+    assert_eq!(res.final_data.unwrap().eval_count, 5 + 5 + 20);
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 3);
+    let messages = requests[2].json()["messages"].clone();
+    assert_eq!(messages.as_array().unwrap().len(), 2);
+    assert_eq!(messages[1]["role"], "assistant");
+    assert_eq!(messages[1]["content"], "The sky is blue because");
+}
+
+#[tokio::test]
+async fn test_generation_continuation_cap() {
+    let response = |text: &str| {
+        json!({
+            "model": "llama2",
+            "created_at": "2024-01-01T00:00:00Z",
+            "response": text,
+            "done": true,
+            "context": [1, 2, 3]
+        })
+    };
+
+    let server = MockOllama::start().await;
+    server.mock("/api/generate", truncated(response("more ")));
+
+    let res = server
+        .client()
+        .generate_with_continuation(
+            GenerationRequest::new("llama2".to_string(), "Count forever"),
+            2,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.response, "more more more ");
+    assert_eq!(res.eval_count, Some(15));
+    assert!(res.done_reason.unwrap().is_truncated());
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 3);
+    assert_eq!(requests[0].json()["prompt"], "Count forever");
+    assert_eq!(requests[1].json()["context"], json!([1, 2, 3]));
+}
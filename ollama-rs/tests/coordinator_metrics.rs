@@ -0,0 +1,46 @@
+use ollama_rs::{
+    coordinator::Coordinator,
+    generation::{chat::ChatMessage, tools::implementations::Calculator},
+    test_utils::{MockOllama, MockResponse},
+};
+use serde_json::json;
+
+#[tokio::test]
+async fn test_coordinator_metrics() {
+    let server = MockOllama::start().await;
+    server
+        .mock_once(
+            "/api/chat",
+            MockResponse::tool_calls([
+                ("calculator", json!({ "expression": "2 + 2" })),
+                ("calculator", json!({ "expression": "3 * 3" })),
+            ]),
+        )
+        .mock("/api/chat", MockResponse::chat("4 and 9"));
+
+    let mut coordinator =
+        Coordinator::new(server.client(), "llama3.2".to_string(), vec![]).add_tool(Calculator {});
+    coordinator
+        .chat(vec![ChatMessage::user("2 + 2 and 3 * 3?".to_string())])
+        .await
+        .unwrap();
+
+    let metrics = coordinator.metrics();
+    assert_eq!(metrics.iterations.len(), 2);
+    assert_eq!(metrics.iterations[0].tools.len(), 2);
+    assert!(metrics.iterations[0].tools.iter().all(|tool| !tool.failed));
+    assert!(metrics.iterations[1].tools.is_empty());
+    assert_eq!(metrics.usage().total_tokens(), 60);
+    assert_eq!(
+        metrics.tool_durations().keys().collect::<Vec<_>>(),
+        ["calculator"]
+    );
+    assert!(metrics.total_duration() >= metrics.iterations[0].latency);
+
+    // Each chat starts a new run
+    coordinator
+        .chat(vec![ChatMessage::user("Thanks".to_string())])
+        .await
+        .unwrap();
+    assert_eq!(coordinator.metrics().iterations.len(), 1);
+}
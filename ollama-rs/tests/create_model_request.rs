@@ -0,0 +1,72 @@
+use ollama_rs::{
+    generation::chat::ChatMessage,
+    models::{
+        create::{CreateModelRequest, QuantizationType},
+        ModelOptions,
+    },
+    test_utils::{MockOllama, MockResponse},
+};
+use serde_json::json;
+
+#[tokio::test]
+async fn test_create_model_sends_typed_fields() {
+    let server = MockOllama::start().await;
+    server.mock(
+        "/api/create",
+        MockResponse::json(json!({ "status": "success" })),
+    );
+
+    let request = CreateModelRequest::new("mario".into())
+        .from_model("llama3.2".into())
+        .adapter("mario-lora.gguf", "sha256:abc")
+        .license("MIT".into())
+        .system("You are Mario.".into())
+        .template("{{ .Prompt }}".into())
+        .parameters(ModelOptions::default().temperature(0.8))
+        .message(ChatMessage::user("Who are you?".into()))
+        .message(ChatMessage::assistant("It's-a me!".into()))
+        .quantize(QuantizationType::Q4KM);
+    let status = server.client().create_model(request).await.unwrap();
+    assert_eq!(status.message, "success");
+
+    let body = server.requests()[0].json();
+    assert_eq!(body["model"], "mario");
+    assert_eq!(body["from"], "llama3.2");
+    assert_eq!(body["adapters"], json!({ "mario-lora.gguf": "sha256:abc" }));
+    assert_eq!(body["license"], json!(["MIT"]));
+    assert_eq!(body["system"], "You are Mario.");
+    assert_eq!(body["template"], "{{ .Prompt }}");
+    assert_eq!(
+        body["parameters"]["temperature"].as_f64().unwrap() as f32,
+        0.8
+    );
+    assert_eq!(body["messages"][0]["role"], "user");
+    assert_eq!(body["messages"][1]["content"], "It's-a me!");
+    assert_eq!(body["quantize"], "q4_K_M");
+    assert_eq!(body["stream"], false);
+}
+
+#[tokio::test]
+async fn test_create_model_from_files_omits_unset_fields() {
+    let server = MockOllama::start().await;
+    server.mock(
+        "/api/create",
+        MockResponse::json(json!({ "status": "success" })),
+    );
+
+    let request = CreateModelRequest::new("local".into())
+        .file("model.gguf", "sha256:123")
+        .file("tokenizer.json", "sha256:456");
+    server.client().create_model(request).await.unwrap();
+
+    let body = server.requests()[0].json();
+    assert_eq!(
+        body["files"],
+        json!({ "model.gguf": "sha256:123", "tokenizer.json": "sha256:456" })
+    );
+    for field in [
+        "from", "adapters", "license", "system", "messages", "quantize",
+    ] {
+        assert!(body.get(field).is_none(), "{field} was sent");
+    }
+}
@@ -0,0 +1,80 @@
+use ollama_rs::generation::{
+    parameters::JsonSchema,
+    tools::{DescribedParameters, Tool},
+};
+use serde::Deserialize;
+
+#[allow(dead_code)]
+#[derive(Deserialize, JsonSchema, DescribedParameters)]
+struct Location {
+    /// The name of the city
+    city: String,
+    #[schemars(description = "The ISO code of the country")]
+    country: Option<String>,
+}
+
+#[allow(dead_code)]
+#[derive(Deserialize, JsonSchema, DescribedParameters)]
+struct WeatherParams {
+    #[serde(flatten)]
+    location: Location,
+    /// The number of days to forecast
+    #[serde(default)]
+    days: u8,
+    #[serde(skip)]
+    cache_key: String,
+}
+
+#[allow(dead_code)]
+#[derive(Deserialize, JsonSchema, DescribedParameters)]
+#[serde(tag = "unit", rename_all = "lowercase")]
+enum Temperature<T> {
+    Celsius {
+        /// The temperature in degrees Celsius
+        value: T,
+    },
+    Kelvin,
+}
+
+struct Weather;
+
+impl Tool for Weather {
+    type Params = WeatherParams;
+
+    fn name() -> &'static str {
+        "weather"
+    }
+
+    fn description() -> &'static str {
+        "Forecasts the weather."
+    }
+
+    async fn call(&self, params: WeatherParams) -> ollama_rs::generation::tools::Result<String> {
+        Ok(format!("Sunny in {}", params.location.city))
+    }
+}
+
+/// Only accepts tools whose parameters are all described.
+fn described_tool_name<T: Tool>() -> &'static str
+where
+    T::Params: DescribedParameters,
+{
+    T::name()
+}
+
+fn assert_described<T: DescribedParameters>() {}
+
+#[test]
+fn test_described_parameters() {
+    assert_eq!(described_tool_name::<Weather>(), "weather");
+    assert_described::<Temperature<f32>>();
+
+    let schema = serde_json::to_value(ollama_rs::generation::parameters::schema_for!(
+        WeatherParams
+    ))
+    .unwrap();
+    assert_eq!(
+        schema["properties"]["country"]["description"],
+        "The ISO code of the country"
+    );
+}
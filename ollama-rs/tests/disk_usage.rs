@@ -0,0 +1,96 @@
+use ollama_rs::{
+    models::{disk_usage::DiskUsage, LocalModel},
+    test_utils::{MockOllama, MockResponse},
+};
+use serde_json::json;
+
+fn model(
+    name: &str,
+    digest: &str,
+    size: u64,
+    family: &str,
+    quantization: &str,
+) -> serde_json::Value {
+    json!({
+        "name": name,
+        "model": name,
+        "modified_at": "2024-06-01T12:00:00Z",
+        "size": size,
+        "digest": digest,
+        "details": {
+            "parent_model": "",
+            "format": "gguf",
+            "family": family,
+            "families": [family],
+            "parameter_size": "3.2B",
+            "quantization_level": quantization
+        }
+    })
+}
+
+fn blob(byte: char) -> String {
+    format!(
+        "/root/.ollama/models/blobs/sha256-{}",
+        byte.to_string().repeat(64)
+    )
+}
+
+#[tokio::test]
+async fn test_models_disk_usage() {
+    let models = json!([
+        model("llama3.2:latest", "a80c4f17", 2_000, "llama", "Q4_K_M"),
+        model("mario:latest", "a80c4f17", 2_000, "llama", "Q4_K_M"),
+        model("mario-precise:latest", "5b1e03ca", 2_001, "llama", "Q4_K_M"),
+        model("llama3.1:8b", "46e0c10c", 5_000, "llama", "Q8_0"),
+        model("qwen2.5:0.5b", "a8b0c515", 400, "qwen2", "Q4_K_M"),
+    ]);
+    let server = MockOllama::start().await;
+    server.mock("/api/tags", MockResponse::json(json!({ "models": models })));
+    server.mock_with("/api/show", |request| {
+        let weights = match request.json()["name"].as_str().unwrap() {
+            "llama3.1:8b" => blob('c'),
+            "qwen2.5:0.5b" => blob('b'),
+            _ => blob('a'),
+        };
+        let modelfile = format!("FROM {weights}\nPARAMETER temperature 0.3\n");
+        MockResponse::json(json!({ "modelfile": modelfile }))
+    });
+
+    let usage = server.client().models_disk_usage().await.unwrap();
+
+    assert_eq!(usage.total_bytes, 7_401);
+    assert_eq!(usage.models.len(), 5);
+    assert_eq!(usage.models[0].name, "llama3.1:8b");
+    assert_eq!(usage.models[4].name, "qwen2.5:0.5b");
+    assert_eq!(
+        usage.models[4].weights.as_deref(),
+        Some(format!("sha256:{}", "b".repeat(64)).as_str())
+    );
+
+    assert_eq!(usage.by_family["llama"], 7_001);
+    assert_eq!(usage.by_family["qwen2"], 400);
+    assert_eq!(usage.by_quantization["Q4_K_M"], 2_401);
+    assert_eq!(usage.by_quantization["Q8_0"], 5_000);
+
+    assert_eq!(usage.duplicates.len(), 1);
+    assert_eq!(
+        usage.duplicates[0].digest,
+        format!("sha256:{}", "a".repeat(64))
+    );
+    assert_eq!(
+        usage.duplicates[0].names,
+        ["llama3.2:latest", "mario-precise:latest", "mario:latest"]
+    );
+    assert_eq!(usage.duplicates[0].size, 2_000);
+
+    // Without the Modelfiles, only identical models are found
+    let models: Vec<LocalModel> = serde_json::from_value(models).unwrap();
+    let usage = DiskUsage::from_models(&models);
+    assert_eq!(usage.total_bytes, 9_401);
+    assert_eq!(usage.duplicates.len(), 1);
+    assert_eq!(usage.duplicates[0].digest, "a80c4f17");
+    assert_eq!(
+        usage.duplicates[0].names,
+        ["llama3.2:latest", "mario:latest"]
+    );
+}
@@ -0,0 +1,48 @@
+use ollama_rs::{
+    generation::tools::implementations::{
+        Document, DocumentRetriever, InMemoryVectorStore, VectorStore,
+    },
+    Ollama,
+};
+
+#[tokio::test]
+async fn test_in_memory_vector_store_search() {
+    let mut store = InMemoryVectorStore::new();
+
+    store
+        .add(
+            vec![
+                Document::new("a", "about cats"),
+                Document::new("b", "about dogs"),
+                Document::new("c", "about birds"),
+            ],
+            vec![vec![1.0, 0.0], vec![0.0, 1.0], vec![0.7, 0.7]],
+        )
+        .await
+        .unwrap();
+
+    let results = store.search(&[1.0, 0.1], 2).await.unwrap();
+
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].document.id, "a");
+    assert_eq!(results[1].document.id, "c");
+}
+
+#[tokio::test]
+async fn test_document_retriever() {
+    let ollama = Ollama::default();
+
+    let mut retriever = DocumentRetriever::new(ollama, "llama2:latest".to_string());
+    retriever
+        .add_documents(vec![
+            Document::new("sky", "The sky is blue because of Rayleigh scattering."),
+            Document::new("grass", "Grass is green because of chlorophyll."),
+        ])
+        .await
+        .unwrap();
+
+    let results = retriever.retrieve("Why is the sky blue?", 1).await.unwrap();
+    dbg!(&results);
+
+    assert_eq!(results.len(), 1);
+}
@@ -0,0 +1,53 @@
+use ollama_rs::{
+    generation::{
+        chat::{request::ChatMessageRequest, ChatMessage},
+        completion::request::GenerationRequest,
+        done_reason::DoneReason,
+    },
+    test_utils::{MockOllama, MockResponse},
+};
+use serde_json::json;
+
+#[tokio::test]
+async fn test_done_reason() {
+    let server = MockOllama::start().await;
+    server
+        .mock_once("/api/generate", MockResponse::generation("Blue"))
+        .mock(
+            "/api/generate",
+            MockResponse::json(json!({
+                "model": "llama2",
+                "created_at": "2024-01-01T00:00:00Z",
+                "response": "The sky is blue because",
+                "done": true,
+                "done_reason": "length"
+            })),
+        )
+        .mock("/api/chat", MockResponse::chat("Blue"));
+
+    let ollama = server.client();
+    let request = || GenerationRequest::new("llama2".to_string(), "Why is the sky blue?");
+
+    let res = ollama.generate(request()).await.unwrap();
+    assert_eq!(res.done_reason, Some(DoneReason::Stop));
+
+    let res = ollama.generate(request()).await.unwrap();
+    assert!(res.done_reason.unwrap().is_truncated());
+
+    let res = ollama
+        .send_chat_messages(ChatMessageRequest::new(
+            "llama2".to_string(),
+            vec![ChatMessage::user("Why?".to_string())],
+        ))
+        .await
+        .unwrap();
+    assert_eq!(res.done_reason, Some(DoneReason::Stop));
+}
+
+#[test]
+fn test_unknown_done_reason() {
+    let reason: DoneReason = serde_json::from_value(json!("cancelled")).unwrap();
+    assert_eq!(reason, DoneReason::Unknown("cancelled".to_string()));
+    assert_eq!(serde_json::to_value(&reason).unwrap(), "cancelled");
+    assert_eq!(DoneReason::from("unload"), DoneReason::Unload);
+}
@@ -0,0 +1,78 @@
+use ollama_rs::{
+    error::OllamaError,
+    generation::embeddings::{request::GenerateEmbeddingsRequest, EmbeddingsEndpoint},
+    test_utils::{MockOllama, MockResponse},
+    Ollama,
+};
+use serde_json::json;
+
+fn request() -> GenerateEmbeddingsRequest {
+    GenerateEmbeddingsRequest::new("nomic-embed-text".to_string(), vec!["a", "b"].into())
+}
+
+#[tokio::test]
+async fn test_legacy_embeddings() {
+    let server = MockOllama::start().await;
+    server.mock(
+        "/api/embeddings",
+        MockResponse::json(json!({ "embedding": [0.5, 0.25] })),
+    );
+
+    let ollama = Ollama::builder()
+        .url(server.url())
+        .embeddings_endpoint(EmbeddingsEndpoint::Legacy)
+        .build()
+        .unwrap();
+    let res = ollama.generate_embeddings(request()).await.unwrap();
+
+    assert_eq!(res.embeddings, [[0.5, 0.25], [0.5, 0.25]]);
+    let requests = server.requests();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(
+        requests[1].json(),
+        json!({ "model": "nomic-embed-text", "prompt": "b" })
+    );
+}
+
+#[tokio::test]
+async fn test_auto_falls_back_to_legacy_embeddings() {
+    let server = MockOllama::start().await;
+    server.mock(
+        "/api/embeddings",
+        MockResponse::json(json!({ "embedding": [1.0] })),
+    );
+
+    let ollama = server.client();
+    let res = ollama.generate_embeddings(request()).await.unwrap();
+    assert_eq!(res.embeddings, [[1.0], [1.0]]);
+
+    // The fallback is remembered
+    ollama.generate_embeddings(request()).await.unwrap();
+    let paths: Vec<_> = server.requests().into_iter().map(|r| r.path).collect();
+    assert_eq!(
+        paths,
+        [
+            "/api/embed",
+            "/api/embeddings",
+            "/api/embeddings",
+            "/api/embeddings",
+            "/api/embeddings"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_auto_keeps_missing_model_errors() {
+    let server = MockOllama::start().await;
+    server.mock(
+        "/api/embed",
+        MockResponse::error(
+            404,
+            r#"model "nomic-embed-text" not found, try pulling it first"#,
+        ),
+    );
+
+    let res = server.client().generate_embeddings(request()).await;
+    assert!(matches!(res, Err(OllamaError::ModelNotFound { .. })));
+    assert_eq!(server.requests().len(), 1);
+}
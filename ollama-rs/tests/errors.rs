@@ -0,0 +1,201 @@
+use ollama_rs::test_utils::{MockOllama, MockResponse};
+use ollama_rs::{
+    error::{Capability, ErrorCategory, OllamaError},
+    generation::{chat::request::ChatMessageRequest, completion::request::GenerationRequest},
+    Ollama,
+};
+use tokio::net::TcpListener;
+
+/// Starts a server answering generation requests with the given response.
+async fn respond_with(response: MockResponse) -> MockOllama {
+    let server = MockOllama::start().await;
+    server.mock("/api/generate", response);
+    server
+}
+
+fn request() -> GenerationRequest<'static> {
+    GenerationRequest::new("llama2:latest".to_string(), "Why is the sky blue?")
+}
+
+#[tokio::test]
+async fn test_model_not_found() {
+    let server = respond_with(MockResponse::error(
+        404,
+        r#"model "llama2:latest" not found, try pulling it first"#,
+    ))
+    .await;
+
+    let err = server.client().generate(request()).await.unwrap_err();
+
+    assert!(!err.is_retryable());
+    match err {
+        OllamaError::ModelNotFound { model, .. } => assert_eq!(model, "llama2:latest"),
+        e => panic!("unexpected error: {e:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_unsupported_capability() {
+    let server = MockOllama::start().await;
+    server.mock(
+        "/api/chat",
+        MockResponse::error(
+            400,
+            r#"registry.ollama.ai/library/gemma:2b does not support tools"#,
+        ),
+    );
+    server.mock(
+        "/api/generate",
+        MockResponse::error(400, r#""llava-phi" does not support insert"#),
+    );
+    let ollama = server.client();
+
+    let chat = ChatMessageRequest::new("gemma:2b".to_string(), vec![]);
+    let err = ollama.send_chat_messages(chat).await.unwrap_err();
+    assert_eq!(err.category(), ErrorCategory::Client);
+    assert!(!err.is_retryable());
+    assert_eq!(
+        err.to_string(),
+        "Model registry.ollama.ai/library/gemma:2b does not support tools"
+    );
+    match err {
+        OllamaError::UnsupportedCapability {
+            model, capability, ..
+        } => {
+            assert_eq!(model, "registry.ollama.ai/library/gemma:2b");
+            assert_eq!(capability, Capability::Tools);
+        }
+        e => panic!("unexpected error: {e:?}"),
+    }
+
+    match ollama.generate(request()).await.unwrap_err() {
+        OllamaError::UnsupportedCapability {
+            model, capability, ..
+        } => {
+            assert_eq!(model, "llava-phi");
+            assert_eq!(capability, Capability::Insert);
+        }
+        e => panic!("unexpected error: {e:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_http_error_message() {
+    let server = respond_with(MockResponse::error(400, "invalid options")).await;
+
+    let err = server.client().generate(request()).await.unwrap_err();
+
+    match err {
+        OllamaError::HttpError {
+            status, message, ..
+        } => {
+            assert_eq!(status.as_u16(), 400);
+            assert_eq!(message, "invalid options");
+        }
+        e => panic!("unexpected error: {e:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_out_of_memory() {
+    let server = respond_with(MockResponse::error(
+        500,
+        "model requires more system memory (10.2 GiB) than is available (4.1 GiB)",
+    ))
+    .await;
+
+    let err = server.client().generate(request()).await.unwrap_err();
+
+    assert_eq!(err.category(), ErrorCategory::Resource);
+    assert!(!err.is_retryable());
+    match err {
+        OllamaError::OutOfMemory {
+            model,
+            required,
+            available,
+            ..
+        } => {
+            assert_eq!(model.as_deref(), Some("llama2:latest"));
+            assert_eq!(required.unwrap().bytes(), 10952166604);
+            assert_eq!(available.unwrap().bytes(), 4402341478);
+        }
+        e => panic!("unexpected error: {e:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_gpu_out_of_memory() {
+    let server = MockOllama::start().await;
+    server.mock(
+        "/api/chat",
+        MockResponse::error(
+            500,
+            "llama runner process has terminated: cudaMalloc failed: out of memory",
+        ),
+    );
+
+    let err = server
+        .client()
+        .send_chat_messages(ChatMessageRequest::new("llama3.1:70b".to_string(), vec![]))
+        .await
+        .unwrap_err();
+
+    match err {
+        OllamaError::OutOfMemory {
+            model,
+            required,
+            available,
+            ..
+        } => {
+            assert_eq!(model.as_deref(), Some("llama3.1:70b"));
+            assert!(required.is_none() && available.is_none());
+        }
+        e => panic!("unexpected error: {e:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_decode_error_keeps_payload() {
+    let server = respond_with(MockResponse::body(r#"{"unexpected":true}"#)).await;
+
+    let err = server.client().generate(request()).await.unwrap_err();
+
+    match err {
+        OllamaError::DecodeError { payload, .. } => assert_eq!(payload, r#"{"unexpected":true}"#),
+        e => panic!("unexpected error: {e:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_connection_error() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    drop(listener);
+
+    let err = Ollama::new("http://127.0.0.1", port)
+        .generate(request())
+        .await
+        .unwrap_err();
+
+    assert!(
+        matches!(err, OllamaError::ConnectionError { .. }),
+        "{err:?}"
+    );
+    assert!(err.request_id().is_some_and(|id| id.starts_with("req_")));
+    assert_eq!(err.category(), ErrorCategory::Network);
+    assert!(err.is_retryable());
+}
+
+#[tokio::test]
+async fn test_server_errors_are_retryable() {
+    let server = respond_with(MockResponse::body("server busy").status(503)).await;
+
+    let err = server.client().generate(request()).await.unwrap_err();
+
+    assert_eq!(err.category(), ErrorCategory::Server);
+    assert!(err.is_retryable());
+    match err {
+        OllamaError::HttpError { message, .. } => assert_eq!(message, "server busy"),
+        e => panic!("unexpected error: {e:?}"),
+    }
+}
@@ -0,0 +1,151 @@
+use std::time::Duration;
+
+use ollama_rs::{
+    error::OllamaError,
+    eval::{EvalConfig, Judge},
+    models::ModelOptions,
+    test_utils::{MockOllama, MockResponse},
+};
+use serde_json::json;
+
+#[tokio::test]
+async fn test_compare() {
+    let server = MockOllama::start().await;
+    server.mock_with("/api/generate", |request| {
+        let body = request.json();
+        let model = body["model"].as_str().unwrap();
+        let prompt = body["prompt"].as_str().unwrap();
+        if prompt == "fail" && model == "small" {
+            return MockResponse::error(500, "model runner has unexpectedly stopped");
+        }
+
+        let delay = if model == "large" { 100 } else { 10 };
+        MockResponse::json(json!({
+            "model": model,
+            "created_at": "2024-01-01T00:00:00Z",
+            "response": format!("{model}: {prompt}"),
+            "done": true,
+            "eval_count": 10,
+            "eval_duration": 500_000_000
+        }))
+        .delay(Duration::from_millis(delay))
+    });
+
+    let a = EvalConfig::new("small").system("Be brief.");
+    let b = EvalConfig::new("large").options(ModelOptions::default().temperature(0.0));
+    let comparison = server
+        .client()
+        .compare(["one", "two", "fail"], &a, &b)
+        .await;
+
+    assert_eq!(comparison.results.len(), 3);
+    let pair = &comparison.results[1];
+    assert_eq!(pair.prompt, "two");
+    assert_eq!(pair.a.output(), Some("small: two"));
+    assert_eq!(pair.b.output(), Some("large: two"));
+    assert!(pair.b.latency >= Duration::from_millis(100));
+    assert!(comparison.results[2].a.result.is_err());
+
+    let summary_a = comparison.summary_a();
+    assert_eq!(summary_a.runs, 3);
+    assert_eq!(summary_a.errors, 1);
+    assert_eq!(summary_a.eval_tokens, 20);
+    assert_eq!(summary_a.mean_tokens_per_second, Some(20.0));
+    let summary_b = comparison.summary_b();
+    assert_eq!(summary_b.errors, 0);
+    assert!(summary_b.mean_latency > summary_a.mean_latency);
+
+    // The configuration running first alternates
+    let models: Vec<_> = server
+        .requests()
+        .iter()
+        .map(|request| request.json()["model"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(
+        models,
+        ["small", "large", "large", "small", "small", "large"]
+    );
+
+    let requests = server.requests();
+    assert_eq!(requests[0].json()["system"], "Be brief.");
+    assert_eq!(requests[1].json()["options"]["temperature"], 0.0);
+}
+
+fn verdict(score: u32) -> MockResponse {
+    MockResponse::generation(
+        &json!({ "rationale": "Correct and concise.", "score": score }).to_string(),
+    )
+}
+
+#[tokio::test]
+async fn test_judge_score() {
+    let server = MockOllama::start().await;
+    server.mock("/api/generate", verdict(4));
+
+    let judge = Judge::new(server.client(), "judge")
+        .rubric("The answer is correct.")
+        .scale(1..=5);
+    let verdict = judge
+        .score(
+            "Why is the sky blue?",
+            "Rayleigh scattering.",
+            Some("Because of Rayleigh scattering."),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(verdict.score, 4);
+    assert_eq!(verdict.rationale, "Correct and concise.");
+
+    let body = server.requests()[0].json();
+    assert_eq!(body["model"], "judge");
+    assert_eq!(body["options"]["temperature"], 0.0);
+    assert_eq!(body["format"]["required"], json!(["rationale", "score"]));
+    let prompt = body["prompt"].as_str().unwrap();
+    assert!(prompt.contains("The answer is correct."));
+    assert!(prompt.contains("from 1 (worst) to 5 (best)"));
+    assert!(prompt.contains("Reference response:\nBecause of Rayleigh scattering."));
+    assert!(prompt.ends_with("Response to evaluate:\nRayleigh scattering."));
+}
+
+#[tokio::test]
+async fn test_judge_score_out_of_scale() {
+    let server = MockOllama::start().await;
+    server.mock("/api/generate", verdict(9));
+
+    let err = Judge::new(server.client(), "judge")
+        .scale(1..=5)
+        .score("Hi", "Hello", None)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, OllamaError::Other(_)), "{err:?}");
+}
+
+#[tokio::test]
+async fn test_judge_comparison() {
+    let server = MockOllama::start().await;
+    server.mock_with("/api/generate", |request| {
+        let body = request.json();
+        match body["model"].as_str().unwrap() {
+            "judge" => verdict(7),
+            "broken" => MockResponse::error(500, "model runner has unexpectedly stopped"),
+            model => MockResponse::generation(model),
+        }
+    });
+
+    let comparison = server
+        .client()
+        .compare(
+            ["Hi"],
+            &EvalConfig::new("small"),
+            &EvalConfig::new("broken"),
+        )
+        .await;
+    let verdicts = Judge::new(server.client(), "judge")
+        .judge_comparison(&comparison)
+        .await;
+
+    assert_eq!(verdicts.len(), 1);
+    assert_eq!(verdicts[0].a.as_ref().unwrap().score, 7);
+    assert!(verdicts[0].b.is_err());
+}
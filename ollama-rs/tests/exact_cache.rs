@@ -0,0 +1,133 @@
+use std::time::Duration;
+
+use ollama_rs::{
+    cache::exact::{DirectoryBackend, ExactCache, InMemoryBackend},
+    generation::{
+        chat::{request::ChatMessageRequest, ChatMessage},
+        completion::request::GenerationRequest,
+    },
+    models::ModelOptions,
+    test_utils::{MockOllama, MockResponse},
+};
+
+async fn server() -> MockOllama {
+    let server = MockOllama::start().await;
+    server
+        .mock_with("/api/generate", |request| {
+            MockResponse::generation(request.json()["prompt"].as_str().unwrap())
+        })
+        .mock("/api/chat", MockResponse::chat("Hello!"));
+    server
+}
+
+fn count(server: &MockOllama, path: &str) -> usize {
+    server
+        .requests()
+        .iter()
+        .filter(|request| request.path == path)
+        .count()
+}
+
+fn greedy() -> ModelOptions {
+    ModelOptions::default().temperature(0.0)
+}
+
+#[tokio::test]
+async fn test_exact_cache_hit() {
+    let server = server().await;
+    let ollama = server.client();
+    let cache = ExactCache::new(InMemoryBackend::new());
+
+    for _ in 0..2 {
+        let request = GenerationRequest::new("llama3.2".into(), "Hi").options(greedy());
+        let res = cache.generate(&ollama, request).await.unwrap();
+        assert_eq!(res.response, "Hi");
+    }
+
+    assert_eq!(count(&server, "/api/generate"), 1);
+    assert_eq!(cache.backend().len(), 1);
+}
+
+#[tokio::test]
+async fn test_exact_cache_keys_on_model_and_request() {
+    let server = server().await;
+    let ollama = server.client();
+    let cache = ExactCache::new(InMemoryBackend::new());
+
+    let requests = [
+        GenerationRequest::new("llama3.2".into(), "Hi").options(greedy()),
+        GenerationRequest::new("qwen2.5".into(), "Hi").options(greedy()),
+        GenerationRequest::new("llama3.2".into(), "Hello").options(greedy()),
+        GenerationRequest::new("llama3.2".into(), "Hi").options(greedy().num_ctx(4096)),
+    ];
+    for request in requests {
+        cache.generate(&ollama, request).await.unwrap();
+    }
+
+    assert_eq!(count(&server, "/api/generate"), 4);
+}
+
+#[tokio::test]
+async fn test_exact_cache_skips_sampled_requests() {
+    let server = server().await;
+    let ollama = server.client();
+    let cache = ExactCache::new(InMemoryBackend::new());
+
+    for _ in 0..2 {
+        let request = GenerationRequest::new("llama3.2".into(), "Hi");
+        cache.generate(&ollama, request).await.unwrap();
+    }
+
+    assert_eq!(count(&server, "/api/generate"), 2);
+    assert!(cache.backend().is_empty());
+}
+
+#[tokio::test]
+async fn test_exact_cache_seeded_chat() {
+    let server = server().await;
+    let ollama = server.client();
+    let cache = ExactCache::new(InMemoryBackend::new());
+
+    for _ in 0..2 {
+        let request =
+            ChatMessageRequest::new("llama3.2".into(), vec![ChatMessage::user("Hi".into())])
+                .options(ModelOptions::default().seed(42));
+        let res = cache.send_chat_messages(&ollama, request).await.unwrap();
+        assert_eq!(res.message.content, "Hello!");
+    }
+
+    assert_eq!(count(&server, "/api/chat"), 1);
+}
+
+#[tokio::test]
+async fn test_exact_cache_ttl() {
+    let server = server().await;
+    let ollama = server.client();
+    let cache = ExactCache::new(InMemoryBackend::new()).ttl(Duration::from_millis(50));
+
+    let request = GenerationRequest::new("llama3.2".into(), "Hi").options(greedy());
+    cache.generate(&ollama, request.clone()).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    cache.generate(&ollama, request).await.unwrap();
+
+    assert_eq!(count(&server, "/api/generate"), 2);
+}
+
+#[tokio::test]
+async fn test_exact_cache_directory_backend() {
+    let server = server().await;
+    let ollama = server.client();
+    let dir = std::env::temp_dir().join(format!("ollama-rs-exact-cache-{}", std::process::id()));
+
+    for _ in 0..2 {
+        // A new cache every time, as after a restart.
+        let cache = ExactCache::new(DirectoryBackend::new(&dir));
+        let request = GenerationRequest::new("llama3.2".into(), "Hi").options(greedy());
+        let res = cache.generate(&ollama, request).await.unwrap();
+        assert_eq!(res.response, "Hi");
+    }
+
+    assert_eq!(count(&server, "/api/generate"), 1);
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
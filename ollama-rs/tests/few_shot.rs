@@ -0,0 +1,47 @@
+use ollama_rs::generation::chat::{few_shot::FewShot, ChatMessage, MessageRole};
+
+#[test]
+fn test_few_shot_prefix() {
+    let request = FewShot::new()
+        .system("Answer with the capital city.")
+        .example("France", "Paris")
+        .examples([("Italy", "Rome"), ("Spain", "Madrid")])
+        .request(
+            "llama3.2".to_string(),
+            vec![ChatMessage::user("Germany".to_string())],
+        );
+
+    let messages: Vec<_> = request
+        .messages
+        .iter()
+        .map(|m| (m.role.clone(), m.content.as_str()))
+        .collect();
+
+    assert_eq!(
+        messages,
+        [
+            (MessageRole::System, "Answer with the capital city."),
+            (MessageRole::User, "France"),
+            (MessageRole::Assistant, "Paris"),
+            (MessageRole::User, "Italy"),
+            (MessageRole::Assistant, "Rome"),
+            (MessageRole::User, "Spain"),
+            (MessageRole::Assistant, "Madrid"),
+            (MessageRole::User, "Germany"),
+        ]
+    );
+}
+
+#[test]
+fn test_few_shot_token_cap() {
+    // Each example is estimated at 2 + 1 tokens
+    let few_shot = FewShot::new()
+        .example("12345678", "1234")
+        .example("abcdefgh", "abcd")
+        .example("ABCDEFGH", "ABCD");
+
+    assert_eq!(few_shot.clone().max_example_tokens(6).messages().len(), 4);
+    assert_eq!(few_shot.clone().max_example_tokens(5).messages().len(), 2);
+    assert!(few_shot.clone().max_example_tokens(2).messages().is_empty());
+    assert_eq!(few_shot.messages().len(), 6);
+}
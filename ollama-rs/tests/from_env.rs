@@ -0,0 +1,82 @@
+use ollama_rs::{
+    error::OllamaError,
+    generation::completion::request::GenerationRequest,
+    test_utils::{MockOllama, MockResponse},
+    Ollama,
+};
+
+fn env_vars() -> [&'static str; 4] {
+    [
+        "OLLAMA_HOST",
+        "OLLAMA_API_KEY",
+        "OLLAMA_TIMEOUT",
+        "OLLAMA_CONNECT_TIMEOUT",
+    ]
+}
+
+// Environment variables are process-wide, so every case runs in the same test.
+#[tokio::test]
+async fn test_from_env() {
+    for key in env_vars() {
+        std::env::remove_var(key);
+    }
+
+    // Same cases as the `TestHost` test of Ollama
+    let cases = [
+        ("", "http://127.0.0.1:11434/"),
+        ("1.2.3.4", "http://1.2.3.4:11434/"),
+        (":1234", "http://127.0.0.1:1234/"),
+        ("1.2.3.4:1234", "http://1.2.3.4:1234/"),
+        ("example.com", "http://example.com:11434/"),
+        ("example.com:1234", "http://example.com:1234/"),
+        ("0.0.0.0", "http://0.0.0.0:11434/"),
+        ("0.0.0.0:1234", "http://0.0.0.0:1234/"),
+        ("[0::1]", "http://[::1]:11434/"),
+        ("[0::1]:1234", "http://[::1]:1234/"),
+        ("0::1", "http://[::1]:11434/"),
+        ("http://example.com", "http://example.com/"),
+        ("https://example.com", "https://example.com/"),
+        ("http://example.com:1234", "http://example.com:1234/"),
+        ("example.com:99999", "http://example.com:11434/"),
+        ("example.com:", "http://example.com:11434/"),
+        ("\"example.com:1234\"", "http://example.com:1234/"),
+        ("https://example.com/ollama", "https://example.com/ollama"),
+    ];
+
+    for (host, expected) in cases {
+        std::env::set_var("OLLAMA_HOST", host);
+        assert_eq!(Ollama::from_env().url().as_str(), expected, "{host:?}");
+    }
+
+    // Authentication
+    let server = MockOllama::start().await;
+    server.mock("/api/generate", MockResponse::generation("Hi"));
+    std::env::set_var("OLLAMA_HOST", server.url().as_str());
+    std::env::set_var("OLLAMA_API_KEY", "secret");
+
+    Ollama::from_env()
+        .generate(GenerationRequest::new("llama2".to_string(), "Hi"))
+        .await
+        .unwrap();
+    assert_eq!(
+        server.requests()[0].headers["authorization"],
+        "Bearer secret"
+    );
+
+    // Timeouts
+    server.mock(
+        "/api/generate",
+        MockResponse::generation("Hi").delay(std::time::Duration::from_millis(500)),
+    );
+    std::env::set_var("OLLAMA_TIMEOUT", "100ms");
+
+    let err = Ollama::from_env()
+        .generate(GenerationRequest::new("llama2".to_string(), "Hi"))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, OllamaError::Timeout { .. }), "{err:?}");
+
+    for key in env_vars() {
+        std::env::remove_var(key);
+    }
+}
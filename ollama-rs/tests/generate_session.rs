@@ -0,0 +1,127 @@
+use ollama_rs::{
+    generation::completion::{session::GenerateSession, GenerationContext},
+    test_utils::{MockOllama, MockResponse},
+};
+use serde_json::{json, Value};
+use tokio_stream::StreamExt;
+
+fn generation_with_context(response: &str, context: &[i32]) -> MockResponse {
+    MockResponse::json(json!({
+        "model": "llama2",
+        "created_at": "2023-08-04T08:52:19.385406455-07:00",
+        "response": response,
+        "done": true,
+        "context": context,
+    }))
+}
+
+fn sent_contexts(server: &MockOllama) -> Vec<Value> {
+    server
+        .requests()
+        .iter()
+        .map(|request| request.json()["context"].clone())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_session_threads_context() {
+    let server = MockOllama::start().await;
+    server.mock_once("/api/generate", generation_with_context("Hi Ada.", &[1, 2]));
+    server.mock_once(
+        "/api/generate",
+        generation_with_context("Ada.", &[1, 2, 3, 4]),
+    );
+
+    let mut session =
+        GenerateSession::new(server.client(), "llama2".to_string()).system("Be brief.");
+    session.generate("My name is Ada.").await.unwrap();
+    let res = session.generate("What is my name?").await.unwrap();
+
+    assert_eq!(res.response, "Ada.");
+    assert_eq!(session.context().unwrap().0, vec![1, 2, 3, 4]);
+    assert_eq!(sent_contexts(&server), vec![Value::Null, json!([1, 2])]);
+    assert!(server
+        .requests()
+        .iter()
+        .all(|request| request.json()["system"] == "Be brief."));
+}
+
+#[tokio::test]
+async fn test_session_reset_and_fork() {
+    let server = MockOllama::start().await;
+    server.mock_once("/api/generate", generation_with_context("Hi.", &[1]));
+    server.mock_once(
+        "/api/generate",
+        generation_with_context("Hi Grace.", &[1, 2]),
+    );
+    server.mock_once("/api/generate", generation_with_context("Ada.", &[1, 3]));
+    server.mock("/api/generate", generation_with_context("Who?", &[4]));
+
+    let mut session = GenerateSession::new(server.client(), "llama2".to_string());
+    session.generate("My name is Ada.").await.unwrap();
+
+    let mut branch = session.fork();
+    branch.generate("Call me Grace.").await.unwrap();
+    session.generate("What is my name?").await.unwrap();
+    assert_eq!(branch.context().unwrap().0, vec![1, 2]);
+    assert_eq!(session.context().unwrap().0, vec![1, 3]);
+
+    session.reset();
+    assert!(session.context().is_none());
+    session.generate("What is my name?").await.unwrap();
+
+    assert_eq!(
+        sent_contexts(&server),
+        vec![Value::Null, json!([1]), json!([1]), Value::Null]
+    );
+}
+
+#[tokio::test]
+async fn test_session_with_saved_context() {
+    let server = MockOllama::start().await;
+    server.mock("/api/generate", generation_with_context("Ada.", &[7, 8, 9]));
+
+    let mut session = GenerateSession::new(server.client(), "llama2".to_string())
+        .with_context(GenerationContext(vec![7, 8]));
+    session.generate("What is my name?").await.unwrap();
+
+    assert_eq!(sent_contexts(&server), vec![json!([7, 8])]);
+}
+
+#[tokio::test]
+async fn test_session_stream_updates_context() {
+    let server = MockOllama::start().await;
+    server.mock(
+        "/api/generate",
+        MockResponse::stream([
+            json!({
+                "model": "llama2",
+                "created_at": "2023-08-04T08:52:19.385406455-07:00",
+                "response": "Hi",
+                "done": false,
+            }),
+            json!({
+                "model": "llama2",
+                "created_at": "2023-08-04T08:52:19.385406455-07:00",
+                "response": " Ada.",
+                "done": true,
+                "context": [5, 6],
+            }),
+        ]),
+    );
+
+    let mut session = GenerateSession::new(server.client(), "llama2".to_string());
+    let mut text = String::new();
+    {
+        let mut stream = session.generate_stream("My name is Ada.").await.unwrap();
+        while let Some(chunk) = stream.next().await {
+            chunk
+                .unwrap()
+                .iter()
+                .for_each(|res| text.push_str(&res.response));
+        }
+    }
+
+    assert_eq!(text, "Hi Ada.");
+    assert_eq!(session.context().unwrap().0, vec![5, 6]);
+}
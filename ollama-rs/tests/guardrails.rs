@@ -0,0 +1,131 @@
+use ollama_rs::{
+    error::OllamaError,
+    generation::{
+        chat::{request::ChatMessageRequest, ChatMessage},
+        completion::request::GenerationRequest,
+    },
+    guardrails::{Guardrails, JsonSchemaValidator, RegexValidator},
+    test_utils::{MockOllama, MockResponse},
+};
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+#[tokio::test]
+async fn test_passing_response_is_returned() {
+    let server = MockOllama::start().await;
+    server.mock("/api/generate", MockResponse::generation("ORDER-1234"));
+
+    let guardrails =
+        Guardrails::new().validator(RegexValidator::new(Regex::new(r"^ORDER-\d+$").unwrap()));
+    let request = GenerationRequest::new("llama3.2".into(), "Give me an order number");
+    let res = guardrails
+        .generate(&server.client(), request)
+        .await
+        .unwrap();
+
+    assert_eq!(res.response, "ORDER-1234");
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn test_generation_is_retried_with_violations() {
+    let server = MockOllama::start().await;
+    server.mock_once("/api/generate", MockResponse::generation("Sure! ORDER-12"));
+    server.mock_once("/api/generate", MockResponse::generation("ORDER-12"));
+
+    let guardrails = Guardrails::new().validator(
+        RegexValidator::new(Regex::new(r"^ORDER-\d+$").unwrap())
+            .violation("Answer with the order number only"),
+    );
+    let request = GenerationRequest::new("llama3.2".into(), "Give me an order number");
+    let res = guardrails
+        .generate(&server.client(), request)
+        .await
+        .unwrap();
+
+    assert_eq!(res.response, "ORDER-12");
+    let requests = server.requests();
+    assert_eq!(requests.len(), 2);
+    let retry = requests[1].json()["prompt"].as_str().unwrap().to_string();
+    assert!(retry.starts_with("Give me an order number"), "{retry}");
+    assert!(retry.contains("Sure! ORDER-12"), "{retry}");
+    assert!(
+        retry.contains("Answer with the order number only"),
+        "{retry}"
+    );
+}
+
+#[tokio::test]
+async fn test_chat_retry_appends_the_correction() {
+    let server = MockOllama::start().await;
+    server.mock_once("/api/chat", MockResponse::chat("This is way too long."));
+    server.mock_once("/api/chat", MockResponse::chat("Short."));
+
+    let guardrails = Guardrails::new().validator(|response: &str| {
+        if response.split_whitespace().count() <= 2 {
+            Ok(())
+        } else {
+            Err("Use at most two words".to_string())
+        }
+    });
+    let request = ChatMessageRequest::new("llama3.2".into(), vec![ChatMessage::user("Hi".into())]);
+    let res = guardrails
+        .send_chat_messages(&server.client(), request)
+        .await
+        .unwrap();
+
+    assert_eq!(res.message.content, "Short.");
+    let requests = server.requests();
+    let messages = requests[1].json()["messages"].as_array().unwrap().clone();
+    assert_eq!(messages.len(), 3);
+    assert_eq!(messages[1]["role"], "assistant");
+    assert_eq!(messages[1]["content"], "This is way too long.");
+    assert_eq!(messages[2]["role"], "user");
+    assert!(messages[2]["content"]
+        .as_str()
+        .unwrap()
+        .contains("Use at most two words"));
+}
+
+#[derive(Deserialize, JsonSchema)]
+#[allow(dead_code)]
+struct Answer {
+    value: u32,
+}
+
+#[tokio::test]
+async fn test_violation_after_retries() {
+    let server = MockOllama::start().await;
+    server.mock("/api/generate", MockResponse::generation("not json"));
+
+    let guardrails = Guardrails::new()
+        .validator(JsonSchemaValidator::of::<Answer>())
+        .max_retries(1);
+    let request = GenerationRequest::new("llama3.2".into(), "Answer in JSON");
+    let err = guardrails
+        .generate(&server.client(), request)
+        .await
+        .unwrap_err();
+
+    let OllamaError::GuardrailViolation {
+        violations,
+        response,
+        attempts,
+    } = err
+    else {
+        panic!("{err:?}");
+    };
+    assert_eq!(attempts, 2);
+    assert_eq!(response, "not json");
+    assert!(violations[0].contains("valid JSON"), "{violations:?}");
+    assert_eq!(server.requests().len(), 2);
+}
+
+#[test]
+fn test_schema_violations_are_reported() {
+    let guardrails = Guardrails::new().validator(JsonSchemaValidator::of::<Answer>());
+
+    assert!(guardrails.check(r#"{"value": 3}"#).is_empty());
+    assert_eq!(guardrails.check(r#"{"value": "three"}"#).len(), 1);
+}
@@ -0,0 +1,104 @@
+use std::time::{Duration, Instant};
+
+use ollama_rs::{
+    generation::completion::{hedging::HedgeOptions, request::GenerationRequest},
+    test_utils::{MockOllama, MockResponse},
+};
+
+/// Answers with the name of the model, after the delay in milliseconds given as prompt.
+async fn server() -> MockOllama {
+    let server = MockOllama::start().await;
+    server.mock_with("/api/generate", |request| {
+        let body = request.json();
+        let model = body["model"].as_str().unwrap().to_string();
+        if model == "broken" {
+            return MockResponse::error(500, "model runner has unexpectedly stopped");
+        }
+        let delay = body["prompt"].as_str().unwrap().parse().unwrap();
+        MockResponse::generation(&model).delay(Duration::from_millis(delay))
+    });
+    server
+}
+
+#[tokio::test]
+async fn test_backup_wins_when_primary_is_slow() {
+    let server = server().await;
+    let backup = MockOllama::start().await;
+    backup.mock("/api/generate", MockResponse::generation("backup"));
+
+    let start = Instant::now();
+    let res = server
+        .client()
+        .hedged_generate(
+            GenerationRequest::new("slow".to_string(), "2000"),
+            HedgeOptions::new(Duration::from_millis(100)).backup_client(backup.client()),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.response, "backup");
+    assert!(start.elapsed() < Duration::from_millis(1000));
+    assert!(start.elapsed() >= Duration::from_millis(100));
+}
+
+#[tokio::test]
+async fn test_no_backup_when_primary_is_fast() {
+    let server = server().await;
+
+    let res = server
+        .client()
+        .hedged_generate(
+            GenerationRequest::new("primary".to_string(), "10"),
+            HedgeOptions::new(Duration::from_millis(500)).backup_model("backup"),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.response, "primary");
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn test_backup_sent_when_primary_fails() {
+    let server = server().await;
+
+    let start = Instant::now();
+    let res = server
+        .client()
+        .hedged_generate(
+            GenerationRequest::new("broken".to_string(), "0"),
+            HedgeOptions::new(Duration::from_secs(5)).backup_model("backup"),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.response, "backup");
+    assert!(start.elapsed() < Duration::from_secs(1));
+
+    let err = server
+        .client()
+        .hedged_generate(
+            GenerationRequest::new("broken".to_string(), "0"),
+            HedgeOptions::new(Duration::from_millis(10)).backup_model("broken"),
+        )
+        .await;
+    assert!(err.is_err());
+}
+
+#[tokio::test]
+async fn test_unacceptable_response() {
+    let server = server().await;
+
+    let res = server
+        .client()
+        .hedged_generate(
+            GenerationRequest::new("primary".to_string(), "0"),
+            HedgeOptions::new(Duration::from_secs(5))
+                .backup_model("backup")
+                .accept(|res| res.response != "primary"),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.response, "backup");
+}
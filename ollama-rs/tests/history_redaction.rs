@@ -0,0 +1,106 @@
+use std::borrow::Cow;
+
+use ollama_rs::{
+    generation::{
+        chat::ChatMessage,
+        images::Image,
+        tools::{ToolCall, ToolCallFunction},
+    },
+    history::{ChatHistory, MessageHistory, REDACTED},
+};
+use serde_json::json;
+
+#[test]
+fn test_remove_message() {
+    let mut history = MessageHistory::new();
+    let first = history.push_message(ChatMessage::user("My card is 4242".to_string()));
+    let second = history.push_message(ChatMessage::assistant("Noted.".to_string()));
+
+    let removed = history.remove(&first).unwrap();
+    assert_eq!(removed.message.content, "My card is 4242");
+    assert_eq!(history.len(), 1);
+    assert_eq!(history.entries()[0].id, second);
+    assert!(history.remove(&first).is_none());
+    assert!(!history.remove_message(&first));
+}
+
+#[test]
+fn test_redact_message() {
+    let mut history = MessageHistory::new();
+    let id = history.push_message(
+        ChatMessage::user("Here is my passport".to_string())
+            .add_image(Image::from_base64("aGVsbG8=")),
+    );
+    let mut call = ChatMessage::assistant(String::new());
+    call.tool_calls.push(ToolCall {
+        id: Some("call_1".to_string()),
+        function: ToolCallFunction {
+            name: "lookup".to_string(),
+            arguments: json!({ "passport": "X123" }),
+        },
+    });
+    let call_id = history.push_message(call);
+    history.push_message(ChatMessage::assistant("Thanks.".to_string()));
+
+    assert!(history.redact(&id));
+    assert!(history.redact_message(&call_id, "[removed]"));
+    assert!(!history.redact("msg_unknown"));
+
+    let messages = history.messages();
+    assert_eq!(messages.len(), 3);
+    assert_eq!(messages[0].content, REDACTED);
+    assert!(messages[0].images.is_none());
+    assert_eq!(messages[1].content, "[removed]");
+    assert_eq!(messages[1].tool_calls[0].function.name, "lookup");
+    assert_eq!(messages[1].tool_calls[0].function.arguments, json!({}));
+
+    let json = serde_json::to_value(&history).unwrap();
+    assert_eq!(json[0]["redacted"], true);
+    assert!(json[2].get("redacted").is_none());
+    assert!(!json.to_string().contains("X123"));
+}
+
+#[test]
+fn test_histories_without_ids() {
+    let mut history = vec![ChatMessage::user("Hi".to_string())];
+    assert!(!history.remove_message("msg_1"));
+    assert!(!history.redact_message("msg_1", REDACTED));
+    assert_eq!(history.len(), 1);
+}
+
+/// A history persisting its messages, here in a list of deleted ids standing for a database.
+#[derive(Default)]
+struct StoredHistory {
+    history: MessageHistory,
+    deleted: Vec<String>,
+}
+
+impl ChatHistory for StoredHistory {
+    fn push(&mut self, message: ChatMessage) {
+        self.history.push(message);
+    }
+
+    fn messages(&self) -> Cow<'_, [ChatMessage]> {
+        self.history.messages()
+    }
+
+    fn remove_message(&mut self, id: &str) -> bool {
+        self.deleted.push(id.to_string());
+        self.history.remove_message(id)
+    }
+}
+
+fn forget(history: &mut impl ChatHistory, id: &str) -> bool {
+    history.remove_message(id)
+}
+
+#[test]
+fn test_removal_reaches_custom_histories() {
+    let mut stored = StoredHistory::default();
+    stored.push(ChatMessage::user("Delete me".to_string()));
+    let id = stored.history.entries()[0].id.clone();
+
+    assert!(forget(&mut stored, &id));
+    assert_eq!(stored.deleted, [id]);
+    assert!(stored.messages().is_empty());
+}
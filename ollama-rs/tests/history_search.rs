@@ -0,0 +1,76 @@
+use ollama_rs::{
+    generation::chat::ChatMessage,
+    history::{ChatHistory, SemanticHistorySearch},
+    test_utils::{MockOllama, MockResponse},
+};
+
+fn history() -> Vec<ChatMessage> {
+    vec![
+        ChatMessage::user("I moved to Lisbon last year.".to_string()),
+        ChatMessage::assistant("Lisbon is lovely in the spring!".to_string()),
+        ChatMessage::user("What should I cook tonight?".to_string()),
+        ChatMessage::assistant("How about a Lisbon classic, bacalhau?".to_string()),
+    ]
+}
+
+#[test]
+fn test_keyword_search() {
+    let history = history();
+
+    let matches = history.search("lisbon spring");
+    let indices: Vec<_> = matches.iter().map(|m| m.index).collect();
+    // Both words first, then the most recent messages
+    assert_eq!(indices, [1, 3, 0]);
+    assert_eq!(matches[0].score, 1.0);
+    assert_eq!(matches[1].score, 0.5);
+
+    assert!(history.search("Paris").is_empty());
+    assert!(history.search("  ").is_empty());
+}
+
+/// A toy embedding: whether the text is about places or about food.
+fn embed(text: &str) -> Vec<f32> {
+    let text = text.to_lowercase();
+    if text.contains("live") || text.contains("moved") {
+        vec![1.0, 0.0]
+    } else if text.contains("cook") || text.contains("bacalhau") {
+        vec![0.0, 1.0]
+    } else {
+        vec![0.6, 0.8]
+    }
+}
+
+#[tokio::test]
+async fn test_semantic_search() {
+    let server = MockOllama::start().await;
+    server.mock_with("/api/embed", |request| {
+        let inputs = request.json()["input"].as_array().unwrap().clone();
+        MockResponse::embeddings(
+            inputs
+                .iter()
+                .map(|input| embed(input.as_str().unwrap()))
+                .collect(),
+        )
+    });
+
+    let search = SemanticHistorySearch::new(server.client(), "nomic-embed-text")
+        .limit(2)
+        .threshold(0.7);
+    let history = history();
+
+    let matches = search
+        .search(&history, "Where does the user live?")
+        .await
+        .unwrap();
+    assert_eq!(matches[0].index, 0);
+    assert_eq!(matches.len(), 1);
+
+    let matches = search.search(&history, "Any dinner ideas?").await.unwrap();
+    assert_eq!(matches.len(), 2);
+    assert_eq!(matches[0].message.content, history[1].content);
+
+    // The messages are embedded once
+    let requests = server.requests();
+    assert_eq!(requests[0].json()["input"].as_array().unwrap().len(), 5);
+    assert_eq!(requests[1].json()["input"].as_array().unwrap().len(), 1);
+}
@@ -0,0 +1,54 @@
+use std::time::Duration;
+
+use ollama_rs::{
+    models::ByteSize,
+    test_utils::{MockOllama, MockResponse},
+};
+use serde_json::json;
+
+#[tokio::test]
+async fn test_list_running_models() {
+    let server = MockOllama::start().await;
+    server.mock(
+        "/api/ps",
+        MockResponse::json(json!({
+            "models": [
+                {
+                    "name": "llama3.2:latest",
+                    "model": "llama3.2:latest",
+                    "size": 4_700_000_000u64,
+                    "digest": "a80c4f17",
+                    "details": { "family": "llama", "quantization_level": "Q4_K_M" },
+                    "expires_at": "2999-01-01T00:00:00.123456789+02:00",
+                    "size_vram": 2_256_000_000u64
+                },
+                {
+                    "name": "qwen2.5:0.5b",
+                    "size": 500_000_000,
+                    "expires_at": "2024-01-01T00:00:00Z",
+                    "size_vram": 500_000_000
+                }
+            ]
+        })),
+    );
+
+    let models = server.client().list_running_models().await.unwrap();
+
+    assert_eq!(models[0].size.to_string(), "4.7 GB");
+    assert_eq!(models[0].size.to_binary_string(), "4.4 GiB");
+    assert_eq!(models[0].size_vram.bytes(), 2_256_000_000);
+    assert_eq!(models[0].processor(), "52%/48% CPU/GPU");
+    assert_eq!(models[0].details.family, "llama");
+    assert!(models[0].expires_in().unwrap() > Duration::from_secs(3600));
+
+    assert_eq!(models[1].processor(), "100% GPU");
+    assert_eq!(models[1].expires_in(), None);
+}
+
+#[test]
+fn test_byte_size_display() {
+    assert_eq!(ByteSize(512).to_string(), "512 B");
+    assert_eq!(ByteSize(1_500).to_string(), "1.5 KB");
+    assert_eq!(ByteSize(1_536).to_binary_string(), "1.5 KiB");
+    assert_eq!(ByteSize(0).to_string(), "0 B");
+}
@@ -0,0 +1,82 @@
+use std::time::SystemTime;
+
+use ollama_rs::{
+    generation::chat::{request::ChatMessageRequest, ChatMessage},
+    history::{ChatHistory, HistoryEntry, MessageHistory},
+    test_utils::{MockOllama, MockResponse},
+    Timestamp,
+};
+
+#[test]
+fn test_message_history_metadata() {
+    let before = SystemTime::now();
+    let mut history = MessageHistory::new();
+    let first = history.push_message(ChatMessage::user("I moved to Lisbon.".to_string()));
+    let second = history.push_entry(
+        HistoryEntry::new(ChatMessage::assistant("Welcome!".to_string())).tag("greeting"),
+    );
+
+    assert_ne!(first, second);
+    assert!(first.starts_with("msg_"));
+    assert!(SystemTime::from(history.get(&first).unwrap().created_at.clone()) >= before);
+
+    assert!(history.add_tag(&first, "profile"));
+    assert!(history.add_tag(&first, "profile"));
+    assert_eq!(history.get(&first).unwrap().tags, ["profile"]);
+    assert!(!history.add_tag("msg_unknown", "profile"));
+
+    assert!(history.edit(&second, "Welcome to Lisbon!"));
+    assert_eq!(history.messages()[1].content, "Welcome to Lisbon!");
+
+    assert!(history.remove_tag(&second, "greeting"));
+    assert_eq!(history.tagged("greeting").count(), 0);
+    assert_eq!(history.tagged("profile").next().unwrap().id, first);
+}
+
+#[test]
+fn test_message_history_serialization() {
+    let mut history: MessageHistory = [ChatMessage::user("Hi".to_string())].into_iter().collect();
+    let id = history.entries()[0].id.clone();
+    history.add_tag(&id, "pinned");
+
+    let json = serde_json::to_value(&history).unwrap();
+    assert_eq!(json[0]["id"], id.as_str());
+    assert_eq!(json[0]["role"], "user");
+    assert_eq!(json[0]["content"], "Hi");
+    assert_eq!(json[0]["tags"][0], "pinned");
+    assert!(Timestamp::parse(json[0]["created_at"].as_str().unwrap()).is_some());
+
+    let restored: MessageHistory = serde_json::from_value(json).unwrap();
+    assert_eq!(restored.entries()[0].id, id);
+    assert_eq!(
+        restored.entries()[0].created_at,
+        history.entries()[0].created_at
+    );
+    assert!(restored.entries()[0].has_tag("pinned"));
+}
+
+#[tokio::test]
+async fn test_message_history_sends_messages_only() {
+    let server = MockOllama::start().await;
+    server.mock("/api/chat", MockResponse::chat("Hello!"));
+
+    let mut history = MessageHistory::new();
+    server
+        .client()
+        .send_chat_messages_with_history(
+            &mut history,
+            ChatMessageRequest::new(
+                "llama3.2".to_string(),
+                vec![ChatMessage::user("Hi".to_string())],
+            ),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(history.len(), 2);
+    assert_eq!(history.entries()[1].message.content, "Hello!");
+
+    let request = server.requests()[0].json();
+    assert!(request["messages"][0].get("id").is_none());
+    assert!(request["messages"][0].get("created_at").is_none());
+}
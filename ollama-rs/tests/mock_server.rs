@@ -0,0 +1,195 @@
+use std::time::{Duration, Instant};
+
+use ollama_rs::{
+    coordinator::Coordinator,
+    error::OllamaError,
+    generation::{
+        chat::{request::ChatMessageRequest, ChatMessage},
+        completion::request::GenerationRequest,
+        tools::implementations::Calculator,
+    },
+    test_utils::{MockOllama, MockResponse},
+    Ollama,
+};
+use serde_json::json;
+use tokio_stream::StreamExt;
+
+#[tokio::test]
+async fn test_canned_generation() {
+    let server = MockOllama::start().await;
+    server.mock(
+        "/api/generate",
+        MockResponse::generation("Rayleigh scattering."),
+    );
+
+    let res = server
+        .client()
+        .generate(GenerationRequest::new(
+            "llama2".to_string(),
+            "Why is the sky blue?",
+        ))
+        .await
+        .unwrap();
+
+    assert_eq!(res.response, "Rayleigh scattering.");
+    assert!(res.done);
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].method, "POST");
+    assert_eq!(requests[0].path, "/api/generate");
+    assert_eq!(requests[0].json()["prompt"], "Why is the sky blue?");
+}
+
+#[tokio::test]
+async fn test_scripted_chat_stream() {
+    let server = MockOllama::start().await;
+    server.mock(
+        "/api/chat",
+        MockResponse::chat_stream(&["Hello", ",", " world"]),
+    );
+
+    let mut stream = server
+        .client()
+        .send_chat_messages_stream(ChatMessageRequest::new(
+            "llama2".to_string(),
+            vec![ChatMessage::user("Hi".to_string())],
+        ))
+        .await
+        .unwrap();
+
+    let mut content = String::new();
+    let mut done = false;
+    while let Some(res) = stream.next().await {
+        let res = res.unwrap();
+        content.push_str(&res.message.content);
+        done = res.done;
+    }
+
+    assert_eq!(content, "Hello, world");
+    assert!(done);
+}
+
+#[tokio::test]
+async fn test_tool_call_scenario() {
+    let server = MockOllama::start().await;
+    server
+        .mock_once(
+            "/api/chat",
+            MockResponse::tool_call("calculator", json!({ "expression": "2 + 2" })),
+        )
+        .mock("/api/chat", MockResponse::chat("2 + 2 = 4"));
+
+    let mut coordinator =
+        Coordinator::new(server.client(), "llama2".to_string(), vec![]).add_tool(Calculator {});
+
+    let res = coordinator
+        .chat(vec![ChatMessage::user("What is 2 + 2?".to_string())])
+        .await
+        .unwrap();
+
+    assert_eq!(res.message.content, "2 + 2 = 4");
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 2);
+    // The result of the tool is sent back to the model
+    let messages = requests[1].json()["messages"].clone();
+    assert_eq!(messages.as_array().unwrap().last().unwrap()["role"], "tool");
+    assert_eq!(messages.as_array().unwrap().last().unwrap()["content"], "4");
+}
+
+#[tokio::test]
+async fn test_streamed_tool_call_scenario() {
+    let chunk = |message: serde_json::Value, done: bool| json!({ "model": "llama2", "created_at": "2024-01-01T00:00:00Z", "message": message, "done": done });
+
+    let server = MockOllama::start().await;
+    server
+        .mock_once(
+            "/api/chat",
+            MockResponse::stream([
+                chunk(json!({ "role": "assistant", "content": "Computing" }), false),
+                chunk(
+                    json!({
+                        "role": "assistant",
+                        "content": "",
+                        "tool_calls": [{ "function": { "name": "calculator", "arguments": { "expression": "2 + 2" } } }]
+                    }),
+                    false,
+                ),
+                chunk(json!({ "role": "assistant", "content": "" }), true),
+            ]),
+        )
+        .mock("/api/chat", MockResponse::chat_stream(&["2 + 2", " = 4"]));
+
+    let mut coordinator =
+        Coordinator::new(server.client(), "llama2".to_string(), vec![]).add_tool(Calculator {});
+
+    let chunks: Vec<_> = coordinator
+        .chat_stream(vec![ChatMessage::user("What is 2 + 2?".to_string())])
+        .map(|res| res.unwrap())
+        .collect()
+        .await;
+
+    let content: String = chunks.iter().map(|c| c.message.content.as_str()).collect();
+    assert_eq!(content, "Computing2 + 2 = 4");
+    assert_eq!(chunks.iter().filter(|c| c.done).count(), 2);
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0].json()["stream"], true);
+    let messages = requests[1].json()["messages"].clone();
+    let messages = messages.as_array().unwrap();
+    assert_eq!(messages[1]["content"], "Computing");
+    assert_eq!(
+        messages[1]["tool_calls"][0]["function"]["name"],
+        "calculator"
+    );
+    assert_eq!(messages[2]["role"], "tool");
+    assert_eq!(messages[2]["content"], "4");
+}
+
+#[tokio::test]
+async fn test_latency_injection() {
+    let server = MockOllama::start().await;
+    server.mock(
+        "/api/generate",
+        MockResponse::generation("Late").delay(Duration::from_millis(500)),
+    );
+
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_millis(100))
+        .build()
+        .unwrap();
+    let url = server.url();
+    let ollama = Ollama::new_with_client(
+        format!("http://{}", url.host_str().unwrap()),
+        url.port().unwrap(),
+        client,
+    );
+
+    let start = Instant::now();
+    let err = ollama
+        .generate(GenerationRequest::new("llama2".to_string(), "Hi"))
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, OllamaError::Timeout { .. }), "{err:?}");
+    assert!(err.request_id().is_some());
+    assert!(start.elapsed() < Duration::from_millis(500));
+}
+
+#[tokio::test]
+async fn test_unmocked_path_returns_404() {
+    let server = MockOllama::start().await;
+
+    let err = server
+        .client()
+        .generate(GenerationRequest::new("llama2".to_string(), "Hi"))
+        .await
+        .unwrap_err();
+
+    match err {
+        OllamaError::HttpError { status, .. } => assert_eq!(status.as_u16(), 404),
+        e => panic!("unexpected error: {e:?}"),
+    }
+}
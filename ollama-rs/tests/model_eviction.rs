@@ -0,0 +1,78 @@
+use ollama_rs::{
+    models::{eviction::ModelEvictionManager, ByteSize},
+    test_utils::{MockOllama, MockResponse},
+};
+use serde_json::json;
+
+fn running(name: &str, size_vram: u64, expires_at: &str) -> serde_json::Value {
+    json!({ "name": name, "size": size_vram, "size_vram": size_vram, "expires_at": expires_at })
+}
+
+fn ps() -> MockResponse {
+    MockResponse::json(json!({
+        "models": [
+            running("llama3.2:latest", 4_000, "2024-01-01T00:10:00Z"),
+            running("qwen2.5:7b", 6_000, "2024-01-01T00:20:00Z"),
+            running("mistral:latest", 5_000, "2024-01-01T00:30:00Z"),
+            running("nomic-embed-text:latest", 1_000, "2024-01-01T00:05:00Z"),
+        ]
+    }))
+}
+
+fn unloaded(server: &MockOllama) -> Vec<String> {
+    server
+        .requests()
+        .iter()
+        .filter(|request| request.path == "/api/generate")
+        .map(|request| {
+            let body = request.json();
+            assert_eq!(body["keep_alive"], 0);
+            body["model"].as_str().unwrap().to_string()
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_evicts_least_recently_used() {
+    let server = MockOllama::start().await;
+    server
+        .mock("/api/ps", ps())
+        .mock("/api/generate", MockResponse::generation(""));
+
+    let manager = ModelEvictionManager::new(server.client(), ByteSize(10_000));
+    manager.touch("qwen2.5:7b");
+    manager.touch("mistral");
+    manager.touch("nomic-embed-text");
+
+    // 16 000 bytes are loaded, and llama3.2 is about to be used
+    let evicted = manager.use_model("llama3.2").await.unwrap();
+    assert_eq!(evicted, ["qwen2.5:7b"]);
+    assert_eq!(unloaded(&server), ["qwen2.5:7b"]);
+}
+
+#[tokio::test]
+async fn test_evicts_untracked_models_first() {
+    let server = MockOllama::start().await;
+    server
+        .mock("/api/ps", ps())
+        .mock("/api/generate", MockResponse::generation(""));
+
+    let manager = ModelEvictionManager::new(server.client(), ByteSize(7_000));
+    manager.touch("mistral");
+
+    let evicted = manager.enforce().await.unwrap();
+    assert_eq!(
+        evicted,
+        ["nomic-embed-text:latest", "llama3.2:latest", "qwen2.5:7b"]
+    );
+}
+
+#[tokio::test]
+async fn test_within_budget() {
+    let server = MockOllama::start().await;
+    server.mock("/api/ps", ps());
+
+    let manager = ModelEvictionManager::new(server.client(), ByteSize(20_000));
+    assert!(manager.enforce().await.unwrap().is_empty());
+    assert!(unloaded(&server).is_empty());
+}
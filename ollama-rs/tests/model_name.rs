@@ -0,0 +1,85 @@
+use ollama_rs::{
+    generation::completion::request::GenerationRequest,
+    models::ModelName,
+    test_utils::{MockOllama, MockResponse},
+};
+
+#[test]
+fn test_parse_forms() {
+    let name: ModelName = "llama3.2".parse().unwrap();
+    assert_eq!(
+        (name.registry(), name.namespace(), name.name(), name.tag()),
+        (None, None, "llama3.2", None)
+    );
+
+    let name: ModelName = "jmorgan/mymodel:7b-q4_K_M".parse().unwrap();
+    assert_eq!(
+        (name.registry(), name.namespace(), name.name(), name.tag()),
+        (None, Some("jmorgan"), "mymodel", Some("7b-q4_K_M"))
+    );
+
+    let name: ModelName = "localhost:5000/team/model".parse().unwrap();
+    assert_eq!(
+        (name.registry(), name.namespace(), name.name(), name.tag()),
+        (Some("localhost:5000"), Some("team"), "model", None)
+    );
+}
+
+#[test]
+fn test_invalid_names() {
+    for name in [
+        "",
+        "llama3.2:",
+        "-llama",
+        "llama 3",
+        "a/b/c/d",
+        "llama3.2:1b:q4",
+        "host:port/ns/model",
+    ] {
+        assert!(name.parse::<ModelName>().is_err(), "{name:?}");
+    }
+}
+
+#[test]
+fn test_full_and_short_names() {
+    let name: ModelName = "llama3.2".parse().unwrap();
+    assert_eq!(
+        name.full_name(),
+        "registry.ollama.ai/library/llama3.2:latest"
+    );
+    assert_eq!(name.short_name(), "llama3.2:latest");
+
+    let name: ModelName = "registry.ollama.ai/library/llama3.2:1b".parse().unwrap();
+    assert_eq!(name.short_name(), "llama3.2:1b");
+
+    let name: ModelName = "registry.example.com/team/model".parse().unwrap();
+    assert_eq!(name.short_name(), "registry.example.com/team/model:latest");
+}
+
+#[test]
+fn test_with_tag() {
+    let name: ModelName = "jmorgan/mymodel:7b".parse().unwrap();
+    assert_eq!(name.with_tag("13b").unwrap(), "jmorgan/mymodel:13b");
+
+    let name: ModelName = "localhost:5000/team/model".parse().unwrap();
+    assert_eq!(name.with_tag("v2").unwrap(), "localhost:5000/team/model:v2");
+}
+
+#[test]
+fn test_serde() {
+    let name: ModelName = serde_json::from_str(r#""llama3.2:1b""#).unwrap();
+    assert_eq!(serde_json::to_string(&name).unwrap(), r#""llama3.2:1b""#);
+    assert!(serde_json::from_str::<ModelName>(r#""llama 3""#).is_err());
+}
+
+#[tokio::test]
+async fn test_model_name_in_requests() {
+    let server = MockOllama::start().await;
+    server.mock("/api/generate", MockResponse::generation("Hi!"));
+
+    let model: ModelName = "llama3.2:1b".parse().unwrap();
+    let request = GenerationRequest::new(model.into(), "Hi");
+    server.client().generate(request).await.unwrap();
+
+    assert_eq!(server.requests()[0].json()["model"], "llama3.2:1b");
+}
@@ -0,0 +1,86 @@
+use ollama_rs::{
+    coordinator::Coordinator,
+    generation::{
+        chat::{request::ChatMessageRequest, ChatMessage},
+        images::Image,
+        tools::implementations::Calculator,
+    },
+    router::{LatencyTier, ModelRouter, RequestTraits, RoutedModel},
+    test_utils::{MockOllama, MockResponse},
+};
+use serde_json::json;
+
+fn router() -> ModelRouter {
+    ModelRouter::new()
+        .model(RoutedModel::new("small").latency(LatencyTier::Interactive))
+        .model(RoutedModel::new("large").tools(true).context_length(32_768))
+        .model(
+            RoutedModel::new("vision")
+                .vision(true)
+                .tools(true)
+                .latency(LatencyTier::Batch),
+        )
+}
+
+#[test]
+fn test_route() {
+    let router = router();
+    let user = |content: &str| ChatMessage::user(content.to_string());
+
+    let request = ChatMessageRequest::new(String::new(), vec![user("Hi")]);
+    assert_eq!(router.route(&request), Some("small"));
+
+    // Too long for the context of the small model
+    let request = ChatMessageRequest::new(String::new(), vec![user(&"a".repeat(10_000))]);
+    assert_eq!(router.route(&request), Some("large"));
+
+    let request = ChatMessageRequest::new(String::new(), vec![user("Hi")]).num_ctx(65_536);
+    assert_eq!(router.route(&request), None);
+
+    let request = ChatMessageRequest::new(
+        String::new(),
+        vec![user("What is this?").with_images([Image::from_base64("aGVsbG8=")])],
+    );
+    assert_eq!(router.route(&request), None);
+    assert_eq!(
+        router.route_traits(&RequestTraits::of(&request).latency(LatencyTier::Batch)),
+        Some("vision")
+    );
+
+    let router = router.fallback("default");
+    assert_eq!(router.route(&request), Some("default"));
+}
+
+#[tokio::test]
+async fn test_coordinator_routes_turns() {
+    let server = MockOllama::start().await;
+    server
+        .mock_once(
+            "/api/chat",
+            MockResponse::tool_call("calculator", json!({ "expression": "2 + 2" })),
+        )
+        .mock("/api/chat", MockResponse::chat("2 + 2 = 4"));
+
+    let mut coordinator = Coordinator::new(server.client(), "default".to_string(), vec![])
+        .add_tool(Calculator {})
+        .router(router());
+
+    coordinator
+        .chat(vec![ChatMessage::user("What is 2 + 2?".to_string())])
+        .await
+        .unwrap();
+
+    // Both turns offer tools, which the small model can't call
+    let requests = server.requests();
+    assert_eq!(requests[0].json()["model"], "large");
+    assert_eq!(requests[1].json()["model"], "large");
+
+    let mut coordinator = Coordinator::new(server.client(), "default".to_string(), vec![])
+        .router(router())
+        .latency(LatencyTier::Interactive);
+    coordinator
+        .chat(vec![ChatMessage::user("Hi".to_string())])
+        .await
+        .unwrap();
+    assert_eq!(server.requests()[2].json()["model"], "small");
+}
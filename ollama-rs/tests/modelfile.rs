@@ -0,0 +1,174 @@
+use ollama_rs::{
+    generation::chat::MessageRole,
+    models::modelfile::Modelfile,
+    test_utils::{MockOllama, MockResponse},
+};
+use serde_json::json;
+
+const BLOB: &str = "sha256-6a0746a1ec1aef3e7ec53868f220ff6e389f6f8ef87a01d77c96807de94ca2aa";
+
+const MODELFILE: &str = r#"# Modelfile generated by "ollama show"
+FROM /root/.ollama/models/blobs/sha256-6a0746a1ec1aef3e7ec53868f220ff6e389f6f8ef87a01d77c96807de94ca2aa
+TEMPLATE """{{ if .System }}<|system|>
+{{ .System }}{{ end }}<|user|>
+{{ .Prompt }}"""
+system "You are \"helpful\"."
+PARAMETER stop <|system|>
+PARAMETER stop "<|user|>"
+PARAMETER temperature 0.7
+PARAMETER num_ctx 4096
+LICENSE """MIT License
+
+Copyright"""
+MESSAGE user Who are you?
+MESSAGE assistant "I'm a model."
+"#;
+
+#[test]
+fn test_parse_modelfile() {
+    let modelfile: Modelfile = MODELFILE.parse().unwrap();
+
+    assert_eq!(
+        modelfile.from.as_deref(),
+        Some(format!("/root/.ollama/models/blobs/{BLOB}").as_str())
+    );
+    assert_eq!(
+        modelfile.template.as_deref(),
+        Some("{{ if .System }}<|system|>\n{{ .System }}{{ end }}<|user|>\n{{ .Prompt }}")
+    );
+    assert_eq!(modelfile.system.as_deref(), Some("You are \"helpful\"."));
+    assert_eq!(modelfile.parameter("temperature"), Some("0.7"));
+    assert_eq!(modelfile.parameter("stop"), Some("<|system|>"));
+    assert_eq!(modelfile.parameters.len(), 4);
+    assert_eq!(modelfile.licenses, ["MIT License\n\nCopyright"]);
+    assert_eq!(modelfile.messages.len(), 2);
+    assert_eq!(modelfile.messages[0].role, MessageRole::User);
+    assert_eq!(modelfile.messages[0].content, "Who are you?");
+    assert_eq!(modelfile.messages[1].content, "I'm a model.");
+}
+
+#[test]
+fn test_modelfile_round_trip() {
+    let mut modelfile: Modelfile = MODELFILE.parse().unwrap();
+    modelfile.set_parameter("temperature", "0.3");
+    modelfile.adapters.push("./lora.gguf".into());
+
+    let written = modelfile.to_string();
+    assert!(written.contains("PARAMETER temperature 0.3\n"));
+    assert!(written.contains("ADAPTER ./lora.gguf\n"));
+
+    let reparsed: Modelfile = written.parse().unwrap();
+    assert_eq!(reparsed.from, modelfile.from);
+    assert_eq!(reparsed.template, modelfile.template);
+    assert_eq!(reparsed.system, modelfile.system);
+    assert_eq!(reparsed.parameters, modelfile.parameters);
+    assert_eq!(reparsed.licenses, modelfile.licenses);
+    assert_eq!(reparsed.adapters, modelfile.adapters);
+    assert_eq!(reparsed.messages.len(), 2);
+    assert_eq!(reparsed.messages[1].content, "I'm a model.");
+}
+
+#[test]
+fn test_quoted_values_round_trip() {
+    let values = [
+        "Say \"hi\".\nThen say \"bye\"",
+        "\"Quoted\"\nacross lines",
+        "Ends with \"\"\"\nand more",
+        "Windows path:\nC:\\models\\",
+        " leading space",
+        "\"",
+    ];
+    for value in values {
+        let modelfile = Modelfile {
+            system: Some(value.to_string()),
+            ..Default::default()
+        };
+        let reparsed: Modelfile = modelfile.to_string().parse().unwrap();
+        assert_eq!(reparsed.system.as_deref(), Some(value), "{modelfile}");
+    }
+}
+
+#[test]
+fn test_invalid_modelfile() {
+    let err = "FROM llama3.2\nTEMPERATURE 0.3"
+        .parse::<Modelfile>()
+        .unwrap_err();
+    assert!(err.to_string().contains("line 2"), "{err}");
+
+    assert!("SYSTEM \"\"\"unterminated".parse::<Modelfile>().is_err());
+    assert!("MESSAGE robot hi".parse::<Modelfile>().is_err());
+    assert!("SYSTEM \"a\" b".parse::<Modelfile>().is_err());
+}
+
+#[test]
+fn test_modelfile_options() {
+    let modelfile: Modelfile = MODELFILE.parse().unwrap();
+    let options = serde_json::to_value(modelfile.options().unwrap()).unwrap();
+    assert_eq!(options["stop"], json!(["<|system|>", "<|user|>"]));
+    assert_eq!(options["num_ctx"], 4096);
+    assert!((options["temperature"].as_f64().unwrap() - 0.7).abs() < 1e-6);
+}
+
+#[tokio::test]
+async fn test_derive_model_from_show_info() {
+    // With the `modelfile` feature, the response is parsed by the `modelfile` crate, which
+    // doesn't support escaped quotes
+    let (text, system) = if cfg!(feature = "modelfile") {
+        (
+            MODELFILE.replace(r#""You are \"helpful\".""#, r#""You are helpful.""#),
+            "You are helpful.",
+        )
+    } else {
+        (MODELFILE.to_string(), "You are \"helpful\".")
+    };
+    let server = MockOllama::start().await;
+    server.mock(
+        "/api/show",
+        MockResponse::json(json!({ "modelfile": text })),
+    );
+    server.mock(
+        "/api/create",
+        MockResponse::json(json!({ "status": "success" })),
+    );
+    let ollama = server.client();
+
+    let info = ollama.show_model_info("mock".into()).await.unwrap();
+    let mut modelfile = info.parse_modelfile().unwrap();
+    modelfile.set_parameter("temperature", "0.3");
+    ollama
+        .create_model(modelfile.create_request("precise".into()).unwrap())
+        .await
+        .unwrap();
+
+    let body = server.requests()[1].json();
+    assert_eq!(body["model"], "precise");
+    assert_eq!(
+        body["files"],
+        json!({ BLOB: format!("sha256:{}", &BLOB["sha256-".len()..]) })
+    );
+    assert!(body.get("from").is_none());
+    assert!((body["parameters"]["temperature"].as_f64().unwrap() - 0.3).abs() < 1e-6);
+    assert_eq!(body["system"], system);
+    assert_eq!(body["messages"][0]["content"], "Who are you?");
+
+    modelfile.from = Some("./model.gguf".into());
+    assert!(modelfile.create_request("local".into()).is_err());
+}
+
+#[cfg(feature = "modelfile")]
+#[test]
+fn test_convert_modelfile_crate_type() {
+    let text = MODELFILE.replace(r#""You are \"helpful\".""#, r#""You are helpful.""#);
+    let parsed: modelfile::Modelfile = text.parse().unwrap();
+    let modelfile = Modelfile::from(parsed);
+
+    let expected: Modelfile = text.parse().unwrap();
+    assert_eq!(modelfile.from, expected.from);
+    assert_eq!(modelfile.template, expected.template);
+    assert_eq!(modelfile.system, expected.system);
+    assert_eq!(modelfile.licenses, expected.licenses);
+    assert_eq!(modelfile.parameters, expected.parameters);
+    assert_eq!(modelfile.messages.len(), 2);
+    assert_eq!(modelfile.messages[0].role, MessageRole::User);
+    assert_eq!(modelfile.messages[1].content, "I'm a model.");
+}
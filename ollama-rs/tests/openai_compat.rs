@@ -0,0 +1,282 @@
+use ollama_rs::{
+    error::OllamaError,
+    generation::{
+        chat::{request::ChatMessageRequest, ChatMessage},
+        done_reason::DoneReason,
+        parameters::FormatType,
+        tools::{ToolCall, ToolCallFunction, ToolInfo},
+    },
+    models::ModelOptions,
+    test_utils::{MockOllama, MockResponse},
+};
+use serde_json::json;
+use tokio_stream::StreamExt;
+
+const PATH: &str = "/v1/chat/completions";
+
+#[tokio::test]
+async fn test_openai_compatible_chat() {
+    let server = MockOllama::start().await;
+    server.mock(
+        PATH,
+        MockResponse::json(json!({
+            "id": "chatcmpl-1",
+            "object": "chat.completion",
+            "created": 1704067200,
+            "model": "llama3.2",
+            "choices": [{
+                "index": 0,
+                "message": { "role": "assistant", "content": "Paris" },
+                "finish_reason": "stop"
+            }],
+            "usage": { "prompt_tokens": 12, "completion_tokens": 2, "total_tokens": 14 }
+        })),
+    );
+
+    let mut ollama = server.client();
+    ollama.set_openai_compatible(true);
+
+    let res = ollama
+        .send_chat_messages(
+            ChatMessageRequest::new(
+                "llama3.2".to_string(),
+                vec![ChatMessage::user("Capital of France?".to_string())],
+            )
+            .options(ModelOptions::default().temperature(0.5).num_predict(64))
+            .format(FormatType::Json),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.message.content, "Paris");
+    assert!(res.done);
+    assert_eq!(res.done_reason, Some(DoneReason::Stop));
+    assert_eq!(res.created_at.unix_timestamp(), 1704067200);
+    let usage = res.usage().unwrap();
+    assert_eq!(usage.prompt_eval_count, 12);
+    assert_eq!(usage.eval_count, 2);
+
+    let body = server.requests()[0].json();
+    assert_eq!(body["model"], "llama3.2");
+    assert_eq!(body["stream"], false);
+    assert_eq!(body["messages"][0]["content"], "Capital of France?");
+    assert_eq!(body["temperature"], 0.5);
+    assert_eq!(body["max_tokens"], 64);
+    assert_eq!(body["response_format"]["type"], "json_object");
+}
+
+#[tokio::test]
+async fn test_openai_compatible_tool_calls() {
+    let server = MockOllama::start().await;
+    server.mock(
+        PATH,
+        MockResponse::json(json!({
+            "created": 1704067200,
+            "model": "llama3.2",
+            "choices": [{
+                "message": {
+                    "role": "assistant",
+                    "content": null,
+                    "tool_calls": [{
+                        "id": "call_1",
+                        "type": "function",
+                        "function": { "name": "get_weather", "arguments": "{\"city\":\"Paris\"}" }
+                    }]
+                },
+                "finish_reason": "tool_calls"
+            }]
+        })),
+    );
+
+    let mut ollama = server.client();
+    ollama.set_openai_compatible(true);
+
+    let mut call = ChatMessage::assistant(String::new());
+    call.tool_calls.push(ToolCall {
+        id: None,
+        function: ToolCallFunction {
+            name: "get_weather".to_string(),
+            arguments: json!({ "city": "Lyon" }),
+        },
+    });
+    let res = ollama
+        .send_chat_messages(ChatMessageRequest::new(
+            "llama3.2".to_string(),
+            vec![
+                ChatMessage::user("Weather in Lyon, then Paris?".to_string()),
+                call,
+                ChatMessage::tool("Sunny".to_string()),
+            ],
+        ))
+        .await
+        .unwrap();
+
+    assert_eq!(res.message.content, "");
+    assert_eq!(res.message.tool_calls.len(), 1);
+    assert_eq!(res.message.tool_calls[0].function.name, "get_weather");
+    assert_eq!(
+        res.message.tool_calls[0].function.arguments,
+        json!({ "city": "Paris" })
+    );
+
+    let messages = &server.requests()[0].json()["messages"];
+    let id = &messages[1]["tool_calls"][0]["id"];
+    assert_eq!(
+        messages[1]["tool_calls"][0]["function"]["arguments"],
+        "{\"city\":\"Lyon\"}"
+    );
+    assert_eq!(&messages[2]["tool_call_id"], id);
+}
+
+#[tokio::test]
+async fn test_openai_compatible_stream() {
+    let server = MockOllama::start().await;
+    let chunk = |delta: serde_json::Value, finish_reason: serde_json::Value| {
+        format!(
+            "data: {}\n\n",
+            json!({
+                "model": "llama3.2",
+                "created": 1704067200,
+                "choices": [{ "index": 0, "delta": delta, "finish_reason": finish_reason }]
+            })
+        )
+    };
+    server.mock(
+        PATH,
+        MockResponse::frames([
+            chunk(
+                json!({ "role": "assistant", "content": "Hel" }),
+                json!(null),
+            ),
+            // An event split across frames
+            chunk(json!({ "content": "lo" }), json!(null))[..20].to_string(),
+            chunk(json!({ "content": "lo" }), json!(null))[20..].to_string(),
+            chunk(json!({}), json!("length")),
+            format!(
+                "data: {}\n\n",
+                json!({
+                    "model": "llama3.2",
+                    "created": 1704067200,
+                    "choices": [],
+                    "usage": { "prompt_tokens": 3, "completion_tokens": 2 }
+                })
+            ),
+            "data: [DONE]\n\n".to_string(),
+        ]),
+    );
+
+    let mut ollama = server.client();
+    ollama.set_openai_compatible(true);
+
+    let stream = ollama
+        .send_chat_messages_stream(ChatMessageRequest::new(
+            "llama3.2".to_string(),
+            vec![ChatMessage::user("Hi".to_string())],
+        ))
+        .await
+        .unwrap();
+    let responses: Vec<_> = stream.collect::<Result<_, _>>().await.unwrap();
+
+    let text: String = responses
+        .iter()
+        .map(|r| r.message.content.as_str())
+        .collect();
+    assert_eq!(text, "Hello");
+
+    let last = responses.last().unwrap();
+    assert!(last.done);
+    assert!(last.done_reason.as_ref().unwrap().is_truncated());
+    assert_eq!(last.usage().unwrap().eval_count, 2);
+
+    let body = server.requests()[0].json();
+    assert_eq!(body["stream"], true);
+    assert_eq!(body["stream_options"]["include_usage"], true);
+}
+
+#[tokio::test]
+async fn test_openai_compatible_stream_closed_early() {
+    let server = MockOllama::start().await;
+    let chunk = json!({
+        "model": "llama3.2",
+        "created": 1704067200,
+        "choices": [{ "index": 0, "delta": { "content": "Hel" }, "finish_reason": null }]
+    });
+    server.mock(PATH, MockResponse::frames([format!("data: {chunk}\n\n")]));
+
+    let mut ollama = server.client();
+    ollama.set_openai_compatible(true);
+
+    let stream = ollama
+        .send_chat_messages_stream(ChatMessageRequest::new(
+            "llama3.2".to_string(),
+            vec![ChatMessage::user("Hi".to_string())],
+        ))
+        .await
+        .unwrap();
+    let responses: Vec<_> = stream.collect().await;
+
+    assert_eq!(responses.len(), 2);
+    assert!(!responses[0].as_ref().unwrap().done);
+    match &responses[1] {
+        Err(OllamaError::StreamInterrupted {
+            source: None,
+            partial_response,
+            ..
+        }) => assert_eq!(partial_response, "Hel"),
+        other => panic!("expected an interrupted stream, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_openai_compatible_error() {
+    let server = MockOllama::start().await;
+    server.mock(
+        PATH,
+        MockResponse::json(json!({
+            "error": { "message": "model is required", "type": "invalid_request_error" }
+        }))
+        .status(400),
+    );
+
+    let mut ollama = server.client();
+    ollama.set_openai_compatible(true);
+
+    let err = ollama
+        .send_chat_messages(ChatMessageRequest::new(String::new(), vec![]))
+        .await
+        .unwrap_err();
+
+    match err {
+        OllamaError::HttpError { message, .. } => assert_eq!(message, "model is required"),
+        err => panic!("unexpected error: {err:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_openai_compatible_tools() {
+    let server = MockOllama::start().await;
+    server.mock(PATH, MockResponse::json(json!({
+        "model": "llama3.2",
+        "choices": [{ "message": { "role": "assistant", "content": "Hi" }, "finish_reason": "stop" }]
+    })));
+
+    let mut ollama = server.client();
+    ollama.set_openai_compatible(true);
+
+    let tool = ToolInfo::from_value(
+        "get_weather",
+        "Get the weather of a city",
+        json!({ "type": "object", "properties": { "city": { "type": "string" } } }),
+    )
+    .unwrap();
+    ollama
+        .send_chat_messages(
+            ChatMessageRequest::new("llama3.2".to_string(), vec![]).tools(vec![tool]),
+        )
+        .await
+        .unwrap();
+
+    let tool = &server.requests()[0].json()["tools"][0];
+    assert_eq!(tool["type"], "function");
+    assert_eq!(tool["function"]["name"], "get_weather");
+}
@@ -0,0 +1,152 @@
+use async_openai::types::chat::{
+    ChatCompletionRequestMessage, ChatCompletionToolChoiceOption, ChatCompletionTools,
+    CreateChatCompletionRequest, CreateChatCompletionResponse, FinishReason,
+};
+use ollama_rs::generation::{
+    chat::{request::ChatMessageRequest, ChatMessage, ChatMessageResponse, MessageRole},
+    done_reason::DoneReason,
+    images::Image,
+    tools::{ToolCall, ToolCallFunction, ToolChoice, ToolInfo},
+};
+use serde_json::json;
+
+fn tool_call_message() -> ChatMessage {
+    let mut message = ChatMessage::assistant(String::new());
+    message.tool_calls.push(ToolCall {
+        id: Some("call_1".to_string()),
+        function: ToolCallFunction {
+            name: "get_weather".to_string(),
+            arguments: json!({ "city": "Paris" }),
+        },
+    });
+    message
+}
+
+#[test]
+fn test_message_round_trip() {
+    let messages = vec![
+        ChatMessage::system("Be brief".to_string()),
+        ChatMessage::user("What is this?".to_string())
+            .with_images([Image::from_base64("aGVsbG8=")]),
+        tool_call_message(),
+        ChatMessage::tool("Sunny".to_string()).with_tool_call_id("call_1"),
+    ];
+
+    for message in messages {
+        let converted = ChatCompletionRequestMessage::try_from(message.clone()).unwrap();
+        let back = ChatMessage::try_from(converted).unwrap();
+
+        assert_eq!(back.role, message.role);
+        assert_eq!(back.content, message.content);
+        assert_eq!(back.tool_call_id, message.tool_call_id);
+        assert_eq!(
+            back.images.as_deref().map(|i| i.len()),
+            message.images.as_deref().map(|i| i.len())
+        );
+        assert_eq!(
+            json!(back.tool_calls).to_string(),
+            json!(message.tool_calls).to_string()
+        );
+    }
+}
+
+#[test]
+fn test_request_links_tool_results() {
+    let tool = ToolInfo::from_value(
+        "get_weather",
+        "Get the weather of a city",
+        json!({ "type": "object", "properties": { "city": { "type": "string" } } }),
+    )
+    .unwrap();
+    let request = ChatMessageRequest::new(
+        "llama3.2".to_string(),
+        vec![
+            ChatMessage::user("Weather in Paris?".to_string()),
+            tool_call_message(),
+            ChatMessage::tool("Sunny".to_string()),
+        ],
+    )
+    .tools(vec![tool])
+    .tool_choice(ToolChoice::Required);
+
+    let request = CreateChatCompletionRequest::try_from(request).unwrap();
+    assert_eq!(request.model, "llama3.2");
+    assert_eq!(request.stream, None);
+
+    let ChatCompletionRequestMessage::Assistant(call) = &request.messages[1] else {
+        panic!("expected an assistant message");
+    };
+    let ChatCompletionRequestMessage::Tool(result) = &request.messages[2] else {
+        panic!("expected a tool message");
+    };
+    let call_id = match &call.tool_calls.as_ref().unwrap()[0] {
+        async_openai::types::chat::ChatCompletionMessageToolCalls::Function(call) => &call.id,
+        _ => panic!("expected a function call"),
+    };
+    assert_eq!(&result.tool_call_id, call_id);
+
+    let tools = request.tools.unwrap();
+    let ChatCompletionTools::Function(tool) = &tools[0] else {
+        panic!("expected a function tool");
+    };
+    assert_eq!(tool.function.name, "get_weather");
+    assert_eq!(
+        ToolChoice::try_from(request.tool_choice.unwrap()).unwrap(),
+        ToolChoice::Required
+    );
+}
+
+#[test]
+fn test_tool_conversions() {
+    let tool = ToolInfo::from_value("noop", "Does nothing", json!({ "type": "object" })).unwrap();
+    let converted = ChatCompletionTools::from(tool);
+    let back = ToolInfo::try_from(converted).unwrap();
+    assert_eq!(back.name(), "noop");
+
+    let choice = ChatCompletionToolChoiceOption::from(ToolChoice::Tool("noop".to_string()));
+    assert_eq!(
+        ToolChoice::try_from(choice).unwrap(),
+        ToolChoice::Tool("noop".to_string())
+    );
+}
+
+#[test]
+fn test_response_conversions() {
+    let response: CreateChatCompletionResponse = serde_json::from_value(json!({
+        "id": "chatcmpl-1",
+        "object": "chat.completion",
+        "created": 1704067200,
+        "model": "gpt-4o-mini",
+        "choices": [{
+            "index": 0,
+            "message": {
+                "role": "assistant",
+                "content": null,
+                "tool_calls": [{
+                    "id": "call_abc",
+                    "type": "function",
+                    "function": { "name": "get_weather", "arguments": "{\"city\":\"Paris\"}" }
+                }]
+            },
+            "finish_reason": "tool_calls"
+        }],
+        "usage": { "prompt_tokens": 10, "completion_tokens": 5, "total_tokens": 15 }
+    }))
+    .unwrap();
+
+    let converted = ChatMessageResponse::try_from(response).unwrap();
+    assert_eq!(converted.model, "gpt-4o-mini");
+    assert_eq!(converted.message.role, MessageRole::Assistant);
+    assert_eq!(
+        converted.message.tool_calls[0].function.arguments,
+        json!({ "city": "Paris" })
+    );
+    assert_eq!(converted.done_reason, Some(DoneReason::Stop));
+    assert_eq!(converted.usage().unwrap().total_tokens(), 15);
+    assert_eq!(converted.created_at.to_string(), "2024-01-01T00:00:00Z");
+
+    let back = CreateChatCompletionResponse::from(converted);
+    assert_eq!(back.created, 1704067200);
+    assert_eq!(back.choices[0].finish_reason, Some(FinishReason::ToolCalls));
+    assert_eq!(back.usage.unwrap().total_tokens, 15);
+}
@@ -0,0 +1,68 @@
+use ollama_rs::openapi::register_schemas;
+use serde_json::Value;
+use utoipa::OpenApi;
+
+#[derive(OpenApi)]
+struct ApiDoc;
+
+fn refs<'a>(value: &'a Value, found: &mut Vec<&'a str>) {
+    match value {
+        Value::Object(map) => {
+            if let Some(Value::String(r)) = map.get("$ref") {
+                found.push(r);
+            }
+            map.values().for_each(|v| refs(v, found));
+        }
+        Value::Array(values) => values.iter().for_each(|v| refs(v, found)),
+        _ => {}
+    }
+}
+
+#[test]
+fn test_register_schemas() {
+    let mut openapi = ApiDoc::openapi();
+    register_schemas(&mut openapi);
+    let doc = serde_json::to_value(&openapi).unwrap();
+    let schemas = &doc["components"]["schemas"];
+
+    for name in [
+        "ChatMessageRequest",
+        "ChatMessageResponse",
+        "GenerationRequest",
+        "GenerationResponse",
+        "GenerateEmbeddingsRequest",
+        "GenerateEmbeddingsResponse",
+        "ToolInfo",
+        "ModelOptions",
+        "PullModelStatus",
+    ] {
+        assert!(schemas.get(name).is_some(), "missing schema {name}");
+    }
+
+    let request = &schemas["ChatMessageRequest"]["properties"];
+    assert!(request.get("model").is_some());
+    assert!(request.get("messages").is_some());
+
+    let response = &schemas["GenerationResponse"]["properties"];
+    assert_eq!(response["created_at"]["type"], "string");
+    assert_eq!(response["created_at"]["format"], "date-time");
+    assert!(response.get("raw").is_none());
+
+    // Every referenced schema is registered
+    let mut found = Vec::new();
+    refs(&doc, &mut found);
+    assert!(!found.is_empty());
+    for r in found {
+        let name = r.trim_start_matches("#/components/schemas/");
+        assert!(schemas.get(name).is_some(), "dangling reference {r}");
+    }
+}
+
+#[test]
+fn test_register_keeps_existing_schemas() {
+    let mut openapi = ApiDoc::openapi();
+    register_schemas(&mut openapi);
+    let count = openapi.components.as_ref().unwrap().schemas.len();
+    register_schemas(&mut openapi);
+    assert_eq!(openapi.components.unwrap().schemas.len(), count);
+}
@@ -0,0 +1,159 @@
+use ollama_rs::{
+    generation::{
+        chat::{request::ChatMessageRequest, ChatMessage},
+        completion::request::GenerationRequest,
+        partial_json::{parse_partial, PartialJson},
+    },
+    test_utils::{MockOllama, MockResponse},
+};
+use serde::Deserialize;
+use serde_json::json;
+use tokio_stream::StreamExt;
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+struct Recipe {
+    title: Option<String>,
+    ingredients: Option<Vec<String>>,
+    servings: Option<u32>,
+}
+
+#[test]
+fn test_parse_partial() {
+    assert_eq!(parse_partial(""), None);
+    assert_eq!(parse_partial("  "), None);
+    assert_eq!(parse_partial("{"), Some(json!({})));
+    assert_eq!(parse_partial(r#"{"ti"#), Some(json!({})));
+    assert_eq!(parse_partial(r#"{"title""#), Some(json!({})));
+    assert_eq!(parse_partial(r#"{"title": "#), Some(json!({})));
+    assert_eq!(
+        parse_partial(r#"{"title": "Pan"#),
+        Some(json!({ "title": "Pan" }))
+    );
+    assert_eq!(
+        parse_partial(r#"{"title": "Pan", "ingredients": ["eggs", "mi"#),
+        Some(json!({ "title": "Pan", "ingredients": ["eggs", "mi"] }))
+    );
+    assert_eq!(
+        parse_partial(r#"{"a": [1, 2], "b": {"c": tr"#),
+        Some(json!({ "a": [1, 2], "b": {} }))
+    );
+    assert_eq!(
+        parse_partial(r#"{"a": 1.5, "b": 2."#),
+        Some(json!({ "a": 1.5 }))
+    );
+    assert_eq!(parse_partial(r#"[1, 2,"#), Some(json!([1, 2])));
+}
+
+#[test]
+fn test_parse_partial_drops_trailing_numbers() {
+    assert_eq!(parse_partial(r#"{"a": 12"#), Some(json!({})));
+    assert_eq!(parse_partial(r#"{"a": 12 "#), Some(json!({ "a": 12 })));
+    assert_eq!(parse_partial(r#"{"a": 12,"#), Some(json!({ "a": 12 })));
+    assert_eq!(
+        parse_partial(r#"{"a": "x", "b": -1e"#),
+        Some(json!({ "a": "x" }))
+    );
+    assert_eq!(parse_partial(r#"[1, 23"#), Some(json!([1])));
+    assert_eq!(parse_partial(r#"{"a": "12"#), Some(json!({ "a": "12" })));
+    assert_eq!(parse_partial(r#"{"a": true"#), Some(json!({ "a": true })));
+}
+
+#[test]
+fn test_parse_partial_escapes() {
+    assert_eq!(
+        parse_partial(r#"{"a": "say \"hi"#),
+        Some(json!({ "a": "say \"hi" }))
+    );
+    assert_eq!(
+        parse_partial(r#"{"a": "line\"#),
+        Some(json!({ "a": "line" }))
+    );
+    assert_eq!(parse_partial(r#"{"a": "x\u00"#), Some(json!({ "a": "x" })));
+    assert_eq!(parse_partial(r#"{"a": "xé"#), Some(json!({ "a": "xé" })));
+    assert_eq!(parse_partial(r#"{"a": "{[,"#), Some(json!({ "a": "{[," })));
+}
+
+#[test]
+fn test_partial_json_accumulates() {
+    let mut json = PartialJson::new();
+    assert!(json.push("{\"ti").is_some());
+    // The value doesn't change until the title starts
+    assert!(json.push("tle\":").is_none());
+    assert_eq!(
+        json.push(" \"Pan").cloned(),
+        Some(json!({ "title": "Pan" }))
+    );
+    assert_eq!(
+        json.deserialize::<Recipe>(),
+        Some(Recipe {
+            title: Some("Pan".into()),
+            ingredients: None,
+            servings: None,
+        })
+    );
+    assert_eq!(json.text(), "{\"title\": \"Pan");
+}
+
+const CHUNKS: &[&str] = &[
+    "{\"title\": \"Panc",
+    "akes\", \"ingre",
+    "dients\": [\"eggs\", ",
+    "\"milk\"], \"servings\": 4",
+    "}",
+];
+
+#[tokio::test]
+async fn test_partial_generation_stream() {
+    let server = MockOllama::start().await;
+    server.mock("/api/generate", MockResponse::generation_stream(CHUNKS));
+
+    let stream = server
+        .client()
+        .generate_stream(GenerationRequest::new("mock".into(), "Pancakes"))
+        .await
+        .unwrap();
+    let values: Vec<_> = PartialJson::generation_stream::<Recipe>(stream)
+        .map(|res| res.unwrap())
+        .collect()
+        .await;
+
+    let titles: Vec<_> = values.iter().map(|v| v.value.title.clone()).collect();
+    assert_eq!(titles[0].as_deref(), Some("Panc"));
+    assert_eq!(titles[1].as_deref(), Some("Pancakes"));
+    assert_eq!(values[2].value.ingredients, Some(vec!["eggs".to_string()]));
+
+    let last = values.last().unwrap();
+    assert!(last.done);
+    assert!(values[..values.len() - 1].iter().all(|v| !v.done));
+    assert_eq!(
+        last.value,
+        Recipe {
+            title: Some("Pancakes".into()),
+            ingredients: Some(vec!["eggs".into(), "milk".into()]),
+            servings: Some(4),
+        }
+    );
+}
+
+#[tokio::test]
+async fn test_partial_chat_stream_fails_on_invalid_response() {
+    let server = MockOllama::start().await;
+    server.mock(
+        "/api/chat",
+        MockResponse::chat_stream(&["{\"title\": \"Pan", "cakes\""]),
+    );
+
+    let request = ChatMessageRequest::new("mock".into(), vec![ChatMessage::user("Hi".into())]);
+    let stream = server
+        .client()
+        .send_chat_messages_stream(request)
+        .await
+        .unwrap();
+    let values: Vec<_> = PartialJson::chat_stream::<Recipe>(stream).collect().await;
+
+    assert_eq!(
+        values[0].as_ref().unwrap().value.title.as_deref(),
+        Some("Pan")
+    );
+    assert!(values.last().unwrap().is_err());
+}
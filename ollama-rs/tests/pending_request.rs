@@ -0,0 +1,53 @@
+use ollama_rs::{
+    generation::{
+        chat::{request::ChatMessageRequest, ChatMessage},
+        completion::request::GenerationRequest,
+        embeddings::request::GenerateEmbeddingsRequest,
+    },
+    test_utils::{MockOllama, MockResponse},
+};
+
+#[tokio::test]
+async fn test_generation_request_is_sent_when_awaited() {
+    let server = MockOllama::start().await;
+    server.mock(
+        "/api/generate",
+        MockResponse::generation("Rayleigh scattering"),
+    );
+    let ollama = server.client();
+
+    let pending = GenerationRequest::new("llama3.2".into(), "Why is the sky blue?")
+        .send(&ollama)
+        .map(|request| request.system("Be brief."));
+    assert!(server.requests().is_empty());
+
+    let res = pending.await.unwrap();
+    assert_eq!(res.response, "Rayleigh scattering");
+    assert_eq!(server.requests()[0].json()["system"], "Be brief.");
+}
+
+#[tokio::test]
+async fn test_chat_request_is_sent_when_awaited() {
+    let server = MockOllama::start().await;
+    server.mock("/api/chat", MockResponse::chat("Hello!"));
+    let ollama = server.client();
+
+    let res = ChatMessageRequest::new("llama3.2".into(), vec![ChatMessage::user("Hi".into())])
+        .send(&ollama)
+        .await
+        .unwrap();
+    assert_eq!(res.message.content, "Hello!");
+}
+
+#[tokio::test]
+async fn test_embeddings_request_is_sent_when_awaited() {
+    let server = MockOllama::start().await;
+    server.mock("/api/embed", MockResponse::embeddings(vec![vec![0.5, 0.5]]));
+    let ollama = server.client();
+
+    let res = GenerateEmbeddingsRequest::new("nomic-embed-text".into(), "Hi".into())
+        .send(&ollama)
+        .await
+        .unwrap();
+    assert_eq!(res.embeddings, vec![vec![0.5, 0.5]]);
+}
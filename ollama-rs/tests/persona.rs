@@ -0,0 +1,77 @@
+use ollama_rs::{
+    coordinator::Coordinator,
+    error::{OllamaError, TemplateError},
+    generation::{chat::ChatMessage, tools::implementations::Calculator},
+    models::ModelOptions,
+    persona::Persona,
+    test_utils::{MockOllama, MockResponse},
+};
+
+fn accountant() -> Persona {
+    Persona::new("accountant", "{{> intro}} Be {{tone}}.")
+        .partial("intro", "You are {{name}}, an accountant.")
+        .variable("name", "Ada")
+        .variable("tone", "meticulous")
+        .add_tool(Calculator {})
+        .options(ModelOptions::default().temperature(0.2))
+}
+
+#[test]
+fn test_persona_system_prompt() {
+    let persona = accountant();
+    assert_eq!(persona.name(), "accountant");
+    assert_eq!(
+        persona.system_prompt().unwrap(),
+        "You are Ada, an accountant. Be meticulous."
+    );
+    assert_eq!(
+        persona
+            .clone()
+            .variable("tone", "brief")
+            .system_prompt()
+            .unwrap(),
+        "You are Ada, an accountant. Be brief."
+    );
+    assert_eq!(persona.tool_infos()[0].name(), "calculator");
+}
+
+#[tokio::test]
+async fn test_coordinator_persona() {
+    let server = MockOllama::start().await;
+    server.mock("/api/chat", MockResponse::chat("30"));
+
+    let persona = accountant();
+    for tone in ["meticulous", "friendly"] {
+        let mut coordinator = Coordinator::new(server.client(), "llama3.2".to_string(), vec![])
+            .persona(&persona.clone().variable("tone", tone))
+            .unwrap();
+        coordinator
+            .chat(vec![ChatMessage::user("What is 12% of 250?".to_string())])
+            .await
+            .unwrap();
+    }
+
+    let requests = server.requests();
+    for (request, tone) in requests.iter().zip(["meticulous", "friendly"]) {
+        let request = request.json();
+        assert_eq!(request["messages"][0]["role"], "system");
+        assert_eq!(
+            request["messages"][0]["content"],
+            format!("You are Ada, an accountant. Be {tone}.")
+        );
+        assert_eq!(request["messages"][1]["role"], "user");
+        assert_eq!(request["tools"][0]["function"]["name"], "calculator");
+        assert_eq!(request["options"]["temperature"], 0.2);
+    }
+}
+
+#[test]
+fn test_persona_missing_variable() {
+    let persona = Persona::new("greeter", "Greet {{user}}.");
+    let res =
+        Coordinator::new(Default::default(), "llama3.2".to_string(), vec![]).persona(&persona);
+    assert!(matches!(
+        res,
+        Err(OllamaError::TemplateError(TemplateError::MissingVariable(name))) if name == "user"
+    ));
+}
@@ -0,0 +1,70 @@
+use ollama_rs::{
+    models::registry::RegistryOptions,
+    test_utils::{MockOllama, MockResponse},
+};
+use serde_json::json;
+
+fn success() -> MockResponse {
+    MockResponse::json(json!({ "status": "success" }))
+}
+
+#[tokio::test]
+async fn test_pull_sends_insecure() {
+    let server = MockOllama::start().await;
+    server.mock("/api/pull", success());
+
+    let options = RegistryOptions::new().insecure(true);
+    let status = server
+        .client()
+        .pull_model_with("team/model:v2".into(), &options)
+        .await
+        .unwrap();
+    assert_eq!(status.message, "success");
+
+    let body = server.requests()[0].json();
+    assert_eq!(body["name"], "team/model:v2");
+    assert_eq!(body["insecure"], true);
+}
+
+#[tokio::test]
+async fn test_registry_override() {
+    let server = MockOllama::start().await;
+    server.mock("/api/push", success());
+    server.mock("/api/pull", success());
+
+    let options = RegistryOptions::new().registry("registry.internal:5000");
+    let ollama = server.client();
+    ollama
+        .push_model_with("team/model:v2".into(), &options)
+        .await
+        .unwrap();
+    ollama
+        .pull_model_with("llama3.2".into(), &options)
+        .await
+        .unwrap();
+
+    let requests = server.requests();
+    assert_eq!(
+        requests[0].json()["name"],
+        "registry.internal:5000/team/model:v2"
+    );
+    assert_eq!(
+        requests[1].json()["name"],
+        "registry.internal:5000/library/llama3.2"
+    );
+}
+
+#[tokio::test]
+async fn test_plain_pull() {
+    let server = MockOllama::start().await;
+    server.mock("/api/pull", success());
+
+    server
+        .client()
+        .pull_model("llama3.2".into(), false)
+        .await
+        .unwrap();
+
+    let body = server.requests()[0].json();
+    assert_eq!(body["insecure"], false);
+}
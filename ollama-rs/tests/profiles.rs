@@ -0,0 +1,102 @@
+use std::path::PathBuf;
+
+use ollama_rs::{
+    error::OllamaError,
+    generation::completion::request::GenerationRequest,
+    test_utils::{MockOllama, MockResponse},
+    Ollama, OllamaBuilder,
+};
+use serde_json::json;
+
+fn write_config(name: &str, content: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("ollama-rs-profiles-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(name);
+    std::fs::write(&path, content).unwrap();
+    path
+}
+
+#[tokio::test]
+async fn test_profile_from_toml() {
+    let server = MockOllama::start().await;
+    server.mock("/api/generate", MockResponse::generation("Blue"));
+
+    let path = write_config(
+        "config.toml",
+        &format!(
+            r#"
+[profiles.local]
+host = "{}"
+api_key = "secret"
+model = "llama3.2"
+timeout = "30s"
+
+[profiles.local.options]
+temperature = 0.5
+
+[profiles.other]
+host = "https://ollama.example.com"
+"#,
+            server.url()
+        ),
+    );
+
+    let ollama = OllamaBuilder::from_profile_file(&path, "local")
+        .unwrap()
+        .build()
+        .unwrap();
+    assert_eq!(ollama.default_model(), Some("llama3.2"));
+
+    ollama
+        .generate(GenerationRequest::new(
+            ollama.default_model().unwrap().to_string(),
+            "Why is the sky blue?",
+        ))
+        .await
+        .unwrap();
+
+    let request = &server.requests()[0];
+    assert_eq!(request.headers["authorization"], "Bearer secret");
+    assert_eq!(request.json()["model"], "llama3.2");
+    assert_eq!(request.json()["options"], json!({ "temperature": 0.5 }));
+
+    let other = OllamaBuilder::from_profile_file(&path, "other")
+        .unwrap()
+        .build()
+        .unwrap();
+    assert_eq!(other.url().as_str(), "https://ollama.example.com/");
+    assert_eq!(other.default_model(), None);
+
+    let err = OllamaBuilder::from_profile_file(&path, "missing").unwrap_err();
+    assert!(matches!(err, OllamaError::ConfigError(_)), "{err:?}");
+}
+
+#[test]
+fn test_profile_from_json_and_default_path() {
+    let path = write_config(
+        "config.json",
+        r#"{ "profiles": { "prod": { "host": "gw.example.com/ollama", "api_key_env": "OLLAMA_RS_TEST_KEY" } } }"#,
+    );
+
+    // The API key variable isn't set
+    let err = OllamaBuilder::from_profile_file(&path, "prod").unwrap_err();
+    assert!(matches!(err, OllamaError::ConfigError(_)), "{err:?}");
+
+    std::env::set_var("OLLAMA_RS_TEST_KEY", "secret");
+    std::env::set_var("OLLAMA_RS_CONFIG", &path);
+
+    let ollama = Ollama::from_profile("prod").unwrap();
+    assert_eq!(ollama.url().as_str(), "http://gw.example.com:11434/ollama");
+}
+
+#[test]
+fn test_invalid_profile_file() {
+    let path = write_config("invalid.toml", "[profiles.local]\ntimeout = \"soon\"\n");
+
+    let err = OllamaBuilder::from_profile_file(&path, "local").unwrap_err();
+    assert!(matches!(err, OllamaError::ConfigError(_)), "{err:?}");
+
+    let path = write_config("broken.toml", "[profiles.local\n");
+    let err = OllamaBuilder::from_profile_file(&path, "local").unwrap_err();
+    assert!(matches!(err, OllamaError::ConfigError(_)), "{err:?}");
+}
@@ -0,0 +1,55 @@
+use indicatif::{MultiProgress, ProgressDrawTarget};
+use ollama_rs::{
+    error::OllamaError,
+    test_utils::{MockOllama, MockResponse},
+};
+use serde_json::json;
+
+fn hidden() -> MultiProgress {
+    MultiProgress::with_draw_target(ProgressDrawTarget::hidden())
+}
+
+#[tokio::test]
+async fn test_pull_model_with_progress_bar() {
+    let server = MockOllama::start().await;
+    server.mock(
+        "/api/pull",
+        MockResponse::stream([
+            json!({ "status": "pulling manifest" }),
+            json!({ "status": "pulling", "digest": "sha256:4f21", "total": 100, "completed": 40 }),
+            json!({ "status": "pulling", "digest": "sha256:9e1c", "total": 10, "completed": 10 }),
+            json!({ "status": "pulling", "digest": "sha256:4f21", "total": 100, "completed": 100 }),
+            json!({ "status": "verifying sha256 digest" }),
+            json!({ "status": "success" }),
+        ]),
+    );
+
+    let status = server
+        .client()
+        .pull_model_with_progress_bars("llama3.2".to_string(), false, &hidden())
+        .await
+        .unwrap();
+
+    assert_eq!(status.message, "success");
+    assert_eq!(server.requests()[0].json()["stream"], true);
+}
+
+#[tokio::test]
+async fn test_push_model_with_progress_bar_error() {
+    let server = MockOllama::start().await;
+    server.mock(
+        "/api/push",
+        MockResponse::stream([
+            json!({ "status": "pushing", "digest": "sha256:4f21", "total": 100, "completed": 40 }),
+            json!({ "error": "unauthorized" }),
+        ]),
+    );
+
+    let err = server
+        .client()
+        .push_model_with_progress_bars("me/llama3.2".to_string(), false, &hidden())
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, OllamaError::InternalError(_)), "{err:?}");
+}
@@ -0,0 +1,83 @@
+use std::fmt;
+
+use ollama_rs::generation::{
+    chat::ChatMessage,
+    prompt::{Prompt, PromptTemplate},
+};
+use serde_json::json;
+
+struct Money(u32);
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "${}.{:02}", self.0 / 100, self.0 % 100)
+    }
+}
+
+#[derive(Prompt)]
+struct Order {
+    order_id: u32,
+    #[prompt(label = "Total (USD)")]
+    total: Money,
+    coupon: Option<String>,
+    shipping_address: String,
+    #[prompt(skip)]
+    #[allow(dead_code)]
+    internal_notes: String,
+}
+
+#[derive(Prompt)]
+struct Labelled<'a, T: fmt::Display> {
+    r#type: &'a str,
+    value: T,
+}
+
+fn order(coupon: Option<&str>) -> Order {
+    Order {
+        order_id: 42,
+        total: Money(1999),
+        coupon: coupon.map(str::to_string),
+        shipping_address: "1 Main St\nSpringfield".to_string(),
+        internal_notes: "VIP".to_string(),
+    }
+}
+
+#[test]
+fn test_prompt_labels_fields() {
+    assert_eq!(
+        order(Some("SPRING")).render_prompt(),
+        "Order id: 42\nTotal (USD): $19.99\nCoupon: SPRING\nShipping address:\n1 Main St\nSpringfield"
+    );
+}
+
+#[test]
+fn test_prompt_leaves_out_missing_options() {
+    let prompt = order(None).render_prompt();
+    assert!(!prompt.contains("Coupon"));
+    assert!(!prompt.contains("VIP"));
+}
+
+#[test]
+fn test_prompt_with_generics_and_raw_identifiers() {
+    let labelled = Labelled {
+        r#type: "refund",
+        value: 3.5,
+    };
+    assert_eq!(labelled.render_prompt(), "Type: refund\nValue: 3.5");
+}
+
+#[test]
+fn test_prompt_in_messages_and_templates() {
+    let message = ChatMessage::user(format!(
+        "Write a confirmation email for this order.\n\n{}",
+        order(None).render_prompt()
+    ));
+    assert!(message
+        .content
+        .ends_with("Order id: 42\nTotal (USD): $19.99\nShipping address:\n1 Main St\nSpringfield"));
+
+    let prompt = PromptTemplate::new("Order:\n{{order}}")
+        .render(&json!({ "order": order(None).render_prompt() }))
+        .unwrap();
+    assert!(prompt.starts_with("Order:\nOrder id: 42\n"));
+}
@@ -0,0 +1,77 @@
+use std::collections::HashMap;
+
+use ollama_rs::{
+    error::TemplateError,
+    generation::{chat::MessageRole, prompt::PromptTemplate},
+};
+use serde::Serialize;
+use serde_json::json;
+
+#[test]
+fn test_render_variables() {
+    #[derive(Serialize)]
+    struct Vars {
+        name: String,
+        count: u32,
+    }
+
+    let template = PromptTemplate::new("Hello {{name}}, you have {{ count }} messages.");
+    let prompt = template
+        .render(&Vars {
+            name: "Ada".to_string(),
+            count: 3,
+        })
+        .unwrap();
+    assert_eq!(prompt, "Hello Ada, you have 3 messages.");
+
+    let vars = HashMap::from([("name", "Bob"), ("count", "none")]);
+    assert_eq!(
+        template.render(&vars).unwrap(),
+        "Hello Bob, you have none messages."
+    );
+}
+
+#[test]
+fn test_render_nested_partials_and_escapes() {
+    let template = PromptTemplate::new("{{> header}}\n\\{{not a tag}} {{user.name}}")
+        .partial("header", "[{{> inner}}]")
+        .partial("inner", "{{title}}");
+
+    let vars = json!({ "title": "Report", "user": { "name": "Ada" } });
+    assert_eq!(
+        template.render(&vars).unwrap(),
+        "[Report]\n{{not a tag}} Ada"
+    );
+    assert_eq!(template.variables().unwrap(), ["title", "user.name"]);
+
+    let message = template.render_message(MessageRole::System, &vars).unwrap();
+    assert_eq!(message.role, MessageRole::System);
+    assert_eq!(message.content, "[Report]\n{{not a tag}} Ada");
+}
+
+#[test]
+fn test_render_errors() {
+    let err = PromptTemplate::new("Hi {{name}}")
+        .render(&json!({}))
+        .unwrap_err();
+    assert!(matches!(err, TemplateError::MissingVariable(ref name) if name == "name"));
+
+    let err = PromptTemplate::new("{{> missing}}")
+        .render(&json!({}))
+        .unwrap_err();
+    assert!(matches!(err, TemplateError::UnknownPartial(_)), "{err:?}");
+
+    let err = PromptTemplate::new("Hi {{name")
+        .render(&json!({ "name": "Ada" }))
+        .unwrap_err();
+    assert!(matches!(err, TemplateError::UnclosedTag(3)), "{err:?}");
+
+    let err = PromptTemplate::new("{{> loop}}")
+        .partial("loop", "{{> loop}}")
+        .render(&json!({}))
+        .unwrap_err();
+    assert!(matches!(err, TemplateError::PartialTooDeep), "{err:?}");
+
+    let err = PromptTemplate::new("Hi").render(&"Ada").unwrap_err();
+    assert!(matches!(err, TemplateError::InvalidVariables(_)), "{err:?}");
+}
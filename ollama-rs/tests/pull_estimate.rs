@@ -0,0 +1,84 @@
+use ollama_rs::{
+    models::registry::RegistryOptions,
+    test_utils::{MockOllama, MockResponse},
+};
+use serde_json::json;
+use tokio_stream::StreamExt;
+
+/// Statuses as the server sends them: the layers are downloaded one after another, each one
+/// reported repeatedly until done. The first one is already downloaded.
+fn pull_statuses() -> MockResponse {
+    MockResponse::stream([
+        json!({ "status": "pulling manifest" }),
+        json!({ "status": "pulling sha256:aaa", "digest": "sha256:aaa", "total": 200, "completed": 200 }),
+        json!({ "status": "pulling sha256:bbb", "digest": "sha256:bbb", "total": 1000, "completed": 50 }),
+        json!({ "status": "pulling sha256:bbb", "digest": "sha256:bbb", "total": 1000, "completed": 500 }),
+        json!({ "status": "pulling sha256:bbb", "digest": "sha256:bbb", "total": 1000, "completed": 1000 }),
+        json!({ "status": "pulling sha256:ccc", "digest": "sha256:ccc", "total": 50, "completed": 0 }),
+        json!({ "status": "pulling sha256:ccc", "digest": "sha256:ccc", "total": 50, "completed": 50 }),
+        json!({ "status": "verifying sha256 digest" }),
+        json!({ "status": "success" }),
+    ])
+}
+
+#[tokio::test]
+async fn test_prepare_pull_estimates_size() {
+    let server = MockOllama::start().await;
+    server.mock("/api/pull", pull_statuses());
+
+    let pull = server
+        .client()
+        .prepare_pull("llama3.2".into(), &RegistryOptions::new())
+        .await
+        .unwrap();
+    // What is left of the first layer to download: the layers after it aren't reported yet
+    assert_eq!(pull.estimated_download_size(), 950);
+
+    // The statuses read for the estimate are replayed
+    let statuses: Vec<_> = pull
+        .ensure_free_space(|needed| needed <= 2000)
+        .unwrap()
+        .into_stream()
+        .map(|status| status.unwrap().message)
+        .collect()
+        .await;
+    assert_eq!(statuses.len(), 9);
+    assert_eq!(statuses[0], "pulling manifest");
+    assert_eq!(statuses[8], "success");
+}
+
+#[tokio::test]
+async fn test_prepare_pull_aborts_without_space() {
+    let server = MockOllama::start().await;
+    server.mock("/api/pull", pull_statuses());
+
+    let err = server
+        .client()
+        .prepare_pull("llama3.2".into(), &RegistryOptions::new())
+        .await
+        .unwrap()
+        .ensure_free_space(|needed| needed <= 900)
+        .unwrap_err();
+    assert!(err.to_string().contains("950 B needed"), "{err}");
+}
+
+#[tokio::test]
+async fn test_prepare_pull_of_pulled_model() {
+    let server = MockOllama::start().await;
+    server.mock(
+        "/api/pull",
+        MockResponse::stream([
+            json!({ "status": "pulling manifest" }),
+            json!({ "status": "pulling sha256:aaa", "digest": "sha256:aaa", "total": 1000, "completed": 1000 }),
+            json!({ "status": "verifying sha256 digest" }),
+            json!({ "status": "success" }),
+        ]),
+    );
+
+    let pull = server
+        .client()
+        .prepare_pull("llama3.2".into(), &RegistryOptions::new())
+        .await
+        .unwrap();
+    assert_eq!(pull.estimated_download_size(), 0);
+}
@@ -0,0 +1,48 @@
+use std::time::{Duration, Instant};
+
+use ollama_rs::{
+    models::registry::RegistryOptions,
+    test_utils::{MockOllama, MockResponse},
+};
+use serde_json::json;
+use tokio_stream::StreamExt;
+
+#[tokio::test]
+async fn test_cancel_push() {
+    let server = MockOllama::start().await;
+    server.mock(
+        "/api/push",
+        MockResponse::stream([
+            json!({ "status": "pushing sha256:aaa", "digest": "sha256:aaa", "total": 1000, "completed": 250 }),
+            json!({ "status": "pushing sha256:aaa", "digest": "sha256:aaa", "total": 1000, "completed": 1000 }),
+            json!({ "status": "success" }),
+        ])
+        .chunk_delay(Duration::from_secs(10)),
+    );
+
+    let (mut stream, handle) = server
+        .client()
+        .push_model_stream_cancellable("me/model".to_string(), &RegistryOptions::new())
+        .await
+        .unwrap();
+
+    let status = stream.next().await.unwrap().unwrap();
+    assert_eq!(status.completed, Some(250));
+
+    let canceller = handle.clone();
+    let start = Instant::now();
+    let cancel = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        canceller.cancel()
+    });
+
+    // The stream ends without waiting for the next status
+    assert!(stream.next().await.is_none());
+    assert!(start.elapsed() < Duration::from_secs(5));
+
+    let progress = cancel.await.unwrap();
+    assert_eq!(progress.completed(), 250);
+    assert_eq!(progress.total(), 1000);
+    assert!(handle.is_cancelled());
+    assert_eq!(handle.progress().layers[0].digest, "sha256:aaa");
+}
@@ -0,0 +1,43 @@
+use ollama_rs::{
+    generation::completion::request::GenerationRequest,
+    test_utils::{MockOllama, MockResponse},
+};
+const BODY: &str = r#"{"model":"llama2","created_at":"2023-08-04T08:52:19.385406455-07:00","response":"Blue.","done":true,"new_field":42}"#;
+
+async fn respond_with_body() -> MockOllama {
+    let server = MockOllama::start().await;
+    server.mock(
+        "/api/generate",
+        MockResponse::body(BODY).header("x-server-version", "0.6.0"),
+    );
+    server
+}
+
+#[tokio::test]
+async fn test_raw_response_captured() {
+    let server = respond_with_body().await;
+    let mut ollama = server.client();
+    ollama.set_capture_raw_responses(true);
+
+    let res = ollama
+        .generate(GenerationRequest::new("llama2".to_string(), "Why?"))
+        .await
+        .unwrap();
+
+    let raw = res.raw.unwrap();
+    assert_eq!(raw.body, BODY);
+    assert_eq!(raw.headers["x-server-version"], "0.6.0");
+}
+
+#[tokio::test]
+async fn test_raw_response_not_captured_by_default() {
+    let server = respond_with_body().await;
+    let ollama = server.client();
+
+    let res = ollama
+        .generate(GenerationRequest::new("llama2".to_string(), "Why?"))
+        .await
+        .unwrap();
+
+    assert!(res.raw.is_none());
+}
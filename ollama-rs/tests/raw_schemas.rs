@@ -0,0 +1,89 @@
+use std::{future::Future, pin::Pin};
+
+use ollama_rs::{
+    coordinator::Coordinator,
+    generation::{
+        chat::ChatMessage,
+        completion::request::GenerationRequest,
+        parameters::{FormatType, JsonStructure},
+        tools::{self, DynTool, ToolInfo},
+    },
+    test_utils::{MockOllama, MockResponse},
+};
+use serde_json::{json, Value};
+
+struct Lookup;
+
+impl DynTool for Lookup {
+    fn info(&self) -> ToolInfo {
+        ToolInfo::from_value(
+            "lookup",
+            "Looks up a word in the dictionary",
+            json!({
+                "type": "object",
+                "properties": { "word": { "type": "string" } },
+                "required": ["word"]
+            }),
+        )
+        .unwrap()
+    }
+
+    fn call(&self, parameters: Value) -> Pin<Box<dyn Future<Output = tools::Result<String>> + '_>> {
+        Box::pin(async move { Ok(format!("{} means hello", parameters["word"])) })
+    }
+}
+
+#[tokio::test]
+async fn test_raw_format_schema() {
+    let schema = json!({
+        "type": "object",
+        "properties": { "answer": { "type": "integer" } },
+        "required": ["answer"]
+    });
+
+    let server = MockOllama::start().await;
+    server.mock("/api/generate", MockResponse::generation("{\"answer\": 4}"));
+
+    server
+        .client()
+        .generate(
+            GenerationRequest::new("llama3.2".to_string(), "2 + 2?").format(
+                FormatType::StructuredJson(JsonStructure::from_value(schema.clone()).unwrap()),
+            ),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(server.requests()[0].json()["format"], schema);
+    assert!(JsonStructure::from_value(json!("object")).is_err());
+}
+
+#[tokio::test]
+async fn test_raw_tool_schema() {
+    let server = MockOllama::start().await;
+    server
+        .mock_once(
+            "/api/chat",
+            MockResponse::tool_call("lookup", json!({ "word": "hola" })),
+        )
+        .mock("/api/chat", MockResponse::chat("Hello"));
+
+    let mut coordinator = Coordinator::new(server.client(), "llama3.2".to_string(), vec![])
+        .add_dyn_tool(Box::new(Lookup));
+    coordinator
+        .chat(vec![ChatMessage::user("What does hola mean?".to_string())])
+        .await
+        .unwrap();
+
+    let requests = server.requests();
+    let tool = &requests[0].json()["tools"][0];
+    // The native API gets the type as it always did, only OpenAI requests lowercase it
+    assert_eq!(tool["type"], "Function");
+    assert_eq!(tool["function"]["name"], "lookup");
+    assert_eq!(tool["function"]["parameters"]["required"], json!(["word"]));
+
+    let messages = requests[1].json()["messages"].clone();
+    assert_eq!(messages[2]["content"], "\"hola\" means hello");
+
+    assert!(ToolInfo::from_value("lookup", "", json!(42)).is_err());
+}
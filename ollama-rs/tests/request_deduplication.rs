@@ -0,0 +1,140 @@
+use std::time::Duration;
+
+use ollama_rs::{
+    generation::{
+        chat::{request::ChatMessageRequest, ChatMessage},
+        completion::request::GenerationRequest,
+    },
+    test_utils::{MockOllama, MockResponse},
+    Ollama,
+};
+
+fn count(server: &MockOllama, path: &str) -> usize {
+    server
+        .requests()
+        .iter()
+        .filter(|request| request.path == path)
+        .count()
+}
+
+fn client(server: &MockOllama) -> Ollama {
+    let mut ollama = server.client();
+    ollama.set_deduplicate_requests(true);
+    ollama
+}
+
+#[tokio::test]
+async fn test_concurrent_identical_requests_are_coalesced() {
+    let server = MockOllama::start().await;
+    server.mock(
+        "/api/generate",
+        MockResponse::generation("Paris").delay(Duration::from_millis(100)),
+    );
+    let ollama = client(&server);
+    let clone = ollama.clone();
+
+    let request = || GenerationRequest::new("llama3.2".into(), "Capital of France?");
+    let (a, b, c) = tokio::join!(
+        ollama.generate(request()),
+        ollama.generate(request()),
+        clone.generate(request()),
+    );
+
+    assert_eq!(a.unwrap().response, "Paris");
+    assert_eq!(b.unwrap().response, "Paris");
+    assert_eq!(c.unwrap().response, "Paris");
+    assert_eq!(count(&server, "/api/generate"), 1);
+}
+
+#[tokio::test]
+async fn test_different_requests_are_not_coalesced() {
+    let server = MockOllama::start().await;
+    server.mock_with("/api/generate", |request| {
+        MockResponse::generation(request.json()["prompt"].as_str().unwrap())
+            .delay(Duration::from_millis(50))
+    });
+    let ollama = client(&server);
+
+    let (a, b) = tokio::join!(
+        ollama.generate(GenerationRequest::new("llama3.2".into(), "a")),
+        ollama.generate(GenerationRequest::new("llama3.2".into(), "b")),
+    );
+
+    assert_eq!(a.unwrap().response, "a");
+    assert_eq!(b.unwrap().response, "b");
+    assert_eq!(count(&server, "/api/generate"), 2);
+}
+
+#[tokio::test]
+async fn test_sequential_requests_are_sent_again() {
+    let server = MockOllama::start().await;
+    server.mock("/api/chat", MockResponse::chat("Hello!"));
+    let ollama = client(&server);
+
+    for _ in 0..2 {
+        let request =
+            ChatMessageRequest::new("llama3.2".into(), vec![ChatMessage::user("Hi".into())]);
+        let res = ollama.send_chat_messages(request).await.unwrap();
+        assert_eq!(res.message.content, "Hello!");
+    }
+
+    assert_eq!(count(&server, "/api/chat"), 2);
+}
+
+#[tokio::test]
+async fn test_requests_waiting_on_a_failed_request_are_sent() {
+    let server = MockOllama::start().await;
+    server
+        .mock_once(
+            "/api/generate",
+            MockResponse::error(500, "boom").delay(Duration::from_millis(100)),
+        )
+        .mock("/api/generate", MockResponse::generation("Paris"));
+    let ollama = client(&server);
+
+    let request = || GenerationRequest::new("llama3.2".into(), "Capital of France?");
+    let (a, b) = tokio::join!(ollama.generate(request()), async {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        ollama.generate(request()).await
+    });
+
+    assert!(a.is_err());
+    assert_eq!(b.unwrap().response, "Paris");
+    assert_eq!(count(&server, "/api/generate"), 2);
+}
+
+#[tokio::test]
+async fn test_deduplication_is_off_by_default() {
+    let server = MockOllama::start().await;
+    server.mock(
+        "/api/generate",
+        MockResponse::generation("Paris").delay(Duration::from_millis(50)),
+    );
+    let ollama = server.client();
+
+    let request = || GenerationRequest::new("llama3.2".into(), "Capital of France?");
+    let (a, b) = tokio::join!(ollama.generate(request()), ollama.generate(request()));
+
+    assert!(a.is_ok() && b.is_ok());
+    assert_eq!(count(&server, "/api/generate"), 2);
+}
+
+#[tokio::test]
+async fn test_clones_with_other_api_keys_are_not_coalesced() {
+    let server = MockOllama::start().await;
+    server.mock_with("/api/generate", |request| {
+        MockResponse::generation(&request.headers["authorization"])
+            .delay(Duration::from_millis(100))
+    });
+    let mut alice = client(&server);
+    alice.set_api_key(Some("alice".to_string()));
+    let mut bob = alice.clone();
+    bob.set_api_key(Some("bob".to_string()));
+
+    let request = || GenerationRequest::new("llama3.2".into(), "Capital of France?");
+    let (a, b) = tokio::join!(alice.generate(request()), bob.generate(request()));
+
+    assert_eq!(a.unwrap().response, "Bearer alice");
+    assert_eq!(b.unwrap().response, "Bearer bob");
+    assert_eq!(count(&server, "/api/generate"), 2);
+}
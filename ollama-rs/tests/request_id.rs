@@ -0,0 +1,141 @@
+use std::time::Duration;
+
+use ollama_rs::{
+    error::OllamaError,
+    generation::completion::request::GenerationRequest,
+    request_id::REQUEST_ID_HEADER,
+    test_utils::{MockOllama, MockResponse},
+    Ollama, RetryPolicy,
+};
+use serde_json::json;
+use tokio_stream::StreamExt;
+
+fn request_ids(server: &MockOllama) -> Vec<String> {
+    server
+        .requests()
+        .into_iter()
+        .map(|r| r.headers[REQUEST_ID_HEADER].clone())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_requests_have_unique_ids() {
+    let server = MockOllama::start().await;
+    server.mock("/api/generate", MockResponse::generation("Hello"));
+    let ollama = server.client();
+
+    for _ in 0..2 {
+        ollama
+            .generate(GenerationRequest::new("llama3.2".to_string(), "Hi"))
+            .await
+            .unwrap();
+    }
+
+    let ids = request_ids(&server);
+    assert!(ids.iter().all(|id| id.starts_with("req_")), "{ids:?}");
+    assert_ne!(ids[0], ids[1]);
+}
+
+#[tokio::test]
+async fn test_errors_carry_the_request_id() {
+    let server = MockOllama::start().await;
+    server.mock("/api/generate", MockResponse::error(400, "invalid options"));
+
+    let err = server
+        .client()
+        .generate(GenerationRequest::new("llama3.2".to_string(), "Hi"))
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, OllamaError::HttpError { .. }), "{err:?}");
+    assert_eq!(err.request_id(), Some(request_ids(&server)[0].as_str()));
+}
+
+#[tokio::test]
+async fn test_retries_keep_the_request_id() {
+    let server = MockOllama::start().await;
+    server
+        .mock_once("/api/generate", MockResponse::error(503, "busy"))
+        .mock("/api/generate", MockResponse::error(503, "still busy"));
+    let ollama = Ollama::builder()
+        .url(server.url())
+        .retry_policy(RetryPolicy::new(1).initial_backoff(Duration::from_millis(10)))
+        .build()
+        .unwrap();
+
+    let err = ollama
+        .generate(GenerationRequest::new("llama3.2".to_string(), "Hi"))
+        .await
+        .unwrap_err();
+
+    let ids = request_ids(&server);
+    assert_eq!(ids.len(), 2);
+    assert_eq!(ids[0], ids[1]);
+    assert_eq!(err.request_id(), Some(ids[0].as_str()));
+}
+
+#[tokio::test]
+async fn test_stream_errors_carry_the_request_id() {
+    let server = MockOllama::start().await;
+    server.mock(
+        "/api/generate",
+        MockResponse::stream([json!({ "unexpected": true })]),
+    );
+
+    let mut stream = server
+        .client()
+        .generate_stream(GenerationRequest::new("llama3.2".to_string(), "Hi"))
+        .await
+        .unwrap();
+
+    let err = stream.next().await.unwrap().unwrap_err();
+    assert!(matches!(err, OllamaError::DecodeError { .. }), "{err:?}");
+    assert_eq!(err.request_id(), Some(request_ids(&server)[0].as_str()));
+}
+
+#[tokio::test]
+async fn test_request_id_header_is_kept() {
+    let server = MockOllama::start().await;
+    server.mock("/api/generate", MockResponse::error(500, "oops"));
+    let mut headers = reqwest::header::HeaderMap::new();
+    headers.insert(REQUEST_ID_HEADER, "trace-42".parse().unwrap());
+    let ollama = Ollama::builder()
+        .url(server.url())
+        .headers(headers)
+        .build()
+        .unwrap();
+
+    let err = ollama
+        .generate(GenerationRequest::new("llama3.2".to_string(), "Hi"))
+        .await
+        .unwrap_err();
+
+    assert_eq!(request_ids(&server), ["trace-42"]);
+    assert_eq!(err.request_id(), Some("trace-42"));
+}
+
+#[tokio::test]
+async fn test_body_read_errors_carry_the_request_id() {
+    let server = MockOllama::start().await;
+    server.mock(
+        "/api/generate",
+        MockResponse::frames([
+            "{\"model\":\"llama3.2\",",
+            "\"response\":\"Hi\",\"done\":true}",
+        ])
+        .chunk_delay(Duration::from_millis(500)),
+    );
+    let ollama = Ollama::builder()
+        .url(server.url())
+        .timeout(Duration::from_millis(200))
+        .build()
+        .unwrap();
+
+    let err = ollama
+        .generate(GenerationRequest::new("llama3.2".to_string(), "Hi"))
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, OllamaError::Timeout { .. }), "{err:?}");
+    assert_eq!(err.request_id(), Some(request_ids(&server)[0].as_str()));
+}
@@ -0,0 +1,135 @@
+use ollama_rs::{
+    generation::{
+        chat::{request::ChatMessageRequest, ChatMessage},
+        completion::request::GenerationRequest,
+        images::Image,
+    },
+    models::{KvCacheType, LogitBias, LogitBiasToken, ModelOptions},
+    test_utils::{MockOllama, MockResponse},
+};
+use serde_json::json;
+
+#[tokio::test]
+async fn test_fluent_generation_options() {
+    let server = MockOllama::start().await;
+    server.mock("/api/generate", MockResponse::generation("Blue"));
+
+    let request = GenerationRequest::new("llama2".to_string(), "Why is the sky blue?")
+        .options(ModelOptions::default().top_k(10))
+        .temperature(0.5)
+        .num_ctx(8192)
+        .seed(42)
+        .stop(vec!["\n".to_string()]);
+    server.client().generate(request).await.unwrap();
+
+    assert_eq!(
+        server.requests()[0].json()["options"],
+        json!({ "top_k": 10, "temperature": 0.5, "num_ctx": 8192, "seed": 42, "stop": ["\n"] })
+    );
+}
+
+#[tokio::test]
+async fn test_fluent_chat_options() {
+    let server = MockOllama::start().await;
+    server.mock("/api/chat", MockResponse::chat("Hello"));
+
+    let request = ChatMessageRequest::new(
+        "llama2".to_string(),
+        vec![ChatMessage::user("Hi".to_string())],
+    )
+    .num_predict(128)
+    .top_p(0.5)
+    .repeat_penalty(1.5);
+    server.client().send_chat_messages(request).await.unwrap();
+
+    assert_eq!(
+        server.requests()[0].json()["options"],
+        json!({ "num_predict": 128, "top_p": 0.5, "repeat_penalty": 1.5 })
+    );
+}
+
+#[tokio::test]
+async fn test_logit_bias() {
+    let server = MockOllama::start().await;
+    server.mock("/api/generate", MockResponse::generation("Blue"));
+
+    let logit_bias = LogitBias::new()
+        .token(15043, 2.0)
+        .ban_text("Certainly")
+        .token(15043, -1.5);
+    let request = GenerationRequest::new("llama2".to_string(), "Why is the sky blue?")
+        .options(ModelOptions::default().logit_bias(logit_bias));
+    server.client().generate(request).await.unwrap();
+
+    assert_eq!(
+        server.requests()[0].json()["options"]["logit_bias"],
+        json!([[15043, -1.5], ["Certainly", -100.0]])
+    );
+
+    let options: ModelOptions =
+        serde_json::from_value(json!({ "logit_bias": { "50256": -100.0 } })).unwrap();
+    let logit_bias = serde_json::to_value(&options).unwrap()["logit_bias"].clone();
+    let logit_bias: LogitBias = serde_json::from_value(logit_bias).unwrap();
+    assert_eq!(
+        logit_bias.entries(),
+        [(LogitBiasToken::Id(50256), LogitBias::BAN)]
+    );
+}
+
+#[test]
+fn test_kv_cache_and_flash_attention() {
+    let options = ModelOptions::default()
+        .kv_cache_type(KvCacheType::Q8_0)
+        .flash_attention(true);
+
+    assert_eq!(
+        serde_json::to_value(&options).unwrap(),
+        json!({ "kv_cache_type": "q8_0", "flash_attn": true })
+    );
+
+    let options: ModelOptions =
+        serde_json::from_value(json!({ "kv_cache_type": "q4_0", "flash_attn": false })).unwrap();
+    assert_eq!(
+        serde_json::to_value(&options).unwrap()["kv_cache_type"],
+        "q4_0"
+    );
+}
+
+#[tokio::test]
+async fn test_multiple_images() {
+    let server = MockOllama::start().await;
+    server
+        .mock("/api/generate", MockResponse::generation("Two cats"))
+        .mock("/api/chat", MockResponse::chat("Two cats"));
+
+    let images = ["aW1hZ2Ux", "aW1hZ2Uy"].map(Image::from_base64);
+
+    let request = GenerationRequest::new("llava".to_string(), "Compare these images")
+        .add_image(Image::from_base64("aW1hZ2Uw"))
+        .add_images(images.clone());
+    server.client().generate(request).await.unwrap();
+
+    let message = ChatMessage::user("Compare these images".to_string())
+        .add_images(images.clone())
+        .add_image(Image::from_base64("aW1hZ2Uz"));
+    server
+        .client()
+        .send_chat_messages(ChatMessageRequest::new("llava".to_string(), vec![message]))
+        .await
+        .unwrap();
+
+    let requests = server.requests();
+    assert_eq!(
+        requests[0].json()["images"],
+        json!(["aW1hZ2Uw", "aW1hZ2Ux", "aW1hZ2Uy"])
+    );
+    assert_eq!(
+        requests[1].json()["messages"][0]["images"],
+        json!(["aW1hZ2Ux", "aW1hZ2Uy", "aW1hZ2Uz"])
+    );
+
+    let request = GenerationRequest::new("llava".to_string(), "Describe")
+        .add_image(Image::from_base64("aW1hZ2Uw"))
+        .images(images);
+    assert_eq!(request.images.len(), 2);
+}
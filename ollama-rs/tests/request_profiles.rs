@@ -0,0 +1,132 @@
+use std::path::PathBuf;
+
+use ollama_rs::{
+    error::OllamaError,
+    generation::{
+        chat::{request::ChatMessageRequest, ChatMessage},
+        completion::request::GenerationRequest,
+        parameters::FormatType,
+    },
+    models::ModelOptions,
+    request_profile::{RequestProfile, RequestProfiles},
+    test_utils::{MockOllama, MockResponse},
+};
+use serde_json::json;
+
+fn temp_path(name: &str) -> PathBuf {
+    let dir =
+        std::env::temp_dir().join(format!("ollama-rs-request-profiles-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir.join(name)
+}
+
+fn profiles() -> RequestProfiles {
+    let mut profiles = RequestProfiles::new();
+    profiles.insert(
+        "summarize",
+        RequestProfile::new("llama3.2")
+            .options(ModelOptions::default().temperature(0.2).num_ctx(8192))
+            .system("Summarize the text."),
+    );
+    profiles.insert(
+        "extract",
+        RequestProfile::new("qwen2.5:7b").format(FormatType::Json),
+    );
+    profiles
+}
+
+#[tokio::test]
+async fn test_generation_request_from_profile() {
+    let server = MockOllama::start().await;
+    server.mock("/api/generate", MockResponse::generation("Short."));
+
+    let request = profiles()
+        .generation_request("summarize", "A long text.")
+        .unwrap();
+    server.client().generate(request).await.unwrap();
+
+    let body = server.requests()[0].json();
+    assert_eq!(body["model"], "llama3.2");
+    assert_eq!(body["prompt"], "A long text.");
+    assert_eq!(body["system"], "Summarize the text.");
+    assert_eq!(body["options"]["num_ctx"], 8192);
+}
+
+#[tokio::test]
+async fn test_apply_profile_to_chat_keeps_request_settings() {
+    let server = MockOllama::start().await;
+    server.mock("/api/chat", MockResponse::chat("Short."));
+
+    let request = ChatMessageRequest::new(
+        "other".to_string(),
+        vec![
+            ChatMessage::system("Be brief.".to_string()),
+            ChatMessage::user("A long text.".to_string()),
+        ],
+    )
+    .options(ModelOptions::default().temperature(0.9));
+    let request = profiles().apply_to_chat("summarize", request).unwrap();
+    server.client().send_chat_messages(request).await.unwrap();
+
+    let body = server.requests()[0].json();
+    assert_eq!(body["model"], "llama3.2");
+    assert_eq!(body["messages"].as_array().unwrap().len(), 2);
+    assert_eq!(body["messages"][0]["content"], "Be brief.");
+    assert!((body["options"]["temperature"].as_f64().unwrap() - 0.9).abs() < 1e-6);
+    assert_eq!(body["options"]["num_ctx"], 8192);
+}
+
+#[test]
+fn test_chat_request_prepends_system_prompt() {
+    let request = profiles()
+        .chat_request(
+            "summarize",
+            vec![ChatMessage::user("A long text.".to_string())],
+        )
+        .unwrap();
+
+    assert_eq!(request.messages.len(), 2);
+    assert_eq!(request.messages[0].content, "Summarize the text.");
+}
+
+#[test]
+fn test_profiles_round_trip_through_files() {
+    let schema = json!({
+        "type": "object",
+        "properties": { "name": { "type": "string" } },
+    });
+    let mut profiles = profiles();
+    profiles.insert(
+        "structured",
+        RequestProfile::new("llama3.2")
+            .format(serde_json::from_value::<FormatType>(schema.clone()).unwrap()),
+    );
+
+    for name in ["profiles.json", "profiles.toml"] {
+        let path = temp_path(name);
+        profiles.save(&path).unwrap();
+        let loaded = RequestProfiles::load(&path).unwrap();
+
+        let request = loaded.generation_request("extract", "Text").unwrap();
+        assert_eq!(request.model_name, "qwen2.5:7b");
+        assert!(matches!(request.format, Some(FormatType::Json)));
+
+        let request: GenerationRequest = loaded.generation_request("summarize", "Text").unwrap();
+        assert_eq!(request.system.as_deref(), Some("Summarize the text."));
+        assert_eq!(
+            serde_json::to_value(&request.options).unwrap()["num_ctx"],
+            8192
+        );
+
+        let request = loaded.generation_request("structured", "Text").unwrap();
+        assert_eq!(serde_json::to_value(&request.format).unwrap(), schema);
+    }
+}
+
+#[test]
+fn test_unknown_profile_is_an_error() {
+    let err = profiles()
+        .generation_request("translate", "Text")
+        .unwrap_err();
+    assert!(matches!(err, OllamaError::ConfigError(_)));
+}
@@ -0,0 +1,142 @@
+use std::time::{Duration, Instant};
+
+use ollama_rs::{
+    generation::completion::request::GenerationRequest,
+    router::LatencyTier,
+    scheduler::Scheduler,
+    test_utils::{MockOllama, MockResponse},
+};
+
+async fn server() -> MockOllama {
+    let server = MockOllama::start().await;
+    server.mock_with("/api/generate", |request| {
+        MockResponse::generation(request.json()["prompt"].as_str().unwrap())
+            .delay(Duration::from_millis(50))
+    });
+    server
+}
+
+fn prompts(server: &MockOllama) -> Vec<String> {
+    server
+        .requests()
+        .iter()
+        .map(|request| request.json()["prompt"].as_str().unwrap().to_string())
+        .collect()
+}
+
+async fn generate(
+    scheduler: &Scheduler,
+    model: &str,
+    prompt: &str,
+    priority: LatencyTier,
+    after: Duration,
+) -> String {
+    tokio::time::sleep(after).await;
+    let request = GenerationRequest::new(model.into(), prompt.to_string());
+    scheduler
+        .generate(request, priority)
+        .await
+        .unwrap()
+        .response
+}
+
+#[tokio::test]
+async fn test_scheduler_dispatches_urgent_requests_first() {
+    let server = server().await;
+    let scheduler = Scheduler::new(server.client()).max_concurrency(1);
+
+    let ms = Duration::from_millis;
+    tokio::join!(
+        generate(&scheduler, "llama3.2", "first", LatencyTier::Batch, ms(0)),
+        generate(&scheduler, "llama3.2", "batch", LatencyTier::Batch, ms(10)),
+        generate(
+            &scheduler,
+            "llama3.2",
+            "standard",
+            LatencyTier::Standard,
+            ms(15)
+        ),
+        generate(
+            &scheduler,
+            "llama3.2",
+            "chat",
+            LatencyTier::Interactive,
+            ms(20)
+        ),
+    );
+
+    assert_eq!(prompts(&server), ["first", "chat", "standard", "batch"]);
+}
+
+#[tokio::test]
+async fn test_scheduler_limits_concurrency_per_model() {
+    let server = server().await;
+    let scheduler = Scheduler::new(server.client())
+        .max_concurrency(2)
+        .model_concurrency("qwen2.5", 1);
+
+    let probe = async {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        (
+            scheduler.running("llama3.2"),
+            scheduler.queued("llama3.2"),
+            scheduler.running("qwen2.5"),
+            scheduler.queued("qwen2.5"),
+        )
+    };
+    let zero = Duration::ZERO;
+    let start = Instant::now();
+    let (.., counts) = tokio::join!(
+        generate(&scheduler, "llama3.2", "a", LatencyTier::Standard, zero),
+        generate(&scheduler, "llama3.2", "b", LatencyTier::Standard, zero),
+        generate(&scheduler, "llama3.2", "c", LatencyTier::Standard, zero),
+        generate(&scheduler, "qwen2.5", "d", LatencyTier::Standard, zero),
+        generate(&scheduler, "qwen2.5", "e", LatencyTier::Standard, zero),
+        probe,
+    );
+
+    assert_eq!(counts, (2, 1, 1, 1));
+    assert!(start.elapsed() >= Duration::from_millis(100));
+    assert_eq!(scheduler.running("llama3.2"), 0);
+    assert_eq!(server.requests().len(), 5);
+}
+
+#[tokio::test]
+async fn test_scheduler_forgets_cancelled_requests() {
+    let server = server().await;
+    let scheduler = Scheduler::new(server.client());
+
+    let ms = Duration::from_millis;
+    let cancelled = tokio::time::timeout(
+        ms(30),
+        generate(
+            &scheduler,
+            "llama3.2",
+            "cancelled",
+            LatencyTier::Interactive,
+            ms(10),
+        ),
+    );
+    let (_, res, _) = tokio::join!(
+        generate(
+            &scheduler,
+            "llama3.2",
+            "first",
+            LatencyTier::Standard,
+            ms(0)
+        ),
+        generate(
+            &scheduler,
+            "llama3.2",
+            "second",
+            LatencyTier::Standard,
+            ms(5)
+        ),
+        cancelled,
+    );
+
+    assert_eq!(res, "second");
+    assert_eq!(prompts(&server), ["first", "second"]);
+    assert_eq!(scheduler.queued("llama3.2"), 0);
+    assert_eq!(scheduler.running("llama3.2"), 0);
+}
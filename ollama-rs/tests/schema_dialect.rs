@@ -0,0 +1,114 @@
+use ollama_rs::{
+    generation::{
+        chat::request::ChatMessageRequest,
+        completion::request::GenerationRequest,
+        parameters::{FormatType, JsonSchema, JsonStructure, SchemaDialect},
+        tools::{ToolInfo, ToolSet},
+    },
+    test_utils::{MockOllama, MockResponse},
+};
+use serde::Deserialize;
+use serde_json::json;
+
+#[allow(dead_code)]
+#[derive(Deserialize, JsonSchema)]
+struct Point {
+    /// The coordinates
+    coordinates: (f32, f32),
+}
+
+#[test]
+fn test_generated_schemas() {
+    let draft07 = JsonStructure::new::<Point>();
+    let draft07 = serde_json::to_value(FormatType::StructuredJson(draft07)).unwrap();
+    assert_eq!(
+        draft07["$schema"],
+        "http://json-schema.org/draft-07/schema#"
+    );
+    assert!(draft07["properties"]["coordinates"]["items"].is_array());
+
+    let modern = JsonStructure::new_with_dialect::<Point>(SchemaDialect::Draft2020_12);
+    let modern = serde_json::to_value(FormatType::StructuredJson(modern)).unwrap();
+    assert_eq!(
+        modern["$schema"],
+        "https://json-schema.org/draft/2020-12/schema"
+    );
+    assert!(modern["properties"]["coordinates"]["prefixItems"].is_array());
+}
+
+#[test]
+fn test_converted_schemas() {
+    let schema = json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "type": "object",
+        "properties": {
+            "pair": { "type": "array", "items": [{ "type": "string" }, { "$ref": "#/definitions/Id" }], "additionalItems": false },
+            // A property named like a keyword is left alone
+            "definitions": { "type": "string", "enum": ["items"] },
+        },
+        "dependencies": { "pair": ["definitions"] },
+        "definitions": { "Id": { "type": "integer" } },
+    });
+
+    let structure = JsonStructure::from_value(schema.clone())
+        .unwrap()
+        .to_dialect(SchemaDialect::Draft2020_12);
+    let modern = serde_json::to_value(FormatType::StructuredJson(structure.clone())).unwrap();
+    assert_eq!(
+        modern,
+        json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "type": "object",
+            "properties": {
+                "pair": { "type": "array", "prefixItems": [{ "type": "string" }, { "$ref": "#/$defs/Id" }], "items": false },
+                "definitions": { "type": "string", "enum": ["items"] },
+            },
+            "dependentRequired": { "pair": ["definitions"] },
+            "$defs": { "Id": { "type": "integer" } },
+        })
+    );
+
+    let back = structure.to_dialect(SchemaDialect::Draft07);
+    assert_eq!(
+        serde_json::to_value(FormatType::StructuredJson(back)).unwrap(),
+        schema
+    );
+}
+
+#[tokio::test]
+async fn test_request_schema_dialect() {
+    let server = MockOllama::start().await;
+    server.mock("/api/chat", MockResponse::chat("{}"));
+    server.mock("/api/generate", MockResponse::generation("{}"));
+
+    let tools = ToolSet::new().infos();
+    let tool = ToolInfo::from_value(
+        "locate",
+        "Locates a point.",
+        json!({ "type": "object", "properties": { "at": { "items": [{ "type": "number" }] } } }),
+    )
+    .unwrap();
+
+    // The dialect applies to the format and tools set before and after it
+    let request = ChatMessageRequest::new("llama3.2".to_string(), vec![])
+        .format(FormatType::StructuredJson(JsonStructure::new::<Point>()))
+        .schema_dialect(SchemaDialect::Draft2020_12)
+        .tools(tools.into_iter().chain([tool]).collect());
+    server.client().send_chat_messages(request).await.unwrap();
+
+    let request = GenerationRequest::new("llama3.2".to_string(), "Where?")
+        .schema_dialect(SchemaDialect::Draft2020_12)
+        .format(FormatType::StructuredJson(JsonStructure::new::<Point>()));
+    server.client().generate(request).await.unwrap();
+
+    let requests = server.requests();
+    let chat = requests[0].json();
+    assert!(chat["format"]["properties"]["coordinates"]["prefixItems"].is_array());
+    assert!(
+        chat["tools"][0]["function"]["parameters"]["properties"]["at"]["prefixItems"].is_array()
+    );
+    assert!(chat.get("schema_dialect").is_none());
+
+    let generate = requests[1].json();
+    assert!(generate["format"]["properties"]["coordinates"]["prefixItems"].is_array());
+}
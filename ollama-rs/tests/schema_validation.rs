@@ -0,0 +1,66 @@
+use ollama_rs::{error::OllamaError, generation::parameters::JsonStructure};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::json;
+
+#[allow(dead_code)]
+#[derive(Debug, Deserialize, JsonSchema)]
+struct Item {
+    name: String,
+    quantity: u32,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Deserialize, JsonSchema)]
+struct Order {
+    items: Vec<Item>,
+    notes: Option<String>,
+}
+
+#[test]
+fn test_validate_reports_violation_paths() {
+    let structure = JsonStructure::new::<Order>();
+
+    structure
+        .validate(&json!({ "items": [{ "name": "apple", "quantity": 2 }] }))
+        .unwrap();
+
+    let err = structure
+        .validate(&json!({ "items": [{ "name": "apple", "quantity": -1 }, { "quantity": 1 }] }))
+        .unwrap_err();
+    let OllamaError::SchemaValidationError { violations } = err else {
+        panic!("unexpected error: {err:?}");
+    };
+
+    let paths: Vec<_> = violations
+        .iter()
+        .map(|v| v.instance_path.as_str())
+        .collect();
+    assert_eq!(paths, ["/items/0/quantity", "/items/1"]);
+    assert!(violations[1].message.contains("name"), "{violations:?}");
+    assert!(
+        violations[1].schema_path.ends_with("/required"),
+        "{violations:?}"
+    );
+}
+
+#[test]
+fn test_parse_validates_before_deserializing() {
+    let structure = JsonStructure::new::<Order>();
+
+    let order: Order = structure
+        .parse(r#"{ "items": [{ "name": "pear", "quantity": 3 }], "notes": null }"#)
+        .unwrap();
+    assert_eq!(order.items[0].name, "pear");
+
+    let err = structure
+        .parse::<Order>(r#"{ "items": "none" }"#)
+        .unwrap_err();
+    assert!(
+        matches!(err, OllamaError::SchemaValidationError { .. }),
+        "{err:?}"
+    );
+
+    let err = structure.parse::<Order>("not json").unwrap_err();
+    assert!(matches!(err, OllamaError::DecodeError { .. }), "{err:?}");
+}
@@ -0,0 +1,77 @@
+use ollama_rs::{
+    coordinator::Coordinator,
+    generation::{
+        chat::ChatMessage,
+        tools::{implementations::Scratchpad, ToolSet},
+    },
+    test_utils::{MockOllama, MockResponse},
+};
+use serde_json::json;
+
+#[tokio::test]
+async fn test_scratchpad_actions() {
+    let scratchpad = Scratchpad::new();
+    let mut tools = ToolSet::new();
+    tools.insert(scratchpad.clone());
+
+    let call = |arguments| tools.call("scratchpad", arguments);
+    assert_eq!(
+        call(json!({ "action": "list" })).await.unwrap(),
+        "The scratchpad is empty"
+    );
+    call(json!({ "action": "set", "key": "plan", "value": "1. Flights" }))
+        .await
+        .unwrap();
+    call(json!({ "action": "append", "key": "plan", "value": "\n2. Hotel" }))
+        .await
+        .unwrap();
+    assert_eq!(
+        call(json!({ "action": "get", "key": "plan" }))
+            .await
+            .unwrap(),
+        "1. Flights\n2. Hotel"
+    );
+    assert_eq!(
+        call(json!({ "action": "get", "key": "budget" }))
+            .await
+            .unwrap(),
+        "No note under budget"
+    );
+    assert_eq!(
+        call(json!({ "action": "get" })).await.unwrap(),
+        "A key is required"
+    );
+    assert_eq!(call(json!({ "action": "list" })).await.unwrap(), "plan");
+
+    // The application sees the notes of the model
+    assert_eq!(scratchpad.get("plan").unwrap(), "1. Flights\n2. Hotel");
+}
+
+#[tokio::test]
+async fn test_scratchpad_notes_stay_out_of_the_conversation() {
+    let server = MockOllama::start().await;
+    server
+        .mock_once(
+            "/api/chat",
+            MockResponse::tool_call(
+                "scratchpad",
+                json!({ "action": "set", "key": "city", "value": "Lisbon" }),
+            ),
+        )
+        .mock("/api/chat", MockResponse::chat("Noted"));
+
+    let scratchpad = Scratchpad::new();
+    let other = Scratchpad::new();
+    let mut coordinator = Coordinator::new(server.client(), "llama3.2".to_string(), vec![])
+        .add_tool(scratchpad.clone());
+    coordinator
+        .chat(vec![ChatMessage::user("Remember Lisbon".to_string())])
+        .await
+        .unwrap();
+
+    assert_eq!(scratchpad.get("city").as_deref(), Some("Lisbon"));
+    assert!(other.notes().is_empty());
+
+    let request = server.requests()[1].json();
+    assert_eq!(request["messages"][2]["content"], "Set city");
+}
@@ -0,0 +1,138 @@
+use std::time::Duration;
+
+use ollama_rs::{
+    cache::semantic::SemanticCache,
+    generation::{
+        completion::{request::GenerationRequest, GenerationContext},
+        images::Image,
+    },
+    models::ModelOptions,
+    test_utils::{MockOllama, MockResponse},
+};
+
+/// Embeds prompts about France and about Germany in nearby directions.
+async fn server() -> MockOllama {
+    let server = MockOllama::start().await;
+    server
+        .mock_with("/api/embed", |request| {
+            let input = request.json()["input"].as_str().unwrap().to_lowercase();
+            let embedding = if input.contains("france") {
+                vec![1.0, 0.1, 0.0]
+            } else if input.contains("french") {
+                vec![0.98, 0.12, 0.0]
+            } else {
+                vec![0.0, 0.1, 1.0]
+            };
+            MockResponse::embeddings(vec![embedding])
+        })
+        .mock_with("/api/generate", |request| {
+            MockResponse::generation(request.json()["prompt"].as_str().unwrap())
+        });
+    server
+}
+
+fn generations(server: &MockOllama) -> usize {
+    server
+        .requests()
+        .iter()
+        .filter(|request| request.path == "/api/generate")
+        .count()
+}
+
+#[tokio::test]
+async fn test_semantic_cache_hit() {
+    let server = server().await;
+    let ollama = server.client();
+    let cache = SemanticCache::new("nomic-embed-text").threshold(0.99);
+
+    let request = |prompt| GenerationRequest::new("llama3.2".to_string(), prompt);
+    let first = cache
+        .generate(&ollama, request("Capital of France?"))
+        .await
+        .unwrap();
+    let rephrased = cache
+        .generate(&ollama, request("What is the French capital?"))
+        .await
+        .unwrap();
+    assert_eq!(rephrased.response, first.response);
+    assert_eq!(generations(&server), 1);
+
+    // Another topic, or another model, misses
+    cache
+        .generate(&ollama, request("Capital of Germany?"))
+        .await
+        .unwrap();
+    cache
+        .generate(
+            &ollama,
+            GenerationRequest::new("qwen2.5".to_string(), "Capital of France?"),
+        )
+        .await
+        .unwrap();
+    assert_eq!(generations(&server), 3);
+    assert_eq!(cache.len(), 3);
+
+    let embed = &server.requests()[0];
+    assert_eq!(embed.json()["model"], "nomic-embed-text");
+}
+
+#[tokio::test]
+async fn test_semantic_cache_bounds() {
+    let server = server().await;
+    let ollama = server.client();
+    let request = |prompt| GenerationRequest::new("llama3.2".to_string(), prompt);
+
+    let cache = SemanticCache::new("nomic-embed-text").max_entries(1);
+    cache.generate(&ollama, request("France")).await.unwrap();
+    cache.generate(&ollama, request("Germany")).await.unwrap();
+    cache.generate(&ollama, request("France")).await.unwrap();
+    assert_eq!(cache.len(), 1);
+    assert_eq!(generations(&server), 3);
+
+    let cache = SemanticCache::new("nomic-embed-text").ttl(Duration::from_millis(50));
+    cache.generate(&ollama, request("France")).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    cache.generate(&ollama, request("France")).await.unwrap();
+    assert_eq!(generations(&server), 5);
+}
+
+#[tokio::test]
+async fn test_semantic_cache_compares_the_rest_of_the_request() {
+    let server = server().await;
+    let ollama = server.client();
+    let cache = SemanticCache::new("nomic-embed-text");
+    let request = || GenerationRequest::new("llama3.2".to_string(), "Capital of France?");
+
+    cache.generate(&ollama, request()).await.unwrap();
+    cache
+        .generate(&ollama, request().system("Answer in French."))
+        .await
+        .unwrap();
+    cache
+        .generate(
+            &ollama,
+            request().options(ModelOptions::default().temperature(0.1)),
+        )
+        .await
+        .unwrap();
+    assert_eq!(generations(&server), 3);
+    cache
+        .generate(&ollama, request().system("Answer in French."))
+        .await
+        .unwrap();
+    assert_eq!(generations(&server), 3);
+
+    // Requests with a context or images are never cached
+    for _ in 0..2 {
+        cache
+            .generate(&ollama, request().context(GenerationContext(vec![1, 2, 3])))
+            .await
+            .unwrap();
+        cache
+            .generate(&ollama, request().add_image(Image::from_base64("aGk=")))
+            .await
+            .unwrap();
+    }
+    assert_eq!(generations(&server), 7);
+    assert_eq!(cache.len(), 3);
+}
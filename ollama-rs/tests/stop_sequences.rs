@@ -0,0 +1,171 @@
+use std::time::{Duration, Instant};
+
+use ollama_rs::{
+    generation::{
+        chat::{request::ChatMessageRequest, ChatMessage},
+        completion::request::GenerationRequest,
+        done_reason::DoneReason,
+        stop::StopSequences,
+    },
+    test_utils::{MockOllama, MockResponse},
+};
+use regex::Regex;
+use tokio_stream::StreamExt;
+
+async fn generate(stops: &StopSequences, tokens: &[&str]) -> (String, Option<DoneReason>) {
+    let server = MockOllama::start().await;
+    server.mock("/api/generate", MockResponse::generation_stream(tokens));
+    generate_with(&server, stops).await
+}
+
+async fn generate_with(server: &MockOllama, stops: &StopSequences) -> (String, Option<DoneReason>) {
+    let request = GenerationRequest::new("llama3.2".into(), "Write a dialogue");
+    let stream = server.client().generate_stream(request).await.unwrap();
+    let mut stream = stops.generation_stream(stream);
+
+    let mut text = String::new();
+    let mut done_reason = None;
+    while let Some(chunk) = stream.next().await {
+        for res in chunk.unwrap() {
+            text.push_str(&res.response);
+            if res.done {
+                done_reason = res.done_reason;
+            }
+        }
+    }
+    (text, done_reason)
+}
+
+#[tokio::test]
+async fn test_stream_is_truncated_at_text() {
+    let stops = StopSequences::new().text("\nUser:");
+    let (text, done_reason) = generate(&stops, &["Bot: Hi", "!", "\nUser:", " Bye"]).await;
+
+    assert_eq!(text, "Bot: Hi!");
+    assert_eq!(done_reason, Some(DoneReason::Stop));
+}
+
+#[tokio::test]
+async fn test_stop_text_split_across_chunks_is_never_yielded() {
+    let server = MockOllama::start().await;
+    server.mock(
+        "/api/generate",
+        MockResponse::generation_stream(&["Hello", " EN", "D", " more"]),
+    );
+
+    let request = GenerationRequest::new("llama3.2".into(), "Say hello");
+    let stream = server.client().generate_stream(request).await.unwrap();
+    let mut stream = StopSequences::new().text("END").generation_stream(stream);
+
+    let mut chunks = Vec::new();
+    while let Some(chunk) = stream.next().await {
+        chunks.extend(chunk.unwrap().into_iter().map(|res| res.response));
+    }
+    assert_eq!(chunks, ["Hello", " ", ""]);
+}
+
+#[tokio::test]
+async fn test_held_back_text_is_released() {
+    let stops = StopSequences::new().text("END");
+    let (text, done_reason) = generate(&stops, &["The E", "nd is near, EN"]).await;
+
+    assert_eq!(text, "The End is near, EN");
+    assert_eq!(done_reason, Some(DoneReason::Stop));
+}
+
+#[tokio::test]
+async fn test_stream_is_truncated_at_pattern() {
+    let stops = StopSequences::new().pattern(Regex::new(r"\[\d+\]").unwrap());
+    let (text, _) = generate(&stops, &["See the", " docs [12]", " for details"]).await;
+
+    assert_eq!(text, "See the docs ");
+}
+
+#[tokio::test]
+async fn test_request_is_aborted() {
+    let server = MockOllama::start().await;
+    let tokens = ["a"; 30];
+    let mut tokens = tokens.to_vec();
+    tokens[2] = "STOP";
+    server.mock(
+        "/api/chat",
+        MockResponse::chat_stream(&tokens).chunk_delay(Duration::from_millis(100)),
+    );
+
+    let request = ChatMessageRequest::new("llama3.2".into(), vec![ChatMessage::user("Hi".into())]);
+    let stream = server
+        .client()
+        .send_chat_messages_stream(request)
+        .await
+        .unwrap();
+    let mut stream = StopSequences::new().text("STOP").chat_stream(stream);
+
+    let start = Instant::now();
+    let mut content = String::new();
+    let mut done = false;
+    while let Some(res) = stream.next().await {
+        let res = res.unwrap();
+        content.push_str(&res.message.content);
+        done = res.done;
+    }
+
+    assert_eq!(content, "aa");
+    assert!(done);
+    assert!(start.elapsed() < Duration::from_secs(1));
+}
+
+#[tokio::test]
+async fn test_pattern_hold_back() {
+    let server = MockOllama::start().await;
+    server.mock(
+        "/api/generate",
+        MockResponse::generation_stream(&["See the docs [", "12", "]", " for details"]),
+    );
+
+    let request = GenerationRequest::new("llama3.2".into(), "Write a dialogue");
+    let stream = server.client().generate_stream(request).await.unwrap();
+    let stops = StopSequences::new()
+        .pattern(Regex::new(r"\[\d+\]").unwrap())
+        .hold_back(4);
+    let mut stream = stops.generation_stream(stream);
+
+    let mut chunks = Vec::new();
+    while let Some(chunk) = stream.next().await {
+        chunks.extend(chunk.unwrap().into_iter().map(|res| res.response));
+    }
+    assert_eq!(chunks.concat(), "See the docs ");
+    assert!(chunks.iter().all(|chunk| !chunk.contains('[')));
+}
+
+#[tokio::test]
+async fn test_held_back_text_is_flushed_when_stream_ends_early() {
+    let server = MockOllama::start().await;
+    server.mock(
+        "/api/generate",
+        MockResponse::stream(["The E", "nd is near, EN"].map(|response| {
+            serde_json::json!({
+                "model": "llama3.2",
+                "created_at": "2024-01-01T00:00:00Z",
+                "response": response,
+                "done": false,
+            })
+        })),
+    );
+
+    let stops = StopSequences::new().text("END");
+    let (text, done_reason) = generate_with(&server, &stops).await;
+
+    assert_eq!(text, "The End is near, EN");
+    assert_eq!(done_reason, None);
+}
+
+#[tokio::test]
+async fn test_stop_text_after_long_response() {
+    let mut tokens = vec!["word "; 2_000];
+    tokens.extend(["E", "N", "D", " more"]);
+    let stops = StopSequences::new().text("END");
+    let (text, done_reason) = generate(&stops, &tokens).await;
+
+    assert_eq!(text, "word ".repeat(2_000));
+    assert_eq!(done_reason, Some(DoneReason::Stop));
+}
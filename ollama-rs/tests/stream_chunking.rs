@@ -0,0 +1,86 @@
+use ollama_rs::{
+    generation::{
+        chat::{request::ChatMessageRequest, ChatMessage},
+        chunking::TextChunker,
+        completion::request::GenerationRequest,
+    },
+    test_utils::{MockOllama, MockResponse},
+};
+use tokio_stream::StreamExt;
+
+async fn generate(chunker: &TextChunker, tokens: &[&str]) -> Vec<String> {
+    let server = MockOllama::start().await;
+    server.mock("/api/generate", MockResponse::generation_stream(tokens));
+
+    let request = GenerationRequest::new("llama3.2".into(), "Tell me a story");
+    let stream = server.client().generate_stream(request).await.unwrap();
+    let mut stream = chunker.generation_stream(stream);
+
+    let mut pieces = Vec::new();
+    while let Some(chunk) = stream.next().await {
+        pieces.extend(chunk.unwrap().into_iter().map(|res| res.response));
+    }
+    pieces
+}
+
+#[tokio::test]
+async fn test_words() {
+    let pieces = generate(
+        &TextChunker::words(),
+        &["Hel", "lo wor", "ld", ", how", " are", " you"],
+    )
+    .await;
+
+    assert_eq!(pieces, ["Hello ", "world, ", "how ", "are you"]);
+}
+
+#[tokio::test]
+async fn test_sentences() {
+    let pieces = generate(
+        &TextChunker::sentences(),
+        &["Pi is 3", ".14. It", " never ends! Or", " does it?"],
+    )
+    .await;
+
+    assert_eq!(pieces, ["Pi is 3.14. ", "It never ends! ", "Or does it?"]);
+}
+
+#[tokio::test]
+async fn test_custom_delimiters() {
+    let pieces = generate(
+        &TextChunker::delimiters([',', ';']),
+        &["red, gr", "een; bl", "ue"],
+    )
+    .await;
+
+    assert_eq!(pieces, ["red, ", "green; ", "blue"]);
+}
+
+#[tokio::test]
+async fn test_chat_stream_keeps_the_final_chunk() {
+    let server = MockOllama::start().await;
+    server.mock(
+        "/api/chat",
+        MockResponse::chat_stream(&["One", " two", " three", ""]),
+    );
+
+    let request =
+        ChatMessageRequest::new("llama3.2".into(), vec![ChatMessage::user("Count".into())]);
+    let stream = server
+        .client()
+        .send_chat_messages_stream(request)
+        .await
+        .unwrap();
+    let mut stream = TextChunker::words().chat_stream(stream);
+
+    let mut pieces = Vec::new();
+    let mut done = false;
+    while let Some(res) = stream.next().await {
+        let res = res.unwrap();
+        pieces.push(res.message.content);
+        done = res.done;
+    }
+
+    assert_eq!(pieces, ["One ", "two ", "three"]);
+    assert!(done);
+}
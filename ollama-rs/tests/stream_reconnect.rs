@@ -0,0 +1,164 @@
+use ollama_rs::{
+    error::OllamaError,
+    generation::{
+        chat::{request::ChatMessageRequest, ChatMessage},
+        completion::request::GenerationRequest,
+        reconnect::StreamEvent,
+    },
+    test_utils::{MockOllama, MockResponse},
+};
+use std::time::Duration;
+use tokio_stream::StreamExt;
+
+/// A streamed response whose connection drops after the given lines.
+fn interrupted(lines: &[String]) -> MockResponse {
+    MockResponse::frames(lines.iter().map(|line| line.clone().into_bytes()))
+        .chunk_delay(Duration::from_millis(1))
+        .interrupt()
+}
+
+fn generation_line(response: &str) -> String {
+    format!(
+        "{{\"model\":\"llama2\",\"created_at\":\"2023-08-04T08:52:19.385406455-07:00\",\"response\":\"{response}\",\"done\":false}}\n"
+    )
+}
+
+fn chat_line(content: &str) -> String {
+    format!(
+        "{{\"model\":\"llama2\",\"created_at\":\"2023-08-04T08:52:19.385406455-07:00\",\"message\":{{\"role\":\"assistant\",\"content\":\"{content}\"}},\"done\":false}}\n"
+    )
+}
+
+fn chat_request() -> ChatMessageRequest {
+    ChatMessageRequest::new(
+        "llama2".to_string(),
+        vec![ChatMessage::user("Why is the sky blue?".to_string())],
+    )
+}
+
+#[tokio::test]
+async fn test_chat_stream_resumes_with_partial_response() {
+    let server = MockOllama::start().await;
+    server.mock_once(
+        "/api/chat",
+        interrupted(&[chat_line("The sky"), chat_line(" is")]),
+    );
+    server.mock("/api/chat", MockResponse::chat_stream(&[" blue", "."]));
+
+    let mut stream = server
+        .client()
+        .send_chat_messages_stream_resumable(chat_request(), 2)
+        .await
+        .unwrap();
+
+    let mut text = String::new();
+    let mut resumed = Vec::new();
+    while let Some(event) = stream.next().await {
+        match event.unwrap() {
+            StreamEvent::Chunk(res) => text.push_str(&res.message.content),
+            StreamEvent::Resumed {
+                attempt,
+                partial_response,
+                error,
+            } => {
+                assert!(matches!(error, Some(OllamaError::StreamInterrupted { .. })));
+                resumed.push((attempt, partial_response));
+            }
+        }
+    }
+
+    assert_eq!(text, "The sky is blue.");
+    assert_eq!(resumed, vec![(1, "The sky is".to_string())]);
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 2);
+    let messages = &requests[1].json()["messages"];
+    assert_eq!(messages.as_array().unwrap().len(), 2);
+    assert_eq!(messages[1]["role"], "assistant");
+    assert_eq!(messages[1]["content"], "The sky is");
+}
+
+#[tokio::test]
+async fn test_generation_stream_resumes_with_partial_response() {
+    let server = MockOllama::start().await;
+    server.mock_once("/api/generate", interrupted(&[generation_line("The sky")]));
+    server.mock(
+        "/api/generate",
+        MockResponse::generation_stream(&[" is", " blue."]),
+    );
+
+    let request = GenerationRequest::new("llama2".to_string(), "Why is the sky blue?");
+    let mut stream = server
+        .client()
+        .generate_stream_resumable(request, 1)
+        .await
+        .unwrap();
+
+    let mut text = String::new();
+    let mut resumed = 0;
+    while let Some(event) = stream.next().await {
+        match event.unwrap() {
+            StreamEvent::Chunk(chunk) => chunk.iter().for_each(|r| text.push_str(&r.response)),
+            StreamEvent::Resumed { .. } => resumed += 1,
+        }
+    }
+
+    assert_eq!(text, "The sky is blue.");
+    assert_eq!(resumed, 1);
+
+    let prompt = server.requests()[1].json()["prompt"].clone();
+    let prompt = prompt.as_str().unwrap();
+    assert!(prompt.starts_with("Why is the sky blue?"));
+    assert!(prompt.contains("The sky"));
+}
+
+#[tokio::test]
+async fn test_resumable_stream_yields_error_when_reconnects_exhausted() {
+    let server = MockOllama::start().await;
+    server.mock("/api/chat", interrupted(&[chat_line("The sky")]));
+
+    let mut stream = server
+        .client()
+        .send_chat_messages_stream_resumable(chat_request(), 1)
+        .await
+        .unwrap();
+
+    let mut resumed = 0;
+    let mut error = None;
+    while let Some(event) = stream.next().await {
+        match event {
+            Ok(StreamEvent::Resumed { .. }) => resumed += 1,
+            Ok(StreamEvent::Chunk(_)) => {}
+            Err(e) => error = Some(e),
+        }
+    }
+
+    assert_eq!(resumed, 1);
+    assert_eq!(server.requests().len(), 2);
+    match error {
+        Some(OllamaError::StreamInterrupted {
+            partial_response, ..
+        }) => assert_eq!(partial_response, "The sky"),
+        other => panic!("expected an interrupted stream, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_resumable_stream_without_drop_has_no_seam() {
+    let server = MockOllama::start().await;
+    server.mock("/api/chat", MockResponse::chat_stream(&["Hello", "!"]));
+
+    let events: Vec<_> = server
+        .client()
+        .send_chat_messages_stream_resumable(chat_request(), 3)
+        .await
+        .unwrap()
+        .collect()
+        .await;
+
+    assert_eq!(events.len(), 2);
+    assert!(events
+        .iter()
+        .all(|e| matches!(e, Ok(StreamEvent::Chunk(_)))));
+    assert_eq!(server.requests().len(), 1);
+}
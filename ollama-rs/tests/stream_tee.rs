@@ -0,0 +1,53 @@
+use ollama_rs::{
+    generation::{
+        chat::{request::ChatMessageRequest, ChatMessage},
+        completion::request::GenerationRequest,
+        tee::TeeStream,
+    },
+    test_utils::{MockOllama, MockResponse},
+};
+
+#[tokio::test]
+async fn test_generation_is_written_and_collected() {
+    let server = MockOllama::start().await;
+    server.mock(
+        "/api/generate",
+        MockResponse::generation_stream(&["The sky", " is", " blue."]),
+    );
+
+    let request = GenerationRequest::new("llama3.2".into(), "Why is the sky blue?");
+    let mut out = Vec::new();
+    let res = server
+        .client()
+        .generate_stream(request)
+        .await
+        .unwrap()
+        .tee_to(&mut out)
+        .await
+        .unwrap();
+
+    assert_eq!(String::from_utf8(out).unwrap(), "The sky is blue.");
+    assert_eq!(res.response, "The sky is blue.");
+    assert!(res.done);
+}
+
+#[tokio::test]
+async fn test_chat_is_written_and_collected() {
+    let server = MockOllama::start().await;
+    server.mock("/api/chat", MockResponse::chat_stream(&["Hel", "lo", "!"]));
+
+    let request = ChatMessageRequest::new("llama3.2".into(), vec![ChatMessage::user("Hi".into())]);
+    let mut out = Vec::new();
+    let res = server
+        .client()
+        .send_chat_messages_stream(request)
+        .await
+        .unwrap()
+        .tee_to(&mut out)
+        .await
+        .unwrap();
+
+    assert_eq!(out, b"Hello!");
+    assert_eq!(res.message.content, "Hello!");
+    assert!(res.done);
+}
@@ -0,0 +1,280 @@
+use ollama_rs::{
+    error::OllamaError,
+    generation::{
+        chat::{request::ChatMessageRequest, ChatMessage},
+        completion::request::GenerationRequest,
+    },
+    test_utils::{MockOllama, MockResponse},
+};
+use std::time::Duration;
+use tokio_stream::StreamExt;
+
+/// Starts a server streaming the given frames, flushing each one separately.
+/// If `complete` is false, the connection is dropped before the end of the body.
+async fn serve_frames(path: &str, frames: Vec<Vec<u8>>, complete: bool) -> MockOllama {
+    let mut response = MockResponse::frames(frames).chunk_delay(Duration::from_millis(1));
+    if !complete {
+        response = response.interrupt();
+    }
+
+    let server = MockOllama::start().await;
+    server.mock(path, response);
+    server
+}
+
+fn generation_line(response: &str, done: bool) -> String {
+    format!(
+        "{{\"model\":\"llama2\",\"created_at\":\"2023-08-04T08:52:19.385406455-07:00\",\"response\":\"{response}\",\"done\":{done}}}\n"
+    )
+}
+
+fn chat_line(content: &str, done: bool) -> String {
+    format!(
+        "{{\"model\":\"llama2\",\"created_at\":\"2023-08-04T08:52:19.385406455-07:00\",\"message\":{{\"role\":\"assistant\",\"content\":\"{content}\"}},\"done\":{done}}}\n"
+    )
+}
+
+#[tokio::test]
+async fn test_generation_stream_interrupted_keeps_partial_response() {
+    let server = serve_frames(
+        "/api/generate",
+        vec![
+            generation_line("The sky", false).into_bytes(),
+            generation_line(" is", false).into_bytes(),
+        ],
+        false,
+    )
+    .await;
+
+    let mut stream = server
+        .client()
+        .generate_stream(GenerationRequest::new("llama2".to_string(), "Why?"))
+        .await
+        .unwrap();
+
+    let mut error = None;
+    while let Some(res) = stream.next().await {
+        if let Err(e) = res {
+            error = Some(e);
+        }
+    }
+
+    match error {
+        Some(OllamaError::StreamInterrupted {
+            partial_response,
+            last_chunk,
+            ..
+        }) => {
+            assert_eq!(partial_response, "The sky is");
+            assert!(last_chunk.unwrap().contains("\" is\""));
+        }
+        e => panic!("unexpected result: {e:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_chat_stream_interrupted_keeps_partial_response() {
+    let server = serve_frames(
+        "/api/chat",
+        vec![
+            chat_line("Hello", false).into_bytes(),
+            chat_line(" there", false).into_bytes(),
+        ],
+        false,
+    )
+    .await;
+
+    let mut stream = server
+        .client()
+        .send_chat_messages_stream(ChatMessageRequest::new(
+            "llama2".to_string(),
+            vec![ChatMessage::user("Hi".to_string())],
+        ))
+        .await
+        .unwrap();
+
+    let mut error = None;
+    while let Some(res) = stream.next().await {
+        if let Err(e) = res {
+            error = Some(e);
+        }
+    }
+
+    match error {
+        Some(OllamaError::StreamInterrupted {
+            partial_response, ..
+        }) => assert_eq!(partial_response, "Hello there"),
+        e => panic!("unexpected result: {e:?}"),
+    }
+}
+
+/// A small deterministic pseudo-random generator, so failures can be reproduced.
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self, bound: usize) -> usize {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        ((self.0 >> 33) as usize) % bound
+    }
+}
+
+const TOKENS: [&str; 8] = ["The", " sky", " is", " blue", " ☀️", " 日本", " é", "!\\n"];
+
+/// Splits `bytes` at random positions, which may fall inside a JSON object or a UTF-8 character.
+fn split_randomly(bytes: &[u8], rng: &mut Lcg) -> Vec<Vec<u8>> {
+    let mut frames = Vec::new();
+    let mut rest = bytes;
+    while !rest.is_empty() {
+        let len = (rng.next(48) + 1).min(rest.len());
+        frames.push(rest[..len].to_vec());
+        rest = &rest[len..];
+    }
+    frames
+}
+
+#[tokio::test]
+async fn test_generation_stream_reassembles_split_frames() {
+    let mut rng = Lcg(42);
+
+    for _ in 0..25 {
+        let tokens = (0..rng.next(10) + 1)
+            .map(|_| TOKENS[rng.next(TOKENS.len())])
+            .collect::<Vec<_>>();
+
+        let mut body = String::new();
+        for (i, token) in tokens.iter().enumerate() {
+            body.push_str(&generation_line(token, i + 1 == tokens.len()));
+        }
+
+        let server = serve_frames(
+            "/api/generate",
+            split_randomly(body.as_bytes(), &mut rng),
+            true,
+        )
+        .await;
+        let mut stream = server
+            .client()
+            .generate_stream(GenerationRequest::new("llama2".to_string(), "Why?"))
+            .await
+            .unwrap();
+
+        let mut received = Vec::new();
+        while let Some(res) = stream.next().await {
+            received.extend(res.unwrap());
+        }
+
+        assert_eq!(received.len(), tokens.len());
+        assert!(received.last().unwrap().done);
+        assert_eq!(
+            received
+                .iter()
+                .map(|r| r.response.as_str())
+                .collect::<String>(),
+            tokens.concat().replace("\\n", "\n")
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_chat_stream_reassembles_split_frames() {
+    let mut rng = Lcg(7);
+
+    for _ in 0..25 {
+        let tokens = (0..rng.next(10) + 1)
+            .map(|_| TOKENS[rng.next(TOKENS.len())])
+            .collect::<Vec<_>>();
+
+        let mut body = String::new();
+        for (i, token) in tokens.iter().enumerate() {
+            body.push_str(&chat_line(token, i + 1 == tokens.len()));
+        }
+
+        let server =
+            serve_frames("/api/chat", split_randomly(body.as_bytes(), &mut rng), true).await;
+        let mut stream = server
+            .client()
+            .send_chat_messages_stream(ChatMessageRequest::new(
+                "llama2".to_string(),
+                vec![ChatMessage::user("Hi".to_string())],
+            ))
+            .await
+            .unwrap();
+
+        let mut received = Vec::new();
+        while let Some(res) = stream.next().await {
+            received.push(res.unwrap());
+        }
+
+        assert_eq!(received.len(), tokens.len());
+        assert_eq!(
+            received
+                .iter()
+                .map(|r| r.message.content.as_str())
+                .collect::<String>(),
+            tokens.concat().replace("\\n", "\n")
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_stream_handles_many_objects_per_frame() {
+    let body = [
+        generation_line("a", false),
+        generation_line("b", false),
+        generation_line("c", true),
+    ]
+    .concat();
+
+    // No trailing newline after the last object
+    let server = serve_frames(
+        "/api/generate",
+        vec![body.trim_end().as_bytes().to_vec()],
+        true,
+    )
+    .await;
+    let mut stream = server
+        .client()
+        .generate_stream(GenerationRequest::new("llama2".to_string(), "Why?"))
+        .await
+        .unwrap();
+
+    let mut received = Vec::new();
+    while let Some(res) = stream.next().await {
+        received.extend(res.unwrap());
+    }
+
+    assert_eq!(
+        received
+            .iter()
+            .map(|r| r.response.as_str())
+            .collect::<String>(),
+        "abc"
+    );
+}
+
+#[tokio::test]
+async fn test_stream_surfaces_server_errors() {
+    let server = serve_frames(
+        "/api/generate",
+        vec![
+            generation_line("a", false).into_bytes(),
+            b"{\"error\":\"something went wrong\"}\n".to_vec(),
+        ],
+        true,
+    )
+    .await;
+    let mut stream = server
+        .client()
+        .generate_stream(GenerationRequest::new("llama2".to_string(), "Why?"))
+        .await
+        .unwrap();
+
+    assert!(stream.next().await.unwrap().is_ok());
+    match stream.next().await.unwrap() {
+        Err(OllamaError::InternalError(e)) => assert_eq!(e.message, "something went wrong"),
+        r => panic!("unexpected result: {r:?}"),
+    }
+}
@@ -0,0 +1,164 @@
+use ollama_rs::{
+    generation::{
+        chat::{request::ChatMessageRequest, ChatMessage},
+        parameters::JsonSchema,
+        structured::Extraction,
+    },
+    test_utils::{MockOllama, MockResponse},
+};
+use serde::Deserialize;
+
+#[derive(Debug, PartialEq, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+enum Sentiment {
+    Positive,
+    Negative,
+    /// Neither positive nor negative
+    Neutral,
+}
+
+#[tokio::test]
+async fn test_classify() {
+    let server = MockOllama::start().await;
+    server.mock(
+        "/api/generate",
+        MockResponse::generation(r#"{ "label": "negative" }"#),
+    );
+
+    let sentiment: Sentiment = server
+        .client()
+        .classify("llama3.2".to_string(), "The delivery was late again.")
+        .await
+        .unwrap();
+    assert_eq!(sentiment, Sentiment::Negative);
+
+    let request = server.requests()[0].json();
+    let prompt = request["prompt"].as_str().unwrap();
+    assert!(prompt.contains("- positive\n- negative\n- neutral (Neither positive nor negative)\n"));
+    assert!(prompt.ends_with("The delivery was late again."));
+    assert_eq!(request["format"]["required"][0], "label");
+}
+
+#[tokio::test]
+async fn test_classify_unknown_label() {
+    let server = MockOllama::start().await;
+    server.mock(
+        "/api/generate",
+        MockResponse::generation(r#"{ "label": "mixed" }"#),
+    );
+
+    let res = server
+        .client()
+        .classify::<Sentiment>("llama3.2".to_string(), "Meh.")
+        .await;
+    assert!(res.is_err());
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Deserialize, JsonSchema)]
+struct Contact {
+    name: String,
+    /// The email address, if any
+    email: Option<String>,
+}
+
+#[tokio::test]
+async fn test_extract() {
+    let server = MockOllama::start().await;
+    server.mock(
+        "/api/generate",
+        MockResponse::generation(r#"{ "name": "Ada", "email": "ada@example.com" }"#),
+    );
+
+    let contact: Contact = server
+        .client()
+        .extract(
+            "llama3.2".to_string(),
+            "Hi, I'm Ada, reach me at ada@example.com",
+        )
+        .await
+        .unwrap();
+    assert_eq!(contact.name, "Ada");
+    assert_eq!(contact.email.as_deref(), Some("ada@example.com"));
+
+    let request = server.requests()[0].json();
+    let prompt = request["prompt"].as_str().unwrap();
+    assert!(prompt.starts_with("Extract the following fields"));
+    assert!(prompt.contains("- name\n- email: The email address, if any\n"));
+    assert!(prompt.ends_with("reach me at ada@example.com"));
+}
+
+#[tokio::test]
+async fn test_extract_retries_unparsable_responses() {
+    let server = MockOllama::start().await;
+    server
+        .mock_once("/api/generate", MockResponse::generation("{ \"name\": "))
+        .mock_once(
+            "/api/generate",
+            MockResponse::generation("{ \"email\": null }"),
+        )
+        .mock(
+            "/api/generate",
+            MockResponse::generation("{ \"name\": \"Ada\" }"),
+        );
+
+    let contact: Contact = server
+        .client()
+        .extract_with(
+            Extraction::new("llama3.2".to_string(), "I'm Ada").instruction("Find the contact."),
+        )
+        .await
+        .unwrap();
+    assert_eq!(contact.name, "Ada");
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 3);
+    assert!(requests[0].json()["prompt"]
+        .as_str()
+        .unwrap()
+        .starts_with("Find the contact.\n\nFields:"));
+
+    let server = MockOllama::start().await;
+    server.mock("/api/generate", MockResponse::generation("{}"));
+
+    let res = server
+        .client()
+        .extract_with::<Contact>(
+            Extraction::new("llama3.2".to_string(), "I'm Ada").parse_retries(0),
+        )
+        .await;
+    assert!(res.is_err());
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn test_chat_structured() {
+    let server = MockOllama::start().await;
+    server
+        .mock_once("/api/chat", MockResponse::chat("{ \"name\": "))
+        .mock(
+            "/api/chat",
+            MockResponse::chat("{ \"name\": \"Ada\", \"email\": \"ada@example.com\" }"),
+        );
+
+    let request = ChatMessageRequest::new(
+        "llama3.2".to_string(),
+        vec![ChatMessage::user("I'm Ada, ada@example.com".to_string())],
+    );
+    let contact: Contact = server.client().chat_structured(request).await.unwrap();
+    assert_eq!(contact.name, "Ada");
+    assert_eq!(contact.email.as_deref(), Some("ada@example.com"));
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 2);
+    let format = &requests[0].json()["format"];
+    assert_eq!(format["type"], "object");
+    assert_eq!(format["required"][0], "name");
+}
+
+#[test]
+fn test_chat_request_structured_format() {
+    let request = ChatMessageRequest::new("llama3.2".to_string(), vec![]).structured::<Contact>();
+    let request = serde_json::to_value(request).unwrap();
+    assert!(request["format"]["properties"]["email"].is_object());
+}
@@ -0,0 +1,179 @@
+use ollama_rs::{
+    coordinator::Coordinator,
+    error::OllamaError,
+    generation::{
+        chat::{request::ChatMessageRequest, ChatMessage},
+        completion::request::GenerationRequest,
+        parameters::Think,
+    },
+    test_utils::{MockOllama, MockResponse},
+    version::Feature,
+    Ollama,
+};
+use serde_json::json;
+
+#[test]
+fn test_think_serialization() {
+    assert_eq!(serde_json::to_value(Think::Disabled).unwrap(), json!(false));
+    assert_eq!(serde_json::to_value(Think::Enabled).unwrap(), json!(true));
+    assert_eq!(serde_json::to_value(Think::High).unwrap(), json!("high"));
+
+    let request = ChatMessageRequest::new("qwen3".to_string(), vec![]);
+    assert!(serde_json::to_value(&request)
+        .unwrap()
+        .get("think")
+        .is_none());
+    let request = request.think(Think::Low);
+    assert_eq!(serde_json::to_value(&request).unwrap()["think"], "low");
+}
+
+#[tokio::test]
+async fn test_chat_returns_thinking() {
+    let server = MockOllama::start().await;
+    server.mock(
+        "/api/chat",
+        MockResponse::json(json!({
+            "model": "qwen3",
+            "created_at": "2024-01-01T00:00:00Z",
+            "message": {
+                "role": "assistant",
+                "content": "4",
+                "thinking": "2 + 2 is 4.",
+            },
+            "done": true,
+        })),
+    );
+
+    let res = server
+        .client()
+        .send_chat_messages(
+            ChatMessageRequest::new(
+                "qwen3".to_string(),
+                vec![ChatMessage::user("2 + 2?".to_string())],
+            )
+            .think(true),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.message.content, "4");
+    assert_eq!(res.message.thinking.as_deref(), Some("2 + 2 is 4."));
+    assert_eq!(server.requests()[0].json()["think"], true);
+}
+
+#[tokio::test]
+async fn test_generate_returns_thinking() {
+    let server = MockOllama::start().await;
+    server.mock(
+        "/api/generate",
+        MockResponse::json(json!({
+            "model": "qwen3",
+            "created_at": "2024-01-01T00:00:00Z",
+            "response": "Paris",
+            "thinking": "The capital of France is Paris.",
+            "done": true,
+        })),
+    );
+
+    let res = server
+        .client()
+        .generate(
+            GenerationRequest::new("qwen3".to_string(), "Capital of France?").think(Think::Medium),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.response, "Paris");
+    assert_eq!(
+        res.thinking.as_deref(),
+        Some("The capital of France is Paris.")
+    );
+    assert_eq!(server.requests()[0].json()["think"], "medium");
+}
+
+#[tokio::test]
+async fn test_thinking_requires_recent_server() {
+    let server = MockOllama::start().await;
+    server.mock(
+        "/api/version",
+        MockResponse::json(json!({ "version": "0.8.0" })),
+    );
+    let ollama = Ollama::builder()
+        .url(server.url())
+        .version_check(true)
+        .build()
+        .unwrap();
+
+    let err = ollama
+        .generate(GenerationRequest::new("qwen3".to_string(), "Hi").think(Think::High))
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        OllamaError::Unsupported {
+            feature: Feature::Thinking,
+            ..
+        }
+    ));
+
+    // Disabling thinking is ignored by older servers, so it isn't checked
+    server.mock("/api/generate", MockResponse::generation("Hello"));
+    ollama
+        .generate(GenerationRequest::new("qwen3".to_string(), "Hi").think(false))
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_coordinator_passes_think() {
+    let server = MockOllama::start().await;
+    server.mock("/api/chat", MockResponse::chat("Hello"));
+
+    let mut coordinator =
+        Coordinator::new(server.client(), "qwen3".to_string(), vec![]).think(Think::Disabled);
+    coordinator
+        .chat(vec![ChatMessage::user("Hi".to_string())])
+        .await
+        .unwrap();
+
+    assert_eq!(server.requests()[0].json()["think"], false);
+}
+
+#[tokio::test]
+async fn test_coordinator_stream_keeps_thinking() {
+    use tokio_stream::StreamExt;
+
+    let server = MockOllama::start().await;
+    let chunk = |content: &str, thinking: &str, done: bool| {
+        json!({
+            "model": "qwen3",
+            "created_at": "2024-01-01T00:00:00Z",
+            "message": { "role": "assistant", "content": content, "thinking": thinking },
+            "done": done,
+        })
+    };
+    server
+        .mock_once(
+            "/api/chat",
+            MockResponse::stream([
+                chunk("", "2 + 2", false),
+                chunk("", " is 4.", false),
+                chunk("4", "", false),
+                chunk("", "", true),
+            ]),
+        )
+        .mock("/api/chat", MockResponse::chat("Sure."));
+
+    let mut coordinator = Coordinator::new(server.client(), "qwen3".to_string(), vec![]);
+    let stream = coordinator.chat_stream(vec![ChatMessage::user("2 + 2?".to_string())]);
+    let responses: Vec<_> = stream.collect().await;
+    assert!(responses.iter().all(Result::is_ok));
+    coordinator
+        .chat(vec![ChatMessage::user("Thanks!".to_string())])
+        .await
+        .unwrap();
+
+    let answer = &server.requests()[1].json()["messages"][1];
+    assert_eq!(answer["content"], "4");
+    assert_eq!(answer["thinking"], "2 + 2 is 4.");
+}
@@ -0,0 +1,125 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use ollama_rs::{
+    generation::completion::{request::GenerationRequest, GenerationResponse},
+    test_utils::{MockOllama, MockResponse},
+    Timestamp,
+};
+use serde_json::json;
+
+fn parse(s: &str) -> Option<Timestamp> {
+    let res = json!({ "model": "llama2", "created_at": s, "response": "", "done": false });
+    serde_json::from_value::<GenerationResponse>(res)
+        .ok()
+        .map(|res| res.created_at)
+}
+
+#[test]
+fn test_parse_timestamps() {
+    let ts = parse("2023-08-04T08:52:19.385406455-07:00").unwrap();
+    assert_eq!(
+        SystemTime::from(ts.clone()),
+        UNIX_EPOCH + Duration::new(1_691_164_339, 385_406_455)
+    );
+
+    // The same instant in UTC
+    let utc = parse("2023-08-04T15:52:19.385406455Z").unwrap();
+    assert_eq!(ts, utc);
+
+    let later = parse("2023-08-04T15:52:20.5Z").unwrap();
+    assert!(later > ts);
+    assert_eq!(
+        SystemTime::from(later)
+            .duration_since(SystemTime::from(ts))
+            .unwrap(),
+        Duration::from_nanos(1_114_593_545)
+    );
+
+    assert_eq!(
+        SystemTime::from(parse("1969-12-31T23:59:59Z").unwrap()),
+        UNIX_EPOCH - Duration::from_secs(1)
+    );
+    assert_eq!(
+        SystemTime::from(parse("2024-02-29T00:00:00+00:00").unwrap()),
+        UNIX_EPOCH + Duration::from_secs(1_709_164_800)
+    );
+
+    for invalid in [
+        "",
+        "2023-08-04",
+        "2023-13-04T08:52:19Z",
+        "2023-08-04T08:52:19",
+        "2023-08-04T08:52:19.Z",
+        "2023-08-04T08:52:19+0700",
+        "2023-08-04T08:52:1é+07:00",
+    ] {
+        assert!(parse(invalid).is_none(), "{invalid}");
+        assert!(Timestamp::parse(invalid).is_none(), "{invalid}");
+    }
+}
+
+#[test]
+fn test_timestamp_accessors() {
+    let ts = Timestamp::parse("2023-08-04T08:52:19.385406455-07:00").unwrap();
+    assert_eq!(ts.to_string(), "2023-08-04T08:52:19.385406455-07:00");
+    assert_eq!(ts.utc_offset_seconds(), -7 * 3600);
+    assert_eq!(ts.unix_timestamp(), 1_691_164_339);
+    assert_eq!(ts.unix_timestamp_nanos(), 1_691_164_339_385_406_455);
+
+    let later = Timestamp::from_unix_timestamp(1_691_164_340);
+    assert_eq!(later.to_string(), "2023-08-04T15:52:20Z");
+    assert_eq!(
+        later.duration_since(&ts),
+        Some(Duration::from_nanos(614_593_545))
+    );
+    assert_eq!(ts.duration_since(&later), None);
+
+    let time: time::OffsetDateTime = ts.clone().into();
+    assert_eq!(time.offset().whole_hours(), -7);
+    assert_eq!(Timestamp::from(time), ts);
+    assert_eq!(Timestamp::from(SystemTime::from(ts.clone())), ts);
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn test_chrono_conversions() {
+    let ts = Timestamp::parse("2023-08-04T08:52:19.385406455-07:00").unwrap();
+
+    let chrono: chrono::DateTime<chrono::FixedOffset> = ts.clone().into();
+    assert_eq!(chrono.to_rfc3339(), "2023-08-04T08:52:19.385406455-07:00");
+
+    let back = Timestamp::from(chrono);
+    assert_eq!(back, ts);
+    assert_eq!(back.utc_offset_seconds(), -7 * 3600);
+    assert_eq!(Timestamp::from(chrono.to_utc()), ts);
+}
+
+#[tokio::test]
+async fn test_response_created_at() {
+    let server = MockOllama::start().await;
+    server.mock(
+        "/api/generate",
+        MockResponse::json(json!({
+            "model": "llama2",
+            "created_at": "2023-08-04T08:52:19.385406455-07:00",
+            "response": "Blue",
+            "done": true
+        })),
+    );
+
+    let res = server
+        .client()
+        .generate(GenerationRequest::new("llama2".to_string(), "Why?"))
+        .await
+        .unwrap();
+
+    assert_eq!(res.created_at.utc_offset_seconds(), -7 * 3600);
+    assert_eq!(
+        SystemTime::from(res.created_at.clone()),
+        UNIX_EPOCH + Duration::new(1_691_164_339, 385_406_455)
+    );
+    assert_eq!(
+        serde_json::to_value(&res).unwrap()["created_at"],
+        "2023-08-04T08:52:19.385406455-07:00"
+    );
+}
@@ -0,0 +1,111 @@
+use std::sync::{Arc, Mutex};
+
+use ollama_rs::{
+    coordinator::{Coordinator, ToolEvent},
+    generation::{chat::ChatMessage, tools::implementations::Calculator},
+    test_utils::{MockOllama, MockResponse},
+};
+use serde_json::json;
+
+#[tokio::test]
+async fn test_coordinator_correlates_tool_calls() {
+    let server = MockOllama::start().await;
+    server
+        .mock_once(
+            "/api/chat",
+            MockResponse::tool_calls([
+                ("calculator", json!({ "expression": "2 + 2" })),
+                ("calculator", json!({ "expression": "3 * 3" })),
+            ]),
+        )
+        .mock("/api/chat", MockResponse::chat("4 and 9"));
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let recorded = events.clone();
+    let mut coordinator = Coordinator::new(server.client(), "llama3.2".to_string(), vec![])
+        .add_tool(Calculator {})
+        .on_tool_event(move |event| {
+            let kind = match event {
+                ToolEvent::Call { .. } => "call",
+                ToolEvent::Result { .. } => "result",
+                ToolEvent::Error { .. } => "error",
+            };
+            recorded
+                .lock()
+                .unwrap()
+                .push((kind, event.id().to_string(), event.name().to_string()));
+        });
+
+    coordinator
+        .chat(vec![ChatMessage::user("2 + 2 and 3 * 3?".to_string())])
+        .await
+        .unwrap();
+
+    let events = events.lock().unwrap();
+    let kinds: Vec<_> = events.iter().map(|(kind, _, _)| *kind).collect();
+    assert_eq!(kinds, ["call", "result", "call", "result"]);
+    assert!(events.iter().all(|(_, _, name)| name == "calculator"));
+    assert_eq!(events[0].1, events[1].1);
+    assert_eq!(events[2].1, events[3].1);
+    assert_ne!(events[0].1, events[2].1);
+
+    // The ids are sent back with the calls and their results
+    let request = server.requests()[1].json();
+    let messages = request["messages"].as_array().unwrap();
+    let calls = messages[1]["tool_calls"].as_array().unwrap();
+    assert_eq!(calls[0]["id"], events[0].1);
+    assert_eq!(calls[1]["id"], events[2].1);
+    assert_eq!(messages[2]["role"], "tool");
+    assert_eq!(messages[2]["tool_call_id"], events[0].1);
+    assert_eq!(messages[3]["tool_call_id"], events[2].1);
+}
+
+#[tokio::test]
+async fn test_coordinator_keeps_tool_call_ids() {
+    let server = MockOllama::start().await;
+    server
+        .mock_once(
+            "/api/chat",
+            MockResponse::json(json!({
+                "model": "llama3.2",
+                "created_at": "2024-01-01T00:00:00Z",
+                "message": {
+                    "role": "assistant",
+                    "content": "",
+                    "tool_calls": [{
+                        "id": "call_abc",
+                        "function": { "name": "calculator", "arguments": { "expression": "1 + 1" } },
+                    }],
+                },
+                "done": true,
+            })),
+        )
+        .mock("/api/chat", MockResponse::chat("2"));
+
+    let mut coordinator =
+        Coordinator::new(server.client(), "llama3.2".to_string(), vec![]).add_tool(Calculator {});
+    coordinator
+        .chat(vec![ChatMessage::user("1 + 1?".to_string())])
+        .await
+        .unwrap();
+
+    let request = server.requests()[1].json();
+    let messages = request["messages"].as_array().unwrap();
+    assert_eq!(messages[1]["tool_calls"][0]["id"], "call_abc");
+    assert_eq!(messages[2]["tool_call_id"], "call_abc");
+}
+
+#[test]
+fn test_tool_call_id_serialization() {
+    let message = ChatMessage::tool("4".to_string());
+    assert!(serde_json::to_value(&message)
+        .unwrap()
+        .get("tool_call_id")
+        .is_none());
+
+    let message = message.with_tool_call_id("call_1");
+    assert_eq!(
+        serde_json::to_value(&message).unwrap()["tool_call_id"],
+        "call_1"
+    );
+}
@@ -0,0 +1,105 @@
+use ollama_rs::{
+    coordinator::Coordinator,
+    error::{OllamaError, ToolCallError},
+    generation::{
+        chat::{request::ChatMessageRequest, ChatMessage},
+        tools::{
+            implementations::{Calculator, DDGSearcher},
+            ToolChoice,
+        },
+    },
+    test_utils::{MockOllama, MockResponse},
+};
+use serde_json::json;
+
+fn question() -> Vec<ChatMessage> {
+    vec![ChatMessage::user("What is 2 + 2?".to_string())]
+}
+
+fn tool_names(request: &serde_json::Value) -> Vec<String> {
+    request["tools"]
+        .as_array()
+        .map(|tools| {
+            tools
+                .iter()
+                .map(|t| t["function"]["name"].as_str().unwrap().to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[test]
+fn test_tool_choice_serialization() {
+    let request = |choice| {
+        serde_json::to_value(
+            ChatMessageRequest::new("llama3.2".to_string(), vec![]).tool_choice(choice),
+        )
+        .unwrap()["tool_choice"]
+            .clone()
+    };
+
+    assert_eq!(request(ToolChoice::Auto), "auto");
+    assert_eq!(request(ToolChoice::None), "none");
+    assert_eq!(request(ToolChoice::Required), "required");
+    assert_eq!(
+        request(ToolChoice::Tool("calculator".to_string())),
+        json!({ "type": "function", "function": { "name": "calculator" } })
+    );
+
+    let request =
+        serde_json::to_value(ChatMessageRequest::new("llama3.2".to_string(), vec![])).unwrap();
+    assert!(request.get("tool_choice").is_none());
+}
+
+#[tokio::test]
+async fn test_coordinator_specific_tool() {
+    let server = MockOllama::start().await;
+    server
+        .mock_once(
+            "/api/chat",
+            MockResponse::tool_call("calculator", json!({ "expression": "2 + 2" })),
+        )
+        .mock("/api/chat", MockResponse::chat("4"));
+
+    let mut coordinator = Coordinator::new(server.client(), "llama3.2".to_string(), vec![])
+        .add_tool(Calculator {})
+        .add_tool(DDGSearcher::new())
+        .tool_choice(ToolChoice::Tool("calculator".to_string()));
+
+    coordinator.chat(question()).await.unwrap();
+
+    let requests = server.requests();
+    let first = requests[0].json();
+    assert_eq!(tool_names(&first), ["calculator"]);
+    assert_eq!(first["tool_choice"]["function"]["name"], "calculator");
+
+    // Once the tool has been called, the model is free to answer
+    let second = requests[1].json();
+    assert_eq!(tool_names(&second).len(), 2);
+    assert!(second.get("tool_choice").is_none());
+}
+
+#[tokio::test]
+async fn test_coordinator_tool_choice_none_and_required() {
+    let server = MockOllama::start().await;
+    server.mock("/api/chat", MockResponse::chat("4"));
+
+    let mut coordinator = Coordinator::new(server.client(), "llama3.2".to_string(), vec![])
+        .add_tool(Calculator {})
+        .tool_choice(ToolChoice::None);
+    coordinator.chat(question()).await.unwrap();
+    assert!(tool_names(&server.requests()[0].json()).is_empty());
+
+    let mut coordinator = Coordinator::new(server.client(), "llama3.2".to_string(), vec![])
+        .add_tool(Calculator {})
+        .tool_choice(ToolChoice::Required);
+    let err = coordinator.chat(question()).await.unwrap_err();
+    assert!(
+        matches!(
+            err,
+            OllamaError::ToolCallError(ToolCallError::ToolCallRequired)
+        ),
+        "{err:?}"
+    );
+    assert_eq!(server.requests()[1].json()["tool_choice"], "required");
+}
@@ -0,0 +1,138 @@
+use ollama_rs::{
+    coordinator::Coordinator,
+    generation::{
+        chat::ChatMessage,
+        tools::{
+            emulation::{parse_tool_calls, ToolCallStrategy},
+            implementations::Calculator,
+            ToolSet,
+        },
+    },
+    test_utils::{MockOllama, MockResponse},
+};
+use serde_json::json;
+use tokio_stream::StreamExt;
+
+#[test]
+fn test_parse_tool_calls() {
+    let tools = ToolSet::new().add_tool(Calculator {}).infos();
+
+    let calls = parse_tool_calls(
+        "Sure!\n```json\n{\"name\": \"calculator\", \"arguments\": {\"expression\": \"2 + 2\"}}\n```",
+        &tools,
+    );
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].function.name, "calculator");
+    assert_eq!(
+        calls[0].function.arguments,
+        json!({ "expression": "2 + 2" })
+    );
+
+    let calls = parse_tool_calls(
+        r#"[{"tool": "calculator", "parameters": {"expression": "1"}}, {"name": "unknown", "arguments": {}}]"#,
+        &tools,
+    );
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].function.arguments, json!({ "expression": "1" }));
+
+    assert!(parse_tool_calls("The answer is {probably} 4", &tools).is_empty());
+    assert!(parse_tool_calls("The answer is 4", &tools).is_empty());
+}
+
+#[tokio::test]
+async fn test_emulated_tool_call_scenario() {
+    let server = MockOllama::start().await;
+    server
+        .mock_once(
+            "/api/chat",
+            MockResponse::chat(r#"{"name": "calculator", "arguments": {"expression": "2 + 2"}}"#),
+        )
+        .mock("/api/chat", MockResponse::chat("2 + 2 = 4"));
+
+    let mut coordinator = Coordinator::new(server.client(), "phi".to_string(), vec![])
+        .add_tool(Calculator {})
+        .tool_call_strategy(ToolCallStrategy::Emulated);
+
+    let res = coordinator
+        .chat(vec![
+            ChatMessage::system("Be brief.".to_string()),
+            ChatMessage::user("What is 2 + 2?".to_string()),
+        ])
+        .await
+        .unwrap();
+    assert_eq!(res.message.content, "2 + 2 = 4");
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 2);
+
+    // The tools are described in the system prompt instead of being sent to Ollama
+    let first = requests[0].json();
+    assert!(first.get("tools").is_none());
+    let system = first["messages"][0]["content"].as_str().unwrap();
+    assert!(system.starts_with("Be brief.\n\n"));
+    assert!(system.contains("\"calculator\""));
+
+    // The result of the tool is sent as a user message
+    let messages = requests[1].json()["messages"].clone();
+    let last = messages.as_array().unwrap().last().unwrap().clone();
+    assert_eq!(last["role"], "user");
+    assert_eq!(last["content"], "Result of the tool call:\n4");
+}
+
+#[tokio::test]
+async fn test_emulated_tool_calls_are_not_streamed_as_text() {
+    let server = MockOllama::start().await;
+    server
+        .mock_once(
+            "/api/chat",
+            MockResponse::chat_stream(&[
+                "{\"name\": \"calculator\", ",
+                "\"arguments\": {\"expression\": \"2 + 2\"}}",
+            ]),
+        )
+        .mock_once(
+            "/api/chat",
+            MockResponse::chat_stream(&["2 + 2", " = 4", ""]),
+        )
+        .mock(
+            "/api/chat",
+            MockResponse::chat_stream(&["[1, 2]", " are numbers"]),
+        );
+
+    let mut coordinator = Coordinator::new(server.client(), "phi".to_string(), vec![])
+        .add_tool(Calculator {})
+        .tool_call_strategy(ToolCallStrategy::Emulated);
+
+    let responses: Vec<_> = coordinator
+        .chat_stream(vec![ChatMessage::user("What is 2 + 2?".to_string())])
+        .collect::<Result<_, _>>()
+        .await
+        .unwrap();
+    let text: String = responses
+        .iter()
+        .map(|r| r.message.content.as_str())
+        .collect();
+    assert_eq!(text, "2 + 2 = 4");
+
+    // The call comes with the last chunk of its turn
+    let calls: Vec<_> = responses
+        .iter()
+        .flat_map(|r| &r.message.tool_calls)
+        .collect();
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].function.name, "calculator");
+    assert!(calls[0].id.is_some());
+    assert!(responses[0].done);
+
+    // Text which turns out not to be a call is streamed at the end of the turn
+    let responses: Vec<_> = coordinator
+        .chat_stream(vec![ChatMessage::user("Some numbers?".to_string())])
+        .collect::<Result<_, _>>()
+        .await
+        .unwrap();
+    let text: String = responses
+        .iter()
+        .map(|r| r.message.content.as_str())
+        .collect();
+    assert_eq!(text, "[1, 2] are numbers");
+}
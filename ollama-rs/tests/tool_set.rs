@@ -0,0 +1,75 @@
+use ollama_rs::{
+    error::{OllamaError, ToolCallError},
+    generation::tools::{implementations::Calculator, DynTool, Tool, ToolSet},
+};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::json;
+
+#[derive(Deserialize, JsonSchema)]
+struct EchoParams {
+    #[schemars(description = "The text to echo back")]
+    text: String,
+}
+
+struct Echo;
+
+impl Tool for Echo {
+    type Params = EchoParams;
+
+    fn name() -> &'static str {
+        "echo"
+    }
+
+    fn description() -> &'static str {
+        "Echoes the given text"
+    }
+
+    async fn call(
+        &self,
+        parameters: Self::Params,
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(parameters.text)
+    }
+}
+
+#[tokio::test]
+async fn test_tool_set_mixed_tools() {
+    let use_calculator = true;
+
+    let mut tools = ToolSet::new().add_tool(Echo);
+    if use_calculator {
+        let calculator: Box<dyn DynTool> = Box::new(Calculator {});
+        tools.insert_dyn(calculator);
+    }
+
+    assert_eq!(tools.len(), 2);
+    assert!(tools.contains("echo"));
+    assert!(tools.contains("calculator"));
+
+    let echoed = tools.call("echo", json!({ "text": "hi" })).await.unwrap();
+    assert_eq!(echoed, "hi");
+
+    let sum = tools
+        .call("calculator", json!({ "expression": "1 + 2" }))
+        .await
+        .unwrap();
+    assert_eq!(sum, "3");
+}
+
+#[tokio::test]
+async fn test_tool_set_replaces_and_removes() {
+    let mut tools = ToolSet::new().add_tool(Echo).add_tool(Echo);
+    assert_eq!(tools.len(), 1);
+    assert_eq!(tools.infos().len(), 1);
+
+    assert!(tools.remove("echo"));
+    assert!(!tools.remove("echo"));
+    assert!(tools.is_empty());
+
+    let err = tools.call("echo", json!({})).await.unwrap_err();
+    assert!(matches!(
+        err,
+        OllamaError::ToolCallError(ToolCallError::UnknownToolName)
+    ));
+}
@@ -0,0 +1,66 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use ollama_rs::generation::tools::{StatefulTool, Tool, WithState};
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+struct AppState {
+    calls: AtomicUsize,
+}
+
+#[derive(Deserialize, JsonSchema)]
+struct Params {
+    #[schemars(description = "The name to greet")]
+    name: String,
+}
+
+struct Greeter;
+
+impl StatefulTool<AppState> for Greeter {
+    type Params = Params;
+
+    fn name() -> &'static str {
+        "greeter"
+    }
+
+    fn description() -> &'static str {
+        "Greets someone"
+    }
+
+    async fn call(
+        &self,
+        state: &AppState,
+        parameters: Self::Params,
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        let n = state.calls.fetch_add(1, Ordering::SeqCst) + 1;
+        Ok(format!("Hello {} (call #{n})", parameters.name))
+    }
+}
+
+#[tokio::test]
+async fn test_tool_with_shared_state() {
+    let state = Arc::new(AppState {
+        calls: AtomicUsize::new(0),
+    });
+
+    let first = WithState::new(Greeter, state.clone());
+    let second = WithState::new(Greeter, state.clone());
+
+    assert_eq!(<WithState<Greeter, AppState> as Tool>::name(), "greeter");
+
+    let (a, b) = tokio::join!(
+        first.call(Params {
+            name: "Alice".to_string()
+        }),
+        second.call(Params {
+            name: "Bob".to_string()
+        }),
+    );
+
+    assert!(a.unwrap().starts_with("Hello Alice"));
+    assert!(b.unwrap().starts_with("Hello Bob"));
+    assert_eq!(state.calls.load(Ordering::SeqCst), 2);
+}
@@ -0,0 +1,62 @@
+use ollama_rs::{
+    coordinator::{Coordinator, Transcript, TranscriptStep},
+    generation::{chat::ChatMessage, tools::implementations::Calculator},
+    test_utils::{MockOllama, MockResponse},
+};
+use serde_json::json;
+
+#[tokio::test]
+async fn test_coordinator_transcript() {
+    let server = MockOllama::start().await;
+    server
+        .mock_once(
+            "/api/chat",
+            MockResponse::tool_call("calculator", json!({ "expression": "2 + 2" })),
+        )
+        .mock("/api/chat", MockResponse::chat("4"));
+
+    let mut coordinator =
+        Coordinator::new(server.client(), "llama3.2".to_string(), vec![]).add_tool(Calculator {});
+    coordinator
+        .chat(vec![ChatMessage::user("What is 2 + 2?".to_string())])
+        .await
+        .unwrap();
+
+    let transcript = coordinator.transcript();
+    assert_eq!(transcript.messages.len(), 4);
+    assert_eq!(transcript.steps.len(), 3);
+
+    let TranscriptStep::Model { message, .. } = &transcript.steps[0] else {
+        panic!("Expected a turn of the model");
+    };
+    let call_id = message.tool_calls[0].id.clone().unwrap();
+    let TranscriptStep::ToolCall {
+        id,
+        name,
+        arguments,
+        result,
+        error,
+        ..
+    } = &transcript.steps[1]
+    else {
+        panic!("Expected a tool call");
+    };
+    assert_eq!(id, &call_id);
+    assert_eq!(name, "calculator");
+    assert_eq!(arguments, &json!({ "expression": "2 + 2" }));
+    assert!(result.is_some());
+    assert!(error.is_none());
+
+    let TranscriptStep::Model { message, .. } = &transcript.steps[2] else {
+        panic!("Expected a turn of the model");
+    };
+    assert_eq!(message.content, "4");
+    assert_eq!(transcript.usage().total_tokens(), 60);
+
+    // The transcript survives a round trip through JSON
+    let value = serde_json::to_value(&transcript).unwrap();
+    assert_eq!(value["steps"][1]["type"], "tool_call");
+    let back: Transcript = serde_json::from_value(value).unwrap();
+    assert_eq!(back.steps.len(), 3);
+    assert_eq!(back.usage(), transcript.usage());
+}
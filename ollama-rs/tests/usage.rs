@@ -0,0 +1,56 @@
+use std::time::Duration;
+
+use ollama_rs::{
+    generation::{
+        chat::{request::ChatMessageRequest, ChatMessage},
+        completion::request::GenerationRequest,
+    },
+    test_utils::{MockOllama, MockResponse},
+};
+use tokio_stream::StreamExt;
+
+#[tokio::test]
+async fn test_generation_usage() {
+    let server = MockOllama::start().await;
+    server.mock("/api/generate", MockResponse::generation("Blue"));
+
+    let res = server
+        .client()
+        .generate(GenerationRequest::new("llama2".to_string(), "Why?"))
+        .await
+        .unwrap();
+
+    let usage = res.usage().unwrap();
+    assert_eq!(usage.total_duration, Duration::from_millis(1));
+    assert_eq!(usage.load_duration, Duration::from_micros(100));
+    assert_eq!(usage.prompt_eval_count, 10);
+    assert_eq!(usage.prompt_eval_duration, Duration::from_micros(200));
+    assert_eq!(usage.eval_count, 20);
+    assert_eq!(usage.eval_duration, Duration::from_micros(700));
+    assert_eq!(usage.total_tokens(), 30);
+    assert_eq!(usage.prompt_tokens_per_second(), Some(50_000.0));
+    assert!((usage.tokens_per_second().unwrap() - 20.0 / 0.0007).abs() < 1e-6);
+}
+
+#[tokio::test]
+async fn test_chat_usage_only_in_last_chunk() {
+    let server = MockOllama::start().await;
+    server.mock("/api/chat", MockResponse::chat_stream(&["Bl", "ue"]));
+
+    let chunks: Vec<_> = server
+        .client()
+        .send_chat_messages_stream(ChatMessageRequest::new(
+            "llama2".to_string(),
+            vec![ChatMessage::user("Why?".to_string())],
+        ))
+        .await
+        .unwrap()
+        .map(|c| c.unwrap())
+        .collect()
+        .await;
+
+    assert!(chunks[0].usage().is_none());
+    let usage = chunks[1].usage().unwrap();
+    assert_eq!(usage.eval_count, 20);
+    assert_eq!(usage.load_duration, Duration::from_micros(100));
+}
@@ -0,0 +1,104 @@
+use std::path::PathBuf;
+
+use ollama_rs::{
+    generation::completion::request::GenerationRequest,
+    test_utils::{MockOllama, MockResponse},
+};
+use tokio_stream::StreamExt;
+
+fn cassette_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir()
+        .join(format!("ollama-rs-{}", std::process::id()))
+        .join(format!("{name}.json"));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+async fn stream_text(server: &MockOllama, prompt: &str) -> String {
+    let mut stream = server
+        .client()
+        .generate_stream(GenerationRequest::new("llama2".to_string(), prompt))
+        .await
+        .unwrap();
+
+    let mut text = String::new();
+    while let Some(res) = stream.next().await {
+        for r in res.unwrap() {
+            text.push_str(&r.response);
+        }
+    }
+    text
+}
+
+#[tokio::test]
+async fn test_record_then_replay() {
+    let cassette = cassette_path("record_then_replay");
+
+    // Stands in for a real Ollama server
+    let upstream = MockOllama::start().await;
+    upstream
+        .mock_once("/api/generate", MockResponse::generation("First"))
+        .mock(
+            "/api/generate",
+            MockResponse::generation_stream(&["The sky", " is", " blue"]),
+        );
+
+    {
+        let recorder = MockOllama::record(&cassette, upstream.url()).await;
+        let res = recorder
+            .client()
+            .generate(GenerationRequest::new("llama2".to_string(), "Hi"))
+            .await
+            .unwrap();
+        assert_eq!(res.response, "First");
+        assert_eq!(stream_text(&recorder, "Why?").await, "The sky is blue");
+    }
+    drop(upstream);
+
+    let replayer = MockOllama::replay(&cassette).await;
+
+    let res = replayer
+        .client()
+        .generate(GenerationRequest::new("llama2".to_string(), "Hi"))
+        .await
+        .unwrap();
+    assert_eq!(res.response, "First");
+    assert_eq!(stream_text(&replayer, "Why?").await, "The sky is blue");
+
+    // Requests that weren't recorded aren't answered
+    assert!(replayer
+        .client()
+        .generate(GenerationRequest::new("llama2".to_string(), "Other"))
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_record_or_replay_uses_existing_cassette() {
+    let cassette = cassette_path("record_or_replay");
+
+    let upstream = MockOllama::start().await;
+    upstream.mock("/api/generate", MockResponse::generation("Recorded"));
+
+    let url = upstream.url();
+    {
+        let recorder = MockOllama::record_or_replay(&cassette, url.clone()).await;
+        let res = recorder
+            .client()
+            .generate(GenerationRequest::new("llama2".to_string(), "Hi"))
+            .await
+            .unwrap();
+        assert_eq!(res.response, "Recorded");
+    }
+
+    upstream.mock("/api/generate", MockResponse::generation("Changed"));
+
+    let replayer = MockOllama::record_or_replay(&cassette, url).await;
+    let res = replayer
+        .client()
+        .generate(GenerationRequest::new("llama2".to_string(), "Hi"))
+        .await
+        .unwrap();
+    assert_eq!(res.response, "Recorded");
+    assert_eq!(upstream.requests().len(), 1);
+}
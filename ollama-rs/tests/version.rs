@@ -0,0 +1,201 @@
+use ollama_rs::{
+    error::OllamaError,
+    generation::{
+        chat::{request::ChatMessageRequest, ChatMessage},
+        completion::request::GenerationRequest,
+        parameters::{FormatType, JsonStructure},
+        tools::{implementations::Calculator, DynTool},
+    },
+    test_utils::{MockOllama, MockResponse},
+    version::{Feature, Version},
+    Ollama,
+};
+use serde_json::json;
+
+#[derive(schemars::JsonSchema)]
+#[allow(dead_code)]
+struct Answer {
+    text: String,
+}
+
+fn client(server: &MockOllama) -> Ollama {
+    Ollama::builder()
+        .url(server.url())
+        .version_check(true)
+        .build()
+        .unwrap()
+}
+
+fn server_paths(server: &MockOllama) -> Vec<String> {
+    server.requests().into_iter().map(|r| r.path).collect()
+}
+
+#[test]
+fn test_parse_and_compare_versions() {
+    let version: Version = "0.5.7".parse().unwrap();
+    assert_eq!(version, Version::new(0, 5, 7));
+    assert_eq!("v0.6".parse::<Version>().unwrap(), Version::new(0, 6, 0));
+
+    let rc: Version = "0.6.0-rc1".parse().unwrap();
+    assert_eq!(rc.pre.as_deref(), Some("rc1"));
+    assert_eq!(rc.to_string(), "0.6.0-rc1");
+    assert!(rc < Version::new(0, 6, 0));
+    assert!(rc > Version::new(0, 5, 12));
+
+    // Pre-releases compare by identifier, with numbers compared by value
+    let ordered = [
+        "1.0.0-alpha",
+        "1.0.0-alpha.1",
+        "1.0.0-alpha.beta",
+        "1.0.0-beta",
+        "1.0.0-beta.2",
+        "1.0.0-beta.11",
+        "1.0.0-rc1",
+        "1.0.0-rc2",
+        "1.0.0-rc10",
+        "1.0.0",
+    ];
+    for pair in ordered.windows(2) {
+        let (a, b) = (
+            pair[0].parse::<Version>().unwrap(),
+            pair[1].parse::<Version>().unwrap(),
+        );
+        assert!(a < b, "{a} < {b}");
+    }
+
+    assert!("0.x".parse::<Version>().is_err());
+    assert!("1.2.3.4".parse::<Version>().is_err());
+}
+
+#[tokio::test]
+async fn test_server_version_is_fetched_once() {
+    let server = MockOllama::start().await;
+    server.mock(
+        "/api/version",
+        MockResponse::json(json!({ "version": "0.4.7" })),
+    );
+    let ollama = server.client();
+
+    assert_eq!(
+        ollama.server_version().await.unwrap(),
+        Version::new(0, 4, 7)
+    );
+    assert!(ollama.supports(Feature::Tools).await.unwrap());
+    assert!(!ollama
+        .clone()
+        .supports(Feature::StructuredOutputs)
+        .await
+        .unwrap());
+    assert_eq!(server_paths(&server), ["/api/version"]);
+}
+
+#[tokio::test]
+async fn test_unsupported_feature_is_not_sent() {
+    let server = MockOllama::start().await;
+    server.mock(
+        "/api/version",
+        MockResponse::json(json!({ "version": "0.4.7" })),
+    );
+    server.mock("/api/generate", MockResponse::generation("{}"));
+    let ollama = client(&server);
+
+    let request = GenerationRequest::new("mock".into(), "Hi")
+        .format(FormatType::StructuredJson(JsonStructure::new::<Answer>()));
+    let err = ollama.generate(request).await.unwrap_err();
+    match &err {
+        OllamaError::Unsupported {
+            feature,
+            required,
+            server,
+        } => {
+            assert_eq!(*feature, Feature::StructuredOutputs);
+            assert_eq!(*required, Version::new(0, 5, 0));
+            assert_eq!(*server, Version::new(0, 4, 7));
+        }
+        e => panic!("unexpected error {e:?}"),
+    }
+    assert_eq!(
+        err.to_string(),
+        "Structured outputs requires Ollama 0.5.0 or greater, but the server runs 0.4.7"
+    );
+
+    // Requests not using the feature are sent
+    ollama
+        .generate(GenerationRequest::new("mock".into(), "Hi").format(FormatType::Json))
+        .await
+        .unwrap();
+    assert_eq!(server_paths(&server), ["/api/version", "/api/generate"]);
+}
+
+#[tokio::test]
+async fn test_tool_streaming_requires_recent_server() {
+    let server = MockOllama::start().await;
+    server.mock(
+        "/api/version",
+        MockResponse::json(json!({ "version": "0.6.2" })),
+    );
+    server.mock("/api/chat", MockResponse::chat("4"));
+    let ollama = client(&server);
+
+    let request = || {
+        ChatMessageRequest::new("mock".into(), vec![ChatMessage::user("2 + 2?".into())])
+            .tools(vec![Calculator {}.info()])
+    };
+    ollama.send_chat_messages(request()).await.unwrap();
+
+    let err = ollama
+        .send_chat_messages_stream(request())
+        .await
+        .err()
+        .unwrap();
+    assert!(matches!(
+        err,
+        OllamaError::Unsupported {
+            feature: Feature::ToolStreaming,
+            ..
+        }
+    ));
+}
+
+#[tokio::test]
+async fn test_version_check_is_skipped_when_unknown() {
+    let server = MockOllama::start().await;
+    server.mock("/api/version", MockResponse::error(404, "not found"));
+    server.mock("/api/generate", MockResponse::generation("{}"));
+
+    let request = || {
+        GenerationRequest::new("mock".into(), "Hi")
+            .format(FormatType::StructuredJson(JsonStructure::new::<Answer>()))
+    };
+    let ollama = client(&server);
+    ollama.generate(request()).await.unwrap();
+    // The failure is remembered rather than fetching the version before every request
+    ollama.generate(request()).await.unwrap();
+    assert_eq!(
+        server_paths(&server),
+        ["/api/version", "/api/generate", "/api/generate"]
+    );
+
+    // Development builds support everything
+    let server = MockOllama::start().await;
+    server.mock(
+        "/api/version",
+        MockResponse::json(json!({ "version": "0.0.0" })),
+    );
+    server.mock("/api/generate", MockResponse::generation("{}"));
+
+    let request = GenerationRequest::new("mock".into(), "Hi")
+        .format(FormatType::StructuredJson(JsonStructure::new::<Answer>()));
+    client(&server).generate(request).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_version_check_is_disabled_by_default() {
+    let server = MockOllama::start().await;
+    server.mock("/api/generate", MockResponse::generation("{}"));
+
+    let request = GenerationRequest::new("mock".into(), "Hi")
+        .format(FormatType::StructuredJson(JsonStructure::new::<Answer>()));
+    server.client().generate(request).await.unwrap();
+    assert_eq!(server_paths(&server), ["/api/generate"]);
+}
@@ -0,0 +1,44 @@
+use std::time::{Duration, Instant};
+
+use ollama_rs::test_utils::{MockOllama, MockResponse};
+use serde_json::json;
+
+#[tokio::test]
+async fn test_warmup_models() {
+    let server = MockOllama::start().await;
+    server.mock_with("/api/generate", |request| {
+        let model = request.json()["model"].as_str().unwrap().to_string();
+        if model == "missing" {
+            return MockResponse::error(404, "model \"missing\" not found, try pulling it first");
+        }
+        MockResponse::json(json!({
+            "model": model,
+            "created_at": "2024-01-01T00:00:00Z",
+            "response": "",
+            "done": true,
+            "done_reason": "load"
+        }))
+        .delay(Duration::from_millis(200))
+    });
+
+    let start = Instant::now();
+    let results = server
+        .client()
+        .warmup_models(["llama3.2", "missing", "qwen2.5", "mistral"], 2)
+        .await;
+    let elapsed = start.elapsed();
+
+    let names: Vec<_> = results.iter().map(|r| r.model_name.as_str()).collect();
+    assert_eq!(names, ["llama3.2", "missing", "qwen2.5", "mistral"]);
+    assert!(results[0].is_loaded());
+    assert!(!results[1].is_loaded());
+    assert!(results[2].is_loaded());
+    assert!(*results[3].result.as_ref().unwrap() >= Duration::from_millis(200));
+
+    // The three models that load take two rounds
+    assert!(elapsed >= Duration::from_millis(400), "{elapsed:?}");
+    assert!(elapsed < Duration::from_millis(600), "{elapsed:?}");
+
+    let request = server.requests()[0].json();
+    assert_eq!(request["prompt"], "");
+}